@@ -0,0 +1,126 @@
+use {
+    crate::{
+        makepad_draw::*,
+        makepad_widgets::*,
+    }
+};
+
+live_design!{
+    use link::shaders::*;
+
+    DrawMeter = {{DrawMeter}} {
+        fn level_color(self, level: float) -> vec4 {
+            if level > 0.9 {
+                return #f44
+            }
+            if level > 0.75 {
+                return #fc3
+            }
+            return #4c6
+        }
+
+        fn pixel(self) -> vec4 {
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            let half = self.rect_size.x * 0.5;
+            let gap = 1.0;
+            sdf.rect(0., 0., self.rect_size.x, self.rect_size.y);
+            sdf.fill(#1a);
+            // the rms level as a filled bar, the peak level as a line on top of it
+            let bar_width = half - 2.0 * gap;
+            let rms_left = self.rect_size.y * (1.0 - self.rms_left);
+            sdf.rect(gap, rms_left, bar_width, self.rect_size.y - rms_left);
+            sdf.fill(self.level_color(self.rms_left));
+            let rms_right = self.rect_size.y * (1.0 - self.rms_right);
+            sdf.rect(half + gap, rms_right, bar_width, self.rect_size.y - rms_right);
+            sdf.fill(self.level_color(self.rms_right));
+            sdf.rect(gap, self.rect_size.y * (1.0 - self.peak_left), bar_width, 2.0);
+            sdf.fill(self.level_color(self.peak_left));
+            sdf.rect(half + gap, self.rect_size.y * (1.0 - self.peak_right), bar_width, 2.0);
+            sdf.fill(self.level_color(self.peak_right));
+            return sdf.result
+        }
+    }
+
+    pub LevelMeter = {{LevelMeter}} {
+        width: 12,
+        height: Fill,
+        min_db: -60.0,
+        decay: 0.1
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister)]#[repr(C)]
+struct DrawMeter {
+    #[deref] draw_super: DrawQuad,
+    #[live] peak_left: f32,
+    #[live] peak_right: f32,
+    #[live] rms_left: f32,
+    #[live] rms_right: f32,
+}
+
+/// A stereo level meter, showing peak and RMS levels on a dB scale.
+#[derive(Live, LiveHook, Widget)]
+pub struct LevelMeter {
+    #[walk] walk: Walk,
+    #[redraw] #[live] draw_meter: DrawMeter,
+    /// The level in dB at the bottom of the meter.
+    #[live] min_db: f32,
+    /// How much of the shown level is kept after a second when the signal drops.
+    #[live] decay: f32,
+    #[rust] peak: [f32; 2],
+    #[rust] rms: [f32; 2],
+    #[rust] last_time: Option<f64>,
+}
+
+impl Widget for LevelMeter {
+    fn handle_event(&mut self, _cx: &mut Cx, _event: &Event, _scope: &mut Scope){
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.draw_meter.peak_left = self.meter_pos(self.peak[0]);
+        self.draw_meter.peak_right = self.meter_pos(self.peak[1]);
+        self.draw_meter.rms_left = self.meter_pos(self.rms[0]);
+        self.draw_meter.rms_right = self.meter_pos(self.rms[1]);
+        self.draw_meter.draw_walk(cx, walk);
+        DrawStep::done()
+    }
+}
+
+impl LevelMeter {
+    // maps a linear level onto the height of the meter
+    fn meter_pos(&self, level: f32) -> f32 {
+        let db = 20.0 * level.max(1e-6).log10();
+        (1.0 - db / self.min_db.min(-1.0)).clamp(0.0, 1.0)
+    }
+
+    /// Shows new levels for the left and right channel, as linear amplitudes. Levels rise at once and fall
+    /// back with the decay over the time since the last call, so short peaks stay visible however often
+    /// levels come in.
+    pub fn set_levels(&mut self, cx: &mut Cx, peak: [f32; 2], rms: [f32; 2]) {
+        let time = cx.seconds_since_app_start();
+        let elapsed = self.last_time.map_or(0.0, | last_time | (time - last_time).max(0.0));
+        self.last_time = Some(time);
+        let decay = self.decay.clamp(0.0, 1.0).powf(elapsed as f32);
+        for side in 0..2 {
+            self.peak[side] = peak[side].max(self.peak[side] * decay);
+            self.rms[side] = rms[side].max(self.rms[side] * decay);
+        }
+        self.draw_meter.redraw(cx);
+    }
+}
+
+impl LevelMeterRef {
+    pub fn set_levels(&self, cx: &mut Cx, peak: [f32; 2], rms: [f32; 2]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_levels(cx, peak, rms);
+        }
+    }
+}
+
+impl LevelMeterSet {
+    pub fn set_levels(&self, cx: &mut Cx, peak: [f32; 2], rms: [f32; 2]) {
+        for item in self.iter(){
+            item.set_levels(cx, peak, rms);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 
 pub mod piano;
 pub mod display_audio;
+pub mod level_meter;
 
 use makepad_platform::Cx;
 pub use makepad_widgets;
@@ -12,4 +13,5 @@ pub fn live_design(cx:&mut Cx){
     makepad_widgets::live_design(cx);
     self::piano::live_design(cx);
     self::display_audio::live_design(cx);
+    self::level_meter::live_design(cx);
 }
@@ -25,7 +25,8 @@ pub enum AudioGraphAction<'a> {
         voice: usize,
         buffer: &'a AudioBuffer
     },
-    VoiceOff {voice: usize}
+    VoiceOff {voice: usize},
+    Meter {input: Option<LiveId>, levels: AudioMeterLevels}
 }
 
 #[derive(Live, LiveRegister)]
@@ -110,7 +111,8 @@ fn render_to_output_buffer(node: &mut Node, to_ui: &ToUISender<ToUIDisplayMsg>,
             //node.buffer.resize_like_output(output);
             let mut dg = DisplayAudioGraph {
                 to_ui,
-                buffers: &mut node.display_buffers
+                buffers: &mut node.display_buffers,
+                meter: true
             };
             root.render_to_audio_buffer(info, &mut [output], &[], &mut dg);
             // lets output this buffer to the UI
@@ -164,6 +166,9 @@ pub fn handle_event_with(
                     //log!("GOT DISPLAY AUDIO");
                     dispatch_action(cx, AudioGraphAction::VoiceOff {voice});
                 },
+                ToUIDisplayMsg::Meter {input, levels} => {
+                    dispatch_action(cx, AudioGraphAction::Meter {input, levels});
+                },
                 ToUIDisplayMsg::OutOfBuffers => { // inject some new buffers
                 }
             }
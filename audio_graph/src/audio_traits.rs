@@ -30,6 +30,10 @@ fn render_to_audio_buffer(
         inputs: &[&AudioBuffer],
         display: &mut DisplayAudioGraph
     );
+    /// Switches the node to render at `sample_rate`. Nodes that hold other nodes pass it on to them.
+    /// This is called before rendering starts, not from the audio thread, so it may allocate.
+    fn set_sample_rate(&mut self, _sample_rate: f64) {
+    }
 }
 
 generate_any_trait_api!(AudioComponent);
@@ -38,15 +42,46 @@ fn render_to_audio_buffer(
 // Audio component registry
 
 
+/// Peak and RMS levels of the left and right channel over one block, as linear amplitudes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioMeterLevels {
+    pub peak: [f32; 2],
+    pub rms: [f32; 2],
+}
+
+impl AudioMeterLevels {
+    /// Measures the first two channels of a buffer, a mono buffer shows up on both sides.
+    pub fn from_buffer(buffer: &AudioBuffer) -> Self {
+        let mut levels = Self::default();
+        if buffer.channel_count() == 0 || buffer.frame_count() == 0 {
+            return levels
+        }
+        for side in 0..2 {
+            let channel = buffer.channel(side.min(buffer.channel_count() - 1));
+            let mut sum = 0.0;
+            for sample in channel {
+                levels.peak[side] = levels.peak[side].max(sample.abs());
+                sum += sample * sample;
+            }
+            levels.rms[side] = (sum / channel.len() as f32).sqrt();
+        }
+        levels
+    }
+}
+
 pub enum ToUIDisplayMsg{
     DisplayAudio{voice: usize, buffer:AudioBuffer, active:bool},
     VoiceOff{voice: usize},
+    /// Levels of a mixer input, or of the mixer output when `input` is `None`.
+    Meter{input: Option<LiveId>, levels: AudioMeterLevels},
     OutOfBuffers
 }
 
 pub struct DisplayAudioGraph<'a> {
     pub to_ui: &'a ToUISender<ToUIDisplayMsg>, 
     pub buffers: &'a mut Vec<AudioBuffer>,
+    /// Whether something reads meter levels; `send_meter` drops them otherwise.
+    pub meter: bool,
 }
 
 // Audio component registry
@@ -68,6 +103,12 @@ pub fn send_buffer(&self, active:bool, voice: usize, buffer:AudioBuffer){
     pub fn send_voice_off(&self, voice: usize){
         self.to_ui.send(ToUIDisplayMsg::VoiceOff{voice}).unwrap();
     }
+    
+    pub fn send_meter(&self, input: Option<LiveId>, levels: AudioMeterLevels){
+        if self.meter{
+            let _ = self.to_ui.send(ToUIDisplayMsg::Meter{input, levels});
+        }
+    }
 }
 
 
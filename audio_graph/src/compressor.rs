@@ -0,0 +1,154 @@
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_platform::live_atomic::*,
+        register_audio_component,
+        audio_traits::*
+    },
+    std::sync::Arc,
+};
+
+live_design!{
+    pub Compressor = {{Compressor}} {
+        sample_rate: 48000.0
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister, LiveAtomic, Debug, LiveRead)]
+pub struct CompressorSettings {
+    /// Level in dB above which the signal is compressed.
+    #[live(-18.0)] pub threshold: f32a,
+    /// Compression ratio, from 20.0 upwards the compressor acts as a limiter.
+    #[live(4.0)] pub ratio: f32a,
+    /// Width in dB of the soft knee around the threshold, 0.0 is a hard knee.
+    #[live(6.0)] pub knee: f32a,
+    /// Attack time in seconds.
+    #[live(0.005)] pub attack: f32a,
+    /// Release time in seconds.
+    #[live(0.1)] pub release: f32a,
+    /// Makeup gain in dB.
+    #[live(0.0)] pub makeup: f32a,
+}
+
+/// A feed forward compressor and limiter, processing the first input into the first output.
+///
+/// The level is detected on the peak of all channels, so the stereo image stays in place.
+#[derive(Live, LiveHook)]
+pub struct Compressor {
+    #[live] settings: Arc<CompressorSettings>,
+    #[live] sample_rate: f64,
+}
+
+impl LiveRegister for Compressor {
+    fn live_register(cx: &mut Cx) {
+        register_audio_component!(cx, Compressor)
+    }
+}
+
+/// The gain reduction in dB for a signal at `level` dB.
+pub fn compressor_gain_reduction(level: f32, threshold: f32, ratio: f32, knee: f32) -> f32 {
+    let slope = 1.0 - 1.0 / ratio.max(1.0);
+    let over = level - threshold;
+    if knee > 0.0 && over.abs() <= knee / 2.0 {
+        // quadratic interpolation across the knee
+        slope * (over + knee / 2.0).powi(2) / (2.0 * knee)
+    }
+    else if over > 0.0 {
+        slope * over
+    }
+    else {
+        0.0
+    }
+}
+
+pub struct CompressorNode {
+    settings: Arc<CompressorSettings>,
+    sample_rate: f32,
+    reduction: f32,
+}
+
+impl CompressorNode {
+    pub fn new(settings: Arc<CompressorSettings>, sample_rate: f64) -> Self {
+        Self {
+            settings,
+            sample_rate: sample_rate as f32,
+            reduction: 0.0,
+        }
+    }
+
+    /// The current gain reduction in dB, for metering.
+    pub fn gain_reduction(&self) -> f32 {
+        self.reduction
+    }
+
+    fn time_coefficient(&self, seconds: f32) -> f32 {
+        if seconds <= 0.0 {
+            0.0
+        }
+        else {
+            (-1.0 / (seconds * self.sample_rate)).exp()
+        }
+    }
+}
+
+impl AudioGraphNode for CompressorNode {
+    fn handle_midi_data(&mut self, _data: MidiData) {
+    }
+
+    fn all_notes_off(&mut self) {
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate as f32;
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        let output = &mut outputs[0];
+        // effects work in place on a copy of their input
+        match inputs.first() {
+            Some(input) => {output.copy_from(input);}
+            None => output.zero()
+        }
+        let threshold = self.settings.threshold.get();
+        let ratio = self.settings.ratio.get();
+        let knee = self.settings.knee.get().max(0.0);
+        let attack = self.time_coefficient(self.settings.attack.get());
+        let release = self.time_coefficient(self.settings.release.get());
+        let makeup = self.settings.makeup.get();
+
+        for frame in 0..output.frame_count() {
+            let mut peak = 0.0f32;
+            for channel in 0..output.channel_count() {
+                peak = peak.max(output.channel(channel)[frame].abs());
+            }
+            let level = 20.0 * peak.max(1e-6).log10();
+            let target = compressor_gain_reduction(level, threshold, ratio, knee);
+            // the reduction rises with the attack time and falls back with the release time
+            let coefficient = if target > self.reduction {attack} else {release};
+            self.reduction = target + (self.reduction - target) * coefficient;
+            let gain = 10f32.powf((makeup - self.reduction) / 20.0);
+            for channel in 0..output.channel_count() {
+                output.channel_mut(channel)[frame] *= gain;
+            }
+        }
+    }
+}
+
+impl AudioComponent for Compressor {
+    fn get_graph_node(&mut self, _cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
+        Box::new(CompressorNode::new(self.settings.clone(), self.sample_rate))
+    }
+
+    fn handle_event_with(&mut self, _cx: &mut Cx, _event: &Event, _dispatch_action: &mut dyn FnMut(&mut Cx, AudioComponentAction)) {
+    }
+
+    fn audio_query(&mut self, _query: &AudioQuery, _callback: &mut Option<AudioQueryCb>) -> AudioResult<'_> {
+        AudioResult::not_found()
+    }
+}
@@ -0,0 +1,140 @@
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_platform::live_atomic::*,
+        register_audio_component,
+        audio_traits::*
+    },
+    std::sync::Arc,
+};
+
+live_design!{
+    pub Delay = {{Delay}} {
+        sample_rate: 48000.0
+    }
+}
+
+/// The longest delay time in seconds, which sets the size of the delay lines.
+pub const MAX_DELAY_TIME: f32 = 4.0;
+
+#[derive(Live, LiveHook, LiveRegister, LiveAtomic, Debug, LiveRead)]
+pub struct DelaySettings {
+    /// Delay time in seconds.
+    #[live(0.375)] pub time: f32a,
+    #[live(0.4)] pub feedback: f32a,
+    /// How much of the feedback crosses over to the other channel, 1.0 is a ping pong delay.
+    #[live(0.0)] pub cross: f32a,
+    #[live(0.3)] pub mix: f32a,
+}
+
+/// A feedback delay, processing the first input into the first output.
+#[derive(Live, LiveHook)]
+pub struct Delay {
+    #[live] settings: Arc<DelaySettings>,
+    #[live] sample_rate: f64,
+}
+
+impl LiveRegister for Delay {
+    fn live_register(cx: &mut Cx) {
+        register_audio_component!(cx, Delay)
+    }
+}
+
+pub struct DelayNode {
+    settings: Arc<DelaySettings>,
+    sample_rate: f32,
+    lines: Vec<Vec<f32>>,
+    delayed: Vec<f32>,
+    write_pos: usize,
+}
+
+impl DelayNode {
+    pub fn new(settings: Arc<DelaySettings>, sample_rate: f64) -> Self {
+        Self {
+            settings,
+            sample_rate: sample_rate as f32,
+            lines: Vec::new(),
+            delayed: Vec::new(),
+            write_pos: 0,
+        }
+    }
+
+    fn read(line: &[f32], write_pos: usize, delay: f32) -> f32 {
+        // linear interpolation between the two samples around the delay time
+        let len = line.len();
+        let whole = delay as usize;
+        let fract = delay - whole as f32;
+        let a = line[(write_pos + len - whole) % len];
+        let b = line[(write_pos + len - whole - 1) % len];
+        a + (b - a) * fract
+    }
+}
+
+impl AudioGraphNode for DelayNode {
+    fn handle_midi_data(&mut self, _data: MidiData) {
+    }
+
+    fn all_notes_off(&mut self) {
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        // the delay lines are sized for the new rate on the next block
+        self.sample_rate = sample_rate as f32;
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        let output = &mut outputs[0];
+        // effects work in place on a copy of their input
+        match inputs.first() {
+            Some(input) => {output.copy_from(input);}
+            None => output.zero()
+        }
+        let channel_count = output.channel_count();
+        let line_len = (MAX_DELAY_TIME * self.sample_rate) as usize + 2;
+        if self.lines.len() != channel_count || self.lines.iter().any( | line | line.len() != line_len) {
+            self.lines = vec![vec![0.0; line_len]; channel_count];
+            self.delayed = vec![0.0; channel_count];
+            self.write_pos = 0;
+        }
+        // a delay of less than a frame would read the sample that is about to be written
+        let delay = (self.settings.time.get() * self.sample_rate).clamp(1.0, line_len as f32 - 2.0);
+        let feedback = self.settings.feedback.get().clamp(0.0, 1.0);
+        let cross = self.settings.cross.get().clamp(0.0, 1.0);
+        let mix = self.settings.mix.get().clamp(0.0, 1.0);
+
+        let delayed = &mut self.delayed;
+        for frame in 0..output.frame_count() {
+            for (channel, line) in self.lines.iter().enumerate() {
+                delayed[channel] = Self::read(line, self.write_pos, delay);
+            }
+            for channel in 0..channel_count {
+                let input = output.channel(channel)[frame];
+                // channels cross over in pairs, a lone last channel feeds back into itself
+                let other = if channel ^ 1 < channel_count {channel ^ 1} else {channel};
+                let feedback_in = delayed[channel] * (1.0 - cross) + delayed[other] * cross;
+                self.lines[channel][self.write_pos] = input + feedback_in * feedback;
+                output.channel_mut(channel)[frame] = input * (1.0 - mix) + delayed[channel] * mix;
+            }
+            self.write_pos = (self.write_pos + 1) % line_len;
+        }
+    }
+}
+
+impl AudioComponent for Delay {
+    fn get_graph_node(&mut self, _cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
+        Box::new(DelayNode::new(self.settings.clone(), self.sample_rate))
+    }
+
+    fn handle_event_with(&mut self, _cx: &mut Cx, _event: &Event, _dispatch_action: &mut dyn FnMut(&mut Cx, AudioComponentAction)) {
+    }
+
+    fn audio_query(&mut self, _query: &AudioQuery, _callback: &mut Option<AudioQueryCb>) -> AudioResult<'_> {
+        AudioResult::not_found()
+    }
+}
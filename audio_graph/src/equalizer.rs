@@ -0,0 +1,242 @@
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_platform::live_atomic::*,
+        register_audio_component,
+        audio_traits::*
+    },
+    std::{
+        f32::consts::PI,
+        sync::Arc,
+    },
+};
+
+live_design!{
+    pub Equalizer = {{Equalizer}} {
+        sample_rate: 48000.0
+    }
+}
+
+/// The coefficients of a biquad filter, normalized so `a0` is 1.
+///
+/// The filter shapes follow Robert Bristow-Johnson's Audio EQ Cookbook.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiquadCoefficients {
+    pub b0: f32,
+    pub b1: f32,
+    pub b2: f32,
+    pub a1: f32,
+    pub a2: f32,
+}
+
+impl Default for BiquadCoefficients {
+    fn default() -> Self {
+        Self::bypass()
+    }
+}
+
+impl BiquadCoefficients {
+    pub fn bypass() -> Self {
+        Self {b0: 1.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0}
+    }
+
+    fn normalize(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0}
+    }
+
+    // the angular frequency as cos and the alpha of the cookbook, with the frequency kept below nyquist
+    fn omega(sample_rate: f32, freq: f32, q: f32) -> (f32, f32) {
+        let w0 = 2.0 * PI * freq.clamp(1.0, sample_rate * 0.49) / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q.max(0.01)))
+    }
+
+    pub fn low_pass(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::omega(sample_rate, freq, q);
+        Self::normalize((1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+    }
+
+    pub fn high_pass(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::omega(sample_rate, freq, q);
+        Self::normalize((1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha)
+    }
+
+    pub fn peaking(sample_rate: f32, freq: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::omega(sample_rate, freq, q);
+        let a = 10f32.powf(gain_db / 40.0);
+        Self::normalize(1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a, 1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a)
+    }
+
+    pub fn low_shelf(sample_rate: f32, freq: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::omega(sample_rate, freq, q);
+        let a = 10f32.powf(gain_db / 40.0);
+        let beta = 2.0 * a.sqrt() * alpha;
+        Self::normalize(
+            a * ((a + 1.0) - (a - 1.0) * cos + beta),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+            a * ((a + 1.0) - (a - 1.0) * cos - beta),
+            (a + 1.0) + (a - 1.0) * cos + beta,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+            (a + 1.0) + (a - 1.0) * cos - beta,
+        )
+    }
+
+    pub fn high_shelf(sample_rate: f32, freq: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::omega(sample_rate, freq, q);
+        let a = 10f32.powf(gain_db / 40.0);
+        let beta = 2.0 * a.sqrt() * alpha;
+        Self::normalize(
+            a * ((a + 1.0) + (a - 1.0) * cos + beta),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+            a * ((a + 1.0) + (a - 1.0) * cos - beta),
+            (a + 1.0) - (a - 1.0) * cos + beta,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos),
+            (a + 1.0) - (a - 1.0) * cos - beta,
+        )
+    }
+}
+
+/// A biquad filter for a single channel, in transposed direct form II.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Biquad {
+    pub coefficients: BiquadCoefficients,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    pub fn new(coefficients: BiquadCoefficients) -> Self {
+        Self {coefficients, z1: 0.0, z2: 0.0}
+    }
+
+    pub fn reset(&mut self) {
+        self.z1 = 0.0;
+        self.z2 = 0.0;
+    }
+
+    pub fn process(&mut self, input: f32) -> f32 {
+        let c = &self.coefficients;
+        let output = c.b0 * input + self.z1;
+        self.z1 = c.b1 * input - c.a1 * output + self.z2;
+        self.z2 = c.b2 * input - c.a2 * output;
+        output
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister, LiveAtomic, Debug, LiveRead)]
+pub struct EqualizerSettings {
+    /// Frequencies below this are cut off, 0.0 disables the filter.
+    #[live(0.0)] pub low_cut: f32a,
+    #[live(100.0)] pub low_freq: f32a,
+    #[live(0.0)] pub low_gain: f32a,
+    #[live(1000.0)] pub mid_freq: f32a,
+    #[live(0.0)] pub mid_gain: f32a,
+    #[live(0.707)] pub mid_q: f32a,
+    #[live(8000.0)] pub high_freq: f32a,
+    #[live(0.0)] pub high_gain: f32a,
+    /// Output gain in dB.
+    #[live(0.0)] pub gain: f32a,
+}
+
+/// A parametric equalizer with a low cut, low and high shelves and a peaking mid band, processing the
+/// first input into the first output. Gains are in dB.
+#[derive(Live, LiveHook)]
+pub struct Equalizer {
+    #[live] settings: Arc<EqualizerSettings>,
+    #[live] sample_rate: f64,
+}
+
+impl LiveRegister for Equalizer {
+    fn live_register(cx: &mut Cx) {
+        register_audio_component!(cx, Equalizer)
+    }
+}
+
+const BAND_COUNT: usize = 4;
+const SHELF_Q: f32 = 0.707;
+
+pub struct EqualizerNode {
+    settings: Arc<EqualizerSettings>,
+    sample_rate: f32,
+    bands: [BiquadCoefficients; BAND_COUNT],
+    filters: Vec<[Biquad; BAND_COUNT]>,
+}
+
+impl EqualizerNode {
+    pub fn new(settings: Arc<EqualizerSettings>, sample_rate: f64) -> Self {
+        Self {
+            settings,
+            sample_rate: sample_rate as f32,
+            bands: [BiquadCoefficients::bypass(); BAND_COUNT],
+            filters: Vec::new(),
+        }
+    }
+
+    fn update_bands(&mut self) {
+        let s = &self.settings;
+        let sample_rate = self.sample_rate;
+        let low_cut = s.low_cut.get();
+        self.bands = [
+            if low_cut > 0.0 {BiquadCoefficients::high_pass(sample_rate, low_cut, SHELF_Q)} else {BiquadCoefficients::bypass()},
+            BiquadCoefficients::low_shelf(sample_rate, s.low_freq.get(), SHELF_Q, s.low_gain.get()),
+            BiquadCoefficients::peaking(sample_rate, s.mid_freq.get(), s.mid_q.get(), s.mid_gain.get()),
+            BiquadCoefficients::high_shelf(sample_rate, s.high_freq.get(), SHELF_Q, s.high_gain.get()),
+        ];
+    }
+}
+
+impl AudioGraphNode for EqualizerNode {
+    fn handle_midi_data(&mut self, _data: MidiData) {
+    }
+
+    fn all_notes_off(&mut self) {
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_rate = sample_rate as f32;
+        self.filters.clear();
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        let output = &mut outputs[0];
+        // effects work in place on a copy of their input
+        match inputs.first() {
+            Some(input) => {output.copy_from(input);}
+            None => output.zero()
+        }
+        // the settings can change at any time, so the coefficients follow them once per block
+        self.update_bands();
+        self.filters.resize(output.channel_count(), [Biquad::default(); BAND_COUNT]);
+        let gain = 10f32.powf(self.settings.gain.get() / 20.0);
+        for (channel, filters) in self.filters.iter_mut().enumerate() {
+            for (filter, coefficients) in filters.iter_mut().zip(self.bands.iter()) {
+                filter.coefficients = *coefficients;
+            }
+            for sample in output.channel_mut(channel) {
+                let mut value = *sample;
+                for filter in filters.iter_mut() {
+                    value = filter.process(value);
+                }
+                *sample = value * gain;
+            }
+        }
+    }
+}
+
+impl AudioComponent for Equalizer {
+    fn get_graph_node(&mut self, _cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
+        Box::new(EqualizerNode::new(self.settings.clone(), self.sample_rate))
+    }
+
+    fn handle_event_with(&mut self, _cx: &mut Cx, _event: &Event, _dispatch_action: &mut dyn FnMut(&mut Cx, AudioComponentAction)) {
+    }
+
+    fn audio_query(&mut self, _query: &AudioQuery, _callback: &mut Option<AudioQueryCb>) -> AudioResult<'_> {
+        AudioResult::not_found()
+    }
+}
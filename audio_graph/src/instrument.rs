@@ -44,6 +44,12 @@ fn handle_midi_data(&mut self, data: MidiData) {
             step.graph_node.handle_midi_data(data);
         }
     }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        for step in &mut self.steps {
+            step.graph_node.set_sample_rate(sample_rate);
+        }
+    }
     
     fn render_to_audio_buffer(&mut self, info: AudioInfo, outputs: &mut [&mut AudioBuffer], inputs: &[&AudioBuffer], display:&mut DisplayAudioGraph) {
         // reverse over the steps chaining the audio nodes
@@ -8,15 +8,28 @@
 pub mod mixer;
 pub mod instrument;
 pub mod audio_stream;
+pub mod offline;
+pub mod sequencer;
+pub mod delay;
+pub mod reverb;
+pub mod equalizer;
+pub mod compressor;
 
 use makepad_platform::Cx;
 pub use makepad_platform;
 pub use makepad_platform::makepad_math;
 pub use crate::audio_graph::*;
 pub use crate::audio_traits::*;
+pub use crate::offline::*;
+pub use crate::sequencer::*;
 
 pub fn live_design(cx:&mut Cx){
     self::audio_graph::live_design(cx);
     self::mixer::live_design(cx);
     self::instrument::live_design(cx);
+    self::sequencer::live_design(cx);
+    self::delay::live_design(cx);
+    self::reverb::live_design(cx);
+    self::equalizer::live_design(cx);
+    self::compressor::live_design(cx);
 }
@@ -1,4 +1,5 @@
 use {
+    std::collections::HashMap,
     crate::{
         makepad_platform::*,
         register_audio_component,
@@ -11,12 +12,38 @@
     }
 }
 
+/// The controls of one mixer input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MixerChannel {
+    /// Linear gain.
+    pub gain: f32,
+    /// Balance from -1.0 (left) to 1.0 (right). The centre leaves both channels at full level.
+    pub pan: f32,
+    pub mute: bool,
+    /// While any input is soloed, only soloed inputs are heard.
+    pub solo: bool,
+}
+
+impl Default for MixerChannel {
+    fn default() -> Self {
+        Self {
+            gain: 1.0,
+            pan: 0.0,
+            mute: false,
+            solo: false
+        }
+    }
+}
+
 //enum ToUI {}
-enum FromUI {}
+enum FromUI {
+    SetChannel(LiveId, MixerChannel)
+}
 
 #[derive(Live)]
-struct Mixer {
+pub struct Mixer {
     #[rust] inputs: ComponentMap<LiveId, AudioComponentRef>,
+    #[rust] channels: HashMap<LiveId, MixerChannel>,
     #[rust] from_ui: FromUISender<FromUI>,
 }
 
@@ -40,25 +67,74 @@ fn after_apply(&mut self, _cx: &mut Cx, apply: &mut Apply, _index: usize, _nodes
     }
 }
 
-struct Node {
-    _from_ui: FromUIReceiver<FromUI>,
+impl Mixer {
+    pub fn channel(&self, input: LiveId) -> MixerChannel {
+        self.channels.get(&input).copied().unwrap_or_default()
+    }
+    
+    pub fn set_channel(&mut self, input: LiveId, channel: MixerChannel) {
+        self.channels.insert(input, channel);
+        let _ = self.from_ui.send(FromUI::SetChannel(input, channel));
+    }
+    
+    pub fn set_gain(&mut self, input: LiveId, gain: f32) {
+        self.set_channel(input, MixerChannel {gain, ..self.channel(input)});
+    }
+    
+    pub fn set_pan(&mut self, input: LiveId, pan: f32) {
+        self.set_channel(input, MixerChannel {pan: pan.clamp(-1.0, 1.0), ..self.channel(input)});
+    }
+    
+    pub fn set_mute(&mut self, input: LiveId, mute: bool) {
+        self.set_channel(input, MixerChannel {mute, ..self.channel(input)});
+    }
+    
+    pub fn set_solo(&mut self, input: LiveId, solo: bool) {
+        self.set_channel(input, MixerChannel {solo, ..self.channel(input)});
+    }
+}
+
+/// One input of a [`MixerNode`].
+pub struct MixerInput {
+    pub id: LiveId,
+    pub channel: MixerChannel,
+    pub graph_node: Box<dyn AudioGraphNode + Send>,
+}
+
+/// Mixes its inputs into the first output and sends their levels to the UI as meter messages.
+pub struct MixerNode {
+    from_ui: FromUIReceiver<FromUI>,
     buffer: AudioBuffer,
-    inputs: Vec<Box<dyn AudioGraphNode + Send >>
+    inputs: Vec<MixerInput>
 }
 
-// ok so how do we spawn this shit up.
+impl MixerNode {
+    /// Creates a mixer of graph nodes without a [`Mixer`] component, so its channels stay as given.
+    pub fn new(inputs: Vec<MixerInput>) -> Self {
+        Self {
+            inputs,
+            buffer: AudioBuffer::default(),
+            from_ui: FromUISender::default().receiver()
+        }
+    }
+}
 
-impl AudioGraphNode for Node {
+impl AudioGraphNode for MixerNode {
     fn all_notes_off(&mut self) {
-        for i in 0..self.inputs.len() {
-            let input = &mut self.inputs[i];
-            input.all_notes_off();
+        for input in &mut self.inputs {
+            input.graph_node.all_notes_off();
         }
     }
     
     fn handle_midi_data(&mut self, data: MidiData) {
         for input in &mut self.inputs {
-            input.handle_midi_data(data);
+            input.graph_node.handle_midi_data(data);
+        }
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        for input in &mut self.inputs {
+            input.graph_node.set_sample_rate(sample_rate);
         }
     }
     
@@ -69,19 +145,42 @@ fn render_to_audio_buffer(
         _inputs: &[&AudioBuffer],
         display: &mut DisplayAudioGraph
     ) {
+        while let Ok(msg) = self.from_ui.try_recv() {
+            match msg {
+                FromUI::SetChannel(id, channel) => {
+                    if let Some(input) = self.inputs.iter_mut().find( | input | input.id == id) {
+                        input.channel = channel;
+                    }
+                }
+            }
+        }
+        let any_solo = self.inputs.iter().any( | input | input.channel.solo);
         let output = &mut outputs[0];
-        self.buffer.resize_like(*output);
+        self.buffer.resize_like(output);
         output.zero();
-        for i in 0..self.inputs.len() {
-            let input = &mut self.inputs[i];
-            input.render_to_audio_buffer(info, &mut [&mut self.buffer], &[], display);
+        for input in &mut self.inputs {
+            input.graph_node.render_to_audio_buffer(info, &mut [&mut self.buffer], &[], display);
+            // muted inputs keep rendering, so their voices and effect tails stay in time
+            let channel = input.channel;
+            let audible = !channel.mute && (channel.solo || !any_solo);
             for c in 0..output.channel_count() {
+                let mut gain = if audible {channel.gain} else {0.0};
+                if output.channel_count() == 2 {
+                    gain *= if c == 0 {(1.0 - channel.pan).min(1.0)} else {(1.0 + channel.pan).min(1.0)};
+                }
                 let out_channel = output.channel_mut(c);
-                let in_channel = self.buffer.channel(c);
+                let in_channel = self.buffer.channel_mut(c);
                 for j in 0..out_channel.len() {
-                    out_channel[j] += in_channel[j]; //*0.1;
+                    in_channel[j] *= gain;
+                    out_channel[j] += in_channel[j];
                 }
             }
+            if display.meter {
+                display.send_meter(Some(input.id), AudioMeterLevels::from_buffer(&self.buffer));
+            }
+        }
+        if display.meter {
+            display.send_meter(None, AudioMeterLevels::from_buffer(output));
         }
     }
 }
@@ -92,15 +191,19 @@ fn get_graph_node(&mut self, cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
         
         self.from_ui.new_channel();
         let mut inputs = Vec::new();
-        for input in self.inputs.values_mut() {
+        for (id, input) in self.inputs.iter_mut() {
             if let Some(input) = input.as_mut() {
-                inputs.push(input.get_graph_node(cx));
+                inputs.push(MixerInput {
+                    id: *id,
+                    channel: self.channels.get(id).copied().unwrap_or_default(),
+                    graph_node: input.get_graph_node(cx)
+                });
             }
         }
-        Box::new(Node {
+        Box::new(MixerNode {
             inputs,
             buffer: AudioBuffer::default(),
-            _from_ui: self.from_ui.receiver()
+            from_ui: self.from_ui.receiver()
         })
     }
     
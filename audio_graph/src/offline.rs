@@ -0,0 +1,154 @@
+use {
+    crate::{
+        makepad_platform::*,
+        audio_traits::*,
+    },
+    std::path::Path,
+};
+
+/// A MIDI message scheduled at a frame offset from the start of an offline render.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MidiTimelineEvent {
+    pub frame: u64,
+    pub data: MidiData,
+}
+
+/// A scripted sequence of MIDI messages to drive an offline render with.
+#[derive(Clone, Debug, Default)]
+pub struct MidiTimeline {
+    events: Vec<MidiTimelineEvent>,
+}
+
+impl MidiTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedules `data` at `frame`. Messages at the same frame are delivered in the order they were pushed.
+    pub fn push(&mut self, frame: u64, data: impl Into<MidiData>) -> &mut Self {
+        let index = self.events.partition_point( | event | event.frame <= frame);
+        self.events.insert(index, MidiTimelineEvent {frame, data: data.into()});
+        self
+    }
+
+    /// Schedules a note on at `frame` and the matching note off `length` frames later.
+    pub fn note(&mut self, frame: u64, length: u64, channel: u8, note_number: u8, velocity: u8) -> &mut Self {
+        self.push(frame, MidiNote {is_on: true, channel, note_number, velocity});
+        self.push(frame + length, MidiNote {is_on: false, channel, note_number, velocity: 0})
+    }
+
+    pub fn events(&self) -> &[MidiTimelineEvent] {
+        &self.events
+    }
+
+    /// The frame right after the last message.
+    pub fn end_frame(&self) -> u64 {
+        self.events.last().map(|event| event.frame + 1).unwrap_or(0)
+    }
+}
+
+/// Renders an audio graph without an audio device, as fast as the graph can go.
+///
+/// The graph is rendered in blocks of at most `block_size` frames. Blocks are split at the frames of the
+/// timeline messages, so every message reaches the graph exactly at its frame. The graph is switched to
+/// the sample rate before it is rendered, which is also the rate of written files.
+pub struct OfflineRenderer {
+    pub sample_rate: u32,
+    pub block_size: usize,
+    pub channel_count: usize,
+    to_ui: ToUIReceiver<ToUIDisplayMsg>,
+    display_buffers: Vec<AudioBuffer>,
+}
+
+impl OfflineRenderer {
+    pub fn new(sample_rate: u32, block_size: usize, channel_count: usize) -> Self {
+        assert!(block_size > 0 && channel_count > 0);
+        Self {
+            sample_rate,
+            block_size,
+            channel_count,
+            to_ui: ToUIReceiver::default(),
+            display_buffers: (0..32).map( | _ | AudioBuffer::new_with_size(block_size, channel_count)).collect(),
+        }
+    }
+
+    pub fn seconds_to_frames(&self, seconds: f64) -> u64 {
+        (seconds * self.sample_rate as f64).round().max(0.0) as u64
+    }
+
+    /// Renders `frame_count` frames, calling `block_fn` with the output of each block as it is rendered.
+    pub fn render_blocks(
+        &mut self,
+        root: &mut dyn AudioGraphNode,
+        timeline: &MidiTimeline,
+        frame_count: u64,
+        mut block_fn: impl FnMut(&AudioBuffer)
+    ) {
+        root.set_sample_rate(self.sample_rate as f64);
+        let mut output = AudioBuffer::new_with_size(self.block_size, self.channel_count);
+        let mut events = timeline.events().iter().peekable();
+        let mut frame = 0;
+        while frame < frame_count {
+            while let Some(event) = events.next_if( | event | event.frame <= frame) {
+                root.handle_midi_data(event.data);
+            }
+            let mut block_end = (frame + self.block_size as u64).min(frame_count);
+            if let Some(event) = events.peek() {
+                block_end = block_end.min(event.frame);
+            }
+            output.resize((block_end - frame) as usize, self.channel_count);
+            output.zero();
+            let info = AudioInfo {
+                device_id: AudioDeviceId::default(),
+                time: Some(AudioTime {
+                    sample_time: frame as f64,
+                    host_time: 0,
+                    rate_scalar: 1.0
+                })
+            };
+            let to_ui = self.to_ui.sender();
+            let mut display = DisplayAudioGraph {
+                to_ui: &to_ui,
+                buffers: &mut self.display_buffers,
+                // nothing reads meter levels, so the mixers skip them
+                meter: false
+            };
+            root.render_to_audio_buffer(info, &mut [&mut output], &[], &mut display);
+            // there is no UI to show display buffers, so they go straight back into the pool
+            while let Ok(msg) = self.to_ui.try_recv() {
+                if let ToUIDisplayMsg::DisplayAudio {buffer, ..} = msg {
+                    self.display_buffers.push(buffer);
+                }
+            }
+            block_fn(&output);
+            frame = block_end;
+        }
+    }
+
+    /// Renders `frame_count` frames into a single buffer.
+    pub fn render(&mut self, root: &mut dyn AudioGraphNode, timeline: &MidiTimeline, frame_count: u64) -> AudioBuffer {
+        let mut result = AudioBuffer::new_with_size(frame_count as usize, self.channel_count);
+        let mut offset = 0;
+        self.render_blocks(root, timeline, frame_count, | block | {
+            for channel in 0..block.channel_count() {
+                let len = block.frame_count();
+                result.channel_mut(channel)[offset..offset + len].copy_from_slice(block.channel(channel));
+            }
+            offset += block.frame_count();
+        });
+        result
+    }
+
+    /// Renders `frame_count` frames and writes them to a wav file at `path`.
+    pub fn render_to_wav(
+        &mut self,
+        root: &mut dyn AudioGraphNode,
+        timeline: &MidiTimeline,
+        frame_count: u64,
+        path: impl AsRef<Path>,
+        sample_format: WavSampleFormat
+    ) -> Result<(), WavError> {
+        let buffer = self.render(root, timeline, frame_count);
+        WavFile::new(buffer, self.sample_rate, sample_format).write_to_path(path)
+    }
+}
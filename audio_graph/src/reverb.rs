@@ -0,0 +1,201 @@
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_platform::live_atomic::*,
+        register_audio_component,
+        audio_traits::*
+    },
+    std::sync::Arc,
+};
+
+live_design!{
+    pub Reverb = {{Reverb}} {
+        sample_rate: 48000.0
+    }
+}
+
+// The Freeverb tunings, in samples at 44.1 kHz
+const COMB_TUNINGS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNINGS: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+const FIXED_GAIN: f32 = 0.015;
+const SCALE_WET: f32 = 3.0;
+const SCALE_DAMPING: f32 = 0.4;
+const SCALE_ROOM: f32 = 0.28;
+const OFFSET_ROOM: f32 = 0.7;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+
+#[derive(Live, LiveHook, LiveRegister, LiveAtomic, Debug, LiveRead)]
+pub struct ReverbSettings {
+    #[live(0.5)] pub room_size: f32a,
+    #[live(0.5)] pub damping: f32a,
+    /// Stereo width of the reverb tail, 0.0 is mono.
+    #[live(1.0)] pub width: f32a,
+    #[live(0.25)] pub mix: f32a,
+}
+
+/// A Freeverb style reverb, processing the first input into the first output.
+///
+/// The first two channels are treated as left and right, any further channels pass through dry.
+#[derive(Live, LiveHook)]
+pub struct Reverb {
+    #[live] settings: Arc<ReverbSettings>,
+    #[live] sample_rate: f64,
+}
+
+impl LiveRegister for Reverb {
+    fn live_register(cx: &mut Cx) {
+        register_audio_component!(cx, Reverb)
+    }
+}
+
+#[derive(Default)]
+struct Comb {
+    buffer: Vec<f32>,
+    pos: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {buffer: vec![0.0; len.max(1)], ..Self::default()}
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.pos];
+        // a one pole lowpass in the feedback path makes the high frequencies die out first
+        self.filter_store = output * (1.0 - damping) + self.filter_store * damping;
+        self.buffer[self.pos] = input + self.filter_store * feedback;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[derive(Default)]
+struct Allpass {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Self {buffer: vec![0.0; len.max(1)], pos: 0}
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.pos];
+        self.buffer[self.pos] = input + buffered * ALLPASS_FEEDBACK;
+        self.pos = (self.pos + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl ReverbChannel {
+    fn new(sample_rate: f32, spread: usize) -> Self {
+        let scale = | tuning: usize | ((tuning + spread) as f32 * sample_rate / 44100.0) as usize;
+        Self {
+            combs: COMB_TUNINGS.iter().map( | tuning | Comb::new(scale(*tuning))).collect(),
+            allpasses: ALLPASS_TUNINGS.iter().map( | tuning | Allpass::new(scale(*tuning))).collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let mut output = 0.0;
+        for comb in &mut self.combs {
+            output += comb.process(input, feedback, damping);
+        }
+        for allpass in &mut self.allpasses {
+            output = allpass.process(output);
+        }
+        output
+    }
+}
+
+pub struct ReverbNode {
+    settings: Arc<ReverbSettings>,
+    sample_rate: f64,
+    left: ReverbChannel,
+    right: ReverbChannel,
+}
+
+impl ReverbNode {
+    pub fn new(settings: Arc<ReverbSettings>, sample_rate: f64) -> Self {
+        Self {
+            settings,
+            sample_rate,
+            left: ReverbChannel::new(sample_rate as f32, 0),
+            right: ReverbChannel::new(sample_rate as f32, STEREO_SPREAD),
+        }
+    }
+}
+
+impl AudioGraphNode for ReverbNode {
+    fn handle_midi_data(&mut self, _data: MidiData) {
+    }
+
+    fn all_notes_off(&mut self) {
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        // the filter lengths are tuned to the sample rate
+        if sample_rate != self.sample_rate {
+            *self = Self::new(self.settings.clone(), sample_rate);
+        }
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        let output = &mut outputs[0];
+        // effects work in place on a copy of their input
+        match inputs.first() {
+            Some(input) => {output.copy_from(input);}
+            None => output.zero()
+        }
+        if output.channel_count() == 0 {
+            return
+        }
+        let feedback = self.settings.room_size.get().clamp(0.0, 1.0) * SCALE_ROOM + OFFSET_ROOM;
+        let damping = self.settings.damping.get().clamp(0.0, 1.0) * SCALE_DAMPING;
+        let width = self.settings.width.get().clamp(0.0, 1.0);
+        let mix = self.settings.mix.get().clamp(0.0, 1.0);
+        let wet_1 = mix * SCALE_WET * (width / 2.0 + 0.5);
+        let wet_2 = mix * SCALE_WET * ((1.0 - width) / 2.0);
+        let dry = 1.0 - mix;
+
+        let is_stereo = output.channel_count() > 1;
+        for frame in 0..output.frame_count() {
+            let in_left = output.channel(0)[frame];
+            let in_right = if is_stereo {output.channel(1)[frame]} else {in_left};
+            let input = (in_left + in_right) * FIXED_GAIN;
+            let out_left = self.left.process(input, feedback, damping);
+            let out_right = self.right.process(input, feedback, damping);
+            output.channel_mut(0)[frame] = in_left * dry + out_left * wet_1 + out_right * wet_2;
+            if is_stereo {
+                output.channel_mut(1)[frame] = in_right * dry + out_right * wet_1 + out_left * wet_2;
+            }
+        }
+    }
+}
+
+impl AudioComponent for Reverb {
+    fn get_graph_node(&mut self, _cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
+        Box::new(ReverbNode::new(self.settings.clone(), self.sample_rate))
+    }
+
+    fn handle_event_with(&mut self, _cx: &mut Cx, _event: &Event, _dispatch_action: &mut dyn FnMut(&mut Cx, AudioComponentAction)) {
+    }
+
+    fn audio_query(&mut self, _query: &AudioQuery, _callback: &mut Option<AudioQueryCb>) -> AudioResult<'_> {
+        AudioResult::not_found()
+    }
+}
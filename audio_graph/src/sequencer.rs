@@ -0,0 +1,329 @@
+use {
+    crate::{
+        makepad_platform::*,
+        register_audio_component,
+        audio_traits::*
+    },
+    std::{array, mem},
+};
+
+live_design!{
+    pub Sequencer = {{Sequencer}} {
+        sample_rate: 48000.0
+    }
+}
+
+/// MIDI messages with their time in seconds from the start, sorted by time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MidiSequence {
+    events: Vec<(f64, MidiData)>,
+}
+
+impl MidiSequence {
+    /// Builds a sequence from timed messages, which are sorted while keeping simultaneous ones in order.
+    pub fn from_events(mut events: Vec<(f64, MidiData)>) -> Self {
+        events.sort_by( | a, b | a.0.total_cmp(&b.0));
+        Self {events}
+    }
+
+    /// The channel messages of all tracks, timed with the tempo map of the file.
+    pub fn from_midi_file(file: &MidiFile) -> Self {
+        Self {events: file.timed_midi_events()}
+    }
+
+    pub fn events(&self) -> &[(f64, MidiData)] {
+        &self.events
+    }
+
+    /// The time of the last message in seconds.
+    pub fn duration(&self) -> f64 {
+        self.events.last().map( | (time, _) | *time).unwrap_or(0.0)
+    }
+}
+
+/// Blocks split at a message are rendered through scratch buffers made up front, for this many inputs and
+/// outputs of up to `SCRATCH_FRAMES` stereo frames, so splitting a block doesn't allocate on the audio thread.
+const SCRATCH_BUFFERS: usize = 4;
+const SCRATCH_FRAMES: usize = 1024;
+
+fn time_to_frame(time: f64, sample_rate: f64) -> u64 {
+    (time * sample_rate).round().max(0.0) as u64
+}
+
+/// Plays a `MidiSequence` into the node it wraps.
+///
+/// Blocks are split where messages are due, so they reach the wrapped node on the exact frame. Live MIDI sent
+/// to the sequencer is passed through at the start of the next block. Messages are timed at the sample rate
+/// the sequencer was created with, until the graph is switched to another one.
+pub struct MidiSequencer {
+    sequence: MidiSequence,
+    sample_rate: f64,
+    position: u64,
+    next_event: usize,
+    is_playing: bool,
+    input: Box<dyn AudioGraphNode + Send>,
+    output_buffers: Vec<AudioBuffer>,
+    input_buffers: Vec<AudioBuffer>,
+}
+
+impl MidiSequencer {
+    pub fn new(input: Box<dyn AudioGraphNode + Send>, sample_rate: f64) -> Self {
+        Self {
+            sequence: MidiSequence::default(),
+            sample_rate,
+            position: 0,
+            next_event: 0,
+            is_playing: false,
+            input,
+            output_buffers: (0..SCRATCH_BUFFERS).map( | _ | AudioBuffer::new_with_size(SCRATCH_FRAMES, 2)).collect(),
+            input_buffers: (0..SCRATCH_BUFFERS).map( | _ | AudioBuffer::new_with_size(SCRATCH_FRAMES, 2)).collect(),
+        }
+    }
+
+    /// Replaces the sequence and rewinds to the start, keeping the transport state.
+    pub fn load(&mut self, sequence: MidiSequence) {
+        self.sequence = sequence;
+        self.seek(0.0);
+    }
+
+    pub fn play(&mut self) {
+        self.is_playing = true;
+    }
+
+    /// Stops playback, silencing any notes the sequence left on.
+    pub fn stop(&mut self) {
+        self.is_playing = false;
+        self.input.all_notes_off();
+    }
+
+    /// Moves the playback position to `seconds`. Messages before it are skipped, not replayed.
+    pub fn seek(&mut self, seconds: f64) {
+        self.input.all_notes_off();
+        self.position = time_to_frame(seconds, self.sample_rate);
+        let sample_rate = self.sample_rate;
+        let position = self.position;
+        self.next_event = self.sequence.events.partition_point( | (time, _) | time_to_frame(*time, sample_rate) < position);
+    }
+
+    /// Keeps the playback position in seconds, and switches the wrapped node to the new rate too.
+    pub fn set_sample_rate(&mut self, sample_rate: f64) {
+        let seconds = self.position_seconds();
+        self.sample_rate = sample_rate;
+        self.position = time_to_frame(seconds, sample_rate);
+        self.input.set_sample_rate(sample_rate);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    /// Whether every message of the sequence has been played.
+    pub fn is_finished(&self) -> bool {
+        self.next_event >= self.sequence.events.len()
+    }
+
+    pub fn position_seconds(&self) -> f64 {
+        self.position as f64 / self.sample_rate
+    }
+}
+
+impl AudioGraphNode for MidiSequencer {
+    fn handle_midi_data(&mut self, data: MidiData) {
+        self.input.handle_midi_data(data);
+    }
+
+    fn all_notes_off(&mut self) {
+        self.input.all_notes_off();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        MidiSequencer::set_sample_rate(self, sample_rate);
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        display: &mut DisplayAudioGraph
+    ) {
+        if !self.is_playing {
+            self.input.render_to_audio_buffer(info, outputs, inputs, display);
+            return
+        }
+        let frame_count = outputs[0].frame_count();
+        let mut offset = 0;
+        while offset < frame_count {
+            while let Some((time, data)) = self.sequence.events.get(self.next_event) {
+                if time_to_frame(*time, self.sample_rate) > self.position {
+                    break;
+                }
+                self.input.handle_midi_data(*data);
+                self.next_event += 1;
+            }
+            let mut end = frame_count;
+            if let Some((time, _)) = self.sequence.events.get(self.next_event) {
+                let frames_to_event = time_to_frame(*time, self.sample_rate) - self.position;
+                end = end.min(offset + frames_to_event as usize);
+            }
+            if offset == 0 && end == frame_count {
+                self.input.render_to_audio_buffer(info, outputs, inputs, display);
+            }
+            else {
+                // render the part of the block up to the next message separately
+                let len = end - offset;
+                let info = AudioInfo {
+                    time: info.time.map( | time | AudioTime {sample_time: time.sample_time + offset as f64, ..time}),
+                    ..info
+                };
+                // inputs past the scratch buffers are left out of split blocks
+                for (input, buffer) in inputs.iter().zip(self.input_buffers.iter_mut()) {
+                    buffer.resize(len, input.channel_count());
+                    for channel in 0..input.channel_count() {
+                        buffer.channel_mut(channel).copy_from_slice(&input.channel(channel)[offset..end]);
+                    }
+                }
+                let sub_inputs: [&AudioBuffer; SCRATCH_BUFFERS] = array::from_fn( | index | &self.input_buffers[index]);
+                let sub_inputs = &sub_inputs[..inputs.len().min(SCRATCH_BUFFERS)];
+                // a node with more outputs than there are scratch buffers gets more, once
+                if self.output_buffers.len() < outputs.len() {
+                    self.output_buffers.resize_with(outputs.len(), AudioBuffer::default);
+                }
+                // the scratch buffers stand in for the outputs while the part is rendered
+                for (output, buffer) in outputs.iter_mut().zip(self.output_buffers.iter_mut()) {
+                    buffer.resize(len, output.channel_count());
+                    buffer.zero();
+                    mem::swap(&mut **output, buffer);
+                }
+                self.input.render_to_audio_buffer(info, outputs, sub_inputs, display);
+                for (output, buffer) in outputs.iter_mut().zip(self.output_buffers.iter_mut()) {
+                    mem::swap(&mut **output, buffer);
+                    for channel in 0..buffer.channel_count() {
+                        output.channel_mut(channel)[offset..end].copy_from_slice(buffer.channel(channel));
+                    }
+                }
+            }
+            self.position += (end - offset) as u64;
+            offset = end;
+        }
+    }
+}
+
+enum FromUI {
+    Load(MidiSequence),
+    Play,
+    Stop,
+    Seek(f64),
+}
+
+/// An audio component that plays MIDI sequences, such as Standard MIDI Files, into its input.
+#[derive(Live)]
+pub struct Sequencer {
+    #[live] input: AudioComponentRef,
+    #[live] sample_rate: f64,
+    #[rust] from_ui: FromUISender<FromUI>,
+}
+
+impl LiveRegister for Sequencer {
+    fn live_register(cx: &mut Cx) {
+        register_audio_component!(cx, Sequencer)
+    }
+}
+
+impl LiveHook for Sequencer {}
+
+impl Sequencer {
+    pub fn load(&self, sequence: MidiSequence) {
+        let _ = self.from_ui.send(FromUI::Load(sequence));
+    }
+
+    pub fn play(&self) {
+        let _ = self.from_ui.send(FromUI::Play);
+    }
+
+    pub fn stop(&self) {
+        let _ = self.from_ui.send(FromUI::Stop);
+    }
+
+    pub fn seek(&self, seconds: f64) {
+        let _ = self.from_ui.send(FromUI::Seek(seconds));
+    }
+}
+
+struct Node {
+    from_ui: FromUIReceiver<FromUI>,
+    sequencer: MidiSequencer,
+}
+
+impl AudioGraphNode for Node {
+    fn handle_midi_data(&mut self, data: MidiData) {
+        self.sequencer.handle_midi_data(data);
+    }
+
+    fn all_notes_off(&mut self) {
+        self.sequencer.all_notes_off();
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sequencer.set_sample_rate(sample_rate);
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        inputs: &[&AudioBuffer],
+        display: &mut DisplayAudioGraph
+    ) {
+        while let Ok(msg) = self.from_ui.try_recv() {
+            match msg {
+                FromUI::Load(sequence) => self.sequencer.load(sequence),
+                FromUI::Play => self.sequencer.play(),
+                FromUI::Stop => self.sequencer.stop(),
+                FromUI::Seek(seconds) => self.sequencer.seek(seconds),
+            }
+        }
+        self.sequencer.render_to_audio_buffer(info, outputs, inputs, display);
+    }
+}
+
+// a sequencer without an input still keeps time, it just has nothing to play into
+struct Silence;
+
+impl AudioGraphNode for Silence {
+    fn handle_midi_data(&mut self, _data: MidiData) {}
+    fn all_notes_off(&mut self) {}
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        _inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        outputs[0].zero();
+    }
+}
+
+impl AudioComponent for Sequencer {
+    fn get_graph_node(&mut self, cx: &mut Cx) -> Box<dyn AudioGraphNode + Send> {
+        self.from_ui.new_channel();
+        let input = match self.input.as_mut() {
+            Some(input) => input.get_graph_node(cx),
+            None => Box::new(Silence)
+        };
+        Box::new(Node {
+            from_ui: self.from_ui.receiver(),
+            sequencer: MidiSequencer::new(input, self.sample_rate),
+        })
+    }
+
+    fn handle_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, AudioComponentAction)) {
+        if let Some(input) = self.input.as_mut() {
+            input.handle_event_with(cx, event, dispatch_action);
+        }
+    }
+
+    fn audio_query(&mut self, query: &AudioQuery, callback: &mut Option<AudioQueryCb>) -> AudioResult<'_> {
+        self.input.audio_query(query, callback)
+    }
+}
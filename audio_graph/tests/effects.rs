@@ -0,0 +1,272 @@
+use {
+    makepad_audio_graph::{
+        makepad_platform::*,
+        makepad_platform::live_atomic::*,
+        compressor::*,
+        delay::*,
+        equalizer::*,
+        reverb::*,
+        AudioGraphNode,
+        DisplayAudioGraph,
+        ToUIDisplayMsg,
+    },
+    std::{
+        f32::consts::PI,
+        sync::Arc,
+    },
+};
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+// Runs `input` through `node` in blocks of 128 frames, like an audio device would.
+fn process(node: &mut dyn AudioGraphNode, input: &AudioBuffer) -> AudioBuffer {
+    let to_ui = ToUIReceiver::<ToUIDisplayMsg>::default();
+    let sender = to_ui.sender();
+    let mut buffers = Vec::new();
+    let mut result = AudioBuffer::new_like(input);
+    let mut block_in = AudioBuffer::default();
+    let mut block_out = AudioBuffer::default();
+    let info = AudioInfo {device_id: AudioDeviceId::default(), time: None};
+    for start in (0..input.frame_count()).step_by(128) {
+        let end = (start + 128).min(input.frame_count());
+        block_in.resize(end - start, input.channel_count());
+        for channel in 0..input.channel_count() {
+            block_in.channel_mut(channel).copy_from_slice(&input.channel(channel)[start..end]);
+        }
+        block_out.resize(end - start, input.channel_count());
+        let mut display = DisplayAudioGraph {to_ui: &sender, buffers: &mut buffers, meter: false};
+        node.render_to_audio_buffer(info, &mut [&mut block_out], &[&block_in], &mut display);
+        for channel in 0..input.channel_count() {
+            result.channel_mut(channel)[start..end].copy_from_slice(block_out.channel(channel));
+        }
+    }
+    result
+}
+
+fn impulse(frame_count: usize, channel_count: usize) -> AudioBuffer {
+    let mut buffer = AudioBuffer::new_with_size(frame_count, channel_count);
+    for channel in 0..channel_count {
+        buffer.channel_mut(channel)[0] = 1.0;
+    }
+    buffer
+}
+
+fn sine(freq: f32, amplitude: f32, frame_count: usize) -> AudioBuffer {
+    let data = (0..frame_count).map( | i | (2.0 * PI * freq * i as f32 / SAMPLE_RATE as f32).sin() * amplitude).collect();
+    AudioBuffer::from_data(data, 1)
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples.iter().fold(0.0, | peak, sample | peak.max(sample.abs()))
+}
+
+fn energy(samples: &[f32]) -> f32 {
+    samples.iter().map( | sample | sample * sample).sum()
+}
+
+fn delay_settings(time: f32, feedback: f32, cross: f32, mix: f32) -> Arc<DelaySettings> {
+    Arc::new(DelaySettings {time: time.into(), feedback: feedback.into(), cross: cross.into(), mix: mix.into()})
+}
+
+#[test]
+fn delay_repeats_with_feedback() {
+    let mut node = DelayNode::new(delay_settings(0.01, 0.5, 0.0, 1.0), SAMPLE_RATE);
+    let output = process(&mut node, &impulse(2000, 2));
+    let left = output.channel(0);
+    // 10 ms is 480 frames, every repeat is half as loud
+    for (frame, expected) in [(480, 1.0), (960, 0.5), (1440, 0.25)] {
+        assert!((left[frame] - expected).abs() < 1e-6, "frame {}: {}", frame, left[frame]);
+    }
+    assert_eq!(energy(&left[..480]), 0.0);
+    assert!((energy(left) - (1.0 + 0.25 + 0.0625 + 0.015625)).abs() < 1e-5);
+}
+
+#[test]
+fn delay_ping_pongs_between_channels() {
+    let mut node = DelayNode::new(delay_settings(0.01, 1.0, 1.0, 1.0), SAMPLE_RATE);
+    let mut input = AudioBuffer::new_with_size(1500, 2);
+    input.channel_mut(0)[0] = 1.0;
+    let output = process(&mut node, &input);
+    assert_eq!(output.channel(0)[480], 1.0);
+    assert_eq!(output.channel(1)[480], 0.0);
+    // the first repeat crossed over to the right
+    assert_eq!(output.channel(0)[960], 0.0);
+    assert_eq!(output.channel(1)[960], 1.0);
+    assert_eq!(output.channel(0)[1440], 1.0);
+}
+
+#[test]
+fn delay_time_follows_the_sample_rate() {
+    let mut node = DelayNode::new(delay_settings(0.01, 0.0, 0.0, 1.0), SAMPLE_RATE);
+    node.set_sample_rate(96000.0);
+    let output = process(&mut node, &impulse(2000, 1));
+    // 10 ms is 960 frames at 96 kHz
+    assert_eq!(output.channel(0)[960], 1.0);
+    assert_eq!(energy(output.channel(0)), 1.0);
+}
+
+#[test]
+fn dry_delay_passes_through() {
+    let mut node = DelayNode::new(delay_settings(0.01, 0.5, 0.0, 0.0), SAMPLE_RATE);
+    let input = sine(440.0, 0.5, 1000);
+    assert_eq!(process(&mut node, &input).data, input.data);
+}
+
+fn reverb_settings(room_size: f32, mix: f32) -> Arc<ReverbSettings> {
+    Arc::new(ReverbSettings {room_size: room_size.into(), damping: 0.5.into(), width: 1.0.into(), mix: mix.into()})
+}
+
+#[test]
+fn reverb_tail_decays() {
+    let mut node = ReverbNode::new(reverb_settings(0.5, 1.0), SAMPLE_RATE);
+    let output = process(&mut node, &impulse(48000 * 4, 2));
+    let left = output.channel(0);
+    assert!(left.iter().all( | sample | sample.is_finite()));
+    // nothing comes out before the shortest comb filter
+    assert_eq!(energy(&left[..1000]), 0.0);
+    let second = | n: usize | energy(&left[48000 * n..48000 * (n + 1)]);
+    assert!(second(0) > 0.0);
+    assert!(second(1) < second(0) * 0.1);
+    assert!(second(3) < second(0) * 1e-4);
+    // the channels use different delay lengths, which gives the stereo image
+    assert_ne!(output.channel(0), output.channel(1));
+}
+
+#[test]
+fn reverb_room_size_lengthens_the_tail() {
+    let tail = | room_size | {
+        let mut node = ReverbNode::new(reverb_settings(room_size, 1.0), SAMPLE_RATE);
+        let output = process(&mut node, &impulse(48000 * 2, 2));
+        energy(&output.channel(0)[48000..])
+    };
+    assert!(tail(0.9) > tail(0.2) * 10.0);
+}
+
+#[test]
+fn dry_reverb_passes_through() {
+    let mut node = ReverbNode::new(reverb_settings(0.5, 0.0), SAMPLE_RATE);
+    let input = sine(440.0, 0.5, 1000);
+    assert_eq!(process(&mut node, &input).data, input.data);
+}
+
+// the steady state gain of a sine after the filter has settled
+fn sine_gain(node: &mut dyn AudioGraphNode, freq: f32) -> f32 {
+    let output = process(node, &sine(freq, 0.5, 48000));
+    peak(&output.channel(0)[24000..]) / 0.5
+}
+
+fn equalizer_settings() -> EqualizerSettings {
+    EqualizerSettings {
+        low_cut: 0.0.into(),
+        low_freq: 100.0.into(),
+        low_gain: 0.0.into(),
+        mid_freq: 1000.0.into(),
+        mid_gain: 0.0.into(),
+        mid_q: 0.707.into(),
+        high_freq: 8000.0.into(),
+        high_gain: 0.0.into(),
+        gain: 0.0.into(),
+    }
+}
+
+fn db(gain: f32) -> f32 {
+    20.0 * gain.log10()
+}
+
+#[test]
+fn flat_equalizer_is_transparent() {
+    let mut node = EqualizerNode::new(Arc::new(equalizer_settings()), SAMPLE_RATE);
+    for freq in [50.0, 1000.0, 10000.0] {
+        assert!(db(sine_gain(&mut node, freq)).abs() < 0.05, "{} Hz", freq);
+    }
+}
+
+#[test]
+fn equalizer_boosts_and_cuts_bands() {
+    let settings = equalizer_settings();
+    settings.mid_gain.set(6.0);
+    settings.mid_q.set(2.0);
+    settings.low_gain.set(-12.0);
+    settings.low_freq.set(200.0);
+    settings.high_gain.set(3.0);
+    let settings = Arc::new(settings);
+    let gain = | freq | db(sine_gain(&mut EqualizerNode::new(settings.clone(), SAMPLE_RATE), freq));
+    assert!((gain(1000.0) - 6.0).abs() < 0.2);
+    assert!((gain(30.0) + 12.0).abs() < 0.5);
+    assert!((gain(20000.0) - 3.0).abs() < 0.3);
+    assert!(gain(4000.0).abs() < 1.5);
+}
+
+#[test]
+fn equalizer_low_cut_removes_rumble() {
+    let settings = equalizer_settings();
+    settings.low_cut.set(200.0);
+    let mut node = EqualizerNode::new(Arc::new(settings), SAMPLE_RATE);
+    assert!(db(sine_gain(&mut node, 20.0)) < -35.0);
+    assert!(db(sine_gain(&mut node, 2000.0)).abs() < 0.1);
+}
+
+#[test]
+fn biquad_low_pass_has_unity_dc_gain() {
+    let mut filter = Biquad::new(BiquadCoefficients::low_pass(48000.0, 1000.0, 0.707));
+    let mut value = 0.0;
+    for _ in 0..10000 {
+        value = filter.process(1.0);
+    }
+    assert!((value - 1.0).abs() < 1e-4);
+    filter.reset();
+    assert_eq!(filter.process(0.0), 0.0);
+}
+
+fn compressor_settings(threshold: f32, ratio: f32, attack: f32, release: f32) -> Arc<CompressorSettings> {
+    Arc::new(CompressorSettings {
+        threshold: threshold.into(),
+        ratio: ratio.into(),
+        knee: 0.0.into(),
+        attack: attack.into(),
+        release: release.into(),
+        makeup: 0.0.into(),
+    })
+}
+
+#[test]
+fn compressor_gain_curve() {
+    assert_eq!(compressor_gain_reduction(-30.0, -20.0, 4.0, 0.0), 0.0);
+    assert_eq!(compressor_gain_reduction(0.0, -20.0, 4.0, 0.0), 15.0);
+    // a ratio of one never compresses
+    assert_eq!(compressor_gain_reduction(0.0, -20.0, 1.0, 0.0), 0.0);
+    // the soft knee starts below the threshold and meets the hard curve above it
+    assert!(compressor_gain_reduction(-22.0, -20.0, 4.0, 6.0) > 0.0);
+    assert_eq!(compressor_gain_reduction(-10.0, -20.0, 4.0, 6.0), 7.5);
+}
+
+#[test]
+fn compressor_reduces_loud_signals() {
+    let mut node = CompressorNode::new(compressor_settings(-20.0, 4.0, 0.001, 0.05), SAMPLE_RATE);
+    let output = process(&mut node, &AudioBuffer::from_data(vec![1.0; 48000], 1));
+    // 20 dB over the threshold at 4:1 comes out 5 dB over it
+    assert!((db(output.channel(0)[47999]) + 15.0).abs() < 0.01);
+    assert!((node.gain_reduction() - 15.0).abs() < 0.01);
+    // the attack is not instant
+    assert!(output.channel(0)[0] > 0.9);
+}
+
+#[test]
+fn compressor_leaves_quiet_signals_alone() {
+    let mut node = CompressorNode::new(compressor_settings(-20.0, 4.0, 0.001, 0.05), SAMPLE_RATE);
+    let input = sine(440.0, 0.05, 4800);
+    assert_eq!(process(&mut node, &input).data, input.data);
+}
+
+#[test]
+fn compressor_releases_after_loud_part() {
+    let mut node = CompressorNode::new(compressor_settings(-20.0, 20.0, 0.0, 0.05), SAMPLE_RATE);
+    let mut data = vec![1.0; 4800];
+    data.extend(vec![0.01; 48000]);
+    let output = process(&mut node, &AudioBuffer::from_data(data, 1));
+    // as a limiter the loud part stays close to the threshold
+    assert!(db(output.channel(0)[4799]) < -18.0);
+    // right after the loud part the quiet signal is still turned down, a while later it is back
+    assert!(output.channel(0)[4800] < 0.002);
+    assert!((output.channel(0)[4800 + 24000] - 0.01).abs() < 1e-4);
+}
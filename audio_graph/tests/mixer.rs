@@ -0,0 +1,188 @@
+use {
+    makepad_audio_graph::{
+        makepad_platform::*,
+        mixer::*,
+        AudioGraphNode,
+        AudioMeterLevels,
+        DisplayAudioGraph,
+        ToUIDisplayMsg,
+    },
+    std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+// Outputs a constant level, and counts how often it was rendered.
+struct LevelNode {
+    level: f32,
+    renders: Arc<AtomicUsize>,
+}
+
+impl AudioGraphNode for LevelNode {
+    fn handle_midi_data(&mut self, _data: MidiData) {}
+
+    fn all_notes_off(&mut self) {}
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        _inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        self.renders.fetch_add(1, Ordering::Relaxed);
+        for channel in 0..outputs[0].channel_count() {
+            outputs[0].channel_mut(channel).fill(self.level);
+        }
+    }
+}
+
+fn input(id: LiveId, level: f32, channel: MixerChannel) -> MixerInput {
+    MixerInput {
+        id,
+        channel,
+        graph_node: Box::new(LevelNode {level, renders: Arc::default()}),
+    }
+}
+
+struct Mixed {
+    output: AudioBuffer,
+    meters: Vec<(Option<LiveId>, AudioMeterLevels)>,
+}
+
+// Renders one block of 16 frames with the given number of channels.
+fn mix(inputs: Vec<MixerInput>, channel_count: usize) -> Mixed {
+    let mut node = MixerNode::new(inputs);
+    let to_ui = ToUIReceiver::<ToUIDisplayMsg>::default();
+    let sender = to_ui.sender();
+    let mut buffers = Vec::new();
+    let mut output = AudioBuffer::new_with_size(16, channel_count);
+    // the mixer overwrites what was in the output
+    output.channel_mut(0).fill(9.0);
+    let info = AudioInfo {device_id: AudioDeviceId::default(), time: None};
+    let mut display = DisplayAudioGraph {to_ui: &sender, buffers: &mut buffers, meter: true};
+    node.render_to_audio_buffer(info, &mut [&mut output], &[], &mut display);
+    let mut meters = Vec::new();
+    while let Ok(msg) = to_ui.try_recv() {
+        if let ToUIDisplayMsg::Meter {input, levels} = msg {
+            meters.push((input, levels));
+        }
+    }
+    Mixed {output, meters}
+}
+
+fn levels(mixed: &Mixed) -> Vec<f32> {
+    (0..mixed.output.channel_count()).map( | channel | {
+        let samples = mixed.output.channel(channel);
+        assert!(samples.iter().all( | sample | *sample == samples[0]), "the output is not constant");
+        samples[0]
+    }).collect()
+}
+
+fn assert_levels(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len());
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert!((actual - expected).abs() < 1e-6, "{:?} is not {:?}", actual, expected);
+    }
+}
+
+fn channel(gain: f32, pan: f32, mute: bool, solo: bool) -> MixerChannel {
+    MixerChannel {gain, pan, mute, solo}
+}
+
+#[test]
+fn sums_inputs_with_their_gain() {
+    let mixed = mix(vec![
+        input(live_id!(a), 0.25, MixerChannel::default()),
+        input(live_id!(b), 0.5, channel(0.5, 0.0, false, false)),
+        input(live_id!(c), 0.5, channel(0.0, 0.0, false, false)),
+    ], 2);
+    assert_levels(&levels(&mixed), &[0.5, 0.5]);
+}
+
+#[test]
+fn pan_turns_down_the_other_side() {
+    let mixed = mix(vec![input(live_id!(a), 1.0, channel(1.0, -1.0, false, false))], 2);
+    assert_levels(&levels(&mixed), &[1.0, 0.0]);
+    let mixed = mix(vec![input(live_id!(a), 1.0, channel(0.5, 0.5, false, false))], 2);
+    assert_levels(&levels(&mixed), &[0.25, 0.5]);
+    // a mono output has nothing to pan between
+    let mixed = mix(vec![input(live_id!(a), 1.0, channel(1.0, -1.0, false, false))], 1);
+    assert_levels(&levels(&mixed), &[1.0]);
+}
+
+#[test]
+fn muted_inputs_are_silent_but_keep_rendering() {
+    let renders = Arc::new(AtomicUsize::new(0));
+    let muted = MixerInput {
+        id: live_id!(muted),
+        channel: channel(1.0, 0.0, true, false),
+        graph_node: Box::new(LevelNode {level: 1.0, renders: renders.clone()}),
+    };
+    let mixed = mix(vec![muted, input(live_id!(a), 0.5, MixerChannel::default())], 2);
+    assert_levels(&levels(&mixed), &[0.5, 0.5]);
+    assert_eq!(renders.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn solo_silences_the_other_inputs() {
+    let mixed = mix(vec![
+        input(live_id!(a), 0.25, channel(1.0, 0.0, false, true)),
+        input(live_id!(b), 0.5, MixerChannel::default()),
+        input(live_id!(c), 0.125, channel(1.0, 0.0, false, true)),
+    ], 2);
+    assert_levels(&levels(&mixed), &[0.375, 0.375]);
+}
+
+#[test]
+fn mute_wins_over_solo() {
+    // a muted solo still silences the inputs that aren't soloed
+    let mixed = mix(vec![
+        input(live_id!(a), 0.25, channel(1.0, 0.0, true, true)),
+        input(live_id!(b), 0.5, MixerChannel::default()),
+    ], 2);
+    assert_levels(&levels(&mixed), &[0.0, 0.0]);
+}
+
+#[test]
+fn meters_every_input_and_the_output() {
+    let mixed = mix(vec![
+        input(live_id!(a), 0.5, channel(1.0, -1.0, false, false)),
+        input(live_id!(b), 0.25, channel(1.0, 0.0, true, false)),
+    ], 2);
+    let meters: Vec<_> = mixed.meters.iter().map( | (input, levels) | (*input, levels.peak)).collect();
+    // input levels are measured after gain, pan and mute
+    assert_eq!(meters, [
+        (Some(live_id!(a)), [0.5, 0.0]),
+        (Some(live_id!(b)), [0.0, 0.0]),
+        (None, [0.5, 0.0]),
+    ]);
+}
+
+#[test]
+fn channel_setters() {
+    let mut mixer = Mixer::new(&mut Cx::new(Box::new( | _, _ | {})));
+    assert_eq!(mixer.channel(live_id!(a)), MixerChannel::default());
+    mixer.set_gain(live_id!(a), 0.5);
+    mixer.set_pan(live_id!(a), -3.0);
+    mixer.set_mute(live_id!(a), true);
+    mixer.set_solo(live_id!(b), true);
+    assert_eq!(mixer.channel(live_id!(a)), channel(0.5, -1.0, true, false));
+    assert_eq!(mixer.channel(live_id!(b)), channel(1.0, 0.0, false, true));
+}
+
+#[test]
+fn meter_levels_from_buffer() {
+    assert_eq!(AudioMeterLevels::from_buffer(&AudioBuffer::default()), AudioMeterLevels::default());
+    let stereo = AudioBuffer::from_data(vec![0.5, 0.0, -0.5, 0.0, 1.0, -1.0, 1.0, -1.0], 2);
+    let levels = AudioMeterLevels::from_buffer(&stereo);
+    assert_eq!(levels.peak, [0.5, 1.0]);
+    assert!((levels.rms[0] - 0.125f32.sqrt()).abs() < 1e-6);
+    assert!((levels.rms[1] - 1.0).abs() < 1e-6);
+    // a mono buffer shows up on both sides
+    let mono = AudioBuffer::from_data(vec![0.0, -0.25], 1);
+    let levels = AudioMeterLevels::from_buffer(&mono);
+    assert_eq!(levels.peak, [0.25, 0.25]);
+    assert_eq!(levels.rms[0], levels.rms[1]);
+}
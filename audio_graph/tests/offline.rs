@@ -0,0 +1,138 @@
+use makepad_audio_graph::{
+    makepad_platform::*,
+    AudioGraphNode,
+    DisplayAudioGraph,
+    MidiSequence,
+    MidiSequencer,
+    MidiTimeline,
+    OfflineRenderer,
+};
+
+// Outputs the velocity of the held note as a constant level, and a running frame counter on the
+// second channel so block boundaries and the timing info can be checked.
+#[derive(Default)]
+struct GateNode {
+    level: f32,
+    frame: u64,
+    midi_log: Vec<u64>,
+}
+
+impl AudioGraphNode for GateNode {
+    fn handle_midi_data(&mut self, data: MidiData) {
+        self.midi_log.push(self.frame);
+        if let MidiEvent::Note(note) = data.decode() {
+            self.level = if note.is_on {note.velocity as f32 / 127.0} else {0.0};
+        }
+    }
+
+    fn all_notes_off(&mut self) {
+        self.level = 0.0;
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        _inputs: &[&AudioBuffer],
+        display: &mut DisplayAudioGraph
+    ) {
+        assert_eq!(info.time.unwrap().sample_time as u64, self.frame);
+        let output = &mut outputs[0];
+        let frame_count = output.frame_count();
+        output.channel_mut(0).fill(self.level);
+        for (i, sample) in output.channel_mut(1).iter_mut().enumerate() {
+            *sample = (self.frame + i as u64) as f32;
+        }
+        if let Some(mut buffer) = display.pop_buffer_resize(frame_count, 2) {
+            buffer.copy_from(output);
+            display.send_buffer(true, 0, buffer);
+        }
+        self.frame += frame_count as u64;
+    }
+}
+
+fn timeline() -> MidiTimeline {
+    let mut timeline = MidiTimeline::new();
+    timeline.note(100, 50, 0, 60, 127).note(301, 7, 0, 64, 127);
+    timeline
+}
+
+#[test]
+fn timeline_is_sorted_and_stable() {
+    let mut timeline = MidiTimeline::new();
+    timeline.note(10, 5, 0, 60, 100);
+    timeline.push(15, MidiControlChange {channel: 0, param: 7, value: 1});
+    timeline.push(0, MidiControlChange {channel: 0, param: 7, value: 2});
+    let frames: Vec<u64> = timeline.events().iter().map( | event | event.frame).collect();
+    assert_eq!(frames, vec![0, 10, 15, 15]);
+    // the note off was pushed first, so it stays in front of the control change
+    assert_eq!(timeline.events()[2].data.data[0], 0x80);
+    assert_eq!(timeline.end_frame(), 16);
+}
+
+#[test]
+fn midi_is_sample_accurate() {
+    let mut renderer = OfflineRenderer::new(48000, 64, 2);
+    let mut node = GateNode::default();
+    let buffer = renderer.render(&mut node, &timeline(), 400);
+    assert_eq!(buffer.frame_count(), 400);
+    assert_eq!(node.midi_log, vec![100, 150, 301, 308]);
+    let gate = buffer.channel(0);
+    for (frame, sample) in gate.iter().enumerate() {
+        let on = (100..150).contains(&frame) || (301..308).contains(&frame);
+        assert_eq!(*sample, if on {1.0} else {0.0}, "frame {}", frame);
+    }
+    for (frame, sample) in buffer.channel(1).iter().enumerate() {
+        assert_eq!(*sample, frame as f32);
+    }
+}
+
+#[test]
+fn output_does_not_depend_on_block_size() {
+    let reference = OfflineRenderer::new(44100, 1, 2).render(&mut GateNode::default(), &timeline(), 500);
+    for block_size in [7, 64, 256, 1024] {
+        let mut renderer = OfflineRenderer::new(44100, block_size, 2);
+        let buffer = renderer.render(&mut GateNode::default(), &timeline(), 500);
+        assert_eq!(buffer.data, reference.data, "block size {}", block_size);
+    }
+}
+
+#[test]
+fn blocks_are_split_at_events() {
+    let mut renderer = OfflineRenderer::new(44100, 128, 2);
+    let mut sizes = Vec::new();
+    renderer.render_blocks(&mut GateNode::default(), &timeline(), 400, | block | sizes.push(block.frame_count()));
+    assert_eq!(sizes, vec![100, 50, 128, 23, 7, 92]);
+}
+
+#[test]
+fn renders_to_wav() {
+    let path = std::env::temp_dir().join(format!("makepad_offline_test_{}.wav", std::process::id()));
+    let mut renderer = OfflineRenderer::new(22050, 64, 2);
+    assert_eq!(renderer.seconds_to_frames(0.5), 11025);
+    renderer.render_to_wav(&mut GateNode::default(), &timeline(), 400, &path, WavSampleFormat::F32).unwrap();
+    let wav = WavFile::read_from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(wav.sample_rate, 22050);
+    assert_eq!(wav.buffer.frame_count(), 400);
+    assert_eq!(wav.buffer.channel(0)[120], 1.0);
+    assert_eq!(wav.buffer.channel(0)[200], 0.0);
+}
+
+#[test]
+fn graph_renders_at_the_chosen_sample_rate() {
+    let note = | is_on | -> MidiData {MidiNote {is_on, channel: 0, note_number: 60, velocity: 127}.into()};
+    let sequence = MidiSequence::from_events(vec![(0.5, note(true)), (0.75, note(false))]);
+    for sample_rate in [22050, 44100, 96000] {
+        // the sequencer is made for another rate, the renderer switches it over
+        let mut sequencer = MidiSequencer::new(Box::new(GateNode::default()), 48000.0);
+        sequencer.load(sequence.clone());
+        sequencer.play();
+        let mut renderer = OfflineRenderer::new(sample_rate, 256, 2);
+        let buffer = renderer.render(&mut sequencer, &MidiTimeline::new(), renderer.seconds_to_frames(1.0));
+        let on = renderer.seconds_to_frames(0.5) as usize..renderer.seconds_to_frames(0.75) as usize;
+        for (frame, sample) in buffer.channel(0).iter().enumerate() {
+            assert_eq!(*sample, if on.contains(&frame) {1.0} else {0.0}, "frame {} at {} Hz", frame, sample_rate);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use makepad_audio_graph::{
+    makepad_platform::*,
+    AudioGraphNode,
+    DisplayAudioGraph,
+    ToUIDisplayMsg,
+    MidiSequence,
+    MidiSequencer,
+    MidiTimeline,
+    OfflineRenderer,
+};
+
+// Outputs the note number of the held note as a constant level.
+#[derive(Default)]
+struct NoteLevelNode {
+    level: f32,
+}
+
+impl AudioGraphNode for NoteLevelNode {
+    fn handle_midi_data(&mut self, data: MidiData) {
+        if let MidiEvent::Note(note) = data.decode() {
+            self.level = if note.is_on {note.note_number as f32} else {0.0};
+        }
+    }
+
+    fn all_notes_off(&mut self) {
+        self.level = 0.0;
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        _inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        for channel in 0..outputs[0].channel_count() {
+            outputs[0].channel_mut(channel).fill(self.level);
+        }
+    }
+}
+
+// Outputs the note number of the held note times the index of the output plus one, on every output.
+#[derive(Default)]
+struct MultiOutputNode {
+    level: NoteLevelNode,
+}
+
+impl AudioGraphNode for MultiOutputNode {
+    fn handle_midi_data(&mut self, data: MidiData) {
+        self.level.handle_midi_data(data);
+    }
+
+    fn all_notes_off(&mut self) {
+        self.level.all_notes_off();
+    }
+
+    fn render_to_audio_buffer(
+        &mut self,
+        _info: AudioInfo,
+        outputs: &mut [&mut AudioBuffer],
+        _inputs: &[&AudioBuffer],
+        _display: &mut DisplayAudioGraph
+    ) {
+        for (index, output) in outputs.iter_mut().enumerate() {
+            for channel in 0..output.channel_count() {
+                output.channel_mut(channel).fill(self.level.level * (index + 1) as f32);
+            }
+        }
+    }
+}
+
+fn note(is_on: bool, note_number: u8) -> MidiData {
+    MidiNote {is_on, channel: 0, note_number, velocity: 100}.into()
+}
+
+// at 120 beats per minute and 4 ticks per quarter a tick is 1/8th of a second, or 125 frames at 1000 Hz
+fn midi_file() -> MidiFile {
+    let mut track = MidiTrack::default();
+    track.push_midi(1, note(true, 60));
+    track.push_midi(2, note(false, 60));
+    // double the tempo from tick 4, so tick 6 is at 0.625 seconds
+    track.push_meta(4, MidiMetaEvent::Tempo(250_000));
+    track.push_midi(6, note(true, 62));
+    track.push_midi(7, note(false, 62));
+    let mut file = MidiFile::new(MidiFileFormat::SingleTrack, MidiTimeDivision::TicksPerQuarter(4));
+    file.tracks.push(track);
+    file
+}
+
+fn sequencer() -> MidiSequencer {
+    let mut sequencer = MidiSequencer::new(Box::new(NoteLevelNode::default()), 1000.0);
+    sequencer.load(MidiSequence::from_midi_file(&midi_file()));
+    sequencer.play();
+    sequencer
+}
+
+fn expected_level(frame: usize) -> f32 {
+    match frame {
+        125..=249 => 60.0,
+        625..=687 => 62.0,
+        _ => 0.0
+    }
+}
+
+#[test]
+fn plays_a_midi_file_sample_accurately() {
+    for block_size in [1, 64, 100, 1000] {
+        let mut renderer = OfflineRenderer::new(1000, block_size, 2);
+        let buffer = renderer.render(&mut sequencer(), &MidiTimeline::new(), 800);
+        for (frame, sample) in buffer.channel(0).iter().enumerate() {
+            assert_eq!(*sample, expected_level(frame), "frame {} with block size {}", frame, block_size);
+        }
+        assert_eq!(buffer.channel(1), buffer.channel(0));
+    }
+}
+
+#[test]
+fn seeks_and_stops() {
+    let mut renderer = OfflineRenderer::new(1000, 64, 1);
+    let mut sequencer = sequencer();
+    sequencer.seek(0.2);
+    let buffer = renderer.render(&mut sequencer, &MidiTimeline::new(), 480);
+    // the note on at 0.125 seconds is skipped, the note on at 0.625 seconds starts at frame 425
+    assert!(buffer.channel(0)[..425].iter().all( | sample | *sample == 0.0));
+    assert_eq!(buffer.channel(0)[425], 62.0);
+    assert_eq!(sequencer.position_seconds(), 0.68);
+    assert!(!sequencer.is_finished());
+
+    sequencer.seek(0.65);
+    sequencer.play();
+    let mut timeline = MidiTimeline::new();
+    // live MIDI still reaches the wrapped node
+    timeline.push(0, note(true, 64));
+    let buffer = renderer.render(&mut sequencer, &timeline, 10);
+    assert_eq!(buffer.channel(0)[0], 64.0);
+    sequencer.stop();
+    let buffer = renderer.render(&mut sequencer, &MidiTimeline::new(), 100);
+    // the note off at 0.688 seconds is not played while stopped
+    assert!(buffer.channel(0).iter().all( | sample | *sample == 0.0));
+    assert!(!sequencer.is_playing());
+    sequencer.play();
+    renderer.render(&mut sequencer, &MidiTimeline::new(), 100);
+    assert!(sequencer.is_finished());
+}
+
+#[test]
+fn orders_simultaneous_events() {
+    let sequence = MidiSequence::from_events(vec![(0.5, note(true, 1)), (0.25, note(true, 2)), (0.5, note(false, 1))]);
+    let numbers: Vec<(u8, u8)> = sequence.events().iter().map( | (_, data) | (data.data[0], data.data[1])).collect();
+    assert_eq!(numbers, vec![(0x90, 2), (0x90, 1), (0x80, 1)]);
+    assert_eq!(sequence.duration(), 0.5);
+}
+
+#[test]
+fn splits_every_output() {
+    let mut sequencer = MidiSequencer::new(Box::new(MultiOutputNode::default()), 1000.0);
+    sequencer.load(MidiSequence::from_events(vec![(0.03, note(true, 60)), (0.07, note(false, 60))]));
+    sequencer.play();
+    let mut first = AudioBuffer::new_with_size(100, 1);
+    let mut second = AudioBuffer::new_with_size(100, 2);
+    let to_ui = ToUIReceiver::<ToUIDisplayMsg>::default();
+    let mut display = DisplayAudioGraph {
+        to_ui: &to_ui.sender(),
+        buffers: &mut Vec::new(),
+        meter: false
+    };
+    let info = AudioInfo {device_id: AudioDeviceId::default(), time: None};
+    sequencer.render_to_audio_buffer(info, &mut [&mut first, &mut second], &[], &mut display);
+    // the note is held from frame 30 to 70 on both outputs
+    for frame in 0..100 {
+        let level = if (30..70).contains(&frame) {60.0} else {0.0};
+        assert_eq!(first.channel(0)[frame], level, "frame {} of the first output", frame);
+        assert_eq!(second.channel(0)[frame], 2.0 * level, "frame {} of the second output", frame);
+        assert_eq!(second.channel(1)[frame], 2.0 * level, "frame {} of the second output", frame);
+    }
+}
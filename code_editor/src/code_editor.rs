@@ -27,6 +27,7 @@
         delimiter_highlight: #f,
         error_decoration: #f00,
         warning_decoration: #0f0,
+        search_match_decoration: #ffd70040,
         
         unknown: #C0C0C0,
         branch_keyword: #C485BE,
@@ -54,6 +55,11 @@ fn pixel(self) -> vec4 {
 
     DrawDecoration = {{DrawDecoration}} {
         fn pixel(self) -> vec4 {
+            if self.is_highlight > 0.5 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(0.0, 0.0, self.rect_size.x, self.rect_size.y, 2.0);
+                return sdf.fill(self.color);
+            }
             let transformed_pos = vec2(self.pos.x, self.pos.y + 0.03 * sin(self.pos.x * self.rect_size.x));
             let cx = Sdf2d::viewport(transformed_pos * self.rect_size);
             cx.move_to(0.0, self.rect_size.y - 1.0);
@@ -236,6 +242,7 @@ pub struct CodeEditor {
     #[live] draw_bg: DrawColor,
     #[rust(KeepCursorInView::Off)] keep_cursor_in_view: KeepCursorInView,
     #[rust] last_cursor_screen_pos: Option<DVec2>,
+    #[rust] last_text_input: String,
     #[live] pad_left_top: DVec2, 
     #[rust] cell_size: DVec2,
     #[rust] gutter_rect: Rect,
@@ -532,6 +539,16 @@ pub fn draw_walk_editor(&mut self, cx: &mut Cx2d, session: &mut CodeSession, wal
         
         self.scroll_bars.end(cx);
         
+        if cx.has_key_focus(self.scroll_bars.area()) {
+            if let Some(cursor_screen_pos) = self.last_cursor_screen_pos {
+                let area_pos = self.scroll_bars.area().rect(cx).pos;
+                cx.show_text_ime(
+                    self.scroll_bars.area(),
+                    self.viewport_rect.pos - area_pos + cursor_screen_pos
+                );
+            }
+        }
+        
         if session.update_folds() {
             cx.redraw_area_in_draw(self.scroll_bars.area());
         } else if self.keep_cursor_in_view.is_locked() {
@@ -543,6 +560,32 @@ pub fn set_key_focus(&mut self, cx: &mut Cx) {
         cx.set_key_focus(self.scroll_bars.area());
     }
 
+    pub fn has_key_focus(&self, cx: &Cx) -> bool {
+        cx.has_key_focus(self.scroll_bars.area())
+    }
+
+    pub fn line_height(&self) -> f64 {
+        self.cell_size.y
+    }
+
+    /// The position in the text under the absolute point `abs`, or `None` if the point is outside
+    /// the text area, such as in the gutter.
+    pub fn pick_position(&self, cx: &Cx, session: &CodeSession, abs: DVec2) -> Option<Position> {
+        if !self.scroll_bars.area().rect(cx).contains(abs) || abs.x < self.viewport_rect.pos.x {
+            return None;
+        }
+        let ((position, _), _) = self.pick(session, abs);
+        Some(position)
+    }
+
+    /// The absolute point at the top left of `position` as it was last drawn.
+    pub fn position_to_abs(&self, session: &CodeSession, position: Position) -> DVec2 {
+        let (x, y) = session
+            .layout()
+            .logical_to_normalized_position(position, Affinity::Before);
+        self.viewport_rect.pos + dvec2(x, y) * self.cell_size
+    }
+
     pub fn set_cursor_and_scroll(
         &mut self,
         cx: &mut Cx,
@@ -636,6 +679,7 @@ pub fn handle_event(
         match event.hits(cx, self.scroll_bars.area()) {
             Hit::KeyFocusLost(_) => {
                 self.animator_play(cx, id!(focus.off));
+                cx.hide_text_ime();
             }
             Hit::KeyFocus(_) => {
                 self.animator_play(cx, id!(focus.on));
@@ -817,11 +861,24 @@ pub fn handle_event(
                 keyboard_moved_cursor = true;
                 self.redraw(cx);
             }
+            Hit::TextInput(TextInputEvent {
+                ref input,
+                replace_last: true,
+                ..
+            }) if !self.read_only => {
+                // an input method replacing the text it is composing
+                session.replace_before_cursor(&self.last_text_input, input.as_str().into());
+                self.last_text_input = input.clone();
+                self.redraw(cx);
+                keyboard_moved_cursor = true;
+                actions.push(CodeEditorAction::TextDidChange);
+            }
             Hit::TextInput(TextInputEvent {
                 ref input,
                 was_paste: false,
                 ..
             }) if input.len() > 0 && !self.read_only => {
+                self.last_text_input = input.clone();
                 session.insert(input.into());
                 self.redraw(cx);
                 keyboard_moved_cursor = true;
@@ -1239,8 +1296,17 @@ fn draw_indent_guide_layer(&mut self, cx: &mut Cx2d<'_>, session: &CodeSession)
     }
 
     fn draw_decoration_layer(&mut self, cx: &mut Cx2d<'_>, session: &CodeSession) {
+        self.draw_decorations(cx, session, &session.search_matches());
+        self.draw_decorations(cx, session, &session.document().decorations());
+    }
+
+    fn draw_decorations(
+        &mut self,
+        cx: &mut Cx2d<'_>,
+        session: &CodeSession,
+        decorations: &[Decoration],
+    ) {
         let mut active_decoration = None;
-        let decorations = session.document().decorations();
         let mut decorations = decorations.iter();
         while decorations.as_slice().first().map_or(false, |decoration| {
             decoration.end().line_index < self.line_start
@@ -1672,11 +1738,16 @@ fn draw_decoration(
     ) {
         let start_x = mem::take(&mut self.active_decoration.as_mut().unwrap().start_x);
         let (x, y) = line.grid_to_normalized_position(row_index, column_index);
-        self.code_editor.draw_decoration.color =
-            match self.active_decoration.as_mut().unwrap().decoration.ty {
-                DecorationType::Warning => self.code_editor.token_colors.warning_decoration,
-                DecorationType::Error => self.code_editor.token_colors.error_decoration,
-            };
+        let ty = self.active_decoration.as_ref().unwrap().decoration.ty;
+        self.code_editor.draw_decoration.color = match ty {
+            DecorationType::Warning => self.code_editor.token_colors.warning_decoration,
+            DecorationType::Error => self.code_editor.token_colors.error_decoration,
+            DecorationType::SearchMatch => self.code_editor.token_colors.search_match_decoration,
+        };
+        self.code_editor.draw_decoration.is_highlight = match ty {
+            DecorationType::SearchMatch => 1.0,
+            DecorationType::Warning | DecorationType::Error => 0.0,
+        };
 
         self.code_editor.draw_decoration.draw_abs(
             cx,
@@ -1986,6 +2057,8 @@ struct TokenColors {
     error_decoration: Vec4,
     #[live]
     warning_decoration: Vec4,
+    #[live]
+    search_match_decoration: Vec4,
 }
 
 #[derive(Live, LiveHook, LiveRegister)]
@@ -1998,11 +2071,14 @@ pub struct DrawIndentGuide {
 }
 
 #[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
 struct DrawDecoration {
     #[deref]
     draw_super: DrawQuad,
     #[live]
     color: Vec4,
+    #[live]
+    is_highlight: f32,
 }
 
 #[derive(Live, LiveHook, LiveRegister)]
@@ -0,0 +1,76 @@
+//! Highlights the fenced code blocks of the `Markdown` and `Html` widgets with the same
+//! tokenizers the editor uses.
+
+use {
+    crate::{
+        text::Text,
+        token::TokenKind,
+        tokenizer::{TokenizerFactory, TokenizerRegistry},
+    },
+    makepad_widgets::{
+        code_highlight::{CodeToken, CodeTokenKind, CodeTokenizers},
+        Cx,
+    },
+};
+
+/// Names that code blocks use for a language, next to the file extension it is registered for.
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[("rust", "rs")];
+
+/// Registers every tokenizer of `registry` with [`CodeTokenizers`], by its extensions and
+/// the usual names of its language.
+pub fn register_code_tokenizers(cx: &mut Cx, registry: &TokenizerRegistry) {
+    for extension in registry.extensions() {
+        let Some(factory) = registry.get(extension) else {
+            continue;
+        };
+        let mut languages = vec![extension];
+        languages.extend(
+            LANGUAGE_ALIASES
+                .iter()
+                .filter(|(_, alias_extension)| *alias_extension == extension)
+                .map(|(language, _)| *language),
+        );
+        CodeTokenizers::register(cx, &languages, move |code| tokenize(&factory, code));
+    }
+}
+
+/// Tokenizes `code` as a document of its own, with a whitespace token for each newline.
+pub fn tokenize(factory: &TokenizerFactory, code: &str) -> Vec<CodeToken> {
+    let text = Text::from(code);
+    let line_count = text.as_lines().len();
+    let mut tokens = vec![Vec::new(); line_count];
+    factory(line_count).update(&text, &mut tokens);
+    let mut code_tokens = Vec::new();
+    for (line, line_tokens) in tokens.into_iter().enumerate() {
+        if line > 0 {
+            code_tokens.push(CodeToken {
+                len: 1,
+                kind: CodeTokenKind::Whitespace,
+            });
+        }
+        code_tokens.extend(line_tokens.into_iter().map(|token| CodeToken {
+            len: token.len,
+            kind: code_token_kind(token.kind),
+        }));
+    }
+    code_tokens
+}
+
+fn code_token_kind(kind: TokenKind) -> CodeTokenKind {
+    match kind {
+        TokenKind::Unknown => CodeTokenKind::Unknown,
+        TokenKind::BranchKeyword => CodeTokenKind::BranchKeyword,
+        TokenKind::Comment => CodeTokenKind::Comment,
+        TokenKind::Constant => CodeTokenKind::Constant,
+        TokenKind::Delimiter => CodeTokenKind::Delimiter,
+        TokenKind::Identifier => CodeTokenKind::Identifier,
+        TokenKind::LoopKeyword => CodeTokenKind::LoopKeyword,
+        TokenKind::OtherKeyword => CodeTokenKind::OtherKeyword,
+        TokenKind::Number => CodeTokenKind::Number,
+        TokenKind::Punctuator => CodeTokenKind::Punctuator,
+        TokenKind::Typename => CodeTokenKind::Typename,
+        TokenKind::Function => CodeTokenKind::Function,
+        TokenKind::String => CodeTokenKind::String,
+        TokenKind::Whitespace => CodeTokenKind::Whitespace,
+    }
+}
@@ -7,6 +7,7 @@
 pub enum DecorationType {
     Error,
     Warning,
+    SearchMatch,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -94,6 +95,10 @@ pub fn clear(&mut self) {
         self.decorations.clear();
     }
 
+    pub fn remove_decorations_with_id(&mut self, id: usize) {
+        self.decorations.retain(|decoration| decoration.id != id);
+    }
+
     pub fn apply_edit(&mut self, edit: &Edit) {
         for decoration in &mut self.decorations {
             *decoration = decoration.apply_edit(edit);
@@ -11,7 +11,7 @@
         str::StrExt,
         text::{Change, Drift, Edit, Length, Position, Text},
         token::{Token, TokenKind},
-        tokenizer::Tokenizer,
+        tokenizer::{DocumentTokenizer, IncrementalTokenizer, RustTokenizer},
     },
     std::{
         cell::{Ref, RefCell},
@@ -29,6 +29,19 @@
 
 impl CodeDocument {
     pub fn new(text: Text, decorations: DecorationSet) -> Self {
+        Self::with_tokenizer(text, decorations, |line_count| {
+            Box::new(IncrementalTokenizer::new(RustTokenizer, line_count))
+        })
+    }
+
+    /// Creates a document that is highlighted by the tokenizer that `new_tokenizer` creates for
+    /// the given number of lines, such as one from a
+    /// [`TokenizerRegistry`](crate::tokenizer::TokenizerRegistry).
+    pub fn with_tokenizer(
+        text: Text,
+        decorations: DecorationSet,
+        new_tokenizer: impl FnOnce(usize) -> Box<dyn DocumentTokenizer>,
+    ) -> Self {
         let line_count = text.as_lines().len();
         let tokens: Vec<_> = (0..line_count)
             .map(|line| tokenize(&text.as_lines()[line]).collect::<Vec<_>>())
@@ -41,9 +54,10 @@ pub fn new(text: Text, decorations: DecorationSet) -> Self {
                 inline_inlays: (0..line_count).map(|_| Vec::new()).collect(),
                 block_inlays: Vec::new(),
             }),
-            tokenizer: RefCell::new(Tokenizer::new(line_count)),
+            tokenizer: RefCell::new(new_tokenizer(line_count)),
             decorations: RefCell::new(decorations),
             edit_senders: RefCell::new(HashMap::new()),
+            edit_listeners: RefCell::new(Vec::new()),
         }));
         inner.update_indent_state();
         inner.0.tokenizer.borrow_mut().update(
@@ -210,6 +224,18 @@ pub fn clear_decorations(&mut self) {
         self.0.decorations.borrow_mut().clear()
     }
 
+    /// Removes the decorations with the given `id`, leaving the others in place.
+    pub fn remove_decorations_with_id(&mut self, id: usize) {
+        self.0.decorations.borrow_mut().remove_decorations_with_id(id)
+    }
+
+    /// Sends every edit made to the document from now on to `edit_sender`, in the order they are
+    /// applied. Each edit is relative to the text as it was after the ones before it. The sender is
+    /// dropped once its receiver is.
+    pub fn add_edit_listener(&self, edit_sender: Sender<Vec<Edit>>) {
+        self.0.edit_listeners.borrow_mut().push(edit_sender);
+    }
+
     pub fn add_session(
         &mut self,
         session_id: SessionId,
@@ -435,6 +461,10 @@ fn update_after_edit(
                     .unwrap();
             }
         }
+        self.0
+            .edit_listeners
+            .borrow_mut()
+            .retain(|edit_sender| edit_sender.send(edits.to_vec()).is_ok());
     }
 
     fn apply_change_to_tokens(&self, change: &Change) {
@@ -710,9 +740,10 @@ pub fn apply_edit(&mut self, edit: Edit) {
 struct DocumentInner {
     history: RefCell<History>,
     layout: RefCell<DocumentLayout>,
-    tokenizer: RefCell<Tokenizer>,
+    tokenizer: RefCell<Box<dyn DocumentTokenizer>>,
     decorations: RefCell<DecorationSet>,
     edit_senders: RefCell<HashMap<SessionId, Sender<(Option<SelectionSet>, Vec<Edit>)>>>,
+    edit_listeners: RefCell<Vec<Sender<Vec<Edit>>>>,
 }
 
 fn tokenize(text: &str) -> impl Iterator<Item = Token> + '_ {
@@ -3,12 +3,15 @@
 
 pub mod char;
 pub mod code_editor;
+pub mod code_highlight;
 pub mod decoration;
 pub mod document;
 pub mod history;
 pub mod inlays;
 pub mod iter;
 pub mod layout;
+pub mod regex;
+pub mod search;
 pub mod selection;
 pub mod session;
 pub mod settings;
@@ -22,10 +25,11 @@
 
 pub use self::{
     code_editor::CodeEditor, document::CodeDocument, history::History, layout::Line,
-    selection::Selection, session::CodeSession, settings::Settings, token::Token, tokenizer::Tokenizer,
+    search::SearchQuery, selection::Selection, session::CodeSession, settings::Settings, token::Token, tokenizer::{Tokenizer, TokenizerRegistry},
 };
 
 pub fn live_design(cx: &mut Cx) {
     crate::code_editor::live_design(cx);
     crate::code_view::live_design(cx);
+    crate::code_highlight::register_code_tokenizers(cx, &TokenizerRegistry::default());
 }
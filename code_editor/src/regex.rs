@@ -0,0 +1,798 @@
+use std::{fmt, ops::Range};
+
+/// A regular expression that matches within a single line of text.
+///
+/// The syntax is the common subset of Perl style expressions: literals, `.`, classes like `[a-z]`
+/// and `[^0-9]`, the escapes `\d`, `\w`, `\s` and their negations, the anchors `^`, `$`, `\b`
+/// and `\B`, groups `(...)` and `(?:...)`, alternation `|` and the quantifiers `*`, `+`, `?` and
+/// `{n,m}`, each of which can be made lazy by a trailing `?`. Matching is leftmost first, like in
+/// most other engines, and runs in time linear in the length of the text.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    insts: Vec<Inst>,
+    slot_count: usize,
+    ignore_case: bool,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, RegexError> {
+        Ok(Self::from_node(parse(pattern)?))
+    }
+
+    /// Creates a regex that matches `text` literally.
+    pub fn literal(text: &str) -> Self {
+        Self::from_node(Node::literal(text))
+    }
+
+    pub(crate) fn from_node(node: Node) -> Self {
+        let mut compiler = Compiler {
+            insts: Vec::new(),
+            group_count: 0,
+        };
+        compiler.insts.push(Inst::Save(0));
+        compiler.compile(&node);
+        compiler.insts.push(Inst::Save(1));
+        compiler.insts.push(Inst::Match);
+        Self {
+            insts: compiler.insts,
+            slot_count: 2 * (compiler.group_count + 1),
+            ignore_case: false,
+        }
+    }
+
+    pub fn with_ignore_case(self, ignore_case: bool) -> Self {
+        Self {
+            ignore_case,
+            ..self
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.captures_at(text, 0).is_some()
+    }
+
+    /// Finds the first match that starts at or after the byte index `start`. The text before
+    /// `start` is still seen by anchors such as `\b`.
+    pub fn captures_at(&self, text: &str, start: usize) -> Option<Captures> {
+        self.captures_at_with(text, start, &mut Visited::default())
+    }
+
+    fn captures_at_with(
+        &self,
+        text: &str,
+        mut start: usize,
+        visited: &mut Visited,
+    ) -> Option<Captures> {
+        visited.reset(self.insts.len() * (text.len() + 1));
+        let mut slots = vec![None; self.slot_count];
+        loop {
+            if self.backtrack(text, start, visited, &mut slots) {
+                return Some(Captures { slots });
+            }
+            start += text[start..].chars().next()?.len_utf8();
+        }
+    }
+
+    pub fn find_at(&self, text: &str, start: usize) -> Option<Range<usize>> {
+        self.captures_at(text, start)
+            .map(|captures| captures.range())
+    }
+
+    /// Iterates over all non-overlapping matches in `text`, including empty ones.
+    pub fn captures_iter<'a>(&'a self, text: &'a str) -> CapturesIter<'a> {
+        CapturesIter {
+            regex: self,
+            text,
+            start: Some(0),
+            visited: Visited::default(),
+        }
+    }
+
+    fn backtrack(
+        &self,
+        text: &str,
+        start: usize,
+        visited: &mut Visited,
+        slots: &mut [Option<usize>],
+    ) -> bool {
+        // A thread that reaches an instruction at a position that was tried before can not do
+        // better than the first time, so every pair is visited at most once. This holds across
+        // start positions as well, since a failed pair leaves the slots as they were.
+        let stride = text.len() + 1;
+        let mut jobs = vec![Job::Try { pc: 0, pos: start }];
+        while let Some(job) = jobs.pop() {
+            let (mut pc, mut pos) = match job {
+                Job::Try { pc, pos } => (pc, pos),
+                Job::Restore { slot, value } => {
+                    slots[slot] = value;
+                    continue;
+                }
+            };
+            loop {
+                if !visited.insert(pc * stride + pos) {
+                    break;
+                }
+                match self.insts[pc] {
+                    Inst::Char(expected) => match text[pos..].chars().next() {
+                        Some(char) if chars_eq(char, expected, self.ignore_case) => {
+                            pc += 1;
+                            pos += char.len_utf8();
+                        }
+                        _ => break,
+                    },
+                    Inst::Any => match text[pos..].chars().next() {
+                        Some(char) => {
+                            pc += 1;
+                            pos += char.len_utf8();
+                        }
+                        None => break,
+                    },
+                    Inst::Class(ref class) => match text[pos..].chars().next() {
+                        Some(char) if class.matches(char, self.ignore_case) => {
+                            pc += 1;
+                            pos += char.len_utf8();
+                        }
+                        _ => break,
+                    },
+                    Inst::Assert(assert) => {
+                        if !assert.holds(text, pos) {
+                            break;
+                        }
+                        pc += 1;
+                    }
+                    Inst::Split(first, second) => {
+                        jobs.push(Job::Try { pc: second, pos });
+                        pc = first;
+                    }
+                    Inst::Jump(target) => pc = target,
+                    Inst::Save(slot) => {
+                        jobs.push(Job::Restore {
+                            slot,
+                            value: slots[slot],
+                        });
+                        slots[slot] = Some(pos);
+                        pc += 1;
+                    }
+                    Inst::Match => return true,
+                }
+            }
+        }
+        false
+    }
+}
+
+/// The byte ranges matched by a regex and its groups.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Captures {
+    slots: Vec<Option<usize>>,
+}
+
+impl Captures {
+    /// The range of the whole match.
+    pub fn range(&self) -> Range<usize> {
+        self.slots[0].unwrap()..self.slots[1].unwrap()
+    }
+
+    /// The range of group `index`, if that group took part in the match.
+    pub fn get(&self, index: usize) -> Option<Range<usize>> {
+        match (*self.slots.get(2 * index)?, *self.slots.get(2 * index + 1)?) {
+            (Some(start), Some(end)) => Some(start..end),
+            _ => None,
+        }
+    }
+
+    /// Expands `replacement` for this match in `text`, where `$0` to `$9` and `${n}` stand for
+    /// the text matched by a group and `$$` for a dollar sign.
+    pub fn expand(&self, text: &str, replacement: &str) -> String {
+        let mut string = String::new();
+        let mut rest = replacement;
+        while let Some(index) = rest.find('$') {
+            string.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+            if let Some(stripped) = rest.strip_prefix('$') {
+                string.push('$');
+                rest = stripped;
+                continue;
+            }
+            let (group, len) = if let Some(digit) = rest.chars().next().and_then(|char| char.to_digit(10)) {
+                (Some(digit as usize), 1)
+            } else if let Some(end) = rest.strip_prefix('{').and_then(|braced| braced.find('}')) {
+                (rest[1..end + 1].parse().ok(), end + 2)
+            } else {
+                (None, 0)
+            };
+            match group {
+                Some(group) => {
+                    if let Some(range) = self.get(group) {
+                        string.push_str(&text[range]);
+                    }
+                    rest = &rest[len..];
+                }
+                None => string.push('$'),
+            }
+        }
+        string.push_str(rest);
+        string
+    }
+}
+
+pub struct CapturesIter<'a> {
+    regex: &'a Regex,
+    text: &'a str,
+    start: Option<usize>,
+    visited: Visited,
+}
+
+impl<'a> Iterator for CapturesIter<'a> {
+    type Item = Captures;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let captures = self
+            .regex
+            .captures_at_with(self.text, self.start?, &mut self.visited)?;
+        let range = captures.range();
+        self.start = if range.is_empty() {
+            // Step over a character so that an empty match is not found again.
+            self.text[range.end..]
+                .chars()
+                .next()
+                .map(|char| range.end + char.len_utf8())
+        } else {
+            Some(range.end)
+        };
+        Some(captures)
+    }
+}
+
+/// The (instruction, position) pairs a search has tried.
+///
+/// Every search in a line needs a table as large as the program times the line, so the iterator
+/// keeps one around and starts a new generation instead of clearing or allocating it again.
+#[derive(Debug, Default)]
+struct Visited {
+    generations: Vec<u32>,
+    generation: u32,
+}
+
+impl Visited {
+    fn reset(&mut self, len: usize) {
+        if self.generations.len() != len {
+            self.generations.clear();
+            self.generations.resize(len, 0);
+            self.generation = 0;
+        }
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.generations.fill(0);
+            self.generation = 1;
+        }
+    }
+
+    /// Marks `index` as visited and returns whether it wasn't already.
+    fn insert(&mut self, index: usize) -> bool {
+        let generation = &mut self.generations[index];
+        if *generation == self.generation {
+            return false;
+        }
+        *generation = self.generation;
+        true
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RegexError {
+    pub message: String,
+    /// The byte index in the pattern where the error was found.
+    pub index: usize,
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.index)
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Class),
+    Assert(Assert),
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+impl Node {
+    pub(crate) fn literal(text: &str) -> Self {
+        Node::Concat(text.chars().map(Node::Char).collect())
+    }
+
+    fn inst_count(&self) -> usize {
+        match *self {
+            Node::Empty => 0,
+            Node::Char(_) | Node::Any | Node::Class(_) | Node::Assert(_) => 1,
+            Node::Group(ref node, index) => node.inst_count() + if index.is_some() { 2 } else { 0 },
+            Node::Concat(ref nodes) => nodes.iter().map(|node| node.inst_count()).sum(),
+            Node::Alternate(ref nodes) => {
+                nodes.iter().map(|node| node.inst_count() + 2).sum::<usize>() - 2
+            }
+            Node::Repeat {
+                ref node, min, max, ..
+            } => {
+                let count = node.inst_count();
+                let optional_count = match max {
+                    Some(max) => (max - min).saturating_mul(count + 1),
+                    None => count + 2,
+                };
+                min.saturating_mul(count).saturating_add(optional_count)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Assert {
+    LineStart,
+    LineEnd,
+    WordBoundary,
+    NotWordBoundary,
+    /// Not preceded by a word character, which unlike `\b` also holds before punctuation.
+    NotWordBefore,
+    /// Not followed by a word character.
+    NotWordAfter,
+}
+
+impl Assert {
+    fn holds(self, text: &str, pos: usize) -> bool {
+        let word_before = text[..pos].chars().next_back().map_or(false, is_word_char);
+        let word_after = text[pos..].chars().next().map_or(false, is_word_char);
+        match self {
+            Self::LineStart => pos == 0,
+            Self::LineEnd => pos == text.len(),
+            Self::WordBoundary => word_before != word_after,
+            Self::NotWordBoundary => word_before == word_after,
+            Self::NotWordBefore => !word_before,
+            Self::NotWordAfter => !word_after,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub(crate) struct Class {
+    items: Vec<ClassItem>,
+    negated: bool,
+}
+
+impl Class {
+    fn matches(&self, char: char, ignore_case: bool) -> bool {
+        let matches = |char| self.items.iter().any(|item| item.matches(char));
+        let is_match = matches(char)
+            || ignore_case
+                && (char.to_lowercase().any(matches) || char.to_uppercase().any(matches));
+        is_match != self.negated
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(self, char: char) -> bool {
+        match self {
+            Self::Range(start, end) => start <= char && char <= end,
+            Self::Digit(negated) => char.is_ascii_digit() != negated,
+            Self::Word(negated) => is_word_char(char) != negated,
+            Self::Space(negated) => char.is_whitespace() != negated,
+        }
+    }
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
+fn chars_eq(char: char, expected: char, ignore_case: bool) -> bool {
+    char == expected || ignore_case && char.to_lowercase().eq(expected.to_lowercase())
+}
+
+#[derive(Clone, Debug)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Assert(Assert),
+    Split(usize, usize),
+    Jump(usize),
+    Save(usize),
+    Match,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Job {
+    Try { pc: usize, pos: usize },
+    Restore { slot: usize, value: Option<usize> },
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    group_count: usize,
+}
+
+impl Compiler {
+    fn compile(&mut self, node: &Node) {
+        match *node {
+            Node::Empty => {}
+            Node::Char(char) => self.insts.push(Inst::Char(char)),
+            Node::Any => self.insts.push(Inst::Any),
+            Node::Class(ref class) => self.insts.push(Inst::Class(class.clone())),
+            Node::Assert(assert) => self.insts.push(Inst::Assert(assert)),
+            Node::Group(ref node, None) => self.compile(node),
+            Node::Group(ref node, Some(index)) => {
+                self.group_count = self.group_count.max(index);
+                self.insts.push(Inst::Save(2 * index));
+                self.compile(node);
+                self.insts.push(Inst::Save(2 * index + 1));
+            }
+            Node::Concat(ref nodes) => {
+                for node in nodes {
+                    self.compile(node);
+                }
+            }
+            Node::Alternate(ref nodes) => {
+                let mut jumps = Vec::new();
+                for (index, node) in nodes.iter().enumerate() {
+                    if index + 1 == nodes.len() {
+                        self.compile(node);
+                    } else {
+                        let split = self.push_placeholder();
+                        self.compile(node);
+                        jumps.push(self.push_placeholder());
+                        self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                    }
+                }
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jump(self.insts.len());
+                }
+            }
+            Node::Repeat {
+                ref node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..min {
+                    self.compile(node);
+                }
+                match max {
+                    None => {
+                        let split = self.push_placeholder();
+                        self.compile(node);
+                        self.insts.push(Inst::Jump(split));
+                        self.insts[split] = self.split(split + 1, self.insts.len(), greedy);
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in min..max {
+                            splits.push(self.push_placeholder());
+                            self.compile(node);
+                        }
+                        let end = self.insts.len();
+                        for split in splits {
+                            self.insts[split] = self.split(split + 1, end, greedy);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn push_placeholder(&mut self) -> usize {
+        self.insts.push(Inst::Match);
+        self.insts.len() - 1
+    }
+
+    fn split(&self, more: usize, done: usize, greedy: bool) -> Inst {
+        if greedy {
+            Inst::Split(more, done)
+        } else {
+            Inst::Split(done, more)
+        }
+    }
+}
+
+/// The largest count allowed in a `{n,m}` quantifier, which is expanded when compiled.
+const MAX_REPEAT_COUNT: usize = 1000;
+/// The largest compiled program allowed, so that nested counts can't make matching run out of memory.
+const MAX_INST_COUNT: usize = 10_000;
+
+pub(crate) fn parse(pattern: &str) -> Result<Node, RegexError> {
+    let mut parser = Parser {
+        pattern,
+        chars: pattern.char_indices().collect(),
+        index: 0,
+        group_count: 0,
+    };
+    let node = parser.parse_alternate()?;
+    match parser.peek() {
+        Some(')') => Err(parser.error("unmatched `)`")),
+        Some(_) => unreachable!(),
+        None if node.inst_count() > MAX_INST_COUNT => Err(RegexError {
+            message: "pattern is too large".to_string(),
+            index: 0,
+        }),
+        None => Ok(node),
+    }
+}
+
+struct Parser<'a> {
+    pattern: &'a str,
+    chars: Vec<(usize, char)>,
+    index: usize,
+    group_count: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse_alternate(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = vec![self.parse_concat()?];
+        while self.eat('|') {
+            nodes.push(self.parse_concat()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            Node::Alternate(nodes)
+        })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = Vec::new();
+        while let Some(char) = self.peek() {
+            if char == '|' || char == ')' {
+                break;
+            }
+            let node = self.parse_atom()?;
+            nodes.push(self.parse_quantifier(node)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn parse_quantifier(&mut self, node: Node) -> Result<Node, RegexError> {
+        let start = self.index;
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.parse_counts()? {
+                Some(counts) => counts,
+                None => return Ok(node),
+            },
+            _ => return Ok(node),
+        };
+        if start == self.index {
+            self.index += 1;
+        }
+        if matches!(node, Node::Empty | Node::Assert(_)) {
+            self.index = start;
+            return Err(self.error("nothing to repeat"));
+        }
+        let greedy = !self.eat('?');
+        if matches!(self.peek(), Some('*' | '+' | '?')) {
+            return Err(self.error("nothing to repeat"));
+        }
+        Ok(Node::Repeat {
+            node: Box::new(node),
+            min,
+            max,
+            greedy,
+        })
+    }
+
+    // A `{` that doesn't start a valid count is taken literally.
+    fn parse_counts(&mut self) -> Result<Option<(usize, Option<usize>)>, RegexError> {
+        let start = self.index;
+        self.index += 1;
+        let min = self.parse_number();
+        let max = if self.eat(',') {
+            self.parse_number()
+        } else {
+            min
+        };
+        let (Some(min), Some('}')) = (min, self.peek()) else {
+            self.index = start;
+            return Ok(None);
+        };
+        self.index += 1;
+        if max.map_or(min, |max| max.max(min)) > MAX_REPEAT_COUNT {
+            self.index = start;
+            return Err(self.error("repeat count is too large"));
+        }
+        if max.map_or(false, |max| max < min) {
+            self.index = start;
+            return Err(self.error("repeat counts are out of order"));
+        }
+        Ok(Some((min, max)))
+    }
+
+    // A number too large to fit saturates, the caller rejects it as too large anyway.
+    fn parse_number(&mut self) -> Option<usize> {
+        let start = self.index;
+        while self.peek().map_or(false, |char| char.is_ascii_digit()) {
+            self.index += 1;
+        }
+        if start == self.index {
+            return None;
+        }
+        let digits: String = self.chars[start..self.index].iter().map(|&(_, char)| char).collect();
+        Some(digits.parse().unwrap_or(usize::MAX))
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, RegexError> {
+        let char = self.next().unwrap();
+        Ok(match char {
+            '.' => Node::Any,
+            '^' => Node::Assert(Assert::LineStart),
+            '$' => Node::Assert(Assert::LineEnd),
+            '(' => {
+                let index = if self.eat('?') {
+                    if !self.eat(':') {
+                        return Err(self.error("unsupported group syntax"));
+                    }
+                    None
+                } else {
+                    self.group_count += 1;
+                    Some(self.group_count)
+                };
+                let node = self.parse_alternate()?;
+                if !self.eat(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                Node::Group(Box::new(node), index)
+            }
+            '[' => Node::Class(self.parse_class()?),
+            '\\' => match self.parse_escape()? {
+                Escape::Char(char) => Node::Char(char),
+                Escape::Class(item) => Node::Class(Class {
+                    items: vec![item],
+                    negated: false,
+                }),
+                Escape::Assert(assert) => Node::Assert(assert),
+            },
+            '*' | '+' | '?' => {
+                self.index -= 1;
+                return Err(self.error("nothing to repeat"));
+            }
+            char => Node::Char(char),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Class, RegexError> {
+        let mut class = Class {
+            items: Vec::new(),
+            negated: self.eat('^'),
+        };
+        let mut is_first = true;
+        loop {
+            let char = match self.next() {
+                Some(']') if !is_first => break,
+                Some(char) => char,
+                None => return Err(self.error("unclosed character class")),
+            };
+            is_first = false;
+            let start = match char {
+                '\\' => match self.parse_escape()? {
+                    Escape::Char(char) => char,
+                    Escape::Class(item) => {
+                        class.items.push(item);
+                        continue;
+                    }
+                    Escape::Assert(_) => {
+                        return Err(self.error("anchors are not allowed in a class"));
+                    }
+                },
+                char => char,
+            };
+            if self.peek() == Some('-') && !matches!(self.peek_nth(1), Some(']') | None) {
+                self.index += 1;
+                let end = match self.next().unwrap() {
+                    '\\' => match self.parse_escape()? {
+                        Escape::Char(char) => char,
+                        _ => return Err(self.error("invalid class range")),
+                    },
+                    char => char,
+                };
+                if end < start {
+                    return Err(self.error("class range is out of order"));
+                }
+                class.items.push(ClassItem::Range(start, end));
+            } else {
+                class.items.push(ClassItem::Range(start, start));
+            }
+        }
+        Ok(class)
+    }
+
+    fn parse_escape(&mut self) -> Result<Escape, RegexError> {
+        let Some(char) = self.next() else {
+            return Err(self.error("trailing backslash"));
+        };
+        Ok(match char {
+            'd' => Escape::Class(ClassItem::Digit(false)),
+            'D' => Escape::Class(ClassItem::Digit(true)),
+            'w' => Escape::Class(ClassItem::Word(false)),
+            'W' => Escape::Class(ClassItem::Word(true)),
+            's' => Escape::Class(ClassItem::Space(false)),
+            'S' => Escape::Class(ClassItem::Space(true)),
+            'b' => Escape::Assert(Assert::WordBoundary),
+            'B' => Escape::Assert(Assert::NotWordBoundary),
+            't' => Escape::Char('\t'),
+            'r' => Escape::Char('\r'),
+            'n' => Escape::Char('\n'),
+            'f' => Escape::Char('\x0C'),
+            'v' => Escape::Char('\x0B'),
+            '0' => Escape::Char('\0'),
+            char if char.is_ascii_alphanumeric() => {
+                self.index -= 1;
+                return Err(self.error("unknown escape"));
+            }
+            char => Escape::Char(char),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.index + n).map(|&(_, char)| char)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.index += 1;
+        Some(char)
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        if self.peek() == Some(char) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> RegexError {
+        RegexError {
+            message: message.to_string(),
+            index: self
+                .chars
+                .get(self.index)
+                .map_or(self.pattern.len(), |&(index, _)| index),
+        }
+    }
+}
+
+enum Escape {
+    Char(char),
+    Class(ClassItem),
+    Assert(Assert),
+}
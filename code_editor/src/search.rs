@@ -0,0 +1,127 @@
+use crate::{
+    regex::{self, Assert, Node, Regex, RegexError},
+    text::Position,
+};
+
+/// What to search a document for.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SearchQuery {
+    pub text: String,
+    /// Whether `text` is a regular expression, see [`Regex`] for the syntax.
+    pub is_regex: bool,
+    pub ignore_case: bool,
+    /// Only match text that isn't part of a longer word.
+    pub whole_word: bool,
+}
+
+impl SearchQuery {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_regex(self, is_regex: bool) -> Self {
+        Self { is_regex, ..self }
+    }
+
+    pub fn with_ignore_case(self, ignore_case: bool) -> Self {
+        Self {
+            ignore_case,
+            ..self
+        }
+    }
+
+    pub fn with_whole_word(self, whole_word: bool) -> Self {
+        Self { whole_word, ..self }
+    }
+
+    pub fn compile(&self) -> Result<Regex, RegexError> {
+        let mut node = if self.is_regex {
+            regex::parse(&self.text)?
+        } else {
+            Node::literal(&self.text)
+        };
+        if self.whole_word {
+            node = Node::Concat(vec![
+                Node::Assert(Assert::NotWordBefore),
+                node,
+                Node::Assert(Assert::NotWordAfter),
+            ]);
+        }
+        Ok(Regex::from_node(node).with_ignore_case(self.ignore_case))
+    }
+
+    /// The text that replaces the match of `regex` that starts at `byte_index` in `line`. For a
+    /// regex query, `$1` and the like in `replacement` stand for the text of a group.
+    pub fn replacement(
+        &self,
+        regex: &Regex,
+        line: &str,
+        byte_index: usize,
+        replacement: &str,
+    ) -> String {
+        if !self.is_regex {
+            return replacement.to_string();
+        }
+        match regex.captures_at(line, byte_index) {
+            Some(captures) => captures.expand(line, replacement),
+            None => replacement.to_string(),
+        }
+    }
+
+    /// Replaces every match of `regex` in `text`, which can have many lines, and returns the new
+    /// text along with the number of matches that were replaced.
+    pub fn replace_all(&self, regex: &Regex, text: &str, replacement: &str) -> (String, usize) {
+        let mut new_text = String::with_capacity(text.len());
+        let mut count = 0;
+        for (line_index, line) in text.split('\n').enumerate() {
+            if line_index > 0 {
+                new_text.push('\n');
+            }
+            let mut end = 0;
+            for captures in regex.captures_iter(line) {
+                let range = captures.range();
+                if range.is_empty() {
+                    continue;
+                }
+                new_text.push_str(&line[end..range.start]);
+                if self.is_regex {
+                    new_text.push_str(&captures.expand(line, replacement));
+                } else {
+                    new_text.push_str(replacement);
+                }
+                end = range.end;
+                count += 1;
+            }
+            new_text.push_str(&line[end..]);
+        }
+        (new_text, count)
+    }
+}
+
+/// Finds the start and end of every match of `regex` in `lines`. Matches never span lines, and
+/// empty matches are left out since there is nothing to highlight or replace.
+pub fn find_matches(regex: &Regex, lines: &[String]) -> Vec<(Position, Position)> {
+    let mut matches = Vec::new();
+    for (line_index, line) in lines.iter().enumerate() {
+        for captures in regex.captures_iter(line) {
+            let range = captures.range();
+            if range.is_empty() {
+                continue;
+            }
+            matches.push((
+                Position {
+                    line_index,
+                    byte_index: range.start,
+                },
+                Position {
+                    line_index,
+                    byte_index: range.end,
+                },
+            ));
+        }
+    }
+    matches
+}
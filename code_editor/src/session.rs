@@ -1,9 +1,12 @@
 use {
     crate::{
         char::CharExt,
+        decoration::{Decoration, DecorationSet, DecorationType},
         document::CodeDocument,
         history::{EditKind,NewGroup},
         layout::{BlockElement, Layout, WrappedElement},
+        regex::{Regex, RegexError},
+        search::{self, SearchQuery},
         selection::{Affinity, Cursor, SelectionSet},
         str::StrExt,
         text::{Change, Drift, Edit, Length, Position, Text},
@@ -30,6 +33,7 @@ pub struct CodeSession {
     selection_state: RefCell<SelectionState>,
     wrap_column: Cell<Option<usize>>,
     fold_state: RefCell<FoldState>,
+    search_state: RefCell<SearchState>,
     edit_receiver: Receiver<(Option<SelectionSet>, Vec<Edit>)>,
 }
 
@@ -63,6 +67,7 @@ pub fn new(document: CodeDocument) -> Self {
                 folded_lines: HashSet::new(),
                 unfolding_lines: HashSet::new(),
             }),
+            search_state: RefCell::new(SearchState::default()),
             edit_receiver,
         };
         for line in 0..line_count {
@@ -442,6 +447,47 @@ pub fn paste(&self, text: Text) {
         );
     }
     
+    /// Replaces `replaced` right before every cursor with `text`. Input methods use this to replace the
+    /// text they are still composing. Cursors that don't have `replaced` before them just get `text`
+    /// inserted, so we never delete text that isn't ours, or split a char.
+    pub fn replace_before_cursor(&self, replaced: &str, text: Text) {
+        self.document.edit_selections(
+            self.id,
+            EditKind::Other,
+            &self.selection_state.borrow().selections,
+            &self.settings,
+            |mut editor, position, length| {
+                editor.apply_edit(Edit {
+                    change: Change::Delete(position, length),
+                    drift: Drift::Before,
+                });
+                let line = &editor.as_text().as_lines()[position.line_index];
+                let byte_count = match position.byte_index.checked_sub(replaced.len()) {
+                    Some(start) if line.get(start..position.byte_index) == Some(replaced) => replaced.len(),
+                    _ => 0,
+                };
+                let start = Position {
+                    line_index: position.line_index,
+                    byte_index: position.byte_index - byte_count,
+                };
+                editor.apply_edit(Edit {
+                    change: Change::Delete(
+                        start,
+                        Length {
+                            line_count: 0,
+                            byte_count,
+                        },
+                    ),
+                    drift: Drift::Before,
+                });
+                editor.apply_edit(Edit {
+                    change: Change::Insert(start, text.clone()),
+                    drift: Drift::Before,
+                });
+            },
+        );
+    }
+
     pub fn paste_grouped(&self, text: Text, group:u64) {
         self.document.edit_selections(
             self.id,
@@ -822,6 +868,132 @@ pub fn redo(&self) -> bool {
             .redo(self.id, &self.selection_state.borrow().selections)
     }
 
+    /// Searches the document for `query` and highlights every match, or stops searching when
+    /// `query` is `None`. The matches are kept up to date as the document changes. Returns the
+    /// number of matches.
+    pub fn set_search_query(&self, query: Option<SearchQuery>) -> Result<usize, RegexError> {
+        let mut search_state = self.search_state.borrow_mut();
+        search_state.query = None;
+        search_state.regex = None;
+        search_state.matches.clear();
+        let Some(query) = query.filter(|query| !query.text.is_empty()) else {
+            return Ok(0);
+        };
+        search_state.regex = Some(query.compile()?);
+        search_state.query = Some(query);
+        drop(search_state);
+        self.update_search_matches();
+        Ok(self.search_matches().len())
+    }
+
+    pub fn search_query(&self) -> Option<SearchQuery> {
+        self.search_state.borrow().query.clone()
+    }
+
+    /// The matches of the search query, in document order.
+    pub fn search_matches(&self) -> Ref<'_, [Decoration]> {
+        Ref::map(self.search_state.borrow(), |search_state| {
+            search_state.matches.as_decorations()
+        })
+    }
+
+    /// The index of the match that is selected, if any.
+    pub fn selected_search_match_index(&self) -> Option<usize> {
+        let selection = self.last_added_selection();
+        self.search_matches().iter().position(|decoration| {
+            decoration.start() == selection.start() && decoration.end() == selection.end()
+        })
+    }
+
+    /// Selects the first match after the last added selection, wrapping around at the end of
+    /// the document. Returns `false` if there are no matches.
+    pub fn find_next(&self) -> bool {
+        let end = self.last_added_selection().end();
+        let decoration = {
+            let matches = self.search_matches();
+            match matches
+                .iter()
+                .find(|decoration| decoration.start() >= end)
+                .or(matches.first())
+            {
+                Some(&decoration) => decoration,
+                None => return false,
+            }
+        };
+        self.select_search_match(decoration);
+        true
+    }
+
+    /// Selects the last match before the last added selection, wrapping around at the start of
+    /// the document. Returns `false` if there are no matches.
+    pub fn find_prev(&self) -> bool {
+        let start = self.last_added_selection().start();
+        let decoration = {
+            let matches = self.search_matches();
+            match matches
+                .iter()
+                .rev()
+                .find(|decoration| decoration.end() <= start)
+                .or(matches.last())
+            {
+                Some(&decoration) => decoration,
+                None => return false,
+            }
+        };
+        self.select_search_match(decoration);
+        true
+    }
+
+    /// Selects every match, with a cursor at the end of each. Returns `false` if there are no
+    /// matches.
+    pub fn select_all_search_matches(&self) -> bool {
+        let Some(selections) = self.search_match_selections() else {
+            return false;
+        };
+        let mut selection_state = self.selection_state.borrow_mut();
+        selection_state.mode = SelectionMode::Simple;
+        selection_state.last_added_selection_index = Some(selections.len() - 1);
+        selection_state.selections = selections;
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        self.update_highlighted_delimiter_positions();
+        self.document().force_new_group();
+        true
+    }
+
+    /// Replaces the selected match with `replacement` and selects the next match once the edit
+    /// is handled. If no match is selected, only selects the next one. Returns whether a match
+    /// was replaced.
+    pub fn replace_search_match(&self, replacement: &str) -> bool {
+        let Some(index) = self.selected_search_match_index() else {
+            self.find_next();
+            return false;
+        };
+        let decoration = self.search_matches()[index];
+        let text = self.search_replacement(decoration, replacement);
+        let mut selections = SelectionSet::new();
+        selections.set_selection(search_match_selection(decoration));
+        self.replace_selections(selections, vec![text]);
+        self.search_state.borrow_mut().select_next_match = true;
+        true
+    }
+
+    /// Replaces every match with `replacement`, as a single step that can be undone. Returns the
+    /// number of matches that were replaced.
+    pub fn replace_all_search_matches(&self, replacement: &str) -> usize {
+        let Some(selections) = self.search_match_selections() else {
+            return 0;
+        };
+        let texts: Vec<_> = self
+            .search_matches()
+            .iter()
+            .map(|&decoration| self.search_replacement(decoration, replacement))
+            .collect();
+        let count = texts.len();
+        self.replace_selections(selections, texts);
+        count
+    }
+
     pub fn handle_changes(&mut self) {
         while let Ok((selections, edits)) = self.edit_receiver.try_recv() {
             self.update_after_edit(selections, &edits);
@@ -929,6 +1101,102 @@ fn update_after_edit(&self, selections: Option<SelectionSet>, edits: &[Edit]) {
         }
         drop(selection_state);
         self.update_highlighted_delimiter_positions();
+        self.update_search_matches();
+        if mem::take(&mut self.search_state.borrow_mut().select_next_match) {
+            self.find_next();
+        }
+    }
+
+    fn last_added_selection(&self) -> Selection {
+        let selection_state = self.selection_state.borrow();
+        let index = selection_state
+            .last_added_selection_index
+            .unwrap_or(selection_state.selections.len() - 1);
+        selection_state.selections[index]
+    }
+
+    fn select_search_match(&self, decoration: Decoration) {
+        let mut selection_state = self.selection_state.borrow_mut();
+        selection_state.mode = SelectionMode::Simple;
+        selection_state
+            .selections
+            .set_selection(search_match_selection(decoration));
+        selection_state.last_added_selection_index = Some(0);
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        self.update_highlighted_delimiter_positions();
+        self.document().force_new_group();
+    }
+
+    fn search_match_selections(&self) -> Option<SelectionSet> {
+        let matches = self.search_matches();
+        let (first, rest) = matches.split_first()?;
+        let mut selections = SelectionSet::new();
+        selections.set_selection(search_match_selection(*first));
+        for &decoration in rest {
+            selections.add_selection(search_match_selection(decoration));
+        }
+        Some(selections)
+    }
+
+    fn search_replacement(&self, decoration: Decoration, replacement: &str) -> Text {
+        let search_state = self.search_state.borrow();
+        let (Some(query), Some(regex)) = (&search_state.query, &search_state.regex) else {
+            return Text::from(replacement);
+        };
+        let start = decoration.start();
+        Text::from(query.replacement(
+            regex,
+            &self.document.as_text().as_lines()[start.line_index],
+            start.byte_index,
+            replacement,
+        ))
+    }
+
+    // Selects `selections` and replaces each with the text at the same index. The edit kind keeps
+    // the replacements in an undo group of their own, so a single undo brings all of them back.
+    fn replace_selections(&self, selections: SelectionSet, texts: Vec<Text>) {
+        let mut selection_state = self.selection_state.borrow_mut();
+        selection_state.mode = SelectionMode::Simple;
+        selection_state.last_added_selection_index = Some(selections.len() - 1);
+        selection_state.selections = selections;
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        let mut texts = texts.into_iter();
+        self.document.edit_selections(
+            self.id,
+            EditKind::Other,
+            &self.selection_state.borrow().selections,
+            &self.settings,
+            |mut editor, position, length| {
+                editor.apply_edit(Edit {
+                    change: Change::Delete(position, length),
+                    drift: Drift::Before,
+                });
+                editor.apply_edit(Edit {
+                    change: Change::Insert(position, texts.next().unwrap()),
+                    drift: Drift::Before,
+                });
+            },
+        );
+    }
+
+    fn update_search_matches(&self) {
+        let mut search_state = self.search_state.borrow_mut();
+        let Some(regex) = &search_state.regex else {
+            return;
+        };
+        let matches = search::find_matches(regex, self.document.as_text().as_lines());
+        let mut decorations = DecorationSet::new();
+        for (index, (start, end)) in matches.into_iter().enumerate() {
+            decorations.add_decoration(Decoration::new(
+                index,
+                start,
+                end,
+                DecorationType::SearchMatch,
+            ));
+        }
+        search_state.matches = decorations;
     }
 
     fn update_y(&self) {
@@ -1057,6 +1325,14 @@ struct SelectionState {
     highlighted_delimiter_positions: HashSet<Position>,
 }
 
+#[derive(Debug, Default)]
+struct SearchState {
+    query: Option<SearchQuery>,
+    regex: Option<Regex>,
+    matches: DecorationSet,
+    select_next_match: bool,
+}
+
 #[derive(Debug)]
 struct FoldState {
     folding_lines: HashSet<usize>,
@@ -1077,6 +1353,17 @@ pub fn reindent(string: &str, f: impl FnOnce(usize) -> usize) -> (usize, usize,
     )
 }
 
+fn search_match_selection(decoration: Decoration) -> Selection {
+    Selection {
+        cursor: Cursor {
+            position: decoration.end(),
+            affinity: Affinity::Before,
+            preferred_column_index: None,
+        },
+        anchor: decoration.start(),
+    }
+}
+
 fn grow_selection(
     selection: Selection,
     lines: &[String],
@@ -0,0 +1,125 @@
+use {
+    super::{CharExt, Cursor, LanguageTokenizer},
+    crate::token::TokenKind,
+};
+
+/// The tokenizer for JSON.
+///
+/// Object keys are highlighted as identifiers rather than strings. Comments are accepted as
+/// well, since many JSON configuration files contain them.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct JsonTokenizer;
+
+impl LanguageTokenizer for JsonTokenizer {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::BlockCommentTail => block_comment_tail(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    BlockCommentTail,
+}
+
+fn initial(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    match (cursor.peek(0), cursor.peek(1)) {
+        ('/', '/') => {
+            cursor.skip_line();
+            (State::Initial, TokenKind::Comment)
+        }
+        ('/', '*') => {
+            cursor.skip(2);
+            block_comment_tail(cursor)
+        }
+        ('"', _) => string(cursor),
+        ('{', _) | ('}', _) | ('[', _) | (']', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        (':', _) | (',', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('-', char) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_identifier_start() => {
+            let start = cursor.index();
+            while cursor.skip_if(|char| char.is_identifier_continue()) {}
+            let end = cursor.index();
+            (
+                State::Initial,
+                match &cursor.as_str()[start..end] {
+                    "true" | "false" | "null" => TokenKind::OtherKeyword,
+                    _ => TokenKind::Unknown,
+                },
+            )
+        }
+        (char, _) if char.is_whitespace() => {
+            cursor.skip_whitespace();
+            (State::Initial, TokenKind::Whitespace)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Unknown)
+        }
+    }
+}
+
+fn string(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '"');
+    cursor.skip(1);
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('"', _) => {
+                cursor.skip(1);
+                break;
+            }
+            ('\0', _) => return (State::Initial, TokenKind::String),
+            ('\\', '"') | ('\\', '\\') => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+    // A string that is followed by a colon is the key of an object member.
+    let mut index = 0;
+    while cursor.peek(index).is_whitespace() {
+        index += 1;
+    }
+    if cursor.peek(index) == ':' {
+        (State::Initial, TokenKind::Identifier)
+    } else {
+        (State::Initial, TokenKind::String)
+    }
+}
+
+fn number(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    cursor.skip_if(|char| char == '-');
+    cursor.skip_digits(10);
+    if cursor.peek(0) == '.' {
+        cursor.skip(1);
+        cursor.skip_digits(10);
+    }
+    if (cursor.peek(0) == 'E' || cursor.peek(0) == 'e') && !cursor.skip_exponent() {
+        return (State::Initial, TokenKind::Unknown);
+    }
+    (State::Initial, TokenKind::Number)
+}
+
+fn block_comment_tail(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('*', '/') => {
+                cursor.skip(2);
+                break (State::Initial, TokenKind::Comment);
+            }
+            ('\0', _) => break (State::BlockCommentTail, TokenKind::Comment),
+            _ => cursor.skip(1),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use {
+    super::{Cursor, LanguageTokenizer},
+    crate::token::TokenKind,
+};
+
+/// The tokenizer for Markdown.
+///
+/// Headings are highlighted as type names, inline code and the contents of fenced code blocks
+/// as strings, link destinations as constants and markup characters as punctuators.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MarkdownTokenizer;
+
+impl LanguageTokenizer for MarkdownTokenizer {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::FencedCode(state) => state.next(cursor),
+            State::HtmlCommentTail => html_comment_tail(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    FencedCode(FencedCodeState),
+    HtmlCommentTail,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FencedCodeState {
+    fence_char: char,
+    fence_len: usize,
+}
+
+impl FencedCodeState {
+    fn next(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        let rest = &cursor.as_str()[cursor.index()..];
+        let fence_len = fence_len(rest, self.fence_char);
+        if fence_len >= self.fence_len && rest.trim_start()[fence_len..].trim().is_empty() {
+            cursor.skip_line();
+            return (State::Initial, TokenKind::Delimiter);
+        }
+        cursor.skip_line();
+        (State::FencedCode(self), TokenKind::String)
+    }
+}
+
+fn initial(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    if cursor.is_at_line_start() {
+        if let Some(result) = line_start(cursor) {
+            return result;
+        }
+    }
+    match (cursor.peek(0), cursor.peek(1), cursor.peek(2), cursor.peek(3)) {
+        ('<', '!', '-', '-') => {
+            cursor.skip(4);
+            html_comment_tail(cursor)
+        }
+        ('\\', char, _, _) if char.is_ascii_punctuation() => {
+            cursor.skip(2);
+            (State::Initial, TokenKind::Identifier)
+        }
+        ('`', _, _, _) => inline_code(cursor),
+        ('<', char, _, _) if char.is_ascii_alphabetic() || char == '/' => tag_or_autolink(cursor),
+        (']', '(', _, _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('(', _, _, _) if cursor.index() > 0 && cursor.as_str()[..cursor.index()].ends_with(']') => {
+            link_destination(cursor)
+        }
+        ('[', _, _, _) | (']', _, _, _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('!', '[', _, _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('*', _, _, _) | ('_', _, _, _) | ('~', _, _, _) => {
+            let char = cursor.peek(0);
+            while cursor.skip_if(|next_char| next_char == char) {}
+            (State::Initial, TokenKind::Punctuator)
+        }
+        (char, _, _, _) if char.is_whitespace() => {
+            cursor.skip_whitespace();
+            (State::Initial, TokenKind::Whitespace)
+        }
+        _ => {
+            cursor.skip(1);
+            loop {
+                match (cursor.peek(0), cursor.peek(1)) {
+                    // Underscores inside a word do not start emphasis.
+                    ('_', char) if char.is_alphanumeric() => cursor.skip(2),
+                    (char, _) if !char.is_whitespace() && !is_markup(char) => cursor.skip(1),
+                    _ => break,
+                }
+            }
+            (State::Initial, TokenKind::Identifier)
+        }
+    }
+}
+
+/// Tokenizes the constructs that can only appear at the start of a line. Returns `None` if
+/// there is none here.
+fn line_start(cursor: &mut Cursor<'_>) -> Option<(State, TokenKind)> {
+    let rest = &cursor.as_str()[cursor.index()..];
+    match cursor.peek(0) {
+        '#' => {
+            let level = rest.chars().take_while(|&char| char == '#').count();
+            let after = rest[level..].chars().next();
+            if level <= 6 && after.is_none_or(|char| char.is_whitespace()) {
+                cursor.skip_line();
+                return Some((State::Initial, TokenKind::Typename));
+            }
+        }
+        char @ ('`' | '~') => {
+            let fence_len = fence_len(rest, char);
+            if fence_len >= 3 && !(char == '`' && rest[fence_len..].contains('`')) {
+                cursor.skip_line();
+                return Some((
+                    State::FencedCode(FencedCodeState {
+                        fence_char: char,
+                        fence_len,
+                    }),
+                    TokenKind::Delimiter,
+                ));
+            }
+        }
+        '>' => {
+            cursor.skip(1);
+            return Some((State::Initial, TokenKind::Punctuator));
+        }
+        char @ ('-' | '*' | '_') => {
+            // A thematic break is a line of at least three of the same character.
+            let count = rest.chars().filter(|&next_char| next_char == char).count();
+            if count >= 3 && rest.chars().all(|next_char| next_char == char || next_char.is_whitespace()) {
+                cursor.skip_line();
+                return Some((State::Initial, TokenKind::Punctuator));
+            }
+            if char != '_' && cursor.peek(1).is_whitespace() {
+                cursor.skip(1);
+                return Some((State::Initial, TokenKind::Punctuator));
+            }
+        }
+        '+' if cursor.peek(1).is_whitespace() => {
+            cursor.skip(1);
+            return Some((State::Initial, TokenKind::Punctuator));
+        }
+        char if char.is_ascii_digit() => {
+            let digit_count = rest.chars().take_while(|char| char.is_ascii_digit()).count();
+            let marker = cursor.peek(digit_count);
+            if (marker == '.' || marker == ')') && cursor.peek(digit_count + 1).is_whitespace() {
+                cursor.skip(digit_count + 1);
+                return Some((State::Initial, TokenKind::Punctuator));
+            }
+        }
+        _ => {}
+    }
+    None
+}
+
+fn fence_len(string: &str, fence_char: char) -> usize {
+    string
+        .trim_start()
+        .chars()
+        .take_while(|&char| char == fence_char)
+        .count()
+}
+
+fn inline_code(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    let mut len = 0;
+    while cursor.skip_if(|char| char == '`') {
+        len += 1;
+    }
+    // Inline code ends at the next run of backticks of the same length.
+    loop {
+        match cursor.peek(0) {
+            '\0' => break,
+            '`' => {
+                let mut end_len = 0;
+                while cursor.skip_if(|char| char == '`') {
+                    end_len += 1;
+                }
+                if end_len == len {
+                    break;
+                }
+            }
+            _ => cursor.skip(1),
+        }
+    }
+    (State::Initial, TokenKind::String)
+}
+
+fn tag_or_autolink(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '<');
+    let start = cursor.index();
+    cursor.skip(1);
+    while cursor.skip_if(|char| char != '>' && char != '\0') {}
+    cursor.skip_if(|char| char == '>');
+    let string = &cursor.as_str()[start..cursor.index()];
+    if string.contains("://") || (string.contains('@') && !string.contains(' ')) {
+        (State::Initial, TokenKind::Constant)
+    } else {
+        (State::Initial, TokenKind::Typename)
+    }
+}
+
+fn link_destination(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '(');
+    cursor.skip(1);
+    let mut depth = 0;
+    loop {
+        match cursor.peek(0) {
+            '\0' => break,
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                cursor.skip(1);
+                break;
+            }
+            ')' => depth -= 1,
+            _ => {}
+        }
+        cursor.skip(1);
+    }
+    (State::Initial, TokenKind::Constant)
+}
+
+fn html_comment_tail(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    loop {
+        match (cursor.peek(0), cursor.peek(1), cursor.peek(2)) {
+            ('-', '-', '>') => {
+                cursor.skip(3);
+                break (State::Initial, TokenKind::Comment);
+            }
+            ('\0', _, _) => break (State::HtmlCommentTail, TokenKind::Comment),
+            _ => cursor.skip(1),
+        }
+    }
+}
+
+fn is_markup(char: char) -> bool {
+    matches!(
+        char,
+        '\\' | '`' | '<' | '[' | ']' | '(' | '!' | '*' | '_' | '~' | '\0'
+    )
+}
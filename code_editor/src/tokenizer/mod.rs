@@ -0,0 +1,337 @@
+pub mod json;
+pub mod markdown;
+pub mod plain;
+pub mod rust;
+pub mod shader;
+pub mod toml;
+
+pub use self::{
+    json::JsonTokenizer, markdown::MarkdownTokenizer, plain::PlainTokenizer,
+    rust::RustTokenizer, shader::ShaderTokenizer, toml::TomlTokenizer,
+};
+
+use {
+    crate::{
+        text::{Change, Text},
+        token::TokenKind,
+        Token,
+    },
+    std::{collections::HashMap, fmt, path::Path, rc::Rc},
+};
+
+/// A tokenizer for a single language.
+///
+/// Documents are tokenized one line at a time. The state at the end of each line is carried
+/// over to the start of the next one, so tokens such as block comments and multiline strings
+/// can span several lines. Because we cache the start and end state of each line, only lines
+/// whose start state changed need to be tokenized again after an edit.
+pub trait LanguageTokenizer: fmt::Debug {
+    type State: Clone + Copy + fmt::Debug + Default + Eq;
+
+    /// Reads the next token from `cursor`, which is never at the end of the line, and returns
+    /// the state after that token together with its kind. If the cursor did not move, the
+    /// next char becomes a token of kind [`TokenKind::Unknown`] and the state is kept.
+    fn next_token(&self, state: Self::State, cursor: &mut Cursor<'_>) -> (Self::State, TokenKind);
+}
+
+/// The part of a tokenizer that a document drives as its text changes.
+pub trait DocumentTokenizer: fmt::Debug {
+    fn apply_change(&mut self, change: &Change);
+
+    fn update(&mut self, text: &Text, tokens: &mut [Vec<Token>]);
+}
+
+/// Creates a [`DocumentTokenizer`] for a document with the given number of lines.
+pub type TokenizerFactory = Rc<dyn Fn(usize) -> Box<dyn DocumentTokenizer>>;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IncrementalTokenizer<T: LanguageTokenizer> {
+    tokenizer: T,
+    state: Vec<Option<(T::State, T::State)>>,
+}
+
+impl<T: LanguageTokenizer> IncrementalTokenizer<T> {
+    pub fn new(tokenizer: T, line_count: usize) -> Self {
+        Self {
+            tokenizer,
+            state: (0..line_count).map(|_| None).collect(),
+        }
+    }
+}
+
+impl<T: LanguageTokenizer> DocumentTokenizer for IncrementalTokenizer<T> {
+    fn apply_change(&mut self, change: &Change) {
+        match *change {
+            Change::Insert(point, ref text) => {
+                self.state[point.line_index] = None;
+                let line_count = text.length().line_count;
+                if line_count > 0 {
+                    let line = point.line_index + 1;
+                    self.state.splice(line..line, (0..line_count).map(|_| None));
+                }
+            }
+            Change::Delete(start, length) => {
+                self.state[start.line_index] = None;
+                let line_count = length.line_count;
+                if line_count > 0 {
+                    let start_line = start.line_index + 1;
+                    let end_line = start_line + line_count;
+                    self.state.drain(start_line..end_line);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, text: &Text, tokens: &mut [Vec<Token>]) {
+        let mut state = T::State::default();
+        for line in 0..text.as_lines().len() {
+            match self.state[line] {
+                Some((start_state, end_state)) if state == start_state => {
+                    state = end_state;
+                }
+                _ => {
+                    let start_state = state;
+                    let mut new_tokens = Vec::new();
+                    let mut cursor = Cursor::new(&text.as_lines()[line]);
+                    while cursor.peek(0) != '\0' {
+                        let start = cursor.index;
+                        let (next_state, kind) = self.tokenizer.next_token(state, &mut cursor);
+                        let kind = if cursor.index > start {
+                            state = next_state;
+                            kind
+                        } else {
+                            // A tokenizer that gets stuck would hang the editor, so we force
+                            // progress by taking a single char as an error token.
+                            cursor.index = start;
+                            cursor.skip(1);
+                            TokenKind::Unknown
+                        };
+                        let end = cursor.index;
+                        new_tokens.push(Token {
+                            len: end - start,
+                            kind,
+                        });
+                    }
+                    self.state[line] = Some((start_state, state));
+                    tokens[line] = new_tokens;
+                }
+            }
+        }
+    }
+}
+
+/// The Rust tokenizer for a whole document, which is what the editor used before tokenizers
+/// became per language. Use a [`TokenizerRegistry`] to pick one by file extension instead.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Tokenizer {
+    inner: IncrementalTokenizer<RustTokenizer>,
+}
+
+impl Tokenizer {
+    pub fn new(line_count: usize) -> Self {
+        Self {
+            inner: IncrementalTokenizer::new(RustTokenizer, line_count),
+        }
+    }
+
+    pub fn apply_change(&mut self, change: &Change) {
+        self.inner.apply_change(change);
+    }
+
+    pub fn update(&mut self, text: &Text, tokens: &mut [Vec<Token>]) {
+        self.inner.update(text, tokens);
+    }
+}
+
+/// Maps file extensions to the tokenizers used to highlight them.
+///
+/// The default registry knows about Rust, TOML, JSON, Markdown and the makepad shader dialect.
+/// Extensions are matched case insensitively.
+#[derive(Clone)]
+pub struct TokenizerRegistry {
+    factories: HashMap<String, TokenizerFactory>,
+}
+
+impl TokenizerRegistry {
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registers `tokenizer` for the given extensions, replacing any existing ones.
+    pub fn register<T>(&mut self, extensions: &[&str], tokenizer: T)
+    where
+        T: LanguageTokenizer + Clone + 'static,
+    {
+        let factory: TokenizerFactory = Rc::new(move |line_count| {
+            Box::new(IncrementalTokenizer::new(tokenizer.clone(), line_count))
+        });
+        for extension in extensions {
+            self.factories
+                .insert(extension.to_lowercase(), factory.clone());
+        }
+    }
+
+    /// Returns the extensions that have a tokenizer, in no particular order.
+    pub fn extensions(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(|extension| extension.as_str())
+    }
+
+    pub fn get(&self, extension: &str) -> Option<TokenizerFactory> {
+        self.factories.get(&extension.to_lowercase()).cloned()
+    }
+
+    /// Returns the tokenizer for the extension of `path`, or a [`PlainTokenizer`] if there
+    /// is none.
+    pub fn get_for_path(&self, path: &str) -> TokenizerFactory {
+        Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.get(extension))
+            .unwrap_or_else(|| {
+                Rc::new(|line_count| {
+                    Box::new(IncrementalTokenizer::new(PlainTokenizer, line_count))
+                })
+            })
+    }
+}
+
+impl Default for TokenizerRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(&["rs"], RustTokenizer);
+        registry.register(&["toml"], TomlTokenizer);
+        registry.register(&["json"], JsonTokenizer);
+        registry.register(&["md", "markdown"], MarkdownTokenizer);
+        registry.register(&["glsl", "vert", "frag", "shader"], ShaderTokenizer);
+        registry
+    }
+}
+
+impl fmt::Debug for TokenizerRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenizerRegistry")
+            .field("extensions", &self.factories.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct Cursor<'a> {
+    string: &'a str,
+    index: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(string: &'a str) -> Self {
+        Cursor { string, index: 0 }
+    }
+
+    /// Returns the line being tokenized.
+    pub fn as_str(&self) -> &'a str {
+        self.string
+    }
+
+    /// Returns the byte index of the cursor in the line.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns true if there is only whitespace before the cursor.
+    pub fn is_at_line_start(&self) -> bool {
+        self.string[..self.index].chars().all(|char| char.is_whitespace())
+    }
+
+    pub fn peek(&self, index: usize) -> char {
+        self.string[self.index..].chars().nth(index).unwrap_or('\0')
+    }
+
+    pub fn skip(&mut self, count: usize) {
+        self.index = self.string[self.index..]
+            .char_indices()
+            .nth(count)
+            .map_or(self.string.len(), |(index, _)| self.index + index);
+    }
+
+    pub fn skip_if<P>(&mut self, predicate: P) -> bool
+    where
+        P: FnOnce(char) -> bool,
+    {
+        if predicate(self.peek(0)) {
+            self.skip(1);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skips the rest of the line.
+    pub fn skip_line(&mut self) {
+        self.index = self.string.len();
+    }
+
+    pub fn skip_exponent(&mut self) -> bool {
+        debug_assert!(self.peek(0) == 'E' || self.peek(0) == 'e');
+        self.skip(1);
+        if self.peek(0) == '+' || self.peek(0) == '-' {
+            self.skip(1);
+        }
+        self.skip_digits(10)
+    }
+
+    pub fn skip_digits(&mut self, radix: u32) -> bool {
+        let mut has_skip_digits = false;
+        loop {
+            match self.peek(0) {
+                '_' => {
+                    self.skip(1);
+                }
+                char if char.is_digit(radix) => {
+                    self.skip(1);
+                    has_skip_digits = true;
+                }
+                _ => break,
+            }
+        }
+        has_skip_digits
+    }
+
+    pub fn skip_suffix(&mut self) -> bool {
+        if self.peek(0).is_identifier_start() {
+            self.skip(1);
+            while self.skip_if(|char| char.is_identifier_continue()) {}
+            return true;
+        }
+        false
+    }
+
+    pub fn skip_whitespace(&mut self) -> bool {
+        let mut has_skip_whitespace = false;
+        while self.skip_if(|char| char.is_whitespace()) {
+            has_skip_whitespace = true;
+        }
+        has_skip_whitespace
+    }
+}
+
+pub trait CharExt {
+    fn is_identifier_start(self) -> bool;
+    fn is_identifier_continue(self) -> bool;
+}
+
+impl CharExt for char {
+    fn is_identifier_start(self) -> bool {
+        match self {
+            'A'..='Z' | '_' | 'a'..='z' => true,
+            _ => false,
+        }
+    }
+
+    fn is_identifier_continue(self) -> bool {
+        match self {
+            '0'..='9' | 'A'..='Z' | '_' | 'a'..='z' => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use {
+    super::{Cursor, LanguageTokenizer},
+    crate::token::TokenKind,
+};
+
+/// A tokenizer for files we know nothing about, which only tells whitespace apart from
+/// everything else.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PlainTokenizer;
+
+impl LanguageTokenizer for PlainTokenizer {
+    type State = ();
+
+    fn next_token(&self, _state: (), cursor: &mut Cursor<'_>) -> ((), TokenKind) {
+        if cursor.skip_whitespace() {
+            return ((), TokenKind::Whitespace);
+        }
+        while cursor.skip_if(|char| char != '\0' && !char.is_whitespace()) {}
+        ((), TokenKind::Unknown)
+    }
+}
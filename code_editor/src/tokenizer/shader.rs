@@ -0,0 +1,183 @@
+use {
+    super::{CharExt, Cursor, LanguageTokenizer},
+    crate::token::TokenKind,
+};
+
+/// The tokenizer for the makepad shader dialect, which is also good enough for GLSL.
+///
+/// Besides the Rust-like syntax of shaders, this knows about their builtin types, declaration
+/// keywords such as `instance` and `uniform`, and the `#rrggbb` color literals of the DSL.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ShaderTokenizer;
+
+impl LanguageTokenizer for ShaderTokenizer {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::BlockCommentTail => block_comment_tail(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    BlockCommentTail,
+}
+
+fn initial(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    match (cursor.peek(0), cursor.peek(1)) {
+        ('/', '/') => {
+            cursor.skip_line();
+            (State::Initial, TokenKind::Comment)
+        }
+        ('/', '*') => {
+            cursor.skip(2);
+            block_comment_tail(cursor)
+        }
+        ('#', char) if char.is_ascii_hexdigit() => color_or_directive(cursor),
+        ('#', char) if char.is_identifier_start() => directive(cursor),
+        ('"', _) => string(cursor),
+        ('(', _) | (')', _) | ('[', _) | (']', _) | ('{', _) | ('}', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('.', char) if char.is_ascii_digit() => number(cursor),
+        ('!', '=')
+        | ('%', '=')
+        | ('&', '&')
+        | ('*', '=')
+        | ('+', '=')
+        | ('-', '=')
+        | ('-', '>')
+        | ('.', '.')
+        | ('/', '=')
+        | (':', ':')
+        | ('<', '=')
+        | ('=', '=')
+        | ('=', '>')
+        | ('>', '=')
+        | ('|', '|') => {
+            cursor.skip(2);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        (char, _) if char.is_identifier_start() => identifier_or_keyword(cursor),
+        (char, _) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_whitespace() => {
+            cursor.skip_whitespace();
+            (State::Initial, TokenKind::Whitespace)
+        }
+        (char, _) if char.is_ascii_punctuation() => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Unknown)
+        }
+    }
+}
+
+fn identifier_or_keyword(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    let start = cursor.index();
+    cursor.skip(1);
+    while cursor.skip_if(|char| char.is_identifier_continue()) {}
+    let end = cursor.index();
+    let string = &cursor.as_str()[start..end];
+    (
+        State::Initial,
+        match string {
+            "else" | "if" | "return" | "discard" => TokenKind::BranchKeyword,
+            "break" | "continue" | "for" | "while" | "from" | "to" | "step" => {
+                TokenKind::LoopKeyword
+            }
+            "bool" | "int" | "uint" | "float" | "vec2" | "vec3" | "vec4" | "bvec2" | "bvec3"
+            | "bvec4" | "ivec2" | "ivec3" | "ivec4" | "mat2" | "mat3" | "mat4" | "texture2D"
+            | "textureOES" | "sampler2D" | "void" | "Sdf2d" => TokenKind::Typename,
+            "const" | "fn" | "let" | "var" | "in" | "out" | "inout" | "self" | "Self" | "struct"
+            | "impl" | "true" | "false" | "instance" | "uniform" | "varying" | "texture"
+            | "geometry" | "pub" | "use" | "link" | "layout" | "precision" | "highp"
+            | "mediump" | "lowp" | "attribute" => TokenKind::OtherKeyword,
+            _ => {
+                let mut chars = string.chars();
+                if chars.next().unwrap().is_uppercase() {
+                    match chars.next() {
+                        Some(char) if char.is_uppercase() => TokenKind::Constant,
+                        _ => TokenKind::Typename,
+                    }
+                } else if cursor.peek(0) == '(' {
+                    TokenKind::Function
+                } else {
+                    TokenKind::Identifier
+                }
+            }
+        },
+    )
+}
+
+fn color_or_directive(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '#');
+    // A color is a `#` followed by hex digits only, so `#define` is a preprocessor directive.
+    let mut len = 1;
+    while cursor.peek(len).is_ascii_hexdigit() {
+        len += 1;
+    }
+    if cursor.peek(len).is_identifier_continue() {
+        return directive(cursor);
+    }
+    cursor.skip(len);
+    (State::Initial, TokenKind::Number)
+}
+
+fn directive(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '#');
+    cursor.skip(1);
+    while cursor.skip_if(|char| char.is_identifier_continue()) {}
+    (State::Initial, TokenKind::OtherKeyword)
+}
+
+fn number(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    cursor.skip_digits(10);
+    if cursor.peek(0) == '.' && cursor.peek(1) != '.' {
+        cursor.skip(1);
+        cursor.skip_digits(10);
+    }
+    if (cursor.peek(0) == 'E' || cursor.peek(0) == 'e') && !cursor.skip_exponent() {
+        return (State::Initial, TokenKind::Unknown);
+    }
+    cursor.skip_suffix();
+    (State::Initial, TokenKind::Number)
+}
+
+fn string(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '"');
+    cursor.skip(1);
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('"', _) => {
+                cursor.skip(1);
+                break;
+            }
+            ('\0', _) => break,
+            ('\\', '"') | ('\\', '\\') => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+    (State::Initial, TokenKind::String)
+}
+
+fn block_comment_tail(cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('*', '/') => {
+                cursor.skip(2);
+                break (State::Initial, TokenKind::Comment);
+            }
+            ('\0', _) => break (State::BlockCommentTail, TokenKind::Comment),
+            _ => cursor.skip(1),
+        }
+    }
+}
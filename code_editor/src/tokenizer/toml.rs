@@ -0,0 +1,202 @@
+use {
+    super::{Cursor, LanguageTokenizer},
+    crate::token::TokenKind,
+};
+
+/// The tokenizer for TOML.
+///
+/// Table headers are highlighted as type names, keys and bare values as identifiers.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TomlTokenizer;
+
+impl LanguageTokenizer for TomlTokenizer {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        match state {
+            State::Initial(state) => state.next(cursor),
+            State::MultilineStringTail(state) => state.next(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum State {
+    Initial(InitialState),
+    MultilineStringTail(MultilineStringTailState),
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::Initial(InitialState { depth: 0 })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InitialState {
+    // The number of brackets and braces that are open in the current value, so we can tell a
+    // table header apart from a nested array at the start of a line.
+    depth: usize,
+}
+
+impl InitialState {
+    fn next(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        match (cursor.peek(0), cursor.peek(1), cursor.peek(2)) {
+            ('[', _, _) if self.depth == 0 && cursor.is_at_line_start() => {
+                self.table_header(cursor)
+            }
+            ('"', '"', '"') | ('\'', '\'', '\'') => self.multiline_string(cursor),
+            ('"', _, _) | ('\'', _, _) => self.string(cursor),
+            ('#', _, _) => {
+                cursor.skip_line();
+                (State::Initial(self), TokenKind::Comment)
+            }
+            ('[', _, _) | ('{', _, _) => {
+                cursor.skip(1);
+                (
+                    State::Initial(InitialState {
+                        depth: self.depth + 1,
+                    }),
+                    TokenKind::Delimiter,
+                )
+            }
+            (']', _, _) | ('}', _, _) => {
+                cursor.skip(1);
+                (
+                    State::Initial(InitialState {
+                        depth: self.depth.saturating_sub(1),
+                    }),
+                    TokenKind::Delimiter,
+                )
+            }
+            ('=', _, _) | (',', _, _) | ('.', _, _) => {
+                cursor.skip(1);
+                (State::Initial(self), TokenKind::Punctuator)
+            }
+            ('+', char, _) | ('-', char, _) if char.is_ascii_digit() => self.number(cursor),
+            ('+', 'i', 'n') | ('-', 'i', 'n') | ('+', 'n', 'a') | ('-', 'n', 'a') => {
+                cursor.skip(1);
+                self.bare_key_or_value(cursor)
+            }
+            (char, _, _) if char.is_ascii_digit() => self.number(cursor),
+            (char, _, _) if is_bare_key(char) => self.bare_key_or_value(cursor),
+            (char, _, _) if char.is_whitespace() => {
+                cursor.skip_whitespace();
+                (State::Initial(self), TokenKind::Whitespace)
+            }
+            _ => {
+                cursor.skip(1);
+                (State::Initial(self), TokenKind::Unknown)
+            }
+        }
+    }
+
+    fn table_header(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        debug_assert!(cursor.peek(0) == '[');
+        let mut quote = None;
+        loop {
+            match (cursor.peek(0), quote) {
+                ('\0', _) => break,
+                (']', None) => {
+                    cursor.skip(1);
+                    cursor.skip_if(|char| char == ']');
+                    break;
+                }
+                ('"', None) | ('\'', None) => {
+                    quote = Some(cursor.peek(0));
+                    cursor.skip(1);
+                }
+                (char, Some(quote_char)) if char == quote_char => {
+                    quote = None;
+                    cursor.skip(1);
+                }
+                _ => cursor.skip(1),
+            }
+        }
+        (State::Initial(self), TokenKind::Typename)
+    }
+
+    fn bare_key_or_value(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        let start = cursor.index();
+        while cursor.skip_if(is_bare_key) {}
+        let end = cursor.index();
+        (
+            State::Initial(self),
+            match &cursor.as_str()[start..end] {
+                "true" | "false" => TokenKind::OtherKeyword,
+                "inf" | "nan" => TokenKind::Number,
+                _ => TokenKind::Identifier,
+            },
+        )
+    }
+
+    fn number(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        // This also covers dates and times, so we are lenient about what goes in a number.
+        cursor.skip_if(|char| char == '+' || char == '-');
+        while cursor.skip_if(|char| {
+            char.is_ascii_alphanumeric() || matches!(char, '_' | '.' | ':' | '+' | '-')
+        }) {}
+        (State::Initial(self), TokenKind::Number)
+    }
+
+    fn string(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        let quote = cursor.peek(0);
+        debug_assert!(quote == '"' || quote == '\'');
+        cursor.skip(1);
+        loop {
+            match (cursor.peek(0), cursor.peek(1)) {
+                (char, _) if char == quote => {
+                    cursor.skip(1);
+                    break;
+                }
+                ('\0', _) => break,
+                ('\\', '"') | ('\\', '\\') if quote == '"' => cursor.skip(2),
+                _ => cursor.skip(1),
+            }
+        }
+        (State::Initial(self), TokenKind::String)
+    }
+
+    fn multiline_string(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        let quote = cursor.peek(0);
+        cursor.skip(3);
+        MultilineStringTailState {
+            is_literal: quote == '\'',
+            depth: self.depth,
+        }
+        .next(cursor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MultilineStringTailState {
+    is_literal: bool,
+    depth: usize,
+}
+
+impl MultilineStringTailState {
+    fn next(self, cursor: &mut Cursor<'_>) -> (State, TokenKind) {
+        let quote = if self.is_literal { '\'' } else { '"' };
+        loop {
+            match (cursor.peek(0), cursor.peek(1), cursor.peek(2)) {
+                (char_0, char_1, char_2) if char_0 == quote && char_1 == quote && char_2 == quote => {
+                    cursor.skip(3);
+                    // A multiline string can end with up to two quotes of its own.
+                    cursor.skip_if(|char| char == quote);
+                    cursor.skip_if(|char| char == quote);
+                    break (
+                        State::Initial(InitialState { depth: self.depth }),
+                        TokenKind::String,
+                    );
+                }
+                ('\0', _, _) => break (State::MultilineStringTail(self), TokenKind::String),
+                ('\\', _, _) if !self.is_literal => cursor.skip(2),
+                _ => cursor.skip(1),
+            }
+        }
+    }
+}
+
+fn is_bare_key(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_' || char == '-'
+}
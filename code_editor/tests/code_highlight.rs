@@ -0,0 +1,114 @@
+use makepad_code_editor::{
+    code_highlight::{register_code_tokenizers, tokenize},
+    makepad_widgets::{
+        code_highlight::{CodeToken, CodeTokenKind, CodeTokenizers},
+        *,
+    },
+    TokenizerRegistry,
+};
+
+/// Pairs up the text of each token with its kind, leaving out whitespace.
+fn kinds<'a>(code: &'a str, tokens: &[CodeToken]) -> Vec<(&'a str, CodeTokenKind)> {
+    let mut start = 0;
+    let mut kinds = Vec::new();
+    for token in tokens {
+        let end = start + token.len;
+        if token.kind != CodeTokenKind::Whitespace {
+            kinds.push((&code[start..end], token.kind));
+        }
+        start = end;
+    }
+    assert_eq!(start, code.len(), "tokens do not cover {:?}", code);
+    kinds
+}
+
+fn registered_cx() -> Cx {
+    let mut cx = Cx::new(Box::new(|_, _| {}));
+    register_code_tokenizers(&mut cx, &TokenizerRegistry::default());
+    cx
+}
+
+use CodeTokenKind::*;
+
+#[test]
+fn newlines_are_whitespace_tokens() {
+    let factory = TokenizerRegistry::default().get("json").unwrap();
+    let code = "[1,\n/* a\nb */ \"é\"]\n";
+    assert_eq!(
+        kinds(code, &tokenize(&factory, code)),
+        [
+            ("[", Delimiter),
+            ("1", Number),
+            (",", Punctuator),
+            ("/* a", Comment),
+            ("b */", Comment),
+            ("\"é\"", String),
+            ("]", Delimiter),
+        ]
+    );
+}
+
+#[test]
+fn registers_every_language_of_the_registry() {
+    let mut cx = registered_cx();
+    let code = "[a]\nb = 1";
+    let tokens = CodeTokenizers::tokenize(&mut cx, "toml", code).unwrap();
+    assert_eq!(
+        kinds(code, &tokens),
+        [("[a]", Typename), ("b", Identifier), ("=", Punctuator), ("1", Number)]
+    );
+    for language in ["rs", "json", "md", "markdown", "glsl", "shader"] {
+        assert!(CodeTokenizers::tokenize(&mut cx, language, "x").is_some(), "{}", language);
+    }
+    assert!(CodeTokenizers::tokenize(&mut cx, "cobol", "x").is_none());
+}
+
+#[test]
+fn info_strings_are_matched_by_language() {
+    let mut cx = registered_cx();
+    let code = "for x in y {}";
+    for language in ["rust", "Rust", "rust,ignore", "rust no_run"] {
+        let tokens = CodeTokenizers::tokenize(&mut cx, language, code).unwrap();
+        assert_eq!(kinds(code, &tokens)[0], ("for", LoopKeyword), "{}", language);
+    }
+}
+
+#[test]
+fn live_design_keeps_its_own_tokenizer() {
+    let mut cx = registered_cx();
+    let code = "link widgets;";
+    let tokens = CodeTokenizers::tokenize(&mut cx, "live", code).unwrap();
+    assert_eq!(kinds(code, &tokens)[0], ("link", OtherKeyword));
+}
+
+#[test]
+fn code_tokenizers_are_pluggable() {
+    let mut cx = registered_cx();
+    CodeTokenizers::register(&mut cx, &["Shout"], |code| {
+        vec![CodeToken {
+            len: code.len(),
+            kind: Constant,
+        }]
+    });
+    let tokens = CodeTokenizers::tokenize(&mut cx, "shout", "HEY").unwrap();
+    assert_eq!(kinds("HEY", &tokens), [("HEY", Constant)]);
+}
+
+#[test]
+fn text_flow_has_code_colors() {
+    let mut cx = Cx::new(Box::new(|_, _| {}));
+    TextFlow::register_main_module(&mut cx);
+    makepad_widgets::live_design(&mut cx);
+    cx.live_expand();
+    let text_flow = TextFlow::new_from_module(
+        &mut cx,
+        LiveModuleId::from_str("makepad_widgets::text_flow").unwrap(),
+        live_id!(TextFlow),
+    )
+    .unwrap();
+    let colors = &text_flow.code_colors;
+    assert_eq!(colors.color(Comment), Some(colors.comment));
+    assert_eq!(colors.color(Whitespace), None);
+    assert_ne!(colors.comment, Vec4::default());
+    assert_ne!(colors.comment, colors.typename);
+}
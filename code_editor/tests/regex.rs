@@ -0,0 +1,179 @@
+use makepad_code_editor::regex::Regex;
+
+fn find(pattern: &str, text: &str) -> Option<String> {
+    let regex = Regex::new(pattern).unwrap();
+    regex.find_at(text, 0).map(|range| text[range].to_string())
+}
+
+fn find_all(pattern: &str, text: &str) -> Vec<String> {
+    let regex = Regex::new(pattern).unwrap();
+    regex
+        .captures_iter(text)
+        .map(|captures| text[captures.range()].to_string())
+        .collect()
+}
+
+fn error(pattern: &str) -> (String, usize) {
+    let error = Regex::new(pattern).unwrap_err();
+    (error.message, error.index)
+}
+
+#[test]
+fn literals_and_any() {
+    assert_eq!(find("b.d", "abcde").as_deref(), Some("bcd"));
+    assert_eq!(find("a\\.b", "axb a.b").as_deref(), Some("a.b"));
+    assert_eq!(find("é.", "aéz").as_deref(), Some("éz"));
+    assert_eq!(find("x", "abc"), None);
+}
+
+#[test]
+fn classes() {
+    assert_eq!(find("[a-c]+", "xxbcaz").as_deref(), Some("bca"));
+    assert_eq!(find("[^0-9 ]+", "12 ab3").as_deref(), Some("ab"));
+    assert_eq!(find("[]a]+", "x]a]").as_deref(), Some("]a]"));
+    assert_eq!(find("[a-]+", "x-a-").as_deref(), Some("-a-"));
+    assert_eq!(find("\\d+", "ab123c").as_deref(), Some("123"));
+    assert_eq!(find("\\w+", "  foo_1 ").as_deref(), Some("foo_1"));
+    assert_eq!(find("\\s\\S", "ab c").as_deref(), Some(" c"));
+    assert_eq!(find("[\\d.]+", "v1.25;").as_deref(), Some("1.25"));
+    assert_eq!(find("\\D\\W", "1a-").as_deref(), Some("a-"));
+    assert_eq!(error("[z-a]").0, "class range is out of order");
+    assert_eq!(error("[ab").0, "unclosed character class");
+    assert_eq!(error("[\\b]").0, "anchors are not allowed in a class");
+}
+
+#[test]
+fn anchors() {
+    assert_eq!(find("^a", "ba"), None);
+    assert_eq!(find("^b", "ba").as_deref(), Some("b"));
+    assert_eq!(find("a$", "ab"), None);
+    assert_eq!(find("b$", "ab").as_deref(), Some("b"));
+    assert_eq!(find_all("^", "abc"), [""]);
+    // the text before the start is still seen by `^`
+    assert_eq!(Regex::new("^a").unwrap().find_at("aa", 1), None);
+}
+
+#[test]
+fn word_boundaries() {
+    assert_eq!(find_all("\\bcat\\b", "cat concat cat_ cat."), ["cat", "cat"]);
+    assert_eq!(find_all("\\Bcat", "cat concat"), ["cat"]);
+    assert_eq!(
+        Regex::new("\\bcat").unwrap().find_at("concat", 3),
+        None,
+        "the char before the start is seen by `\\b`"
+    );
+}
+
+#[test]
+fn alternation_and_groups() {
+    assert_eq!(find("ab|a", "ab").as_deref(), Some("ab"));
+    assert_eq!(find("a|ab", "ab").as_deref(), Some("a"));
+    assert_eq!(find("x(?:ab)+y", "xababy").as_deref(), Some("xababy"));
+    assert_eq!(find("(|a)b", "ab").as_deref(), Some("ab"));
+    assert_eq!(error("(a").0, "unclosed group");
+    assert_eq!(error("a)"), ("unmatched `)`".to_string(), 1));
+    assert_eq!(error("(?=a)").0, "unsupported group syntax");
+}
+
+#[test]
+fn greedy_and_lazy_quantifiers() {
+    assert_eq!(find("<.*>", "<a><b>").as_deref(), Some("<a><b>"));
+    assert_eq!(find("<.*?>", "<a><b>").as_deref(), Some("<a>"));
+    assert_eq!(find("a+?", "aaa").as_deref(), Some("a"));
+    assert_eq!(find("a??b", "ab").as_deref(), Some("ab"));
+    assert_eq!(find("a{2,}?", "aaaa").as_deref(), Some("aa"));
+    assert_eq!(find("a{1,3}?b", "aaab").as_deref(), Some("aaab"));
+    assert_eq!(error("*a"), ("nothing to repeat".to_string(), 0));
+    assert_eq!(error("a**").0, "nothing to repeat");
+    assert_eq!(error("^*").0, "nothing to repeat");
+}
+
+#[test]
+fn counted_repeats() {
+    assert_eq!(find("a{2}", "a aa").as_deref(), Some("aa"));
+    assert_eq!(find("a{2,3}", "aaaa").as_deref(), Some("aaa"));
+    assert_eq!(find("a{0,1}b", "aab").as_deref(), Some("ab"));
+    assert_eq!(find_all("\\d{2,}", "1 22 333"), ["22", "333"]);
+    // a `{` that doesn't start a count is a literal
+    assert_eq!(find("a{,2}", "a{,2}").as_deref(), Some("a{,2}"));
+    assert_eq!(find("a{x}", "a{x}").as_deref(), Some("a{x}"));
+    assert_eq!(error("a{1001}"), ("repeat count is too large".to_string(), 1));
+    assert_eq!(error("a{99999999999999999999999}").0, "repeat count is too large");
+    assert_eq!(error("a{3,2}").0, "repeat counts are out of order");
+    assert_eq!(error("(a{1000}){1000}").0, "pattern is too large");
+    assert!(Regex::new("a{1000}").is_ok());
+}
+
+#[test]
+fn escapes() {
+    assert_eq!(find("\\t", "a\tb").as_deref(), Some("\t"));
+    assert_eq!(find("\\$\\(", "x$(y").as_deref(), Some("$("));
+    assert_eq!(error("\\q"), ("unknown escape".to_string(), 1));
+    assert_eq!(error("a\\").0, "trailing backslash");
+}
+
+#[test]
+fn captures() {
+    let regex = Regex::new("(\\w+)=(\\d+)?(x)?").unwrap();
+    let text = "let a=1, b=";
+    let captures = regex.captures_at(text, 0).unwrap();
+    assert_eq!(captures.range(), 4..7);
+    assert_eq!(captures.get(0), Some(4..7));
+    assert_eq!(captures.get(1), Some(4..5));
+    assert_eq!(captures.get(2), Some(6..7));
+    assert_eq!(captures.get(3), None);
+    assert_eq!(captures.get(4), None);
+    // groups from a failed branch are not left behind
+    let captures = regex.captures_at(text, 7).unwrap();
+    assert_eq!(captures.get(1), Some(9..10));
+    assert_eq!(captures.get(2), None);
+}
+
+#[test]
+fn expand() {
+    let regex = Regex::new("(\\w+)@(\\w+)").unwrap();
+    let text = "mail me@host now";
+    let captures = regex.captures_at(text, 0).unwrap();
+    assert_eq!(captures.expand(text, "$2 at $1"), "host at me");
+    assert_eq!(captures.expand(text, "<$0>"), "<me@host>");
+    assert_eq!(captures.expand(text, "${1}1"), "me1");
+    assert_eq!(captures.expand(text, "$$1 costs $"), "$1 costs $");
+    assert_eq!(captures.expand(text, "$9|${x}"), "|${x}");
+}
+
+#[test]
+fn ignore_case() {
+    let regex = Regex::new("straße|[a-c]+").unwrap().with_ignore_case(true);
+    assert_eq!(regex.find_at("xSTRAßE", 0), Some(1..8));
+    assert_eq!(regex.find_at("xyCAB", 0), Some(2..5));
+    assert_eq!(regex.find_at("ΑΒ", 0), None);
+    let regex = Regex::new("[^a]").unwrap().with_ignore_case(true);
+    assert_eq!(regex.find_at("Ab", 0), Some(1..2));
+    assert!(!Regex::new("abc").unwrap().is_match("ABC"));
+    // case folding is per char, so `ß` does not match `SS`
+    assert!(!Regex::new("ß").unwrap().with_ignore_case(true).is_match("SS"));
+}
+
+#[test]
+fn captures_iter_finds_every_match() {
+    assert_eq!(find_all("a+", "aa b aaa"), ["aa", "aaa"]);
+    assert_eq!(find_all("a*", "ba"), ["", "a", ""]);
+    assert_eq!(find_all("", "é"), ["", ""]);
+    // matches after the first can use text an earlier search already went over
+    assert_eq!(find_all("a|ab", "abab"), ["a", "a"]);
+    assert_eq!(find_all("(?:a|b)*?c", "abcabc"), ["abc", "abc"]);
+}
+
+#[test]
+fn literal() {
+    let regex = Regex::literal("a.b*");
+    assert_eq!(regex.find_at("axb a.b*", 0), Some(4..8));
+}
+
+#[test]
+fn long_lines_run_in_linear_time() {
+    let text = "a".repeat(10_000);
+    let regex = Regex::new("(a*)*b").unwrap();
+    assert!(!regex.is_match(&text));
+    assert_eq!(Regex::new("a").unwrap().captures_iter(&text).count(), 10_000);
+}
@@ -0,0 +1,113 @@
+use makepad_code_editor::{
+    search::{find_matches, SearchQuery},
+    text::Position,
+};
+
+fn matches(query: &SearchQuery, text: &str) -> Vec<(usize, usize, usize)> {
+    let lines: Vec<String> = text.split('\n').map(|line| line.to_string()).collect();
+    find_matches(&query.compile().unwrap(), &lines)
+        .into_iter()
+        .map(|(start, end)| {
+            assert_eq!(start.line_index, end.line_index);
+            (start.line_index, start.byte_index, end.byte_index)
+        })
+        .collect()
+}
+
+fn replace_all(query: &SearchQuery, text: &str, replacement: &str) -> (String, usize) {
+    query.replace_all(&query.compile().unwrap(), text, replacement)
+}
+
+#[test]
+fn plain_text_is_literal() {
+    let query = SearchQuery::new("a.b");
+    assert_eq!(matches(&query, "axb a.b\na.ba.b"), [(0, 4, 7), (1, 0, 3), (1, 3, 6)]);
+    assert_eq!(matches(&SearchQuery::new("$("), "x$(y"), [(0, 1, 3)]);
+}
+
+#[test]
+fn ignore_case() {
+    let query = SearchQuery::new("Foo");
+    assert_eq!(matches(&query, "foo Foo FOO"), [(0, 4, 7)]);
+    let query = query.with_ignore_case(true);
+    assert_eq!(matches(&query, "foo Foo FOO"), [(0, 0, 3), (0, 4, 7), (0, 8, 11)]);
+    let query = SearchQuery::new("[a-z]+").with_regex(true).with_ignore_case(true);
+    assert_eq!(matches(&query, "AB1c"), [(0, 0, 2), (0, 3, 4)]);
+}
+
+#[test]
+fn whole_word() {
+    let query = SearchQuery::new("cat").with_whole_word(true);
+    assert_eq!(matches(&query, "cat concat cats cat_ (cat)"), [(0, 0, 3), (0, 22, 25)]);
+    // unlike `\b`, whole word also holds next to punctuation
+    let query = SearchQuery::new("->").with_whole_word(true);
+    assert_eq!(matches(&query, "a->b a -> b"), [(0, 7, 9)]);
+    let query = SearchQuery::new("\\d+").with_regex(true).with_whole_word(true);
+    assert_eq!(matches(&query, "1 a2 33 4b"), [(0, 0, 1), (0, 5, 7)]);
+}
+
+#[test]
+fn regex_errors_are_reported() {
+    let error = SearchQuery::new("a(").with_regex(true).compile().unwrap_err();
+    assert_eq!(error.message, "unclosed group");
+    assert!(SearchQuery::new("a(").compile().is_ok());
+}
+
+#[test]
+fn empty_matches_are_left_out() {
+    let query = SearchQuery::new("x*").with_regex(true);
+    assert_eq!(matches(&query, "axxb\n\nx"), [(0, 1, 3), (2, 0, 1)]);
+    assert_eq!(replace_all(&query, "axxb\n\nx", "-"), ("a-b\n\n-".to_string(), 2));
+}
+
+#[test]
+fn replace_all_plain_text() {
+    let query = SearchQuery::new("$1");
+    assert_eq!(
+        replace_all(&query, "a $1 b\n$1$1", "$2"),
+        ("a $2 b\n$2$2".to_string(), 3)
+    );
+    assert_eq!(replace_all(&query, "none", "x"), ("none".to_string(), 0));
+}
+
+#[test]
+fn replace_all_expands_groups() {
+    let query = SearchQuery::new("(\\w+)=(\\w+)").with_regex(true);
+    assert_eq!(
+        replace_all(&query, "a=1, b=2\nc=3", "$2=$1"),
+        ("1=a, 2=b\n3=c".to_string(), 3)
+    );
+    assert_eq!(
+        replace_all(&query, "a=1", "${1}_$$"),
+        ("a_$".to_string(), 1)
+    );
+}
+
+#[test]
+fn replace_all_matches_do_not_span_lines() {
+    let query = SearchQuery::new("b\\s*c").with_regex(true);
+    assert_eq!(replace_all(&query, "ab\nc bc", "X"), ("ab\nc X".to_string(), 1));
+}
+
+#[test]
+fn replacement_for_a_single_match() {
+    let query = SearchQuery::new("(\\d)(\\d)").with_regex(true);
+    let regex = query.compile().unwrap();
+    assert_eq!(query.replacement(&regex, "12 34", 3, "$2$1"), "43");
+    let query = SearchQuery::new("12");
+    let regex = query.compile().unwrap();
+    assert_eq!(query.replacement(&regex, "12", 0, "$1"), "$1");
+}
+
+#[test]
+fn positions_are_byte_indices() {
+    let lines = vec!["éa".to_string()];
+    let query = SearchQuery::new("a");
+    assert_eq!(
+        find_matches(&query.compile().unwrap(), &lines),
+        [(
+            Position { line_index: 0, byte_index: 2 },
+            Position { line_index: 0, byte_index: 3 }
+        )]
+    );
+}
@@ -0,0 +1,62 @@
+use makepad_code_editor::{
+    decoration::DecorationSet,
+    history::NewGroup,
+    selection::Affinity,
+    session::SelectionMode,
+    text::{Position, Text},
+    CodeDocument, CodeSession,
+};
+
+/// A session on `text` with the cursor at the given byte of the last line.
+fn session_at(text: &str, byte_index: usize) -> CodeSession {
+    let mut session = CodeSession::new(CodeDocument::new(Text::from(text), DecorationSet::new()));
+    let line_index = text.split('\n').count() - 1;
+    session.set_selection(
+        Position { line_index, byte_index },
+        Affinity::Before,
+        SelectionMode::Simple,
+        NewGroup::Yes,
+    );
+    session.handle_changes();
+    session
+}
+
+fn replace_before_cursor(session: &mut CodeSession, replaced: &str, text: &str) -> String {
+    session.replace_before_cursor(replaced, text.into());
+    session.handle_changes();
+    session.document().as_text().to_string()
+}
+
+#[test]
+fn replaces_the_text_being_composed() {
+    let mut session = session_at("x = ;", 4);
+    session.insert("n".into());
+    session.handle_changes();
+    assert_eq!(replace_before_cursor(&mut session, "n", "ni"), "x = ni;");
+    assert_eq!(replace_before_cursor(&mut session, "ni", "に"), "x = に;");
+    assert_eq!(replace_before_cursor(&mut session, "に", "日本"), "x = 日本;");
+}
+
+#[test]
+fn keeps_text_that_does_not_match() {
+    let mut session = session_at("hello", 5);
+    assert_eq!(replace_before_cursor(&mut session, "xo", "!"), "hello!");
+    // more text than there is before the cursor
+    let mut session = session_at("ab", 0);
+    assert_eq!(replace_before_cursor(&mut session, "abc", "!"), "!ab");
+}
+
+#[test]
+fn never_splits_a_char() {
+    // one byte before the cursor is the middle of `é`
+    let mut session = session_at("é", 2);
+    assert_eq!(replace_before_cursor(&mut session, "b", "e"), "ée");
+    let mut session = session_at("aé", 3);
+    assert_eq!(replace_before_cursor(&mut session, "é", "e"), "ae");
+}
+
+#[test]
+fn only_looks_at_the_line_of_the_cursor() {
+    let mut session = session_at("ab\ncd", 0);
+    assert_eq!(replace_before_cursor(&mut session, "b\n", "x"), "ab\nxcd");
+}
@@ -0,0 +1,277 @@
+use makepad_code_editor::{
+    text::{Change, Position, Text},
+    token::{Token, TokenKind},
+    tokenizer::{
+        Cursor, DocumentTokenizer, IncrementalTokenizer, JsonTokenizer, LanguageTokenizer,
+        MarkdownTokenizer, ShaderTokenizer, TomlTokenizer,
+    },
+    Tokenizer,
+};
+
+/// Pairs up the text of each token with its kind, leaving out whitespace.
+fn kinds<'a>(text: &'a Text, tokens: &[Vec<Token>]) -> Vec<Vec<(&'a str, TokenKind)>> {
+    text.as_lines()
+        .iter()
+        .zip(tokens)
+        .map(|(line, tokens)| {
+            let mut start = 0;
+            let mut kinds = Vec::new();
+            for token in tokens {
+                let end = start + token.len;
+                if token.kind != TokenKind::Whitespace {
+                    kinds.push((&line[start..end], token.kind));
+                }
+                start = end;
+            }
+            assert_eq!(start, line.len(), "tokens do not cover {:?}", line);
+            kinds
+        })
+        .collect()
+}
+
+fn tokenize<T: LanguageTokenizer>(tokenizer: T, text: &Text) -> Vec<Vec<(&str, TokenKind)>> {
+    let mut tokens = vec![Vec::new(); text.as_lines().len()];
+    IncrementalTokenizer::new(tokenizer, text.as_lines().len()).update(text, &mut tokens);
+    kinds(text, &tokens)
+}
+
+use TokenKind::*;
+
+#[test]
+fn json() {
+    let text = Text::from("{\"key\": \"value\", \"n\": [-1.5e3, true, null]}");
+    assert_eq!(
+        tokenize(JsonTokenizer, &text),
+        [vec![
+            ("{", Delimiter),
+            ("\"key\"", Identifier),
+            (":", Punctuator),
+            ("\"value\"", String),
+            (",", Punctuator),
+            ("\"n\"", Identifier),
+            (":", Punctuator),
+            ("[", Delimiter),
+            ("-1.5e3", Number),
+            (",", Punctuator),
+            ("true", OtherKeyword),
+            (",", Punctuator),
+            ("null", OtherKeyword),
+            ("]", Delimiter),
+            ("}", Delimiter),
+        ]]
+    );
+}
+
+#[test]
+fn json_block_comment_spans_lines() {
+    let text = Text::from("1 /* a\nb\nc */ 2 // d");
+    assert_eq!(
+        tokenize(JsonTokenizer, &text),
+        [
+            vec![("1", Number), ("/* a", Comment)],
+            vec![("b", Comment)],
+            vec![("c */", Comment), ("2", Number), ("// d", Comment)],
+        ]
+    );
+}
+
+#[test]
+fn toml() {
+    let text = Text::from("[package]\nname = \"x\" # y\n[[bin]]\nok = true");
+    assert_eq!(
+        tokenize(TomlTokenizer, &text),
+        [
+            vec![("[package]", Typename)],
+            vec![("name", Identifier), ("=", Punctuator), ("\"x\"", String), ("# y", Comment)],
+            vec![("[[bin]]", Typename)],
+            vec![("ok", Identifier), ("=", Punctuator), ("true", OtherKeyword)],
+        ]
+    );
+}
+
+#[test]
+fn toml_array_spans_lines() {
+    // the `[` at the start of the second line opens an array, not a table
+    let text = Text::from("a = [\n[1],\n]\n[t]");
+    assert_eq!(
+        tokenize(TomlTokenizer, &text),
+        [
+            vec![("a", Identifier), ("=", Punctuator), ("[", Delimiter)],
+            vec![("[", Delimiter), ("1", Number), ("]", Delimiter), (",", Punctuator)],
+            vec![("]", Delimiter)],
+            vec![("[t]", Typename)],
+        ]
+    );
+}
+
+#[test]
+fn toml_multiline_string_spans_lines() {
+    let text = Text::from("s = \"\"\"a\n# b\nc\"\"\" # d");
+    assert_eq!(
+        tokenize(TomlTokenizer, &text),
+        [
+            vec![("s", Identifier), ("=", Punctuator), ("\"\"\"a", String)],
+            vec![("# b", String)],
+            vec![("c\"\"\"", String), ("# d", Comment)],
+        ]
+    );
+}
+
+#[test]
+fn markdown() {
+    let text = Text::from("# Title\n- *see* [docs](http://x)");
+    assert_eq!(
+        tokenize(MarkdownTokenizer, &text),
+        [
+            vec![("# Title", Typename)],
+            vec![
+                ("-", Punctuator),
+                ("*", Punctuator),
+                ("see", Identifier),
+                ("*", Punctuator),
+                ("[", Delimiter),
+                ("docs", Identifier),
+                ("]", Delimiter),
+                ("(http://x)", Constant),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn markdown_code_block_spans_lines() {
+    let text = Text::from("```rust\n# not a heading\n```\n# heading");
+    assert_eq!(
+        tokenize(MarkdownTokenizer, &text),
+        [
+            vec![("```rust", Delimiter)],
+            vec![("# not a heading", String)],
+            vec![("```", Delimiter)],
+            vec![("# heading", Typename)],
+        ]
+    );
+}
+
+#[test]
+fn markdown_comment_spans_lines() {
+    let text = Text::from("<!-- a\nb -->c");
+    assert_eq!(
+        tokenize(MarkdownTokenizer, &text),
+        [
+            vec![("<!-- a", Comment)],
+            vec![("b -->", Comment), ("c", Identifier)],
+        ]
+    );
+}
+
+#[test]
+fn shader() {
+    let text = Text::from("fn pixel(self) -> vec4 {\nfor i in 0..4 { return #f0f; }");
+    assert_eq!(
+        tokenize(ShaderTokenizer, &text),
+        [
+            vec![
+                ("fn", OtherKeyword),
+                ("pixel", Function),
+                ("(", Delimiter),
+                ("self", OtherKeyword),
+                (")", Delimiter),
+                ("->", Punctuator),
+                ("vec4", Typename),
+                ("{", Delimiter),
+            ],
+            vec![
+                ("for", LoopKeyword),
+                ("i", Identifier),
+                ("in", OtherKeyword),
+                ("0", Number),
+                ("..", Punctuator),
+                ("4", Number),
+                ("{", Delimiter),
+                ("return", BranchKeyword),
+                ("#f0f", Number),
+                (";", Punctuator),
+                ("}", Delimiter),
+            ],
+        ]
+    );
+}
+
+#[test]
+fn shader_block_comment_spans_lines() {
+    let text = Text::from("/* a\nb */ DrawQuad");
+    assert_eq!(
+        tokenize(ShaderTokenizer, &text),
+        [
+            vec![("/* a", Comment)],
+            vec![("b */", Comment), ("DrawQuad", Typename)],
+        ]
+    );
+}
+
+#[test]
+fn changes_carry_over_to_the_next_lines() {
+    let mut text = Text::from("1\n2\n3");
+    let mut tokens = vec![Vec::new(); 3];
+    let mut tokenizer = IncrementalTokenizer::new(JsonTokenizer, 3);
+    tokenizer.update(&text, &mut tokens);
+    assert_eq!(kinds(&text, &tokens)[2], [("3", Number)]);
+
+    // opening a comment on the first line turns the lines below it into comment
+    let change = Change::Insert(Position { line_index: 0, byte_index: 1 }, Text::from(" /*"));
+    tokenizer.apply_change(&change);
+    text.apply_change(change);
+    tokenizer.update(&text, &mut tokens);
+    assert_eq!(
+        kinds(&text, &tokens),
+        [vec![("1", Number), ("/*", Comment)], vec![("2", Comment)], vec![("3", Comment)]]
+    );
+
+    // and inserting a line that closes it again turns them back
+    let change = Change::Insert(Position { line_index: 0, byte_index: 4 }, Text::from("\n*/"));
+    tokenizer.apply_change(&change);
+    text.apply_change(change);
+    let mut tokens = {
+        let mut new_tokens = vec![Vec::new(); 4];
+        new_tokens[0] = tokens[0].clone();
+        new_tokens[2..].clone_from_slice(&tokens[1..]);
+        new_tokens
+    };
+    tokenizer.update(&text, &mut tokens);
+    assert_eq!(
+        kinds(&text, &tokens),
+        [
+            vec![("1", Number), ("/*", Comment)],
+            vec![("*/", Comment)],
+            vec![("2", Number)],
+            vec![("3", Number)],
+        ]
+    );
+}
+
+/// A tokenizer that never moves the cursor.
+#[derive(Clone, Debug)]
+struct StuckTokenizer;
+
+impl LanguageTokenizer for StuckTokenizer {
+    type State = ();
+
+    fn next_token(&self, _state: (), _cursor: &mut Cursor<'_>) -> ((), TokenKind) {
+        ((), Identifier)
+    }
+}
+
+#[test]
+fn stuck_tokenizer_makes_progress() {
+    let text = Text::from("aé");
+    assert_eq!(tokenize(StuckTokenizer, &text), [vec![("a", Unknown), ("é", Unknown)]]);
+}
+
+#[test]
+fn rust_tokenizer_is_still_available() {
+    let text = Text::from("fn main() {}");
+    let mut tokens = vec![Vec::new(); 1];
+    let mut tokenizer = Tokenizer::new(1);
+    tokenizer.update(&text, &mut tokens);
+    assert_eq!(kinds(&text, &tokens)[0][0], ("fn", OtherKeyword));
+}
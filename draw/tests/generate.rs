@@ -0,0 +1,174 @@
+// Generates WGSL, GLSL, HLSL and Metal for the stock draw shaders and compares
+// them with the snapshots in tests/snapshots. The WGSL is also validated with
+// naga. Set MAKEPAD_UPDATE_SNAPSHOTS=1 to rewrite the snapshots after a
+// generator change.
+use {
+    std::path::Path,
+    makepad_draw::*,
+    makepad_draw::makepad_platform::makepad_shader_compiler::{generate_glsl, generate_hlsl, generate_metal, generate_wgsl},
+};
+
+live_design!{
+    use link::shaders::*;
+
+    // not a stock shader, it exercises the parts of the generator the stock
+    // shaders leave alone: inout methods, select, polyfills and swizzle assignment
+    pub DrawWgslFixture = {{DrawWgslFixture}} {
+        uniform radius: float
+
+        fn shift(inout v: vec2, by: float) {
+            v.xy += vec2(by);
+        }
+
+        fn pixel(self) -> vec4 {
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            sdf.box(1., 1., self.rect_size.x - 2., self.rect_size.y - 2., self.radius);
+            sdf.fill_keep(mix(#f00, self.color, step(0.5, self.pos.x)));
+            sdf.stroke(#fff, 1.);
+            let p = self.pos;
+            shift(p, mod(p.y, 0.25));
+            let a = 0.0;
+            let b = 0.0;
+            a = b = max(p.x, 0.5);
+            let c = max(sdf.result.xyz, 0.1);
+            c.xy *= p;
+            let alpha = p.x > 0.5 ? a : b;
+            return vec4(c * alpha, sdf.result.w);
+        }
+    }
+
+    // calls a method with more args than it has params
+    pub DrawBadCall = {{DrawBadCall}} {
+        fn scale(self, v: float) -> float {
+            return v * 2.0;
+        }
+
+        fn pixel(self) -> vec4 {
+            return vec4(self.scale(1.0, 2.0));
+        }
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawWgslFixture {
+    #[deref] pub draw_super: DrawQuad,
+    #[live] pub color: Vec4,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawBadCall {
+    #[deref] pub draw_super: DrawQuad,
+}
+
+/// The source of a shader on each backend
+struct Generated {
+    wgsl: String,
+    glsl: String,
+    hlsl: String,
+    metal: String,
+}
+
+fn generate<T: LiveNew + LiveApply>(register: fn(&mut Cx), draw_vars: fn(&T) -> &DrawVars) -> Generated {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    T::register_main_module(&mut cx);
+    register(&mut cx);
+    cx.live_expand();
+    let draw = T::new_main(&mut cx);
+    let draw_shader = draw_vars(&draw).draw_shader.expect("draw shader did not compile");
+    let def = cx.shader_registry.draw_shader_defs.get(&draw_shader.draw_shader_ptr).unwrap();
+    let const_table = &cx.draw_shaders.shaders[draw_shader.draw_shader_id].mapping.const_table;
+    let registry = &cx.shader_registry;
+    Generated {
+        wgsl: generate_wgsl::generate_shader(def, const_table, registry).wgsl,
+        glsl: format!(
+            "// vertex\n{}\n// pixel\n{}",
+            generate_glsl::generate_vertex_shader(def, const_table, registry),
+            generate_glsl::generate_pixel_shader(def, const_table, registry)
+        ),
+        hlsl: generate_hlsl::generate_shader(def, const_table, registry),
+        metal: generate_metal::generate_shader(def, const_table, registry).mtlsl,
+    }
+}
+
+fn check_snapshot(name: &str, source: &str, errors: &mut Vec<String>) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots").join(name);
+    if std::env::var("MAKEPAD_UPDATE_SNAPSHOTS").is_ok() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        return
+    }
+    match std::fs::read_to_string(&path) {
+        Ok(snapshot) if snapshot == source => (),
+        Ok(_) => errors.push(format!("{}: output differs from {}", name, path.display())),
+        Err(_) => errors.push(format!("{}: missing snapshot {}", name, path.display())),
+    }
+}
+
+fn check(name: &str, generated: &Generated, errors: &mut Vec<String>) {
+    if let Err(err) = validate(&generated.wgsl) {
+        errors.push(format!("{}: invalid wgsl: {}", name, err));
+    }
+    check_snapshot(&format!("{}.wgsl", name), &generated.wgsl, errors);
+    check_snapshot(&format!("{}.glsl", name), &generated.glsl, errors);
+    check_snapshot(&format!("{}.hlsl", name), &generated.hlsl, errors);
+    check_snapshot(&format!("{}.metal", name), &generated.metal, errors);
+}
+
+#[test]
+fn stock_shaders() {
+    let mut errors = Vec::new();
+    macro_rules! check_shader {
+        ($name: literal, $ty: ty) => {
+            check($name, &generate::<$ty>(makepad_draw::live_design, | d | &d.draw_vars), &mut errors)
+        }
+    }
+    check_shader!("draw_quad", DrawQuad);
+    check_shader!("draw_color", DrawColor);
+    check_shader!("draw_icon", DrawIcon);
+    check_shader!("draw_text", DrawText);
+    check_shader!("draw_text_2", DrawText2);
+    check_shader!("draw_line", DrawLine);
+    check_shader!("draw_trapezoid_vector", makepad_draw::shader::draw_trapezoid::DrawTrapezoidVector);
+    assert!(errors.is_empty(), "{}\nrun with MAKEPAD_UPDATE_SNAPSHOTS=1 to update the snapshots", errors.join("\n"));
+}
+
+#[test]
+fn fixture_shader() {
+    let generated = generate::<DrawWgslFixture>( | cx | {
+        makepad_draw::live_design(cx);
+        live_design(cx);
+    }, | d | &d.draw_vars);
+    let mut errors = Vec::new();
+    check("fixture", &generated, &mut errors);
+    assert!(errors.is_empty(), "{}\nrun with MAKEPAD_UPDATE_SNAPSHOTS=1 to update the snapshots", errors.join("\n"));
+    // the inout param is passed by pointer and the ternary became a select
+    assert!(generated.wgsl.contains("ptr<function, vec2<f32>>"));
+    assert!(generated.wgsl.contains("select("));
+    // while the c-like backends keep them
+    assert!(generated.glsl.contains("inout vec2"));
+    assert!(generated.hlsl.contains(" ? "));
+}
+
+#[test]
+fn bad_call_is_a_compile_error() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    DrawBadCall::register_main_module(&mut cx);
+    makepad_draw::live_design(&mut cx);
+    live_design(&mut cx);
+    cx.live_expand();
+    // the type checker rejects the call, so it never reaches the generators
+    let draw = DrawBadCall::new_main(&mut cx);
+    assert!(draw.draw_vars.draw_shader.is_none());
+    assert_eq!(cx.draw_shaders.error_set.len(), 1);
+}
+
+/// Parses and validates the module with naga, the WGSL front end of wgpu.
+fn validate(wgsl: &str) -> Result<(), String> {
+    let module = naga::front::wgsl::parse_str(wgsl).map_err( | err | err.emit_to_string(wgsl))?;
+    naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+        .validate(&module)
+        .map_err( | err | err.emit_to_string(wgsl))?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+// Draws quads on the headless software renderer and checks the pixels of the frame.
+// Run with `cargo test -p makepad-draw --features headless --test headless`.
+use {
+    std::{rc::Rc, cell::RefCell},
+    makepad_draw::*,
+    makepad_draw::makepad_platform::os::linux::headless::software_renderer::SoftwareFrame,
+};
+
+live_design!{
+    use link::shaders::*;
+
+    pub DrawGradient = {{DrawGradient}} {
+        fn pixel(self) -> vec4 {
+            return mix(#f00, #00f, step(0.5, self.pos.x));
+        }
+    }
+
+    pub DrawDerivative = {{DrawDerivative}} {
+        // the width of a pixel in rect space, as seen by dFdx
+        fn pixel(self) -> vec4 {
+            return vec4(dFdx(self.pos.x) * 16.0, 0.0, 0.0, 1.0);
+        }
+    }
+
+    pub DrawTinted = {{DrawTinted}} {
+        fn pixel(self) -> vec4 {
+            return vec4(self.color.rgb * self.color.a, self.color.a);
+        }
+    }
+
+    pub DrawBroken = {{DrawBroken}} {
+        // undefined behaviour on a gpu, an error on the interpreter
+        fn pixel(self) -> vec4 {
+            if self.pos.x < 0.5 {
+                return #0f0;
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawGradient {
+    #[deref] draw_super: DrawQuad,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawTinted {
+    #[deref] draw_super: DrawQuad,
+    #[live] color: Vec4,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawDerivative {
+    #[deref] draw_super: DrawQuad,
+}
+
+#[derive(Live, LiveHook, LiveRegister)]
+#[repr(C)]
+pub struct DrawBroken {
+    #[deref] draw_super: DrawQuad,
+}
+
+struct App {
+    window: WindowHandle,
+    pass: Pass,
+    draw_list: DrawList2d,
+    gradient: DrawGradient,
+    tinted: DrawTinted,
+    derivative: DrawDerivative,
+    broken: DrawBroken,
+}
+
+fn rect(x: f64, y: f64, w: f64, h: f64) -> Rect {
+    Rect {pos: dvec2(x, y), size: dvec2(w, h)}
+}
+
+/// Runs the event loop until it is idle and returns what the window shows
+fn render() -> SoftwareFrame {
+    let app: Rc<RefCell<Option<App >>> = Rc::new(RefCell::new(None));
+    let draw_app = app.clone();
+    let cx = Rc::new(RefCell::new(Cx::new(Box::new(move | cx, event | {
+        if let Event::Draw(draw_event) = event {
+            let mut app = draw_app.borrow_mut();
+            let app = app.as_mut().unwrap();
+            let cx = &mut Cx2d::new(cx, draw_event);
+            cx.begin_pass(&app.pass, None);
+            app.draw_list.begin_always(cx);
+            cx.begin_pass_sized_turtle(Layout::default());
+            app.gradient.draw_abs(cx, rect(0.0, 0.0, 32.0, 16.0));
+            app.tinted.draw_abs(cx, rect(0.0, 16.0, 32.0, 16.0));
+            app.derivative.draw_abs(cx, rect(0.0, 32.0, 16.0, 16.0));
+            app.broken.draw_abs(cx, rect(32.0, 0.0, 32.0, 32.0));
+            cx.end_pass_sized_turtle();
+            app.draw_list.end(cx);
+            cx.end_pass(&app.pass);
+        }
+    }))));
+    {
+        let cx = &mut *cx.borrow_mut();
+        DrawGradient::register_main_module(cx);
+        makepad_draw::live_design(cx);
+        live_design(cx);
+        cx.live_expand();
+        let window = WindowHandle::new(cx);
+        cx.windows[window.window_id()].create_inner_size = Some(dvec2(64.0, 64.0));
+        let pass = Pass::new(cx);
+        pass.set_window_clear_color(cx, vec4(0.0, 0.0, 0.0, 1.0));
+        window.set_pass(cx, &pass);
+        let mut tinted = DrawTinted::new_local(cx);
+        tinted.color = vec4(1.0, 1.0, 0.0, 0.5);
+        *app.borrow_mut() = Some(App {
+            window,
+            pass,
+            draw_list: DrawList2d::new(cx),
+            gradient: DrawGradient::new_local(cx),
+            tinted,
+            derivative: DrawDerivative::new_local(cx),
+            broken: DrawBroken::new_local(cx),
+        });
+    }
+    Cx::event_loop(cx.clone());
+    let window_id = app.borrow().as_ref().unwrap().window.window_id();
+    let cx = cx.borrow();
+    cx.headless_window_frame(window_id).expect("window was not painted").clone()
+}
+
+fn assert_pixel(frame: &SoftwareFrame, x: usize, y: usize, expected: Vec4) {
+    let pixel = frame.pixel(x, y);
+    let d = pixel - expected;
+    assert!(d.x.abs().max(d.y.abs()).max(d.z.abs()).max(d.w.abs()) < 0.01, "pixel {},{} is {:?}, expected {:?}", x, y, pixel, expected);
+}
+
+#[test]
+fn runs_the_pixel_shaders() {
+    let frame = render();
+    assert_eq!((frame.width, frame.height), (64, 64));
+    // the gradient is red on the left and blue on the right half of its rect
+    assert_pixel(&frame, 4, 8, vec4(1.0, 0.0, 0.0, 1.0));
+    assert_pixel(&frame, 28, 8, vec4(0.0, 0.0, 1.0, 1.0));
+    // the color comes from the instance and is blended over the clear color
+    assert_pixel(&frame, 16, 24, vec4(0.5, 0.5, 0.0, 1.0));
+    // pos.x goes from 0 to 1 over 16 pixels
+    assert_pixel(&frame, 8, 40, vec4(1.0, 0.0, 0.0, 1.0));
+    // outside of the quads the clear color shows
+    assert_pixel(&frame, 40, 56, vec4(0.0, 0.0, 0.0, 1.0));
+}
+
+#[test]
+fn draws_a_placeholder_where_a_shader_fails() {
+    let frame = render();
+    // the left half of the broken shader returns a color
+    assert_pixel(&frame, 36, 4, vec4(0.0, 1.0, 0.0, 1.0));
+    // and the right half, where it doesn't, is the checkerboard
+    assert_pixel(&frame, 52, 4, vec4(1.0, 0.0, 1.0, 1.0));
+    assert_pixel(&frame, 52, 12, vec4(0.0, 0.0, 0.0, 1.0));
+}
@@ -0,0 +1,56 @@
+cbuffer LiveUniforms : register(b0) {
+};
+cbuffer ConstTable : register(b1){float4 const_table[1];};
+cbuffer Uniforms_view : register(b4) {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+cbuffer Uniforms_pass : register(b3) {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+cbuffer Uniforms_draw : register(b2) {
+    float ds_draw_zbias;
+};
+struct Geometries {
+    float2 ds_geom_pos: GEOMA;
+};
+struct Instances {
+    float2 ds_rect_pos: INSTA;
+    float2 ds_rect_size: INSTB;
+    float4 ds_draw_clip: INSTC;
+    float ds_draw_depth: INSTD;
+    float ds_0: INSTE;
+    float4 ds_color: INSTF;
+};
+struct Varyings {
+    float4 position: SV_POSITION;
+    float4 ds_color: VARYA;
+    float2 ds_pos: VARYB;
+};
+float4 consfn_vec4_vec3_float(float3 x0, float x1) {    return float4(x0[0], x0[1], x0[2], x1);}
+float4 fn_2_12_pixel(inout Varyings varyings) {
+    return consfn_vec4_vec3_float((varyings.ds_color.rgb * varyings.ds_color.a), varyings.ds_color.a);
+}
+float4 fn_2_9_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return mul(ds_camera_projection, mul(ds_camera_view, mul(ds_view_transform, float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+}
+float4 fn_2_11_vertex(in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    return fn_2_9_clip_and_transform_vertex (instances.ds_rect_pos, instances.ds_rect_size, geometries, instances, varyings);
+}
+Varyings vertex_main(Geometries geometries, Instances instances, uint inst_id: SV_InstanceID) {
+    Varyings varyings = {float4(0.0,0.0,0.0,0.0), float4(0.0,0.0,0.0,0.0), float2(0.0,0.0)};
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_2_11_vertex(geometries, instances, varyings);
+    return varyings;
+}
+float4 pixel_main(Varyings varyings) : SV_TARGET{
+    return     fn_2_12_pixel(varyings);
+}
@@ -0,0 +1,107 @@
+@group(0) @binding(0) var<uniform> const_table: array<vec4<f32>, 1>;
+@group(0) @binding(2) var<uniform> view_table: array<vec4<f32>, 6>;
+@group(0) @binding(3) var<uniform> pass_table: array<vec4<f32>, 13>;
+@group(0) @binding(4) var<uniform> draw_table: array<vec4<f32>, 1>;
+var<private> ds_camera_projection: mat4x4<f32>;
+var<private> ds_camera_view: mat4x4<f32>;
+var<private> ds_camera_inv: mat4x4<f32>;
+var<private> ds_dpi_factor: f32;
+var<private> ds_dpi_dilate: f32;
+var<private> ds_time: f32;
+var<private> ds_view_transform: mat4x4<f32>;
+var<private> ds_view_clip: vec4<f32>;
+var<private> ds_view_shift: vec2<f32>;
+var<private> ds_draw_zbias: f32;
+var<private> ds_rect_pos: vec2<f32>;
+var<private> ds_rect_size: vec2<f32>;
+var<private> ds_draw_clip: vec4<f32>;
+var<private> ds_draw_depth: f32;
+var<private> ds_color: vec4<f32>;
+var<private> ds_geom_pos: vec2<f32>;
+var<private> ds_pos: vec2<f32>;
+struct VertexInput {
+    @location(0) packed_geometry_0: vec2<f32>,
+    @location(1) packed_instance_0: vec4<f32>,
+    @location(2) packed_instance_1: vec4<f32>,
+    @location(3) packed_instance_2: vec4<f32>,
+    @location(4) packed_instance_3: vec2<f32>,
+}
+struct Varyings {
+    @builtin(position) position: vec4<f32>,
+    @location(0) packed_varying_0: vec4<f32>,
+    @location(1) packed_varying_1: vec2<f32>,
+}
+fn fn_2_12_pixel() -> vec4<f32> {
+    return vec4<f32>((ds_color.rgb * ds_color.a), ds_color.a);
+}
+fn fn_2_9_clip_and_transform_vertex(var_rect_pos_0: vec2<f32>, var_rect_size_0: vec2<f32>) -> vec4<f32> {
+    {
+        var var_rect_pos_0: vec2<f32> = var_rect_pos_0;
+        var var_rect_size_0: vec2<f32> = var_rect_size_0;
+        {
+                    var var_clipped_0: vec2<f32> = clamp((clamp(((ds_geom_pos * var_rect_size_0) + var_rect_pos_0), ds_draw_clip.xy, ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw);
+            ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0);
+            return (ds_camera_projection * (ds_camera_view * (ds_view_transform * vec4<f32>(var_clipped_0.x, var_clipped_0.y, (ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+        }
+    }
+}
+fn fn_2_11_vertex() -> vec4<f32> {
+    return fn_2_9_clip_and_transform_vertex (ds_rect_pos, ds_rect_size);
+}
+@vertex
+fn vertex_main(input: VertexInput) -> Varyings {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_geom_pos.x = input.packed_geometry_0.x;
+    ds_geom_pos.y = input.packed_geometry_0.y;
+    ds_rect_pos.x = input.packed_instance_0.x;
+    ds_rect_pos.y = input.packed_instance_0.y;
+    ds_rect_size.x = input.packed_instance_0.z;
+    ds_rect_size.y = input.packed_instance_0.w;
+    ds_draw_clip.x = input.packed_instance_1.x;
+    ds_draw_clip.y = input.packed_instance_1.y;
+    ds_draw_clip.z = input.packed_instance_1.z;
+    ds_draw_clip.w = input.packed_instance_1.w;
+    ds_draw_depth = input.packed_instance_2.x;
+    ds_color.x = input.packed_instance_2.z;
+    ds_color.y = input.packed_instance_2.w;
+    ds_color.z = input.packed_instance_3.x;
+    ds_color.w = input.packed_instance_3.y;
+    var varyings: Varyings;
+    varyings.position = fn_2_11_vertex();
+    varyings.packed_varying_0.x = ds_color.x;
+    varyings.packed_varying_0.y = ds_color.y;
+    varyings.packed_varying_0.z = ds_color.z;
+    varyings.packed_varying_0.w = ds_color.w;
+    varyings.packed_varying_1.x = ds_pos.x;
+    varyings.packed_varying_1.y = ds_pos.y;
+    return varyings;
+}
+@fragment
+fn pixel_main(varyings: Varyings) -> @location(0) vec4<f32> {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_color.x = varyings.packed_varying_0.x;
+    ds_color.y = varyings.packed_varying_0.y;
+    ds_color.z = varyings.packed_varying_0.z;
+    ds_color.w = varyings.packed_varying_0.w;
+    ds_pos.x = varyings.packed_varying_1.x;
+    ds_pos.y = varyings.packed_varying_1.y;
+    return fn_2_12_pixel();
+}
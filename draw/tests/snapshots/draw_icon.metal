@@ -0,0 +1,96 @@
+#include <metal_stdlib>
+using namespace metal;
+float4 sample2d_rt(texture2d<float> tex, float2 pos){return tex.sample(sampler(mag_filter::linear,min_filter::linear),pos);}
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Uniforms_user {
+    float ds_u_brightness;
+    float ds_u_curve;
+};
+struct Textures {
+    texture2d<float> ds_tex [[texture(0)]];};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float2 ds_rect_pos;
+    packed_float2 ds_rect_size;
+    packed_float4 ds_draw_clip;
+    float ds_draw_depth;
+    packed_float4 ds_color;
+    packed_float2 ds_icon_t1;
+    packed_float2 ds_icon_t2;
+};
+struct Varyings {
+    float4 position [[position]];
+    float4 ds_color;
+    float2 ds_pos;
+    float2 ds_tex_coord1;
+    float2 ds_clipped;
+};
+float4 fn_3_17_get_color(constant const float *const_table, thread Varyings &varyings) {
+    return varyings.ds_color;
+}
+float4 fn_3_18_pixel(constant const float *const_table, thread Varyings &varyings, Textures textures, constant Uniforms_user &uniforms_user) {
+    float var_dx_0 = dfdx(float2((varyings.ds_tex_coord1.x * const_table[1]), const_table[2])).x;
+    float var_dp_0 = (const_table[3] / const_table[4]);
+    float var_s_0 = sample2d_rt(textures.ds_tex, varyings.ds_tex_coord1.xy).x;
+    (var_s_0 = pow(var_s_0, uniforms_user.ds_u_curve));
+    float4 var_col_0 = fn_3_17_get_color (const_table, varyings);
+    return float4((((var_s_0 * var_col_0.rgb) * uniforms_user.ds_u_brightness) * var_col_0.a), (var_s_0 * var_col_0.a));
+}
+float4 fn_3_15_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + uniforms_view.ds_view_shift), uniforms_view.ds_view_clip.xy, uniforms_view.ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    (varyings.ds_tex_coord1 = mix(instances.ds_icon_t1.xy, instances.ds_icon_t2.xy, varyings.ds_pos.xy));
+    return (uniforms_pass.ds_camera_projection * float4((uniforms_pass.ds_camera_view * float4((uniforms_view.ds_view_transform * float4(float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + uniforms_draw.ds_draw_zbias), const_table[0])))))));
+}
+float4 fn_3_16_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    return fn_3_15_clip_and_transform_vertex (instances.ds_rect_pos, instances.ds_rect_size, const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_3_16_vertex(const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+) {
+    return     fn_3_18_pixel(const_table, varyings, textures, uniforms_user);
+}
@@ -0,0 +1,169 @@
+// vertex
+uniform float const_table[8];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+
+attribute vec2 packed_geometry_0;
+
+attribute vec4 packed_instance_0;
+attribute vec4 packed_instance_1;
+attribute vec4 packed_instance_2;
+attribute vec4 packed_instance_3;
+attribute vec3 packed_instance_4;
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec4 packed_varying_2;
+varying float packed_varying_3;
+
+vec2 ds_rect_pos=vec2(0.0);
+vec2 ds_rect_size=vec2(0.0);
+vec4 ds_draw_clip=vec4(0.0);
+float ds_draw_depth=0.0;
+float ds_0=0.0;
+vec2 ds_line_start=vec2(0.0);
+vec2 ds_line_end=vec2(0.0);
+float ds_half_line_width=0.0;
+vec4 ds_color=vec4(0.0);
+vec2 ds_geom_pos=vec2(0.0);
+vec2 ds_pos=vec2(0.0);
+
+vec4 fn_6_9_clip_and_transform_vertex(vec2 var_rect_pos_0, vec2 var_rect_size_0) {
+    vec2 var_clipped_0 = clamp((clamp(((ds_geom_pos * var_rect_size_0) + var_rect_pos_0), ds_draw_clip.xy, ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw);
+    (ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return (ds_camera_projection * (ds_camera_view * (ds_view_transform * vec4(var_clipped_0.x, var_clipped_0.y, (ds_draw_depth + ds_draw_zbias), const_table[0]))));
+}
+
+vec4 fn_6_11_vertex() {
+    return fn_6_9_clip_and_transform_vertex (ds_rect_pos, ds_rect_size);
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_geom_pos.xy = packed_geometry_0.xy;
+    ds_rect_pos.xy = packed_instance_0.xy;
+    ds_rect_size.xy = packed_instance_0.zw;
+    ds_draw_clip.xyzw = packed_instance_1.xyzw;
+    ds_draw_depth = packed_instance_2.x;
+    ds_0 = packed_instance_2.y;
+    ds_line_start.xy = packed_instance_2.zw;
+    ds_line_end.xy = packed_instance_3.xy;
+    ds_half_line_width = packed_instance_3.z;
+    ds_color.x = packed_instance_3.w;
+    ds_color.yzw = packed_instance_4.xyz;
+
+    gl_Position = fn_6_11_vertex();
+
+    packed_varying_0.xy = ds_rect_size.xy;
+    packed_varying_0.zw = ds_line_start.xy;
+    packed_varying_1.xy = ds_line_end.xy;
+    packed_varying_1.z = ds_half_line_width;
+    packed_varying_1.w = ds_color.x;
+    packed_varying_2.xyz = ds_color.yzw;
+    packed_varying_2.w = ds_pos.x;
+    packed_varying_3 = ds_pos.y;
+}
+
+// pixel
+uniform float const_table[8];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+
+
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec4 packed_varying_2;
+varying float packed_varying_3;
+
+vec2 ds_rect_size=vec2(0.0);
+vec2 ds_line_start=vec2(0.0);
+vec2 ds_line_end=vec2(0.0);
+float ds_half_line_width=0.0;
+vec4 ds_color=vec4(0.0);
+vec2 ds_pos=vec2(0.0);
+
+vec4 fn_6_13_stroke(float var_side_0, float var_progress_0) {
+    return ds_color;
+}
+
+vec4 fn_6_12_pixel() {
+    vec2 var_p_0 = (ds_pos * ds_rect_size);
+    vec2 var_b_0 = ds_line_end;
+    vec2 var_a_0 = ds_line_start;
+    vec2 var_ba_0 = (var_b_0 - var_a_0);
+    vec2 var_pa_0 = (var_p_0 - var_a_0);
+    float var_h_0 = clamp((dot(var_pa_0, var_ba_0) / dot(var_ba_0, var_ba_0)), const_table[1], const_table[2]);
+    float var_dist_0 = length((var_pa_0 - (var_h_0 * var_ba_0)));
+    float var_linemult_0 = smoothstep((ds_half_line_width - const_table[3]), ds_half_line_width, var_dist_0);
+    vec4 var_C_0 = fn_6_13_stroke (var_dist_0, var_h_0);
+    return vec4((var_C_0.xyz * (const_table[4] - var_linemult_0)), ((const_table[5] - var_linemult_0) * var_C_0.a));
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_rect_size.xy = packed_varying_0.xy;
+    ds_line_start.xy = packed_varying_0.zw;
+    ds_line_end.xy = packed_varying_1.xy;
+    ds_half_line_width = packed_varying_1.z;
+    ds_color.x = packed_varying_1.w;
+    ds_color.yzw = packed_varying_2.xyz;
+    ds_pos.x = packed_varying_2.w;
+    ds_pos.y = packed_varying_3;
+
+    gl_FragColor = fn_6_12_pixel();
+}
@@ -0,0 +1,79 @@
+cbuffer LiveUniforms : register(b0) {
+};
+cbuffer ConstTable : register(b1){float4 const_table[2];};
+cbuffer Uniforms_view : register(b4) {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+cbuffer Uniforms_pass : register(b3) {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+cbuffer Uniforms_draw : register(b2) {
+    float ds_draw_zbias;
+};
+struct Geometries {
+    float2 ds_geom_pos: GEOMA;
+};
+struct Instances {
+    float2 ds_rect_pos: INSTA;
+    float2 ds_rect_size: INSTB;
+    float4 ds_draw_clip: INSTC;
+    float ds_draw_depth: INSTD;
+    float ds_0: INSTE;
+    float2 ds_line_start: INSTF;
+    float2 ds_line_end: INSTG;
+    float ds_half_line_width: INSTH;
+    float4 ds_color: INSTI;
+};
+struct Varyings {
+    float4 position: SV_POSITION;
+    float2 ds_rect_size: VARYA;
+    float2 ds_line_start: VARYB;
+    float2 ds_line_end: VARYC;
+    float ds_half_line_width: VARYD;
+    float4 ds_color: VARYE;
+    float2 ds_pos: VARYF;
+};
+float4 consfn_vec4_vec3_float(float3 x0, float x1) {    return float4(x0[0], x0[1], x0[2], x1);}
+float4 fn_6_13_stroke(float var_side_0, float var_progress_0, inout Varyings varyings) {
+    return varyings.ds_color;
+}
+float4 fn_6_12_pixel(inout Varyings varyings) {
+    float2 var_p_0 = (varyings.ds_pos * varyings.ds_rect_size);
+    float2 var_b_0 = varyings.ds_line_end;
+    float2 var_a_0 = varyings.ds_line_start;
+    float2 var_ba_0 = (var_b_0 - var_a_0);
+    float2 var_pa_0 = (var_p_0 - var_a_0);
+    float var_h_0 = clamp((dot(var_pa_0, var_ba_0) / dot(var_ba_0, var_ba_0)), const_table[0].y, const_table[0].z);
+    float var_dist_0 = length((var_pa_0 - (var_h_0 * var_ba_0)));
+    float var_linemult_0 = smoothstep((varyings.ds_half_line_width - const_table[0].w), varyings.ds_half_line_width, var_dist_0);
+    float4 var_C_0 = fn_6_13_stroke (var_dist_0, var_h_0, varyings);
+    return consfn_vec4_vec3_float((var_C_0.xyz * (const_table[1].x - var_linemult_0)), ((const_table[1].y - var_linemult_0) * var_C_0.a));
+}
+float4 fn_6_9_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return mul(ds_camera_projection, mul(ds_camera_view, mul(ds_view_transform, float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+}
+float4 fn_6_11_vertex(in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    return fn_6_9_clip_and_transform_vertex (instances.ds_rect_pos, varyings.ds_rect_size, geometries, instances, varyings);
+}
+Varyings vertex_main(Geometries geometries, Instances instances, uint inst_id: SV_InstanceID) {
+    Varyings varyings = {float4(0.0,0.0,0.0,0.0), float2(0.0,0.0), float2(0.0,0.0), float2(0.0,0.0), 0.0, float4(0.0,0.0,0.0,0.0), float2(0.0,0.0)};
+    varyings.ds_rect_size = instances.ds_rect_size;
+    varyings.ds_line_start = instances.ds_line_start;
+    varyings.ds_line_end = instances.ds_line_end;
+    varyings.ds_half_line_width = instances.ds_half_line_width;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_6_11_vertex(geometries, instances, varyings);
+    return varyings;
+}
+float4 pixel_main(Varyings varyings) : SV_TARGET{
+    return     fn_6_12_pixel(varyings);
+}
@@ -0,0 +1,100 @@
+#include <metal_stdlib>
+using namespace metal;
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Textures {
+};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float2 ds_rect_pos;
+    packed_float2 ds_rect_size;
+    packed_float4 ds_draw_clip;
+    float ds_draw_depth;
+    float pad_0;
+    packed_float2 ds_line_start;
+    packed_float2 ds_line_end;
+    float ds_half_line_width;
+    packed_float4 ds_color;
+};
+struct Varyings {
+    float4 position [[position]];
+    float2 ds_rect_size;
+    float2 ds_line_start;
+    float2 ds_line_end;
+    float ds_half_line_width;
+    float4 ds_color;
+    float2 ds_pos;
+};
+float4 fn_6_13_stroke(float var_side_0, float var_progress_0, constant const float *const_table, thread Varyings &varyings) {
+    return varyings.ds_color;
+}
+float4 fn_6_12_pixel(constant const float *const_table, thread Varyings &varyings) {
+    float2 var_p_0 = (varyings.ds_pos * varyings.ds_rect_size);
+    float2 var_b_0 = varyings.ds_line_end;
+    float2 var_a_0 = varyings.ds_line_start;
+    float2 var_ba_0 = (var_b_0 - var_a_0);
+    float2 var_pa_0 = (var_p_0 - var_a_0);
+    float var_h_0 = clamp((dot(var_pa_0, var_ba_0) / dot(var_ba_0, var_ba_0)), const_table[1], const_table[2]);
+    float var_dist_0 = length((var_pa_0 - (var_h_0 * var_ba_0)));
+    float var_linemult_0 = smoothstep((varyings.ds_half_line_width - const_table[3]), varyings.ds_half_line_width, var_dist_0);
+    float4 var_C_0 = fn_6_13_stroke (var_dist_0, var_h_0, const_table, varyings);
+    return float4((var_C_0.xyz * (const_table[4] - var_linemult_0)), ((const_table[5] - var_linemult_0) * var_C_0.a));
+}
+float4 fn_6_9_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + uniforms_view.ds_view_shift), uniforms_view.ds_view_clip.xy, uniforms_view.ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return (uniforms_pass.ds_camera_projection * float4((uniforms_pass.ds_camera_view * float4((uniforms_view.ds_view_transform * float4(float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + uniforms_draw.ds_draw_zbias), const_table[0])))))));
+}
+float4 fn_6_11_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    return fn_6_9_clip_and_transform_vertex (instances.ds_rect_pos, varyings.ds_rect_size, const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_rect_size = instances.ds_rect_size;
+    varyings.ds_line_start = instances.ds_line_start;
+    varyings.ds_line_end = instances.ds_line_end;
+    varyings.ds_half_line_width = instances.ds_half_line_width;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_6_11_vertex(const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+) {
+    return     fn_6_12_pixel(const_table, varyings);
+}
@@ -0,0 +1,181 @@
+// vertex
+uniform float const_table[16];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+uniform sampler2D ds_tex;
+
+attribute vec2 packed_geometry_0;
+
+attribute vec4 packed_instance_0;
+attribute vec4 packed_instance_1;
+attribute vec4 packed_instance_2;
+attribute vec4 packed_instance_3;
+attribute float packed_instance_4;
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec4 packed_varying_2;
+varying vec2 packed_varying_3;
+
+vec4 ds_color=vec4(0.0);
+vec2 ds_font_t1=vec2(0.0);
+vec2 ds_font_t2=vec2(0.0);
+vec2 ds_rect_pos=vec2(0.0);
+vec2 ds_rect_size=vec2(0.0);
+vec4 ds_draw_clip=vec4(0.0);
+float ds_char_depth=0.0;
+vec2 ds_geom_pos=vec2(0.0);
+vec2 ds_tex_coord1=vec2(0.0);
+vec2 ds_tex_coord2=vec2(0.0);
+vec2 ds_tex_coord3=vec2(0.0);
+vec2 ds_clipped=vec2(0.0);
+vec2 ds_pos=vec2(0.0);
+
+vec4 fn_4_15_vertex() {
+    vec2 var_min_pos_0 = vec2(ds_rect_pos.x, ds_rect_pos.y);
+    vec2 var_max_pos_0 = vec2((ds_rect_pos.x + ds_rect_size.x), (ds_rect_pos.y - ds_rect_size.y));
+    (ds_clipped = clamp((clamp(mix(var_min_pos_0, var_max_pos_0, ds_geom_pos), ds_draw_clip.xy, ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw));
+    vec2 var_normalized_0 = ((ds_clipped - var_min_pos_0) / vec2(ds_rect_size.x, -ds_rect_size.y));
+    (ds_tex_coord1 = mix(vec2(ds_font_t1.x, (const_table[0] - ds_font_t1.y)), vec2(ds_font_t2.x, (const_table[1] - ds_font_t2.y)), var_normalized_0.xy));
+    (ds_pos = var_normalized_0);
+    return (ds_camera_projection * (ds_camera_view * (ds_view_transform * vec4(ds_clipped.x, ds_clipped.y, (ds_char_depth + ds_draw_zbias), const_table[2]))));
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_geom_pos.xy = packed_geometry_0.xy;
+    ds_color.xyzw = packed_instance_0.xyzw;
+    ds_font_t1.xy = packed_instance_1.xy;
+    ds_font_t2.xy = packed_instance_1.zw;
+    ds_rect_pos.xy = packed_instance_2.xy;
+    ds_rect_size.xy = packed_instance_2.zw;
+    ds_draw_clip.xyzw = packed_instance_3.xyzw;
+    ds_char_depth = packed_instance_4;
+
+    gl_Position = fn_4_15_vertex();
+
+    packed_varying_0.xyzw = ds_color.xyzw;
+    packed_varying_1.xy = ds_tex_coord1.xy;
+    packed_varying_1.zw = ds_tex_coord2.xy;
+    packed_varying_2.xy = ds_tex_coord3.xy;
+    packed_varying_2.zw = ds_clipped.xy;
+    packed_varying_3.xy = ds_pos.xy;
+}
+
+// pixel
+uniform float const_table[16];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+uniform sampler2D ds_tex;
+
+
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec4 packed_varying_2;
+varying vec2 packed_varying_3;
+
+vec4 ds_color=vec4(0.0);
+vec2 ds_tex_coord1=vec2(0.0);
+vec2 ds_tex_coord2=vec2(0.0);
+vec2 ds_tex_coord3=vec2(0.0);
+vec2 ds_clipped=vec2(0.0);
+vec2 ds_pos=vec2(0.0);
+
+float fn_4_18_get_brightness() {
+    return const_table[13];
+}
+
+vec4 fn_4_17_blend_color(vec4 var_incol_0) {
+    return var_incol_0;
+}
+
+vec4 fn_4_16_get_color() {
+    return ds_color;
+}
+
+vec4 fn_4_19_sample_color(float var_scale_0, vec2 var_pos_0) {
+    float var_brightness_0 = fn_4_18_get_brightness ();
+    float var_sdf_radius_0 = const_table[5];
+    float var_sdf_cutoff_0 = const_table[6];
+    float var_s_0 = sample2d(ds_tex, var_pos_0).x;
+    float var_curve_0 = const_table[7];
+    vec2 var_texel_coords_0 = (var_pos_0.xy * const_table[8]);
+    (var_s_0 = clamp(((((var_s_0 - (const_table[9] - var_sdf_cutoff_0)) * var_sdf_radius_0) / var_scale_0) + const_table[10]), const_table[11], const_table[12]));
+    vec4 var_col_0 = fn_4_16_get_color ();
+    return fn_4_17_blend_color (vec4((((var_s_0 * var_col_0.rgb) * var_brightness_0) * var_col_0.a), (var_s_0 * var_col_0.a)));
+}
+
+vec4 fn_4_20_pixel() {
+    vec2 var_texel_coords_0 = ds_tex_coord1.xy;
+    float var_dxt_0 = length(dFdx(var_texel_coords_0));
+    float var_dyt_0 = length(dFdy(var_texel_coords_0));
+    float var_scale_0 = (((var_dxt_0 + var_dyt_0) * const_table[3]) * const_table[4]);
+    return fn_4_19_sample_color (var_scale_0, ds_tex_coord1.xy);
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_color.xyzw = packed_varying_0.xyzw;
+    ds_tex_coord1.xy = packed_varying_1.xy;
+    ds_tex_coord2.xy = packed_varying_1.zw;
+    ds_tex_coord3.xy = packed_varying_2.xy;
+    ds_clipped.xy = packed_varying_2.zw;
+    ds_pos.xy = packed_varying_3.xy;
+
+    gl_FragColor = fn_4_20_pixel();
+}
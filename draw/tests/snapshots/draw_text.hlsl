@@ -0,0 +1,88 @@
+SamplerState default_texture_sampler{Filter=MIN_MAX_MIP_LINEAR;AddressU = Wrap;AddressV=Wrap;};
+float4 sample2d(Texture2D tex, float2 pos){return tex.Sample(default_texture_sampler,pos);}
+cbuffer LiveUniforms : register(b0) {
+};
+cbuffer ConstTable : register(b1){float4 const_table[4];};
+cbuffer Uniforms_view : register(b4) {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+cbuffer Uniforms_pass : register(b3) {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+cbuffer Uniforms_draw : register(b2) {
+    float ds_draw_zbias;
+};
+Texture2D ds_tex: register(t0);struct Geometries {
+    float2 ds_geom_pos: GEOMA;
+};
+struct Instances {
+    float4 ds_color: INSTA;
+    float2 ds_font_t1: INSTB;
+    float2 ds_font_t2: INSTC;
+    float2 ds_rect_pos: INSTD;
+    float2 ds_rect_size: INSTE;
+    float4 ds_draw_clip: INSTF;
+    float ds_char_depth: INSTG;
+};
+struct Varyings {
+    float4 position: SV_POSITION;
+    float4 ds_color: VARYA;
+    float2 ds_tex_coord1: VARYB;
+    float2 ds_tex_coord2: VARYC;
+    float2 ds_tex_coord3: VARYD;
+    float2 ds_clipped: VARYE;
+    float2 ds_pos: VARYF;
+};
+float4 consfn_vec4_vec3_float(float3 x0, float x1) {    return float4(x0[0], x0[1], x0[2], x1);}
+float fn_4_18_get_brightness() {
+    return const_table[3].y;
+}
+float4 fn_4_17_blend_color(float4 var_incol_0) {
+    return var_incol_0;
+}
+float4 fn_4_16_get_color(inout Varyings varyings) {
+    return varyings.ds_color;
+}
+float4 fn_4_19_sample_color(float var_scale_0, float2 var_pos_0, inout Varyings varyings) {
+    float var_brightness_0 = fn_4_18_get_brightness ();
+    float var_sdf_radius_0 = const_table[1].y;
+    float var_sdf_cutoff_0 = const_table[1].z;
+    float var_s_0 = sample2d(ds_tex, var_pos_0).x;
+    float var_curve_0 = const_table[1].w;
+    float2 var_texel_coords_0 = (var_pos_0.xy * const_table[2].x);
+    (var_s_0 = clamp(((((var_s_0 - (const_table[2].y - var_sdf_cutoff_0)) * var_sdf_radius_0) / var_scale_0) + const_table[2].z), const_table[2].w, const_table[3].x));
+    float4 var_col_0 = fn_4_16_get_color (varyings);
+    return fn_4_17_blend_color (consfn_vec4_vec3_float((((var_s_0 * var_col_0.rgb) * var_brightness_0) * var_col_0.a), (var_s_0 * var_col_0.a)));
+}
+float4 fn_4_20_pixel(inout Varyings varyings) {
+    float2 var_texel_coords_0 = varyings.ds_tex_coord1.xy;
+    float var_dxt_0 = length(ddx(var_texel_coords_0));
+    float var_dyt_0 = length(ddy(var_texel_coords_0));
+    float var_scale_0 = (((var_dxt_0 + var_dyt_0) * const_table[0].w) * const_table[1].x);
+    return fn_4_19_sample_color (var_scale_0, varyings.ds_tex_coord1.xy, varyings);
+}
+float4 fn_4_15_vertex(in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    float2 var_min_pos_0 = float2(instances.ds_rect_pos.x, instances.ds_rect_pos.y);
+    float2 var_max_pos_0 = float2((instances.ds_rect_pos.x + instances.ds_rect_size.x), (instances.ds_rect_pos.y - instances.ds_rect_size.y));
+    (varyings.ds_clipped = clamp((clamp(lerp(var_min_pos_0, var_max_pos_0, geometries.ds_geom_pos), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw));
+    float2 var_normalized_0 = ((varyings.ds_clipped - var_min_pos_0) / float2(instances.ds_rect_size.x, -instances.ds_rect_size.y));
+    (varyings.ds_tex_coord1 = lerp(float2(instances.ds_font_t1.x, (const_table[0].x - instances.ds_font_t1.y)), float2(instances.ds_font_t2.x, (const_table[0].y - instances.ds_font_t2.y)), var_normalized_0.xy));
+    (varyings.ds_pos = var_normalized_0);
+    return mul(ds_camera_projection, mul(ds_camera_view, mul(ds_view_transform, float4(varyings.ds_clipped.x, varyings.ds_clipped.y, (instances.ds_char_depth + ds_draw_zbias), const_table[0].z))));
+}
+Varyings vertex_main(Geometries geometries, Instances instances, uint inst_id: SV_InstanceID) {
+    Varyings varyings = {float4(0.0,0.0,0.0,0.0), float4(0.0,0.0,0.0,0.0), float2(0.0,0.0), float2(0.0,0.0), float2(0.0,0.0), float2(0.0,0.0), float2(0.0,0.0)};
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_4_15_vertex(geometries, instances, varyings);
+    return varyings;
+}
+float4 pixel_main(Varyings varyings) : SV_TARGET{
+    return     fn_4_20_pixel(varyings);
+}
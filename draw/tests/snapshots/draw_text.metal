@@ -0,0 +1,108 @@
+#include <metal_stdlib>
+using namespace metal;
+float4 sample2d(texture2d<float> tex, float2 pos){return tex.sample(sampler(mag_filter::linear,min_filter::linear),pos);}
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Textures {
+    texture2d<float> ds_tex [[texture(0)]];};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float4 ds_color;
+    packed_float2 ds_font_t1;
+    packed_float2 ds_font_t2;
+    packed_float2 ds_rect_pos;
+    packed_float2 ds_rect_size;
+    packed_float4 ds_draw_clip;
+    float ds_char_depth;
+};
+struct Varyings {
+    float4 position [[position]];
+    float4 ds_color;
+    float2 ds_tex_coord1;
+    float2 ds_tex_coord2;
+    float2 ds_tex_coord3;
+    float2 ds_clipped;
+    float2 ds_pos;
+};
+float fn_4_18_get_brightness(constant const float *const_table) {
+    return const_table[13];
+}
+float4 fn_4_17_blend_color(float4 var_incol_0, constant const float *const_table) {
+    return var_incol_0;
+}
+float4 fn_4_16_get_color(constant const float *const_table, thread Varyings &varyings) {
+    return varyings.ds_color;
+}
+float4 fn_4_19_sample_color(float var_scale_0, float2 var_pos_0, constant const float *const_table, thread Varyings &varyings, Textures textures) {
+    float var_brightness_0 = fn_4_18_get_brightness (const_table);
+    float var_sdf_radius_0 = const_table[5];
+    float var_sdf_cutoff_0 = const_table[6];
+    float var_s_0 = sample2d(textures.ds_tex, var_pos_0).x;
+    float var_curve_0 = const_table[7];
+    float2 var_texel_coords_0 = (var_pos_0.xy * const_table[8]);
+    (var_s_0 = clamp(((((var_s_0 - (const_table[9] - var_sdf_cutoff_0)) * var_sdf_radius_0) / var_scale_0) + const_table[10]), const_table[11], const_table[12]));
+    float4 var_col_0 = fn_4_16_get_color (const_table, varyings);
+    return fn_4_17_blend_color (float4((((var_s_0 * var_col_0.rgb) * var_brightness_0) * var_col_0.a), (var_s_0 * var_col_0.a)), const_table);
+}
+float4 fn_4_20_pixel(constant const float *const_table, thread Varyings &varyings, Textures textures) {
+    float2 var_texel_coords_0 = varyings.ds_tex_coord1.xy;
+    float var_dxt_0 = length(dfdx(var_texel_coords_0));
+    float var_dyt_0 = length(dfdy(var_texel_coords_0));
+    float var_scale_0 = (((var_dxt_0 + var_dyt_0) * const_table[3]) * const_table[4]);
+    return fn_4_19_sample_color (var_scale_0, varyings.ds_tex_coord1.xy, const_table, varyings, textures);
+}
+float4 fn_4_15_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    float2 var_min_pos_0 = float2(instances.ds_rect_pos.x, instances.ds_rect_pos.y);
+    float2 var_max_pos_0 = float2((instances.ds_rect_pos.x + instances.ds_rect_size.x), (instances.ds_rect_pos.y - instances.ds_rect_size.y));
+    (varyings.ds_clipped = clamp((clamp(mix(var_min_pos_0, var_max_pos_0, geometries.ds_geom_pos), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + uniforms_view.ds_view_shift), uniforms_view.ds_view_clip.xy, uniforms_view.ds_view_clip.zw));
+    float2 var_normalized_0 = ((varyings.ds_clipped - var_min_pos_0) / float2(instances.ds_rect_size.x, -instances.ds_rect_size.y));
+    (varyings.ds_tex_coord1 = mix(float2(instances.ds_font_t1.x, (const_table[0] - instances.ds_font_t1.y)), float2(instances.ds_font_t2.x, (const_table[1] - instances.ds_font_t2.y)), var_normalized_0.xy));
+    (varyings.ds_pos = var_normalized_0);
+    return (uniforms_pass.ds_camera_projection * float4((uniforms_pass.ds_camera_view * float4((uniforms_view.ds_view_transform * float4(float4(varyings.ds_clipped.x, varyings.ds_clipped.y, (instances.ds_char_depth + uniforms_draw.ds_draw_zbias), const_table[2])))))));
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_4_15_vertex(const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+) {
+    return     fn_4_20_pixel(const_table, varyings, textures);
+}
@@ -0,0 +1,83 @@
+SamplerState default_texture_sampler{Filter=MIN_MAX_MIP_LINEAR;AddressU = Wrap;AddressV=Wrap;};
+float4 sample2d(Texture2D tex, float2 pos){return tex.Sample(default_texture_sampler,pos);}
+cbuffer LiveUniforms : register(b0) {
+};
+cbuffer ConstTable : register(b1){float4 const_table[2];};
+cbuffer Uniforms_view : register(b4) {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+cbuffer Uniforms_pass : register(b3) {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+cbuffer Uniforms_draw : register(b2) {
+    float ds_draw_zbias;
+};
+cbuffer Uniforms_user : register(b5) {
+    float ds_radius;
+    float ds_cutoff;
+    float2 ds_grayscale_atlas_size;
+    float2 ds_color_atlas_size;
+};
+Texture2D ds_grayscale_texture: register(t0);Texture2D ds_color_texture: register(t1);struct Geometries {
+    float2 ds_geom_pos: GEOMA;
+};
+struct Instances {
+    float2 ds_rect_pos: INSTA;
+    float2 ds_rect_size: INSTB;
+    float4 ds_draw_clip: INSTC;
+    float ds_draw_depth: INSTD;
+    float4 ds_draw_color: INSTE;
+    float ds_texture_index: INSTF;
+    float2 ds_t_min: INSTG;
+    float2 ds_t_max: INSTH;
+};
+struct Varyings {
+    float4 position: SV_POSITION;
+    float4 ds_draw_color: VARYA;
+    float ds_texture_index: VARYB;
+    float2 ds_t: VARYC;
+};
+float4 consfn_vec4_vec3_float(float3 x0, float x1) {    return float4(x0[0], x0[1], x0[2], x1);}
+float fn_5_17_sdf(float var_scale_0, float2 var_p_0) {
+    float var_s_0 = sample2d(ds_grayscale_texture, var_p_0).x;
+    (var_s_0 = clamp(((((var_s_0 - (const_table[0].w - ds_cutoff)) * ds_radius) / var_scale_0) + const_table[1].x), const_table[1].y, const_table[1].z));
+    return var_s_0;
+}
+float4 fn_5_18_pixel(inout Varyings varyings) {
+    float var_dxt_0 = length(ddx(varyings.ds_t));
+    float var_dyt_0 = length(ddy(varyings.ds_t));
+    if(varyings.ds_texture_index == const_table[0].y) {
+            float var_scale_0 = (((var_dxt_0 + var_dyt_0) * ds_grayscale_atlas_size.x) * const_table[0].z);
+        float var_s_0 = fn_5_17_sdf (var_scale_0, varyings.ds_t.xy);
+        float4 var_c_0 = varyings.ds_draw_color;
+        return (var_s_0 * var_c_0);
+    }else{
+            float4 var_c_0 = sample2d(ds_color_texture, varyings.ds_t);
+        return consfn_vec4_vec3_float((var_c_0.rgb * var_c_0.a), var_c_0.a);
+    }
+
+}
+float4 fn_5_16_vertex(in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    float2 var_p_0 = lerp(instances.ds_rect_pos, (instances.ds_rect_pos + instances.ds_rect_size), geometries.ds_geom_pos);
+    float2 var_p_clipped_0 = clamp(var_p_0, instances.ds_draw_clip.xy, instances.ds_draw_clip.zw);
+    float2 var_p_normalized_0 = ((var_p_clipped_0 - instances.ds_rect_pos) / instances.ds_rect_size);
+    (varyings.ds_t = lerp(instances.ds_t_min, instances.ds_t_max, var_p_normalized_0.xy));
+    return mul(ds_camera_projection, mul(ds_camera_view, mul(ds_view_transform, float4(var_p_clipped_0.x, var_p_clipped_0.y, (instances.ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+}
+Varyings vertex_main(Geometries geometries, Instances instances, uint inst_id: SV_InstanceID) {
+    Varyings varyings = {float4(0.0,0.0,0.0,0.0), float4(0.0,0.0,0.0,0.0), 0.0, float2(0.0,0.0)};
+    varyings.ds_draw_color = instances.ds_draw_color;
+    varyings.ds_texture_index = instances.ds_texture_index;
+    varyings.position = fn_5_16_vertex(geometries, instances, varyings);
+    return varyings;
+}
+float4 pixel_main(Varyings varyings) : SV_TARGET{
+    return     fn_5_18_pixel(varyings);
+}
@@ -0,0 +1,105 @@
+#include <metal_stdlib>
+using namespace metal;
+float4 sample2d(texture2d<float> tex, float2 pos){return tex.sample(sampler(mag_filter::linear,min_filter::linear),pos);}
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Uniforms_user {
+    float ds_radius;
+    float ds_cutoff;
+    float2 ds_grayscale_atlas_size;
+    float2 ds_color_atlas_size;
+};
+struct Textures {
+    texture2d<float> ds_grayscale_texture [[texture(0)]];    texture2d<float> ds_color_texture [[texture(1)]];};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float2 ds_rect_pos;
+    packed_float2 ds_rect_size;
+    packed_float4 ds_draw_clip;
+    float ds_draw_depth;
+    packed_float4 ds_draw_color;
+    float ds_texture_index;
+    packed_float2 ds_t_min;
+    packed_float2 ds_t_max;
+};
+struct Varyings {
+    float4 position [[position]];
+    float4 ds_draw_color;
+    float ds_texture_index;
+    float2 ds_t;
+};
+float fn_5_17_sdf(float var_scale_0, float2 var_p_0, constant const float *const_table, Textures textures, constant Uniforms_user &uniforms_user) {
+    float var_s_0 = sample2d(textures.ds_grayscale_texture, var_p_0).x;
+    (var_s_0 = clamp(((((var_s_0 - (const_table[3] - uniforms_user.ds_cutoff)) * uniforms_user.ds_radius) / var_scale_0) + const_table[4]), const_table[5], const_table[6]));
+    return var_s_0;
+}
+float4 fn_5_18_pixel(constant const float *const_table, thread Varyings &varyings, Textures textures, constant Uniforms_user &uniforms_user) {
+    float var_dxt_0 = length(dfdx(varyings.ds_t));
+    float var_dyt_0 = length(dfdy(varyings.ds_t));
+    if(varyings.ds_texture_index == const_table[1]) {
+            float var_scale_0 = (((var_dxt_0 + var_dyt_0) * uniforms_user.ds_grayscale_atlas_size.x) * const_table[2]);
+        float var_s_0 = fn_5_17_sdf (var_scale_0, varyings.ds_t.xy, const_table, textures, uniforms_user);
+        float4 var_c_0 = varyings.ds_draw_color;
+        return (var_s_0 * var_c_0);
+    }else{
+            float4 var_c_0 = sample2d(textures.ds_color_texture, varyings.ds_t);
+        return float4((var_c_0.rgb * var_c_0.a), var_c_0.a);
+    }
+
+}
+float4 fn_5_16_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    float2 var_p_0 = mix(instances.ds_rect_pos, (instances.ds_rect_pos + instances.ds_rect_size), geometries.ds_geom_pos);
+    float2 var_p_clipped_0 = clamp(var_p_0, instances.ds_draw_clip.xy, instances.ds_draw_clip.zw);
+    float2 var_p_normalized_0 = ((var_p_clipped_0 - instances.ds_rect_pos) / instances.ds_rect_size);
+    (varyings.ds_t = mix(instances.ds_t_min, instances.ds_t_max, var_p_normalized_0.xy));
+    return (uniforms_pass.ds_camera_projection * float4((uniforms_pass.ds_camera_view * float4((uniforms_view.ds_view_transform * float4(float4(var_p_clipped_0.x, var_p_clipped_0.y, (instances.ds_draw_depth + uniforms_draw.ds_draw_zbias), const_table[0])))))));
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_draw_color = instances.ds_draw_color;
+    varyings.ds_texture_index = instances.ds_texture_index;
+    varyings.position = fn_5_16_vertex(const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+) {
+    return     fn_5_18_pixel(const_table, varyings, textures, uniforms_user);
+}
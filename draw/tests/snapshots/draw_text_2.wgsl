@@ -0,0 +1,153 @@
+diagnostic(off, derivative_uniformity);
+@group(0) @binding(0) var<uniform> const_table: array<vec4<f32>, 2>;
+@group(0) @binding(2) var<uniform> view_table: array<vec4<f32>, 6>;
+@group(0) @binding(3) var<uniform> pass_table: array<vec4<f32>, 13>;
+@group(0) @binding(4) var<uniform> draw_table: array<vec4<f32>, 1>;
+@group(0) @binding(5) var<uniform> user_table: array<vec4<f32>, 2>;
+@group(1) @binding(0) var default_sampler: sampler;
+@group(1) @binding(1) var ds_grayscale_texture: texture_2d<f32>;
+@group(1) @binding(2) var ds_color_texture: texture_2d<f32>;
+var<private> ds_camera_projection: mat4x4<f32>;
+var<private> ds_camera_view: mat4x4<f32>;
+var<private> ds_camera_inv: mat4x4<f32>;
+var<private> ds_dpi_factor: f32;
+var<private> ds_dpi_dilate: f32;
+var<private> ds_time: f32;
+var<private> ds_view_transform: mat4x4<f32>;
+var<private> ds_view_clip: vec4<f32>;
+var<private> ds_view_shift: vec2<f32>;
+var<private> ds_draw_zbias: f32;
+var<private> ds_rect_pos: vec2<f32>;
+var<private> ds_rect_size: vec2<f32>;
+var<private> ds_draw_clip: vec4<f32>;
+var<private> ds_draw_depth: f32;
+var<private> ds_draw_color: vec4<f32>;
+var<private> ds_texture_index: f32;
+var<private> ds_t_min: vec2<f32>;
+var<private> ds_t_max: vec2<f32>;
+var<private> ds_geom_pos: vec2<f32>;
+var<private> ds_radius: f32;
+var<private> ds_cutoff: f32;
+var<private> ds_grayscale_atlas_size: vec2<f32>;
+var<private> ds_color_atlas_size: vec2<f32>;
+var<private> ds_t: vec2<f32>;
+struct VertexInput {
+    @location(0) packed_geometry_0: vec2<f32>,
+    @location(1) packed_instance_0: vec4<f32>,
+    @location(2) packed_instance_1: vec4<f32>,
+    @location(3) packed_instance_2: vec4<f32>,
+    @location(4) packed_instance_3: vec4<f32>,
+    @location(5) packed_instance_4: vec2<f32>,
+}
+struct Varyings {
+    @builtin(position) position: vec4<f32>,
+    @location(0) packed_varying_0: vec4<f32>,
+    @location(1) packed_varying_1: vec3<f32>,
+}
+fn fn_5_17_sdf(var_scale_0: f32, var_p_0: vec2<f32>) -> f32 {
+    {
+        var var_scale_0: f32 = var_scale_0;
+        var var_p_0: vec2<f32> = var_p_0;
+        {
+                    var var_s_0: f32 = sample2d_texture2D_vec2(ds_grayscale_texture, var_p_0).x;
+            var_s_0 = clamp(((((var_s_0 - (const_table[0].w - ds_cutoff)) * ds_radius) / var_scale_0) + const_table[1].x), const_table[1].y, const_table[1].z);
+            return var_s_0;
+        }
+    }
+}
+fn fn_5_18_pixel() -> vec4<f32> {
+    var var_dxt_0: f32 = length(dpdx(ds_t));
+    var var_dyt_0: f32 = length(dpdy(ds_t));
+    if(ds_texture_index == const_table[0].y) {
+            var var_scale_0: f32 = (((var_dxt_0 + var_dyt_0) * ds_grayscale_atlas_size.x) * const_table[0].z);
+        var var_s_0: f32 = fn_5_17_sdf (var_scale_0, ds_t.xy);
+        var var_c_0: vec4<f32> = ds_draw_color;
+        return (var_s_0 * var_c_0);
+    }else{
+            var var_c_0: vec4<f32> = sample2d_texture2D_vec2(ds_color_texture, ds_t);
+        return vec4<f32>((var_c_0.rgb * var_c_0.a), var_c_0.a);
+    }
+
+}
+fn fn_5_16_vertex() -> vec4<f32> {
+    var var_p_0: vec2<f32> = mix(ds_rect_pos, (ds_rect_pos + ds_rect_size), ds_geom_pos);
+    var var_p_clipped_0: vec2<f32> = clamp(var_p_0, ds_draw_clip.xy, ds_draw_clip.zw);
+    var var_p_normalized_0: vec2<f32> = ((var_p_clipped_0 - ds_rect_pos) / ds_rect_size);
+    ds_t = mix(ds_t_min, ds_t_max, var_p_normalized_0.xy);
+    return (ds_camera_projection * (ds_camera_view * (ds_view_transform * vec4<f32>(var_p_clipped_0.x, var_p_clipped_0.y, (ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+}
+@vertex
+fn vertex_main(input: VertexInput) -> Varyings {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_radius = user_table[0].x;
+    ds_cutoff = user_table[0].y;
+    ds_grayscale_atlas_size = vec2<f32>(user_table[0].z, user_table[0].w);
+    ds_color_atlas_size = vec2<f32>(user_table[1].x, user_table[1].y);
+    ds_geom_pos.x = input.packed_geometry_0.x;
+    ds_geom_pos.y = input.packed_geometry_0.y;
+    ds_rect_pos.x = input.packed_instance_0.x;
+    ds_rect_pos.y = input.packed_instance_0.y;
+    ds_rect_size.x = input.packed_instance_0.z;
+    ds_rect_size.y = input.packed_instance_0.w;
+    ds_draw_clip.x = input.packed_instance_1.x;
+    ds_draw_clip.y = input.packed_instance_1.y;
+    ds_draw_clip.z = input.packed_instance_1.z;
+    ds_draw_clip.w = input.packed_instance_1.w;
+    ds_draw_depth = input.packed_instance_2.x;
+    ds_draw_color.x = input.packed_instance_2.y;
+    ds_draw_color.y = input.packed_instance_2.z;
+    ds_draw_color.z = input.packed_instance_2.w;
+    ds_draw_color.w = input.packed_instance_3.x;
+    ds_texture_index = input.packed_instance_3.y;
+    ds_t_min.x = input.packed_instance_3.z;
+    ds_t_min.y = input.packed_instance_3.w;
+    ds_t_max.x = input.packed_instance_4.x;
+    ds_t_max.y = input.packed_instance_4.y;
+    var varyings: Varyings;
+    varyings.position = fn_5_16_vertex();
+    varyings.packed_varying_0.x = ds_draw_color.x;
+    varyings.packed_varying_0.y = ds_draw_color.y;
+    varyings.packed_varying_0.z = ds_draw_color.z;
+    varyings.packed_varying_0.w = ds_draw_color.w;
+    varyings.packed_varying_1.x = ds_texture_index;
+    varyings.packed_varying_1.y = ds_t.x;
+    varyings.packed_varying_1.z = ds_t.y;
+    return varyings;
+}
+@fragment
+fn pixel_main(varyings: Varyings) -> @location(0) vec4<f32> {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_radius = user_table[0].x;
+    ds_cutoff = user_table[0].y;
+    ds_grayscale_atlas_size = vec2<f32>(user_table[0].z, user_table[0].w);
+    ds_color_atlas_size = vec2<f32>(user_table[1].x, user_table[1].y);
+    ds_draw_color.x = varyings.packed_varying_0.x;
+    ds_draw_color.y = varyings.packed_varying_0.y;
+    ds_draw_color.z = varyings.packed_varying_0.z;
+    ds_draw_color.w = varyings.packed_varying_0.w;
+    ds_texture_index = varyings.packed_varying_1.x;
+    ds_t.x = varyings.packed_varying_1.y;
+    ds_t.y = varyings.packed_varying_1.z;
+    return fn_5_18_pixel();
+}
+fn sample2d_texture2D_vec2(a: texture_2d<f32>, b: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(a, default_sampler, b, 0.0);
+}
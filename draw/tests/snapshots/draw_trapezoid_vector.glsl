@@ -0,0 +1,211 @@
+// vertex
+uniform float const_table[24];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+
+attribute vec2 packed_geometry_0;
+
+attribute vec4 packed_instance_0;
+attribute vec3 packed_instance_1;
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec3 packed_varying_2;
+
+vec2 ds_a_xs=vec2(0.0);
+vec4 ds_a_ys=vec4(0.0);
+float ds_chan=0.0;
+vec2 ds_geom_pos=vec2(0.0);
+vec2 ds_v_p0=vec2(0.0);
+vec2 ds_v_p1=vec2(0.0);
+vec2 ds_v_p2=vec2(0.0);
+vec2 ds_v_p3=vec2(0.0);
+vec2 ds_v_pixel=vec2(0.0);
+
+vec4 fn_8_18_vertex() {
+    vec2 var_pos_min_0 = vec2(ds_a_xs.x, min(ds_a_ys.x, ds_a_ys.y));
+    vec2 var_pos_max_0 = vec2(ds_a_xs.y, max(ds_a_ys.z, ds_a_ys.w));
+    vec2 var_pos_0 = mix((var_pos_min_0 - const_table[0]), (var_pos_max_0 + const_table[1]), ds_geom_pos);
+    (ds_v_p0 = vec2(ds_a_xs.x, ds_a_ys.x));
+    (ds_v_p1 = vec2(ds_a_xs.y, ds_a_ys.y));
+    (ds_v_p2 = vec2(ds_a_xs.x, ds_a_ys.z));
+    (ds_v_p3 = vec2(ds_a_xs.y, ds_a_ys.w));
+    (ds_v_pixel = var_pos_0);
+    return (ds_camera_projection * vec4(var_pos_0, const_table[2], const_table[3]));
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_geom_pos.xy = packed_geometry_0.xy;
+    ds_a_xs.xy = packed_instance_0.xy;
+    ds_a_ys.xy = packed_instance_0.zw;
+    ds_a_ys.zw = packed_instance_1.xy;
+    ds_chan = packed_instance_1.z;
+
+    gl_Position = fn_8_18_vertex();
+
+    packed_varying_0.x = ds_chan;
+    packed_varying_0.yz = ds_v_p0.xy;
+    packed_varying_0.w = ds_v_p1.x;
+    packed_varying_1.x = ds_v_p1.y;
+    packed_varying_1.yz = ds_v_p2.xy;
+    packed_varying_1.w = ds_v_p3.x;
+    packed_varying_2.x = ds_v_p3.y;
+    packed_varying_2.yz = ds_v_pixel.xy;
+}
+
+// pixel
+uniform float const_table[24];
+
+uniform float view_table[22];
+mat4 ds_view_transform = mat4(0.0);
+vec4 ds_view_clip = vec4(0.0);
+vec2 ds_view_shift = vec2(0.0);
+
+uniform float pass_table[51];
+mat4 ds_camera_projection = mat4(0.0);
+mat4 ds_camera_view = mat4(0.0);
+mat4 ds_camera_inv = mat4(0.0);
+float ds_dpi_factor = 0.0;
+float ds_dpi_dilate = 0.0;
+float ds_time = 0.0;
+
+uniform float draw_table[1];
+float ds_draw_zbias = 0.0;
+
+
+
+
+varying vec4 packed_varying_0;
+varying vec4 packed_varying_1;
+varying vec3 packed_varying_2;
+
+float ds_chan=0.0;
+vec2 ds_v_p0=vec2(0.0);
+vec2 ds_v_p1=vec2(0.0);
+vec2 ds_v_p2=vec2(0.0);
+vec2 ds_v_p3=vec2(0.0);
+vec2 ds_v_pixel=vec2(0.0);
+
+vec2 fn_8_14_intersect_line_segment_with_horizontal_line(vec2 var_p0_0, vec2 var_p1_0, float var_y_0) {
+    return vec2(mix(var_p0_0.x, var_p1_0.x, ((var_y_0 - var_p0_0.y) / (var_p1_0.y - var_p0_0.y))), var_y_0);
+}
+
+vec2 fn_8_13_intersect_line_segment_with_vertical_line(vec2 var_p0_0, vec2 var_p1_0, float var_x_0) {
+    return vec2(var_x_0, mix(var_p0_0.y, var_p1_0.y, ((var_x_0 - var_p0_0.x) / (var_p1_0.x - var_p0_0.x))));
+}
+
+float fn_8_15_compute_clamped_right_trapezoid_area(vec2 var_p0_0, vec2 var_p1_0, vec2 var_p_min_0, vec2 var_p_max_0) {
+    float var_x0_0 = clamp(var_p0_0.x, var_p_min_0.x, var_p_max_0.x);
+    float var_x1_0 = clamp(var_p1_0.x, var_p_min_0.x, var_p_max_0.x);
+    if((var_p0_0.x < var_p_min_0.x) && (var_p_min_0.x < var_p1_0.x)) {
+            (var_p0_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_min_0.x));
+    }
+
+    if((var_p0_0.x < var_p_max_0.x) && (var_p_max_0.x < var_p1_0.x)) {
+            (var_p1_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_max_0.x));
+    }
+
+    if((var_p0_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p1_0.y)) {
+            (var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_min_0.y));
+    }
+
+    if((var_p1_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p0_0.y)) {
+            (var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_min_0.y));
+    }
+
+    if((var_p0_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p1_0.y)) {
+            (var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_max_0.y));
+    }
+
+    if((var_p1_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p0_0.y)) {
+            (var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_max_0.y));
+    }
+
+    (var_p0_0 = clamp(var_p0_0, var_p_min_0, var_p_max_0));
+    (var_p1_0 = clamp(var_p1_0, var_p_min_0, var_p_max_0));
+    float var_h0_0 = (var_p_max_0.y - var_p0_0.y);
+    float var_h1_0 = (var_p_max_0.y - var_p1_0.y);
+    float var_a0_0 = ((var_p0_0.x - var_x0_0) * var_h0_0);
+    float var_a1_0 = (((var_p1_0.x - var_p0_0.x) * (var_h0_0 + var_h1_0)) * const_table[19]);
+    float var_a2_0 = ((var_x1_0 - var_p1_0.x) * var_h1_0);
+    return ((var_a0_0 + var_a1_0) + var_a2_0);
+}
+
+float fn_8_16_compute_clamped_trapezoid_area(vec2 var_p_min_0, vec2 var_p_max_0) {
+    float var_a0_0 = fn_8_15_compute_clamped_right_trapezoid_area (ds_v_p0, ds_v_p1, var_p_min_0, var_p_max_0);
+    float var_a1_0 = fn_8_15_compute_clamped_right_trapezoid_area (ds_v_p2, ds_v_p3, var_p_min_0, var_p_max_0);
+    return (var_a0_0 - var_a1_0);
+}
+
+vec4 fn_8_17_pixel() {
+    vec2 var_p_min_0 = (ds_v_pixel.xy - const_table[4]);
+    vec2 var_p_max_0 = (ds_v_pixel.xy + const_table[5]);
+    float var_t_area_0 = fn_8_16_compute_clamped_trapezoid_area (var_p_min_0, var_p_max_0);
+    if(ds_chan < const_table[6]) {
+            return vec4(var_t_area_0, const_table[7], const_table[8], const_table[9]);
+    }
+
+    if(ds_chan < const_table[10]) {
+            return vec4(const_table[11], var_t_area_0, const_table[12], const_table[13]);
+    }
+
+    if(ds_chan < const_table[14]) {
+            return vec4(const_table[15], const_table[16], var_t_area_0, const_table[17]);
+    }
+
+    return vec4(var_t_area_0, var_t_area_0, var_t_area_0, const_table[18]);
+}
+
+void main() {
+    ds_view_transform = mat4(view_table[0], view_table[1], view_table[2], view_table[3], view_table[4], view_table[5], view_table[6], view_table[7], view_table[8], view_table[9], view_table[10], view_table[11], view_table[12], view_table[13], view_table[14], view_table[15]);
+    ds_view_clip = vec4(view_table[16], view_table[17], view_table[18], view_table[19]);
+    ds_view_shift = vec2(view_table[20], view_table[21]);
+
+    ds_camera_projection = mat4(pass_table[0], pass_table[1], pass_table[2], pass_table[3], pass_table[4], pass_table[5], pass_table[6], pass_table[7], pass_table[8], pass_table[9], pass_table[10], pass_table[11], pass_table[12], pass_table[13], pass_table[14], pass_table[15]);
+    ds_camera_view = mat4(pass_table[16], pass_table[17], pass_table[18], pass_table[19], pass_table[20], pass_table[21], pass_table[22], pass_table[23], pass_table[24], pass_table[25], pass_table[26], pass_table[27], pass_table[28], pass_table[29], pass_table[30], pass_table[31]);
+    ds_camera_inv = mat4(pass_table[32], pass_table[33], pass_table[34], pass_table[35], pass_table[36], pass_table[37], pass_table[38], pass_table[39], pass_table[40], pass_table[41], pass_table[42], pass_table[43], pass_table[44], pass_table[45], pass_table[46], pass_table[47]);
+    ds_dpi_factor = pass_table[48];
+    ds_dpi_dilate = pass_table[49];
+    ds_time = pass_table[50];
+
+    ds_draw_zbias = draw_table[0];
+
+    ds_chan = packed_varying_0.x;
+    ds_v_p0.xy = packed_varying_0.yz;
+    ds_v_p1.x = packed_varying_0.w;
+    ds_v_p1.y = packed_varying_1.x;
+    ds_v_p2.xy = packed_varying_1.yz;
+    ds_v_p3.x = packed_varying_1.w;
+    ds_v_p3.y = packed_varying_2.x;
+    ds_v_pixel.xy = packed_varying_2.yz;
+
+    gl_FragColor = fn_8_17_pixel();
+}
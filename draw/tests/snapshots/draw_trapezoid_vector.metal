@@ -0,0 +1,143 @@
+#include <metal_stdlib>
+using namespace metal;
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Textures {
+};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float2 ds_a_xs;
+    packed_float4 ds_a_ys;
+    float ds_chan;
+};
+struct Varyings {
+    float4 position [[position]];
+    float ds_chan;
+    float2 ds_v_p0;
+    float2 ds_v_p1;
+    float2 ds_v_p2;
+    float2 ds_v_p3;
+    float2 ds_v_pixel;
+};
+float2 fn_8_14_intersect_line_segment_with_horizontal_line(float2 var_p0_0, float2 var_p1_0, float var_y_0, constant const float *const_table) {
+    return float2(mix(var_p0_0.x, var_p1_0.x, ((var_y_0 - var_p0_0.y) / (var_p1_0.y - var_p0_0.y))), var_y_0);
+}
+float2 fn_8_13_intersect_line_segment_with_vertical_line(float2 var_p0_0, float2 var_p1_0, float var_x_0, constant const float *const_table) {
+    return float2(var_x_0, mix(var_p0_0.y, var_p1_0.y, ((var_x_0 - var_p0_0.x) / (var_p1_0.x - var_p0_0.x))));
+}
+float fn_8_15_compute_clamped_right_trapezoid_area(float2 var_p0_0, float2 var_p1_0, float2 var_p_min_0, float2 var_p_max_0, constant const float *const_table) {
+    float var_x0_0 = clamp(var_p0_0.x, var_p_min_0.x, var_p_max_0.x);
+    float var_x1_0 = clamp(var_p1_0.x, var_p_min_0.x, var_p_max_0.x);
+    if((var_p0_0.x < var_p_min_0.x) && (var_p_min_0.x < var_p1_0.x)) {
+            (var_p0_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_min_0.x, const_table));
+    }
+
+    if((var_p0_0.x < var_p_max_0.x) && (var_p_max_0.x < var_p1_0.x)) {
+            (var_p1_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_max_0.x, const_table));
+    }
+
+    if((var_p0_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p1_0.y)) {
+            (var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_min_0.y, const_table));
+    }
+
+    if((var_p1_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p0_0.y)) {
+            (var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_min_0.y, const_table));
+    }
+
+    if((var_p0_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p1_0.y)) {
+            (var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_max_0.y, const_table));
+    }
+
+    if((var_p1_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p0_0.y)) {
+            (var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_max_0.y, const_table));
+    }
+
+    (var_p0_0 = clamp(var_p0_0, var_p_min_0, var_p_max_0));
+    (var_p1_0 = clamp(var_p1_0, var_p_min_0, var_p_max_0));
+    float var_h0_0 = (var_p_max_0.y - var_p0_0.y);
+    float var_h1_0 = (var_p_max_0.y - var_p1_0.y);
+    float var_a0_0 = ((var_p0_0.x - var_x0_0) * var_h0_0);
+    float var_a1_0 = (((var_p1_0.x - var_p0_0.x) * (var_h0_0 + var_h1_0)) * const_table[19]);
+    float var_a2_0 = ((var_x1_0 - var_p1_0.x) * var_h1_0);
+    return ((var_a0_0 + var_a1_0) + var_a2_0);
+}
+float fn_8_16_compute_clamped_trapezoid_area(float2 var_p_min_0, float2 var_p_max_0, constant const float *const_table, thread Varyings &varyings) {
+    float var_a0_0 = fn_8_15_compute_clamped_right_trapezoid_area (varyings.ds_v_p0, varyings.ds_v_p1, var_p_min_0, var_p_max_0, const_table);
+    float var_a1_0 = fn_8_15_compute_clamped_right_trapezoid_area (varyings.ds_v_p2, varyings.ds_v_p3, var_p_min_0, var_p_max_0, const_table);
+    return (var_a0_0 - var_a1_0);
+}
+float4 fn_8_17_pixel(constant const float *const_table, thread Varyings &varyings) {
+    float2 var_p_min_0 = (varyings.ds_v_pixel.xy - const_table[4]);
+    float2 var_p_max_0 = (varyings.ds_v_pixel.xy + const_table[5]);
+    float var_t_area_0 = fn_8_16_compute_clamped_trapezoid_area (var_p_min_0, var_p_max_0, const_table, varyings);
+    if(varyings.ds_chan < const_table[6]) {
+            return float4(var_t_area_0, const_table[7], const_table[8], const_table[9]);
+    }
+
+    if(varyings.ds_chan < const_table[10]) {
+            return float4(const_table[11], var_t_area_0, const_table[12], const_table[13]);
+    }
+
+    if(varyings.ds_chan < const_table[14]) {
+            return float4(const_table[15], const_table[16], var_t_area_0, const_table[17]);
+    }
+
+    return float4(var_t_area_0, var_t_area_0, var_t_area_0, const_table[18]);
+}
+float4 fn_8_18_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_pass &uniforms_pass) {
+    float2 var_pos_min_0 = float2(instances.ds_a_xs.x, min(instances.ds_a_ys.x, instances.ds_a_ys.y));
+    float2 var_pos_max_0 = float2(instances.ds_a_xs.y, max(instances.ds_a_ys.z, instances.ds_a_ys.w));
+    float2 var_pos_0 = mix((var_pos_min_0 - const_table[0]), (var_pos_max_0 + const_table[1]), geometries.ds_geom_pos);
+    (varyings.ds_v_p0 = float2(instances.ds_a_xs.x, instances.ds_a_ys.x));
+    (varyings.ds_v_p1 = float2(instances.ds_a_xs.y, instances.ds_a_ys.y));
+    (varyings.ds_v_p2 = float2(instances.ds_a_xs.x, instances.ds_a_ys.z));
+    (varyings.ds_v_p3 = float2(instances.ds_a_xs.y, instances.ds_a_ys.w));
+    (varyings.ds_v_pixel = var_pos_0);
+    return (uniforms_pass.ds_camera_projection * float4(float4(var_pos_0, const_table[2], const_table[3])));
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_chan = instances.ds_chan;
+    varyings.position = fn_8_18_vertex(const_table, geometries, instances, varyings, uniforms_pass);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+) {
+    return     fn_8_17_pixel(const_table, varyings);
+}
@@ -0,0 +1,199 @@
+@group(0) @binding(0) var<uniform> const_table: array<vec4<f32>, 6>;
+@group(0) @binding(2) var<uniform> view_table: array<vec4<f32>, 6>;
+@group(0) @binding(3) var<uniform> pass_table: array<vec4<f32>, 13>;
+@group(0) @binding(4) var<uniform> draw_table: array<vec4<f32>, 1>;
+var<private> ds_camera_projection: mat4x4<f32>;
+var<private> ds_camera_view: mat4x4<f32>;
+var<private> ds_camera_inv: mat4x4<f32>;
+var<private> ds_dpi_factor: f32;
+var<private> ds_dpi_dilate: f32;
+var<private> ds_time: f32;
+var<private> ds_view_transform: mat4x4<f32>;
+var<private> ds_view_clip: vec4<f32>;
+var<private> ds_view_shift: vec2<f32>;
+var<private> ds_draw_zbias: f32;
+var<private> ds_a_xs: vec2<f32>;
+var<private> ds_a_ys: vec4<f32>;
+var<private> ds_chan: f32;
+var<private> ds_geom_pos: vec2<f32>;
+var<private> ds_v_p0: vec2<f32>;
+var<private> ds_v_p1: vec2<f32>;
+var<private> ds_v_p2: vec2<f32>;
+var<private> ds_v_p3: vec2<f32>;
+var<private> ds_v_pixel: vec2<f32>;
+struct VertexInput {
+    @location(0) packed_geometry_0: vec2<f32>,
+    @location(1) packed_instance_0: vec4<f32>,
+    @location(2) packed_instance_1: vec3<f32>,
+}
+struct Varyings {
+    @builtin(position) position: vec4<f32>,
+    @location(0) packed_varying_0: vec4<f32>,
+    @location(1) packed_varying_1: vec4<f32>,
+    @location(2) packed_varying_2: vec3<f32>,
+}
+fn fn_8_14_intersect_line_segment_with_horizontal_line(var_p0_0: vec2<f32>, var_p1_0: vec2<f32>, var_y_0: f32) -> vec2<f32> {
+    {
+        var var_p0_0: vec2<f32> = var_p0_0;
+        var var_p1_0: vec2<f32> = var_p1_0;
+        var var_y_0: f32 = var_y_0;
+        {
+                    return vec2<f32>(mix(var_p0_0.x, var_p1_0.x, ((var_y_0 - var_p0_0.y) / (var_p1_0.y - var_p0_0.y))), var_y_0);
+        }
+    }
+}
+fn fn_8_13_intersect_line_segment_with_vertical_line(var_p0_0: vec2<f32>, var_p1_0: vec2<f32>, var_x_0: f32) -> vec2<f32> {
+    {
+        var var_p0_0: vec2<f32> = var_p0_0;
+        var var_p1_0: vec2<f32> = var_p1_0;
+        var var_x_0: f32 = var_x_0;
+        {
+                    return vec2<f32>(var_x_0, mix(var_p0_0.y, var_p1_0.y, ((var_x_0 - var_p0_0.x) / (var_p1_0.x - var_p0_0.x))));
+        }
+    }
+}
+fn fn_8_15_compute_clamped_right_trapezoid_area(var_p0_0: vec2<f32>, var_p1_0: vec2<f32>, var_p_min_0: vec2<f32>, var_p_max_0: vec2<f32>) -> f32 {
+    {
+        var var_p0_0: vec2<f32> = var_p0_0;
+        var var_p1_0: vec2<f32> = var_p1_0;
+        var var_p_min_0: vec2<f32> = var_p_min_0;
+        var var_p_max_0: vec2<f32> = var_p_max_0;
+        {
+                    var var_x0_0: f32 = clamp(var_p0_0.x, var_p_min_0.x, var_p_max_0.x);
+            var var_x1_0: f32 = clamp(var_p1_0.x, var_p_min_0.x, var_p_max_0.x);
+            if((var_p0_0.x < var_p_min_0.x) && (var_p_min_0.x < var_p1_0.x)) {
+                            var_p0_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_min_0.x);
+            }
+
+            if((var_p0_0.x < var_p_max_0.x) && (var_p_max_0.x < var_p1_0.x)) {
+                            var_p1_0 = fn_8_13_intersect_line_segment_with_vertical_line (var_p0_0, var_p1_0, var_p_max_0.x);
+            }
+
+            if((var_p0_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p1_0.y)) {
+                            var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_min_0.y);
+            }
+
+            if((var_p1_0.y < var_p_min_0.y) && (var_p_min_0.y < var_p0_0.y)) {
+                            var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_min_0.y);
+            }
+
+            if((var_p0_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p1_0.y)) {
+                            var_p1_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p0_0, var_p1_0, var_p_max_0.y);
+            }
+
+            if((var_p1_0.y < var_p_max_0.y) && (var_p_max_0.y < var_p0_0.y)) {
+                            var_p0_0 = fn_8_14_intersect_line_segment_with_horizontal_line (var_p1_0, var_p0_0, var_p_max_0.y);
+            }
+
+            var_p0_0 = clamp(var_p0_0, var_p_min_0, var_p_max_0);
+            var_p1_0 = clamp(var_p1_0, var_p_min_0, var_p_max_0);
+            var var_h0_0: f32 = (var_p_max_0.y - var_p0_0.y);
+            var var_h1_0: f32 = (var_p_max_0.y - var_p1_0.y);
+            var var_a0_0: f32 = ((var_p0_0.x - var_x0_0) * var_h0_0);
+            var var_a1_0: f32 = (((var_p1_0.x - var_p0_0.x) * (var_h0_0 + var_h1_0)) * const_table[4].w);
+            var var_a2_0: f32 = ((var_x1_0 - var_p1_0.x) * var_h1_0);
+            return ((var_a0_0 + var_a1_0) + var_a2_0);
+        }
+    }
+}
+fn fn_8_16_compute_clamped_trapezoid_area(var_p_min_0: vec2<f32>, var_p_max_0: vec2<f32>) -> f32 {
+    {
+        var var_p_min_0: vec2<f32> = var_p_min_0;
+        var var_p_max_0: vec2<f32> = var_p_max_0;
+        {
+                    var var_a0_0: f32 = fn_8_15_compute_clamped_right_trapezoid_area (ds_v_p0, ds_v_p1, var_p_min_0, var_p_max_0);
+            var var_a1_0: f32 = fn_8_15_compute_clamped_right_trapezoid_area (ds_v_p2, ds_v_p3, var_p_min_0, var_p_max_0);
+            return (var_a0_0 - var_a1_0);
+        }
+    }
+}
+fn fn_8_17_pixel() -> vec4<f32> {
+    var var_p_min_0: vec2<f32> = (ds_v_pixel.xy - const_table[1].x);
+    var var_p_max_0: vec2<f32> = (ds_v_pixel.xy + const_table[1].y);
+    var var_t_area_0: f32 = fn_8_16_compute_clamped_trapezoid_area (var_p_min_0, var_p_max_0);
+    if(ds_chan < const_table[1].z) {
+            return vec4<f32>(var_t_area_0, const_table[1].w, const_table[2].x, const_table[2].y);
+    }
+
+    if(ds_chan < const_table[2].z) {
+            return vec4<f32>(const_table[2].w, var_t_area_0, const_table[3].x, const_table[3].y);
+    }
+
+    if(ds_chan < const_table[3].z) {
+            return vec4<f32>(const_table[3].w, const_table[4].x, var_t_area_0, const_table[4].y);
+    }
+
+    return vec4<f32>(var_t_area_0, var_t_area_0, var_t_area_0, const_table[4].z);
+}
+fn fn_8_18_vertex() -> vec4<f32> {
+    var var_pos_min_0: vec2<f32> = vec2<f32>(ds_a_xs.x, min(ds_a_ys.x, ds_a_ys.y));
+    var var_pos_max_0: vec2<f32> = vec2<f32>(ds_a_xs.y, max(ds_a_ys.z, ds_a_ys.w));
+    var var_pos_0: vec2<f32> = mix((var_pos_min_0 - const_table[0].x), (var_pos_max_0 + const_table[0].y), ds_geom_pos);
+    ds_v_p0 = vec2<f32>(ds_a_xs.x, ds_a_ys.x);
+    ds_v_p1 = vec2<f32>(ds_a_xs.y, ds_a_ys.y);
+    ds_v_p2 = vec2<f32>(ds_a_xs.x, ds_a_ys.z);
+    ds_v_p3 = vec2<f32>(ds_a_xs.y, ds_a_ys.w);
+    ds_v_pixel = var_pos_0;
+    return (ds_camera_projection * vec4<f32>(var_pos_0, const_table[0].z, const_table[0].w));
+}
+@vertex
+fn vertex_main(input: VertexInput) -> Varyings {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_geom_pos.x = input.packed_geometry_0.x;
+    ds_geom_pos.y = input.packed_geometry_0.y;
+    ds_a_xs.x = input.packed_instance_0.x;
+    ds_a_xs.y = input.packed_instance_0.y;
+    ds_a_ys.x = input.packed_instance_0.z;
+    ds_a_ys.y = input.packed_instance_0.w;
+    ds_a_ys.z = input.packed_instance_1.x;
+    ds_a_ys.w = input.packed_instance_1.y;
+    ds_chan = input.packed_instance_1.z;
+    var varyings: Varyings;
+    varyings.position = fn_8_18_vertex();
+    varyings.packed_varying_0.x = ds_chan;
+    varyings.packed_varying_0.y = ds_v_p0.x;
+    varyings.packed_varying_0.z = ds_v_p0.y;
+    varyings.packed_varying_0.w = ds_v_p1.x;
+    varyings.packed_varying_1.x = ds_v_p1.y;
+    varyings.packed_varying_1.y = ds_v_p2.x;
+    varyings.packed_varying_1.z = ds_v_p2.y;
+    varyings.packed_varying_1.w = ds_v_p3.x;
+    varyings.packed_varying_2.x = ds_v_p3.y;
+    varyings.packed_varying_2.y = ds_v_pixel.x;
+    varyings.packed_varying_2.z = ds_v_pixel.y;
+    return varyings;
+}
+@fragment
+fn pixel_main(varyings: Varyings) -> @location(0) vec4<f32> {
+    ds_view_transform = mat4x4<f32>(view_table[0].x, view_table[0].y, view_table[0].z, view_table[0].w, view_table[1].x, view_table[1].y, view_table[1].z, view_table[1].w, view_table[2].x, view_table[2].y, view_table[2].z, view_table[2].w, view_table[3].x, view_table[3].y, view_table[3].z, view_table[3].w);
+    ds_view_clip = vec4<f32>(view_table[4].x, view_table[4].y, view_table[4].z, view_table[4].w);
+    ds_view_shift = vec2<f32>(view_table[5].x, view_table[5].y);
+    ds_camera_projection = mat4x4<f32>(pass_table[0].x, pass_table[0].y, pass_table[0].z, pass_table[0].w, pass_table[1].x, pass_table[1].y, pass_table[1].z, pass_table[1].w, pass_table[2].x, pass_table[2].y, pass_table[2].z, pass_table[2].w, pass_table[3].x, pass_table[3].y, pass_table[3].z, pass_table[3].w);
+    ds_camera_view = mat4x4<f32>(pass_table[4].x, pass_table[4].y, pass_table[4].z, pass_table[4].w, pass_table[5].x, pass_table[5].y, pass_table[5].z, pass_table[5].w, pass_table[6].x, pass_table[6].y, pass_table[6].z, pass_table[6].w, pass_table[7].x, pass_table[7].y, pass_table[7].z, pass_table[7].w);
+    ds_camera_inv = mat4x4<f32>(pass_table[8].x, pass_table[8].y, pass_table[8].z, pass_table[8].w, pass_table[9].x, pass_table[9].y, pass_table[9].z, pass_table[9].w, pass_table[10].x, pass_table[10].y, pass_table[10].z, pass_table[10].w, pass_table[11].x, pass_table[11].y, pass_table[11].z, pass_table[11].w);
+    ds_dpi_factor = pass_table[12].x;
+    ds_dpi_dilate = pass_table[12].y;
+    ds_time = pass_table[12].z;
+    ds_draw_zbias = draw_table[0].x;
+    ds_chan = varyings.packed_varying_0.x;
+    ds_v_p0.x = varyings.packed_varying_0.y;
+    ds_v_p0.y = varyings.packed_varying_0.z;
+    ds_v_p1.x = varyings.packed_varying_0.w;
+    ds_v_p1.y = varyings.packed_varying_1.x;
+    ds_v_p2.x = varyings.packed_varying_1.y;
+    ds_v_p2.y = varyings.packed_varying_1.z;
+    ds_v_p3.x = varyings.packed_varying_1.w;
+    ds_v_p3.y = varyings.packed_varying_2.x;
+    ds_v_pixel.x = varyings.packed_varying_2.y;
+    ds_v_pixel.y = varyings.packed_varying_2.z;
+    return fn_8_17_pixel();
+}
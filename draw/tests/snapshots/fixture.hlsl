@@ -0,0 +1,159 @@
+struct struct_7_36 {
+    float2 f_pos;
+    float4 f_result;
+    float2 f_last_pos;
+    float2 f_start_pos;
+    float f_shape;
+    float f_clip;
+    float f_has_clip;
+    float f_old_shape;
+    float f_blur;
+    float f_aa;
+    float f_scale_factor;
+    float f_dist;
+};
+struct_7_36 consfn_struct_7_36(
+float2 f_pos, float4 f_result, float2 f_last_pos, float2 f_start_pos, float f_shape, float f_clip, float f_has_clip, float f_old_shape, float f_blur, float f_aa, float f_scale_factor, float f_dist){;
+struct_7_36 r;
+r.f_pos = f_pos;
+r.f_result = f_result;
+r.f_last_pos = f_last_pos;
+r.f_start_pos = f_start_pos;
+r.f_shape = f_shape;
+r.f_clip = f_clip;
+r.f_has_clip = f_has_clip;
+r.f_old_shape = f_old_shape;
+r.f_blur = f_blur;
+r.f_aa = f_aa;
+r.f_scale_factor = f_scale_factor;
+r.f_dist = f_dist;
+return r;
+}
+cbuffer LiveUniforms : register(b0) {
+};
+cbuffer ConstTable : register(b1){float4 const_table[14];};
+cbuffer Uniforms_view : register(b4) {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+cbuffer Uniforms_pass : register(b3) {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+cbuffer Uniforms_draw : register(b2) {
+    float ds_draw_zbias;
+};
+cbuffer Uniforms_user : register(b5) {
+    float ds_radius;
+};
+struct Geometries {
+    float2 ds_geom_pos: GEOMA;
+};
+struct Instances {
+    float2 ds_rect_pos: INSTA;
+    float2 ds_rect_size: INSTB;
+    float4 ds_draw_clip: INSTC;
+    float ds_draw_depth: INSTD;
+    float ds_0: INSTE;
+    float4 ds_color: INSTF;
+};
+struct Varyings {
+    float4 position: SV_POSITION;
+    float2 ds_rect_size: VARYA;
+    float4 ds_color: VARYB;
+    float2 ds_pos: VARYC;
+};
+float2 consfn_vec2_float(float x) {    return float2(x, x);}
+float4 consfn_vec4_float(float x) {    return float4(x, x, x, x);}
+float4 consfn_vec4_vec3_float(float3 x0, float x1) {    return float4(x0[0], x0[1], x0[2], x1);}
+void fn_9_14_shift(inout float2 var_v_0, float var_by_0) {
+    (var_v_0.xy += consfn_vec2_float(var_by_0));
+}
+void fn_7_74_box(inout struct_7_36 var_self_0, float var_x_0, float var_y_0, float var_w_0, float var_h_0, float var_r_0) {
+    float2 var_p_0 = (var_self_0.f_pos - float2(var_x_0, var_y_0));
+    float2 var_size_0 = float2((const_table[11].z * var_w_0), (const_table[11].w * var_h_0));
+    float2 var_bp_0 = max((abs((var_p_0 - var_size_0.xy)) - (var_size_0.xy - float2((const_table[12].x * var_r_0), (const_table[12].y * var_r_0)).xy)), float2(const_table[12].z, const_table[12].w));
+    (var_self_0.f_dist = ((length(var_bp_0) - (const_table[13].x * var_r_0)) / var_self_0.f_scale_factor));
+    (var_self_0.f_old_shape = var_self_0.f_shape);
+    (var_self_0.f_shape = min(var_self_0.f_shape, var_self_0.f_dist));
+}
+float fn_7_55_calc_blur(inout struct_7_36 var_self_0, float var_w_0) {
+    float var_wa_0 = clamp((-var_w_0 * var_self_0.f_aa), const_table[10].x, const_table[10].y);
+    float var_wb_0 = const_table[10].z;
+    if(var_self_0.f_blur > const_table[10].w) {
+            (var_wb_0 = clamp((-var_w_0 / var_self_0.f_blur), const_table[11].x, const_table[11].y));
+    }
+
+    return (var_wa_0 * var_wb_0);
+}
+float4 fn_7_60_stroke_keep(inout struct_7_36 var_self_0, float4 var_color_0, float var_width_0) {
+    float var_f_0 = fn_7_55_calc_blur (var_self_0, (abs(var_self_0.f_shape) - (var_width_0 / var_self_0.f_scale_factor)));
+    float4 var_source_0 = consfn_vec4_vec3_float((var_color_0.rgb * var_color_0.a), var_color_0.a);
+    float4 var_dest_0 = var_self_0.f_result;
+    (var_self_0.f_result = ((var_source_0 * var_f_0) + (var_dest_0 * (const_table[9].w - (var_source_0.a * var_f_0)))));
+    return var_self_0.f_result;
+}
+float4 fn_7_61_stroke(inout struct_7_36 var_self_0, float4 var_color_0, float var_width_0) {
+    fn_7_60_stroke_keep (var_self_0, var_color_0, var_width_0);
+    (var_self_0.f_old_shape = (var_self_0.f_shape = const_table[9].x));
+    (var_self_0.f_clip = -const_table[9].y);
+    (var_self_0.f_has_clip = const_table[9].z);
+    return var_self_0.f_result;
+}
+float4 fn_7_56_fill_keep_premul(inout struct_7_36 var_self_0, float4 var_source_0) {
+    float var_f_0 = fn_7_55_calc_blur (var_self_0, var_self_0.f_shape);
+    (var_self_0.f_result = ((var_source_0 * var_f_0) + (var_self_0.f_result * (const_table[8].x - (var_source_0.a * var_f_0)))));
+    if(var_self_0.f_has_clip > const_table[8].y) {
+            float var_f2_0 = (const_table[8].z - fn_7_55_calc_blur (var_self_0, -var_self_0.f_clip));
+        (var_self_0.f_result = ((var_source_0 * var_f2_0) + (var_self_0.f_result * (const_table[8].w - (var_source_0.a * var_f2_0)))));
+    }
+
+    return var_self_0.f_result;
+}
+float4 fn_7_58_fill_keep(inout struct_7_36 var_self_0, float4 var_color_0) {
+    return fn_7_56_fill_keep_premul (var_self_0, consfn_vec4_vec3_float((var_color_0.rgb * var_color_0.a), var_color_0.a));
+}
+float fn_7_49_antialias(float2 var_p_0) {
+    return (const_table[7].w / length(float2(length(ddx(var_p_0)), length(ddy(var_p_0)))));
+}
+struct_7_36 fn_7_50_viewport(float2 var_pos_0) {
+    return consfn_struct_7_36(var_pos_0,consfn_vec4_float(const_table[5].y),consfn_vec2_float(const_table[5].z),consfn_vec2_float(const_table[5].w),const_table[6].x,-const_table[6].y,const_table[6].z,const_table[6].w,const_table[7].x,fn_7_49_antialias (var_pos_0),const_table[7].y,const_table[7].z);
+}
+float4 fn_9_12_pixel(inout Varyings varyings) {
+    struct_7_36 var_sdf_0 = fn_7_50_viewport ((varyings.ds_pos * varyings.ds_rect_size));
+    fn_7_74_box (var_sdf_0, const_table[0].y, const_table[0].z, (varyings.ds_rect_size.x - const_table[0].w), (varyings.ds_rect_size.y - const_table[1].x), ds_radius);
+    fn_7_58_fill_keep (var_sdf_0, lerp(float4(const_table[1].y, const_table[1].z, const_table[1].w, const_table[2].x), varyings.ds_color, step(const_table[2].y, varyings.ds_pos.x)));
+    fn_7_61_stroke (var_sdf_0, float4(const_table[2].z, const_table[2].w, const_table[3].x, const_table[3].y), const_table[3].z);
+    float2 var_p_0 = varyings.ds_pos;
+    fn_9_14_shift (var_p_0, fmod(var_p_0.y, const_table[3].w));
+    float var_a_0 = const_table[4].x;
+    float var_b_0 = const_table[4].y;
+    (var_a_0 = (var_b_0 = max(var_p_0.x, const_table[4].z)));
+    float3 var_c_0 = max(var_sdf_0.f_result.xyz, const_table[4].w);
+    (var_c_0.xy *= var_p_0);
+    float var_alpha_0 = ((var_p_0.x > const_table[5].x) ? var_a_0 : var_b_0);
+    return consfn_vec4_vec3_float((var_c_0 * var_alpha_0), var_sdf_0.f_result.w);
+}
+float4 fn_9_9_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + ds_view_shift), ds_view_clip.xy, ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return mul(ds_camera_projection, mul(ds_camera_view, mul(ds_view_transform, float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + ds_draw_zbias), const_table[0].x))));
+}
+float4 fn_9_11_vertex(in Geometries geometries, in Instances instances, inout Varyings varyings) {
+    return fn_9_9_clip_and_transform_vertex (instances.ds_rect_pos, varyings.ds_rect_size, geometries, instances, varyings);
+}
+Varyings vertex_main(Geometries geometries, Instances instances, uint inst_id: SV_InstanceID) {
+    Varyings varyings = {float4(0.0,0.0,0.0,0.0), float2(0.0,0.0), float4(0.0,0.0,0.0,0.0), float2(0.0,0.0)};
+    varyings.ds_rect_size = instances.ds_rect_size;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_9_11_vertex(geometries, instances, varyings);
+    return varyings;
+}
+float4 pixel_main(Varyings varyings) : SV_TARGET{
+    return     fn_9_12_pixel(varyings);
+}
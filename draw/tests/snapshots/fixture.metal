@@ -0,0 +1,163 @@
+#include <metal_stdlib>
+using namespace metal;
+struct struct_7_36 {
+    float2 f_pos;
+    float4 f_result;
+    float2 f_last_pos;
+    float2 f_start_pos;
+    float f_shape;
+    float f_clip;
+    float f_has_clip;
+    float f_old_shape;
+    float f_blur;
+    float f_aa;
+    float f_scale_factor;
+    float f_dist;
+};
+struct LiveUniforms {
+};
+struct Uniforms_view {
+    float4x4 ds_view_transform;
+    float4 ds_view_clip;
+    float2 ds_view_shift;
+};
+struct Uniforms_pass {
+    float4x4 ds_camera_projection;
+    float4x4 ds_camera_view;
+    float4x4 ds_camera_inv;
+    float ds_dpi_factor;
+    float ds_dpi_dilate;
+    float ds_time;
+};
+struct Uniforms_draw {
+    float ds_draw_zbias;
+};
+struct Uniforms_user {
+    float ds_radius;
+};
+struct Textures {
+};
+struct Geometries {
+    packed_float2 ds_geom_pos;
+};
+struct Instances {
+    packed_float2 ds_rect_pos;
+    packed_float2 ds_rect_size;
+    packed_float4 ds_draw_clip;
+    float ds_draw_depth;
+    float pad_0;
+    packed_float4 ds_color;
+};
+struct Varyings {
+    float4 position [[position]];
+    float2 ds_rect_size;
+    float4 ds_color;
+    float2 ds_pos;
+};
+void fn_9_14_shift(thread float2 &var_v_0, float var_by_0, constant const float *const_table) {
+    (var_v_0.xy += float2(var_by_0));
+}
+void fn_7_74_box(thread struct_7_36 & var_self_0, float var_x_0, float var_y_0, float var_w_0, float var_h_0, float var_r_0, constant const float *const_table) {
+    float2 var_p_0 = (var_self_0.f_pos - float2(var_x_0, var_y_0));
+    float2 var_size_0 = float2((const_table[46] * var_w_0), (const_table[47] * var_h_0));
+    float2 var_bp_0 = max((abs((var_p_0 - var_size_0.xy)) - (var_size_0.xy - float2((const_table[48] * var_r_0), (const_table[49] * var_r_0)).xy)), float2(const_table[50], const_table[51]));
+    (var_self_0.f_dist = ((length(var_bp_0) - (const_table[52] * var_r_0)) / var_self_0.f_scale_factor));
+    (var_self_0.f_old_shape = var_self_0.f_shape);
+    (var_self_0.f_shape = min(var_self_0.f_shape, var_self_0.f_dist));
+}
+float fn_7_55_calc_blur(thread struct_7_36 & var_self_0, float var_w_0, constant const float *const_table) {
+    float var_wa_0 = clamp((-var_w_0 * var_self_0.f_aa), const_table[40], const_table[41]);
+    float var_wb_0 = const_table[42];
+    if(var_self_0.f_blur > const_table[43]) {
+            (var_wb_0 = clamp((-var_w_0 / var_self_0.f_blur), const_table[44], const_table[45]));
+    }
+
+    return (var_wa_0 * var_wb_0);
+}
+float4 fn_7_60_stroke_keep(thread struct_7_36 & var_self_0, float4 var_color_0, float var_width_0, constant const float *const_table) {
+    float var_f_0 = fn_7_55_calc_blur (var_self_0, (abs(var_self_0.f_shape) - (var_width_0 / var_self_0.f_scale_factor)), const_table);
+    float4 var_source_0 = float4((var_color_0.rgb * var_color_0.a), var_color_0.a);
+    float4 var_dest_0 = var_self_0.f_result;
+    (var_self_0.f_result = ((var_source_0 * var_f_0) + (var_dest_0 * (const_table[39] - (var_source_0.a * var_f_0)))));
+    return var_self_0.f_result;
+}
+float4 fn_7_61_stroke(thread struct_7_36 & var_self_0, float4 var_color_0, float var_width_0, constant const float *const_table) {
+    fn_7_60_stroke_keep (var_self_0, var_color_0, var_width_0, const_table);
+    (var_self_0.f_old_shape = (var_self_0.f_shape = const_table[36]));
+    (var_self_0.f_clip = -const_table[37]);
+    (var_self_0.f_has_clip = const_table[38]);
+    return var_self_0.f_result;
+}
+float4 fn_7_56_fill_keep_premul(thread struct_7_36 & var_self_0, float4 var_source_0, constant const float *const_table) {
+    float var_f_0 = fn_7_55_calc_blur (var_self_0, var_self_0.f_shape, const_table);
+    (var_self_0.f_result = ((var_source_0 * var_f_0) + (var_self_0.f_result * (const_table[32] - (var_source_0.a * var_f_0)))));
+    if(var_self_0.f_has_clip > const_table[33]) {
+            float var_f2_0 = (const_table[34] - fn_7_55_calc_blur (var_self_0, -var_self_0.f_clip, const_table));
+        (var_self_0.f_result = ((var_source_0 * var_f2_0) + (var_self_0.f_result * (const_table[35] - (var_source_0.a * var_f2_0)))));
+    }
+
+    return var_self_0.f_result;
+}
+float4 fn_7_58_fill_keep(thread struct_7_36 & var_self_0, float4 var_color_0, constant const float *const_table) {
+    return fn_7_56_fill_keep_premul (var_self_0, float4((var_color_0.rgb * var_color_0.a), var_color_0.a), const_table);
+}
+float fn_7_49_antialias(float2 var_p_0, constant const float *const_table) {
+    return (const_table[31] / length(float2(length(dfdx(var_p_0)), length(dfdy(var_p_0)))));
+}
+struct_7_36  fn_7_50_viewport(float2 var_pos_0, constant const float *const_table) {
+    return struct_7_36{var_pos_0,float4(const_table[21]),float2(const_table[22]),float2(const_table[23]),const_table[24],-const_table[25],const_table[26],const_table[27],const_table[28],fn_7_49_antialias (var_pos_0, const_table),const_table[29],const_table[30]};
+}
+float4 fn_9_12_pixel(constant const float *const_table, thread Varyings &varyings, constant Uniforms_user &uniforms_user) {
+    struct_7_36  var_sdf_0 = fn_7_50_viewport ((varyings.ds_pos * varyings.ds_rect_size), const_table);
+    fn_7_74_box (var_sdf_0, const_table[1], const_table[2], (varyings.ds_rect_size.x - const_table[3]), (varyings.ds_rect_size.y - const_table[4]), uniforms_user.ds_radius, const_table);
+    fn_7_58_fill_keep (var_sdf_0, mix(float4(const_table[5], const_table[6], const_table[7], const_table[8]), varyings.ds_color, step(const_table[9], varyings.ds_pos.x)), const_table);
+    fn_7_61_stroke (var_sdf_0, float4(const_table[10], const_table[11], const_table[12], const_table[13]), const_table[14], const_table);
+    float2 var_p_0 = varyings.ds_pos;
+    fn_9_14_shift (var_p_0, fmod(var_p_0.y, const_table[15]), const_table);
+    float var_a_0 = const_table[16];
+    float var_b_0 = const_table[17];
+    (var_a_0 = (var_b_0 = max(var_p_0.x, const_table[18])));
+    float3 var_c_0 = max(var_sdf_0.f_result.xyz, const_table[19]);
+    (var_c_0.xy *= var_p_0);
+    float var_alpha_0 = ((var_p_0.x > const_table[20]) ? var_a_0 : var_b_0);
+    return float4((var_c_0 * var_alpha_0), var_sdf_0.f_result.w);
+}
+float4 fn_9_9_clip_and_transform_vertex(float2 var_rect_pos_0, float2 var_rect_size_0, constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    float2 var_clipped_0 = clamp((clamp(((geometries.ds_geom_pos * var_rect_size_0) + var_rect_pos_0), instances.ds_draw_clip.xy, instances.ds_draw_clip.zw) + uniforms_view.ds_view_shift), uniforms_view.ds_view_clip.xy, uniforms_view.ds_view_clip.zw);
+    (varyings.ds_pos = ((var_clipped_0 - var_rect_pos_0) / var_rect_size_0));
+    return (uniforms_pass.ds_camera_projection * float4((uniforms_pass.ds_camera_view * float4((uniforms_view.ds_view_transform * float4(float4(var_clipped_0.x, var_clipped_0.y, (instances.ds_draw_depth + uniforms_draw.ds_draw_zbias), const_table[0])))))));
+}
+float4 fn_9_11_vertex(constant const float *const_table, thread Geometries &geometries, thread Instances &instances, thread Varyings &varyings, constant Uniforms_view &uniforms_view, constant Uniforms_pass &uniforms_pass, constant Uniforms_draw &uniforms_draw) {
+    return fn_9_9_clip_and_transform_vertex (instances.ds_rect_pos, varyings.ds_rect_size, const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+}
+vertex Varyings vertex_main(Textures textures
+, const device Geometries *in_geometries [[buffer(0)]]
+, const device Instances *in_instances [[buffer(1)]]
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+, uint vtx_id [[vertex_id]]
+, uint inst_id [[instance_id]]
+) {
+    Geometries geometries = in_geometries[vtx_id];
+    Instances instances = in_instances[inst_id];
+    Varyings varyings;
+    varyings.ds_rect_size = instances.ds_rect_size;
+    varyings.ds_color = instances.ds_color;
+    varyings.position = fn_9_11_vertex(const_table, geometries, instances, varyings, uniforms_view, uniforms_pass, uniforms_draw);
+    return varyings;
+}
+fragment float4 fragment_main(Varyings varyings[[stage_in]]
+, Textures textures
+, constant LiveUniforms &live_uniforms [[buffer(2)]]
+, constant const float *const_table [[buffer(3)]]
+, constant Uniforms_view &uniforms_view [[buffer(4)]]
+, constant Uniforms_pass &uniforms_pass [[buffer(5)]]
+, constant Uniforms_draw &uniforms_draw [[buffer(6)]]
+, constant Uniforms_user &uniforms_user [[buffer(7)]]
+) {
+    return     fn_9_12_pixel(const_table, varyings, uniforms_user);
+}
@@ -82,6 +82,7 @@ fn pixel(self) -> vec4 {
                     </blockquote><b><i>Bold italic</i><br/>
                     <sep/></br>
                     <pre>this is a preformatted code block</pre>
+                    <pre><code class=\"language-rust\">let x: u32 = add(1, 2); // sum</code></pre>
                     "
                 }
                 <Markdown>{
@@ -104,6 +105,16 @@ fn pixel(self) -> vec4 {
                     
                     [link](https://image)
                     ![image](https://link)
+                    
+                    | Name | Count | Price |
+                    |:-----|:-----:|------:|
+                    | apples | 3 | 1.50 |
+                    | pears **bold** | 12 | 10.00 |
+                    
+                    - [ ] open task
+                    - [x] done task
+                    - plain item
+                    
                     Normal
                     Next line
                     
@@ -119,6 +130,13 @@ fn pixel(self) -> vec4 {
                     let x = 10
                     let y = 10
                     ```
+                    ```rust
+                    // highlighted code
+                    fn main() {
+                        let s = \"hi\";
+                        for i in 0..10 { println!(\"{} {}\", s, i); }
+                    }
+                    ```
                     *italic* **Bold** normal _italic_ __bold__ ***Bolditalic*** normal
                     123
                     "
@@ -101,7 +101,9 @@ fn handle_post(http_server: HttpServer, mut tcp_stream: TcpStream, headers: Http
     let mut body = Vec::new();
     body.resize(bytes_total, 0u8);
     
-    let mut bytes_left = bytes_total;
+    let prefix_len = headers.body_prefix.len().min(bytes_total);
+    body[0..prefix_len].copy_from_slice(&headers.body_prefix[0..prefix_len]);
+    let mut bytes_left = bytes_total - prefix_len;
     while bytes_left > 0 {
         let buf = &mut body[(bytes_total - bytes_left)..bytes_total];
         let bytes_read = tcp_stream.read(buf);
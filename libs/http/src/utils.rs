@@ -68,7 +68,9 @@ pub struct HttpServerHeaders {
     pub search: Option<String>,
     pub content_length: Option<u64>,
     pub accept_encoding: Option<String>,
-    pub sec_websocket_key: Option<String>
+    pub sec_websocket_key: Option<String>,
+    /// body bytes the header reader already pulled off the socket
+    pub body_prefix: Vec<u8>,
 }
 
 impl HttpServerHeaders {
@@ -106,6 +108,7 @@ pub fn from_tcp_stream(tcp_stream: &mut TcpStream) -> Option<HttpServerHeaders>
         if lines.len() <2 {
             return None;
         }
+        let body_prefix = reader.buffer().to_vec();
         let verb;
         let path;
         if let Some(v) = split_header_line(&lines[0], "GET ") {
@@ -139,7 +142,8 @@ pub fn from_tcp_stream(tcp_stream: &mut TcpStream) -> Option<HttpServerHeaders>
             lines,
             content_length,
             accept_encoding,
-            sec_websocket_key
+            sec_websocket_key,
+            body_prefix
         })
     }
 }
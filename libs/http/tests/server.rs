@@ -0,0 +1,73 @@
+// Runs the http server on a local port and talks to it over plain tcp
+use {
+    std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream, SocketAddr},
+        sync::mpsc,
+        time::Duration,
+    },
+    makepad_http::server::*,
+};
+
+fn start() -> (SocketAddr, mpsc::Receiver<HttpServerRequest>) {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let listen_address = SocketAddr::from(([127, 0, 0, 1], port));
+    let (request, requests) = mpsc::channel();
+    start_http_server(HttpServer {
+        listen_address,
+        request,
+        post_max_size: 1024 * 1024,
+    }).unwrap();
+    (listen_address, requests)
+}
+
+/// Sends the request in the given pieces and answers the post it turns into with `ok`
+fn post(pieces: &[&[u8]]) -> Vec<u8> {
+    let (addr, requests) = start();
+    let mut stream = TcpStream::connect(addr).unwrap();
+    for piece in pieces {
+        stream.write_all(piece).unwrap();
+        stream.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    let body = match requests.recv_timeout(Duration::from_secs(5)).unwrap() {
+        HttpServerRequest::Post {body, response, ..} => {
+            response.send(HttpServerResponse {
+                header: "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n".to_string(),
+                body: b"ok".to_vec(),
+            }).unwrap();
+            body
+        }
+        _ => panic!("expected a post")
+    };
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    assert!(response.ends_with(b"\r\n\r\nok"));
+    body
+}
+
+#[test]
+fn post_body_in_the_header_packet() {
+    let body = post(&[b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\n\r\nhello world"]);
+    assert_eq!(body, b"hello world");
+}
+
+#[test]
+fn post_body_split_over_packets() {
+    let body = post(&[
+        b"POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\n\r\nhel",
+        b"lo wo",
+        b"rld",
+    ]);
+    assert_eq!(body, b"hello world");
+}
+
+#[test]
+fn post_body_larger_than_the_header_buffer() {
+    let data: Vec<u8> = (0..100_000u32).map( | i | (i % 251) as u8).collect();
+    let header = format!("POST /upload HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n", data.len());
+    let mut request = header.into_bytes();
+    request.extend_from_slice(&data);
+    let body = post(&[&request]);
+    assert_eq!(body, data);
+}
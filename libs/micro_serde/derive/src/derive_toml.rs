@@ -0,0 +1,251 @@
+use proc_macro::TokenStream;
+use makepad_micro_proc_macro::*;
+
+// like in json, fields that start with an _ are named without it
+fn field_key(field: &StructField) -> &str {
+    if let Some(v) = field.name.strip_prefix("_"){v}else{&field.name}
+}
+
+fn is_option(field: &StructField) -> bool {
+    field.ty.clone().into_iter().next().unwrap().to_string() == "Option"
+}
+
+// pushes the fields into a table `t`, reading them through `prefix`
+fn ser_toml_fields(tb: &mut TokenBuilder, fields: &[StructField], prefix: &str) {
+    tb.add("let mut t = Vec :: new ( ) ;");
+    for field in fields {
+        // toml has no null, fields that are None are left out
+        if is_option(field){
+            tb.add("if let Some ( v ) = &").add(prefix).ident(&field.name).add("{");
+            tb.add("t . push ( (").string(field_key(field)).add(". to_string ( ) , v . ser_toml_field (");
+            tb.string(field_key(field)).add(") ? ) ) ;");
+            tb.add("}");
+        }
+        else{
+            tb.add("t . push ( (").string(field_key(field)).add(". to_string ( ) ,");
+            tb.add(prefix).ident(&field.name).add(". ser_toml_field (").string(field_key(field)).add(") ? ) ) ;");
+        }
+    }
+}
+
+// reads the table `v` into the fields and builds `constructor` from them
+fn de_toml_fields(tb: &mut TokenBuilder, fields: &[StructField], constructor: &str) {
+    for field in fields{
+        tb.add("let mut").ident(&format!("_{}",field.name)).add("= None ;");
+    }
+    tb.add("for ( k , v ) in s . table ( v ) ? {");
+    tb.add("match k . as_ref ( ) {");
+    for field in fields{
+        tb.string(field_key(field)).add("=> {");
+        tb.ident(&format!("_{}",field.name)).add("= Some ( s . field ( k , v ) ? ) ; } ,");
+    }
+    tb.add("_ => return std :: result :: Result :: Err ( s . err_exp ( k ) )");
+    tb.add("} }");
+    tb.add(constructor).add("{");
+    for field in fields{
+        tb.ident(&field.name).add(":");
+        if is_option(field){
+            tb.ident(&format!("_{}",field.name)).add(". flatten ( ) ,");
+        }
+        else{
+            tb.ident(&format!("_{}",field.name)).add(". ok_or_else ( | | s . err_nf (");
+            tb.string(field_key(field)).add(") ) ? ,");
+        }
+    }
+    tb.add("}");
+}
+
+// reads the array `v` into a tuple of `len` items and builds `constructor` from them
+fn de_toml_tuple(tb: &mut TokenBuilder, len: usize, constructor: &str) {
+    tb.add("let items = s . array_len ( v ,").unsuf_usize(len).add(") ? ;");
+    tb.add(constructor).add("(");
+    for i in 0..len{
+        tb.add("s . field (").string(&i.to_string()).add(", & items [").unsuf_usize(i).add("] ) ? ,");
+    }
+    tb.add(")");
+}
+
+pub fn derive_ser_toml_impl(input: TokenStream) -> TokenStream {
+
+    let mut parser = TokenParser::new(input);
+    let mut tb = TokenBuilder::new();
+
+    parser.eat_attributes();
+    parser.eat_ident("pub");
+    if parser.eat_ident("struct"){
+        if let Some(name) = parser.eat_any_ident(){
+
+            let generic = parser.eat_generic();
+            let types = parser.eat_all_types();
+            let where_clause = parser.eat_where_clause(Some("SerToml"));
+
+            tb.add("impl").stream(generic.clone());
+            tb.add("SerToml for").ident(&name).stream(generic).stream(where_clause);
+            tb.add("{ fn ser_toml ( & self ) -> std :: result :: Result < TomlValue , SerTomlErr > {");
+
+            if let Some(types) = types{
+                tb.add("let mut t = Vec :: new ( ) ;");
+                for i in 0..types.len(){
+                    tb.add("t . push ( self .").unsuf_usize(i).add(". ser_toml_field (").string(&i.to_string()).add(") ? ) ;");
+                }
+                tb.add("std :: result :: Result :: Ok ( TomlValue :: Array ( t ) )");
+            }
+            else if let Some(fields) = parser.eat_all_struct_fields(){
+                ser_toml_fields(&mut tb, &fields, "self .");
+                tb.add("std :: result :: Result :: Ok ( TomlValue :: Table ( t ) )");
+            }
+            else{
+                return parser.unexpected()
+            }
+            tb.add("} } ;");
+            return tb.end();
+        }
+    }
+    else if parser.eat_ident("enum"){
+        if let Some(name) = parser.eat_any_ident(){
+            let generic = parser.eat_generic();
+            let where_clause = parser.eat_where_clause(Some("SerToml"));
+
+            tb.add("impl").stream(generic.clone());
+            tb.add("SerToml for").ident(&name).stream(generic).stream(where_clause);
+            tb.add("{ fn ser_toml ( & self ) -> std :: result :: Result < TomlValue , SerTomlErr > {");
+            // a variant is a table with its name as the only key
+            tb.add("let ( name , v ) = match self {");
+
+            if !parser.open_brace(){
+                return parser.unexpected()
+            }
+
+            while !parser.eat_eot(){
+                // parse ident
+                parser.eat_attributes();
+                if let Some(variant) = parser.eat_any_ident(){
+                    if let Some(types) = parser.eat_all_types(){
+
+                        tb.add("Self ::").ident(&variant).add("(");
+                        for i in 0..types.len(){
+                            tb.ident(&format!("n{}", i)).add(",");
+                        }
+                        tb.add(") => {");
+                        tb.add("let mut t = Vec :: new ( ) ;");
+                        for i in 0..types.len(){
+                            tb.add("t . push (").ident(&format!("n{}", i)).add(". ser_toml_field (").string(&i.to_string()).add(") ? ) ;");
+                        }
+                        tb.add("(").string(&variant).add(", TomlValue :: Array ( t ) )");
+                        tb.add("}");
+                    }
+                    else if let Some(fields) = parser.eat_all_struct_fields(){ // named variant
+                        tb.add("Self ::").ident(&variant).add("{");
+                        for field in fields.iter(){
+                            tb.ident(&field.name).add(",");
+                        }
+                        tb.add("} => {");
+                        ser_toml_fields(&mut tb, &fields, "");
+                        tb.add("(").string(&variant).add(", TomlValue :: Table ( t ) )");
+                        tb.add("}");
+                    }
+                    else if parser.is_punct_alone(',') || parser.is_eot(){ // bare variant
+                        tb.add("Self ::").ident(&variant).add("=> {");
+                        tb.add("(").string(&variant).add(", TomlValue :: Array ( Vec :: new ( ) ) ) }");
+                    }
+                    else{
+                        return parser.unexpected();
+                    }
+                    parser.eat_punct_alone(',');
+                }
+                else{
+                    return parser.unexpected()
+                }
+            }
+            tb.add("} ;");
+            tb.add("let mut t = Vec :: new ( ) ;");
+            tb.add("t . push ( ( name . to_string ( ) , v ) ) ;");
+            tb.add("std :: result :: Result :: Ok ( TomlValue :: Table ( t ) )");
+            tb.add("} } ;");
+            return tb.end();
+        }
+    }
+    parser.unexpected()
+}
+
+pub fn derive_de_toml_impl(input: TokenStream) -> TokenStream {
+    let mut parser = TokenParser::new(input);
+    let mut tb = TokenBuilder::new();
+
+    parser.eat_attributes();
+    parser.eat_ident("pub");
+    if parser.eat_ident("struct"){
+        if let Some(name) = parser.eat_any_ident(){
+            let generic = parser.eat_generic();
+            let types = parser.eat_all_types();
+            let where_clause = parser.eat_where_clause(Some("DeToml"));
+
+            tb.add("impl").stream(generic.clone());
+            tb.add("DeToml for").ident(&name).stream(generic).stream(where_clause);
+            tb.add("{ fn de_toml ( s : & mut DeTomlState , v : & TomlValue )");
+            tb.add("-> std :: result :: Result < Self , DeTomlErr > { ");
+            tb.add("std :: result :: Result :: Ok ( {");
+
+            if let Some(types) = types{
+                de_toml_tuple(&mut tb, types.len(), "Self");
+            }
+            else if let Some(fields) = parser.eat_all_struct_fields(){
+                de_toml_fields(&mut tb, &fields, "Self");
+            }
+            else{
+                return parser.unexpected()
+            }
+            tb.add("} ) } } ;");
+            return tb.end();
+        }
+    }
+    else if parser.eat_ident("enum"){
+
+        if let Some(name) = parser.eat_any_ident(){
+            let generic = parser.eat_generic();
+            let where_clause = parser.eat_where_clause(Some("DeToml"));
+
+            tb.add("impl").stream(generic.clone());
+            tb.add("DeToml for").ident(&name).stream(generic).stream(where_clause);
+            tb.add("{ fn de_toml ( s : & mut DeTomlState , v : & TomlValue )");
+            tb.add("-> std :: result :: Result < Self , DeTomlErr > { ");
+            tb.add("let ( name , v ) = s . variant ( v ) ? ;");
+            tb.add("s . path . push ( name . to_string ( ) ) ;");
+            tb.add("let r = match name {");
+
+            if !parser.open_brace(){
+                return parser.unexpected()
+            }
+            while !parser.eat_eot(){
+                // parse ident
+                parser.eat_attributes();
+                if let Some(variant) = parser.eat_any_ident(){
+                    let constructor = format!("Self :: {}", variant);
+                    tb.string(&variant).add("=> {");
+                    if let Some(types) = parser.eat_all_types(){
+                        de_toml_tuple(&mut tb, types.len(), &constructor);
+                    }
+                    else if let Some(fields) = parser.eat_all_struct_fields(){ // named variant
+                        de_toml_fields(&mut tb, &fields, &constructor);
+                    }
+                    else if parser.is_punct_alone(',') || parser.is_eot(){ // bare variant
+                        tb.add("s . array_len ( v , 0 ) ? ;").add(&constructor);
+                    }
+                    else{
+                        return parser.unexpected();
+                    }
+
+                    tb.add("}");
+                    parser.eat_punct_alone(',');
+                }
+                else{
+                    return parser.unexpected()
+                }
+            }
+            tb.add("_ => return std :: result :: Result :: Err ( s . err_enum ( name ) )");
+            tb.add("} ; s . path . pop ( ) ; std :: result :: Result :: Ok ( r ) } }");
+            return tb.end();
+        }
+    }
+    parser.unexpected()
+}
@@ -10,6 +10,9 @@
 mod derive_json;
 use crate::derive_json::*;
 
+mod derive_toml;
+use crate::derive_toml::*;
+
 #[proc_macro_derive(SerBin)]
 pub fn derive_ser_bin(input: TokenStream) -> TokenStream {
     derive_ser_bin_impl(input)
@@ -41,3 +44,12 @@ pub fn derive_de_ron(input: TokenStream) -> TokenStream {
     derive_de_ron_impl(input)
 }
 
+#[proc_macro_derive(SerToml)]
+pub fn derive_ser_toml(input: TokenStream) -> TokenStream {
+    derive_ser_toml_impl(input)
+}
+
+#[proc_macro_derive(DeToml)]
+pub fn derive_de_toml(input: TokenStream) -> TokenStream {
+    derive_de_toml_impl(input)
+}
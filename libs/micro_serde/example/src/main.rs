@@ -2,7 +2,7 @@
 
 use makepad_micro_serde::*;
 
-#[derive(SerBin, DeBin, SerJson, DeJson, SerRon, DeRon, PartialEq)]
+#[derive(SerBin, DeBin, SerJson, DeJson, SerRon, DeRon, SerToml, DeToml, PartialEq)]
 struct MyStruct<T> where T: Clone {
     pub a: T,
     b: u32,
@@ -17,7 +17,7 @@ struct MyStruct<T> where T: Clone {
     k: [u32;2]
 } 
 
-#[derive(SerBin, DeBin, SerJson, DeJson, SerRon, DeRon, PartialEq)]
+#[derive(SerBin, DeBin, SerJson, DeJson, SerRon, DeRon, SerToml, DeToml, PartialEq)]
 enum MyEnum<T> where T: Clone {
     One,
     Two(T, u32),
@@ -55,4 +55,9 @@ fn main() {
     println!("RON Output {}", ron);
     let y:MyStruct<usize> = DeRon::deserialize_ron(&ron).unwrap();
     println!("RON roundtrip equality {}", x == y);
+    
+    let toml = x.serialize_toml().unwrap();
+    println!("TOML Output {}", toml);
+    let y:MyStruct<usize> = DeToml::deserialize_toml(&toml).unwrap();
+    println!("TOML roundtrip equality {}", x == y);
 }
@@ -7,4 +7,7 @@
 pub use crate::serde_json::*;
 
 mod serde_ron;
-pub use crate::serde_ron::*;
\ No newline at end of file
+pub use crate::serde_ron::*;
+
+mod serde_toml;
+pub use crate::serde_toml::*;
\ No newline at end of file
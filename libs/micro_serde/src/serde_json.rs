@@ -714,6 +714,23 @@ pub fn key(&self, key:&str)->Option<&JsonValue>{
         }
         None
     }
+    pub fn array(&self)->Option<&Vec<JsonValue>>{
+        if let JsonValue::Array(arr) = self{
+            return Some(arr)
+        }
+        None
+    }
+    pub fn u64(&self)->Option<u64>{
+        match self{
+            JsonValue::U64(v)=>Some(*v),
+            JsonValue::I64(v)=>u64::try_from(*v).ok(),
+            JsonValue::F64(v) if *v >= 0.0 =>Some(*v as u64),
+            _=>None
+        }
+    }
+    pub fn is_null(&self)->bool{
+        matches!(self, JsonValue::Null | JsonValue::Undefined)
+    }
 }
 
 impl SerJson for JsonValue{
@@ -940,7 +957,7 @@ impl<K, V> SerJson for HashMap<K, V> where K: SerJson,
 V: SerJson {
     fn ser_json(&self, d: usize, s: &mut SerJsonState) {
         s.out.push('{');
-        let last = self.len() - 1;
+        let last = self.len().saturating_sub(1);
         for (index, (k, v)) in self.iter().enumerate() {
             s.indent(d + 1);
             k.ser_json(d + 1, s);
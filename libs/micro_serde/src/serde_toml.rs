@@ -0,0 +1,413 @@
+use std::collections::{HashMap};
+use makepad_live_id::LiveId;
+use makepad_toml_parser::{parse_toml_document, TomlDocument};
+pub use makepad_toml_parser::TomlValue;
+
+pub trait SerToml {
+
+    /// Writes a TOML file, which needs `self` to serialize to a table.
+    fn serialize_toml(&self) -> Result<String,
+    SerTomlErr> {
+        let value = self.ser_toml() ?;
+        if value.as_table().is_none() {
+            return Err(SerTomlErr{msg:"Only a table can be written as a file".to_string(), path:String::new()})
+        }
+        Ok(TomlDocument::from_value(&value).to_string())
+    }
+
+    /// Serializes the value of a key, adding the key to the path of errors.
+    fn ser_toml_field(&self, key: &str) -> Result<TomlValue,
+    SerTomlErr> {
+        self.ser_toml().map_err( | mut err | {
+            err.path = if err.path.is_empty() {key.to_string()} else {format!("{}.{}", key, err.path)};
+            err
+        })
+    }
+
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr>;
+}
+
+pub struct SerTomlErr{
+    pub msg:String,
+    pub path:String
+}
+
+impl std::fmt::Debug for SerTomlErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Toml Serialize error: {}, key:{}", self.msg, self.path)
+    }
+}
+
+pub trait DeToml: Sized {
+
+    fn deserialize_toml(input: &str) -> Result<Self,
+    DeTomlErr> {
+        let document = parse_toml_document(input).map_err( | err | DeTomlErr {
+            msg: format!("{}, at character {}", err.msg, err.span.start),
+            path: String::new()
+        }) ?;
+        let mut state = DeTomlState::default();
+        DeToml::de_toml(&mut state, &document.to_value())
+    }
+
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Self,
+    DeTomlErr>;
+}
+
+#[derive(Default)]
+pub struct DeTomlState {
+    // the keys down to the value being deserialized, for errors
+    pub path: Vec<String>,
+}
+
+pub struct DeTomlErr{
+    pub msg:String,
+    pub path:String
+}
+
+impl std::fmt::Debug for DeTomlErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Toml Deserialize error: {}, key:{}", self.msg, self.path)
+    }
+}
+
+impl DeTomlState {
+    pub fn err_msg(&self, msg: &str) -> DeTomlErr {
+        DeTomlErr{msg:msg.to_string(), path:self.path.join(".")}
+    }
+
+    pub fn err_exp(&self, name: &str) -> DeTomlErr {
+        self.err_msg(&format!("Unexpected key {}", name))
+    }
+
+    pub fn err_nf(&self, name: &str) -> DeTomlErr {
+        self.err_msg(&format!("Key not found {}", name))
+    }
+
+    pub fn err_enum(&self, name: &str) -> DeTomlErr {
+        self.err_msg(&format!("Enum not defined {}", name))
+    }
+
+    pub fn err_type(&self, what: &str, v: &TomlValue) -> DeTomlErr {
+        self.err_msg(&format!("Value {:?} is not {}", v, what))
+    }
+
+    pub fn err_range(&self, what: &str) -> DeTomlErr {
+        self.err_msg(&format!("Value out of range {}", what))
+    }
+
+    /// Deserializes the value of a key, keeping track of the key for errors.
+    pub fn field<T>(&mut self, key: &str, v: &TomlValue) -> Result<T, DeTomlErr> where T: DeToml {
+        self.path.push(key.to_string());
+        let r = DeToml::de_toml(self, v) ?;
+        self.path.pop();
+        Ok(r)
+    }
+
+    pub fn table<'a>(&self, v: &'a TomlValue) -> Result<&'a [(String, TomlValue)], DeTomlErr> {
+        v.as_table().ok_or_else( || self.err_type("a table", v))
+    }
+
+    pub fn array<'a>(&self, v: &'a TomlValue) -> Result<&'a [TomlValue], DeTomlErr> {
+        v.as_array().ok_or_else( || self.err_type("an array", v))
+    }
+
+    /// The items of an array that has to have `len` of them, for tuples.
+    pub fn array_len<'a>(&self, v: &'a TomlValue, len: usize) -> Result<&'a [TomlValue], DeTomlErr> {
+        let items = self.array(v) ?;
+        if items.len() != len {
+            return Err(self.err_msg(&format!("Expected {} items, got {}", len, items.len())))
+        }
+        Ok(items)
+    }
+
+    /// The variant name and value of an enum, which is a table with just that key.
+    pub fn variant<'a>(&self, v: &'a TomlValue) -> Result<(&'a str, &'a TomlValue), DeTomlErr> {
+        match self.table(v) ? {
+            [(name, value)] => Ok((name, value)),
+            _ => Err(self.err_type("an enum variant", v))
+        }
+    }
+
+    pub fn as_int(&self, v: &TomlValue) -> Result<i64, DeTomlErr> {
+        v.as_int().ok_or_else( || self.err_type("an integer", v))
+    }
+
+    pub fn as_float(&self, v: &TomlValue) -> Result<f64, DeTomlErr> {
+        v.as_float().ok_or_else( || self.err_type("a number", v))
+    }
+}
+
+// TOML integers are signed 64 bit, so unsigned values above i64::MAX can't be written
+macro_rules!impl_ser_de_toml_unsigned {
+    ( $ ty: ident) => {
+        impl SerToml for $ ty {
+            fn ser_toml(&self) -> Result<TomlValue,
+            SerTomlErr> {
+                match i64::try_from(*self) {
+                    Ok(val) => Ok(TomlValue::Int(val)),
+                    Err(_) => Err(SerTomlErr{msg:format!("Value out of range {}>{}", self, i64::MAX), path:String::new()})
+                }
+            }
+        }
+
+        impl DeToml for $ ty {
+            fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result< $ ty,
+            DeTomlErr> {
+                let val = s.as_int(v) ?;
+                $ ty::try_from(val).map_err( | _ | s.err_range(&format!("{} not in 0..={}", val, $ ty::MAX)))
+            }
+        }
+    }
+}
+
+macro_rules!impl_ser_de_toml_signed {
+    ( $ ty: ident, $ min: expr, $ max: expr) => {
+        impl SerToml for $ ty {
+            fn ser_toml(&self) -> Result<TomlValue,
+            SerTomlErr> {
+                Ok(TomlValue::Int(*self as i64))
+            }
+        }
+
+        impl DeToml for $ ty {
+            fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result< $ ty,
+            DeTomlErr> {
+                let val = s.as_int(v) ?;
+                if val < $ min as i64 || val > $ max as i64 {
+                    return Err(s.err_range(&format!("{} not in {}..={}", val, $ min, $ max)))
+                }
+                Ok(val as $ ty)
+            }
+        }
+    }
+}
+
+macro_rules!impl_ser_de_toml_float {
+    ( $ ty: ident) => {
+        impl SerToml for $ ty {
+            fn ser_toml(&self) -> Result<TomlValue,
+            SerTomlErr> {
+                Ok(TomlValue::Float(*self as f64))
+            }
+        }
+
+        impl DeToml for $ ty {
+            fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result< $ ty,
+            DeTomlErr> {
+                Ok(s.as_float(v) ? as $ ty)
+            }
+        }
+    }
+}
+
+impl_ser_de_toml_unsigned!(usize);
+impl_ser_de_toml_unsigned!(u64);
+impl_ser_de_toml_unsigned!(u32);
+impl_ser_de_toml_unsigned!(u16);
+impl_ser_de_toml_unsigned!(u8);
+impl_ser_de_toml_signed!(i64, i64::MIN, i64::MAX);
+impl_ser_de_toml_signed!(i32, i32::MIN, i32::MAX);
+impl_ser_de_toml_signed!(i16, i16::MIN, i16::MAX);
+impl_ser_de_toml_signed!(i8, i8::MIN, i8::MAX);
+impl_ser_de_toml_float!(f64);
+impl_ser_de_toml_float!(f32);
+
+// ids of identifiers have the top bit set, so ids are written with the same bits as an i64
+impl SerToml for LiveId {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Int(self.0 as i64))
+    }
+}
+
+impl DeToml for LiveId {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Self,
+    DeTomlErr> {
+        Ok(LiveId(s.as_int(v) ? as u64))
+    }
+}
+
+// TOML has no null, so fields that are None are left out by the derive and the ones that are
+// there are always Some
+impl<T> DeToml for Option<T> where T: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Self,
+    DeTomlErr> {
+        Ok(Some(DeToml::de_toml(s, v) ?))
+    }
+}
+
+impl SerToml for bool {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Bool(*self))
+    }
+}
+
+impl DeToml for bool {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<bool, DeTomlErr> {
+        v.as_bool().ok_or_else( || s.err_type("a boolean", v))
+    }
+}
+
+impl SerToml for String {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Str(self.clone()))
+    }
+}
+
+impl DeToml for String {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<String, DeTomlErr> {
+        v.as_str().map( | v | v.to_string()).ok_or_else( || s.err_type("a string", v))
+    }
+}
+
+impl SerToml for TomlValue {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(self.clone())
+    }
+}
+
+impl DeToml for TomlValue {
+    fn de_toml(_s: &mut DeTomlState, v: &TomlValue) -> Result<TomlValue, DeTomlErr> {
+        Ok(v.clone())
+    }
+}
+
+impl<T> SerToml for Vec<T> where T: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        self.as_slice().ser_toml()
+    }
+}
+
+impl<T> DeToml for Vec<T> where T: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Vec<T>, DeTomlErr> {
+        let mut out = Vec::new();
+        for (index, item) in s.array(v) ?.iter().enumerate() {
+            out.push(s.field(&index.to_string(), item) ?);
+        }
+        Ok(out)
+    }
+}
+
+impl<T> SerToml for [T] where T: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        let mut out = Vec::new();
+        for (index, item) in self.iter().enumerate() {
+            out.push(item.ser_toml_field(&index.to_string()) ?);
+        }
+        Ok(TomlValue::Array(out))
+    }
+}
+
+impl<T, const N: usize> SerToml for [T; N] where T: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        self.as_slice().ser_toml()
+    }
+}
+
+impl<T, const N: usize> DeToml for [T; N] where T: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Self,
+    DeTomlErr> {
+        s.array_len(v, N) ?;
+        let items: Vec<T> = DeToml::de_toml(s, v) ?;
+        Ok(items.try_into().unwrap_or_else( | _ | unreachable!()))
+    }
+}
+
+impl<A, B> SerToml for (A, B) where A: SerToml,
+B: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Array(vec![self.0.ser_toml_field("0") ?, self.1.ser_toml_field("1") ?]))
+    }
+}
+
+impl<A, B> DeToml for (A, B) where A: DeToml,
+B: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<(A, B), DeTomlErr> {
+        let items = s.array_len(v, 2) ?;
+        Ok((s.field("0", &items[0]) ?, s.field("1", &items[1]) ?))
+    }
+}
+
+impl<A, B, C> SerToml for (A, B, C) where A: SerToml,
+B: SerToml,
+C: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Array(vec![self.0.ser_toml_field("0") ?, self.1.ser_toml_field("1") ?, self.2.ser_toml_field("2") ?]))
+    }
+}
+
+impl<A, B, C> DeToml for (A, B, C) where A: DeToml,
+B: DeToml,
+C: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<(A, B, C), DeTomlErr> {
+        let items = s.array_len(v, 3) ?;
+        Ok((s.field("0", &items[0]) ?, s.field("1", &items[1]) ?, s.field("2", &items[2]) ?))
+    }
+}
+
+impl<A, B, C, D> SerToml for (A, B, C, D) where A: SerToml,
+B: SerToml,
+C: SerToml,
+D: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        Ok(TomlValue::Array(vec![self.0.ser_toml_field("0") ?, self.1.ser_toml_field("1") ?, self.2.ser_toml_field("2") ?, self.3.ser_toml_field("3") ?]))
+    }
+}
+
+impl<A, B, C, D> DeToml for (A, B, C, D) where A: DeToml,
+B: DeToml,
+C: DeToml,
+D: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<(A, B, C, D), DeTomlErr> {
+        let items = s.array_len(v, 4) ?;
+        Ok((s.field("0", &items[0]) ?, s.field("1", &items[1]) ?, s.field("2", &items[2]) ?, s.field("3", &items[3]) ?))
+    }
+}
+
+// keys are sorted so the same map is always written the same way
+impl<V> SerToml for HashMap<String, V> where V: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        let mut values = Vec::new();
+        for (k, v) in self {
+            values.push((k.clone(), v.ser_toml_field(k) ?));
+        }
+        values.sort_by( | a: &(String, TomlValue), b | a.0.cmp(&b.0));
+        Ok(TomlValue::Table(values))
+    }
+}
+
+impl<V> DeToml for HashMap<String, V> where V: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Self,
+    DeTomlErr> {
+        let mut h = HashMap::new();
+        for (k, v) in s.table(v) ? {
+            h.insert(k.clone(), s.field(k, v) ?);
+        }
+        Ok(h)
+    }
+}
+
+impl<T> SerToml for Box<T> where T: SerToml {
+    fn ser_toml(&self) -> Result<TomlValue,
+    SerTomlErr> {
+        (**self).ser_toml()
+    }
+}
+
+impl<T> DeToml for Box<T> where T: DeToml {
+    fn de_toml(s: &mut DeTomlState, v: &TomlValue) -> Result<Box<T>, DeTomlErr> {
+        Ok(Box::new(DeToml::de_toml(s, v) ?))
+    }
+}
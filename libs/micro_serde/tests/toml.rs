@@ -0,0 +1,151 @@
+use makepad_micro_serde::*;
+use std::collections::HashMap;
+
+#[derive(SerToml, DeToml, PartialEq, Debug)]
+struct Config<T> where T: Clone {
+    name: String,
+    _type: u32,
+    scale: f64,
+    enabled: bool,
+    tags: Vec<String>,
+    size: [u16; 2],
+    parent: Option<String>,
+    limit: Option<i32>,
+    value: T,
+    mode: Mode,
+    window: Window,
+    plugins: Vec<Plugin>,
+    env: HashMap<String, String>,
+}
+
+#[derive(SerToml, DeToml, PartialEq, Debug, Clone)]
+enum Mode {
+    Dark,
+    Custom(u8, u8, u8),
+    Theme {name: String, contrast: Option<f32>},
+}
+
+#[derive(SerToml, DeToml, PartialEq, Debug)]
+struct Window(i32, i32);
+
+#[derive(SerToml, DeToml, PartialEq, Debug)]
+struct Plugin {
+    path: String,
+    mode: Mode,
+}
+
+fn config() -> Config<u64> {
+    Config {
+        name: "studio".to_string(),
+        _type: 2,
+        scale: 1.5,
+        enabled: true,
+        tags: vec!["a".to_string(), "b".to_string()],
+        size: [800, 600],
+        parent: None,
+        limit: Some(-4),
+        value: i64::MAX as u64,
+        mode: Mode::Theme {name: "night".to_string(), contrast: None},
+        window: Window(-10, 20),
+        plugins: vec![
+            Plugin {path: "lsp".to_string(), mode: Mode::Dark},
+            Plugin {path: "git".to_string(), mode: Mode::Custom(1, 2, 3)},
+        ],
+        env: [("B".to_string(), "2".to_string()), ("A".to_string(), "1".to_string())].into_iter().collect(),
+    }
+}
+
+#[test]
+fn round_trips() {
+    let toml = config().serialize_toml().unwrap();
+    assert_eq!(toml, r#"name = "studio"
+type = 2
+scale = 1.5
+enabled = true
+tags = ["a", "b"]
+size = [800, 600]
+limit = -4
+value = 9223372036854775807
+window = [-10, 20]
+
+[mode.Theme]
+name = "night"
+
+[[plugins]]
+path = "lsp"
+
+[plugins.mode]
+Dark = []
+
+[[plugins]]
+path = "git"
+
+[plugins.mode]
+Custom = [1, 2, 3]
+
+[env]
+A = "1"
+B = "2"
+"#);
+    let back: Config<u64> = DeToml::deserialize_toml(&toml).unwrap();
+    assert_eq!(back, config());
+}
+
+#[test]
+fn reads_handwritten_files() {
+    let toml = r#"
+# the settings
+name = 'studio'
+type = 2
+scale = 1          # integers are fine for floats
+enabled = true
+tags = []
+size = [ 1_024, 768, ]
+value = 7
+mode = { Custom = [255, 0, 0] }
+window = [0, 0]
+env = {}
+
+[[plugins]]
+path = "a"
+mode.Theme.name = "day"
+mode.Theme.contrast = 0.5
+"#;
+    let config: Config<u64> = DeToml::deserialize_toml(toml).unwrap();
+    assert_eq!(config.scale, 1.0);
+    assert_eq!(config.size, [1024, 768]);
+    assert_eq!(config.parent, None);
+    assert_eq!(config.mode, Mode::Custom(255, 0, 0));
+    assert_eq!(config.plugins[0].mode, Mode::Theme {name: "day".to_string(), contrast: Some(0.5)});
+}
+
+#[test]
+fn reports_errors() {
+    let err = |toml: &str| format!("{:?}", <Config<u64> as DeToml>::deserialize_toml(toml).unwrap_err());
+    let base = config().serialize_toml().unwrap();
+    assert!(err(&base.replace("name = \"studio\"\n", "")).contains("Key not found name"));
+    assert!(err(&base.replace("type = 2", "type = 2\nextra = 1")).contains("Unexpected key extra"));
+    assert!(err(&base.replace("size = [800, 600]", "size = [800, 70000]")).contains("key:size.1"));
+    assert!(err(&base.replace("size = [800, 600]", "size = [800]")).contains("Expected 2 items"));
+    assert!(err(&base.replace("Dark = []", "Light = []")).contains("Enum not defined Light"));
+    assert!(err(&base.replace("enabled = true", "enabled = \"yes\"")).contains("is not a boolean"));
+    assert!(err("name = ").contains("at character"));
+}
+
+#[test]
+fn checks_integer_ranges() {
+    let mut config = config();
+    config.value = i64::MAX as u64 + 1;
+    assert!(format!("{:?}", config.serialize_toml().unwrap_err()).contains("key:value"));
+    config.value = 0;
+    let toml = config.serialize_toml().unwrap();
+
+    let err = |toml: &str| format!("{:?}", <Config<u64> as DeToml>::deserialize_toml(toml).unwrap_err());
+    assert!(err(&toml.replace("value = 0", "value = -1")).contains("key:value"));
+    assert!(err(&toml.replace("Custom = [1, 2, 3]", "Custom = [1, -2, 3]")).contains("key:plugins.1.mode.Custom.1"));
+    assert!(err(&toml.replace("type = 2", "type = 4294967296")).contains("Value out of range"));
+
+    let values = (u64::MAX, 1u8);
+    assert!(format!("{:?}", values.ser_toml().unwrap_err()).contains("key:0"));
+    assert!(5u8.serialize_toml().is_err());
+}
@@ -4,6 +4,7 @@
         decode::{Decode, DecodeError, Decoder},
         exec::{self, ThreadedInstr},
         ref_::RefType,
+        v128::V128,
         val::ValType,
     },
     std::sync::Arc,
@@ -122,6 +123,19 @@ fn visit_table_copy(
     fn visit_f64_const(&mut self, val: f64) -> Result<(), Self::Error>;
     fn visit_un_op(&mut self, info: UnOpInfo) -> Result<(), Self::Error>;
     fn visit_bin_op(&mut self, info: BinOpInfo) -> Result<(), Self::Error>;
+
+    // Vector instructions
+    fn visit_v128_const(&mut self, val: V128) -> Result<(), Self::Error>;
+    fn visit_i8x16_shuffle(&mut self, lane_idxs: V128) -> Result<(), Self::Error>;
+    fn visit_vec_op(&mut self, info: VecOpInfo) -> Result<(), Self::Error>;
+    fn visit_vec_lane_op(&mut self, lane_idx: u8, info: VecLaneOpInfo) -> Result<(), Self::Error>;
+    fn visit_vec_mem_op(&mut self, arg: MemArg, info: VecMemOpInfo) -> Result<(), Self::Error>;
+    fn visit_vec_mem_lane_op(
+        &mut self,
+        arg: MemArg,
+        lane_idx: u8,
+        info: VecMemLaneOpInfo,
+    ) -> Result<(), Self::Error>;
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -158,6 +172,7 @@ fn decode_i33_tail(decoder: &mut Decoder<'_>, mut value: i64) -> Result<i64, Dec
             0x7E => Ok(BlockType::ValType(Some(ValType::I64))),
             0x7D => Ok(BlockType::ValType(Some(ValType::F32))),
             0x7C => Ok(BlockType::ValType(Some(ValType::F64))),
+            0x7B => Ok(BlockType::ValType(Some(ValType::V128))),
             0x70 => Ok(BlockType::ValType(Some(ValType::FuncRef))),
             0x6F => Ok(BlockType::ValType(Some(ValType::ExternRef))),
             byte => {
@@ -230,6 +245,33 @@ pub(crate) struct BinOpInfo {
     pub(crate) instr_rr: Option<ThreadedInstr>,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VecOpInfo {
+    pub(crate) _name: &'static str,
+    pub(crate) input_types: &'static [ValType],
+    pub(crate) output_type: Option<ValType>,
+    pub(crate) instr: ThreadedInstr,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VecLaneOpInfo {
+    pub(crate) lane_count: u8,
+    pub(crate) op: VecOpInfo,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VecMemOpInfo {
+    pub(crate) max_align: u32,
+    pub(crate) op: VecOpInfo,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VecMemLaneOpInfo {
+    pub(crate) max_align: u32,
+    pub(crate) lane_count: u8,
+    pub(crate) op: VecOpInfo,
+}
+
 pub(crate) fn decode_instr<V>(
     decoder: &mut Decoder<'_>,
     label_idxs: &mut Vec<u32>,
@@ -2340,6 +2382,1647 @@ pub(crate) fn decode_instr<V>(
             17 => visitor.visit_table_fill(decoder.decode()?),
             _ => Err(DecodeError::new("illegal opcode"))?,
         },
+        0xFD => match decoder.decode::<u32>()? {
+            0 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 4,
+                    op: VecOpInfo {
+                        _name: "v128_load",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load_s,
+                    },
+                },
+            ),
+            1 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load8x8_s",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load8x8_s_s,
+                    },
+                },
+            ),
+            2 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load8x8_u",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load8x8_u_s,
+                    },
+                },
+            ),
+            3 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load16x4_s",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load16x4_s_s,
+                    },
+                },
+            ),
+            4 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load16x4_u",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load16x4_u_s,
+                    },
+                },
+            ),
+            5 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load32x2_s",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load32x2_s_s,
+                    },
+                },
+            ),
+            6 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load32x2_u",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load32x2_u_s,
+                    },
+                },
+            ),
+            7 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 0,
+                    op: VecOpInfo {
+                        _name: "v128_load8_splat",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load8_splat_s,
+                    },
+                },
+            ),
+            8 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 1,
+                    op: VecOpInfo {
+                        _name: "v128_load16_splat",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load16_splat_s,
+                    },
+                },
+            ),
+            9 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 2,
+                    op: VecOpInfo {
+                        _name: "v128_load32_splat",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load32_splat_s,
+                    },
+                },
+            ),
+            10 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load64_splat",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load64_splat_s,
+                    },
+                },
+            ),
+            11 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 4,
+                    op: VecOpInfo {
+                        _name: "v128_store",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: None,
+                        instr: exec::v128_store_ss,
+                    },
+                },
+            ),
+            12 => visitor.visit_v128_const(decoder.decode()?),
+            13 => visitor.visit_i8x16_shuffle(decoder.decode()?),
+            14 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_swizzle",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_swizzle_ss,
+            }),
+            15 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_splat",
+                input_types: &[ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_splat_s,
+            }),
+            16 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_splat",
+                input_types: &[ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_splat_s,
+            }),
+            17 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_splat",
+                input_types: &[ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_splat_s,
+            }),
+            18 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_splat",
+                input_types: &[ValType::I64],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_splat_s,
+            }),
+            19 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_splat",
+                input_types: &[ValType::F32],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_splat_s,
+            }),
+            20 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_splat",
+                input_types: &[ValType::F64],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_splat_s,
+            }),
+            21 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 16,
+                    op: VecOpInfo {
+                        _name: "i8x16_extract_lane_s",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I32),
+                        instr: exec::i8x16_extract_lane_s_s,
+                    },
+                },
+            ),
+            22 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 16,
+                    op: VecOpInfo {
+                        _name: "i8x16_extract_lane_u",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I32),
+                        instr: exec::i8x16_extract_lane_u_s,
+                    },
+                },
+            ),
+            23 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 16,
+                    op: VecOpInfo {
+                        _name: "i8x16_replace_lane",
+                        input_types: &[ValType::V128, ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::i8x16_replace_lane_ss,
+                    },
+                },
+            ),
+            24 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 8,
+                    op: VecOpInfo {
+                        _name: "i16x8_extract_lane_s",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I32),
+                        instr: exec::i16x8_extract_lane_s_s,
+                    },
+                },
+            ),
+            25 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 8,
+                    op: VecOpInfo {
+                        _name: "i16x8_extract_lane_u",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I32),
+                        instr: exec::i16x8_extract_lane_u_s,
+                    },
+                },
+            ),
+            26 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 8,
+                    op: VecOpInfo {
+                        _name: "i16x8_replace_lane",
+                        input_types: &[ValType::V128, ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::i16x8_replace_lane_ss,
+                    },
+                },
+            ),
+            27 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "i32x4_extract_lane",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I32),
+                        instr: exec::i32x4_extract_lane_s,
+                    },
+                },
+            ),
+            28 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "i32x4_replace_lane",
+                        input_types: &[ValType::V128, ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::i32x4_replace_lane_ss,
+                    },
+                },
+            ),
+            29 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "i64x2_extract_lane",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::I64),
+                        instr: exec::i64x2_extract_lane_s,
+                    },
+                },
+            ),
+            30 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "i64x2_replace_lane",
+                        input_types: &[ValType::V128, ValType::I64],
+                        output_type: Some(ValType::V128),
+                        instr: exec::i64x2_replace_lane_ss,
+                    },
+                },
+            ),
+            31 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "f32x4_extract_lane",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::F32),
+                        instr: exec::f32x4_extract_lane_s,
+                    },
+                },
+            ),
+            32 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "f32x4_replace_lane",
+                        input_types: &[ValType::V128, ValType::F32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::f32x4_replace_lane_ss,
+                    },
+                },
+            ),
+            33 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "f64x2_extract_lane",
+                        input_types: &[ValType::V128],
+                        output_type: Some(ValType::F64),
+                        instr: exec::f64x2_extract_lane_s,
+                    },
+                },
+            ),
+            34 => visitor.visit_vec_lane_op(
+                decoder.read_byte()?,
+                VecLaneOpInfo {
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "f64x2_replace_lane",
+                        input_types: &[ValType::V128, ValType::F64],
+                        output_type: Some(ValType::V128),
+                        instr: exec::f64x2_replace_lane_ss,
+                    },
+                },
+            ),
+            35 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_eq_ss,
+            }),
+            36 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_ne_ss,
+            }),
+            37 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_lt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_lt_s_ss,
+            }),
+            38 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_lt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_lt_u_ss,
+            }),
+            39 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_gt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_gt_s_ss,
+            }),
+            40 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_gt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_gt_u_ss,
+            }),
+            41 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_le_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_le_s_ss,
+            }),
+            42 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_le_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_le_u_ss,
+            }),
+            43 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_ge_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_ge_s_ss,
+            }),
+            44 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_ge_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_ge_u_ss,
+            }),
+            45 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_eq_ss,
+            }),
+            46 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_ne_ss,
+            }),
+            47 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_lt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_lt_s_ss,
+            }),
+            48 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_lt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_lt_u_ss,
+            }),
+            49 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_gt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_gt_s_ss,
+            }),
+            50 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_gt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_gt_u_ss,
+            }),
+            51 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_le_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_le_s_ss,
+            }),
+            52 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_le_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_le_u_ss,
+            }),
+            53 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_ge_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_ge_s_ss,
+            }),
+            54 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_ge_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_ge_u_ss,
+            }),
+            55 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_eq_ss,
+            }),
+            56 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_ne_ss,
+            }),
+            57 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_lt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_lt_s_ss,
+            }),
+            58 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_lt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_lt_u_ss,
+            }),
+            59 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_gt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_gt_s_ss,
+            }),
+            60 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_gt_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_gt_u_ss,
+            }),
+            61 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_le_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_le_s_ss,
+            }),
+            62 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_le_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_le_u_ss,
+            }),
+            63 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_ge_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_ge_s_ss,
+            }),
+            64 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_ge_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_ge_u_ss,
+            }),
+            65 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_eq_ss,
+            }),
+            66 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_ne_ss,
+            }),
+            67 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_lt",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_lt_ss,
+            }),
+            68 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_gt",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_gt_ss,
+            }),
+            69 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_le",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_le_ss,
+            }),
+            70 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_ge",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_ge_ss,
+            }),
+            71 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_eq_ss,
+            }),
+            72 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_ne_ss,
+            }),
+            73 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_lt",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_lt_ss,
+            }),
+            74 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_gt",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_gt_ss,
+            }),
+            75 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_le",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_le_ss,
+            }),
+            76 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_ge",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_ge_ss,
+            }),
+            77 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_not",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_not_s,
+            }),
+            78 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_and",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_and_ss,
+            }),
+            79 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_andnot",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_andnot_ss,
+            }),
+            80 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_or",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_or_ss,
+            }),
+            81 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_xor",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_xor_ss,
+            }),
+            82 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_bitselect",
+                input_types: &[ValType::V128, ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::v128_bitselect_sss,
+            }),
+            83 => visitor.visit_vec_op(VecOpInfo {
+                _name: "v128_any_true",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::v128_any_true_s,
+            }),
+            84 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 0,
+                    lane_count: 16,
+                    op: VecOpInfo {
+                        _name: "v128_load8_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load8_lane_ss,
+                    },
+                },
+            ),
+            85 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 1,
+                    lane_count: 8,
+                    op: VecOpInfo {
+                        _name: "v128_load16_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load16_lane_ss,
+                    },
+                },
+            ),
+            86 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 2,
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "v128_load32_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load32_lane_ss,
+                    },
+                },
+            ),
+            87 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 3,
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "v128_load64_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load64_lane_ss,
+                    },
+                },
+            ),
+            88 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 0,
+                    lane_count: 16,
+                    op: VecOpInfo {
+                        _name: "v128_store8_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: None,
+                        instr: exec::v128_store8_lane_ss,
+                    },
+                },
+            ),
+            89 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 1,
+                    lane_count: 8,
+                    op: VecOpInfo {
+                        _name: "v128_store16_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: None,
+                        instr: exec::v128_store16_lane_ss,
+                    },
+                },
+            ),
+            90 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 2,
+                    lane_count: 4,
+                    op: VecOpInfo {
+                        _name: "v128_store32_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: None,
+                        instr: exec::v128_store32_lane_ss,
+                    },
+                },
+            ),
+            91 => visitor.visit_vec_mem_lane_op(
+                decoder.decode()?,
+                decoder.read_byte()?,
+                VecMemLaneOpInfo {
+                    max_align: 3,
+                    lane_count: 2,
+                    op: VecOpInfo {
+                        _name: "v128_store64_lane",
+                        input_types: &[ValType::I32, ValType::V128],
+                        output_type: None,
+                        instr: exec::v128_store64_lane_ss,
+                    },
+                },
+            ),
+            92 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 2,
+                    op: VecOpInfo {
+                        _name: "v128_load32_zero",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load32_zero_s,
+                    },
+                },
+            ),
+            93 => visitor.visit_vec_mem_op(
+                decoder.decode()?,
+                VecMemOpInfo {
+                    max_align: 3,
+                    op: VecOpInfo {
+                        _name: "v128_load64_zero",
+                        input_types: &[ValType::I32],
+                        output_type: Some(ValType::V128),
+                        instr: exec::v128_load64_zero_s,
+                    },
+                },
+            ),
+            94 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_demote_f64x2_zero",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_demote_f64x2_zero_s,
+            }),
+            95 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_promote_low_f32x4",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_promote_low_f32x4_s,
+            }),
+            96 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_abs_s,
+            }),
+            97 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_neg_s,
+            }),
+            98 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_popcnt",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_popcnt_s,
+            }),
+            99 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_all_true",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i8x16_all_true_s,
+            }),
+            100 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_bitmask",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i8x16_bitmask_s,
+            }),
+            101 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_narrow_i16x8_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_narrow_i16x8_s_ss,
+            }),
+            102 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_narrow_i16x8_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_narrow_i16x8_u_ss,
+            }),
+            103 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_ceil",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_ceil_s,
+            }),
+            104 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_floor",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_floor_s,
+            }),
+            105 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_trunc",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_trunc_s,
+            }),
+            106 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_nearest",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_nearest_s,
+            }),
+            107 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_shl",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_shl_ss,
+            }),
+            108 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_shr_s",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_shr_s_ss,
+            }),
+            109 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_shr_u",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_shr_u_ss,
+            }),
+            110 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_add_ss,
+            }),
+            111 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_add_sat_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_add_sat_s_ss,
+            }),
+            112 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_add_sat_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_add_sat_u_ss,
+            }),
+            113 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_sub_ss,
+            }),
+            114 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_sub_sat_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_sub_sat_s_ss,
+            }),
+            115 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_sub_sat_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_sub_sat_u_ss,
+            }),
+            116 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_ceil",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_ceil_s,
+            }),
+            117 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_floor",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_floor_s,
+            }),
+            118 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_min_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_min_s_ss,
+            }),
+            119 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_min_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_min_u_ss,
+            }),
+            120 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_max_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_max_s_ss,
+            }),
+            121 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_max_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_max_u_ss,
+            }),
+            122 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_trunc",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_trunc_s,
+            }),
+            123 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i8x16_avgr_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_avgr_u_ss,
+            }),
+            124 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extadd_pairwise_i8x16_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extadd_pairwise_i8x16_s_s,
+            }),
+            125 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extadd_pairwise_i8x16_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extadd_pairwise_i8x16_u_s,
+            }),
+            126 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extadd_pairwise_i16x8_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extadd_pairwise_i16x8_s_s,
+            }),
+            127 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extadd_pairwise_i16x8_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extadd_pairwise_i16x8_u_s,
+            }),
+            128 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_abs_s,
+            }),
+            129 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_neg_s,
+            }),
+            130 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_q15mulr_sat_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_q15mulr_sat_s_ss,
+            }),
+            131 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_all_true",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i16x8_all_true_s,
+            }),
+            132 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_bitmask",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i16x8_bitmask_s,
+            }),
+            133 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_narrow_i32x4_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_narrow_i32x4_s_ss,
+            }),
+            134 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_narrow_i32x4_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_narrow_i32x4_u_ss,
+            }),
+            135 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extend_low_i8x16_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extend_low_i8x16_s_s,
+            }),
+            136 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extend_high_i8x16_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extend_high_i8x16_s_s,
+            }),
+            137 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extend_low_i8x16_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extend_low_i8x16_u_s,
+            }),
+            138 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extend_high_i8x16_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extend_high_i8x16_u_s,
+            }),
+            139 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_shl",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_shl_ss,
+            }),
+            140 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_shr_s",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_shr_s_ss,
+            }),
+            141 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_shr_u",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_shr_u_ss,
+            }),
+            142 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_add_ss,
+            }),
+            143 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_add_sat_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_add_sat_s_ss,
+            }),
+            144 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_add_sat_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_add_sat_u_ss,
+            }),
+            145 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_sub_ss,
+            }),
+            146 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_sub_sat_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_sub_sat_s_ss,
+            }),
+            147 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_sub_sat_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_sub_sat_u_ss,
+            }),
+            148 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_nearest",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_nearest_s,
+            }),
+            149 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_mul",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_mul_ss,
+            }),
+            150 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_min_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_min_s_ss,
+            }),
+            151 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_min_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_min_u_ss,
+            }),
+            152 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_max_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_max_s_ss,
+            }),
+            153 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_max_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_max_u_ss,
+            }),
+            155 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_avgr_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_avgr_u_ss,
+            }),
+            156 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extmul_low_i8x16_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extmul_low_i8x16_s_ss,
+            }),
+            157 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extmul_high_i8x16_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extmul_high_i8x16_s_ss,
+            }),
+            158 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extmul_low_i8x16_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extmul_low_i8x16_u_ss,
+            }),
+            159 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i16x8_extmul_high_i8x16_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i16x8_extmul_high_i8x16_u_ss,
+            }),
+            160 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_abs_s,
+            }),
+            161 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_neg_s,
+            }),
+            163 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_all_true",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i32x4_all_true_s,
+            }),
+            164 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_bitmask",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i32x4_bitmask_s,
+            }),
+            167 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extend_low_i16x8_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extend_low_i16x8_s_s,
+            }),
+            168 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extend_high_i16x8_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extend_high_i16x8_s_s,
+            }),
+            169 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extend_low_i16x8_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extend_low_i16x8_u_s,
+            }),
+            170 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extend_high_i16x8_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extend_high_i16x8_u_s,
+            }),
+            171 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_shl",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_shl_ss,
+            }),
+            172 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_shr_s",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_shr_s_ss,
+            }),
+            173 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_shr_u",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_shr_u_ss,
+            }),
+            174 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_add_ss,
+            }),
+            177 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_sub_ss,
+            }),
+            181 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_mul",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_mul_ss,
+            }),
+            182 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_min_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_min_s_ss,
+            }),
+            183 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_min_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_min_u_ss,
+            }),
+            184 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_max_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_max_s_ss,
+            }),
+            185 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_max_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_max_u_ss,
+            }),
+            186 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_dot_i16x8_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_dot_i16x8_s_ss,
+            }),
+            188 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extmul_low_i16x8_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extmul_low_i16x8_s_ss,
+            }),
+            189 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extmul_high_i16x8_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extmul_high_i16x8_s_ss,
+            }),
+            190 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extmul_low_i16x8_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extmul_low_i16x8_u_ss,
+            }),
+            191 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_extmul_high_i16x8_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_extmul_high_i16x8_u_ss,
+            }),
+            192 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_abs_s,
+            }),
+            193 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_neg_s,
+            }),
+            195 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_all_true",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i64x2_all_true_s,
+            }),
+            196 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_bitmask",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::I32),
+                instr: exec::i64x2_bitmask_s,
+            }),
+            199 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extend_low_i32x4_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extend_low_i32x4_s_s,
+            }),
+            200 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extend_high_i32x4_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extend_high_i32x4_s_s,
+            }),
+            201 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extend_low_i32x4_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extend_low_i32x4_u_s,
+            }),
+            202 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extend_high_i32x4_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extend_high_i32x4_u_s,
+            }),
+            203 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_shl",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_shl_ss,
+            }),
+            204 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_shr_s",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_shr_s_ss,
+            }),
+            205 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_shr_u",
+                input_types: &[ValType::V128, ValType::I32],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_shr_u_ss,
+            }),
+            206 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_add_ss,
+            }),
+            209 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_sub_ss,
+            }),
+            213 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_mul",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_mul_ss,
+            }),
+            214 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_eq",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_eq_ss,
+            }),
+            215 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_ne",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_ne_ss,
+            }),
+            216 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_lt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_lt_s_ss,
+            }),
+            217 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_gt_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_gt_s_ss,
+            }),
+            218 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_le_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_le_s_ss,
+            }),
+            219 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_ge_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_ge_s_ss,
+            }),
+            220 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extmul_low_i32x4_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extmul_low_i32x4_s_ss,
+            }),
+            221 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extmul_high_i32x4_s",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extmul_high_i32x4_s_ss,
+            }),
+            222 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extmul_low_i32x4_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extmul_low_i32x4_u_ss,
+            }),
+            223 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i64x2_extmul_high_i32x4_u",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i64x2_extmul_high_i32x4_u_ss,
+            }),
+            224 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_abs_s,
+            }),
+            225 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_neg_s,
+            }),
+            227 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_sqrt",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_sqrt_s,
+            }),
+            228 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_add_ss,
+            }),
+            229 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_sub_ss,
+            }),
+            230 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_mul",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_mul_ss,
+            }),
+            231 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_div",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_div_ss,
+            }),
+            232 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_min",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_min_ss,
+            }),
+            233 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_max",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_max_ss,
+            }),
+            234 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_pmin",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_pmin_ss,
+            }),
+            235 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_pmax",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_pmax_ss,
+            }),
+            236 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_abs",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_abs_s,
+            }),
+            237 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_neg",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_neg_s,
+            }),
+            239 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_sqrt",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_sqrt_s,
+            }),
+            240 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_add",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_add_ss,
+            }),
+            241 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_sub",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_sub_ss,
+            }),
+            242 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_mul",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_mul_ss,
+            }),
+            243 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_div",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_div_ss,
+            }),
+            244 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_min",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_min_ss,
+            }),
+            245 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_max",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_max_ss,
+            }),
+            246 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_pmin",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_pmin_ss,
+            }),
+            247 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_pmax",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_pmax_ss,
+            }),
+            248 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_trunc_sat_f32x4_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_trunc_sat_f32x4_s_s,
+            }),
+            249 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_trunc_sat_f32x4_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_trunc_sat_f32x4_u_s,
+            }),
+            250 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_convert_i32x4_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_convert_i32x4_s_s,
+            }),
+            251 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f32x4_convert_i32x4_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f32x4_convert_i32x4_u_s,
+            }),
+            252 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_trunc_sat_f64x2_s_zero",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_trunc_sat_f64x2_s_zero_s,
+            }),
+            253 => visitor.visit_vec_op(VecOpInfo {
+                _name: "i32x4_trunc_sat_f64x2_u_zero",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i32x4_trunc_sat_f64x2_u_zero_s,
+            }),
+            254 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_convert_low_i32x4_s",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_convert_low_i32x4_s_s,
+            }),
+            255 => visitor.visit_vec_op(VecOpInfo {
+                _name: "f64x2_convert_low_i32x4_u",
+                input_types: &[ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::f64x2_convert_low_i32x4_u_s,
+            }),
+            _ => Err(DecodeError::new("illegal opcode"))?,
+        },
         _ => Err(DecodeError::new("illegal opcode"))?,
     }
 }
@@ -4,7 +4,8 @@
         code,
         code::{
             BinOpInfo, BlockType, CompiledCode, InstrSlot, InstrVisitor, LoadInfo, MemArg,
-            StoreInfo, UnOpInfo, UncompiledCode,
+            StoreInfo, UnOpInfo, UncompiledCode, VecLaneOpInfo, VecMemLaneOpInfo, VecMemOpInfo,
+            VecOpInfo,
         },
         decode::DecodeError,
         exec,
@@ -16,6 +17,7 @@
         ref_::RefType,
         stack::StackSlot,
         store::Store,
+        v128::V128,
         val::{UnguardedVal, ValType},
     },
     std::{mem, ops::Deref},
@@ -57,18 +59,25 @@ pub(crate) fn compile(
 
         let type_ = func.type_(store);
         let locals = &mut self.locals;
-        for type_ in type_
-            .params()
-            .iter()
-            .copied()
-            .chain(code.locals.iter().copied())
-        {
+        let first_param_result_stack_idx = -(type_.call_frame_size() as isize);
+        let mut param_stack_idx = first_param_result_stack_idx;
+        for type_ in type_.params().iter().copied() {
+            locals.push(Local {
+                type_,
+                stack_idx: param_stack_idx,
+                first_opd_idx: None,
+            });
+            param_stack_idx += type_.slot_count() as isize;
+        }
+        let mut local_count = 0;
+        for type_ in code.locals.iter().copied() {
             locals.push(Local {
                 type_,
+                stack_idx: local_count as isize,
                 first_opd_idx: None,
             });
+            local_count += type_.slot_count();
         }
-        let local_count = locals.len() - type_.params().len();
 
         let mut compile = Compile {
             store,
@@ -78,7 +87,7 @@ pub(crate) fn compile(
             blocks: &mut self.blocks,
             opds: &mut self.opds,
             fixup_idxs: &mut self.fixup_idxs,
-            first_param_result_stack_idx: -(type_.call_frame_size() as isize),
+            first_param_result_stack_idx,
             first_temp_stack_idx: local_count,
             max_stack_height: local_count,
             regs: [None; 2],
@@ -97,16 +106,20 @@ pub(crate) fn compile(
                 .mem(0)
                 .map(|mem| mem.to_unguarded(store.id())),
         );
+        compile.emit_fuel_check();
 
         let mut decoder = Decoder::new(&code.expr);
         while !compile.blocks.is_empty() {
+            compile.charge_fuel();
             code::decode_instr(&mut decoder, &mut self.label_idxs, &mut compile).unwrap();
         }
 
         for (result_idx, result_type) in type_.clone().results().iter().copied().enumerate().rev() {
             compile.emit(select_copy_stack(result_type));
-            compile.emit_stack_offset(compile.temp_stack_idx(result_idx));
-            compile.emit_stack_offset(compile.param_result_stack_idx(result_idx));
+            compile.emit_stack_offset(
+                (compile.first_temp_stack_idx + slot_count(&type_.results()[..result_idx])) as isize,
+            );
+            compile.emit_stack_offset(compile.result_stack_idx(result_idx));
         }
         compile.emit(exec::return_ as ThreadedInstr);
 
@@ -197,6 +210,39 @@ fn remove_local_opd(&mut self, opd_idx: usize) {
         self.opds[opd_idx].next_opd_idx = None;
     }
 
+    /// Compiles a vector operation, calling `emit_imms` to emit any immediate operands after the
+    /// inputs.
+    ///
+    /// Vector values are never stored in a register, and vector operations are rare enough that we
+    /// don't want to implement an instruction for every combination of operand kinds. Vector
+    /// operations therefore only have an _s{s}* variant, which reads all its inputs from the stack
+    /// and writes its output to the stack.
+    fn compile_vec_op(&mut self, info: VecOpInfo, emit_imms: impl FnOnce(&mut Self)) {
+        // Ensure that all inputs are stack operands.
+        for opd_depth in 0..info.input_types.len() {
+            self.ensure_opd_not_imm(opd_depth);
+            self.ensure_opd_not_reg(opd_depth);
+        }
+
+        // Emit the instruction.
+        self.emit(info.instr);
+
+        // Emit the inputs and pop them from the stack.
+        for _ in 0..info.input_types.len() {
+            self.emit_and_pop_opd();
+        }
+
+        // Emit the immediate operands.
+        emit_imms(self);
+
+        // If the operation has an output, push the output onto the stack and emit its stack
+        // offset.
+        if let Some(output_type) = info.output_type {
+            self.push_opd(output_type);
+            self.emit_stack_offset(self.opd_stack_idx(0));
+        }
+    }
+
     /// Preserve the local with the given index by preserving every local operand that refers to it.
     fn preserve_local(&mut self, local_idx: usize) {
         while let Some(opd_idx) = self.locals[local_idx].first_opd_idx {
@@ -254,6 +300,7 @@ fn pop_hole(&mut self, block_idx: usize) -> Option<usize> {
 
     /// Pushes a block with the given kind and type on stack.
     fn push_block(&mut self, kind: BlockKind, type_: FuncType) {
+        let fuel_idx = self.blocks.last().and_then(|block| block.fuel_idx);
         self.blocks.push(Block {
             kind,
             type_,
@@ -262,6 +309,7 @@ fn push_block(&mut self, kind: BlockKind, type_: FuncType) {
             first_instr_idx: self.code.len(),
             first_hole_idx: None,
             else_hole_idx: None,
+            fuel_idx,
         });
 
         // Push the inputs of the block on the stack.
@@ -280,6 +328,36 @@ fn pop_block(&mut self) -> Block {
         self.blocks.pop().unwrap()
     }
 
+    // Methods for metering fuel.
+
+    /// Emits a `consume_fuel` instruction for the current block.
+    ///
+    /// The cost of the instruction starts out at zero, and is incremented for each Wasm
+    /// instruction that is compiled until the end of the block, or until the next block with its
+    /// own `consume_fuel` instruction. Instructions that are branched over are charged as well.
+    ///
+    /// Emits nothing if the engine doesn't meter fuel, in which case nothing is charged either.
+    fn emit_fuel_check(&mut self) {
+        if !self.store.engine().meters_fuel() {
+            return;
+        }
+        let mem = self
+            .instance
+            .mem(0)
+            .map(|mem| mem.to_unguarded(self.store.id()));
+        self.emit(exec::consume_fuel as ThreadedInstr);
+        self.block_mut(0).fuel_idx = Some(self.code.len());
+        self.emit(0usize);
+        self.emit(mem);
+    }
+
+    /// Charges the next Wasm instruction to the `consume_fuel` instruction of the current block.
+    fn charge_fuel(&mut self) {
+        if let Some(fuel_idx) = self.block(0).fuel_idx {
+            self.code[fuel_idx] += 1;
+        }
+    }
+
     // Methods for operating on operands.
 
     /// Returns a reference to the [`Opd`] at the given depth.
@@ -337,15 +415,18 @@ fn preserve_local_opd(&mut self, opd_idx: usize) {
 
     /// Pushes an operand of the given type on the stack.
     fn push_opd(&mut self, type_: impl Into<ValType>) {
+        let type_ = type_.into();
+        let stack_idx = self.temp_stack_height(self.opds.len());
         self.opds.push(Opd {
-            type_: type_.into(),
+            type_,
             val: None,
             local_idx: None,
             prev_opd_idx: None,
             next_opd_idx: None,
+            stack_idx,
             is_reg: false,
         });
-        let stack_height = self.first_temp_stack_idx as usize + (self.opds.len() - 1);
+        let stack_height = stack_idx + type_.slot_count();
         self.max_stack_height = self.max_stack_height.max(stack_height);
     }
 
@@ -369,23 +450,40 @@ fn emit_and_pop_opd(&mut self) {
 
     // Methods for operating on the stack.
 
-    /// Returns the stack index of the parameter/result with the given index.
-    fn param_result_stack_idx(&self, param_result_idx: usize) -> isize {
-        self.first_param_result_stack_idx + param_result_idx as isize
+    /// Returns the stack index of the result with the given index.
+    ///
+    /// The results overwrite the parameters, so they start at the same stack index.
+    fn result_stack_idx(&self, result_idx: usize) -> isize {
+        self.first_param_result_stack_idx + slot_count(&self.type_.results()[..result_idx]) as isize
     }
 
     /// Returns the stack index of the local with the given index.
     fn local_stack_idx(&self, local_idx: usize) -> isize {
-        if local_idx < self.type_.params().len() {
-            self.param_result_stack_idx(local_idx)
-        } else {
-            (local_idx - self.type_.params().len()) as isize
-        }
+        self.locals[local_idx].stack_idx
     }
 
     /// Returns the stack index of the temporary with the given index.
     fn temp_stack_idx(&self, temp_idx: usize) -> isize {
-        (self.first_temp_stack_idx + temp_idx) as isize
+        self.opds[temp_idx].stack_idx as isize
+    }
+
+    /// Returns the stack index right after the temporaries for the given number of operands.
+    fn temp_stack_height(&self, opd_count: usize) -> usize {
+        match opd_count.checked_sub(1) {
+            Some(opd_idx) => self.opds[opd_idx].stack_idx + self.opds[opd_idx].type_.slot_count(),
+            None => self.first_temp_stack_idx,
+        }
+    }
+
+    /// Returns the stack index of the value with the given index for the label with the given
+    /// index.
+    ///
+    /// The values for a label are stored right after the temporaries below its block, whatever
+    /// operands are currently on the stack above them.
+    fn label_val_stack_idx(&self, label_idx: usize, label_val_idx: usize) -> isize {
+        let block = self.block(label_idx);
+        (self.temp_stack_height(block.height) + slot_count(&block.label_types()[..label_val_idx]))
+            as isize
     }
 
     /// Returns the stack index of the operand at the given depth.
@@ -455,22 +553,24 @@ fn resolve_label_vals(&mut self, label_idx: usize) {
             self.emit(select_copy_stack(label_type));
             self.emit_stack_offset(self.opd_stack_idx(0));
             self.pop_opd();
-            self.emit_stack_offset(
-                self.temp_stack_idx(self.block(label_idx).height + label_val_idx),
-            );
+            self.emit_stack_offset(self.label_val_stack_idx(label_idx, label_val_idx));
         }
     }
 
     // Methods for emitting code.
 
     // Emits the given value.
+    //
+    // Values that are larger than an [`InstrSlot`] span multiple consecutive slots.
     fn emit<T>(&mut self, val: T)
     where
         T: Copy,
     {
-        debug_assert!(mem::size_of::<T>() <= mem::size_of::<InstrSlot>());
-        self.code.push(InstrSlot::default());
-        unsafe { *(self.code.last_mut().unwrap() as *mut _ as *mut T) = val };
+        let slot_count = mem::size_of::<T>().div_ceil(mem::size_of::<InstrSlot>());
+        let slot_idx = self.code.len();
+        self.code
+            .resize(slot_idx + slot_count, InstrSlot::default());
+        unsafe { *(self.code[slot_idx..].as_mut_ptr() as *mut T) = val };
     }
 
     // Emits an operand.
@@ -495,6 +595,7 @@ fn emit_val(&mut self, val: UnguardedVal) {
             UnguardedVal::I64(val) => self.emit(val),
             UnguardedVal::F32(val) => self.emit(val),
             UnguardedVal::F64(val) => self.emit(val),
+            UnguardedVal::V128(val) => self.emit(val),
             UnguardedVal::FuncRef(val) => self.emit(val),
             UnguardedVal::ExternRef(val) => self.emit(val),
         }
@@ -613,6 +714,9 @@ fn visit_loop(&mut self, type_: BlockType) -> Result<(), Self::Error> {
 
         self.push_block(BlockKind::Loop, type_);
 
+        // Every iteration of the loop branches back to here, so this is where we consume fuel.
+        self.emit_fuel_check();
+
         Ok(())
     }
 
@@ -957,7 +1061,7 @@ fn visit_return(&mut self) -> Result<(), Self::Error> {
                 select_copy_stack(result_type)
             });
             self.emit_and_pop_opd();
-            self.emit_stack_offset(self.param_result_stack_idx(result_idx));
+            self.emit_stack_offset(self.result_stack_idx(result_idx));
         }
         self.emit(exec::return_ as ThreadedInstr);
 
@@ -994,7 +1098,7 @@ fn visit_call(&mut self, func_idx: u32) -> Result<(), Self::Error> {
             self.pop_opd();
         }
         self.emit(func.0.to_unguarded(self.store.id()));
-        let first_callee_stack_idx = self.first_temp_stack_idx + self.opds.len();
+        let first_callee_stack_idx = self.temp_stack_height(self.opds.len());
         let last_callee_stack_idx = first_callee_stack_idx + type_.call_frame_size();
         self.max_stack_height = self.max_stack_height.max(last_callee_stack_idx);
         self.emit_stack_offset(last_callee_stack_idx as isize);
@@ -1042,7 +1146,7 @@ fn visit_call_indirect(&mut self, table_idx: u32, type_idx: u32) -> Result<(), S
         }
         self.emit(table.0.to_unguarded(self.store.id()));
         self.emit(interned_type.to_unguarded(self.store.id()));
-        let first_callee_stack_idx = self.first_temp_stack_idx + self.opds.len();
+        let first_callee_stack_idx = self.temp_stack_height(self.opds.len());
         let last_callee_stack_idx = first_callee_stack_idx + type_.call_frame_size();
         self.max_stack_height = self.max_stack_height.max(last_callee_stack_idx as usize);
         self.emit_stack_offset(last_callee_stack_idx as isize);
@@ -1151,6 +1255,22 @@ fn visit_select(&mut self, type_: Option<ValType>) -> Result<(), DecodeError> {
 
         let type_ = type_.unwrap_or_else(|| self.opd(1).type_);
 
+        // Vector values are never stored in a register, so the select instruction only has an _sss
+        // variant for vectors, which writes its output to the stack.
+        if type_.is_vec() {
+            for opd_depth in 0..3 {
+                self.ensure_opd_not_imm(opd_depth);
+                self.ensure_opd_not_reg(opd_depth);
+            }
+            self.emit(exec::select_v128_sss as ThreadedInstr);
+            for _ in 0..3 {
+                self.emit_and_pop_opd();
+            }
+            self.push_opd(type_);
+            self.emit_stack_offset(self.opd_stack_idx(0));
+            return Ok(());
+        }
+
         // The `select` instruction does not have any _{sri}{sri}i variants.
         //
         // For instance, the following sequence of instructions:
@@ -2049,6 +2169,102 @@ fn visit_bin_op(&mut self, info: BinOpInfo) -> Result<(), DecodeError> {
 
         Ok(())
     }
+
+    // Vector instructions
+
+    /// Compiles a `v128.const` instruction.
+    fn visit_v128_const(&mut self, val: V128) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        // Push the output onto the stack and set its value.
+        //
+        // Setting its value will mark the operand as an immediate operand.
+        self.push_opd(ValType::V128);
+        self.opd_mut(0).val = Some(UnguardedVal::V128(val));
+
+        Ok(())
+    }
+
+    /// Compiles an `i8x16.shuffle` instruction.
+    fn visit_i8x16_shuffle(&mut self, lane_idxs: V128) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        // We compile i8x16.shuffle like any other vector operation, except that the lane indices
+        // are emitted as an extra immediate operand.
+        self.compile_vec_op(
+            VecOpInfo {
+                _name: "i8x16_shuffle",
+                input_types: &[ValType::V128, ValType::V128],
+                output_type: Some(ValType::V128),
+                instr: exec::i8x16_shuffle_ss,
+            },
+            |this| this.emit(lane_idxs),
+        );
+
+        Ok(())
+    }
+
+    /// Compiles a vector operation.
+    fn visit_vec_op(&mut self, info: VecOpInfo) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        self.compile_vec_op(info, |_| {});
+
+        Ok(())
+    }
+
+    /// Compiles a vector operation that operates on a single lane.
+    fn visit_vec_lane_op(&mut self, lane_idx: u8, info: VecLaneOpInfo) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        self.compile_vec_op(info.op, |this| this.emit(lane_idx));
+
+        Ok(())
+    }
+
+    /// Compiles a vector load or store instruction.
+    fn visit_vec_mem_op(&mut self, arg: MemArg, info: VecMemOpInfo) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        self.compile_vec_op(info.op, |this| this.emit(arg.offset));
+
+        Ok(())
+    }
+
+    /// Compiles a vector load or store instruction that operates on a single lane.
+    fn visit_vec_mem_lane_op(
+        &mut self,
+        arg: MemArg,
+        lane_idx: u8,
+        info: VecMemLaneOpInfo,
+    ) -> Result<(), DecodeError> {
+        // Skip this instruction if it is unreachable.
+        if self.block(0).is_unreachable {
+            return Ok(());
+        }
+
+        self.compile_vec_op(info.op, |this| {
+            this.emit(arg.offset);
+            this.emit(lane_idx);
+        });
+
+        Ok(())
+    }
 }
 
 /// A local on the stack.
@@ -2056,6 +2272,8 @@ fn visit_bin_op(&mut self, info: BinOpInfo) -> Result<(), DecodeError> {
 struct Local {
     // The type of this local.
     type_: ValType,
+    // The stack index of this local.
+    stack_idx: isize,
     // The index of the first operand in the list of operands for this local.
     first_opd_idx: Option<usize>,
 }
@@ -2077,6 +2295,9 @@ struct Block {
     else_hole_idx: Option<usize>,
     // The index of the first hole for this block.
     first_hole_idx: Option<usize>,
+    // The index of the cost operand of the `consume_fuel` instruction that this block is charged
+    // to.
+    fuel_idx: Option<usize>,
 }
 
 impl Block {
@@ -2131,10 +2352,11 @@ fn deref(&self) -> &Self::Target {
 /// Every operand carries:
 /// - Its type
 ///
-/// - An implicit stack index
+/// - A stack index
 ///   This is the index of the stack slot to be used for the operand, if it is stored on the stack.
-///   It is determined by the position of the operand on the operand stack. Note that we reserve a
-///   stack slot for an operand even if it is not stored on the stack.
+///   It is determined by the operands below it on the operand stack, since vector operands take up
+///   two stack slots. Note that we reserve stack slots for an operand even if it is not stored on
+///   the stack.
 ///
 /// - An implicit register index
 ///   This is the index of the register to be used for the operand, if it is stored in a register.
@@ -2179,6 +2401,9 @@ struct Opd {
     // The index of the next operand in the list of operands for the local this this operand refers
     // to, if it is a local operand.
     next_opd_idx: Option<usize>,
+    // The stack index this operand is stored at if it is a temporary operand, or would be stored at
+    // if it were preserved on the stack.
+    stack_idx: usize,
     // Whether this operand is stored in a register.
     is_reg: bool,
 }
@@ -2233,6 +2458,11 @@ enum OpdKind {
 // functions are used to select a suitable variant of an instruction based on the types and
 // kinds of its operands.
 
+/// Returns the number of stack slots taken up by values of the given types.
+fn slot_count(types: &[ValType]) -> usize {
+    types.iter().map(|type_| type_.slot_count()).sum()
+}
+
 fn select_br_if_z(kind: OpdKind) -> ThreadedInstr {
     match kind {
         OpdKind::Stack => exec::br_if_z_s,
@@ -2424,7 +2654,9 @@ fn select_select(
         // same register. Since we only have one register available for every type, there is no
         // variant of this instruction that can handle this case.
         | (_, OpdKind::Reg, OpdKind::Reg, _)
-        | (_, _, _, OpdKind::Imm) => panic!("no suitable instruction found"),
+        | (_, _, _, OpdKind::Imm)
+        // Vector values are never stored in a register, and are handled separately.
+        | (ValType::V128, _, _, _) => panic!("no suitable instruction found"),
     }
 }
 
@@ -2434,6 +2666,7 @@ fn select_global_get(type_: ValType) -> ThreadedInstr {
         ValType::I64 => exec::global_get_i64,
         ValType::F32 => exec::global_get_f32,
         ValType::F64 => exec::global_get_f64,
+        ValType::V128 => exec::global_get_v128,
         ValType::FuncRef => exec::global_get_func_ref,
         ValType::ExternRef => exec::global_get_extern_ref,
     }
@@ -2453,12 +2686,17 @@ fn select_global_set(type_: ValType, kind: OpdKind) -> ThreadedInstr {
         (ValType::F64, OpdKind::Stack) => exec::global_set_f64_s,
         (ValType::F64, OpdKind::Reg) => exec::global_set_f64_r,
         (ValType::F64, OpdKind::Imm) => exec::global_set_f64_i,
+        (ValType::V128, OpdKind::Stack) => exec::global_set_v128_s,
+        (ValType::V128, OpdKind::Imm) => exec::global_set_v128_i,
         (ValType::FuncRef, OpdKind::Stack) => exec::global_set_func_ref_s,
         (ValType::FuncRef, OpdKind::Reg) => exec::global_set_func_ref_r,
         (ValType::FuncRef, OpdKind::Imm) => exec::global_set_func_ref_i,
         (ValType::ExternRef, OpdKind::Stack) => exec::global_set_extern_ref_s,
         (ValType::ExternRef, OpdKind::Reg) => exec::global_set_extern_ref_r,
         (ValType::ExternRef, OpdKind::Imm) => exec::global_set_extern_ref_i,
+
+        // Vector values are never stored in a register.
+        (ValType::V128, OpdKind::Reg) => panic!("no suitable instruction found"),
     }
 }
 
@@ -2583,6 +2821,7 @@ fn select_copy_imm_to_stack(type_: ValType) -> ThreadedInstr {
         ValType::I64 => exec::copy_imm_to_stack_i64,
         ValType::F32 => exec::copy_imm_to_stack_f32,
         ValType::F64 => exec::copy_imm_to_stack_f64,
+        ValType::V128 => exec::copy_imm_to_stack_v128,
         ValType::FuncRef => exec::copy_imm_to_stack_func_ref,
         ValType::ExternRef => exec::copy_imm_to_stack_extern_ref,
     }
@@ -2594,6 +2833,7 @@ fn select_copy_stack(type_: ValType) -> ThreadedInstr {
         ValType::I64 => exec::copy_stack_i64,
         ValType::F32 => exec::copy_stack_f32,
         ValType::F64 => exec::copy_stack_f64,
+        ValType::V128 => exec::copy_stack_v128,
         ValType::FuncRef => exec::copy_stack_func_ref,
         ValType::ExternRef => exec::copy_stack_extern_ref,
     }
@@ -2605,6 +2845,7 @@ fn select_copy_reg_to_stack(type_: ValType) -> ThreadedInstr {
         ValType::I64 => exec::copy_reg_to_stack_i64,
         ValType::F32 => exec::copy_reg_to_stack_f32,
         ValType::F64 => exec::copy_reg_to_stack_f64,
+        ValType::V128 => panic!("no suitable instruction found"),
         ValType::FuncRef => exec::copy_reg_to_stack_func_ref,
         ValType::ExternRef => exec::copy_reg_to_stack_extern_ref,
     }
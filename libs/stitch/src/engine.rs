@@ -20,15 +20,39 @@ pub struct Engine {
 
 impl Engine {
     /// Creates a new [`Engine`].
+    ///
+    /// Code compiled by this [`Engine`] does not meter fuel, so it runs at full speed but can
+    /// neither run out of fuel nor be interrupted. Use [`Engine::with_fuel_metering`] for that.
     pub fn new() -> Engine {
+        Self::with_options(false)
+    }
+
+    /// Creates a new [`Engine`] that meters fuel.
+    ///
+    /// Code compiled by this [`Engine`] checks for fuel and interrupts at the start of every
+    /// function and loop, which is what [`Store::set_fuel`] and [`InterruptHandle`] need to stop a
+    /// call.
+    ///
+    /// [`InterruptHandle`]: crate::InterruptHandle
+    pub fn with_fuel_metering() -> Engine {
+        Self::with_options(true)
+    }
+
+    fn with_options(meters_fuel: bool) -> Engine {
         Engine {
             inner: Arc::new(EngineInner {
+                meters_fuel,
                 validators: Mutex::new(Pool::new()),
                 compilers: Mutex::new(Pool::new()),
             }),
         }
     }
 
+    /// Returns `true` if code compiled by this [`Engine`] meters fuel.
+    pub fn meters_fuel(&self) -> bool {
+        self.inner.meters_fuel
+    }
+
     pub(crate) fn validate(
         &self,
         type_: &FuncType,
@@ -63,6 +87,7 @@ fn default() -> Self {
 
 #[derive(Debug)]
 struct EngineInner {
+    meters_fuel: bool,
     validators: Mutex<Pool<Validator>>,
     compilers: Mutex<Pool<Compiler>>,
 }
@@ -8,15 +8,17 @@
         error::Error,
         extern_::UnguardedExtern,
         extern_ref::UnguardedExternRef,
-        func::{Func, FuncEntity, UnguardedFunc},
+        func::{Func, FuncEntity, FuncType, UnguardedFunc},
         func_ref::UnguardedFuncRef,
         global::UnguardedGlobal,
         mem::UnguardedMem,
         ops::*,
+        simd,
         stack::{Stack, StackGuard, StackSlot},
         store::{Handle, Store, UnguardedInternedFuncType},
         table::UnguardedTable,
         trap::Trap,
+        v128::V128,
         val::{UnguardedVal, Val},
     },
     std::{hint, mem, ptr},
@@ -158,6 +160,9 @@ pub(crate) fn exec(
     args: &[Val],
     results: &mut [Val],
 ) -> Result<(), Error> {
+    // Discard the suspended call, if any, so that its stack is released.
+    store.set_suspended_call(None);
+
     // Lock the stack for the current thread.
     let mut stack = Stack::lock();
 
@@ -173,10 +178,11 @@ pub(crate) fn exec(
     // Copy the arguments to the stack.
     let mut ptr = stack.ptr();
     for arg in args.iter().copied() {
+        let slot_count = arg.type_().slot_count();
         let arg = arg.to_unguarded(store.id());
         unsafe {
             arg.write_to_stack(ptr);
-            ptr = ptr.add(1);
+            ptr = ptr.add(slot_count);
         };
     }
 
@@ -193,16 +199,17 @@ pub(crate) fn exec(
                 unreachable!();
             };
 
-            // Create a trampoline for the [`WasmFuncEntity`].
-            let mut trampoline = [
+            // Create a trampoline for the [`WasmFuncEntity`]. The trampoline lives on the heap,
+            // because it has to outlive this call if the call is suspended.
+            let mut trampoline = Box::new([
                 call as InstrSlot,
                 code.code.as_mut_ptr() as InstrSlot,
                 type_.call_frame_size() * mem::size_of::<StackSlot>(),
                 stop as InstrSlot,
-            ];
+            ]);
 
             // Create an execution context.
-            let mut context = Context {
+            let context = Context {
                 ip: trampoline.as_mut_ptr(),
                 sp: stack.ptr(),
                 md: ptr::null_mut(),
@@ -214,63 +221,177 @@ pub(crate) fn exec(
                 stack: Some(stack),
                 error: None,
             };
+            stack = run(context, ptr, trampoline, type_)?;
+        }
+        FuncEntity::Host(func) => {
+            // Set the stack pointer to the end of the call frame.
+            stack.set_ptr(unsafe { ptr.add(type_.call_frame_size()) });
 
-            // Main interpreter loop
-            loop {
-                match ControlFlow::from_bits(unsafe {
-                    next_instr(
-                        context.ip,
-                        context.sp,
-                        context.md,
-                        context.ms,
-                        context.ix,
-                        context.sx,
-                        context.dx,
-                        &mut context as *mut _,
-                    )
-                })
-                .unwrap()
-                {
-                    ControlFlow::Stop => {
-                        stack = context.stack.take().unwrap();
+            // Call the [`HostTrampoline`] of the [`HostFuncEntity`].
+            stack = func.trampoline().clone().call(store, stack)?;
+
+            // Reset the stack to the start of the call frame.
+            stack.set_ptr(ptr);
+        }
+    }
+
+    copy_results(store, &mut stack, results);
+    Ok(())
+}
 
-                        // Reset the stack to the start of the call frame.
-                        stack.set_ptr(ptr);
+/// Resumes the given [`SuspendedCall`].
+///
+/// The results are written to the `results` slice.
+pub(crate) fn resume(
+    store: &mut Store,
+    call: SuspendedCall,
+    results: &mut [Val],
+) -> Result<(), Error> {
+    let SuspendedCall {
+        ip,
+        sp,
+        ix,
+        sx,
+        dx,
+        stack,
+        frame_ptr,
+        trampoline,
+        type_,
+    } = call;
+
+    // The memory could have grown while the call was suspended, so we reload the memory registers
+    // from the memory operand of the `consume_fuel` instruction we stopped at.
+    let (mem, _): (Option<UnguardedMem>, _) = unsafe { read_imm(ip.add(2)) };
+    let md;
+    let ms;
+    if let Some(mut mem) = mem {
+        let data = unsafe { mem.as_mut().bytes_mut() };
+        md = data.as_mut_ptr();
+        ms = data.len() as u32;
+    } else {
+        md = ptr::null_mut();
+        ms = 0;
+    }
 
-                        break;
-                    }
-                    ControlFlow::Trap(trap) => {
-                        stack = context.stack.take().unwrap();
+    let context = Context {
+        ip,
+        sp,
+        md,
+        ms,
+        ix,
+        sx,
+        dx,
+        store,
+        stack: Some(stack),
+        error: None,
+    };
+    let mut stack = run(context, frame_ptr, trampoline, type_)?;
+    copy_results(store, &mut stack, results);
+    Ok(())
+}
 
-                        // Reset the stack to the start of the call frame.
-                        stack.set_ptr(ptr);
+/// The state of a call that ran out of fuel or was interrupted.
+///
+/// A [`SuspendedCall`] holds on to the stack it was running on, and to the registers at the
+/// `consume_fuel` instruction it stopped at, so it can be resumed from that instruction.
+#[derive(Debug)]
+pub(crate) struct SuspendedCall {
+    ip: Ip,
+    sp: Sp,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    stack: StackGuard,
+    // The start of the call frame of the outermost function.
+    frame_ptr: *mut StackSlot,
+    // The trampoline that the outermost function returns to.
+    trampoline: Box<[InstrSlot; 4]>,
+    type_: FuncType,
+}
 
-                        return Err(trap)?;
-                    }
-                    ControlFlow::Error => {
-                        stack = context.stack.take().unwrap();
+impl SuspendedCall {
+    /// Returns the [`FuncType`] of the outermost function of this [`SuspendedCall`].
+    pub(crate) fn type_(&self) -> &FuncType {
+        &self.type_
+    }
+}
 
-                        // Reset the stack to the start of the call frame.
-                        stack.set_ptr(ptr);
+/// Runs the interpreter loop until the outermost function returns.
+///
+/// Returns the stack, reset to the start of the call frame, so the results can be read from it.
+fn run(
+    mut context: Context,
+    frame_ptr: *mut StackSlot,
+    trampoline: Box<[InstrSlot; 4]>,
+    type_: FuncType,
+) -> Result<StackGuard, Error> {
+    // Only the outermost call can be suspended. A call made by a host function has nowhere to
+    // resume to once the host function has returned its error.
+    let is_outermost = frame_ptr == unsafe { context.stack.as_mut().unwrap_unchecked().base_ptr() };
+
+    // Run the interpreter until it stops or traps.
+    match ControlFlow::from_bits(unsafe {
+        next_instr(
+            context.ip,
+            context.sp,
+            context.md,
+            context.ms,
+            context.ix,
+            context.sx,
+            context.dx,
+            &mut context as *mut _,
+        )
+    })
+    .unwrap()
+    {
+        ControlFlow::Stop => {
+            let mut stack = context.stack.take().unwrap();
 
-                        return Err(context.error.take().unwrap());
-                    }
-                }
-            }
+            // Reset the stack to the start of the call frame.
+            stack.set_ptr(frame_ptr);
+
+            Ok(stack)
         }
-        FuncEntity::Host(func) => {
-            // Set the stack pointer to the end of the call frame.
-            stack.set_ptr(unsafe { ptr.add(type_.call_frame_size()) });
+        ControlFlow::Trap(trap) if trap.is_resumable() && is_outermost => {
+            // Keep the stack as it is, so the call can be resumed later.
+            let call = SuspendedCall {
+                ip: context.ip,
+                sp: context.sp,
+                ix: context.ix,
+                sx: context.sx,
+                dx: context.dx,
+                stack: context.stack.take().unwrap(),
+                frame_ptr,
+                trampoline,
+                type_,
+            };
+            context.store.set_suspended_call(Some(call));
 
-            // Call the [`HostTrampoline`] of the [`HostFuncEntity`].
-            stack = func.trampoline().clone().call(store, stack)?;
+            Err(trap.into())
+        }
+        ControlFlow::Trap(trap) => {
+            let mut stack = context.stack.take().unwrap();
 
             // Reset the stack to the start of the call frame.
-            stack.set_ptr(ptr);
+            stack.set_ptr(frame_ptr);
+
+            Err(trap.into())
+        }
+        ControlFlow::Error => {
+            // If a host function returned the error, it has already released the stack, so
+            // we need to lock it again.
+            let mut stack = context.stack.take().unwrap_or_else(Stack::lock);
+
+            // Reset the stack to the start of the call frame.
+            stack.set_ptr(frame_ptr);
+
+            Err(context.error.take().unwrap())
         }
     }
+}
 
-    // Copy the results from the stack.
+/// Copies the results of a call from the stack.
+fn copy_results(store: &Store, stack: &mut StackGuard, results: &mut [Val]) {
     let mut ptr = stack.ptr();
     for result in results.iter_mut() {
         unsafe {
@@ -278,11 +399,9 @@ pub(crate) fn exec(
                 UnguardedVal::read_from_stack(ptr, result.type_()),
                 store.id(),
             );
-            ptr = ptr.add(1);
+            ptr = ptr.add(result.type_().slot_count());
         }
     }
-
-    Ok(())
 }
 
 // Helper macros
@@ -1306,6 +1425,33 @@ macro_rules! select_float {
     UnguardedExternRef
 );
 
+// Vector values are never stored in a register, so select_v128 only has an _sss variant, which
+// writes its output to the stack.
+threaded_instr!(select_v128_sss(
+    ip: Ip,
+    sp: Sp,
+    md: Md,
+    ms: Ms,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    cx: Cx,
+) -> ControlFlowBits {
+    // Read operands
+    let (cond, ip): (u32, _) = read_stack(ip, sp);
+    let (x1, ip): (V128, _) = read_stack(ip, sp);
+    let (x0, ip): (V128, _) = read_stack(ip, sp);
+
+    // Perform operation
+    let y = if cond != 0 { x0 } else { x1 };
+
+    // Write result
+    let ip = write_stack(ip, sp, y);
+
+    // Execute next instruction
+    next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+});
+
 // Variable instructions
 
 macro_rules! global_get {
@@ -1343,6 +1489,7 @@ macro_rules! global_get {
 global_get!(global_get_i64, i64);
 global_get!(global_get_f32, f32);
 global_get!(global_get_f64, f64);
+global_get!(global_get_v128, V128);
 global_get!(global_get_func_ref, UnguardedFuncRef);
 global_get!(global_get_extern_ref, UnguardedExternRef);
 
@@ -1442,6 +1589,58 @@ macro_rules! global_set {
     UnguardedExternRef
 );
 
+// Vector globals are never stored in a register, so global_set_v128 does not have an _r variant.
+
+threaded_instr!(global_set_v128_s(
+    ip: Ip,
+    sp: Sp,
+    md: Md,
+    ms: Ms,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    cx: Cx,
+) -> ControlFlowBits {
+    // Read operands
+    let (val, ip) = read_stack(ip, sp);
+    let (mut global, ip): (UnguardedGlobal, _) = read_imm(ip);
+
+    // Perform operation
+    global
+        .as_mut()
+        .downcast_mut::<V128>()
+        .unwrap_unchecked()
+        .set(val);
+
+    // Execute next instruction
+    next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+});
+
+threaded_instr!(global_set_v128_i(
+    ip: Ip,
+    sp: Sp,
+    md: Md,
+    ms: Ms,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    cx: Cx,
+) -> ControlFlowBits {
+    // Read operands
+    let (val, ip) = read_imm(ip);
+    let (mut global, ip): (UnguardedGlobal, _) = read_imm(ip);
+
+    // Perform operation
+    global
+        .as_mut()
+        .downcast_mut::<V128>()
+        .unwrap_unchecked()
+        .set(val);
+
+    // Execute next instruction
+    next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+});
+
 // Table instructions
 
 macro_rules! table_get {
@@ -3817,6 +4016,578 @@ macro_rules! bin_op_noncommutative {
     <u64 as Trunc<f64>>::trunc_sat
 );
 
+// Vector instructions
+
+threaded_instr!(i8x16_shuffle_ss(
+    ip: Ip,
+    sp: Sp,
+    md: Md,
+    ms: Ms,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    cx: Cx,
+) -> ControlFlowBits {
+    // Read operands
+    let (x1, ip) = read_stack(ip, sp);
+    let (x0, ip) = read_stack(ip, sp);
+    let (lane_idxs, ip) = read_imm(ip);
+
+    // Perform operation
+    let y = r#try!(simd::i8x16_shuffle(x0, x1, lane_idxs));
+
+    // Write result
+    let ip = write_stack(ip, sp, y);
+
+    // Execute next instruction
+    next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+});
+
+macro_rules! vec_op_s {
+    ($vec_op_s:ident, $f:expr) => {
+        threaded_instr!($vec_op_s(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x, ip) = read_stack(ip, sp);
+
+            // Perform operation
+            let y = r#try!($f(x));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_op_ss {
+    ($vec_op_ss:ident, $f:expr) => {
+        threaded_instr!($vec_op_ss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x1, ip) = read_stack(ip, sp);
+            let (x0, ip) = read_stack(ip, sp);
+
+            // Perform operation
+            let y = r#try!($f(x0, x1));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_op_sss {
+    ($vec_op_sss:ident, $f:expr) => {
+        threaded_instr!($vec_op_sss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x2, ip) = read_stack(ip, sp);
+            let (x1, ip) = read_stack(ip, sp);
+            let (x0, ip) = read_stack(ip, sp);
+
+            // Perform operation
+            let y = r#try!($f(x0, x1, x2));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_lane_op_s {
+    ($vec_lane_op_s:ident, $f:expr) => {
+        threaded_instr!($vec_lane_op_s(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x, ip) = read_stack(ip, sp);
+            let (lane_idx, ip): (u8, _) = read_imm(ip);
+
+            // Perform operation
+            let y = r#try!($f(x, lane_idx));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_lane_op_ss {
+    ($vec_lane_op_ss:ident, $f:expr) => {
+        threaded_instr!($vec_lane_op_ss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x1, ip) = read_stack(ip, sp);
+            let (x0, ip) = read_stack(ip, sp);
+            let (lane_idx, ip): (u8, _) = read_imm(ip);
+
+            // Perform operation
+            let y = r#try!($f(x0, x1, lane_idx));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_load {
+    ($vec_load_s:ident, $N:literal, $f:expr) => {
+        threaded_instr!($vec_load_s(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (dyn_offset, ip): (u32, _) = read_stack(ip, sp);
+            let (static_offset, ip): (u32, _) = read_imm(ip);
+
+            // Perform operation
+            let offset = dyn_offset as u64 + static_offset as u64;
+            if offset + $N > ms as u64 {
+                return ControlFlow::Trap(Trap::MemAccessOutOfBounds).to_bits();
+            }
+            let mut bytes = [0u8; $N];
+            ptr::copy_nonoverlapping(md.add(offset as usize), bytes.as_mut_ptr(), bytes.len());
+            let y = r#try!($f(bytes));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_load_lane {
+    ($vec_load_lane_ss:ident, $N:literal, $f:expr) => {
+        threaded_instr!($vec_load_lane_ss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x, ip) = read_stack(ip, sp);
+            let (dyn_offset, ip): (u32, _) = read_stack(ip, sp);
+            let (static_offset, ip): (u32, _) = read_imm(ip);
+            let (lane_idx, ip): (u8, _) = read_imm(ip);
+
+            // Perform operation
+            let offset = dyn_offset as u64 + static_offset as u64;
+            if offset + $N > ms as u64 {
+                return ControlFlow::Trap(Trap::MemAccessOutOfBounds).to_bits();
+            }
+            let mut bytes = [0u8; $N];
+            ptr::copy_nonoverlapping(md.add(offset as usize), bytes.as_mut_ptr(), bytes.len());
+            let y = r#try!($f(x, bytes, lane_idx));
+
+            // Write result
+            let ip = write_stack(ip, sp, y);
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_store {
+    ($vec_store_ss:ident, $f:expr) => {
+        threaded_instr!($vec_store_ss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x, ip) = read_stack(ip, sp);
+            let (dyn_offset, ip): (u32, _) = read_stack(ip, sp);
+            let (static_offset, ip): (u32, _) = read_imm(ip);
+
+            // Perform operation
+            let bytes = r#try!($f(x));
+            let offset = dyn_offset as u64 + static_offset as u64;
+            if offset + bytes.len() as u64 > ms as u64 {
+                return ControlFlow::Trap(Trap::MemAccessOutOfBounds).to_bits();
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), md.add(offset as usize), bytes.len());
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+macro_rules! vec_store_lane {
+    ($vec_store_lane_ss:ident, $f:expr) => {
+        threaded_instr!($vec_store_lane_ss(
+            ip: Ip,
+            sp: Sp,
+            md: Md,
+            ms: Ms,
+            ix: Ix,
+            sx: Sx,
+            dx: Dx,
+            cx: Cx,
+        ) -> ControlFlowBits {
+            // Read operands
+            let (x, ip) = read_stack(ip, sp);
+            let (dyn_offset, ip): (u32, _) = read_stack(ip, sp);
+            let (static_offset, ip): (u32, _) = read_imm(ip);
+            let (lane_idx, ip): (u8, _) = read_imm(ip);
+
+            // Perform operation
+            let bytes = r#try!($f(x, lane_idx));
+            let offset = dyn_offset as u64 + static_offset as u64;
+            if offset + bytes.len() as u64 > ms as u64 {
+                return ControlFlow::Trap(Trap::MemAccessOutOfBounds).to_bits();
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), md.add(offset as usize), bytes.len());
+
+            // Execute next instruction
+            next_instr(ip, sp, md, ms, ix, sx, dx, cx)
+        });
+    };
+}
+
+vec_load!(v128_load_s, 16, simd::v128_load);
+vec_load!(v128_load8x8_s_s, 8, simd::v128_load8x8_s);
+vec_load!(v128_load8x8_u_s, 8, simd::v128_load8x8_u);
+vec_load!(v128_load16x4_s_s, 8, simd::v128_load16x4_s);
+vec_load!(v128_load16x4_u_s, 8, simd::v128_load16x4_u);
+vec_load!(v128_load32x2_s_s, 8, simd::v128_load32x2_s);
+vec_load!(v128_load32x2_u_s, 8, simd::v128_load32x2_u);
+vec_load!(v128_load8_splat_s, 1, simd::v128_load8_splat);
+vec_load!(v128_load16_splat_s, 2, simd::v128_load16_splat);
+vec_load!(v128_load32_splat_s, 4, simd::v128_load32_splat);
+vec_load!(v128_load64_splat_s, 8, simd::v128_load64_splat);
+vec_store!(v128_store_ss, simd::v128_store);
+vec_op_ss!(i8x16_swizzle_ss, simd::i8x16_swizzle);
+vec_op_s!(i8x16_splat_s, simd::i8x16_splat);
+vec_op_s!(i16x8_splat_s, simd::i16x8_splat);
+vec_op_s!(i32x4_splat_s, simd::i32x4_splat);
+vec_op_s!(i64x2_splat_s, simd::i64x2_splat);
+vec_op_s!(f32x4_splat_s, simd::f32x4_splat);
+vec_op_s!(f64x2_splat_s, simd::f64x2_splat);
+vec_lane_op_s!(i8x16_extract_lane_s_s, simd::i8x16_extract_lane_s);
+vec_lane_op_s!(i8x16_extract_lane_u_s, simd::i8x16_extract_lane_u);
+vec_lane_op_ss!(i8x16_replace_lane_ss, simd::i8x16_replace_lane);
+vec_lane_op_s!(i16x8_extract_lane_s_s, simd::i16x8_extract_lane_s);
+vec_lane_op_s!(i16x8_extract_lane_u_s, simd::i16x8_extract_lane_u);
+vec_lane_op_ss!(i16x8_replace_lane_ss, simd::i16x8_replace_lane);
+vec_lane_op_s!(i32x4_extract_lane_s, simd::i32x4_extract_lane);
+vec_lane_op_ss!(i32x4_replace_lane_ss, simd::i32x4_replace_lane);
+vec_lane_op_s!(i64x2_extract_lane_s, simd::i64x2_extract_lane);
+vec_lane_op_ss!(i64x2_replace_lane_ss, simd::i64x2_replace_lane);
+vec_lane_op_s!(f32x4_extract_lane_s, simd::f32x4_extract_lane);
+vec_lane_op_ss!(f32x4_replace_lane_ss, simd::f32x4_replace_lane);
+vec_lane_op_s!(f64x2_extract_lane_s, simd::f64x2_extract_lane);
+vec_lane_op_ss!(f64x2_replace_lane_ss, simd::f64x2_replace_lane);
+vec_op_ss!(i8x16_eq_ss, simd::i8x16_eq);
+vec_op_ss!(i8x16_ne_ss, simd::i8x16_ne);
+vec_op_ss!(i8x16_lt_s_ss, simd::i8x16_lt_s);
+vec_op_ss!(i8x16_lt_u_ss, simd::i8x16_lt_u);
+vec_op_ss!(i8x16_gt_s_ss, simd::i8x16_gt_s);
+vec_op_ss!(i8x16_gt_u_ss, simd::i8x16_gt_u);
+vec_op_ss!(i8x16_le_s_ss, simd::i8x16_le_s);
+vec_op_ss!(i8x16_le_u_ss, simd::i8x16_le_u);
+vec_op_ss!(i8x16_ge_s_ss, simd::i8x16_ge_s);
+vec_op_ss!(i8x16_ge_u_ss, simd::i8x16_ge_u);
+vec_op_ss!(i16x8_eq_ss, simd::i16x8_eq);
+vec_op_ss!(i16x8_ne_ss, simd::i16x8_ne);
+vec_op_ss!(i16x8_lt_s_ss, simd::i16x8_lt_s);
+vec_op_ss!(i16x8_lt_u_ss, simd::i16x8_lt_u);
+vec_op_ss!(i16x8_gt_s_ss, simd::i16x8_gt_s);
+vec_op_ss!(i16x8_gt_u_ss, simd::i16x8_gt_u);
+vec_op_ss!(i16x8_le_s_ss, simd::i16x8_le_s);
+vec_op_ss!(i16x8_le_u_ss, simd::i16x8_le_u);
+vec_op_ss!(i16x8_ge_s_ss, simd::i16x8_ge_s);
+vec_op_ss!(i16x8_ge_u_ss, simd::i16x8_ge_u);
+vec_op_ss!(i32x4_eq_ss, simd::i32x4_eq);
+vec_op_ss!(i32x4_ne_ss, simd::i32x4_ne);
+vec_op_ss!(i32x4_lt_s_ss, simd::i32x4_lt_s);
+vec_op_ss!(i32x4_lt_u_ss, simd::i32x4_lt_u);
+vec_op_ss!(i32x4_gt_s_ss, simd::i32x4_gt_s);
+vec_op_ss!(i32x4_gt_u_ss, simd::i32x4_gt_u);
+vec_op_ss!(i32x4_le_s_ss, simd::i32x4_le_s);
+vec_op_ss!(i32x4_le_u_ss, simd::i32x4_le_u);
+vec_op_ss!(i32x4_ge_s_ss, simd::i32x4_ge_s);
+vec_op_ss!(i32x4_ge_u_ss, simd::i32x4_ge_u);
+vec_op_ss!(f32x4_eq_ss, simd::f32x4_eq);
+vec_op_ss!(f32x4_ne_ss, simd::f32x4_ne);
+vec_op_ss!(f32x4_lt_ss, simd::f32x4_lt);
+vec_op_ss!(f32x4_gt_ss, simd::f32x4_gt);
+vec_op_ss!(f32x4_le_ss, simd::f32x4_le);
+vec_op_ss!(f32x4_ge_ss, simd::f32x4_ge);
+vec_op_ss!(f64x2_eq_ss, simd::f64x2_eq);
+vec_op_ss!(f64x2_ne_ss, simd::f64x2_ne);
+vec_op_ss!(f64x2_lt_ss, simd::f64x2_lt);
+vec_op_ss!(f64x2_gt_ss, simd::f64x2_gt);
+vec_op_ss!(f64x2_le_ss, simd::f64x2_le);
+vec_op_ss!(f64x2_ge_ss, simd::f64x2_ge);
+vec_op_s!(v128_not_s, simd::v128_not);
+vec_op_ss!(v128_and_ss, simd::v128_and);
+vec_op_ss!(v128_andnot_ss, simd::v128_andnot);
+vec_op_ss!(v128_or_ss, simd::v128_or);
+vec_op_ss!(v128_xor_ss, simd::v128_xor);
+vec_op_sss!(v128_bitselect_sss, simd::v128_bitselect);
+vec_op_s!(v128_any_true_s, simd::v128_any_true);
+vec_load_lane!(v128_load8_lane_ss, 1, simd::v128_load8_lane);
+vec_load_lane!(v128_load16_lane_ss, 2, simd::v128_load16_lane);
+vec_load_lane!(v128_load32_lane_ss, 4, simd::v128_load32_lane);
+vec_load_lane!(v128_load64_lane_ss, 8, simd::v128_load64_lane);
+vec_store_lane!(v128_store8_lane_ss, simd::v128_store8_lane);
+vec_store_lane!(v128_store16_lane_ss, simd::v128_store16_lane);
+vec_store_lane!(v128_store32_lane_ss, simd::v128_store32_lane);
+vec_store_lane!(v128_store64_lane_ss, simd::v128_store64_lane);
+vec_load!(v128_load32_zero_s, 4, simd::v128_load32_zero);
+vec_load!(v128_load64_zero_s, 8, simd::v128_load64_zero);
+vec_op_s!(f32x4_demote_f64x2_zero_s, simd::f32x4_demote_f64x2_zero);
+vec_op_s!(f64x2_promote_low_f32x4_s, simd::f64x2_promote_low_f32x4);
+vec_op_s!(i8x16_abs_s, simd::i8x16_abs);
+vec_op_s!(i8x16_neg_s, simd::i8x16_neg);
+vec_op_s!(i8x16_popcnt_s, simd::i8x16_popcnt);
+vec_op_s!(i8x16_all_true_s, simd::i8x16_all_true);
+vec_op_s!(i8x16_bitmask_s, simd::i8x16_bitmask);
+vec_op_ss!(i8x16_narrow_i16x8_s_ss, simd::i8x16_narrow_i16x8_s);
+vec_op_ss!(i8x16_narrow_i16x8_u_ss, simd::i8x16_narrow_i16x8_u);
+vec_op_s!(f32x4_ceil_s, simd::f32x4_ceil);
+vec_op_s!(f32x4_floor_s, simd::f32x4_floor);
+vec_op_s!(f32x4_trunc_s, simd::f32x4_trunc);
+vec_op_s!(f32x4_nearest_s, simd::f32x4_nearest);
+vec_op_ss!(i8x16_shl_ss, simd::i8x16_shl);
+vec_op_ss!(i8x16_shr_s_ss, simd::i8x16_shr_s);
+vec_op_ss!(i8x16_shr_u_ss, simd::i8x16_shr_u);
+vec_op_ss!(i8x16_add_ss, simd::i8x16_add);
+vec_op_ss!(i8x16_add_sat_s_ss, simd::i8x16_add_sat_s);
+vec_op_ss!(i8x16_add_sat_u_ss, simd::i8x16_add_sat_u);
+vec_op_ss!(i8x16_sub_ss, simd::i8x16_sub);
+vec_op_ss!(i8x16_sub_sat_s_ss, simd::i8x16_sub_sat_s);
+vec_op_ss!(i8x16_sub_sat_u_ss, simd::i8x16_sub_sat_u);
+vec_op_s!(f64x2_ceil_s, simd::f64x2_ceil);
+vec_op_s!(f64x2_floor_s, simd::f64x2_floor);
+vec_op_ss!(i8x16_min_s_ss, simd::i8x16_min_s);
+vec_op_ss!(i8x16_min_u_ss, simd::i8x16_min_u);
+vec_op_ss!(i8x16_max_s_ss, simd::i8x16_max_s);
+vec_op_ss!(i8x16_max_u_ss, simd::i8x16_max_u);
+vec_op_s!(f64x2_trunc_s, simd::f64x2_trunc);
+vec_op_ss!(i8x16_avgr_u_ss, simd::i8x16_avgr_u);
+vec_op_s!(
+    i16x8_extadd_pairwise_i8x16_s_s,
+    simd::i16x8_extadd_pairwise_i8x16_s
+);
+vec_op_s!(
+    i16x8_extadd_pairwise_i8x16_u_s,
+    simd::i16x8_extadd_pairwise_i8x16_u
+);
+vec_op_s!(
+    i32x4_extadd_pairwise_i16x8_s_s,
+    simd::i32x4_extadd_pairwise_i16x8_s
+);
+vec_op_s!(
+    i32x4_extadd_pairwise_i16x8_u_s,
+    simd::i32x4_extadd_pairwise_i16x8_u
+);
+vec_op_s!(i16x8_abs_s, simd::i16x8_abs);
+vec_op_s!(i16x8_neg_s, simd::i16x8_neg);
+vec_op_ss!(i16x8_q15mulr_sat_s_ss, simd::i16x8_q15mulr_sat_s);
+vec_op_s!(i16x8_all_true_s, simd::i16x8_all_true);
+vec_op_s!(i16x8_bitmask_s, simd::i16x8_bitmask);
+vec_op_ss!(i16x8_narrow_i32x4_s_ss, simd::i16x8_narrow_i32x4_s);
+vec_op_ss!(i16x8_narrow_i32x4_u_ss, simd::i16x8_narrow_i32x4_u);
+vec_op_s!(i16x8_extend_low_i8x16_s_s, simd::i16x8_extend_low_i8x16_s);
+vec_op_s!(i16x8_extend_high_i8x16_s_s, simd::i16x8_extend_high_i8x16_s);
+vec_op_s!(i16x8_extend_low_i8x16_u_s, simd::i16x8_extend_low_i8x16_u);
+vec_op_s!(i16x8_extend_high_i8x16_u_s, simd::i16x8_extend_high_i8x16_u);
+vec_op_ss!(i16x8_shl_ss, simd::i16x8_shl);
+vec_op_ss!(i16x8_shr_s_ss, simd::i16x8_shr_s);
+vec_op_ss!(i16x8_shr_u_ss, simd::i16x8_shr_u);
+vec_op_ss!(i16x8_add_ss, simd::i16x8_add);
+vec_op_ss!(i16x8_add_sat_s_ss, simd::i16x8_add_sat_s);
+vec_op_ss!(i16x8_add_sat_u_ss, simd::i16x8_add_sat_u);
+vec_op_ss!(i16x8_sub_ss, simd::i16x8_sub);
+vec_op_ss!(i16x8_sub_sat_s_ss, simd::i16x8_sub_sat_s);
+vec_op_ss!(i16x8_sub_sat_u_ss, simd::i16x8_sub_sat_u);
+vec_op_s!(f64x2_nearest_s, simd::f64x2_nearest);
+vec_op_ss!(i16x8_mul_ss, simd::i16x8_mul);
+vec_op_ss!(i16x8_min_s_ss, simd::i16x8_min_s);
+vec_op_ss!(i16x8_min_u_ss, simd::i16x8_min_u);
+vec_op_ss!(i16x8_max_s_ss, simd::i16x8_max_s);
+vec_op_ss!(i16x8_max_u_ss, simd::i16x8_max_u);
+vec_op_ss!(i16x8_avgr_u_ss, simd::i16x8_avgr_u);
+vec_op_ss!(i16x8_extmul_low_i8x16_s_ss, simd::i16x8_extmul_low_i8x16_s);
+vec_op_ss!(
+    i16x8_extmul_high_i8x16_s_ss,
+    simd::i16x8_extmul_high_i8x16_s
+);
+vec_op_ss!(i16x8_extmul_low_i8x16_u_ss, simd::i16x8_extmul_low_i8x16_u);
+vec_op_ss!(
+    i16x8_extmul_high_i8x16_u_ss,
+    simd::i16x8_extmul_high_i8x16_u
+);
+vec_op_s!(i32x4_abs_s, simd::i32x4_abs);
+vec_op_s!(i32x4_neg_s, simd::i32x4_neg);
+vec_op_s!(i32x4_all_true_s, simd::i32x4_all_true);
+vec_op_s!(i32x4_bitmask_s, simd::i32x4_bitmask);
+vec_op_s!(i32x4_extend_low_i16x8_s_s, simd::i32x4_extend_low_i16x8_s);
+vec_op_s!(i32x4_extend_high_i16x8_s_s, simd::i32x4_extend_high_i16x8_s);
+vec_op_s!(i32x4_extend_low_i16x8_u_s, simd::i32x4_extend_low_i16x8_u);
+vec_op_s!(i32x4_extend_high_i16x8_u_s, simd::i32x4_extend_high_i16x8_u);
+vec_op_ss!(i32x4_shl_ss, simd::i32x4_shl);
+vec_op_ss!(i32x4_shr_s_ss, simd::i32x4_shr_s);
+vec_op_ss!(i32x4_shr_u_ss, simd::i32x4_shr_u);
+vec_op_ss!(i32x4_add_ss, simd::i32x4_add);
+vec_op_ss!(i32x4_sub_ss, simd::i32x4_sub);
+vec_op_ss!(i32x4_mul_ss, simd::i32x4_mul);
+vec_op_ss!(i32x4_min_s_ss, simd::i32x4_min_s);
+vec_op_ss!(i32x4_min_u_ss, simd::i32x4_min_u);
+vec_op_ss!(i32x4_max_s_ss, simd::i32x4_max_s);
+vec_op_ss!(i32x4_max_u_ss, simd::i32x4_max_u);
+vec_op_ss!(i32x4_dot_i16x8_s_ss, simd::i32x4_dot_i16x8_s);
+vec_op_ss!(i32x4_extmul_low_i16x8_s_ss, simd::i32x4_extmul_low_i16x8_s);
+vec_op_ss!(
+    i32x4_extmul_high_i16x8_s_ss,
+    simd::i32x4_extmul_high_i16x8_s
+);
+vec_op_ss!(i32x4_extmul_low_i16x8_u_ss, simd::i32x4_extmul_low_i16x8_u);
+vec_op_ss!(
+    i32x4_extmul_high_i16x8_u_ss,
+    simd::i32x4_extmul_high_i16x8_u
+);
+vec_op_s!(i64x2_abs_s, simd::i64x2_abs);
+vec_op_s!(i64x2_neg_s, simd::i64x2_neg);
+vec_op_s!(i64x2_all_true_s, simd::i64x2_all_true);
+vec_op_s!(i64x2_bitmask_s, simd::i64x2_bitmask);
+vec_op_s!(i64x2_extend_low_i32x4_s_s, simd::i64x2_extend_low_i32x4_s);
+vec_op_s!(i64x2_extend_high_i32x4_s_s, simd::i64x2_extend_high_i32x4_s);
+vec_op_s!(i64x2_extend_low_i32x4_u_s, simd::i64x2_extend_low_i32x4_u);
+vec_op_s!(i64x2_extend_high_i32x4_u_s, simd::i64x2_extend_high_i32x4_u);
+vec_op_ss!(i64x2_shl_ss, simd::i64x2_shl);
+vec_op_ss!(i64x2_shr_s_ss, simd::i64x2_shr_s);
+vec_op_ss!(i64x2_shr_u_ss, simd::i64x2_shr_u);
+vec_op_ss!(i64x2_add_ss, simd::i64x2_add);
+vec_op_ss!(i64x2_sub_ss, simd::i64x2_sub);
+vec_op_ss!(i64x2_mul_ss, simd::i64x2_mul);
+vec_op_ss!(i64x2_eq_ss, simd::i64x2_eq);
+vec_op_ss!(i64x2_ne_ss, simd::i64x2_ne);
+vec_op_ss!(i64x2_lt_s_ss, simd::i64x2_lt_s);
+vec_op_ss!(i64x2_gt_s_ss, simd::i64x2_gt_s);
+vec_op_ss!(i64x2_le_s_ss, simd::i64x2_le_s);
+vec_op_ss!(i64x2_ge_s_ss, simd::i64x2_ge_s);
+vec_op_ss!(i64x2_extmul_low_i32x4_s_ss, simd::i64x2_extmul_low_i32x4_s);
+vec_op_ss!(
+    i64x2_extmul_high_i32x4_s_ss,
+    simd::i64x2_extmul_high_i32x4_s
+);
+vec_op_ss!(i64x2_extmul_low_i32x4_u_ss, simd::i64x2_extmul_low_i32x4_u);
+vec_op_ss!(
+    i64x2_extmul_high_i32x4_u_ss,
+    simd::i64x2_extmul_high_i32x4_u
+);
+vec_op_s!(f32x4_abs_s, simd::f32x4_abs);
+vec_op_s!(f32x4_neg_s, simd::f32x4_neg);
+vec_op_s!(f32x4_sqrt_s, simd::f32x4_sqrt);
+vec_op_ss!(f32x4_add_ss, simd::f32x4_add);
+vec_op_ss!(f32x4_sub_ss, simd::f32x4_sub);
+vec_op_ss!(f32x4_mul_ss, simd::f32x4_mul);
+vec_op_ss!(f32x4_div_ss, simd::f32x4_div);
+vec_op_ss!(f32x4_min_ss, simd::f32x4_min);
+vec_op_ss!(f32x4_max_ss, simd::f32x4_max);
+vec_op_ss!(f32x4_pmin_ss, simd::f32x4_pmin);
+vec_op_ss!(f32x4_pmax_ss, simd::f32x4_pmax);
+vec_op_s!(f64x2_abs_s, simd::f64x2_abs);
+vec_op_s!(f64x2_neg_s, simd::f64x2_neg);
+vec_op_s!(f64x2_sqrt_s, simd::f64x2_sqrt);
+vec_op_ss!(f64x2_add_ss, simd::f64x2_add);
+vec_op_ss!(f64x2_sub_ss, simd::f64x2_sub);
+vec_op_ss!(f64x2_mul_ss, simd::f64x2_mul);
+vec_op_ss!(f64x2_div_ss, simd::f64x2_div);
+vec_op_ss!(f64x2_min_ss, simd::f64x2_min);
+vec_op_ss!(f64x2_max_ss, simd::f64x2_max);
+vec_op_ss!(f64x2_pmin_ss, simd::f64x2_pmin);
+vec_op_ss!(f64x2_pmax_ss, simd::f64x2_pmax);
+vec_op_s!(i32x4_trunc_sat_f32x4_s_s, simd::i32x4_trunc_sat_f32x4_s);
+vec_op_s!(i32x4_trunc_sat_f32x4_u_s, simd::i32x4_trunc_sat_f32x4_u);
+vec_op_s!(f32x4_convert_i32x4_s_s, simd::f32x4_convert_i32x4_s);
+vec_op_s!(f32x4_convert_i32x4_u_s, simd::f32x4_convert_i32x4_u);
+vec_op_s!(
+    i32x4_trunc_sat_f64x2_s_zero_s,
+    simd::i32x4_trunc_sat_f64x2_s_zero
+);
+vec_op_s!(
+    i32x4_trunc_sat_f64x2_u_zero_s,
+    simd::i32x4_trunc_sat_f64x2_u_zero
+);
+vec_op_s!(f64x2_convert_low_i32x4_s_s, simd::f64x2_convert_low_i32x4_s);
+vec_op_s!(f64x2_convert_low_i32x4_u_s, simd::f64x2_convert_low_i32x4_u);
+
 // Miscellaneous instructions
 
 macro_rules! copy_imm_to_stack {
@@ -3847,6 +4618,7 @@ macro_rules! copy_imm_to_stack {
 copy_imm_to_stack!(copy_imm_to_stack_i64, i64);
 copy_imm_to_stack!(copy_imm_to_stack_f32, f32);
 copy_imm_to_stack!(copy_imm_to_stack_f64, f64);
+copy_imm_to_stack!(copy_imm_to_stack_v128, V128);
 copy_imm_to_stack!(copy_imm_to_stack_func_ref, UnguardedFuncRef);
 copy_imm_to_stack!(copy_imm_to_stack_extern_ref, UnguardedExternRef);
 
@@ -3878,6 +4650,7 @@ macro_rules! copy_stack {
 copy_stack!(copy_stack_i64, i64);
 copy_stack!(copy_stack_f32, f32);
 copy_stack!(copy_stack_f64, f64);
+copy_stack!(copy_stack_v128, V128);
 copy_stack!(copy_stack_func_ref, UnguardedFuncRef);
 copy_stack!(copy_stack_extern_ref, UnguardedExternRef);
 
@@ -3992,6 +4765,36 @@ macro_rules! copy_reg_to_stack {
     next_instr(ip, sp, md, ms, ix, sx, dx, cx)
 });
 
+threaded_instr!(consume_fuel(
+    ip: Ip,
+    sp: Sp,
+    md: Md,
+    ms: Ms,
+    ix: Ix,
+    sx: Sx,
+    dx: Dx,
+    cx: Cx,
+) -> ControlFlowBits {
+    // Read operands
+    let (cost, next_ip): (usize, _) = read_imm(ip);
+    let (_mem, next_ip): (Option<UnguardedMem>, _) = read_imm(next_ip);
+
+    if let Err(trap) = (*cx).store.consume_fuel(cost as u64) {
+        // Save the registers, so the call can be resumed from this instruction.
+        (*cx).ip = ip.sub(1);
+        (*cx).sp = sp;
+        (*cx).md = md;
+        (*cx).ms = ms;
+        (*cx).ix = ix;
+        (*cx).sx = sx;
+        (*cx).dx = dx;
+        return ControlFlow::Trap(trap).to_bits();
+    }
+
+    // Execute the next instruction.
+    next_instr(next_ip, sp, md, ms, ix, sx, dx, cx)
+});
+
 // Helper functions
 
 /// Executes the next instruction.
@@ -4010,12 +4813,14 @@ pub(crate) unsafe fn next_instr(
 }
 
 /// Reads an immediate value.
+///
+/// Immediate values that are larger than an [`InstrSlot`] span multiple consecutive slots.
 unsafe fn read_imm<T>(ip: Ip) -> (T, Ip)
 where
     T: Copy,
 {
     let val = *ip.cast();
-    let ip = ip.add(1);
+    let ip = ip.add(mem::size_of::<T>().div_ceil(mem::size_of::<InstrSlot>()));
     (val, ip)
 }
 
@@ -145,12 +145,13 @@ pub fn results(&self) -> &[ValType] {
     /// [`FuncType`] [] -> [`ValType`?].
     pub(crate) fn from_val_type(type_: Option<ValType>) -> FuncType {
         thread_local! {
-            static TYPES: [FuncType; 7] = [
+            static TYPES: [FuncType; 8] = [
                 FuncType::new(vec![], vec![]),
                 FuncType::new(vec![], vec![ValType::I32]),
                 FuncType::new(vec![], vec![ValType::I64]),
                 FuncType::new(vec![], vec![ValType::F32]),
                 FuncType::new(vec![], vec![ValType::F64]),
+                FuncType::new(vec![], vec![ValType::V128]),
                 FuncType::new(vec![], vec![ValType::FuncRef]),
                 FuncType::new(vec![], vec![ValType::ExternRef]),
             ];
@@ -162,15 +163,18 @@ pub(crate) fn from_val_type(type_: Option<ValType>) -> FuncType {
             Some(ValType::I64) => types[2].clone(),
             Some(ValType::F32) => types[3].clone(),
             Some(ValType::F64) => types[4].clone(),
-            Some(ValType::FuncRef) => types[5].clone(),
-            Some(ValType::ExternRef) => types[6].clone(),
+            Some(ValType::V128) => types[5].clone(),
+            Some(ValType::FuncRef) => types[6].clone(),
+            Some(ValType::ExternRef) => types[7].clone(),
         })
     }
 
     /// Returns the size of a call frame for a function with this [`FuncType`], in number of
     /// [`StackSlot`]s.
     pub(crate) fn call_frame_size(&self) -> usize {
-        self.params().len().max(self.results().len()) + 4
+        let param_slot_count: usize = self.params().iter().map(|type_| type_.slot_count()).sum();
+        let result_slot_count: usize = self.results().iter().map(|type_| type_.slot_count()).sum();
+        param_slot_count.max(result_slot_count) + 4
     }
 }
 
@@ -199,6 +203,7 @@ pub enum FuncError {
     ParamCountMismatch,
     ParamTypeMismatch,
     ResultCountMismatch,
+    NoSuspendedCall,
 }
 
 impl fmt::Display for FuncError {
@@ -207,6 +212,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             Self::ParamCountMismatch => write!(f, "function parameter count mismatch"),
             Self::ParamTypeMismatch => write!(f, "function parameter type mismatch"),
             Self::ResultCountMismatch => write!(f, "function result count mismatch"),
+            Self::NoSuspendedCall => write!(f, "no suspended call to resume"),
         }
     }
 }
@@ -5,6 +5,7 @@
     func_ref::{FuncRef, UnguardedFuncRef},
     stack::{Stack, StackGuard, StackSlot},
     store::{Store, StoreId},
+    v128::V128,
     val::ValType,
 };
 
@@ -220,13 +221,13 @@ fn type_() -> ValType {
 
             unsafe fn read_from_stack(ptr: &mut *mut StackSlot, _store_id: StoreId) -> Self {
                 let val = *ptr.cast::<$T>();
-                *ptr = ptr.add(1);
+                *ptr = ptr.add(ValType::$ValType.slot_count());
                 val
             }
 
             unsafe fn write_to_stack(self, ptr: &mut *mut StackSlot, _store_id: StoreId) {
                 *ptr.cast::<$T>() = self;
-                *ptr = ptr.add(1);
+                *ptr = ptr.add(ValType::$ValType.slot_count());
             }
         }
     };
@@ -241,13 +242,13 @@ fn type_() -> ValType {
 
             unsafe fn read_from_stack(ptr: &mut *mut StackSlot, store_id: StoreId) -> Self {
                 let val = <$T>::from_unguarded(*ptr.cast::<$RawT>(), store_id);
-                *ptr = ptr.add(1);
+                *ptr = ptr.add(ValType::$ValType.slot_count());
                 val
             }
 
             unsafe fn write_to_stack(self, ptr: &mut *mut StackSlot, store_id: StoreId) {
                 *ptr.cast::<$RawT>() = self.to_unguarded(store_id);
-                *ptr = ptr.add(1);
+                *ptr = ptr.add(ValType::$ValType.slot_count());
             }
         }
     };
@@ -259,5 +260,6 @@ unsafe fn write_to_stack(self, ptr: &mut *mut StackSlot, store_id: StoreId) {
 impl_host_val!(u64, I64);
 impl_host_val!(f32, F32);
 impl_host_val!(f64, F64);
+impl_host_val!(V128, V128);
 impl_host_val_raw!(FuncRef, UnguardedFuncRef, FuncRef);
 impl_host_val_raw!(ExternRef, UnguardedExternRef, ExternRef);
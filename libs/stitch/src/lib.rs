@@ -24,12 +24,16 @@
 mod module;
 mod ops;
 mod ref_;
+mod simd;
 mod stack;
 mod store;
 mod table;
 mod trap;
+mod v128;
 mod val;
 mod validate;
+#[cfg(feature = "wasi")]
+pub mod wasi;
 
 pub use self::{
     decode::DecodeError,
@@ -46,7 +50,9 @@
     mem::{Mem, MemError, MemType},
     module::{Module, ModuleExports, ModuleImports},
     ref_::{Ref, RefType},
-    store::Store,
+    store::{InterruptHandle, Store},
     table::{Table, TableError, TableType},
+    trap::Trap,
+    v128::V128,
     val::{Val, ValType},
 };
@@ -1,18 +1,24 @@
 use {
-    makepad_stitch::{Engine, Linker, Module, Store, Val, ValType},
-    std::{env, fs},
+    makepad_stitch::{wasi::Wasi, Engine, Linker, Module, Store, Val, ValType, V128},
+    std::{env, fs, process},
 };
 
 fn main() {
     let mut args = env::args();
     args.next().unwrap();
     let wasm_file = args.next().unwrap();
-    let func_name = args.next().unwrap();
-    let args: Vec<_> = args.collect();
     let engine = Engine::new();
     let mut store = Store::new(engine);
-    let bytes = fs::read(wasm_file).unwrap();
+    let bytes = fs::read(&wasm_file).unwrap();
     let module = Module::new(store.engine(), &bytes).unwrap();
+    if module
+        .imports()
+        .any(|((module, _), _)| module == "wasi_snapshot_preview1")
+    {
+        run_wasi(store, &module, wasm_file, args);
+    }
+    let func_name = args.next().unwrap();
+    let args: Vec<_> = args.collect();
     let linker = Linker::new();
     let instance = linker.instantiate(&mut store, &module).unwrap();
     let func = instance.exported_func(&func_name).unwrap();
@@ -35,12 +41,37 @@ fn main() {
     }
 }
 
+/// Runs a WASI command with the given arguments, and exits with its exit code.
+///
+/// The command has access to the current directory and inherits the environment and the standard
+/// streams.
+fn run_wasi(
+    mut store: Store,
+    module: &Module,
+    wasm_file: String,
+    args: impl Iterator<Item = String>,
+) -> ! {
+    let wasi = Wasi::builder()
+        .arg(wasm_file)
+        .args(args)
+        .inherit_env()
+        .inherit_stdio()
+        .preopen_dir(".", ".")
+        .build();
+    let mut linker = Linker::new();
+    wasi.add_to_linker(&mut store, &mut linker);
+    let instance = linker.instantiate(&mut store, module).unwrap();
+    let exit_code = wasi.run(&mut store, &instance).unwrap();
+    process::exit(exit_code);
+}
+
 fn parse_val(type_: ValType, string: &str) -> Val {
     match type_ {
         ValType::I32 => string.parse::<i32>().unwrap().into(),
         ValType::I64 => string.parse::<i64>().unwrap().into(),
         ValType::F32 => string.parse::<f32>().unwrap().into(),
         ValType::F64 => string.parse::<f64>().unwrap().into(),
+        ValType::V128 => V128::from(string.parse::<u128>().unwrap()).into(),
         ValType::FuncRef => unimplemented!(),
         ValType::ExternRef => unimplemented!(),
     }
@@ -52,6 +83,7 @@ fn print_val(val: Val) {
         Val::I64(val) => println!("{}", val),
         Val::F32(val) => println!("{}", val),
         Val::F64(val) => println!("{}", val),
+        Val::V128(val) => println!("{}", u128::from(val)),
         Val::FuncRef(_) => unimplemented!(),
         Val::ExternRef(_) => unimplemented!(),
     }
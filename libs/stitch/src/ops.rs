@@ -178,14 +178,27 @@ fn neg(self) -> Result<Self, Trap> {
             }
 
             fn ceil(self) -> Result<Self, Trap> {
+                // `ceil` returns signaling NaNs unchanged, but Wasm requires the result to be an
+                // arithmetic NaN, so we quiet them first.
+                if self.is_nan() {
+                    return Ok(self + self);
+                }
                 Ok(self.ceil())
             }
 
             fn floor(self) -> Result<Self, Trap> {
+                // Quiet signaling NaNs (see `ceil`).
+                if self.is_nan() {
+                    return Ok(self + self);
+                }
                 Ok(self.floor())
             }
 
             fn trunc(self) -> Result<Self, Trap> {
+                // Quiet signaling NaNs (see `ceil`).
+                if self.is_nan() {
+                    return Ok(self + self);
+                }
                 Ok(self.trunc())
             }
 
@@ -0,0 +1,1120 @@
+//! Operations on [`V128`] values.
+//!
+//! Each operation is implemented in terms of the lanes of its operands. None of these operations
+//! can trap, but they return a `Result` like the scalar operations in [`crate::ops`], so that they
+//! can be used interchangeably by the interpreter.
+
+use {
+    crate::{
+        ops::{FloatOps, Trunc},
+        trap::Trap,
+        v128::{Lane, V128},
+    },
+    std::array,
+};
+
+// Constructor operations
+
+pub(crate) fn i8x16_splat(x: i32) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x as i8; 16]))
+}
+
+pub(crate) fn i16x8_splat(x: i32) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x as i16; 8]))
+}
+
+pub(crate) fn i32x4_splat(x: i32) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x; 4]))
+}
+
+pub(crate) fn i64x2_splat(x: i64) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x; 2]))
+}
+
+pub(crate) fn f32x4_splat(x: f32) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x; 4]))
+}
+
+pub(crate) fn f64x2_splat(x: f64) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([x; 2]))
+}
+
+// Lane operations
+
+pub(crate) fn i8x16_extract_lane_s(x: V128, lane_idx: u8) -> Result<i32, Trap> {
+    Ok(x.to_lanes::<i8, 16>()[lane_idx as usize] as i32)
+}
+
+pub(crate) fn i8x16_extract_lane_u(x: V128, lane_idx: u8) -> Result<i32, Trap> {
+    Ok(x.to_lanes::<u8, 16>()[lane_idx as usize] as i32)
+}
+
+pub(crate) fn i16x8_extract_lane_s(x: V128, lane_idx: u8) -> Result<i32, Trap> {
+    Ok(x.to_lanes::<i16, 8>()[lane_idx as usize] as i32)
+}
+
+pub(crate) fn i16x8_extract_lane_u(x: V128, lane_idx: u8) -> Result<i32, Trap> {
+    Ok(x.to_lanes::<u16, 8>()[lane_idx as usize] as i32)
+}
+
+pub(crate) fn i32x4_extract_lane(x: V128, lane_idx: u8) -> Result<i32, Trap> {
+    Ok(x.to_lanes::<i32, 4>()[lane_idx as usize])
+}
+
+pub(crate) fn i64x2_extract_lane(x: V128, lane_idx: u8) -> Result<i64, Trap> {
+    Ok(x.to_lanes::<i64, 2>()[lane_idx as usize])
+}
+
+pub(crate) fn f32x4_extract_lane(x: V128, lane_idx: u8) -> Result<f32, Trap> {
+    Ok(x.to_lanes::<f32, 4>()[lane_idx as usize])
+}
+
+pub(crate) fn f64x2_extract_lane(x: V128, lane_idx: u8) -> Result<f64, Trap> {
+    Ok(x.to_lanes::<f64, 2>()[lane_idx as usize])
+}
+
+pub(crate) fn i8x16_replace_lane(x: V128, y: i32, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<i8, 16>(x, y as i8, lane_idx))
+}
+
+pub(crate) fn i16x8_replace_lane(x: V128, y: i32, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<i16, 8>(x, y as i16, lane_idx))
+}
+
+pub(crate) fn i32x4_replace_lane(x: V128, y: i32, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<i32, 4>(x, y, lane_idx))
+}
+
+pub(crate) fn i64x2_replace_lane(x: V128, y: i64, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<i64, 2>(x, y, lane_idx))
+}
+
+pub(crate) fn f32x4_replace_lane(x: V128, y: f32, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<f32, 4>(x, y, lane_idx))
+}
+
+pub(crate) fn f64x2_replace_lane(x: V128, y: f64, lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<f64, 2>(x, y, lane_idx))
+}
+
+pub(crate) fn i8x16_swizzle(x: V128, y: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<u8, 16>();
+    Ok(map::<u8, u8, 16>(y, |lane_idx| {
+        x.get(lane_idx as usize).copied().unwrap_or(0)
+    }))
+}
+
+pub(crate) fn i8x16_shuffle(x: V128, y: V128, lane_idxs: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<u8, 16>();
+    let y = y.to_lanes::<u8, 16>();
+    Ok(map::<u8, u8, 16>(lane_idxs, |lane_idx| {
+        let lane_idx = lane_idx as usize;
+        if lane_idx < 16 {
+            x[lane_idx]
+        } else {
+            y[lane_idx - 16]
+        }
+    }))
+}
+
+// Bitwise operations
+
+pub(crate) fn v128_not(x: V128) -> Result<V128, Trap> {
+    Ok(map::<u64, u64, 2>(x, |x| !x))
+}
+
+pub(crate) fn v128_and(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u64, u64, 2>(x, y, |x, y| x & y))
+}
+
+pub(crate) fn v128_andnot(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u64, u64, 2>(x, y, |x, y| x & !y))
+}
+
+pub(crate) fn v128_or(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u64, u64, 2>(x, y, |x, y| x | y))
+}
+
+pub(crate) fn v128_xor(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u64, u64, 2>(x, y, |x, y| x ^ y))
+}
+
+pub(crate) fn v128_bitselect(x: V128, y: V128, mask: V128) -> Result<V128, Trap> {
+    let mask = mask.to_lanes::<u64, 2>();
+    let x = x.to_lanes::<u64, 2>();
+    let y = y.to_lanes::<u64, 2>();
+    Ok(V128::from_lanes::<u64, 2>(array::from_fn(|idx| {
+        x[idx] & mask[idx] | y[idx] & !mask[idx]
+    })))
+}
+
+pub(crate) fn v128_any_true(x: V128) -> Result<i32, Trap> {
+    Ok((u128::from(x) != 0).into())
+}
+
+// Integer operations
+
+macro_rules! int_ops {
+    (
+        $T:ty,
+        $U:ty,
+        $N:literal,
+        $eq:ident,
+        $ne:ident,
+        $lt_s:ident,
+        $gt_s:ident,
+        $le_s:ident,
+        $ge_s:ident,
+        $abs:ident,
+        $neg:ident,
+        $all_true:ident,
+        $bitmask:ident,
+        $shl:ident,
+        $shr_s:ident,
+        $shr_u:ident,
+        $add:ident,
+        $sub:ident
+    ) => {
+        pub(crate) fn $eq(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x == y)))
+        }
+
+        pub(crate) fn $ne(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x != y)))
+        }
+
+        pub(crate) fn $lt_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x < y)))
+        }
+
+        pub(crate) fn $gt_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x > y)))
+        }
+
+        pub(crate) fn $le_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x <= y)))
+        }
+
+        pub(crate) fn $ge_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| mask(x >= y)))
+        }
+
+        pub(crate) fn $abs(x: V128) -> Result<V128, Trap> {
+            Ok(map::<$T, $T, $N>(x, |x| x.wrapping_abs()))
+        }
+
+        pub(crate) fn $neg(x: V128) -> Result<V128, Trap> {
+            Ok(map::<$T, $T, $N>(x, |x| x.wrapping_neg()))
+        }
+
+        pub(crate) fn $all_true(x: V128) -> Result<i32, Trap> {
+            Ok(x.to_lanes::<$T, $N>().iter().all(|&x| x != 0).into())
+        }
+
+        pub(crate) fn $bitmask(x: V128) -> Result<i32, Trap> {
+            Ok(x.to_lanes::<$T, $N>()
+                .iter()
+                .enumerate()
+                .fold(0, |mask, (idx, &x)| mask | ((x < 0) as i32) << idx))
+        }
+
+        pub(crate) fn $shl(x: V128, y: i32) -> Result<V128, Trap> {
+            Ok(map::<$T, $T, $N>(x, |x| x.wrapping_shl(y as u32)))
+        }
+
+        pub(crate) fn $shr_s(x: V128, y: i32) -> Result<V128, Trap> {
+            Ok(map::<$T, $T, $N>(x, |x| x.wrapping_shr(y as u32)))
+        }
+
+        pub(crate) fn $shr_u(x: V128, y: i32) -> Result<V128, Trap> {
+            Ok(map::<$U, $U, $N>(x, |x| x.wrapping_shr(y as u32)))
+        }
+
+        pub(crate) fn $add(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.wrapping_add(y)))
+        }
+
+        pub(crate) fn $sub(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.wrapping_sub(y)))
+        }
+    };
+}
+
+int_ops!(
+    i8,
+    u8,
+    16,
+    i8x16_eq,
+    i8x16_ne,
+    i8x16_lt_s,
+    i8x16_gt_s,
+    i8x16_le_s,
+    i8x16_ge_s,
+    i8x16_abs,
+    i8x16_neg,
+    i8x16_all_true,
+    i8x16_bitmask,
+    i8x16_shl,
+    i8x16_shr_s,
+    i8x16_shr_u,
+    i8x16_add,
+    i8x16_sub
+);
+int_ops!(
+    i16,
+    u16,
+    8,
+    i16x8_eq,
+    i16x8_ne,
+    i16x8_lt_s,
+    i16x8_gt_s,
+    i16x8_le_s,
+    i16x8_ge_s,
+    i16x8_abs,
+    i16x8_neg,
+    i16x8_all_true,
+    i16x8_bitmask,
+    i16x8_shl,
+    i16x8_shr_s,
+    i16x8_shr_u,
+    i16x8_add,
+    i16x8_sub
+);
+int_ops!(
+    i32,
+    u32,
+    4,
+    i32x4_eq,
+    i32x4_ne,
+    i32x4_lt_s,
+    i32x4_gt_s,
+    i32x4_le_s,
+    i32x4_ge_s,
+    i32x4_abs,
+    i32x4_neg,
+    i32x4_all_true,
+    i32x4_bitmask,
+    i32x4_shl,
+    i32x4_shr_s,
+    i32x4_shr_u,
+    i32x4_add,
+    i32x4_sub
+);
+int_ops!(
+    i64,
+    u64,
+    2,
+    i64x2_eq,
+    i64x2_ne,
+    i64x2_lt_s,
+    i64x2_gt_s,
+    i64x2_le_s,
+    i64x2_ge_s,
+    i64x2_abs,
+    i64x2_neg,
+    i64x2_all_true,
+    i64x2_bitmask,
+    i64x2_shl,
+    i64x2_shr_s,
+    i64x2_shr_u,
+    i64x2_add,
+    i64x2_sub
+);
+
+macro_rules! small_int_ops {
+    (
+        $T:ty,
+        $U:ty,
+        $N:literal,
+        $lt_u:ident,
+        $gt_u:ident,
+        $le_u:ident,
+        $ge_u:ident,
+        $min_s:ident,
+        $min_u:ident,
+        $max_s:ident,
+        $max_u:ident
+    ) => {
+        pub(crate) fn $lt_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $T, $N>(x, y, |x, y| mask(x < y)))
+        }
+
+        pub(crate) fn $gt_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $T, $N>(x, y, |x, y| mask(x > y)))
+        }
+
+        pub(crate) fn $le_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $T, $N>(x, y, |x, y| mask(x <= y)))
+        }
+
+        pub(crate) fn $ge_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $T, $N>(x, y, |x, y| mask(x >= y)))
+        }
+
+        pub(crate) fn $min_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.min(y)))
+        }
+
+        pub(crate) fn $min_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $U, $N>(x, y, |x, y| x.min(y)))
+        }
+
+        pub(crate) fn $max_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.max(y)))
+        }
+
+        pub(crate) fn $max_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $U, $N>(x, y, |x, y| x.max(y)))
+        }
+    };
+}
+
+small_int_ops!(
+    i8,
+    u8,
+    16,
+    i8x16_lt_u,
+    i8x16_gt_u,
+    i8x16_le_u,
+    i8x16_ge_u,
+    i8x16_min_s,
+    i8x16_min_u,
+    i8x16_max_s,
+    i8x16_max_u
+);
+small_int_ops!(
+    i16,
+    u16,
+    8,
+    i16x8_lt_u,
+    i16x8_gt_u,
+    i16x8_le_u,
+    i16x8_ge_u,
+    i16x8_min_s,
+    i16x8_min_u,
+    i16x8_max_s,
+    i16x8_max_u
+);
+small_int_ops!(
+    i32,
+    u32,
+    4,
+    i32x4_lt_u,
+    i32x4_gt_u,
+    i32x4_le_u,
+    i32x4_ge_u,
+    i32x4_min_s,
+    i32x4_min_u,
+    i32x4_max_s,
+    i32x4_max_u
+);
+
+macro_rules! sat_ops {
+    (
+        $T:ty,
+        $U:ty,
+        $N:literal,
+        $add_sat_s:ident,
+        $add_sat_u:ident,
+        $sub_sat_s:ident,
+        $sub_sat_u:ident
+    ) => {
+        pub(crate) fn $add_sat_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.saturating_add(y)))
+        }
+
+        pub(crate) fn $add_sat_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $U, $N>(x, y, |x, y| x.saturating_add(y)))
+        }
+
+        pub(crate) fn $sub_sat_s(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| x.saturating_sub(y)))
+        }
+
+        pub(crate) fn $sub_sat_u(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$U, $U, $N>(x, y, |x, y| x.saturating_sub(y)))
+        }
+    };
+}
+
+sat_ops!(
+    i8,
+    u8,
+    16,
+    i8x16_add_sat_s,
+    i8x16_add_sat_u,
+    i8x16_sub_sat_s,
+    i8x16_sub_sat_u
+);
+sat_ops!(
+    i16,
+    u16,
+    8,
+    i16x8_add_sat_s,
+    i16x8_add_sat_u,
+    i16x8_sub_sat_s,
+    i16x8_sub_sat_u
+);
+
+pub(crate) fn i8x16_popcnt(x: V128) -> Result<V128, Trap> {
+    Ok(map::<u8, u8, 16>(x, |x| x.count_ones() as u8))
+}
+
+pub(crate) fn i8x16_avgr_u(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u8, u8, 16>(x, y, |x, y| {
+        (x as u16 + y as u16).div_ceil(2) as u8
+    }))
+}
+
+pub(crate) fn i16x8_avgr_u(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<u16, u16, 8>(x, y, |x, y| {
+        (x as u32 + y as u32).div_ceil(2) as u16
+    }))
+}
+
+pub(crate) fn i16x8_mul(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<i16, i16, 8>(x, y, |x, y| x.wrapping_mul(y)))
+}
+
+pub(crate) fn i32x4_mul(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<i32, i32, 4>(x, y, |x, y| x.wrapping_mul(y)))
+}
+
+pub(crate) fn i64x2_mul(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<i64, i64, 2>(x, y, |x, y| x.wrapping_mul(y)))
+}
+
+pub(crate) fn i16x8_q15mulr_sat_s(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(zip::<i16, i16, 8>(x, y, |x, y| {
+        ((x as i32 * y as i32 + 0x4000) >> 15).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }))
+}
+
+pub(crate) fn i32x4_dot_i16x8_s(x: V128, y: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<i16, 8>();
+    let y = y.to_lanes::<i16, 8>();
+    Ok(V128::from_lanes::<i32, 4>(array::from_fn(|idx| {
+        (x[2 * idx] as i32 * y[2 * idx] as i32)
+            .wrapping_add(x[2 * idx + 1] as i32 * y[2 * idx + 1] as i32)
+    })))
+}
+
+pub(crate) fn i8x16_narrow_i16x8_s(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(narrow::<i16, i8, 8, 16>(x, y, |x| {
+        x.clamp(i8::MIN as i16, i8::MAX as i16) as i8
+    }))
+}
+
+pub(crate) fn i8x16_narrow_i16x8_u(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(narrow::<i16, u8, 8, 16>(x, y, |x| {
+        x.clamp(u8::MIN as i16, u8::MAX as i16) as u8
+    }))
+}
+
+pub(crate) fn i16x8_narrow_i32x4_s(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(narrow::<i32, i16, 4, 8>(x, y, |x| {
+        x.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }))
+}
+
+pub(crate) fn i16x8_narrow_i32x4_u(x: V128, y: V128) -> Result<V128, Trap> {
+    Ok(narrow::<i32, u16, 4, 8>(x, y, |x| {
+        x.clamp(u16::MIN as i32, u16::MAX as i32) as u16
+    }))
+}
+
+macro_rules! extend_ops {
+    (
+        $T:ty,
+        $U:ty,
+        $N:literal,
+        $extend_low:ident,
+        $extend_high:ident,
+        $extmul_low:ident,
+        $extmul_high:ident
+    ) => {
+        pub(crate) fn $extend_low(x: V128) -> Result<V128, Trap> {
+            let x = x.to_lanes::<$T, { 2 * $N }>();
+            Ok(V128::from_lanes::<$U, $N>(array::from_fn(|idx| {
+                x[idx] as $U
+            })))
+        }
+
+        pub(crate) fn $extend_high(x: V128) -> Result<V128, Trap> {
+            let x = x.to_lanes::<$T, { 2 * $N }>();
+            Ok(V128::from_lanes::<$U, $N>(array::from_fn(|idx| {
+                x[$N + idx] as $U
+            })))
+        }
+
+        pub(crate) fn $extmul_low(x: V128, y: V128) -> Result<V128, Trap> {
+            let x = x.to_lanes::<$T, { 2 * $N }>();
+            let y = y.to_lanes::<$T, { 2 * $N }>();
+            Ok(V128::from_lanes::<$U, $N>(array::from_fn(|idx| {
+                (x[idx] as $U).wrapping_mul(y[idx] as $U)
+            })))
+        }
+
+        pub(crate) fn $extmul_high(x: V128, y: V128) -> Result<V128, Trap> {
+            let x = x.to_lanes::<$T, { 2 * $N }>();
+            let y = y.to_lanes::<$T, { 2 * $N }>();
+            Ok(V128::from_lanes::<$U, $N>(array::from_fn(|idx| {
+                (x[$N + idx] as $U).wrapping_mul(y[$N + idx] as $U)
+            })))
+        }
+    };
+}
+
+extend_ops!(
+    i8,
+    i16,
+    8,
+    i16x8_extend_low_i8x16_s,
+    i16x8_extend_high_i8x16_s,
+    i16x8_extmul_low_i8x16_s,
+    i16x8_extmul_high_i8x16_s
+);
+extend_ops!(
+    u8,
+    u16,
+    8,
+    i16x8_extend_low_i8x16_u,
+    i16x8_extend_high_i8x16_u,
+    i16x8_extmul_low_i8x16_u,
+    i16x8_extmul_high_i8x16_u
+);
+extend_ops!(
+    i16,
+    i32,
+    4,
+    i32x4_extend_low_i16x8_s,
+    i32x4_extend_high_i16x8_s,
+    i32x4_extmul_low_i16x8_s,
+    i32x4_extmul_high_i16x8_s
+);
+extend_ops!(
+    u16,
+    u32,
+    4,
+    i32x4_extend_low_i16x8_u,
+    i32x4_extend_high_i16x8_u,
+    i32x4_extmul_low_i16x8_u,
+    i32x4_extmul_high_i16x8_u
+);
+extend_ops!(
+    i32,
+    i64,
+    2,
+    i64x2_extend_low_i32x4_s,
+    i64x2_extend_high_i32x4_s,
+    i64x2_extmul_low_i32x4_s,
+    i64x2_extmul_high_i32x4_s
+);
+extend_ops!(
+    u32,
+    u64,
+    2,
+    i64x2_extend_low_i32x4_u,
+    i64x2_extend_high_i32x4_u,
+    i64x2_extmul_low_i32x4_u,
+    i64x2_extmul_high_i32x4_u
+);
+
+pub(crate) fn i16x8_extadd_pairwise_i8x16_s(x: V128) -> Result<V128, Trap> {
+    Ok(extadd_pairwise::<i8, i16, 8>(x, |x, y| x as i16 + y as i16))
+}
+
+pub(crate) fn i16x8_extadd_pairwise_i8x16_u(x: V128) -> Result<V128, Trap> {
+    Ok(extadd_pairwise::<u8, u16, 8>(x, |x, y| x as u16 + y as u16))
+}
+
+pub(crate) fn i32x4_extadd_pairwise_i16x8_s(x: V128) -> Result<V128, Trap> {
+    Ok(extadd_pairwise::<i16, i32, 4>(x, |x, y| {
+        x as i32 + y as i32
+    }))
+}
+
+pub(crate) fn i32x4_extadd_pairwise_i16x8_u(x: V128) -> Result<V128, Trap> {
+    Ok(extadd_pairwise::<u16, u32, 4>(x, |x, y| {
+        x as u32 + y as u32
+    }))
+}
+
+// Floating-point operations
+
+macro_rules! float_ops {
+    (
+        $T:ty,
+        $M:ty,
+        $N:literal,
+        $eq:ident,
+        $ne:ident,
+        $lt:ident,
+        $gt:ident,
+        $le:ident,
+        $ge:ident,
+        $abs:ident,
+        $neg:ident,
+        $sqrt:ident,
+        $ceil:ident,
+        $floor:ident,
+        $trunc:ident,
+        $nearest:ident,
+        $add:ident,
+        $sub:ident,
+        $mul:ident,
+        $div:ident,
+        $min:ident,
+        $max:ident,
+        $pmin:ident,
+        $pmax:ident
+    ) => {
+        pub(crate) fn $eq(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x == y)))
+        }
+
+        pub(crate) fn $ne(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x != y)))
+        }
+
+        pub(crate) fn $lt(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x < y)))
+        }
+
+        pub(crate) fn $gt(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x > y)))
+        }
+
+        pub(crate) fn $le(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x <= y)))
+        }
+
+        pub(crate) fn $ge(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $M, $N>(x, y, |x, y| mask(x >= y)))
+        }
+
+        pub(crate) fn $abs(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::abs)
+        }
+
+        pub(crate) fn $neg(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::neg)
+        }
+
+        pub(crate) fn $sqrt(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::sqrt)
+        }
+
+        pub(crate) fn $ceil(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::ceil)
+        }
+
+        pub(crate) fn $floor(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::floor)
+        }
+
+        pub(crate) fn $trunc(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::trunc)
+        }
+
+        pub(crate) fn $nearest(x: V128) -> Result<V128, Trap> {
+            try_map::<$T, $T, $N>(x, FloatOps::nearest)
+        }
+
+        pub(crate) fn $add(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::add)
+        }
+
+        pub(crate) fn $sub(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::sub)
+        }
+
+        pub(crate) fn $mul(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::mul)
+        }
+
+        pub(crate) fn $div(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::div)
+        }
+
+        pub(crate) fn $min(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::min)
+        }
+
+        pub(crate) fn $max(x: V128, y: V128) -> Result<V128, Trap> {
+            try_zip::<$T, $T, $N>(x, y, FloatOps::max)
+        }
+
+        pub(crate) fn $pmin(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| if y < x { y } else { x }))
+        }
+
+        pub(crate) fn $pmax(x: V128, y: V128) -> Result<V128, Trap> {
+            Ok(zip::<$T, $T, $N>(x, y, |x, y| if x < y { y } else { x }))
+        }
+    };
+}
+
+float_ops!(
+    f32,
+    i32,
+    4,
+    f32x4_eq,
+    f32x4_ne,
+    f32x4_lt,
+    f32x4_gt,
+    f32x4_le,
+    f32x4_ge,
+    f32x4_abs,
+    f32x4_neg,
+    f32x4_sqrt,
+    f32x4_ceil,
+    f32x4_floor,
+    f32x4_trunc,
+    f32x4_nearest,
+    f32x4_add,
+    f32x4_sub,
+    f32x4_mul,
+    f32x4_div,
+    f32x4_min,
+    f32x4_max,
+    f32x4_pmin,
+    f32x4_pmax
+);
+float_ops!(
+    f64,
+    i64,
+    2,
+    f64x2_eq,
+    f64x2_ne,
+    f64x2_lt,
+    f64x2_gt,
+    f64x2_le,
+    f64x2_ge,
+    f64x2_abs,
+    f64x2_neg,
+    f64x2_sqrt,
+    f64x2_ceil,
+    f64x2_floor,
+    f64x2_trunc,
+    f64x2_nearest,
+    f64x2_add,
+    f64x2_sub,
+    f64x2_mul,
+    f64x2_div,
+    f64x2_min,
+    f64x2_max,
+    f64x2_pmin,
+    f64x2_pmax
+);
+
+// Conversion operations
+
+pub(crate) fn i32x4_trunc_sat_f32x4_s(x: V128) -> Result<V128, Trap> {
+    try_map::<f32, i32, 4>(x, <i32 as Trunc<f32>>::trunc_sat)
+}
+
+pub(crate) fn i32x4_trunc_sat_f32x4_u(x: V128) -> Result<V128, Trap> {
+    try_map::<f32, u32, 4>(x, <u32 as Trunc<f32>>::trunc_sat)
+}
+
+pub(crate) fn i32x4_trunc_sat_f64x2_s_zero(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<f64, 2>();
+    Ok(V128::from_lanes::<i32, 4>([
+        <i32 as Trunc<f64>>::trunc_sat(x[0])?,
+        <i32 as Trunc<f64>>::trunc_sat(x[1])?,
+        0,
+        0,
+    ]))
+}
+
+pub(crate) fn i32x4_trunc_sat_f64x2_u_zero(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<f64, 2>();
+    Ok(V128::from_lanes::<u32, 4>([
+        <u32 as Trunc<f64>>::trunc_sat(x[0])?,
+        <u32 as Trunc<f64>>::trunc_sat(x[1])?,
+        0,
+        0,
+    ]))
+}
+
+pub(crate) fn f32x4_convert_i32x4_s(x: V128) -> Result<V128, Trap> {
+    Ok(map::<i32, f32, 4>(x, |x| x as f32))
+}
+
+pub(crate) fn f32x4_convert_i32x4_u(x: V128) -> Result<V128, Trap> {
+    Ok(map::<u32, f32, 4>(x, |x| x as f32))
+}
+
+pub(crate) fn f64x2_convert_low_i32x4_s(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<i32, 4>();
+    Ok(V128::from_lanes::<f64, 2>([x[0] as f64, x[1] as f64]))
+}
+
+pub(crate) fn f64x2_convert_low_i32x4_u(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<u32, 4>();
+    Ok(V128::from_lanes::<f64, 2>([x[0] as f64, x[1] as f64]))
+}
+
+pub(crate) fn f32x4_demote_f64x2_zero(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<f64, 2>();
+    Ok(V128::from_lanes::<f32, 4>([
+        x[0] as f32,
+        x[1] as f32,
+        0.0,
+        0.0,
+    ]))
+}
+
+pub(crate) fn f64x2_promote_low_f32x4(x: V128) -> Result<V128, Trap> {
+    let x = x.to_lanes::<f32, 4>();
+    Ok(V128::from_lanes::<f64, 2>([x[0] as f64, x[1] as f64]))
+}
+
+// Memory operations
+
+pub(crate) fn v128_load(bytes: [u8; 16]) -> Result<V128, Trap> {
+    Ok(V128::from_bytes(bytes))
+}
+
+pub(crate) fn v128_load8x8_s(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<i16, 8>(bytes.map(|x| x as i8 as i16)))
+}
+
+pub(crate) fn v128_load8x8_u(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<u16, 8>(bytes.map(|x| x as u16)))
+}
+
+pub(crate) fn v128_load16x4_s(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<i32, 4>(
+        read_lanes::<i16, 4>(&bytes).map(|x| x as i32),
+    ))
+}
+
+pub(crate) fn v128_load16x4_u(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<u32, 4>(
+        read_lanes::<u16, 4>(&bytes).map(|x| x as u32),
+    ))
+}
+
+pub(crate) fn v128_load32x2_s(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<i64, 2>(
+        read_lanes::<i32, 2>(&bytes).map(|x| x as i64),
+    ))
+}
+
+pub(crate) fn v128_load32x2_u(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes::<u64, 2>(
+        read_lanes::<u32, 2>(&bytes).map(|x| x as u64),
+    ))
+}
+
+pub(crate) fn v128_load8_splat(bytes: [u8; 1]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u8::from_le_bytes(bytes); 16]))
+}
+
+pub(crate) fn v128_load16_splat(bytes: [u8; 2]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u16::from_le_bytes(bytes); 8]))
+}
+
+pub(crate) fn v128_load32_splat(bytes: [u8; 4]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u32::from_le_bytes(bytes); 4]))
+}
+
+pub(crate) fn v128_load64_splat(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u64::from_le_bytes(bytes); 2]))
+}
+
+pub(crate) fn v128_load32_zero(bytes: [u8; 4]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u32::from_le_bytes(bytes), 0, 0, 0]))
+}
+
+pub(crate) fn v128_load64_zero(bytes: [u8; 8]) -> Result<V128, Trap> {
+    Ok(V128::from_lanes([u64::from_le_bytes(bytes), 0]))
+}
+
+pub(crate) fn v128_load8_lane(x: V128, bytes: [u8; 1], lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<u8, 16>(
+        x,
+        u8::from_le_bytes(bytes),
+        lane_idx,
+    ))
+}
+
+pub(crate) fn v128_load16_lane(x: V128, bytes: [u8; 2], lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<u16, 8>(
+        x,
+        u16::from_le_bytes(bytes),
+        lane_idx,
+    ))
+}
+
+pub(crate) fn v128_load32_lane(x: V128, bytes: [u8; 4], lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<u32, 4>(
+        x,
+        u32::from_le_bytes(bytes),
+        lane_idx,
+    ))
+}
+
+pub(crate) fn v128_load64_lane(x: V128, bytes: [u8; 8], lane_idx: u8) -> Result<V128, Trap> {
+    Ok(replace_lane::<u64, 2>(
+        x,
+        u64::from_le_bytes(bytes),
+        lane_idx,
+    ))
+}
+
+pub(crate) fn v128_store(x: V128) -> Result<[u8; 16], Trap> {
+    Ok(x.to_bytes())
+}
+
+pub(crate) fn v128_store8_lane(x: V128, lane_idx: u8) -> Result<[u8; 1], Trap> {
+    Ok(x.to_lanes::<u8, 16>()[lane_idx as usize].to_le_bytes())
+}
+
+pub(crate) fn v128_store16_lane(x: V128, lane_idx: u8) -> Result<[u8; 2], Trap> {
+    Ok(x.to_lanes::<u16, 8>()[lane_idx as usize].to_le_bytes())
+}
+
+pub(crate) fn v128_store32_lane(x: V128, lane_idx: u8) -> Result<[u8; 4], Trap> {
+    Ok(x.to_lanes::<u32, 4>()[lane_idx as usize].to_le_bytes())
+}
+
+pub(crate) fn v128_store64_lane(x: V128, lane_idx: u8) -> Result<[u8; 8], Trap> {
+    Ok(x.to_lanes::<u64, 2>()[lane_idx as usize].to_le_bytes())
+}
+
+// Helper functions
+
+/// Applies the given function to each lane of `x`.
+fn map<T, U, const N: usize>(x: V128, f: impl Fn(T) -> U) -> V128
+where
+    T: Lane,
+    U: Lane,
+{
+    V128::from_lanes(x.to_lanes::<T, N>().map(f))
+}
+
+/// Applies the given fallible function to each lane of `x`.
+fn try_map<T, U, const N: usize>(x: V128, f: impl Fn(T) -> Result<U, Trap>) -> Result<V128, Trap>
+where
+    T: Lane,
+    U: Lane,
+{
+    let x = x.to_lanes::<T, N>();
+    let mut y = [x[0]; N].map(|_| None);
+    for idx in 0..N {
+        y[idx] = Some(f(x[idx])?);
+    }
+    Ok(V128::from_lanes(y.map(Option::unwrap)))
+}
+
+/// Applies the given function to each pair of corresponding lanes of `x` and `y`.
+fn zip<T, U, const N: usize>(x: V128, y: V128, f: impl Fn(T, T) -> U) -> V128
+where
+    T: Lane,
+    U: Lane,
+{
+    let x = x.to_lanes::<T, N>();
+    let y = y.to_lanes::<T, N>();
+    V128::from_lanes::<U, N>(array::from_fn(|idx| f(x[idx], y[idx])))
+}
+
+/// Applies the given fallible function to each pair of corresponding lanes of `x` and `y`.
+fn try_zip<T, U, const N: usize>(
+    x: V128,
+    y: V128,
+    f: impl Fn(T, T) -> Result<U, Trap>,
+) -> Result<V128, Trap>
+where
+    T: Lane,
+    U: Lane,
+{
+    let x = x.to_lanes::<T, N>();
+    let y = y.to_lanes::<T, N>();
+    let mut z = [x[0]; N].map(|_| None);
+    for idx in 0..N {
+        z[idx] = Some(f(x[idx], y[idx])?);
+    }
+    Ok(V128::from_lanes(z.map(Option::unwrap)))
+}
+
+/// Narrows the lanes of `x` and `y` with the given function, and concatenates the results.
+fn narrow<T, U, const N: usize, const M: usize>(x: V128, y: V128, f: impl Fn(T) -> U) -> V128
+where
+    T: Lane,
+    U: Lane,
+{
+    let x = x.to_lanes::<T, N>();
+    let y = y.to_lanes::<T, N>();
+    V128::from_lanes::<U, M>(array::from_fn(|idx| {
+        if idx < N {
+            f(x[idx])
+        } else {
+            f(y[idx - N])
+        }
+    }))
+}
+
+/// Adds each pair of adjacent lanes of `x` with the given function.
+fn extadd_pairwise<T, U, const N: usize>(x: V128, f: impl Fn(T, T) -> U) -> V128
+where
+    T: Lane,
+    U: Lane,
+{
+    let bytes = x.to_bytes();
+    V128::from_lanes::<U, N>(array::from_fn(|idx| {
+        let lane = |idx: usize| T::read_le_bytes(&bytes[idx * T::SIZE..][..T::SIZE]);
+        f(lane(2 * idx), lane(2 * idx + 1))
+    }))
+}
+
+/// Replaces the lane of `x` with the given index with `y`.
+fn replace_lane<T, const N: usize>(x: V128, y: T, lane_idx: u8) -> V128
+where
+    T: Lane,
+{
+    let mut x = x.to_lanes::<T, N>();
+    x[lane_idx as usize] = y;
+    V128::from_lanes(x)
+}
+
+/// Reads the lanes of a partial vector from the given bytes.
+fn read_lanes<T, const N: usize>(bytes: &[u8]) -> [T; N]
+where
+    T: Lane,
+{
+    array::from_fn(|idx| T::read_le_bytes(&bytes[idx * T::SIZE..][..T::SIZE]))
+}
+
+/// Converts the result of a comparison to a lane mask, with either all bits set or all bits clear.
+fn mask<M>(cond: bool) -> M
+where
+    M: Mask,
+{
+    if cond {
+        M::ONES
+    } else {
+        M::ZEROS
+    }
+}
+
+trait Mask {
+    const ONES: Self;
+    const ZEROS: Self;
+}
+
+macro_rules! impl_mask {
+    ($T:ty) => {
+        impl Mask for $T {
+            const ONES: Self = -1;
+            const ZEROS: Self = 0;
+        }
+    };
+}
+
+impl_mask!(i8);
+impl_mask!(i16);
+impl_mask!(i32);
+impl_mask!(i64);
@@ -18,8 +18,9 @@ impl Stack {
     pub(crate) const SIZE: usize = 1024 * 1024;
 
     pub fn lock() -> StackGuard {
+        // A suspended call holds on to the stack it was running on, so we might need a new one.
         StackGuard {
-            stack: ManuallyDrop::new(STACK.take().unwrap()),
+            stack: ManuallyDrop::new(STACK.take().unwrap_or_else(Stack::new)),
         }
     }
 
@@ -4,17 +4,26 @@
         data::DataEntity,
         elem::ElemEntity,
         engine::Engine,
+        error::Error,
+        exec,
+        exec::SuspendedCall,
         extern_::ExternEntity,
-        func::{FuncEntity, FuncType},
+        func::{FuncEntity, FuncError, FuncType},
         global::GlobalEntity,
         mem::MemEntity,
         table::TableEntity,
+        trap::Trap,
+        val::Val,
     },
     std::{
         collections::HashMap,
         fmt,
         hash::{Hash, Hasher},
         ptr::NonNull,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -31,6 +40,9 @@ pub struct Store {
     elems: Vec<AliasableBox<ElemEntity>>,
     datas: Vec<AliasableBox<DataEntity>>,
     externs: Vec<AliasableBox<ExternEntity>>,
+    fuel: Option<u64>,
+    interrupted: Arc<AtomicBool>,
+    suspended_call: Option<SuspendedCall>,
 }
 
 impl Store {
@@ -47,6 +59,9 @@ pub fn new(engine: Engine) -> Self {
             elems: Vec::new(),
             datas: Vec::new(),
             externs: Vec::new(),
+            fuel: None,
+            interrupted: Arc::new(AtomicBool::new(false)),
+            suspended_call: None,
         }
     }
 
@@ -54,6 +69,92 @@ pub fn engine(&self) -> &Engine {
         &self.engine
     }
 
+    /// Returns the remaining fuel of this [`Store`], or `None` if fuel is unlimited.
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Sets the fuel of this [`Store`], or makes it unlimited if `fuel` is `None`.
+    ///
+    /// Each Wasm instruction that is executed consumes one unit of fuel. Fuel is charged ahead for
+    /// the instructions up to the next loop or call, so a call can be stopped slightly before it
+    /// has actually executed all of its fuel. When the fuel runs out, the call traps with
+    /// [`Trap::OutOfFuel`], and can be resumed with [`Store::resume`] after adding more fuel.
+    ///
+    /// Fuel is unlimited by default. Fuel is only consumed by code compiled by an [`Engine`] that
+    /// was created with [`Engine::with_fuel_metering`].
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.fuel = fuel;
+    }
+
+    /// Adds the given amount of fuel to this [`Store`].
+    ///
+    /// Has no effect if fuel is unlimited.
+    pub fn add_fuel(&mut self, fuel: u64) {
+        if let Some(remaining) = &mut self.fuel {
+            *remaining = remaining.saturating_add(fuel);
+        }
+    }
+
+    /// Returns an [`InterruptHandle`] for this [`Store`].
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle {
+            interrupted: self.interrupted.clone(),
+        }
+    }
+
+    /// Returns `true` if a call in this [`Store`] ran out of fuel or was interrupted, and can be
+    /// resumed with [`Store::resume`].
+    pub fn has_suspended_call(&self) -> bool {
+        self.suspended_call.is_some()
+    }
+
+    /// Resumes the call that ran out of fuel or was interrupted.
+    ///
+    /// The results of the original call are written to the `results` slice. Starting another call
+    /// discards the suspended call.
+    ///
+    /// # Errors
+    ///
+    /// - If there is no suspended call.
+    /// - If the actual result count does not match the expected result count.
+    pub fn resume(&mut self, results: &mut [Val]) -> Result<(), Error> {
+        let Some(call) = self.suspended_call.take() else {
+            return Err(FuncError::NoSuspendedCall)?;
+        };
+        if results.len() != call.type_().results().len() {
+            self.suspended_call = Some(call);
+            return Err(FuncError::ResultCountMismatch)?;
+        }
+        for (result, result_type) in results
+            .iter_mut()
+            .zip(call.type_().results().iter().copied())
+        {
+            *result = Val::default(result_type);
+        }
+        exec::resume(self, call, results)
+    }
+
+    /// Consumes the given amount of fuel, or returns the [`Trap`] that stops the current call.
+    #[inline]
+    pub(crate) fn consume_fuel(&mut self, cost: u64) -> Result<(), Trap> {
+        if self.interrupted.load(Ordering::Relaxed) {
+            self.interrupted.store(false, Ordering::Relaxed);
+            return Err(Trap::Interrupted);
+        }
+        if let Some(fuel) = &mut self.fuel {
+            if *fuel < cost {
+                return Err(Trap::OutOfFuel);
+            }
+            *fuel -= cost;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_suspended_call(&mut self, call: Option<SuspendedCall>) {
+        self.suspended_call = call;
+    }
+
     pub(crate) fn id(&self) -> StoreId {
         self.id
     }
@@ -96,6 +197,23 @@ pub(crate) fn insert_mem(&mut self, mem: MemEntity) -> Handle<MemEntity> {
         handle
     }
 
+    /// Returns the position of the given [`MemEntity`] in this [`Store`].
+    ///
+    /// Unlike a [`Handle`], a position can be kept by host functions, which have to be `Send`.
+    pub(crate) fn mem_index(&self, mem: Handle<MemEntity>) -> usize {
+        let mem = mem.to_unguarded(self.id);
+        self.mems
+            .iter()
+            .position(|entity| AliasableBox::as_raw(entity) == mem)
+            .unwrap()
+    }
+
+    /// Returns a [`Handle`] to the [`MemEntity`] at the given position in this [`Store`].
+    pub(crate) fn mem_at(&self, index: usize) -> Option<Handle<MemEntity>> {
+        let mem = self.mems.get(index)?;
+        Some(unsafe { Handle::from_unguarded(AliasableBox::as_raw(mem), self.id) })
+    }
+
     /// Inserts the given [`GlobalEntity`] into this [`Store`].
     ///
     /// Returns a [`Handle`] to the inserted [`GlobalEntity`].
@@ -138,6 +256,26 @@ pub(crate) fn insert_extern(&mut self, extern_: ExternEntity) -> Handle<ExternEn
     }
 }
 
+/// A handle that can interrupt the calls in a [`Store`] from another thread.
+///
+/// Interrupting only sets an atomic flag, so it is also safe to do from a signal handler. Only
+/// code compiled by an [`Engine`] that was created with [`Engine::with_fuel_metering`] checks the
+/// flag.
+#[derive(Clone, Debug)]
+pub struct InterruptHandle {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl InterruptHandle {
+    /// Interrupts the call that is running in the [`Store`], or the next call if none is.
+    ///
+    /// The call traps with [`Trap::Interrupted`] at the next loop or call, and can be resumed with
+    /// [`Store::resume`].
+    pub fn interrupt(&self) {
+        self.interrupted.store(true, Ordering::Relaxed);
+    }
+}
+
 /// A unique identifier for a [`Store`].
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct StoreId(usize);
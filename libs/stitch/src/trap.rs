@@ -1,6 +1,6 @@
 use std::{error::Error, fmt};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Trap {
     Unreachable,
     TypeMismatch,
@@ -11,9 +11,17 @@ pub enum Trap {
     TableAccessOutOfBounds,
     MemAccessOutOfBounds,
     StackOverflow,
+    OutOfFuel,
+    Interrupted,
 }
 
 impl Trap {
+    /// Returns `true` if a call that trapped with this [`Trap`] can be resumed with
+    /// [`Store::resume`](crate::Store::resume).
+    pub fn is_resumable(self) -> bool {
+        matches!(self, Self::OutOfFuel | Self::Interrupted)
+    }
+
     pub(crate) fn from_usize(val: usize) -> Option<Self> {
         match val {
             0 => Some(Self::Unreachable),
@@ -25,6 +33,8 @@ pub(crate) fn from_usize(val: usize) -> Option<Self> {
             6 => Some(Self::TableAccessOutOfBounds),
             7 => Some(Self::MemAccessOutOfBounds),
             8 => Some(Self::StackOverflow),
+            9 => Some(Self::OutOfFuel),
+            10 => Some(Self::Interrupted),
             _ => None,
         }
     }
@@ -46,6 +56,8 @@ fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             Self::TableAccessOutOfBounds => write!(f, "table access out of bounds"),
             Self::MemAccessOutOfBounds => write!(f, "memory access out of bounds"),
             Self::StackOverflow => write!(f, "stack overflow"),
+            Self::OutOfFuel => write!(f, "out of fuel"),
+            Self::Interrupted => write!(f, "interrupted"),
         }
     }
 }
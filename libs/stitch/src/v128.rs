@@ -0,0 +1,110 @@
+use {
+    crate::decode::{Decode, DecodeError, Decoder},
+    std::{array, fmt},
+};
+
+/// A 128-bit vector value.
+///
+/// A [`V128`] has no fixed interpretation. Depending on the instruction that operates on it, its
+/// bytes are interpreted as 16 8-bit lanes, 8 16-bit lanes, 4 32-bit lanes, or 2 64-bit lanes,
+/// with the first lane stored in the lowest bytes.
+#[derive(Clone, Copy, Default, Eq, Hash, PartialEq)]
+#[repr(transparent)]
+pub struct V128([u8; 16]);
+
+impl V128 {
+    /// Creates a [`V128`] from its little-endian byte representation.
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the little-endian byte representation of this [`V128`].
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0
+    }
+
+    /// Creates a [`V128`] from the given lanes.
+    pub(crate) fn from_lanes<T, const N: usize>(lanes: [T; N]) -> Self
+    where
+        T: Lane,
+    {
+        debug_assert_eq!(N * T::SIZE, 16);
+        let mut bytes = [0; 16];
+        for (lane, chunk) in lanes.into_iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            lane.write_le_bytes(chunk);
+        }
+        Self(bytes)
+    }
+
+    /// Returns the lanes of this [`V128`].
+    pub(crate) fn to_lanes<T, const N: usize>(self) -> [T; N]
+    where
+        T: Lane,
+    {
+        debug_assert_eq!(N * T::SIZE, 16);
+        array::from_fn(|idx| T::read_le_bytes(&self.0[idx * T::SIZE..][..T::SIZE]))
+    }
+}
+
+impl Decode for V128 {
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, DecodeError> {
+        Ok(Self(decoder.read_bytes(16)?.try_into().unwrap()))
+    }
+}
+
+impl fmt::Debug for V128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "V128({:#034x})", u128::from(*self))
+    }
+}
+
+impl From<u128> for V128 {
+    fn from(val: u128) -> Self {
+        Self(val.to_le_bytes())
+    }
+}
+
+impl From<V128> for u128 {
+    fn from(val: V128) -> Self {
+        u128::from_le_bytes(val.0)
+    }
+}
+
+/// A type that can be used as the lane of a [`V128`].
+pub(crate) trait Lane: Copy {
+    /// The size of this lane, in bytes.
+    const SIZE: usize;
+
+    /// Reads a lane from its little-endian byte representation.
+    fn read_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Writes the little-endian byte representation of this lane.
+    fn write_le_bytes(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_lane {
+    ($T:ty) => {
+        impl Lane for $T {
+            const SIZE: usize = std::mem::size_of::<$T>();
+
+            fn read_le_bytes(bytes: &[u8]) -> Self {
+                <$T>::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            fn write_le_bytes(self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_lane!(i8);
+impl_lane!(u8);
+impl_lane!(i16);
+impl_lane!(u16);
+impl_lane!(i32);
+impl_lane!(u32);
+impl_lane!(i64);
+impl_lane!(u64);
+impl_lane!(f32);
+impl_lane!(f64);
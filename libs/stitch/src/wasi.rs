@@ -0,0 +1,1809 @@
+use {
+    crate::{error::Error, func::Func, instance::Instance, linker::Linker, mem::Mem, store::Store},
+    std::{
+        collections::{hash_map::RandomState, BTreeMap},
+        fs,
+        hash::{BuildHasher, Hasher},
+        io::{self, Cursor, Read, Seek, SeekFrom, Write},
+        path::{Path, PathBuf},
+        str,
+        sync::{Arc, Mutex},
+        thread,
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    },
+};
+
+const MODULE: &str = "wasi_snapshot_preview1";
+
+const CLOCK_REALTIME: u32 = 0;
+const CLOCK_MONOTONIC: u32 = 1;
+const CLOCK_PROCESS_CPUTIME: u32 = 2;
+const CLOCK_THREAD_CPUTIME: u32 = 3;
+
+const FDFLAGS_APPEND: u32 = 1 << 0;
+
+const OFLAGS_CREAT: u32 = 1 << 0;
+const OFLAGS_DIRECTORY: u32 = 1 << 1;
+const OFLAGS_EXCL: u32 = 1 << 2;
+const OFLAGS_TRUNC: u32 = 1 << 3;
+
+const RIGHTS_FD_READ: u64 = 1 << 1;
+const RIGHTS_FD_WRITE: u64 = 1 << 6;
+const RIGHTS_ALL: u64 = (1 << 30) - 1;
+
+const WHENCE_SET: u32 = 0;
+const WHENCE_CUR: u32 = 1;
+const WHENCE_END: u32 = 2;
+
+const EVENTTYPE_CLOCK: u8 = 0;
+const EVENTTYPE_FD_READ: u8 = 1;
+const EVENTTYPE_FD_WRITE: u8 = 2;
+
+const SUBCLOCKFLAGS_ABSTIME: u16 = 1 << 0;
+
+const PREOPENTYPE_DIR: u8 = 0;
+
+/// A host implementation of WASI preview 1.
+///
+/// A [`Wasi`] defines the `wasi_snapshot_preview1` imports in a [`Linker`], so that programs
+/// compiled for `wasm32-wasi` can be run. A program only has access to the arguments, environment
+/// variables, standard streams and directories it was given by the [`WasiBuilder`].
+#[derive(Clone, Debug)]
+pub struct Wasi {
+    state: Arc<Mutex<WasiState>>,
+}
+
+impl Wasi {
+    /// Returns a [`WasiBuilder`] for creating a new [`Wasi`].
+    pub fn builder() -> WasiBuilder {
+        WasiBuilder::new()
+    }
+
+    /// Defines the `wasi_snapshot_preview1` imports in the given [`Linker`].
+    ///
+    /// The imports read their arguments from and write their results to the [`Mem`] that was set
+    /// with [`Wasi::set_mem`].
+    pub fn add_to_linker(&self, store: &mut Store, linker: &mut Linker) {
+        macro_rules! define_funcs {
+            ($($name:ident($($arg:ident: $T:ty),*);)*) => {
+                $(
+                    let state = self.state.clone();
+                    linker.define(
+                        MODULE,
+                        stringify!($name),
+                        Func::wrap(store, move |store: &mut Store, $($arg: $T),*| -> u32 {
+                            let mut state = state.lock().unwrap();
+                            let mem: &mut [u8] = match state.mem.and_then(|index| store.mem_at(index)) {
+                                Some(mem) => Mem(mem).bytes_mut(store),
+                                None => &mut [],
+                            };
+                            match state.$name(mem, $($arg),*) {
+                                Ok(()) => 0,
+                                Err(errno) => errno as u32,
+                            }
+                        }),
+                    );
+                )*
+            };
+        }
+
+        define_funcs! {
+            args_get(argv: u32, argv_buf: u32);
+            args_sizes_get(argc_ptr: u32, argv_buf_size_ptr: u32);
+            environ_get(environ: u32, environ_buf: u32);
+            environ_sizes_get(environc_ptr: u32, environ_buf_size_ptr: u32);
+            clock_res_get(id: u32, resolution_ptr: u32);
+            clock_time_get(id: u32, precision: u64, time_ptr: u32);
+            fd_advise(fd: u32, offset: u64, len: u64, advice: u32);
+            fd_allocate(fd: u32, offset: u64, len: u64);
+            fd_close(fd: u32);
+            fd_datasync(fd: u32);
+            fd_fdstat_get(fd: u32, fdstat_ptr: u32);
+            fd_fdstat_set_flags(fd: u32, flags: u32);
+            fd_fdstat_set_rights(fd: u32, rights_base: u64, rights_inheriting: u64);
+            fd_filestat_get(fd: u32, filestat_ptr: u32);
+            fd_filestat_set_size(fd: u32, size: u64);
+            fd_filestat_set_times(fd: u32, atim: u64, mtim: u64, fst_flags: u32);
+            fd_pread(fd: u32, iovs: u32, iovs_len: u32, offset: u64, nread_ptr: u32);
+            fd_prestat_get(fd: u32, prestat_ptr: u32);
+            fd_prestat_dir_name(fd: u32, path: u32, path_len: u32);
+            fd_pwrite(fd: u32, iovs: u32, iovs_len: u32, offset: u64, nwritten_ptr: u32);
+            fd_read(fd: u32, iovs: u32, iovs_len: u32, nread_ptr: u32);
+            fd_readdir(fd: u32, buf: u32, buf_len: u32, cookie: u64, bufused_ptr: u32);
+            fd_renumber(fd: u32, to: u32);
+            fd_seek(fd: u32, offset: i64, whence: u32, newoffset_ptr: u32);
+            fd_sync(fd: u32);
+            fd_tell(fd: u32, offset_ptr: u32);
+            fd_write(fd: u32, iovs: u32, iovs_len: u32, nwritten_ptr: u32);
+            path_create_directory(fd: u32, path: u32, path_len: u32);
+            path_filestat_get(fd: u32, flags: u32, path: u32, path_len: u32, filestat_ptr: u32);
+            path_filestat_set_times(
+                fd: u32,
+                flags: u32,
+                path: u32,
+                path_len: u32,
+                atim: u64,
+                mtim: u64,
+                fst_flags: u32
+            );
+            path_link(
+                old_fd: u32,
+                old_flags: u32,
+                old_path: u32,
+                old_path_len: u32,
+                new_fd: u32,
+                new_path: u32,
+                new_path_len: u32
+            );
+            path_open(
+                fd: u32,
+                dirflags: u32,
+                path: u32,
+                path_len: u32,
+                oflags: u32,
+                rights_base: u64,
+                rights_inheriting: u64,
+                fdflags: u32,
+                fd_ptr: u32
+            );
+            path_readlink(
+                fd: u32,
+                path: u32,
+                path_len: u32,
+                buf: u32,
+                buf_len: u32,
+                bufused_ptr: u32
+            );
+            path_remove_directory(fd: u32, path: u32, path_len: u32);
+            path_rename(
+                fd: u32,
+                old_path: u32,
+                old_path_len: u32,
+                new_fd: u32,
+                new_path: u32,
+                new_path_len: u32
+            );
+            path_symlink(
+                old_path: u32,
+                old_path_len: u32,
+                fd: u32,
+                new_path: u32,
+                new_path_len: u32
+            );
+            path_unlink_file(fd: u32, path: u32, path_len: u32);
+            poll_oneoff(in_: u32, out: u32, nsubscriptions: u32, nevents_ptr: u32);
+            proc_raise(sig: u32);
+            sched_yield();
+            random_get(buf: u32, buf_len: u32);
+            sock_accept(fd: u32, flags: u32, fd_ptr: u32);
+            sock_recv(
+                fd: u32,
+                ri_data: u32,
+                ri_data_len: u32,
+                ri_flags: u32,
+                ro_datalen_ptr: u32,
+                ro_flags_ptr: u32
+            );
+            sock_send(
+                fd: u32,
+                si_data: u32,
+                si_data_len: u32,
+                si_flags: u32,
+                so_datalen_ptr: u32
+            );
+            sock_shutdown(fd: u32, how: u32);
+        }
+
+        // Exiting unwinds the call, so it is the only import that does not return an errno.
+        linker.define(
+            MODULE,
+            "proc_exit",
+            Func::wrap(store, |code: u32| -> Result<(), Error> {
+                Err(Error::Exit(code as i32))
+            }),
+        );
+    }
+
+    /// Sets the [`Mem`] that the imports read their arguments from and write their results to.
+    ///
+    /// This is the memory that the program exports as `memory`. The imports look the memory up in
+    /// the [`Store`] they are called with, so it has to belong to that [`Store`].
+    pub fn set_mem(&self, store: &Store, mem: Mem) {
+        self.state.lock().unwrap().mem = Some(store.mem_index(mem.0));
+    }
+
+    /// Runs the program in the given [`Instance`] by calling its `_start` function.
+    ///
+    /// Returns the exit code of the program, which is 0 if `_start` returns normally.
+    ///
+    /// # Errors
+    ///
+    /// If the call to `_start` fails for any reason other than the program exiting.
+    ///
+    /// # Panics
+    ///
+    /// If the [`Instance`] does not export a `memory` and a `_start` function.
+    pub fn run(&self, store: &mut Store, instance: &Instance) -> Result<i32, Error> {
+        let mem = instance
+            .exported_mem("memory")
+            .expect("instance does not export memory");
+        let start = instance
+            .exported_func("_start")
+            .expect("instance does not export _start");
+        self.set_mem(store, mem);
+        match start.call(store, &[], &mut []) {
+            Ok(()) => Ok(0),
+            Err(Error::Exit(code)) => Ok(code),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Returns everything the program has written to its standard output so far.
+    ///
+    /// This is always empty if the standard output is inherited.
+    pub fn stdout(&self) -> Vec<u8> {
+        self.state.lock().unwrap().stdout.captured().to_vec()
+    }
+
+    /// Returns everything the program has written to its standard error so far.
+    ///
+    /// This is always empty if the standard error is inherited.
+    pub fn stderr(&self) -> Vec<u8> {
+        self.state.lock().unwrap().stderr.captured().to_vec()
+    }
+}
+
+/// A builder for a [`Wasi`].
+///
+/// By default, a program has no arguments, no environment variables and no directories. Its
+/// standard input is empty, and its standard output and error are captured, so they can be read
+/// with [`Wasi::stdout`] and [`Wasi::stderr`].
+#[derive(Debug, Default)]
+pub struct WasiBuilder {
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    stdin: Input,
+    stdout: Output,
+    stderr: Output,
+    preopens: Vec<(String, Fs)>,
+}
+
+impl WasiBuilder {
+    /// Creates a new [`WasiBuilder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an argument. The first argument is usually the name of the program.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds the given arguments.
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds an environment variable.
+    pub fn env(mut self, key: impl Into<String>, val: impl Into<String>) -> Self {
+        self.envs.push((key.into(), val.into()));
+        self
+    }
+
+    /// Adds the environment variables of the host process.
+    pub fn inherit_env(mut self) -> Self {
+        self.envs.extend(std::env::vars());
+        self
+    }
+
+    /// Sets the standard input to the given bytes.
+    pub fn stdin(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Input::Bytes(Cursor::new(bytes.into()));
+        self
+    }
+
+    /// Reads the standard input from the standard input of the host process.
+    pub fn inherit_stdin(mut self) -> Self {
+        self.stdin = Input::Inherit;
+        self
+    }
+
+    /// Writes the standard output to the standard output of the host process.
+    pub fn inherit_stdout(mut self) -> Self {
+        self.stdout = Output::Inherit;
+        self
+    }
+
+    /// Writes the standard error to the standard error of the host process.
+    pub fn inherit_stderr(mut self) -> Self {
+        self.stderr = Output::Inherit;
+        self
+    }
+
+    /// Inherits all three standard streams from the host process.
+    pub fn inherit_stdio(self) -> Self {
+        self.inherit_stdin().inherit_stdout().inherit_stderr()
+    }
+
+    /// Gives the program access to the given host directory, under the given guest path.
+    ///
+    /// The program cannot use `..` or absolute paths to get out of the directory. Symbolic links
+    /// are only followed if they point to somewhere inside of it.
+    pub fn preopen_dir(
+        mut self,
+        guest_path: impl Into<String>,
+        host_path: impl Into<PathBuf>,
+    ) -> Self {
+        self.preopens
+            .push((guest_path.into(), Fs::Host(host_path.into())));
+        self
+    }
+
+    /// Gives the program access to the given [`VirtualDir`], under the given guest path.
+    pub fn preopen_virtual_dir(mut self, guest_path: impl Into<String>, dir: VirtualDir) -> Self {
+        self.preopens.push((guest_path.into(), Fs::Virtual(dir)));
+        self
+    }
+
+    /// Creates a new [`Wasi`] from this [`WasiBuilder`].
+    pub fn build(self) -> Wasi {
+        let mut fds = vec![Some(Fd::Stdin), Some(Fd::Stdout), Some(Fd::Stderr)];
+        for (guest_path, fs) in self.preopens {
+            fds.push(Some(Fd::Dir(DirFd {
+                fs,
+                path: Vec::new(),
+                preopen: Some(guest_path),
+            })));
+        }
+        Wasi {
+            state: Arc::new(Mutex::new(WasiState {
+                args: self.args,
+                envs: self
+                    .envs
+                    .into_iter()
+                    .map(|(key, val)| format!("{}={}", key, val))
+                    .collect(),
+                stdin: self.stdin,
+                stdout: self.stdout,
+                stderr: self.stderr,
+                fds,
+                mem: None,
+                start: Instant::now(),
+                random: RandomState::new(),
+                random_counter: 0,
+            })),
+        }
+    }
+}
+
+/// A directory that lives in memory.
+///
+/// A [`VirtualDir`] is shared rather than copied when it is cloned, so changes the program makes
+/// to it can be inspected after the program has run.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualDir {
+    entries: Arc<Mutex<BTreeMap<String, VirtualNode>>>,
+}
+
+impl VirtualDir {
+    /// Creates a new, empty [`VirtualDir`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file with the given name and contents to this [`VirtualDir`].
+    pub fn with_file(self, name: impl Into<String>, contents: impl Into<Vec<u8>>) -> Self {
+        self.entries.lock().unwrap().insert(
+            name.into(),
+            VirtualNode::File(VirtualFile {
+                data: Arc::new(Mutex::new(contents.into())),
+            }),
+        );
+        self
+    }
+
+    /// Adds a subdirectory with the given name to this [`VirtualDir`].
+    pub fn with_dir(self, name: impl Into<String>, dir: VirtualDir) -> Self {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(name.into(), VirtualNode::Dir(dir));
+        self
+    }
+
+    /// Returns the contents of the file at the given path in this [`VirtualDir`], if it exists.
+    pub fn read_file(&self, path: &str) -> Option<Vec<u8>> {
+        let path = resolve_path(&[], path).ok()?;
+        match self.lookup(&path).ok()? {
+            VirtualNode::File(file) => Some(file.data.lock().unwrap().clone()),
+            VirtualNode::Dir(_) => None,
+        }
+    }
+
+    fn lookup(&self, path: &[String]) -> Result<VirtualNode, Errno> {
+        let mut node = VirtualNode::Dir(self.clone());
+        for name in path {
+            let VirtualNode::Dir(dir) = node else {
+                return Err(Errno::Notdir);
+            };
+            node = dir
+                .entries
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or(Errno::Noent)?;
+        }
+        Ok(node)
+    }
+
+    fn lookup_dir(&self, path: &[String]) -> Result<VirtualDir, Errno> {
+        match self.lookup(path)? {
+            VirtualNode::File(_) => Err(Errno::Notdir),
+            VirtualNode::Dir(dir) => Ok(dir),
+        }
+    }
+
+    fn is_same(&self, other: &VirtualDir) -> bool {
+        Arc::ptr_eq(&self.entries, &other.entries)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum VirtualNode {
+    File(VirtualFile),
+    Dir(VirtualDir),
+}
+
+impl VirtualNode {
+    fn stat(&self) -> Filestat {
+        match self {
+            Self::File(file) => Filestat {
+                filetype: Filetype::RegularFile,
+                size: file.data.lock().unwrap().len() as u64,
+                ..Filestat::default()
+            },
+            Self::Dir(_) => Filestat {
+                filetype: Filetype::Directory,
+                ..Filestat::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct VirtualFile {
+    data: Arc<Mutex<Vec<u8>>>,
+}
+
+#[derive(Debug)]
+struct WasiState {
+    args: Vec<String>,
+    envs: Vec<String>,
+    stdin: Input,
+    stdout: Output,
+    stderr: Output,
+    fds: Vec<Option<Fd>>,
+    // the position of the memory in the store, as the state has to be `Send`
+    mem: Option<usize>,
+    start: Instant,
+    random: RandomState,
+    random_counter: u64,
+}
+
+impl WasiState {
+    fn args_get(&mut self, mem: &mut [u8], argv: u32, argv_buf: u32) -> Result<(), Errno> {
+        write_strings(mem, &self.args, argv, argv_buf)
+    }
+
+    fn args_sizes_get(
+        &mut self,
+        mem: &mut [u8],
+        argc_ptr: u32,
+        argv_buf_size_ptr: u32,
+    ) -> Result<(), Errno> {
+        write_string_sizes(mem, &self.args, argc_ptr, argv_buf_size_ptr)
+    }
+
+    fn environ_get(&mut self, mem: &mut [u8], environ: u32, environ_buf: u32) -> Result<(), Errno> {
+        write_strings(mem, &self.envs, environ, environ_buf)
+    }
+
+    fn environ_sizes_get(
+        &mut self,
+        mem: &mut [u8],
+        environc_ptr: u32,
+        environ_buf_size_ptr: u32,
+    ) -> Result<(), Errno> {
+        write_string_sizes(mem, &self.envs, environc_ptr, environ_buf_size_ptr)
+    }
+
+    fn clock_res_get(&mut self, mem: &mut [u8], id: u32, resolution_ptr: u32) -> Result<(), Errno> {
+        self.now(id)?;
+        write_u64(mem, resolution_ptr, 1)
+    }
+
+    fn clock_time_get(
+        &mut self,
+        mem: &mut [u8],
+        id: u32,
+        _precision: u64,
+        time_ptr: u32,
+    ) -> Result<(), Errno> {
+        let time = self.now(id)?;
+        write_u64(mem, time_ptr, time)
+    }
+
+    fn fd_advise(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _offset: u64,
+        _len: u64,
+        _advice: u32,
+    ) -> Result<(), Errno> {
+        self.file(fd)?;
+        Ok(())
+    }
+
+    fn fd_allocate(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        offset: u64,
+        len: u64,
+    ) -> Result<(), Errno> {
+        let file = self.writable_file(fd)?;
+        let size = offset.checked_add(len).ok_or(Errno::Inval)?;
+        if file.file.size()? < size {
+            file.file.set_size(size)?;
+        }
+        Ok(())
+    }
+
+    fn fd_close(&mut self, _mem: &mut [u8], fd: u32) -> Result<(), Errno> {
+        self.fd(fd)?;
+        self.fds[fd as usize] = None;
+        Ok(())
+    }
+
+    fn fd_datasync(&mut self, mem: &mut [u8], fd: u32) -> Result<(), Errno> {
+        self.fd_sync(mem, fd)
+    }
+
+    fn fd_fdstat_get(&mut self, mem: &mut [u8], fd: u32, fdstat_ptr: u32) -> Result<(), Errno> {
+        let (filetype, flags, rights) = match self.fd(fd)? {
+            Fd::Stdin => (Filetype::CharacterDevice, 0, RIGHTS_ALL & !RIGHTS_FD_WRITE),
+            Fd::Stdout | Fd::Stderr => (Filetype::CharacterDevice, 0, RIGHTS_ALL & !RIGHTS_FD_READ),
+            Fd::File(file) => {
+                let mut rights = RIGHTS_ALL;
+                if !file.readable {
+                    rights &= !RIGHTS_FD_READ;
+                }
+                if !file.writable {
+                    rights &= !RIGHTS_FD_WRITE;
+                }
+                let flags = if file.append { FDFLAGS_APPEND } else { 0 };
+                (Filetype::RegularFile, flags, rights)
+            }
+            Fd::Dir(_) => (Filetype::Directory, 0, RIGHTS_ALL),
+        };
+        let fdstat = bytes_mut(mem, fdstat_ptr, 24)?;
+        fdstat.fill(0);
+        fdstat[0] = filetype as u8;
+        fdstat[2..4].copy_from_slice(&(flags as u16).to_le_bytes());
+        fdstat[8..16].copy_from_slice(&rights.to_le_bytes());
+        fdstat[16..24].copy_from_slice(&RIGHTS_ALL.to_le_bytes());
+        Ok(())
+    }
+
+    fn fd_fdstat_set_flags(&mut self, _mem: &mut [u8], fd: u32, flags: u32) -> Result<(), Errno> {
+        match self.fd(fd)? {
+            Fd::File(file) => {
+                file.append = flags & FDFLAGS_APPEND != 0;
+                Ok(())
+            }
+            _ if flags == 0 => Ok(()),
+            _ => Err(Errno::Notsup),
+        }
+    }
+
+    fn fd_fdstat_set_rights(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _rights_base: u64,
+        _rights_inheriting: u64,
+    ) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Ok(())
+    }
+
+    fn fd_filestat_get(&mut self, mem: &mut [u8], fd: u32, filestat_ptr: u32) -> Result<(), Errno> {
+        let stat = match self.fd(fd)? {
+            Fd::Stdin | Fd::Stdout | Fd::Stderr => Filestat {
+                filetype: Filetype::CharacterDevice,
+                ..Filestat::default()
+            },
+            Fd::File(file) => file.file.stat()?,
+            Fd::Dir(dir) => dir.fs.stat(&dir.path)?,
+        };
+        write_filestat(mem, filestat_ptr, &stat)
+    }
+
+    fn fd_filestat_set_size(&mut self, _mem: &mut [u8], fd: u32, size: u64) -> Result<(), Errno> {
+        self.writable_file(fd)?.file.set_size(size)
+    }
+
+    fn fd_filestat_set_times(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _atim: u64,
+        _mtim: u64,
+        _fst_flags: u32,
+    ) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Err(Errno::Notsup)
+    }
+
+    fn fd_pread(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        iovs: u32,
+        iovs_len: u32,
+        offset: u64,
+        nread_ptr: u32,
+    ) -> Result<(), Errno> {
+        let iovs = read_iovs(mem, iovs, iovs_len)?;
+        let mut buf = vec![0; iovs_total_len(&iovs)];
+        let file = self.readable_file(fd)?;
+        let count = file.file.read_at(&mut buf, offset)?;
+        scatter(mem, &iovs, &buf[..count])?;
+        write_u32(mem, nread_ptr, count as u32)
+    }
+
+    fn fd_prestat_get(&mut self, mem: &mut [u8], fd: u32, prestat_ptr: u32) -> Result<(), Errno> {
+        let name_len = self.preopen(fd)?.len();
+        let prestat = bytes_mut(mem, prestat_ptr, 8)?;
+        prestat.fill(0);
+        prestat[0] = PREOPENTYPE_DIR;
+        prestat[4..8].copy_from_slice(&(name_len as u32).to_le_bytes());
+        Ok(())
+    }
+
+    fn fd_prestat_dir_name(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+    ) -> Result<(), Errno> {
+        let name = self.preopen(fd)?;
+        if (path_len as usize) < name.len() {
+            return Err(Errno::Nametoolong);
+        }
+        bytes_mut(mem, path, name.len() as u32)?.copy_from_slice(name.as_bytes());
+        Ok(())
+    }
+
+    fn fd_pwrite(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        iovs: u32,
+        iovs_len: u32,
+        offset: u64,
+        nwritten_ptr: u32,
+    ) -> Result<(), Errno> {
+        let buf = gather(mem, iovs, iovs_len)?;
+        let file = self.writable_file(fd)?;
+        let count = file.file.write_at(&buf, offset)?;
+        write_u32(mem, nwritten_ptr, count as u32)
+    }
+
+    fn fd_read(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        iovs: u32,
+        iovs_len: u32,
+        nread_ptr: u32,
+    ) -> Result<(), Errno> {
+        let iovs = read_iovs(mem, iovs, iovs_len)?;
+        let mut buf = vec![0; iovs_total_len(&iovs)];
+        let count = match self.fd(fd)? {
+            Fd::Stdin => self.stdin.read(&mut buf)?,
+            Fd::File(_) => {
+                let file = self.readable_file(fd)?;
+                let count = file.file.read_at(&mut buf, file.offset)?;
+                file.offset += count as u64;
+                count
+            }
+            Fd::Dir(_) => return Err(Errno::Isdir),
+            Fd::Stdout | Fd::Stderr => return Err(Errno::Badf),
+        };
+        scatter(mem, &iovs, &buf[..count])?;
+        write_u32(mem, nread_ptr, count as u32)
+    }
+
+    fn fd_readdir(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        buf: u32,
+        buf_len: u32,
+        cookie: u64,
+        bufused_ptr: u32,
+    ) -> Result<(), Errno> {
+        let dir = self.dir(fd)?;
+        let entries = dir.fs.read_dir(&dir.path)?;
+        let mut dirents = Vec::new();
+        for (index, (name, filetype)) in entries.iter().enumerate().skip(cookie as usize) {
+            if dirents.len() >= buf_len as usize {
+                break;
+            }
+            dirents.extend_from_slice(&(index as u64 + 1).to_le_bytes());
+            dirents.extend_from_slice(&0u64.to_le_bytes());
+            dirents.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            dirents.extend_from_slice(&[*filetype as u8, 0, 0, 0]);
+            dirents.extend_from_slice(name.as_bytes());
+        }
+
+        // If the entries don't fit, we fill the buffer up completely, which tells the program to
+        // call us again.
+        dirents.truncate(buf_len as usize);
+        bytes_mut(mem, buf, dirents.len() as u32)?.copy_from_slice(&dirents);
+        write_u32(mem, bufused_ptr, dirents.len() as u32)
+    }
+
+    fn fd_renumber(&mut self, _mem: &mut [u8], fd: u32, to: u32) -> Result<(), Errno> {
+        self.fd(fd)?;
+        self.fd(to)?;
+        self.fds[to as usize] = self.fds[fd as usize].take();
+        Ok(())
+    }
+
+    fn fd_seek(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        offset: i64,
+        whence: u32,
+        newoffset_ptr: u32,
+    ) -> Result<(), Errno> {
+        let file = self.file(fd)?;
+        let base = match whence {
+            WHENCE_SET => 0,
+            WHENCE_CUR => file.offset,
+            WHENCE_END => file.file.size()?,
+            _ => return Err(Errno::Inval),
+        };
+        let new_offset = base.checked_add_signed(offset).ok_or(Errno::Inval)?;
+        file.offset = new_offset;
+        write_u64(mem, newoffset_ptr, new_offset)
+    }
+
+    fn fd_sync(&mut self, _mem: &mut [u8], fd: u32) -> Result<(), Errno> {
+        match self.fd(fd)? {
+            Fd::File(file) => file.file.sync(),
+            _ => Ok(()),
+        }
+    }
+
+    fn fd_tell(&mut self, mem: &mut [u8], fd: u32, offset_ptr: u32) -> Result<(), Errno> {
+        let offset = self.file(fd)?.offset;
+        write_u64(mem, offset_ptr, offset)
+    }
+
+    fn fd_write(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        iovs: u32,
+        iovs_len: u32,
+        nwritten_ptr: u32,
+    ) -> Result<(), Errno> {
+        let buf = gather(mem, iovs, iovs_len)?;
+        let count = match self.fd(fd)? {
+            Fd::Stdout => self.stdout.write(&buf, io::stdout())?,
+            Fd::Stderr => self.stderr.write(&buf, io::stderr())?,
+            Fd::File(_) => {
+                let file = self.writable_file(fd)?;
+                if file.append {
+                    file.offset = file.file.size()?;
+                }
+                let count = file.file.write_at(&buf, file.offset)?;
+                file.offset += count as u64;
+                count
+            }
+            Fd::Dir(_) => return Err(Errno::Isdir),
+            Fd::Stdin => return Err(Errno::Badf),
+        };
+        write_u32(mem, nwritten_ptr, count as u32)
+    }
+
+    fn path_create_directory(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+    ) -> Result<(), Errno> {
+        let (fs, path) = self.resolve(mem, fd, path, path_len)?;
+        fs.create_dir(&path)
+    }
+
+    fn path_filestat_get(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        _flags: u32,
+        path: u32,
+        path_len: u32,
+        filestat_ptr: u32,
+    ) -> Result<(), Errno> {
+        let (fs, path) = self.resolve(mem, fd, path, path_len)?;
+        let stat = fs.stat(&path)?;
+        write_filestat(mem, filestat_ptr, &stat)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn path_filestat_set_times(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        _flags: u32,
+        path: u32,
+        path_len: u32,
+        _atim: u64,
+        _mtim: u64,
+        _fst_flags: u32,
+    ) -> Result<(), Errno> {
+        self.resolve(mem, fd, path, path_len)?;
+        Err(Errno::Notsup)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn path_link(
+        &mut self,
+        _mem: &mut [u8],
+        _old_fd: u32,
+        _old_flags: u32,
+        _old_path: u32,
+        _old_path_len: u32,
+        _new_fd: u32,
+        _new_path: u32,
+        _new_path_len: u32,
+    ) -> Result<(), Errno> {
+        Err(Errno::Notsup)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn path_open(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        _dirflags: u32,
+        path: u32,
+        path_len: u32,
+        oflags: u32,
+        rights_base: u64,
+        _rights_inheriting: u64,
+        fdflags: u32,
+        fd_ptr: u32,
+    ) -> Result<(), Errno> {
+        let (fs, path) = self.resolve(mem, fd, path, path_len)?;
+        let readable = rights_base & RIGHTS_FD_READ != 0;
+        let writable = rights_base & RIGHTS_FD_WRITE != 0;
+        let fd = match fs.open(&path, oflags, writable)? {
+            Some(file) => Fd::File(FileFd {
+                file,
+                offset: 0,
+                readable,
+                writable,
+                append: fdflags & FDFLAGS_APPEND != 0,
+            }),
+            None => Fd::Dir(DirFd {
+                fs,
+                path,
+                preopen: None,
+            }),
+        };
+        let fd = self.insert_fd(fd);
+        write_u32(mem, fd_ptr, fd)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn path_readlink(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+        _buf: u32,
+        _buf_len: u32,
+        _bufused_ptr: u32,
+    ) -> Result<(), Errno> {
+        self.resolve(mem, fd, path, path_len)?;
+        Err(Errno::Notsup)
+    }
+
+    fn path_remove_directory(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+    ) -> Result<(), Errno> {
+        let (fs, path) = self.resolve(mem, fd, path, path_len)?;
+        fs.remove_dir(&path)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn path_rename(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        old_path: u32,
+        old_path_len: u32,
+        new_fd: u32,
+        new_path: u32,
+        new_path_len: u32,
+    ) -> Result<(), Errno> {
+        let (old_fs, old_path) = self.resolve(mem, fd, old_path, old_path_len)?;
+        let (new_fs, new_path) = self.resolve(mem, new_fd, new_path, new_path_len)?;
+        old_fs.rename(&old_path, &new_fs, &new_path)
+    }
+
+    fn path_symlink(
+        &mut self,
+        _mem: &mut [u8],
+        _old_path: u32,
+        _old_path_len: u32,
+        _fd: u32,
+        _new_path: u32,
+        _new_path_len: u32,
+    ) -> Result<(), Errno> {
+        Err(Errno::Notsup)
+    }
+
+    fn path_unlink_file(
+        &mut self,
+        mem: &mut [u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+    ) -> Result<(), Errno> {
+        let (fs, path) = self.resolve(mem, fd, path, path_len)?;
+        fs.unlink_file(&path)
+    }
+
+    fn poll_oneoff(
+        &mut self,
+        mem: &mut [u8],
+        in_: u32,
+        out: u32,
+        nsubscriptions: u32,
+        nevents_ptr: u32,
+    ) -> Result<(), Errno> {
+        if nsubscriptions == 0 {
+            return Err(Errno::Inval);
+        }
+
+        // File descriptors are always ready, so we only wait for the earliest clock if there are
+        // none.
+        let mut events = Vec::new();
+        let mut earliest_clock: Option<(u64, u64)> = None;
+        for index in 0..nsubscriptions as usize {
+            let subscription = bytes(mem, offset(in_, index * 48)?, 48)?;
+            let userdata = u64::from_le_bytes(subscription[0..8].try_into().unwrap());
+            match subscription[8] {
+                EVENTTYPE_CLOCK => {
+                    let id = u32::from_le_bytes(subscription[16..20].try_into().unwrap());
+                    let timeout = u64::from_le_bytes(subscription[24..32].try_into().unwrap());
+                    let flags = u16::from_le_bytes(subscription[40..42].try_into().unwrap());
+                    let now = self.now(id)?;
+                    let delay = if flags & SUBCLOCKFLAGS_ABSTIME != 0 {
+                        timeout.saturating_sub(now)
+                    } else {
+                        timeout
+                    };
+                    if earliest_clock.is_none_or(|(earliest_delay, _)| delay < earliest_delay) {
+                        earliest_clock = Some((delay, userdata));
+                    }
+                }
+                type_ @ (EVENTTYPE_FD_READ | EVENTTYPE_FD_WRITE) => {
+                    let fd = u32::from_le_bytes(subscription[16..20].try_into().unwrap());
+                    let errno = match self.fd(fd) {
+                        Ok(_) => 0,
+                        Err(errno) => errno as u16,
+                    };
+                    events.push((userdata, errno, type_));
+                }
+                _ => return Err(Errno::Inval),
+            }
+        }
+        if events.is_empty() {
+            let (delay, userdata) = earliest_clock.unwrap();
+            thread::sleep(Duration::from_nanos(delay));
+            events.push((userdata, 0, EVENTTYPE_CLOCK));
+        }
+        for (index, (userdata, errno, type_)) in events.iter().enumerate() {
+            let event = bytes_mut(mem, offset(out, index * 32)?, 32)?;
+            event.fill(0);
+            event[0..8].copy_from_slice(&userdata.to_le_bytes());
+            event[8..10].copy_from_slice(&errno.to_le_bytes());
+            event[10] = *type_;
+        }
+        write_u32(mem, nevents_ptr, events.len() as u32)
+    }
+
+    fn proc_raise(&mut self, _mem: &mut [u8], _sig: u32) -> Result<(), Errno> {
+        Err(Errno::Notsup)
+    }
+
+    fn sched_yield(&mut self, _mem: &mut [u8]) -> Result<(), Errno> {
+        thread::yield_now();
+        Ok(())
+    }
+
+    fn random_get(&mut self, mem: &mut [u8], buf: u32, buf_len: u32) -> Result<(), Errno> {
+        // The bytes are produced by a randomly keyed hasher. This is good enough for seeding hash
+        // maps, but not for cryptography.
+        for chunk in bytes_mut(mem, buf, buf_len)?.chunks_mut(8) {
+            let mut hasher = self.random.build_hasher();
+            hasher.write_u64(self.random_counter);
+            self.random_counter += 1;
+            chunk.copy_from_slice(&hasher.finish().to_le_bytes()[..chunk.len()]);
+        }
+        Ok(())
+    }
+
+    fn sock_accept(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _flags: u32,
+        _fd_ptr: u32,
+    ) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Err(Errno::Notsup)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sock_recv(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _ri_data: u32,
+        _ri_data_len: u32,
+        _ri_flags: u32,
+        _ro_datalen_ptr: u32,
+        _ro_flags_ptr: u32,
+    ) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Err(Errno::Notsup)
+    }
+
+    fn sock_send(
+        &mut self,
+        _mem: &mut [u8],
+        fd: u32,
+        _si_data: u32,
+        _si_data_len: u32,
+        _si_flags: u32,
+        _so_datalen_ptr: u32,
+    ) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Err(Errno::Notsup)
+    }
+
+    fn sock_shutdown(&mut self, _mem: &mut [u8], fd: u32, _how: u32) -> Result<(), Errno> {
+        self.fd(fd)?;
+        Err(Errno::Notsup)
+    }
+
+    // Helper methods
+
+    /// Returns the current time of the clock with the given id, in nanoseconds.
+    ///
+    /// The CPU time clocks are approximated by the monotonic clock.
+    fn now(&self, id: u32) -> Result<u64, Errno> {
+        match id {
+            CLOCK_REALTIME => Ok(SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| Errno::Io)?
+                .as_nanos() as u64),
+            CLOCK_MONOTONIC | CLOCK_PROCESS_CPUTIME | CLOCK_THREAD_CPUTIME => {
+                Ok(self.start.elapsed().as_nanos() as u64)
+            }
+            _ => Err(Errno::Inval),
+        }
+    }
+
+    fn fd(&mut self, fd: u32) -> Result<&mut Fd, Errno> {
+        self.fds
+            .get_mut(fd as usize)
+            .and_then(Option::as_mut)
+            .ok_or(Errno::Badf)
+    }
+
+    fn file(&mut self, fd: u32) -> Result<&mut FileFd, Errno> {
+        match self.fd(fd)? {
+            Fd::File(file) => Ok(file),
+            Fd::Dir(_) => Err(Errno::Isdir),
+            Fd::Stdin | Fd::Stdout | Fd::Stderr => Err(Errno::Spipe),
+        }
+    }
+
+    fn readable_file(&mut self, fd: u32) -> Result<&mut FileFd, Errno> {
+        let file = self.file(fd)?;
+        if !file.readable {
+            return Err(Errno::Badf);
+        }
+        Ok(file)
+    }
+
+    fn writable_file(&mut self, fd: u32) -> Result<&mut FileFd, Errno> {
+        let file = self.file(fd)?;
+        if !file.writable {
+            return Err(Errno::Badf);
+        }
+        Ok(file)
+    }
+
+    fn dir(&mut self, fd: u32) -> Result<&mut DirFd, Errno> {
+        match self.fd(fd)? {
+            Fd::Dir(dir) => Ok(dir),
+            _ => Err(Errno::Notdir),
+        }
+    }
+
+    fn preopen(&mut self, fd: u32) -> Result<&str, Errno> {
+        self.dir(fd)?.preopen.as_deref().ok_or(Errno::Badf)
+    }
+
+    /// Resolves the given path relative to the directory with the given file descriptor.
+    fn resolve(
+        &mut self,
+        mem: &[u8],
+        fd: u32,
+        path: u32,
+        path_len: u32,
+    ) -> Result<(Fs, Vec<String>), Errno> {
+        let path = str::from_utf8(bytes(mem, path, path_len)?).map_err(|_| Errno::Ilseq)?;
+        let dir = self.dir(fd)?;
+        Ok((dir.fs.clone(), resolve_path(&dir.path, path)?))
+    }
+
+    fn insert_fd(&mut self, fd: Fd) -> u32 {
+        match self.fds.iter().position(Option::is_none) {
+            Some(index) => {
+                self.fds[index] = Some(fd);
+                index as u32
+            }
+            None => {
+                self.fds.push(Some(fd));
+                (self.fds.len() - 1) as u32
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Input {
+    Bytes(Cursor<Vec<u8>>),
+    Inherit,
+}
+
+impl Input {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Errno> {
+        Ok(match self {
+            Self::Bytes(bytes) => bytes.read(buf)?,
+            Self::Inherit => io::stdin().read(buf)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum Output {
+    Capture(Vec<u8>),
+    Inherit,
+}
+
+impl Output {
+    fn captured(&self) -> &[u8] {
+        match self {
+            Self::Capture(bytes) => bytes,
+            Self::Inherit => &[],
+        }
+    }
+
+    fn write(&mut self, buf: &[u8], mut host: impl Write) -> Result<usize, Errno> {
+        match self {
+            Self::Capture(bytes) => bytes.extend_from_slice(buf),
+            Self::Inherit => {
+                host.write_all(buf)?;
+                host.flush()?;
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Self::Bytes(Cursor::new(Vec::new()))
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::Capture(Vec::new())
+    }
+}
+
+#[derive(Debug)]
+enum Fd {
+    Stdin,
+    Stdout,
+    Stderr,
+    File(FileFd),
+    Dir(DirFd),
+}
+
+#[derive(Debug)]
+struct FileFd {
+    file: File,
+    offset: u64,
+    readable: bool,
+    writable: bool,
+    append: bool,
+}
+
+#[derive(Debug)]
+struct DirFd {
+    fs: Fs,
+    path: Vec<String>,
+    preopen: Option<String>,
+}
+
+/// A file, either on the host or in a [`VirtualDir`].
+#[derive(Debug)]
+enum File {
+    Host(fs::File),
+    Virtual(VirtualFile),
+}
+
+impl File {
+    fn read_at(&mut self, buf: &mut [u8], offset: u64) -> Result<usize, Errno> {
+        match self {
+            Self::Host(file) => {
+                file.seek(SeekFrom::Start(offset))?;
+                Ok(file.read(buf)?)
+            }
+            Self::Virtual(file) => {
+                let data = file.data.lock().unwrap();
+                let start = (offset as usize).min(data.len());
+                let count = buf.len().min(data.len() - start);
+                buf[..count].copy_from_slice(&data[start..][..count]);
+                Ok(count)
+            }
+        }
+    }
+
+    fn write_at(&mut self, buf: &[u8], offset: u64) -> Result<usize, Errno> {
+        match self {
+            Self::Host(file) => {
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(buf)?;
+            }
+            Self::Virtual(file) => {
+                let mut data = file.data.lock().unwrap();
+                let start = offset as usize;
+                let end = start + buf.len();
+                if data.len() < end {
+                    data.resize(end, 0);
+                }
+                data[start..end].copy_from_slice(buf);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn size(&self) -> Result<u64, Errno> {
+        Ok(self.stat()?.size)
+    }
+
+    fn set_size(&mut self, size: u64) -> Result<(), Errno> {
+        match self {
+            Self::Host(file) => file.set_len(size)?,
+            Self::Virtual(file) => file.data.lock().unwrap().resize(size as usize, 0),
+        }
+        Ok(())
+    }
+
+    fn stat(&self) -> Result<Filestat, Errno> {
+        match self {
+            Self::Host(file) => Ok(Filestat::from_metadata(&file.metadata()?)),
+            Self::Virtual(file) => Ok(VirtualNode::File(file.clone()).stat()),
+        }
+    }
+
+    fn sync(&mut self) -> Result<(), Errno> {
+        match self {
+            Self::Host(file) => file.sync_all()?,
+            Self::Virtual(_) => {}
+        }
+        Ok(())
+    }
+}
+
+/// The root of a preopened directory, either on the host or in memory.
+#[derive(Clone, Debug)]
+enum Fs {
+    Host(PathBuf),
+    Virtual(VirtualDir),
+}
+
+impl Fs {
+    /// Returns the host path for the given path, failing if a symbolic link on the way points
+    /// outside of the root.
+    ///
+    /// If `follow` is `false`, a symbolic link in the last component is not followed, so that it
+    /// can be removed or renamed itself.
+    fn host_path(root: &Path, path: &[String], follow: bool) -> Result<PathBuf, Errno> {
+        let root = root.canonicalize()?;
+        let Some((name, parent_path)) = path.split_last() else {
+            return Ok(root);
+        };
+        let mut parent = root.clone();
+        parent.extend(parent_path);
+        let parent = parent.canonicalize()?;
+        if !parent.starts_with(&root) {
+            return Err(Errno::Perm);
+        }
+        let host_path = parent.join(name);
+        if !follow
+            || !fs::symlink_metadata(&host_path).is_ok_and(|metadata| metadata.is_symlink())
+        {
+            return Ok(host_path);
+        }
+        let target = host_path.canonicalize()?;
+        if !target.starts_with(&root) {
+            return Err(Errno::Perm);
+        }
+        Ok(target)
+    }
+
+    fn stat(&self, path: &[String]) -> Result<Filestat, Errno> {
+        match self {
+            Self::Host(root) => Ok(Filestat::from_metadata(&fs::metadata(Self::host_path(
+                root, path, true,
+            )?)?)),
+            Self::Virtual(root) => Ok(root.lookup(path)?.stat()),
+        }
+    }
+
+    /// Opens the file or directory at the given path.
+    ///
+    /// Returns `None` if it is a directory.
+    fn open(&self, path: &[String], oflags: u32, writable: bool) -> Result<Option<File>, Errno> {
+        let create = oflags & OFLAGS_CREAT != 0;
+        let exclusive = oflags & OFLAGS_EXCL != 0;
+        let truncate = oflags & OFLAGS_TRUNC != 0;
+        let directory = oflags & OFLAGS_DIRECTORY != 0;
+        match self {
+            Self::Host(root) => {
+                let host_path = Self::host_path(root, path, true)?;
+                match fs::metadata(&host_path) {
+                    Ok(metadata) if metadata.is_dir() => {
+                        if create && exclusive {
+                            return Err(Errno::Exist);
+                        }
+                        if truncate {
+                            return Err(Errno::Isdir);
+                        }
+                        return Ok(None);
+                    }
+                    Ok(_) if directory => return Err(Errno::Notdir),
+                    Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                        return Err(error.into())
+                    }
+                    _ => {}
+                }
+                let file = fs::OpenOptions::new()
+                    .read(true)
+                    .write(writable || create || truncate)
+                    .create(create)
+                    .create_new(create && exclusive)
+                    .truncate(truncate)
+                    .open(host_path)?;
+                Ok(Some(File::Host(file)))
+            }
+            Self::Virtual(root) => {
+                let Some((name, parent_path)) = path.split_last() else {
+                    if truncate {
+                        return Err(Errno::Isdir);
+                    }
+                    return Ok(None);
+                };
+                let parent = root.lookup_dir(parent_path)?;
+                let mut entries = parent.entries.lock().unwrap();
+                match entries.get(name) {
+                    Some(_) if create && exclusive => Err(Errno::Exist),
+                    Some(VirtualNode::Dir(_)) if truncate => Err(Errno::Isdir),
+                    Some(VirtualNode::Dir(_)) => Ok(None),
+                    Some(VirtualNode::File(_)) if directory => Err(Errno::Notdir),
+                    Some(VirtualNode::File(file)) => {
+                        if truncate {
+                            file.data.lock().unwrap().clear();
+                        }
+                        Ok(Some(File::Virtual(file.clone())))
+                    }
+                    None if create && !directory => {
+                        let file = VirtualFile {
+                            data: Arc::new(Mutex::new(Vec::new())),
+                        };
+                        entries.insert(name.clone(), VirtualNode::File(file.clone()));
+                        Ok(Some(File::Virtual(file)))
+                    }
+                    None => Err(Errno::Noent),
+                }
+            }
+        }
+    }
+
+    fn create_dir(&self, path: &[String]) -> Result<(), Errno> {
+        match self {
+            Self::Host(root) => Ok(fs::create_dir(Self::host_path(root, path, false)?)?),
+            Self::Virtual(root) => {
+                let (name, parent_path) = path.split_last().ok_or(Errno::Exist)?;
+                let parent = root.lookup_dir(parent_path)?;
+                let mut entries = parent.entries.lock().unwrap();
+                if entries.contains_key(name) {
+                    return Err(Errno::Exist);
+                }
+                entries.insert(name.clone(), VirtualNode::Dir(VirtualDir::new()));
+                Ok(())
+            }
+        }
+    }
+
+    fn remove_dir(&self, path: &[String]) -> Result<(), Errno> {
+        if path.is_empty() {
+            return Err(Errno::Acces);
+        }
+        match self {
+            Self::Host(root) => Ok(fs::remove_dir(Self::host_path(root, path, false)?)?),
+            Self::Virtual(root) => {
+                let (name, parent_path) = path.split_last().unwrap();
+                let parent = root.lookup_dir(parent_path)?;
+                let mut entries = parent.entries.lock().unwrap();
+                match entries.get(name) {
+                    Some(VirtualNode::Dir(dir)) if !dir.entries.lock().unwrap().is_empty() => {
+                        return Err(Errno::Notempty)
+                    }
+                    Some(VirtualNode::Dir(_)) => {}
+                    Some(VirtualNode::File(_)) => return Err(Errno::Notdir),
+                    None => return Err(Errno::Noent),
+                }
+                entries.remove(name);
+                Ok(())
+            }
+        }
+    }
+
+    fn unlink_file(&self, path: &[String]) -> Result<(), Errno> {
+        match self {
+            Self::Host(root) => Ok(fs::remove_file(Self::host_path(root, path, false)?)?),
+            Self::Virtual(root) => {
+                let (name, parent_path) = path.split_last().ok_or(Errno::Isdir)?;
+                let parent = root.lookup_dir(parent_path)?;
+                let mut entries = parent.entries.lock().unwrap();
+                match entries.get(name) {
+                    Some(VirtualNode::File(_)) => {}
+                    Some(VirtualNode::Dir(_)) => return Err(Errno::Isdir),
+                    None => return Err(Errno::Noent),
+                }
+                entries.remove(name);
+                Ok(())
+            }
+        }
+    }
+
+    fn rename(&self, old_path: &[String], new_fs: &Fs, new_path: &[String]) -> Result<(), Errno> {
+        match (self, new_fs) {
+            (Self::Host(old_root), Self::Host(new_root)) => Ok(fs::rename(
+                Self::host_path(old_root, old_path, false)?,
+                Self::host_path(new_root, new_path, false)?,
+            )?),
+            (Self::Virtual(old_root), Self::Virtual(new_root)) if old_root.is_same(new_root) => {
+                let (old_name, old_parent_path) = old_path.split_last().ok_or(Errno::Acces)?;
+                let (new_name, new_parent_path) = new_path.split_last().ok_or(Errno::Acces)?;
+                if new_path.starts_with(old_path) && new_path != old_path {
+                    return Err(Errno::Inval);
+                }
+                let old_parent = old_root.lookup_dir(old_parent_path)?;
+                let new_parent = new_root.lookup_dir(new_parent_path)?;
+                let node = old_parent
+                    .entries
+                    .lock()
+                    .unwrap()
+                    .get(old_name)
+                    .cloned()
+                    .ok_or(Errno::Noent)?;
+                match (&node, new_parent.entries.lock().unwrap().get(new_name)) {
+                    (VirtualNode::File(_), Some(VirtualNode::Dir(_))) => return Err(Errno::Isdir),
+                    (VirtualNode::Dir(_), Some(VirtualNode::File(_))) => return Err(Errno::Notdir),
+                    (VirtualNode::Dir(_), Some(VirtualNode::Dir(dir)))
+                        if !dir.entries.lock().unwrap().is_empty() =>
+                    {
+                        return Err(Errno::Notempty)
+                    }
+                    _ => {}
+                }
+                old_parent.entries.lock().unwrap().remove(old_name);
+                new_parent
+                    .entries
+                    .lock()
+                    .unwrap()
+                    .insert(new_name.clone(), node);
+                Ok(())
+            }
+            _ => Err(Errno::Xdev),
+        }
+    }
+
+    /// Returns the names and types of the entries in the directory at the given path, including
+    /// `.` and `..`.
+    fn read_dir(&self, path: &[String]) -> Result<Vec<(String, Filetype)>, Errno> {
+        let mut entries = vec![
+            (".".to_string(), Filetype::Directory),
+            ("..".to_string(), Filetype::Directory),
+        ];
+        match self {
+            Self::Host(root) => {
+                let mut host_entries = Vec::new();
+                for entry in fs::read_dir(Self::host_path(root, path, true)?)? {
+                    let entry = entry?;
+                    let filetype = Filetype::from_file_type(entry.file_type()?);
+                    host_entries.push((entry.file_name().to_string_lossy().into_owned(), filetype));
+                }
+                host_entries.sort();
+                entries.extend(host_entries);
+            }
+            Self::Virtual(root) => {
+                let dir = root.lookup_dir(path)?;
+                let dir_entries = dir.entries.lock().unwrap();
+                entries.extend(
+                    dir_entries
+                        .iter()
+                        .map(|(name, node)| (name.clone(), node.stat().filetype)),
+                );
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+#[repr(u8)]
+enum Filetype {
+    #[default]
+    Unknown = 0,
+    CharacterDevice = 2,
+    Directory = 3,
+    RegularFile = 4,
+    SymbolicLink = 7,
+}
+
+impl Filetype {
+    fn from_file_type(file_type: fs::FileType) -> Self {
+        if file_type.is_dir() {
+            Self::Directory
+        } else if file_type.is_file() {
+            Self::RegularFile
+        } else if file_type.is_symlink() {
+            Self::SymbolicLink
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Filestat {
+    filetype: Filetype,
+    size: u64,
+    atim: u64,
+    mtim: u64,
+    ctim: u64,
+}
+
+impl Filestat {
+    fn from_metadata(metadata: &fs::Metadata) -> Self {
+        fn nanos(time: io::Result<SystemTime>) -> u64 {
+            time.ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        }
+
+        Self {
+            filetype: Filetype::from_file_type(metadata.file_type()),
+            size: metadata.len(),
+            atim: nanos(metadata.accessed()),
+            mtim: nanos(metadata.modified()),
+            ctim: nanos(metadata.created()),
+        }
+    }
+}
+
+/// A WASI error number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u16)]
+enum Errno {
+    Acces = 2,
+    Badf = 8,
+    Exist = 20,
+    Fault = 21,
+    Ilseq = 25,
+    Inval = 28,
+    Io = 29,
+    Isdir = 31,
+    Nametoolong = 37,
+    Noent = 44,
+    Notdir = 54,
+    Notempty = 55,
+    Notsup = 58,
+    Perm = 63,
+    Spipe = 70,
+    Xdev = 75,
+}
+
+impl From<io::Error> for Errno {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::Noent,
+            io::ErrorKind::PermissionDenied => Self::Acces,
+            io::ErrorKind::AlreadyExists => Self::Exist,
+            io::ErrorKind::InvalidInput => Self::Inval,
+            io::ErrorKind::NotADirectory => Self::Notdir,
+            io::ErrorKind::IsADirectory => Self::Isdir,
+            io::ErrorKind::DirectoryNotEmpty => Self::Notempty,
+            io::ErrorKind::CrossesDevices => Self::Xdev,
+            io::ErrorKind::Unsupported => Self::Notsup,
+            _ => Self::Io,
+        }
+    }
+}
+
+/// Resolves the given path relative to the given base path.
+///
+/// The resolved path is relative to the root of the preopened directory, and is not allowed to
+/// leave it.
+fn resolve_path(base: &[String], path: &str) -> Result<Vec<String>, Errno> {
+    if path.starts_with('/') {
+        return Err(Errno::Perm);
+    }
+    let mut resolved = base.to_vec();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                resolved.pop().ok_or(Errno::Perm)?;
+            }
+            _ => resolved.push(component.to_string()),
+        }
+    }
+    Ok(resolved)
+}
+
+// Helper functions for accessing memory
+
+/// Adds the given offset to the given pointer, failing if the result is out of bounds.
+fn offset(ptr: u32, offset: usize) -> Result<u32, Errno> {
+    u32::try_from(offset)
+        .ok()
+        .and_then(|offset| ptr.checked_add(offset))
+        .ok_or(Errno::Fault)
+}
+
+fn bytes(mem: &[u8], ptr: u32, len: u32) -> Result<&[u8], Errno> {
+    mem.get(ptr as usize..ptr as usize + len as usize)
+        .ok_or(Errno::Fault)
+}
+
+fn bytes_mut(mem: &mut [u8], ptr: u32, len: u32) -> Result<&mut [u8], Errno> {
+    mem.get_mut(ptr as usize..ptr as usize + len as usize)
+        .ok_or(Errno::Fault)
+}
+
+fn read_u32(mem: &[u8], ptr: u32) -> Result<u32, Errno> {
+    Ok(u32::from_le_bytes(bytes(mem, ptr, 4)?.try_into().unwrap()))
+}
+
+fn write_u32(mem: &mut [u8], ptr: u32, val: u32) -> Result<(), Errno> {
+    bytes_mut(mem, ptr, 4)?.copy_from_slice(&val.to_le_bytes());
+    Ok(())
+}
+
+fn write_u64(mem: &mut [u8], ptr: u32, val: u64) -> Result<(), Errno> {
+    bytes_mut(mem, ptr, 8)?.copy_from_slice(&val.to_le_bytes());
+    Ok(())
+}
+
+fn write_filestat(mem: &mut [u8], ptr: u32, stat: &Filestat) -> Result<(), Errno> {
+    let filestat = bytes_mut(mem, ptr, 64)?;
+    filestat.fill(0);
+    filestat[16] = stat.filetype as u8;
+    filestat[24..32].copy_from_slice(&1u64.to_le_bytes());
+    filestat[32..40].copy_from_slice(&stat.size.to_le_bytes());
+    filestat[40..48].copy_from_slice(&stat.atim.to_le_bytes());
+    filestat[48..56].copy_from_slice(&stat.mtim.to_le_bytes());
+    filestat[56..64].copy_from_slice(&stat.ctim.to_le_bytes());
+    Ok(())
+}
+
+/// Writes the given strings as an array of pointers to nul-terminated strings.
+fn write_strings(mem: &mut [u8], strings: &[String], ptrs: u32, buf: u32) -> Result<(), Errno> {
+    let mut string_ptr = buf;
+    for (index, string) in strings.iter().enumerate() {
+        write_u32(mem, offset(ptrs, index * 4)?, string_ptr)?;
+        let bytes = bytes_mut(mem, string_ptr, string.len() as u32 + 1)?;
+        bytes[..string.len()].copy_from_slice(string.as_bytes());
+        bytes[string.len()] = 0;
+        string_ptr = offset(string_ptr, string.len() + 1)?;
+    }
+    Ok(())
+}
+
+fn write_string_sizes(
+    mem: &mut [u8],
+    strings: &[String],
+    count_ptr: u32,
+    buf_size_ptr: u32,
+) -> Result<(), Errno> {
+    let buf_size = strings.iter().map(|string| string.len() + 1).sum::<usize>();
+    write_u32(mem, count_ptr, strings.len() as u32)?;
+    write_u32(mem, buf_size_ptr, buf_size as u32)
+}
+
+fn read_iovs(mem: &[u8], iovs: u32, iovs_len: u32) -> Result<Vec<(u32, u32)>, Errno> {
+    (0..iovs_len)
+        .map(|index| {
+            let iov = offset(iovs, index as usize * 8)?;
+            let ptr = read_u32(mem, iov)?;
+            let len = read_u32(mem, offset(iov, 4)?)?;
+            bytes(mem, ptr, len)?;
+            Ok((ptr, len))
+        })
+        .collect()
+}
+
+fn iovs_total_len(iovs: &[(u32, u32)]) -> usize {
+    iovs.iter().map(|&(_, len)| len as usize).sum()
+}
+
+/// Gathers the bytes in the given I/O vectors into a single buffer.
+fn gather(mem: &[u8], iovs: u32, iovs_len: u32) -> Result<Vec<u8>, Errno> {
+    let mut buf = Vec::new();
+    for (ptr, len) in read_iovs(mem, iovs, iovs_len)? {
+        buf.extend_from_slice(bytes(mem, ptr, len)?);
+    }
+    Ok(buf)
+}
+
+/// Scatters the bytes in the given buffer over the given I/O vectors.
+fn scatter(mem: &mut [u8], iovs: &[(u32, u32)], mut buf: &[u8]) -> Result<(), Errno> {
+    for &(ptr, len) in iovs {
+        let count = buf.len().min(len as usize);
+        bytes_mut(mem, ptr, count as u32)?.copy_from_slice(&buf[..count]);
+        buf = &buf[count..];
+    }
+    Ok(())
+}
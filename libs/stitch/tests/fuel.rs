@@ -0,0 +1,258 @@
+use {
+    makepad_stitch::{Engine, Error, FuncError, Instance, Linker, Module, Store, Trap, Val},
+    std::{thread, time::Duration},
+    wast::{parser, parser::ParseBuffer, Wat},
+};
+
+const WAT: &str = r#"
+    (module
+        (memory 1)
+
+        (func (export "add") (param i32 i32) (result i32)
+            local.get 0
+            local.get 1
+            i32.add
+        )
+
+        (func (export "loop_forever")
+            (loop $l
+                br $l
+            )
+        )
+
+        (func (export "count") (param $n i32) (result i32)
+            (local $i i32)
+            (loop $l
+                (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                (br_if $l (i32.lt_u (local.get $i) (local.get $n)))
+            )
+            local.get $i
+        )
+
+        (func $square (param i32) (result i32)
+            local.get 0
+            local.get 0
+            i32.mul
+        )
+
+        (func (export "sum_of_squares") (param $n i32) (result i64)
+            (local $i i32)
+            (local $sum i64)
+            (block $done
+                (loop $l
+                    (br_if $done (i32.ge_u (local.get $i) (local.get $n)))
+                    (i32.store (i32.const 0) (call $square (local.get $i)))
+                    (local.set $sum (i64.add (local.get $sum) (i64.extend_i32_u (i32.load (i32.const 0)))))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $l)
+                )
+            )
+            local.get $sum
+        )
+    )
+"#;
+
+fn instantiate(store: &mut Store) -> Instance {
+    let buf = ParseBuffer::new(WAT).unwrap();
+    let mut wat = parser::parse::<Wat>(&buf).unwrap();
+    let bytes = wat.encode().unwrap();
+    let module = Module::new(store.engine(), &bytes).unwrap();
+    Linker::new().instantiate(store, &module).unwrap()
+}
+
+fn assert_trap(result: Result<(), Error>, expected: Trap) {
+    match result {
+        Err(Error::Trap(trap)) => assert_eq!(trap, expected),
+        result => panic!("expected trap {:?}, got {:?}", expected, result),
+    }
+}
+
+// Resumes the suspended call, adding the given amount of fuel each time it runs out.
+fn resume_to_completion(store: &mut Store, fuel: u64, results: &mut [Val]) -> usize {
+    let mut resumes = 0;
+    loop {
+        store.add_fuel(fuel);
+        match store.resume(results) {
+            Ok(()) => return resumes,
+            Err(Error::Trap(Trap::OutOfFuel)) => resumes += 1,
+            Err(error) => panic!("unexpected error {:?}", error),
+        }
+    }
+}
+
+#[test]
+fn fuel_is_unlimited_by_default() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let count = instance.exported_func("count").unwrap();
+    let mut results = [Val::I32(0)];
+    count
+        .call(&mut store, &[Val::I32(100_000)], &mut results)
+        .unwrap();
+    assert_eq!(results[0].to_i32(), Some(100_000));
+    assert_eq!(store.fuel(), None);
+}
+
+#[test]
+fn every_instruction_consumes_fuel() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let add = instance.exported_func("add").unwrap();
+    store.set_fuel(Some(100));
+    let mut results = [Val::I32(0)];
+    add.call(&mut store, &[Val::I32(1), Val::I32(2)], &mut results)
+        .unwrap();
+    assert_eq!(results[0].to_i32(), Some(3));
+    // local.get, local.get, i32.add, end
+    assert_eq!(store.fuel(), Some(96));
+}
+
+#[test]
+fn infinite_loop_runs_out_of_fuel() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let loop_forever = instance.exported_func("loop_forever").unwrap();
+    store.set_fuel(Some(10_000));
+    assert_trap(loop_forever.call(&mut store, &[], &mut []), Trap::OutOfFuel);
+    assert!(store.fuel().unwrap() < 10);
+    assert!(store.has_suspended_call());
+
+    // Adding fuel lets the loop continue until it runs out again.
+    store.add_fuel(10_000);
+    assert_trap(store.resume(&mut []), Trap::OutOfFuel);
+    assert!(store.fuel().unwrap() < 10);
+}
+
+#[test]
+fn resumed_call_completes() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let count = instance.exported_func("count").unwrap();
+    store.set_fuel(Some(100));
+    let mut results = [Val::I32(0)];
+    assert_trap(
+        count.call(&mut store, &[Val::I32(10_000)], &mut results),
+        Trap::OutOfFuel,
+    );
+    let resumes = resume_to_completion(&mut store, 100, &mut results);
+    assert!(resumes > 10);
+    assert_eq!(results[0].to_i32(), Some(10_000));
+    assert!(!store.has_suspended_call());
+}
+
+#[test]
+fn resumed_call_with_calls_and_memory_completes() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let sum_of_squares = instance.exported_func("sum_of_squares").unwrap();
+    let mut expected = [Val::I64(0)];
+    sum_of_squares
+        .call(&mut store, &[Val::I32(1000)], &mut expected)
+        .unwrap();
+
+    store.set_fuel(Some(0));
+    let mut results = [Val::I64(0)];
+    assert_trap(
+        sum_of_squares.call(&mut store, &[Val::I32(1000)], &mut results),
+        Trap::OutOfFuel,
+    );
+    resume_to_completion(&mut store, 7, &mut results);
+    assert_eq!(results[0].to_i64(), expected[0].to_i64());
+    assert_eq!(results[0].to_i64(), Some((0..1000i64).map(|i| i * i).sum()));
+}
+
+#[test]
+fn new_call_discards_suspended_call() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let loop_forever = instance.exported_func("loop_forever").unwrap();
+    let add = instance.exported_func("add").unwrap();
+    store.set_fuel(Some(1000));
+    assert_trap(loop_forever.call(&mut store, &[], &mut []), Trap::OutOfFuel);
+
+    store.set_fuel(None);
+    let mut results = [Val::I32(0)];
+    add.call(&mut store, &[Val::I32(2), Val::I32(3)], &mut results)
+        .unwrap();
+    assert_eq!(results[0].to_i32(), Some(5));
+    assert!(!store.has_suspended_call());
+    assert!(matches!(
+        store.resume(&mut []),
+        Err(Error::Func(FuncError::NoSuspendedCall))
+    ));
+}
+
+#[test]
+fn suspended_call_does_not_block_other_stores() {
+    let mut store_0 = Store::new(Engine::with_fuel_metering());
+    let instance_0 = instantiate(&mut store_0);
+    store_0.set_fuel(Some(1000));
+    let loop_forever = instance_0.exported_func("loop_forever").unwrap();
+    assert_trap(
+        loop_forever.call(&mut store_0, &[], &mut []),
+        Trap::OutOfFuel,
+    );
+
+    let mut store_1 = Store::new(Engine::with_fuel_metering());
+    let instance_1 = instantiate(&mut store_1);
+    let count = instance_1.exported_func("count").unwrap();
+    let mut results = [Val::I32(0)];
+    count
+        .call(&mut store_1, &[Val::I32(1000)], &mut results)
+        .unwrap();
+    assert_eq!(results[0].to_i32(), Some(1000));
+    assert!(store_0.has_suspended_call());
+}
+
+#[test]
+fn interrupt_from_another_thread() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let loop_forever = instance.exported_func("loop_forever").unwrap();
+    let handle = store.interrupt_handle();
+    let thread = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        handle.interrupt();
+    });
+    assert_trap(
+        loop_forever.call(&mut store, &[], &mut []),
+        Trap::Interrupted,
+    );
+    thread.join().unwrap();
+
+    // The interrupt is cleared when it is observed, so the call can be resumed.
+    store.set_fuel(Some(1000));
+    assert_trap(store.resume(&mut []), Trap::OutOfFuel);
+}
+
+#[test]
+fn interrupt_before_call() {
+    let mut store = Store::new(Engine::with_fuel_metering());
+    let instance = instantiate(&mut store);
+    let add = instance.exported_func("add").unwrap();
+    store.interrupt_handle().interrupt();
+    let mut results = [Val::I32(0)];
+    assert_trap(
+        add.call(&mut store, &[Val::I32(1), Val::I32(2)], &mut results),
+        Trap::Interrupted,
+    );
+    store.resume(&mut results).unwrap();
+    assert_eq!(results[0].to_i32(), Some(3));
+}
+
+#[test]
+fn engine_without_fuel_metering_ignores_fuel() {
+    let engine = Engine::new();
+    assert!(!engine.meters_fuel());
+    let mut store = Store::new(engine);
+    let instance = instantiate(&mut store);
+    let count = instance.exported_func("count").unwrap();
+    store.set_fuel(Some(10));
+    store.interrupt_handle().interrupt();
+    let mut results = [Val::I32(0)];
+    count
+        .call(&mut store, &[Val::I32(1000)], &mut results)
+        .unwrap();
+    assert_eq!(results[0].to_i32(), Some(1000));
+    assert_eq!(store.fuel(), Some(10));
+}
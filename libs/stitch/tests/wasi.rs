@@ -0,0 +1,491 @@
+use {
+    makepad_stitch::{
+        wasi::{VirtualDir, Wasi},
+        Engine, Instance, Linker, Module, Store,
+    },
+    std::{
+        env, fs, process,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+    wast::{parser, parser::ParseBuffer, Wat},
+};
+
+// The imports and helper functions shared by all test programs. `$check` exits with 100 plus the
+// errno if a call failed, so that failures show up in the exit code.
+const PRELUDE: &str = r#"
+    (import "wasi_snapshot_preview1" "args_get" (func $args_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "args_sizes_get" (func $args_sizes_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "environ_get" (func $environ_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "environ_sizes_get" (func $environ_sizes_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_close" (func $fd_close (param i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_prestat_get" (func $fd_prestat_get (param i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_prestat_dir_name" (func $fd_prestat_dir_name (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_readdir" (func $fd_readdir (param i32 i32 i32 i64 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_seek" (func $fd_seek (param i32 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_create_directory" (func $path_create_directory (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_open" (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_unlink_file" (func $path_unlink_file (param i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+    (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+
+    (memory (export "memory") 1)
+
+    (func $check (param $errno i32)
+        (if (local.get $errno)
+            (then (call $proc_exit (i32.add (i32.const 100) (local.get $errno))))
+        )
+    )
+
+    (func $write (param $fd i32) (param $ptr i32) (param $len i32)
+        (i32.store (i32.const 0) (local.get $ptr))
+        (i32.store (i32.const 4) (local.get $len))
+        (call $check (call $fd_write (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+    )
+
+    (func $read (param $fd i32) (param $ptr i32) (param $len i32) (result i32)
+        (i32.store (i32.const 0) (local.get $ptr))
+        (i32.store (i32.const 4) (local.get $len))
+        (call $check (call $fd_read (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (i32.load (i32.const 8))
+    )
+
+    (func $open (param $path i32) (param $path_len i32) (param $oflags i32) (param $rights i64) (result i32)
+        (call $check
+            (call $path_open
+                (i32.const 3)
+                (i32.const 0)
+                (local.get $path)
+                (local.get $path_len)
+                (local.get $oflags)
+                (local.get $rights)
+                (i64.const 0)
+                (i32.const 0)
+                (i32.const 12)
+            )
+        )
+        (i32.load (i32.const 12))
+    )
+"#;
+
+// Copies `input.txt` to `output/copy.txt` in the preopened directory, and removes `old.txt`.
+const COPY: &str = r#"
+    (data (i32.const 100) "sub/../input.txt")
+    (data (i32.const 120) "output")
+    (data (i32.const 130) "output/copy.txt")
+    (data (i32.const 150) "old.txt")
+
+    (func (export "_start")
+        (local $input i32)
+        (local $output i32)
+        (local $len i32)
+        (local.set $input (call $open (i32.const 100) (i32.const 16) (i32.const 0) (i64.const 2)))
+        (local.set $len (call $read (local.get $input) (i32.const 4096) (i32.const 1024)))
+        (call $check (call $fd_close (local.get $input)))
+        (call $check (call $path_create_directory (i32.const 3) (i32.const 120) (i32.const 6)))
+        ;; O_CREAT | O_TRUNC with the right to write.
+        (local.set $output (call $open (i32.const 130) (i32.const 15) (i32.const 9) (i64.const 64)))
+        (call $write (local.get $output) (i32.const 4096) (local.get $len))
+        (call $check (call $fd_close (local.get $output)))
+        (call $check (call $path_unlink_file (i32.const 3) (i32.const 150) (i32.const 7)))
+    )
+"#;
+
+fn instantiate(wasi: &Wasi, program: &str) -> (Store, Instance) {
+    let wat = format!("(module {} {})", PRELUDE, program);
+    let buf = ParseBuffer::new(&wat).unwrap();
+    let mut wat = parser::parse::<Wat>(&buf).unwrap();
+    let bytes = wat.encode().unwrap();
+    let mut store = Store::new(Engine::new());
+    let module = Module::new(store.engine(), &bytes).unwrap();
+    let mut linker = Linker::new();
+    wasi.add_to_linker(&mut store, &mut linker);
+    let instance = linker.instantiate(&mut store, &module).unwrap();
+    (store, instance)
+}
+
+fn run(wasi: &Wasi, program: &str) -> i32 {
+    let (mut store, instance) = instantiate(wasi, program);
+    wasi.run(&mut store, &instance).unwrap()
+}
+
+// Runs a program that makes a single call on the given path, and exits with the errno it returns.
+fn run_path_call(wasi: &Wasi, path: &str, call: &str) -> i32 {
+    run(
+        wasi,
+        &format!(
+            r#"
+                (data (i32.const 100) "{}")
+
+                (func (export "_start")
+                    (call $proc_exit {})
+                )
+            "#,
+            path,
+            call.replace("$len", &path.len().to_string()),
+        ),
+    )
+}
+
+fn open_path(wasi: &Wasi, path: &str, oflags: u32) -> i32 {
+    run_path_call(
+        wasi,
+        path,
+        &format!(
+            "(call $path_open (i32.const 3) (i32.const 0) (i32.const 100) (i32.const $len) \
+                (i32.const {}) (i64.const 66) (i64.const 0) (i32.const 0) (i32.const 12))",
+            oflags
+        ),
+    )
+}
+
+fn unlink_path(wasi: &Wasi, path: &str) -> i32 {
+    run_path_call(
+        wasi,
+        path,
+        "(call $path_unlink_file (i32.const 3) (i32.const 100) (i32.const $len))",
+    )
+}
+
+#[test]
+fn stdout_and_stderr_are_captured() {
+    let wasi = Wasi::builder().build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (data (i32.const 100) "Hello, world!\n")
+            (data (i32.const 120) "oops\n")
+
+            (func (export "_start")
+                (call $write (i32.const 1) (i32.const 100) (i32.const 14))
+                (call $write (i32.const 2) (i32.const 120) (i32.const 5))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 0);
+    assert_eq!(wasi.stdout(), b"Hello, world!\n");
+    assert_eq!(wasi.stderr(), b"oops\n");
+}
+
+#[test]
+fn stdin_is_read() {
+    let wasi = Wasi::builder().stdin("ping").build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (local $len i32)
+                (local.set $len (call $read (i32.const 0) (i32.const 100) (i32.const 64)))
+                (call $write (i32.const 1) (i32.const 100) (local.get $len))
+                ;; The second read hits the end of the input.
+                (call $proc_exit (call $read (i32.const 0) (i32.const 100) (i32.const 64)))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 0);
+    assert_eq!(wasi.stdout(), b"ping");
+}
+
+#[test]
+fn proc_exit_returns_exit_code() {
+    let wasi = Wasi::builder().build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (data (i32.const 100) "bye")
+
+            (func (export "_start")
+                (call $write (i32.const 1) (i32.const 100) (i32.const 3))
+                (call $proc_exit (i32.const 42))
+                unreachable
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 42);
+    assert_eq!(wasi.stdout(), b"bye");
+}
+
+#[test]
+fn args_are_passed() {
+    let wasi = Wasi::builder().arg("prog").args(["a", "bc"]).build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (call $check (call $args_sizes_get (i32.const 16) (i32.const 20)))
+                (call $check (call $args_get (i32.const 1024) (i32.const 2048)))
+                (call $write (i32.const 1) (i32.const 2048) (i32.load (i32.const 20)))
+                ;; The second pointer points to the second argument.
+                (call $write (i32.const 1) (i32.load (i32.const 1028)) (i32.const 1))
+                (call $proc_exit (i32.load (i32.const 16)))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 3);
+    assert_eq!(wasi.stdout(), b"prog\0a\0bc\0a");
+}
+
+#[test]
+fn environ_is_passed() {
+    let wasi = Wasi::builder().env("KEY", "VALUE").env("EMPTY", "").build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (call $check (call $environ_sizes_get (i32.const 16) (i32.const 20)))
+                (call $check (call $environ_get (i32.const 1024) (i32.const 2048)))
+                (call $write (i32.const 1) (i32.const 2048) (i32.load (i32.const 20)))
+                (call $proc_exit (i32.load (i32.const 16)))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 2);
+    assert_eq!(wasi.stdout(), b"KEY=VALUE\0EMPTY=\0");
+}
+
+#[test]
+fn clock_and_random() {
+    let wasi = Wasi::builder().build();
+    let (mut store, instance) = instantiate(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (call $check (call $clock_time_get (i32.const 0) (i64.const 0) (i32.const 16)))
+                (call $check (call $clock_time_get (i32.const 1) (i64.const 0) (i32.const 24)))
+                (call $check (call $random_get (i32.const 32) (i32.const 32)))
+                ;; Unknown clocks are rejected with EINVAL.
+                (call $proc_exit (call $clock_time_get (i32.const 99) (i64.const 0) (i32.const 16)))
+            )
+        "#,
+    );
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert_eq!(wasi.run(&mut store, &instance).unwrap(), 28);
+    let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    let bytes = instance.exported_mem("memory").unwrap().bytes(&store);
+    let realtime = u64::from_le_bytes(bytes[16..24].try_into().unwrap());
+    assert!(before.as_nanos() as u64 <= realtime && realtime <= after.as_nanos() as u64);
+    assert!(bytes[32..64].iter().any(|&byte| byte != 0));
+}
+
+#[test]
+fn preopens_are_listed() {
+    let wasi = Wasi::builder()
+        .preopen_virtual_dir("/sandbox", VirtualDir::new())
+        .build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (call $check (call $fd_prestat_get (i32.const 3) (i32.const 16)))
+                (call $check (call $fd_prestat_dir_name (i32.const 3) (i32.const 100) (i32.load (i32.const 20))))
+                (call $write (i32.const 1) (i32.const 100) (i32.load (i32.const 20)))
+                ;; There is only one preopen, so the next file descriptor is not a directory.
+                (call $proc_exit (call $fd_prestat_get (i32.const 4) (i32.const 16)))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 8);
+    assert_eq!(wasi.stdout(), b"/sandbox");
+}
+
+#[test]
+fn virtual_dir_is_read_and_written() {
+    let dir = VirtualDir::new()
+        .with_file("input.txt", "some input")
+        .with_file("old.txt", "")
+        .with_dir("sub", VirtualDir::new());
+    let wasi = Wasi::builder()
+        .preopen_virtual_dir(".", dir.clone())
+        .build();
+    assert_eq!(run(&wasi, COPY), 0);
+    assert_eq!(dir.read_file("output/copy.txt").unwrap(), b"some input");
+    assert_eq!(dir.read_file("input.txt").unwrap(), b"some input");
+    assert_eq!(dir.read_file("old.txt"), None);
+}
+
+#[test]
+fn host_dir_is_read_and_written() {
+    let root = env::temp_dir().join(format!("makepad-stitch-wasi-{}", process::id()));
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("input.txt"), "some input").unwrap();
+    fs::write(root.join("old.txt"), "").unwrap();
+    let wasi = Wasi::builder().preopen_dir(".", &root).build();
+    let exit_code = run(&wasi, COPY);
+    let copy = fs::read(root.join("output/copy.txt"));
+    let old_exists = root.join("old.txt").exists();
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(exit_code, 0);
+    assert_eq!(copy.unwrap(), b"some input");
+    assert!(!old_exists);
+}
+
+#[test]
+fn paths_cannot_escape_sandbox() {
+    for path in ["../secret.txt", "sub/../../secret.txt", "/secret.txt"] {
+        let wasi = Wasi::builder()
+            .preopen_virtual_dir(".", VirtualDir::new().with_dir("sub", VirtualDir::new()))
+            .build();
+        // EPERM
+        assert_eq!(open_path(&wasi, path, 0), 63, "{}", path);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_cannot_escape_sandbox() {
+    use std::os::unix::fs::symlink;
+
+    let root = env::temp_dir().join(format!("makepad-stitch-wasi-escape-{}", process::id()));
+    let sandbox = root.join("sandbox");
+    fs::create_dir_all(root.join("outside")).unwrap();
+    fs::create_dir_all(&sandbox).unwrap();
+    fs::write(root.join("outside/secret.txt"), "secret").unwrap();
+    symlink("../outside", sandbox.join("dir")).unwrap();
+    symlink("../outside/secret.txt", sandbox.join("file")).unwrap();
+    symlink(root.join("outside/new.txt"), sandbox.join("dangling")).unwrap();
+    let wasi = Wasi::builder().preopen_dir(".", &sandbox).build();
+    let exit_codes = [
+        open_path(&wasi, "dir/secret.txt", 0),
+        open_path(&wasi, "file", 0),
+        open_path(&wasi, "dir", 0),
+        // O_CREAT
+        open_path(&wasi, "dir/new.txt", 1),
+        open_path(&wasi, "dangling", 1),
+        unlink_path(&wasi, "dir/secret.txt"),
+    ];
+    let secret_exists = root.join("outside/secret.txt").exists();
+    let new_exists = root.join("outside/new.txt").exists();
+    fs::remove_dir_all(&root).unwrap();
+    // EPERM, except for the dangling link, which can not be followed at all (ENOENT).
+    assert_eq!(exit_codes, [63, 63, 63, 63, 44, 63]);
+    assert!(secret_exists);
+    assert!(!new_exists);
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks_inside_sandbox_are_followed() {
+    use std::os::unix::fs::symlink;
+
+    let root = env::temp_dir().join(format!("makepad-stitch-wasi-inside-{}", process::id()));
+    fs::create_dir_all(root.join("data")).unwrap();
+    fs::write(root.join("data/file.txt"), "data").unwrap();
+    symlink("data", root.join("dir")).unwrap();
+    symlink("data/file.txt", root.join("file")).unwrap();
+    let name = root.file_name().unwrap().to_str().unwrap();
+    symlink(format!("../{}/data", name), root.join("roundabout")).unwrap();
+    let wasi = Wasi::builder().preopen_dir(".", &root).build();
+    let exit_codes = [
+        open_path(&wasi, "dir/file.txt", 0),
+        open_path(&wasi, "file", 0),
+        open_path(&wasi, "roundabout/file.txt", 0),
+        open_path(&wasi, "dir/new.txt", 1),
+        // Removes the link, not the file it points to.
+        unlink_path(&wasi, "file"),
+    ];
+    let new_exists = root.join("data/new.txt").exists();
+    let file_exists = root.join("data/file.txt").exists();
+    let link_exists = fs::symlink_metadata(root.join("file")).is_ok();
+    fs::remove_dir_all(&root).unwrap();
+    assert_eq!(exit_codes, [0, 0, 0, 0, 0]);
+    assert!(new_exists);
+    assert!(file_exists);
+    assert!(!link_exists);
+}
+
+#[test]
+fn readdir_lists_entries() {
+    let dir = VirtualDir::new()
+        .with_file("b.txt", "b")
+        .with_dir("a", VirtualDir::new());
+    let wasi = Wasi::builder().preopen_virtual_dir(".", dir).build();
+    let (mut store, instance) = instantiate(
+        &wasi,
+        r#"
+            (func (export "_start")
+                (call $check (call $fd_readdir (i32.const 3) (i32.const 1024) (i32.const 1024) (i64.const 0) (i32.const 16)))
+            )
+        "#,
+    );
+    assert_eq!(wasi.run(&mut store, &instance).unwrap(), 0);
+    let bytes = instance.exported_mem("memory").unwrap().bytes(&store);
+    let bufused = u32::from_le_bytes(bytes[16..20].try_into().unwrap()) as usize;
+    let mut dirents = &bytes[1024..][..bufused];
+    let mut entries = Vec::new();
+    while !dirents.is_empty() {
+        let name_len = u32::from_le_bytes(dirents[16..20].try_into().unwrap()) as usize;
+        let name = String::from_utf8(dirents[24..][..name_len].to_vec()).unwrap();
+        entries.push((name, dirents[20]));
+        dirents = &dirents[24 + name_len..];
+    }
+    assert_eq!(
+        entries,
+        [
+            (".".to_string(), 3),
+            ("..".to_string(), 3),
+            ("a".to_string(), 3),
+            ("b.txt".to_string(), 4),
+        ]
+    );
+}
+
+#[test]
+fn seek_and_write() {
+    let dir = VirtualDir::new().with_file("log.txt", "0123456789");
+    let wasi = Wasi::builder()
+        .preopen_virtual_dir(".", dir.clone())
+        .build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (data (i32.const 100) "log.txt")
+            (data (i32.const 120) "ab")
+
+            (func (export "_start")
+                (local $fd i32)
+                ;; Open with the rights to read, seek and write.
+                (local.set $fd (call $open (i32.const 100) (i32.const 7) (i32.const 0) (i64.const 70)))
+                (call $check (call $fd_seek (local.get $fd) (i64.const -3) (i32.const 2) (i32.const 16)))
+                (call $write (i32.const 1) (i32.const 200) (call $read (local.get $fd) (i32.const 200) (i32.const 64)))
+                (call $check (call $fd_seek (local.get $fd) (i64.const 4) (i32.const 0) (i32.const 16)))
+                (call $write (local.get $fd) (i32.const 120) (i32.const 2))
+                (call $proc_exit (i32.load (i32.const 16)))
+            )
+        "#,
+    );
+    assert_eq!(exit_code, 4);
+    assert_eq!(wasi.stdout(), b"789");
+    assert_eq!(dir.read_file("log.txt").unwrap(), b"0123ab6789");
+}
+
+#[test]
+fn bad_fd_is_an_error() {
+    let wasi = Wasi::builder().build();
+    let exit_code = run(
+        &wasi,
+        r#"
+            (data (i32.const 100) "missing.txt")
+
+            (func (export "_start")
+                ;; There are no preopens, so file descriptor 3 does not exist.
+                (call $proc_exit
+                    (call $path_open
+                        (i32.const 3)
+                        (i32.const 0)
+                        (i32.const 100)
+                        (i32.const 11)
+                        (i32.const 0)
+                        (i64.const 2)
+                        (i64.const 0)
+                        (i32.const 0)
+                        (i32.const 12)
+                    )
+                )
+            )
+        "#,
+    );
+    // EBADF
+    assert_eq!(exit_code, 8);
+}
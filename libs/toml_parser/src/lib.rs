@@ -1,2 +1,4 @@
 mod toml;
-pub use crate::toml::*;
\ No newline at end of file
+mod toml_document;
+pub use crate::toml::*;
+pub use crate::toml_document::*;
\ No newline at end of file
@@ -0,0 +1,1214 @@
+use crate::toml::{TomlErr, TomlSpan};
+use std::fmt;
+
+// A TOML document that remembers how it was written. Every value keeps the text it was parsed
+// from, and the whitespace and comments around keys, values and table headers are kept as
+// they are, so a document that is parsed and written again comes out the same, byte for byte.
+// Only the parts that are changed get written anew.
+
+/// A TOML value, without any of its formatting.
+#[derive(PartialEq, Debug, Clone)]
+pub enum TomlValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// Offset and local date-times, dates and times, as written.
+    Date(String),
+    Array(Vec<TomlValue>),
+    /// An inline table, or a table made of sections and dotted keys, in the order of its keys.
+    Table(Vec<(String, TomlValue)>),
+}
+
+impl TomlValue {
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::Str(v) = self {Some(v)} else {None}
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        if let Self::Int(v) = self {Some(*v)} else {None}
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(v) => Some(*v),
+            Self::Int(v) => Some(*v as f64),
+            _ => None
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Self::Bool(v) = self {Some(*v)} else {None}
+    }
+
+    pub fn as_array(&self) -> Option<&[TomlValue]> {
+        if let Self::Array(v) = self {Some(v)} else {None}
+    }
+
+    pub fn as_table(&self) -> Option<&[(String, TomlValue)]> {
+        if let Self::Table(v) = self {Some(v)} else {None}
+    }
+
+    /// The value of `key`, if this is a table that has it.
+    pub fn get(&self, key: &str) -> Option<&TomlValue> {
+        self.as_table()?.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// The value at `path` in nested tables.
+    pub fn get_path(&self, path: &[&str]) -> Option<&TomlValue> {
+        path.iter().try_fold(self, |value, key| value.get(key))
+    }
+}
+
+impl From<&str> for TomlValue {
+    fn from(v: &str) -> Self {Self::Str(v.to_string())}
+}
+
+impl From<String> for TomlValue {
+    fn from(v: String) -> Self {Self::Str(v)}
+}
+
+impl From<i64> for TomlValue {
+    fn from(v: i64) -> Self {Self::Int(v)}
+}
+
+impl From<f64> for TomlValue {
+    fn from(v: f64) -> Self {Self::Float(v)}
+}
+
+impl From<bool> for TomlValue {
+    fn from(v: bool) -> Self {Self::Bool(v)}
+}
+
+impl From<Vec<TomlValue>> for TomlValue {
+    fn from(v: Vec<TomlValue>) -> Self {Self::Array(v)}
+}
+
+/// Why a value can't be set at a path of a document or a table.
+pub struct TomlPathErr {
+    pub msg: String,
+    /// The part of the path where it went wrong.
+    pub path: Vec<String>,
+}
+
+impl fmt::Debug for TomlPathErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Toml error: {}, path:{}", self.msg, self.path.join("."))
+    }
+}
+
+/// A parsed TOML file that can be changed and written back with `to_string`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TomlDocument {
+    // the root table comes first, then a table for every header in the order they are written
+    tables: Vec<TomlTable>,
+    // comments and whitespace at the end of the file
+    trailing: String,
+}
+
+/// The key values under a table header, or the ones before the first header.
+#[derive(PartialEq, Debug, Clone)]
+pub struct TomlTable {
+    header: Option<TomlHeader>,
+    entries: Vec<TomlEntry>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct TomlHeader {
+    prefix: String,
+    path: Vec<String>,
+    is_array: bool,
+    // from the opening to the closing bracket
+    raw: String,
+    suffix: String,
+}
+
+// a key value in a table or an inline table. in a table the prefix holds the lines before it and
+// the suffix the end of its line, in an inline table they hold the space up to the commas
+#[derive(PartialEq, Debug, Clone)]
+struct TomlEntry {
+    prefix: String,
+    key: Vec<String>,
+    key_raw: String,
+    // the equals sign and the space around it
+    equals_raw: String,
+    node: TomlNode,
+    suffix: String,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+enum TomlNode {
+    Scalar {value: TomlValue, raw: String},
+    Array {items: Vec<TomlArrayItem>, trailing_comma: bool, trailing: String},
+    InlineTable {entries: Vec<TomlEntry>, trailing_comma: bool, trailing: String},
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct TomlArrayItem {
+    before: String,
+    node: TomlNode,
+    after: String,
+}
+
+pub fn parse_toml_document(data: &str) -> Result<TomlDocument, TomlErr> {
+    let mut p = DocumentParser {data, pos: 0};
+    let mut tables = vec![TomlTable {header: None, entries: Vec::new()}];
+    let mut defined = DefinedTable::default();
+    loop {
+        let prefix = p.decor();
+        if p.is_eof() {
+            return Ok(TomlDocument {tables, trailing: prefix});
+        }
+        let start = p.pos;
+        if p.peek() == Some('[') {
+            let is_array = p.eat("[[") || !p.eat("[");
+            p.space();
+            let (path, _) = p.key()?;
+            p.space();
+            if !p.eat(if is_array {"]]"} else {"]"}) {
+                return Err(p.err("Expected ] after the table name"));
+            }
+            defined.define_header(&path, is_array).map_err(|msg| p.err_at(start, msg))?;
+            let raw = p.data[start..p.pos].to_string();
+            let suffix = p.line_end()?;
+            tables.push(TomlTable {
+                header: Some(TomlHeader {prefix, path, is_array, raw, suffix}),
+                entries: Vec::new(),
+            });
+        }
+        else {
+            let mut entry = p.entry(prefix)?;
+            let table = tables.last_mut().unwrap();
+            defined.header_table(table.path()).define_key(&entry.key).map_err(|msg| p.err_at(start, msg))?;
+            entry.suffix = p.line_end()?;
+            table.entries.push(entry);
+        }
+    }
+}
+
+impl TomlDocument {
+    /// A document for a table value. Tables in it get a section of their own and arrays of
+    /// tables a `[[...]]` table for each of them, everything else is written as a key value.
+    pub fn from_value(value: &TomlValue) -> Self {
+        let mut document = Self {tables: vec![TomlTable {header: None, entries: Vec::new()}], trailing: String::new()};
+        if let TomlValue::Table(values) = value {
+            document.add_values(0, &mut Vec::new(), values);
+        }
+        document
+    }
+
+    /// The value at `path`, which can go through tables, dotted keys and inline tables.
+    /// Tables that are made of sections come back as `TomlValue::Table`, arrays of tables as
+    /// arrays of them.
+    pub fn get(&self, path: &[&str]) -> Option<TomlValue> {
+        self.to_value().get_path(path).cloned()
+    }
+
+    /// The whole document as one table.
+    pub fn to_value(&self) -> TomlValue {
+        let mut root = Vec::new();
+        for table in &self.tables {
+            let target = match &table.header {
+                None => &mut root,
+                Some(header) => {
+                    let (last, parents) = header.path.split_last().unwrap();
+                    let parent = parents.iter().fold(&mut root, |table, key| section_table(table, key));
+                    if header.is_array {
+                        let index = match parent.iter().position(|(k, _)| k == last) {
+                            Some(index) => index,
+                            None => {
+                                parent.push((last.clone(), TomlValue::Array(Vec::new())));
+                                parent.len() - 1
+                            }
+                        };
+                        match &mut parent[index].1 {
+                            TomlValue::Array(tables) => {
+                                tables.push(TomlValue::Table(Vec::new()));
+                                let Some(TomlValue::Table(table)) = tables.last_mut() else {unreachable!()};
+                                table
+                            }
+                            // a key that isn't an array of tables, the document is invalid
+                            _ => continue
+                        }
+                    }
+                    else {
+                        section_table(parent, last)
+                    }
+                }
+            };
+            for entry in &table.entries {
+                insert_value(target, &entry.key, entry.node.to_value());
+            }
+        }
+        TomlValue::Table(root)
+    }
+
+    /// The table under the `[path]` header, or the root table for an empty path.
+    pub fn table(&self, path: &[&str]) -> Option<&TomlTable> {
+        self.tables.iter().find(|table| !table.is_array() && table.path_is(path))
+    }
+
+    pub fn table_mut(&mut self, path: &[&str]) -> Option<&mut TomlTable> {
+        self.tables.iter_mut().find(|table| !table.is_array() && table.path_is(path))
+    }
+
+    /// Every table under a `[[path]]` header, in order.
+    pub fn array_tables<'a>(&'a self, path: &'a [&str]) -> impl Iterator<Item = &'a TomlTable> {
+        self.tables.iter().filter(|table| table.is_array() && table.path_is(path))
+    }
+
+    pub fn array_tables_mut<'a>(&'a mut self, path: &'a [&str]) -> impl Iterator<Item = &'a mut TomlTable> {
+        self.tables.iter_mut().filter(|table| table.is_array() && table.path_is(path))
+    }
+
+    /// Adds a `[[path]]` table after the ones there are, or at the end of the document.
+    pub fn push_array_table(&mut self, path: &[&str]) -> &mut TomlTable {
+        self.insert_table(path, true)
+    }
+
+    /// Sets the value at `path`. A value that is there already is replaced where it is written,
+    /// a new one is added to the table that has the most of its path, or to a new table. A table
+    /// made of sections or dotted keys is removed and written anew.
+    ///
+    /// The path can only go through tables, a value in an array of tables is set through
+    /// `array_tables_mut`.
+    pub fn set(&mut self, path: &[&str], value: impl Into<TomlValue>) -> Result<(), TomlPathErr> {
+        let value = value.into();
+        check_path(&self.to_value(), path)?;
+        for index in self.set_candidates(path) {
+            let table = &mut self.tables[index];
+            let rest = &path[table.path().len()..];
+            if let Some(entry) = find_entry_mut(&mut table.entries, rest) {
+                entry.set_value(value);
+                return Ok(())
+            }
+        }
+        self.remove(path);
+        // there is always the root table to fall back to
+        let index = self.set_candidates(path)[0];
+        let table = &mut self.tables[index];
+        let rest = &path[table.path().len()..];
+        // the key goes in the table if it is a plain key, or in the inline table or dotted
+        // keys that are there already for the start of it
+        if rest.len() == 1 || table.entries.iter().any(|entry| shares_start(&entry.key, rest) || is_inline_start(entry, rest)) {
+            table.insert_entry(rest, value);
+            return Ok(())
+        }
+        let (last, parent) = path.split_last().unwrap();
+        let table = self.insert_table(parent, false);
+        table.insert_entry(&[last], value);
+        Ok(())
+    }
+
+    /// Removes the value at `path`, with the comments before it. A path that names tables
+    /// removes them along with the tables under them.
+    pub fn remove(&mut self, path: &[&str]) -> Option<TomlValue> {
+        let value = self.get(path)?;
+        let len = self.tables.len();
+        self.tables.retain(|table| table.header.is_none() || !table.path_starts_with(path));
+        if self.tables.len() == len {
+            for table in &mut self.tables {
+                if table.is_array() || table.path().len() >= path.len() || !table.path_starts(path) {
+                    continue
+                }
+                let rest = &path[table.path().len()..];
+                remove_entries(&mut table.entries, rest, true);
+            }
+        }
+        Some(value)
+    }
+
+    // the tables the value at `path` can be in, the longest header first
+    fn set_candidates(&self, path: &[&str]) -> Vec<usize> {
+        let mut candidates: Vec<usize> = (0..self.tables.len())
+            .filter(|index| {
+                let table = &self.tables[*index];
+                !table.is_array() && table.path().len() < path.len() && table.path_starts(path)
+            })
+            .collect();
+        candidates.sort_by_key(|index| std::cmp::Reverse(self.tables[*index].path().len()));
+        candidates
+    }
+
+    // adds the values of a table to the table at `index`, and sections after it for the tables
+    fn add_values<'a>(&mut self, index: usize, path: &mut Vec<&'a str>, values: &'a [(String, TomlValue)]) {
+        for (key, value) in values {
+            if !is_section(value) {
+                let mut entry = TomlEntry::new(&[key], value.clone());
+                entry.suffix = "\n".to_string();
+                self.tables[index].entries.push(entry);
+            }
+        }
+        for (key, value) in values {
+            path.push(key);
+            match value {
+                // a table that only has tables in it doesn't need a header
+                TomlValue::Table(values) if !values.is_empty() && values.iter().all(|(_, value)| is_section(value)) => {
+                    self.add_values(index, path, values);
+                }
+                TomlValue::Table(values) => {
+                    self.insert_table_at(self.tables.len(), path, false);
+                    self.add_values(self.tables.len() - 1, path, values);
+                }
+                TomlValue::Array(tables) if is_section(value) => for table in tables {
+                    let TomlValue::Table(values) = table else {unreachable!()};
+                    self.insert_table_at(self.tables.len(), path, true);
+                    self.add_values(self.tables.len() - 1, path, values);
+                }
+                _ => ()
+            }
+            path.pop();
+        }
+    }
+
+    // inserts a table after the last one that is under the same table as it
+    fn insert_table(&mut self, path: &[&str], is_array: bool) -> &mut TomlTable {
+        let parent_len = (0..path.len()).rev()
+            .find(|len| self.tables.iter().any(|table| table.header.is_some() && table.path_is(&path[..*len])))
+            .unwrap_or(0);
+        let parent = &path[..if is_array {path.len()} else {parent_len}];
+        let index = self.tables.iter()
+            .rposition(|table| table.header.is_some() && table.path_starts_with(parent))
+            .filter(|_| !parent.is_empty())
+            .map_or(self.tables.len(), |index| {
+                // and after the tables under that one
+                let mut index = index + 1;
+                while index < self.tables.len() && self.tables[index].path_starts_with(parent) {
+                    index += 1;
+                }
+                index
+            });
+        self.insert_table_at(index, path, is_array);
+        &mut self.tables[index]
+    }
+
+    fn insert_table_at(&mut self, index: usize, path: &[&str], is_array: bool) {
+        let is_first = self.tables[..index].iter().all(|table| table.header.is_none() && table.entries.is_empty());
+        if let Some(previous) = self.tables[..index].iter_mut().rev().find(|table| table.header.is_some() || !table.entries.is_empty()) {
+            previous.end_line();
+        }
+        let raw = if is_array {format!("[[{}]]", key_repr(path))} else {format!("[{}]", key_repr(path))};
+        self.tables.insert(index, TomlTable {
+            header: Some(TomlHeader {
+                prefix: if is_first {String::new()} else {"\n".to_string()},
+                path: path.iter().map(|key| key.to_string()).collect(),
+                is_array,
+                raw,
+                suffix: "\n".to_string(),
+            }),
+            entries: Vec::new(),
+        });
+    }
+}
+
+impl fmt::Display for TomlDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for table in &self.tables {
+            if let Some(header) = &table.header {
+                write!(f, "{}{}{}", header.prefix, header.raw, header.suffix)?;
+            }
+            for entry in &table.entries {
+                entry.fmt(f)?;
+            }
+        }
+        f.write_str(&self.trailing)
+    }
+}
+
+impl TomlTable {
+    /// The keys of the header, empty for the root table.
+    pub fn path(&self) -> &[String] {
+        self.header.as_ref().map_or(&[], |header| &header.path)
+    }
+
+    /// Whether this is one of the tables of an array of tables.
+    pub fn is_array(&self) -> bool {
+        self.header.as_ref().is_some_and(|header| header.is_array)
+    }
+
+    /// The keys of the key values in this table, dotted keys having more than one.
+    pub fn keys(&self) -> impl Iterator<Item = &[String]> {
+        self.entries.iter().map(|entry| entry.key.as_slice())
+    }
+
+    /// The value at `path` in this table, which can go through dotted keys and inline tables.
+    pub fn get(&self, path: &[&str]) -> Option<TomlValue> {
+        self.to_value().get_path(path).cloned()
+    }
+
+    /// Sets the value at `path` in this table, replacing the one that is there where it is
+    /// written, or adding it as a new key value. The path can only go through tables.
+    pub fn set(&mut self, path: &[&str], value: impl Into<TomlValue>) -> Result<(), TomlPathErr> {
+        let value = value.into();
+        check_path(&self.to_value(), path)?;
+        match find_entry_mut(&mut self.entries, path) {
+            Some(entry) => entry.set_value(value),
+            None => {
+                // a table of dotted keys is written anew
+                remove_entries(&mut self.entries, path, true);
+                self.insert_entry(path, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the value at `path` in this table, with the comments before it.
+    pub fn remove(&mut self, path: &[&str]) -> Option<TomlValue> {
+        let value = self.get(path)?;
+        remove_entries(&mut self.entries, path, true);
+        Some(value)
+    }
+
+    fn to_value(&self) -> TomlValue {
+        let mut table = Vec::new();
+        for entry in &self.entries {
+            insert_value(&mut table, &entry.key, entry.node.to_value());
+        }
+        TomlValue::Table(table)
+    }
+
+    fn path_is(&self, path: &[&str]) -> bool {
+        self.path().len() == path.len() && self.path_starts(path)
+    }
+
+    // whether the header is the start of `path`
+    fn path_starts(&self, path: &[&str]) -> bool {
+        self.path().iter().zip(path).all(|(a, b)| a == b) && self.path().len() <= path.len()
+    }
+
+    // whether `path` is the start of the header
+    fn path_starts_with(&self, path: &[&str]) -> bool {
+        self.path().len() >= path.len() && self.path().iter().zip(path).all(|(a, b)| a == b)
+    }
+
+    // adds a key value for `path`, into the inline table that has the start of it if there is one
+    fn insert_entry(&mut self, path: &[&str], value: TomlValue) {
+        if insert_inline_entry(&mut self.entries, path, &value) {
+            return
+        }
+        // new keys with a dotted start go after the ones that share it
+        let index = self.entries.iter()
+            .rposition(|entry| shares_start(&entry.key, path))
+            .map_or(self.entries.len(), |index| index + 1);
+        let prefix = match index.checked_sub(1).map(|index| &self.entries[index]) {
+            Some(previous) => indentation(&previous.prefix).to_string(),
+            None => String::new(),
+        };
+        if index == self.entries.len() {
+            self.end_line();
+        }
+        let mut entry = TomlEntry::new(path, value);
+        entry.prefix = prefix;
+        entry.suffix = "\n".to_string();
+        self.entries.insert(index, entry);
+    }
+
+    // makes sure the table ends with a newline, for something to go after it
+    fn end_line(&mut self) {
+        let suffix = match (self.entries.last_mut(), &mut self.header) {
+            (Some(entry), _) => &mut entry.suffix,
+            (None, Some(header)) => &mut header.suffix,
+            (None, None) => return
+        };
+        if !suffix.ends_with('\n') {
+            suffix.push('\n');
+        }
+    }
+}
+
+impl TomlEntry {
+    fn new(path: &[&str], value: TomlValue) -> Self {
+        Self {
+            prefix: String::new(),
+            key: path.iter().map(|key| key.to_string()).collect(),
+            key_raw: key_repr(path),
+            equals_raw: " = ".to_string(),
+            node: TomlNode::from_value(&value),
+            suffix: String::new(),
+        }
+    }
+
+    fn set_value(&mut self, value: TomlValue) {
+        // an unchanged value keeps how it was written
+        if self.node.to_value() != value {
+            self.node = TomlNode::from_value(&value);
+        }
+    }
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}{}{}", self.prefix, self.key_raw, self.equals_raw, self.node, self.suffix)
+    }
+}
+
+impl TomlNode {
+    fn from_value(value: &TomlValue) -> Self {
+        match value {
+            TomlValue::Array(values) => Self::Array {
+                items: values.iter().enumerate().map(|(index, value)| TomlArrayItem {
+                    before: if index == 0 {String::new()} else {" ".to_string()},
+                    node: Self::from_value(value),
+                    after: String::new(),
+                }).collect(),
+                trailing_comma: false,
+                trailing: String::new(),
+            },
+            TomlValue::Table(values) => {
+                let mut entries: Vec<TomlEntry> = values.iter().map(|(key, value)| {
+                    let mut entry = TomlEntry::new(&[key], value.clone());
+                    entry.prefix = " ".to_string();
+                    entry
+                }).collect();
+                if let Some(last) = entries.last_mut() {
+                    last.suffix = " ".to_string();
+                }
+                Self::InlineTable {entries, trailing_comma: false, trailing: String::new()}
+            }
+            _ => Self::Scalar {value: value.clone(), raw: scalar_repr(value)}
+        }
+    }
+
+    fn to_value(&self) -> TomlValue {
+        match self {
+            Self::Scalar {value, ..} => value.clone(),
+            Self::Array {items, ..} => TomlValue::Array(items.iter().map(|item| item.node.to_value()).collect()),
+            Self::InlineTable {entries, ..} => {
+                let mut table = Vec::new();
+                for entry in entries {
+                    insert_value(&mut table, &entry.key, entry.node.to_value());
+                }
+                TomlValue::Table(table)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TomlNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar {raw, ..} => f.write_str(raw),
+            Self::Array {items, trailing_comma, trailing} => {
+                f.write_str("[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}{}{}", item.before, item.node, item.after)?;
+                }
+                if *trailing_comma {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}]", trailing)
+            }
+            Self::InlineTable {entries, trailing_comma, trailing} => {
+                f.write_str("{")?;
+                for (index, entry) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    entry.fmt(f)?;
+                }
+                if *trailing_comma {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}}}", trailing)
+            }
+        }
+    }
+}
+
+// the entry for `path`, looking into inline tables on the way
+fn find_entry_mut<'a>(entries: &'a mut [TomlEntry], path: &[&str]) -> Option<&'a mut TomlEntry> {
+    for entry in entries {
+        if !key_starts(path, &entry.key) {
+            continue
+        }
+        if entry.key.len() == path.len() {
+            return Some(entry)
+        }
+        if let TomlNode::InlineTable {entries, ..} = &mut entry.node {
+            return find_entry_mut(entries, &path[entry.key.len()..])
+        }
+    }
+    None
+}
+
+// adds `path` to the inline table that has the start of it, if any
+fn insert_inline_entry(entries: &mut [TomlEntry], path: &[&str], value: &TomlValue) -> bool {
+    for entry in entries {
+        if !is_inline_start(entry, path) {
+            continue
+        }
+        let TomlNode::InlineTable {entries, ..} = &mut entry.node else {unreachable!()};
+        let rest = &path[entry.key.len()..];
+        if insert_inline_entry(entries, rest, value) {
+            return true
+        }
+        let mut new_entry = TomlEntry::new(rest, value.clone());
+        new_entry.prefix = " ".to_string();
+        // the space before the closing brace moves to the new last entry
+        new_entry.suffix = match entries.last_mut() {
+            Some(last) => std::mem::take(&mut last.suffix),
+            None => " ".to_string(),
+        };
+        entries.push(new_entry);
+        return true
+    }
+    false
+}
+
+// removes the entry for `path`, or the dotted keys that make up the table at `path`
+fn remove_entries(entries: &mut Vec<TomlEntry>, path: &[&str], is_table: bool) {
+    for entry in entries.iter_mut() {
+        if entry.key.len() < path.len() && key_starts(path, &entry.key) {
+            if let TomlNode::InlineTable {entries, ..} = &mut entry.node {
+                remove_entries(entries, &path[entry.key.len()..], false);
+                return
+            }
+        }
+    }
+    let count = entries.len();
+    let last_suffix = entries.last().map(|entry| entry.suffix.clone());
+    entries.retain(|entry| entry.key.len() < path.len() || !entry.key.iter().zip(path).all(|(a, b)| a == b));
+    // in an inline table the last entry has the space before the closing brace
+    if !is_table && entries.len() < count {
+        if let (Some(last), Some(suffix)) = (entries.last_mut(), last_suffix) {
+            last.suffix = suffix;
+        }
+    }
+}
+
+// fails if `path` is empty, or goes through anything but tables on the way to its last key
+fn check_path(value: &TomlValue, path: &[&str]) -> Result<(), TomlPathErr> {
+    let err = |msg: &str, len: usize| Err(TomlPathErr {
+        msg: msg.to_string(),
+        path: path[..len].iter().map(|key| key.to_string()).collect(),
+    });
+    let Some((_, parents)) = path.split_last() else {
+        return err("The path is empty", 0)
+    };
+    let mut value = value;
+    for (index, key) in parents.iter().enumerate() {
+        value = match value.get(key) {
+            None => return Ok(()),
+            Some(value @ TomlValue::Table(_)) => value,
+            Some(value) if is_section(value) => return err("The path goes through an array of tables", index + 1),
+            Some(_) => return err("The path goes through a value that isn't a table", index + 1),
+        };
+    }
+    Ok(())
+}
+
+// whether `key` is the start of `path`
+fn key_starts(path: &[&str], key: &[String]) -> bool {
+    key.len() <= path.len() && key.iter().zip(path).all(|(a, b)| a == b)
+}
+
+// whether a value is written as a section of its own, rather than as a key value
+fn is_section(value: &TomlValue) -> bool {
+    match value {
+        TomlValue::Table(_) => true,
+        TomlValue::Array(values) => !values.is_empty() && values.iter().all(|value| matches!(value, TomlValue::Table(_))),
+        _ => false
+    }
+}
+
+// whether the entry is an inline table that `path` goes into
+fn is_inline_start(entry: &TomlEntry, path: &[&str]) -> bool {
+    entry.key.len() < path.len() && key_starts(path, &entry.key) && matches!(entry.node, TomlNode::InlineTable {..})
+}
+
+// whether a dotted key and a path start with the same key, and so go in the same table
+fn shares_start(key: &[String], path: &[&str]) -> bool {
+    key.len() > 1 && path.len() > 1 && key[0] == path[0]
+}
+
+fn section_table<'a>(table: &'a mut Vec<(String, TomlValue)>, key: &str) -> &'a mut Vec<(String, TomlValue)> {
+    let index = match table.iter().position(|(k, _)| k == key) {
+        Some(index) => index,
+        None => {
+            table.push((key.to_string(), TomlValue::Table(Vec::new())));
+            table.len() - 1
+        }
+    };
+    // a table under an array of tables goes in the last one of them
+    let is_array_of_tables = matches!(&table[index].1, TomlValue::Array(values) if matches!(values.last(), Some(TomlValue::Table(_))));
+    let mut value = &mut table[index].1;
+    if is_array_of_tables {
+        let TomlValue::Array(values) = value else {unreachable!()};
+        value = values.last_mut().unwrap();
+    }
+    if !matches!(value, TomlValue::Table(_)) {
+        *value = TomlValue::Table(Vec::new());
+    }
+    let TomlValue::Table(table) = value else {unreachable!()};
+    table
+}
+
+fn insert_value(table: &mut Vec<(String, TomlValue)>, key: &[String], value: TomlValue) {
+    let (first, rest) = key.split_first().unwrap();
+    if rest.is_empty() {
+        match table.iter_mut().find(|(k, _)| k == first) {
+            // a table that has keys set already through dotted keys or sections
+            Some((_, TomlValue::Table(existing))) if matches!(value, TomlValue::Table(_)) => {
+                let TomlValue::Table(values) = value else {unreachable!()};
+                for (key, value) in values {
+                    insert_value(existing, &[key], value);
+                }
+            }
+            Some((_, existing)) => *existing = value,
+            None => table.push((first.clone(), value)),
+        }
+        return
+    }
+    insert_value(section_table(table, first), rest, value);
+}
+
+// the whitespace a line starts with, after the lines before it
+fn indentation(prefix: &str) -> &str {
+    let line = &prefix[prefix.rfind('\n').map_or(0, |index| index + 1)..];
+    if line.chars().all(|c| c == ' ' || c == '\t') {line} else {""}
+}
+
+fn key_repr(path: &[&str]) -> String {
+    let keys: Vec<String> = path.iter().map(|key| {
+        if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            key.to_string()
+        }
+        else {
+            string_repr(key)
+        }
+    }).collect();
+    keys.join(".")
+}
+
+fn string_repr(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn scalar_repr(value: &TomlValue) -> String {
+    match value {
+        TomlValue::Str(v) => string_repr(v),
+        TomlValue::Int(v) => v.to_string(),
+        TomlValue::Float(v) if v.is_nan() => "nan".to_string(),
+        TomlValue::Float(v) if v.is_infinite() => if *v < 0.0 {"-inf".to_string()} else {"inf".to_string()},
+        TomlValue::Float(v) => format!("{:?}", v),
+        TomlValue::Bool(v) => v.to_string(),
+        TomlValue::Date(v) => v.clone(),
+        TomlValue::Array(_) | TomlValue::Table(_) => unreachable!(),
+    }
+}
+
+// what the keys of a document are defined as, to find the keys and tables that are defined twice
+#[derive(Default)]
+struct DefinedTable {
+    keys: Vec<(String, Defined)>,
+}
+
+enum Defined {
+    Value,
+    // `header` is whether it has a header of its own, `dotted` whether dotted keys made it
+    Table {table: DefinedTable, header: bool, dotted: bool},
+    ArrayOfTables(Vec<DefinedTable>),
+}
+
+impl DefinedTable {
+    fn get_or_insert(&mut self, key: &str, defined: impl FnOnce() -> Defined) -> &mut Defined {
+        let index = match self.keys.iter().position(|(k, _)| k == key) {
+            Some(index) => index,
+            None => {
+                self.keys.push((key.to_string(), defined()));
+                self.keys.len() - 1
+            }
+        };
+        &mut self.keys[index].1
+    }
+
+    fn define_header(&mut self, path: &[String], is_array: bool) -> Result<(), &'static str> {
+        let (last, parents) = path.split_last().unwrap();
+        let mut table = self;
+        for key in parents {
+            table = match table.get_or_insert(key, Defined::new_table) {
+                Defined::Table {table, ..} => table,
+                Defined::ArrayOfTables(tables) => tables.last_mut().unwrap(),
+                Defined::Value => return Err("The key is defined already as a value"),
+            };
+        }
+        let new = if is_array {|| Defined::ArrayOfTables(Vec::new())} else {Defined::new_table};
+        match table.get_or_insert(last, new) {
+            Defined::ArrayOfTables(tables) if is_array => tables.push(DefinedTable::default()),
+            // a table that only had tables under it
+            Defined::Table {header, dotted: false, ..} if !is_array && !*header => *header = true,
+            Defined::Table {..} if !is_array => return Err("The table is defined twice"),
+            _ => return Err("The key is defined already"),
+        }
+        Ok(())
+    }
+
+    // the table that the key values under a header go in
+    fn header_table(&mut self, path: &[String]) -> &mut DefinedTable {
+        path.iter().fold(self, |table, key| match table.get_or_insert(key, Defined::new_table) {
+            Defined::Table {table, ..} => table,
+            Defined::ArrayOfTables(tables) => tables.last_mut().unwrap(),
+            Defined::Value => unreachable!(),
+        })
+    }
+
+    fn define_key(&mut self, key: &[String]) -> Result<(), &'static str> {
+        let (last, parents) = key.split_last().unwrap();
+        let mut table = self;
+        for key in parents {
+            let new = || Defined::Table {table: DefinedTable::default(), header: false, dotted: true};
+            table = match table.get_or_insert(key, new) {
+                // dotted keys can't add to the tables of headers
+                Defined::Table {table, dotted: true, ..} => table,
+                _ => return Err("The key is defined already"),
+            };
+        }
+        if table.keys.iter().any(|(k, _)| k == last) {
+            return Err("The key is defined twice");
+        }
+        table.keys.push((last.clone(), Defined::Value));
+        Ok(())
+    }
+}
+
+impl Defined {
+    fn new_table() -> Self {
+        Self::Table {table: DefinedTable::default(), header: false, dotted: false}
+    }
+}
+
+struct DocumentParser<'a> {
+    data: &'a str,
+    // in bytes
+    pos: usize,
+}
+
+impl<'a> DocumentParser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.data[self.pos..].chars().next()
+    }
+
+    fn is_eof(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn eat(&mut self, what: &str) -> bool {
+        if self.data[self.pos..].starts_with(what) {
+            self.pos += what.len();
+            return true
+        }
+        false
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn err(&self, msg: &str) -> TomlErr {
+        self.err_at(self.pos, msg)
+    }
+
+    fn err_at(&self, pos: usize, msg: &str) -> TomlErr {
+        // spans count characters, like the ones of parse_toml
+        TomlErr {msg: msg.to_string(), span: TomlSpan {start: self.data[..pos].chars().count(), len: 0}}
+    }
+
+    // spaces and tabs
+    fn space(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(' ' | '\t') = self.peek() {
+            self.pos += 1;
+        }
+        &self.data[start..self.pos]
+    }
+
+    // whitespace, newlines and comments
+    fn decor(&mut self) -> String {
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r' | '\n') => self.pos += 1,
+                Some('#') => self.comment(),
+                _ => break
+            }
+        }
+        self.data[start..self.pos].to_string()
+    }
+
+    fn comment(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '\n' || self.data[self.pos..].starts_with("\r\n") {
+                break
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    // the rest of a line after a key value or a header
+    fn line_end(&mut self) -> Result<String, TomlErr> {
+        let start = self.pos;
+        self.space();
+        if self.peek() == Some('#') {
+            self.comment();
+        }
+        if !self.eat("\n") && !self.eat("\r\n") && !self.is_eof() {
+            return Err(self.err("Expected the end of the line"));
+        }
+        Ok(self.data[start..self.pos].to_string())
+    }
+
+    fn key(&mut self) -> Result<(Vec<String>, String), TomlErr> {
+        let start = self.pos;
+        let mut keys = Vec::new();
+        loop {
+            keys.push(self.simple_key()?);
+            let end = self.pos;
+            self.space();
+            if self.eat(".") {
+                self.space();
+                continue
+            }
+            self.pos = end;
+            return Ok((keys, self.data[start..end].to_string()))
+        }
+    }
+
+    fn simple_key(&mut self) -> Result<String, TomlErr> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            _ => {
+                let start = self.pos;
+                while let Some('a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-') = self.peek() {
+                    self.pos += 1;
+                }
+                if start == self.pos {
+                    return Err(self.err("Expected a key"));
+                }
+                Ok(self.data[start..self.pos].to_string())
+            }
+        }
+    }
+
+    fn entry(&mut self, prefix: String) -> Result<TomlEntry, TomlErr> {
+        let (key, key_raw) = self.key()?;
+        let start = self.pos;
+        self.space();
+        if !self.eat("=") {
+            return Err(self.err("Expected = after the key"));
+        }
+        self.space();
+        let equals_raw = self.data[start..self.pos].to_string();
+        let node = self.value()?;
+        Ok(TomlEntry {prefix, key, key_raw, equals_raw, node, suffix: String::new()})
+    }
+
+    fn value(&mut self) -> Result<TomlNode, TomlErr> {
+        let start = self.pos;
+        let value = match self.peek() {
+            Some('[') => return self.array(),
+            Some('{') => return self.inline_table(),
+            Some('"') if self.data[self.pos..].starts_with("\"\"\"") => TomlValue::Str(self.multiline_string("\"\"\"")?),
+            Some('\'') if self.data[self.pos..].starts_with("'''") => TomlValue::Str(self.multiline_string("'''")?),
+            Some('"') => TomlValue::Str(self.basic_string()?),
+            Some('\'') => TomlValue::Str(self.literal_string()?),
+            _ if self.eat("true") => TomlValue::Bool(true),
+            _ if self.eat("false") => TomlValue::Bool(false),
+            _ => self.number_or_date()?,
+        };
+        Ok(TomlNode::Scalar {value, raw: self.data[start..self.pos].to_string()})
+    }
+
+    fn array(&mut self) -> Result<TomlNode, TomlErr> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            let before = self.decor();
+            if self.eat("]") {
+                return Ok(TomlNode::Array {trailing_comma: !items.is_empty(), items, trailing: before})
+            }
+            let node = self.value()?;
+            let after = self.decor();
+            items.push(TomlArrayItem {before, node, after});
+            if self.eat("]") {
+                return Ok(TomlNode::Array {items, trailing_comma: false, trailing: String::new()})
+            }
+            if !self.eat(",") {
+                return Err(self.err("Expected , or ] in the array"));
+            }
+        }
+    }
+
+    fn inline_table(&mut self) -> Result<TomlNode, TomlErr> {
+        self.bump();
+        let mut entries = Vec::new();
+        let mut defined = DefinedTable::default();
+        loop {
+            let prefix = self.decor();
+            if self.eat("}") {
+                return Ok(TomlNode::InlineTable {trailing_comma: !entries.is_empty(), entries, trailing: prefix})
+            }
+            let start = self.pos;
+            let mut entry = self.entry(prefix)?;
+            defined.define_key(&entry.key).map_err(|msg| self.err_at(start, msg))?;
+            entry.suffix = self.decor();
+            entries.push(entry);
+            if self.eat("}") {
+                return Ok(TomlNode::InlineTable {entries, trailing_comma: false, trailing: String::new()})
+            }
+            if !self.eat(",") {
+                return Err(self.err("Expected , or } in the inline table"));
+            }
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, TomlErr> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => self.escape(&mut value)?,
+                Some('\n') | None => return Err(self.err("Unterminated string")),
+                Some(c) => value.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, TomlErr> {
+        self.bump();
+        let start = self.pos;
+        loop {
+            match self.bump() {
+                Some('\'') => return Ok(self.data[start..self.pos - 1].to_string()),
+                Some('\n') | None => return Err(self.err("Unterminated string")),
+                Some(_) => ()
+            }
+        }
+    }
+
+    fn multiline_string(&mut self, quotes: &str) -> Result<String, TomlErr> {
+        self.pos += quotes.len();
+        // a newline right after the opening quotes isn't part of the string
+        if !self.eat("\n") {
+            self.eat("\r\n");
+        }
+        let is_literal = quotes == "'''";
+        let mut value = String::new();
+        loop {
+            if self.data[self.pos..].starts_with(quotes) {
+                self.pos += quotes.len();
+                // up to two quotes can come right before the closing ones
+                for _ in 0..2 {
+                    if self.eat(&quotes[..1]) {
+                        value.push_str(&quotes[..1]);
+                    }
+                }
+                return Ok(value)
+            }
+            match self.bump() {
+                Some('\\') if !is_literal => {
+                    // a backslash at the end of a line trims the whitespace that follows
+                    let rest = &self.data[self.pos..];
+                    let trimmed = rest.trim_start_matches([' ', '\t']);
+                    if trimmed.starts_with('\n') || trimmed.starts_with("\r\n") {
+                        let trimmed = trimmed.trim_start_matches([' ', '\t', '\r', '\n']);
+                        self.pos = self.data.len() - trimmed.len();
+                    }
+                    else {
+                        self.escape(&mut value)?;
+                    }
+                }
+                Some(c) => value.push(c),
+                None => return Err(self.err("Unterminated string")),
+            }
+        }
+    }
+
+    fn escape(&mut self, value: &mut String) -> Result<(), TomlErr> {
+        let c = match self.bump() {
+            Some('b') => '\u{8}',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('f') => '\u{c}',
+            Some('r') => '\r',
+            Some('e') => '\u{1b}',
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some(c @ ('u' | 'U')) => {
+                let len = if c == 'u' {4} else {8};
+                let hex = self.data.get(self.pos..self.pos + len).ok_or_else(|| self.err("Invalid unicode escape"))?;
+                let c = u32::from_str_radix(hex, 16).ok().and_then(char::from_u32).ok_or_else(|| self.err("Invalid unicode escape"))?;
+                self.pos += len;
+                c
+            }
+            _ => return Err(self.err("Invalid escape in string"))
+        };
+        value.push(c);
+        Ok(())
+    }
+
+    fn number_or_date(&mut self) -> Result<TomlValue, TomlErr> {
+        let start = self.pos;
+        let token_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '.' | ':');
+        while self.peek().is_some_and(token_char) {
+            self.pos += 1;
+        }
+        // a date and a time can be apart by a space
+        let bytes = self.data.as_bytes();
+        if self.pos - start == 10 && bytes[start + 4] == b'-' && self.data[self.pos..].len() > 3
+            && bytes[self.pos] == b' ' && bytes[self.pos + 1].is_ascii_digit() && bytes[self.pos + 3] == b':' {
+            self.pos += 1;
+            while self.peek().is_some_and(token_char) {
+                self.pos += 1;
+            }
+        }
+        let token = &self.data[start..self.pos];
+        if token.is_empty() {
+            return Err(self.err("Expected a value"));
+        }
+        let token_bytes = token.as_bytes();
+        let is_date = token_bytes.len() >= 5 && token_bytes[..4].iter().all(u8::is_ascii_digit) && token_bytes[4] == b'-';
+        let is_time = token_bytes.len() >= 3 && token_bytes[..2].iter().all(u8::is_ascii_digit) && token_bytes[2] == b':';
+        if is_date || is_time {
+            return Ok(TomlValue::Date(token.to_string()));
+        }
+        let unsigned = token.trim_start_matches(['+', '-']);
+        let is_negative = token.starts_with('-');
+        match unsigned {
+            "inf" => return Ok(TomlValue::Float(if is_negative {f64::NEG_INFINITY} else {f64::INFINITY})),
+            "nan" => return Ok(TomlValue::Float(f64::NAN)),
+            _ => ()
+        }
+        let digits = token.replace('_', "");
+        let radix = match unsigned.get(..2) {
+            Some("0x") => 16,
+            Some("0o") => 8,
+            Some("0b") => 2,
+            _ => 10
+        };
+        if radix != 10 {
+            return i64::from_str_radix(&digits[2..], radix).map(TomlValue::Int).map_err(|_| self.err("Invalid number"));
+        }
+        if unsigned.contains(['.', 'e', 'E']) {
+            return digits.parse().map(TomlValue::Float).map_err(|_| self.err("Invalid number"));
+        }
+        digits.parse().map(TomlValue::Int).map_err(|_| self.err("Invalid number"))
+    }
+}
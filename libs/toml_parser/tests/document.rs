@@ -0,0 +1,260 @@
+use makepad_toml_parser::{parse_toml_document, TomlDocument, TomlValue};
+
+const CARGO_TOML: &str = r#"# the main package
+[package]
+name = "makepad-example"   # keep in sync
+version = "0.6.0"
+edition = "2021"
+metadata.makepad-auto-version = "zqpv-Yj-HjF0Y0Ml"
+authors = [
+    "Makepad <info@makepad.nl>", # the team
+    'Someone Else',
+]
+
+[dependencies]
+makepad-widgets = { path = "../../widgets", version = "0.6.0" }
+serde = {version="1.0",features=["derive"]}
+
+[target.'cfg(windows)'.dependencies]
+makepad-windows = { path = "../../libs/windows", version = "0.51.1" }
+
+[[bin]]
+name = "example"
+path = "src/main.rs"
+
+[[bin]]
+name = "other"
+
+[profile.release]
+opt-level = 3
+lto = true
+debug = false
+"#;
+
+#[test]
+fn round_trips_unchanged() {
+    let inputs = [
+        CARGO_TOML,
+        "",
+        "# only a comment",
+        "a = 1\r\nb = \"two\"\r\n\r\n[c]\r\nd = [1, 2, 3,]\r\n",
+        "str = \"\"\"\nfirst \\\n    second\"\"\"\nlit = '''\n'raw' \\n'''\n",
+        "[ a . \"b c\" ]  # spaced\n  x = { }\n  y = {a=1,b={c=2}}\n",
+        "int = +1_000\nhex = 0xDEAD_beef\noct = 0o755\nbin = 0b1010\nf = -3.5e-2\ninf = -inf\nnan = nan",
+        "date = 1979-05-27T07:32:00-08:00\nlocal = 1979-05-27 07:32:00\nday = 1979-05-27\ntime = 07:32:00.999\n",
+        "empty = []\nnested = [ [1, 2], [\"a\"], ] # nested\nmulti = [\n  # first\n  1,\n\n  2 # two\n]\n",
+    ];
+    for input in inputs {
+        let doc = parse_toml_document(input).unwrap();
+        assert_eq!(doc.to_string(), input);
+    }
+}
+
+#[test]
+fn reads_values() {
+    let doc = parse_toml_document(CARGO_TOML).unwrap();
+    assert_eq!(doc.get(&["package", "name"]), Some(TomlValue::from("makepad-example")));
+    assert_eq!(doc.get(&["package", "metadata", "makepad-auto-version"]), Some(TomlValue::from("zqpv-Yj-HjF0Y0Ml")));
+    assert_eq!(
+        doc.get(&["package", "authors"]),
+        Some(TomlValue::from(vec![TomlValue::from("Makepad <info@makepad.nl>"), TomlValue::from("Someone Else")]))
+    );
+    assert_eq!(doc.get(&["dependencies", "serde", "features"]), Some(TomlValue::from(vec![TomlValue::from("derive")])));
+    assert_eq!(
+        doc.get(&["target", "cfg(windows)", "dependencies", "makepad-windows", "version"]),
+        Some(TomlValue::from("0.51.1"))
+    );
+    assert_eq!(doc.get(&["profile", "release", "opt-level"]), Some(TomlValue::Int(3)));
+    assert_eq!(doc.get(&["profile", "release", "lto"]), Some(TomlValue::Bool(true)));
+    assert_eq!(doc.get(&["package", "missing"]), None);
+
+    let bins = doc.get(&["bin"]).unwrap();
+    let bins = bins.as_array().unwrap();
+    assert_eq!(bins.len(), 2);
+    assert_eq!(bins[1].get("name").and_then(|v| v.as_str()), Some("other"));
+    assert_eq!(doc.array_tables(&["bin"]).count(), 2);
+
+    let doc = parse_toml_document("s = \"tab\\there \\u00e9\"\nm = \"\"\"\nline \\\n   joined\"\"\"\nhex = 0xff\nf = 1e3\n").unwrap();
+    assert_eq!(doc.get(&["s"]).unwrap().as_str(), Some("tab\there é"));
+    assert_eq!(doc.get(&["m"]).unwrap().as_str(), Some("line joined"));
+    assert_eq!(doc.get(&["hex"]).unwrap().as_int(), Some(255));
+    assert_eq!(doc.get(&["f"]).unwrap().as_float(), Some(1000.0));
+}
+
+#[test]
+fn replaces_values_in_place() {
+    let mut doc = parse_toml_document(CARGO_TOML).unwrap();
+    doc.set(&["package", "name"], "makepad-example").unwrap();
+    doc.set(&["profile", "release", "lto"], true).unwrap();
+    assert_eq!(doc.to_string(), CARGO_TOML);
+
+    doc.set(&["package", "name"], "renamed").unwrap();
+    doc.set(&["package", "metadata", "makepad-auto-version"], "new-hash").unwrap();
+    doc.set(&["dependencies", "makepad-widgets", "version"], "0.7.0").unwrap();
+    doc.set(&["profile", "release", "opt-level"], 2).unwrap();
+    let expected = CARGO_TOML
+        .replace("name = \"makepad-example\"   # keep", "name = \"renamed\"   # keep")
+        .replace("\"zqpv-Yj-HjF0Y0Ml\"", "\"new-hash\"")
+        .replace("version = \"0.6.0\" }", "version = \"0.7.0\" }")
+        .replace("opt-level = 3", "opt-level = 2");
+    assert_eq!(doc.to_string(), expected);
+}
+
+#[test]
+fn adds_values() {
+    let mut doc = parse_toml_document("[package]\nname = \"a\" # the name\n\n[dependencies]\nx = { path = \"x\" }").unwrap();
+    doc.set(&["package", "version"], "0.1.0").unwrap();
+    doc.set(&["dependencies", "x", "version"], "1.0").unwrap();
+    doc.set(&["dependencies", "y"], "2.0").unwrap();
+    doc.set(&["features", "default"], vec![TomlValue::from("std")]).unwrap();
+    doc.set(&["package", "metadata", "hash"], "abc").unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "[package]\nname = \"a\" # the name\nversion = \"0.1.0\"\n\n[package.metadata]\nhash = \"abc\"\n\n[dependencies]\nx = { path = \"x\", version = \"1.0\" }\ny = \"2.0\"\n\n[features]\ndefault = [\"std\"]\n"
+    );
+    assert_eq!(parse_toml_document(&doc.to_string()).unwrap(), doc);
+}
+
+#[test]
+fn removes_values() {
+    let mut doc = parse_toml_document(CARGO_TOML).unwrap();
+    assert_eq!(doc.remove(&["package", "edition"]), Some(TomlValue::from("2021")));
+    assert_eq!(doc.remove(&["dependencies", "serde", "features"]), Some(TomlValue::from(vec![TomlValue::from("derive")])));
+    assert!(doc.remove(&["target"]).is_some());
+    assert!(doc.remove(&["bin"]).is_some());
+    assert!(doc.remove(&["profile", "release", "debug"]).is_some());
+    assert_eq!(doc.remove(&["nothing", "here"]), None);
+    assert_eq!(doc.to_string(), r#"# the main package
+[package]
+name = "makepad-example"   # keep in sync
+version = "0.6.0"
+metadata.makepad-auto-version = "zqpv-Yj-HjF0Y0Ml"
+authors = [
+    "Makepad <info@makepad.nl>", # the team
+    'Someone Else',
+]
+
+[dependencies]
+makepad-widgets = { path = "../../widgets", version = "0.6.0" }
+serde = {version="1.0"}
+
+[profile.release]
+opt-level = 3
+lto = true
+"#);
+}
+
+#[test]
+fn edits_arrays_of_tables() {
+    let mut doc = parse_toml_document(CARGO_TOML).unwrap();
+    doc.array_tables_mut(&["bin"]).nth(1).unwrap().set(&["path"], "src/other.rs").unwrap();
+    let bin = doc.push_array_table(&["bin"]);
+    bin.set(&["name"], "third").unwrap();
+    bin.set(&["test"], false).unwrap();
+    let expected = CARGO_TOML.replace(
+        "name = \"other\"\n",
+        "name = \"other\"\npath = \"src/other.rs\"\n\n[[bin]]\nname = \"third\"\ntest = false\n"
+    );
+    assert_eq!(doc.to_string(), expected);
+
+    let mut doc = parse_toml_document("a = 1").unwrap();
+    doc.push_array_table(&["item"]).set(&["b"], 2).unwrap();
+    assert_eq!(doc.to_string(), "a = 1\n\n[[item]]\nb = 2\n");
+}
+
+#[test]
+fn reports_errors() {
+    for input in ["a = ", "a = \"open", "[table", "a = 1 b = 2", "a = [1 2]", "= 1", "a = {b = 1"] {
+        assert!(parse_toml_document(input).is_err(), "{input:?} should not parse");
+    }
+}
+
+#[test]
+fn rejects_keys_defined_twice() {
+    let inputs = [
+        "a = 1\na = 2",
+        "a = {b = 1, b = 2}",
+        "a = {b.c = 1, b = 2}",
+        "[a]\nx = 1\n[a]\ny = 2",
+        "[a]\n[b]\n[a]",
+        "a.b = 1\na.b = 2",
+        "a = 1\na.b = 2",
+        "a = 1\n[a]",
+        "a = {}\n[a.b]",
+        "a = [1]\n[[a]]",
+        "[[a]]\n[a]",
+        "[a]\n[[a]]",
+        "[a]\nb.c = 1\n[a.b]",
+        "[a.b]\n[a]\nb.c = 1",
+        "[a.b]\nc = 1\n[a]\nb = 2",
+    ];
+    for input in inputs {
+        assert!(parse_toml_document(input).is_err(), "{input:?} should not parse");
+    }
+    let err = parse_toml_document("a = 1\nb = 2\na = 3").unwrap_err();
+    assert_eq!(err.span.start, 12);
+
+    let inputs = [
+        "[a.b]\n[a]",
+        "[a]\n[a.b]",
+        "[[a]]\n[[a]]\nb = 1",
+        "[[a]]\n[a.b]\n[[a]]\n[a.b]",
+        "a.b = 1\na.c = 2",
+        "[a]\nb.c = 1\n[a.b.d]",
+        "a = {b = {c = 1}, d.e = 2, d.f = 3}",
+    ];
+    for input in inputs {
+        assert!(parse_toml_document(input).is_ok(), "{input:?} should parse");
+    }
+}
+
+#[test]
+fn set_keeps_the_document_valid() {
+    for input in ["a = 1", "a = [1]", "a = \"s\"", "a = {x = 1}\n"] {
+        let mut doc = parse_toml_document(input).unwrap();
+        let is_table = doc.get(&["a"]).unwrap().as_table().is_some();
+        assert_eq!(doc.set(&["a", "b"], 5).is_ok(), is_table, "{input:?}");
+        if !is_table {
+            assert_eq!(doc.set(&["a", "b", "c"], 5).unwrap_err().path, ["a"]);
+            assert_eq!(doc.to_string(), input);
+        }
+    }
+
+    let mut doc = parse_toml_document("[[bin]]\nname = \"a\"\n").unwrap();
+    let err = doc.set(&["bin", "path"], 5).unwrap_err();
+    assert_eq!(err.path, ["bin"]);
+    assert!(doc.set(&[], 5).is_err());
+    assert!(doc.array_tables_mut(&["bin"]).next().unwrap().set(&[], 5).is_err());
+    assert!(doc.array_tables_mut(&["bin"]).next().unwrap().set(&["name", "x"], 5).is_err());
+    assert_eq!(doc.to_string(), "[[bin]]\nname = \"a\"\n");
+
+    // tables made of sections or dotted keys are replaced as a whole
+    let mut doc = parse_toml_document("x.y = 1\nx.z = 2\n\n[a]\nb = 1\n\n[a.c]\nd = 2\n").unwrap();
+    doc.set(&["a"], 5).unwrap();
+    doc.set(&["x"], "s").unwrap();
+    assert_eq!(doc.to_string(), "a = 5\nx = \"s\"\n");
+    assert!(parse_toml_document(&doc.to_string()).is_ok());
+
+    let mut doc = parse_toml_document("[t]\nx.y = 1\n").unwrap();
+    doc.table_mut(&["t"]).unwrap().set(&["x"], 2).unwrap();
+    assert_eq!(doc.to_string(), "[t]\nx = 2\n");
+}
+
+#[test]
+fn writes_values() {
+    let table = |values: Vec<(&str, TomlValue)>| TomlValue::Table(values.into_iter().map(|(k, v)| (k.to_string(), v)).collect());
+    let value = table(vec![
+        ("title", "A \"quoted\" title".into()),
+        ("inline", TomlValue::Array(vec![table(vec![("x", 1.into()), ("y", 2.5.into())]), 3.into()])),
+        ("empty", TomlValue::Array(Vec::new())),
+        ("package", table(vec![("name", "app".into()), ("version", "0.1.0".into())])),
+        ("target", table(vec![("cfg(unix)", table(vec![("dependencies", table(vec![("libc", "0.2".into())]))]))])),
+        ("bin", TomlValue::Array(vec![table(vec![("name", "a".into())]), table(vec![("name", "b".into())])])),
+    ]);
+    let doc = TomlDocument::from_value(&value);
+    assert_eq!(
+        doc.to_string(),
+        "title = \"A \\\"quoted\\\" title\"\ninline = [{ x = 1, y = 2.5 }, 3]\nempty = []\n\n[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[target.\"cfg(unix)\".dependencies]\nlibc = \"0.2\"\n\n[[bin]]\nname = \"a\"\n\n[[bin]]\nname = \"b\"\n"
+    );
+    assert_eq!(parse_toml_document(&doc.to_string()).unwrap().to_value(), value);
+}
@@ -12,7 +12,8 @@
             TokenSpan
         },
         shader_ast::*,
-        shader_registry::ShaderRegistry
+        shader_registry::ShaderRegistry,
+        swizzle::Swizzle
     }
 };
 
@@ -77,6 +78,36 @@ fn write_var_decl(
     fn write_ty_lit(&self, string: &mut String, ty_lit: TyLit);
     fn write_builtin_call_ident(&self, string: &mut String, ident: Ident, arg_exprs: &[Expr]);
     
+    // the defaults below produce the c-like syntax of glsl, hlsl and metal
+    
+    fn needs_select_for_cond_expr(&self) -> bool {
+        false
+    }
+    
+    fn needs_assignment_as_stmt(&self) -> bool {
+        false
+    }
+    
+    fn inout_is_pointer(&self) -> bool {
+        false
+    }
+    
+    fn params_are_immutable(&self) -> bool {
+        false
+    }
+    
+    fn write_fn_def_start(&self, string: &mut String, ident: &dyn fmt::Display, return_ty: &Ty) {
+        self.write_var_decl(string, "", false, false, ident, return_ty);
+        write!(string, "(").unwrap();
+    }
+    
+    fn write_fn_def_end(&self, string: &mut String, _return_ty: &Ty) {
+        write!(string, ") ").unwrap();
+    }
+    
+    fn write_let_decl(&self, string: &mut String, ident: &dyn fmt::Display, ty: &Ty) {
+        self.write_var_decl(string, "", false, false, ident, ty);
+    }
 }
 
 pub struct BlockGenerator<'a> {
@@ -100,8 +131,8 @@ pub fn generate_cons_fn(backend_writer:&dyn BackendWriter, string: &mut String,
     if !backend_writer.use_cons_fn(&cons_name) {
         return
     }
-    backend_writer.write_ty_lit(string, ty_lit);
-    write!(string, " {}(", cons_name).unwrap();
+    let ty = ty_lit.to_ty();
+    backend_writer.write_fn_def_start(string, &cons_name, &ty);
     
     let mut sep = "";
     if param_tys.len() == 1 {
@@ -114,11 +145,11 @@ pub fn generate_cons_fn(backend_writer:&dyn BackendWriter, string: &mut String,
         }
     }
     
-    writeln!(string, ") {{").unwrap();
+    backend_writer.write_fn_def_end(string, &ty);
+    writeln!(string, "{{").unwrap();
     write!(string, "    return ").unwrap();
     backend_writer.write_ty_lit(string, ty_lit);
     write!(string, "(").unwrap();
-    let ty = ty_lit.to_ty();
     if param_tys.len() == 1 {
         let param_ty = &param_tys[0];
         match param_ty {
@@ -288,9 +319,11 @@ fn generate_for_stmt(
         } else {
             -1
         };
+        write!(self.string, "for (").unwrap();
+        self.backend_writer.write_let_decl(self.string, &DisplayVarName(ident, ScopeSymShadow(0)), &Ty::Int);
         write!(
             self.string,
-            "for (int {0} = {1}; {0} {2} {3}; {0} {4} {5}) ",
+            " = {1}; {0} {2} {3}; {0} {4} {5}) ",
             &DisplayVarName(ident, ScopeSymShadow(0)),
             if from <= to {from} else {from - 1},
             if from <= to {"<"} else {">="},
@@ -361,11 +394,8 @@ fn generate_let_stmt(
         expr: &Option<Expr>,
         shadow: &Cell<Option<ScopeSymShadow >>
     ) {
-        self.backend_writer.write_var_decl(
+        self.backend_writer.write_let_decl(
             &mut self.string,
-            "",
-            false, 
-            false,
             &DisplayVarName(ident, shadow.get().unwrap()),
             ty.borrow().as_ref().unwrap()
         );
@@ -391,10 +421,86 @@ fn generate_block_stmt(&mut self, _span: TokenSpan, block: &Block) {
     }
     
     fn generate_expr_stmt(&mut self, _span: TokenSpan, expr: &Expr) {
+        if self.backend_writer.needs_assignment_as_stmt() {
+            return self.generate_assign_stmt(expr)
+        }
         self.generate_expr(expr);
         writeln!(self.string, ";").unwrap();
     }
     
+    // for backends without assignment expressions a chain like a = b = c is split
+    // into b = c; a = b; and a swizzled target is written one component at a time
+    fn generate_assign_stmt(&mut self, expr: &Expr) {
+        let (op, left_expr, right_expr) = match &expr.kind {
+            ExprKind::Bin {op, left_expr, right_expr, ..} if op.is_assign() => (*op, left_expr, right_expr),
+            ExprKind::PlainCall {..} | ExprKind::MethodCall {..} => {
+                self.generate_expr(expr);
+                write!(self.string, ";").unwrap();
+                return
+            }
+            _ => {
+                if *expr.ty.borrow() != Some(Ty::Void) {
+                    write!(self.string, "_ = ").unwrap();
+                }
+                self.generate_expr(expr);
+                write!(self.string, ";").unwrap();
+                return
+            }
+        };
+        let value_expr = match &right_expr.kind {
+            ExprKind::Bin {op, left_expr, ..} if op.is_assign() => {
+                self.generate_assign_stmt(right_expr);
+                write!(self.string, " ").unwrap();
+                left_expr
+            }
+            _ => right_expr
+        };
+        if let ExprKind::Field {expr: vec_expr, field_ident, ..} = &left_expr.kind {
+            let is_vector = vec_expr.ty.borrow().as_ref().unwrap().is_vector();
+            if let Some(swizzle) = Swizzle::parse(*field_ident).filter( | swizzle | is_vector && swizzle.len() > 1) {
+                let value_is_vector = value_expr.ty.borrow().as_ref().unwrap().is_vector();
+                write!(self.string, "{{let swizzle = ").unwrap();
+                self.generate_expr(value_expr);
+                write!(self.string, ";").unwrap();
+                for (index, component) in swizzle.iter().enumerate() {
+                    write!(self.string, " ").unwrap();
+                    self.generate_expr(vec_expr);
+                    write!(self.string, ".{} {} swizzle", Swizzle::from_range(*component, *component + 1), op).unwrap();
+                    if value_is_vector {
+                        write!(self.string, ".{}", Swizzle::from_range(index, index + 1)).unwrap();
+                    }
+                    write!(self.string, ";").unwrap();
+                }
+                write!(self.string, "}}").unwrap();
+                return
+            }
+        }
+        self.generate_expr(left_expr);
+        write!(self.string, " {} ", op).unwrap();
+        self.generate_expr(value_expr);
+        write!(self.string, ";").unwrap();
+    }
+    
+    // backends with immutable parameters get a mutable copy of each parameter
+    // in a scope wrapped around the body
+    pub fn generate_fn_block(&mut self, block: &Block, params: &[(Ident, ScopeSymShadow, Ty)]) {
+        if !self.backend_writer.params_are_immutable() || params.is_empty() {
+            return self.generate_block(block)
+        }
+        // the copies cannot share the scope of the parameters they shadow
+        write!(self.string, "{{\n    {{\n").unwrap();
+        for (ident, shadow, ty) in params {
+            write!(self.string, "        ").unwrap();
+            self.backend_writer.write_let_decl(self.string, &DisplayVarName(*ident, *shadow), ty);
+            write!(self.string, " = {};\n", DisplayVarName(*ident, *shadow)).unwrap();
+        }
+        write!(self.string, "        ").unwrap();
+        self.indent_level += 2;
+        self.generate_block(block);
+        self.indent_level -= 2;
+        write!(self.string, "\n    }}\n}}").unwrap();
+    }
+    
     fn generate_expr(&mut self, expr: &Expr) {
         ExprGenerator {
             closure_site_info: self.closure_site_info.clone(),
@@ -421,6 +527,16 @@ fn write_indent(&mut self) {
     // }
 }
 
+/// The params that `arg_exprs` are passed to. A draw shader method gets `self` through its hidden
+/// args, so the args of a call can be one fewer than the params.
+fn call_params<'a>(fn_def: &'a FnDef, arg_exprs: &[Expr]) -> &'a [Param] {
+    // ty_check reports a call with more args than params as a compile error, so a shader that
+    // gets this far never has one
+    let skip = fn_def.params.len().checked_sub(arg_exprs.len())
+        .unwrap_or_else( || panic!("call to `{}` has more args than params, it was not type checked", fn_def.ident));
+    &fn_def.params[skip..]
+}
+
 pub struct ExprGenerator<'a> {
     pub fn_def: Option<&'a FnDef>,
     pub closure_site_info: Option<ClosureSiteInfo<'a >>,
@@ -569,6 +685,16 @@ fn generate_cond_expr(
         expr_if_true: &Expr,
         expr_if_false: &Expr,
     ) {
+        if self.backend_writer.needs_select_for_cond_expr() {
+            write!(self.string, "select(").unwrap();
+            self.generate_expr(expr_if_false);
+            write!(self.string, ", ").unwrap();
+            self.generate_expr(expr_if_true);
+            write!(self.string, ", ").unwrap();
+            self.generate_expr(expr);
+            write!(self.string, ")").unwrap();
+            return
+        }
         write!(self.string, "(").unwrap();
         self.generate_expr(expr);
         write!(self.string, " ? ").unwrap();
@@ -714,7 +840,8 @@ fn generate_call_body(&mut self, _span: TokenSpan, fn_def: &FnDef, arg_exprs: &[
             )).unwrap();
             
             let mut sep = "";
-            for arg_expr in arg_exprs {
+            let params = call_params(fn_def, arg_exprs);
+            for (param, arg_expr) in params.iter().zip(arg_exprs) {
                 // check if the args is a closure, ifso skip it
                 match arg_expr.ty.borrow().as_ref().unwrap(){
                     Ty::ClosureDef(_)=>{
@@ -724,7 +851,7 @@ fn generate_call_body(&mut self, _span: TokenSpan, fn_def: &FnDef, arg_exprs: &[
                 }
                 
                 write!(self.string, "{}", sep).unwrap();
-                self.generate_expr(arg_expr);
+                self.generate_arg_expr(param, arg_expr);
                 sep = ", ";
             }
             // and now the closed over values
@@ -751,9 +878,10 @@ fn generate_call_body(&mut self, _span: TokenSpan, fn_def: &FnDef, arg_exprs: &[
         else {
             write!(self.string, "{}_{} (", fn_def.fn_ptr, fn_def.ident).unwrap();
             let mut sep = "";
-            for arg_expr in arg_exprs {
+            let params = call_params(fn_def, arg_exprs);
+            for (param, arg_expr) in params.iter().zip(arg_exprs) {
                 write!(self.string, "{}", sep).unwrap();
-                self.generate_expr(arg_expr);
+                self.generate_arg_expr(param, arg_expr);
                 sep = ", ";
             }
 
@@ -763,6 +891,13 @@ fn generate_call_body(&mut self, _span: TokenSpan, fn_def: &FnDef, arg_exprs: &[
         }
     }
     
+    fn generate_arg_expr(&mut self, param: &Param, arg_expr: &Expr) {
+        if param.is_inout && self.backend_writer.inout_is_pointer() {
+            write!(self.string, "&").unwrap();
+        }
+        self.generate_expr(arg_expr);
+    }
+    
     fn generate_field_expr(&mut self, _span: TokenSpan, expr: &Expr, field_ident: Ident, ty:&Ty) {
         match expr.ty.borrow().as_ref() {
             Some(Ty::DrawShader(_)) => {
@@ -925,11 +1060,16 @@ fn generate_cons_call_expr(&mut self, _span: TokenSpan, ty_lit: TyLit, arg_exprs
     fn generate_var_expr(&mut self, _span: TokenSpan, kind: &Cell<Option<VarKind >>, _ty: &Option<Ty>) {
         // ok so we have a few varkinds
         match kind.get().unwrap() {
-            VarKind::Local {ident, shadow} => {
-                write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
-            }
-            VarKind::MutLocal {ident, shadow} => {
-                write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
+            VarKind::Local {ident, shadow} | VarKind::MutLocal {ident, shadow} => {
+                let is_inout_param = self.fn_def.is_some_and( | fn_def | fn_def.params.iter().any( | param | {
+                    param.is_inout && param.ident == ident && param.shadow.get() == Some(shadow)
+                }));
+                if is_inout_param && self.backend_writer.inout_is_pointer() {
+                    write!(self.string, "(*{})", DisplayVarName(ident, shadow)).unwrap();
+                }
+                else {
+                    write!(self.string, "{}", DisplayVarName(ident, shadow)).unwrap();
+                }
             }
             VarKind::LiveValue(value_node_ptr) => {
                 // this is a live value.. also prefix needed
@@ -963,15 +1103,11 @@ pub struct FnDefGenerator<'a> {
 impl<'a> FnDefGenerator<'a> {
     pub fn generate_fn_def(&mut self) {
         
-        self.backend_writer.write_var_decl(
+        self.backend_writer.write_fn_def_start(
             &mut self.string,
-            "",
-            false,
-            false,
             &DisplayFnName(self.fn_def.fn_ptr, self.fn_def.ident), // here we must expand IdentPath to something
             self.fn_def.return_ty.borrow().as_ref().unwrap()
         );
-        write!(self.string, "(").unwrap();
         let mut sep = "";
         for param in &self.fn_def.params {
             if !param.shadow.get().is_none() {
@@ -988,13 +1124,14 @@ pub fn generate_fn_def(&mut self) {
             }
         }
         self.backend_writer.write_fn_def_hidden_params(self.string, self.fn_def.hidden_args.borrow().as_ref().unwrap(), sep);
-        write!(self.string, ") ").unwrap();
-        self.generate_block(&self.fn_def.block);
+        self.backend_writer.write_fn_def_end(self.string, self.fn_def.return_ty.borrow().as_ref().unwrap());
+        let params = by_value_params(&self.fn_def.params);
+        self.generate_block(&self.fn_def.block, &params);
         writeln!(self.string).unwrap();
         //self.visited.insert(self.decl.ident_path);
     }
     
-    fn generate_block(&mut self, block: &Block) {
+    fn generate_block(&mut self, block: &Block, params: &[(Ident, ScopeSymShadow, Ty)]) {
         BlockGenerator {
             shader_registry: self.shader_registry,
             closure_site_info: None,
@@ -1005,10 +1142,16 @@ fn generate_block(&mut self, block: &Block) {
             indent_level: 0,
             string: self.string,
         }
-        .generate_block(block)
+        .generate_fn_block(block, params)
     }
 }
 
+fn by_value_params(params: &[Param]) -> Vec<(Ident, ScopeSymShadow, Ty)> {
+    params.iter().filter( | param | !param.is_inout).filter_map( | param | {
+        Some((param.ident, param.shadow.get() ?, param.ty_expr.ty.borrow().clone() ?))
+    }).filter( | (_, _, ty) | !matches!(ty, Ty::DrawShader(_) | Ty::ClosureDef(_) | Ty::ClosureDecl)).collect()
+}
+
 pub struct FnDefWithClosureArgsGenerator<'a> {
     pub closure_site_info: ClosureSiteInfo<'a>,
     pub fn_def: &'a FnDef,
@@ -1080,11 +1223,8 @@ pub fn generate_fn_def_with_all_closures(
     
     pub fn generate_fn_def_with_closure_args(&mut self) {
         
-        self.backend_writer.write_var_decl(
+        self.backend_writer.write_fn_def_start(
             &mut self.string,
-            "",
-            false,
-            false,
             &DisplayFnNameWithClosureArgs(
                 self.closure_site_info.site_index,
                 self.call_def.fn_ptr,
@@ -1092,7 +1232,6 @@ pub fn generate_fn_def_with_closure_args(&mut self) {
             ), // here we must expand IdentPath to something
             self.fn_def.return_ty.borrow().as_ref().unwrap()
         );
-        write!(self.string, "(").unwrap();
         let mut sep = "";
         for param in &self.fn_def.params {
             if !param.shadow.get().is_none() {
@@ -1128,17 +1267,18 @@ pub fn generate_fn_def_with_closure_args(&mut self) {
         merged_hidden_args.extend(self.call_def.hidden_args.borrow().as_ref().unwrap().iter().cloned());
         self.backend_writer.write_fn_def_hidden_params(self.string, &merged_hidden_args, sep);
         
-        write!(self.string, ") ").unwrap();
+        self.backend_writer.write_fn_def_end(self.string, self.fn_def.return_ty.borrow().as_ref().unwrap());
         // alright so here the block is generated.. however
         // we need to know the names and the closed-over-args passthrough
-        self.generate_block(&self.fn_def.block);
+        let params = by_value_params(&self.fn_def.params);
+        self.generate_block(&self.fn_def.block, &params);
         
         
         writeln!(self.string).unwrap();
         //self.visited.insert(self.decl.ident_path);
     }
     
-    fn generate_block(&mut self, block: &Block) {
+    fn generate_block(&mut self, block: &Block, params: &[(Ident, ScopeSymShadow, Ty)]) {
         BlockGenerator {
             shader_registry: self.shader_registry,
             closure_site_info: Some(self.closure_site_info.clone()),
@@ -1149,7 +1289,7 @@ fn generate_block(&mut self, block: &Block) {
             indent_level: 0,
             string: self.string,
         }
-        .generate_block(block)
+        .generate_fn_block(block, params)
     }
 }
 
@@ -1170,18 +1310,15 @@ pub fn generate_fn_def(&mut self) {
         let fn_param = &self.fn_def.params[self.closure_site_arg.param_index];
         
         let mut sep = "";
+        let mut by_value_params = Vec::new();
         
-        if let TyExprKind::ClosureDecl {params, return_ty, ..} = &fn_param.ty_expr.kind {
+        let return_ty = if let TyExprKind::ClosureDecl {params, return_ty, ..} = &fn_param.ty_expr.kind {
             
-            self.backend_writer.write_var_decl(
+            self.backend_writer.write_fn_def_start(
                 &mut self.string,
-                "",
-                false,
-                false,
                 &DisplayClosureName(self.call_def.fn_ptr, self.closure_site_arg.closure_def_index), // here we must expand IdentPath to something
                 return_ty.borrow().as_ref().unwrap(),
             );
-            write!(self.string, "(").unwrap();
             
             // ok we have now params and names
             for (param_index, param) in params.iter().enumerate() {
@@ -1198,11 +1335,15 @@ pub fn generate_fn_def(&mut self) {
                 ) {
                     sep = ", ";
                 }
+                if !param.is_inout {
+                    by_value_params.push((closure_param.ident, shadow, param.ty_expr.ty.borrow().clone().unwrap()));
+                }
             }
+            return_ty.borrow().clone().unwrap()
         }
         else {
             panic!()
-        }
+        };
         
         for sym in self.closure_def.closed_over_syms.borrow().as_ref().unwrap() {
             if self.backend_writer.write_var_decl(
@@ -1215,6 +1356,9 @@ pub fn generate_fn_def(&mut self) {
             ) {
                 sep = ", ";
             }
+            if !matches!(sym.ty, Ty::DrawShader(_) | Ty::ClosureDef(_) | Ty::ClosureDecl) {
+                by_value_params.push((sym.ident, sym.shadow, sym.ty.clone()));
+            }
         } 
 
         let mut merged_hidden_args = BTreeSet::new();
@@ -1222,24 +1366,25 @@ pub fn generate_fn_def(&mut self) {
         merged_hidden_args.extend(self.call_def.hidden_args.borrow().as_ref().unwrap().iter().cloned());
         self.backend_writer.write_fn_def_hidden_params(self.string, &merged_hidden_args, sep);
         
-        writeln!(self.string, ") {{").unwrap();
+        self.backend_writer.write_fn_def_end(self.string, &return_ty);
         
         match &self.closure_def.kind {
             ClosureDefKind::Expr(expr) => {
+                writeln!(self.string, "{{").unwrap();
                 write!(self.string, "    return ").unwrap();
                 self.generate_expr(expr);
                 writeln!(self.string, ";").unwrap();
                 writeln!(self.string, "}}").unwrap();
             }
             ClosureDefKind::Block(block) => {
-                self.generate_block(block);
+                self.generate_block(block, &by_value_params);
                 writeln!(self.string).unwrap();
             }
         }
         //self.visited.insert(self.decl.ident_path);
     }
     
-    fn generate_block(&mut self, block: &Block) {
+    fn generate_block(&mut self, block: &Block, params: &[(Ident, ScopeSymShadow, Ty)]) {
         BlockGenerator {
             shader_registry: self.shader_registry,
             closure_site_info: None,
@@ -1250,7 +1395,7 @@ fn generate_block(&mut self, block: &Block) {
             indent_level: 0,
             string: self.string,
         }
-        .generate_block(block)
+        .generate_fn_block(block, params)
     }
     
     
@@ -0,0 +1,788 @@
+use {
+    std::{
+        cell::RefCell,
+        fmt::Write,
+        fmt,
+        collections::{BTreeMap, BTreeSet}
+    },
+    crate::{
+        makepad_live_id::*,
+        shader_ast::*,
+        generate::*,
+        shader_registry::ShaderRegistry,
+        swizzle::Swizzle,
+    }
+};
+
+/// WGSL source for a draw shader, with `vertex_main` and `pixel_main` as entry points.
+///
+/// Resources are bound as follows:
+/// - group 0 holds the uniforms as `array<vec4<f32>, N>` tables of packed floats:
+///   binding 0 is `const_table`, binding 1 is `live_table` and binding 2 + i is the
+///   `{block}_table` of the i-th entry of `fields_as_uniform_blocks`
+/// - group 1 holds the textures: binding 0 is `default_sampler` and binding 1 + i
+///   is the i-th texture field
+///
+/// Geometry and instance attributes are packed into `vec4<f32>` locations the same
+/// way as the glsl backend packs them, geometries first.
+pub struct WgslGeneratedShader {
+    pub wgsl: String,
+    pub fields_as_uniform_blocks: BTreeMap<Ident, Vec<(usize, Ident) >>
+}
+
+pub fn generate_shader(draw_shader_def: &DrawShaderDef, const_table: &DrawShaderConstTable, shader_registry: &ShaderRegistry) -> WgslGeneratedShader {
+    let mut string = String::new();
+    let fields_as_uniform_blocks = draw_shader_def.fields_as_uniform_blocks();
+    let backend_writer = WgslBackendWriter {
+        shader_registry,
+        polyfills: RefCell::new(BTreeMap::new())
+    };
+    DrawShaderGenerator {
+        draw_shader_def,
+        shader_registry,
+        const_table,
+        string: &mut string,
+        fields_as_uniform_blocks: &fields_as_uniform_blocks,
+        backend_writer: &backend_writer
+    }
+    .generate_shader();
+    // builtins without a wgsl equivalent are emitted as they are encountered
+    for polyfill in backend_writer.polyfills.borrow().values() {
+        string.push_str(polyfill);
+    }
+    WgslGeneratedShader {
+        wgsl: string,
+        fields_as_uniform_blocks
+    }
+}
+
+struct DrawShaderGenerator<'a> {
+    draw_shader_def: &'a DrawShaderDef,
+    shader_registry: &'a ShaderRegistry,
+    string: &'a mut String,
+    fields_as_uniform_blocks: &'a BTreeMap<Ident, Vec<(usize, Ident) >>,
+    backend_writer: &'a WgslBackendWriter<'a>,
+    const_table: &'a DrawShaderConstTable
+}
+
+impl<'a> DrawShaderGenerator<'a> {
+    fn generate_shader(&mut self) {
+        let mut all_constructor_fns = BTreeSet::new();
+        let mut uses_derivatives = false;
+        for fn_iter in self.draw_shader_def.all_fns.borrow().iter() {
+            let fn_def = self.shader_registry.all_fns.get(fn_iter).unwrap();
+            all_constructor_fns.extend(fn_def.constructor_fn_deps.borrow().as_ref().unwrap().iter().cloned());
+            let builtin_deps = fn_def.builtin_deps.borrow();
+            let builtin_deps = builtin_deps.as_ref().unwrap();
+            if builtin_deps.contains(&Ident(live_id!(dFdx))) || builtin_deps.contains(&Ident(live_id!(dFdy))) {
+                uses_derivatives = true;
+            }
+        }
+        // glsl allows derivatives in non-uniform control flow, so we do too
+        if uses_derivatives {
+            writeln!(self.string, "diagnostic(off, derivative_uniformity);").unwrap();
+        }
+
+        self.generate_uniform_decls();
+        self.generate_texture_decls();
+        self.generate_private_decls();
+        self.generate_struct_defs();
+        self.generate_vertex_input_struct();
+        self.generate_varying_struct();
+
+        for (ty_lit, ref param_tys) in all_constructor_fns {
+            generate_cons_fn(self.backend_writer, self.string, ty_lit, param_tys);
+        }
+
+        let all_fns = self.draw_shader_def.all_fns.borrow();
+        for fn_iter in all_fns.iter().rev() {
+            let const_table_offset = self.const_table.offsets.get(fn_iter).cloned();
+            let fn_def = self.shader_registry.all_fns.get(fn_iter).unwrap();
+            if fn_def.has_closure_args() {
+                for call_iter in all_fns.iter().rev() {
+                    // any function that depends on us, will have the closures we need
+                    let call_def = self.shader_registry.all_fns.get(call_iter).unwrap();
+                    if call_def.callees.borrow().as_ref().unwrap().contains(fn_iter) {
+                        FnDefWithClosureArgsGenerator::generate_fn_def_with_all_closures(
+                            self.string,
+                            self.shader_registry,
+                            fn_def,
+                            call_def,
+                            self.backend_writer,
+                            const_table_offset
+                        );
+                    }
+                }
+                continue
+            }
+            FnDefGenerator {
+                fn_def,
+                const_table_offset,
+                shader_registry: self.shader_registry,
+                backend_writer: self.backend_writer,
+                string: self.string,
+            }
+            .generate_fn_def()
+        }
+        self.generate_vertex_main();
+        self.generate_pixel_main();
+    }
+
+    fn generate_uniform_decls(&mut self) {
+        if !self.const_table.table.is_empty() {
+            writeln!(
+                self.string,
+                "@group(0) @binding(0) var<uniform> const_table: array<vec4<f32>, {}>;",
+                vec4_count(self.const_table.table.len())
+            ).unwrap();
+        }
+        let live_slots = self.calc_live_slots();
+        if live_slots > 0 {
+            writeln!(
+                self.string,
+                "@group(0) @binding(1) var<uniform> live_table: array<vec4<f32>, {}>;",
+                vec4_count(live_slots)
+            ).unwrap();
+        }
+        for (binding, (ident, vec)) in self.fields_as_uniform_blocks.iter().enumerate() {
+            let mut slots = 0;
+            for (index, _item) in vec {
+                slots += self.draw_shader_def.fields[*index].ty_expr.ty.borrow().as_ref().unwrap().slots();
+            }
+            writeln!(
+                self.string,
+                "@group(0) @binding({}) var<uniform> {}_table: array<vec4<f32>, {}>;",
+                binding + 2,
+                ident,
+                vec4_count(slots)
+            ).unwrap();
+        }
+    }
+
+    fn generate_texture_decls(&mut self) {
+        let mut binding = 1;
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Texture {..} = field.kind {
+                assert_ne!(*field.ty_expr.ty.borrow().as_ref().unwrap(), Ty::TextureOES, "TextureOES is only available on Android");
+                if binding == 1 {
+                    writeln!(self.string, "@group(1) @binding(0) var default_sampler: sampler;").unwrap();
+                }
+                write!(self.string, "@group(1) @binding({}) var ", binding).unwrap();
+                self.write_var_decl(&DisplayDsIdent(field.ident), field.ty_expr.ty.borrow().as_ref().unwrap());
+                writeln!(self.string, ";").unwrap();
+                binding += 1;
+            }
+        }
+    }
+
+    // the entry points unpack everything into module scope so the shader functions
+    // can access fields and live values without hidden arguments
+    fn generate_private_decls(&mut self) {
+        for (live_ref, ty) in self.draw_shader_def.all_live_refs.borrow().iter() {
+            write!(self.string, "var<private> ").unwrap();
+            self.write_var_decl(live_ref, ty);
+            writeln!(self.string, ";").unwrap();
+        }
+        for field in &self.draw_shader_def.fields {
+            match field.kind {
+                DrawShaderFieldKind::Texture {..} => (),
+                _ if field.ident == Ident(LiveId(0)) => (),
+                _ => {
+                    write!(self.string, "var<private> ").unwrap();
+                    self.write_var_decl(&DisplayDsIdent(field.ident), field.ty_expr.ty.borrow().as_ref().unwrap());
+                    writeln!(self.string, ";").unwrap();
+                }
+            }
+        }
+    }
+
+    fn generate_struct_defs(&mut self) {
+        // we have all the structs already from analyse
+        for struct_ptr in self.draw_shader_def.all_structs.borrow().iter().rev() {
+            let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+            writeln!(self.string, "struct {} {{", struct_ptr).unwrap();
+            for field in &struct_def.fields {
+                write!(self.string, "    ").unwrap();
+                self.write_var_decl(&DisplayStructField(field.ident), field.ty_expr.ty.borrow().as_ref().unwrap());
+                writeln!(self.string, ",").unwrap();
+            }
+            writeln!(self.string, "}}").unwrap();
+        }
+    }
+
+    fn generate_vertex_input_struct(&mut self) {
+        let packed_geometries_slots = self.compute_packed_geometries_slots();
+        let packed_instances_slots = self.compute_packed_instances_slots();
+        if packed_geometries_slots + packed_instances_slots == 0 {
+            return
+        }
+        writeln!(self.string, "struct VertexInput {{").unwrap();
+        let location = self.generate_packed_var_decls("packed_geometry", packed_geometries_slots, 0);
+        self.generate_packed_var_decls("packed_instance", packed_instances_slots, location);
+        writeln!(self.string, "}}").unwrap();
+    }
+
+    fn generate_varying_struct(&mut self) {
+        writeln!(self.string, "struct Varyings {{").unwrap();
+        writeln!(self.string, "    @builtin(position) position: vec4<f32>,").unwrap();
+        let packed_varyings_slots = self.compute_packed_varyings_slots();
+        self.generate_packed_var_decls("packed_varying", packed_varyings_slots, 0);
+        writeln!(self.string, "}}").unwrap();
+    }
+
+    fn generate_packed_var_decls(&mut self, packed_var_name: &str, mut packed_vars_size: usize, mut location: usize) -> usize {
+        let mut packed_var_index = 0;
+        while packed_vars_size > 0 {
+            let packed_var_size = packed_vars_size.min(4);
+            writeln!(
+                self.string,
+                "    @location({}) {}_{}: {},",
+                location,
+                packed_var_name,
+                packed_var_index,
+                match packed_var_size {
+                    1 => "f32",
+                    2 => "vec2<f32>",
+                    3 => "vec3<f32>",
+                    _ => "vec4<f32>",
+                }
+            ).unwrap();
+            packed_vars_size -= packed_var_size;
+            packed_var_index += 1;
+            location += 1;
+        }
+        location
+    }
+
+    fn generate_vertex_main(&mut self) {
+        let packed_geometries_slots = self.compute_packed_geometries_slots();
+        let packed_instances_slots = self.compute_packed_instances_slots();
+        let packed_varyings_slots = self.compute_packed_varyings_slots();
+
+        writeln!(self.string, "@vertex").unwrap();
+        if packed_geometries_slots + packed_instances_slots > 0 {
+            writeln!(self.string, "fn vertex_main(input: VertexInput) -> Varyings {{").unwrap();
+        }
+        else {
+            writeln!(self.string, "fn vertex_main() -> Varyings {{").unwrap();
+        }
+        self.generate_uniform_block_unpack();
+        self.generate_live_unpack();
+
+        let mut geometry_unpacker = PackedSlots::new("input.packed_geometry", packed_geometries_slots);
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Geometry {..} = field.kind {
+                geometry_unpacker.unpack_var(self.string, field.ident, field.ty_expr.ty.borrow().as_ref().unwrap());
+            }
+        }
+        let mut instance_unpacker = PackedSlots::new("input.packed_instance", packed_instances_slots);
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Instance {..} = field.kind {
+                instance_unpacker.unpack_var(self.string, field.ident, field.ty_expr.ty.borrow().as_ref().unwrap());
+            }
+        }
+
+        let vertex_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, Ident(live_id!(vertex))).unwrap();
+        writeln!(self.string, "    var varyings: Varyings;").unwrap();
+        writeln!(self.string, "    varyings.position = {}();", DisplayFnName(vertex_def.fn_ptr, vertex_def.ident)).unwrap();
+
+        let mut varying_packer = PackedSlots::new("varyings.packed_varying", packed_varyings_slots);
+        for field in &self.draw_shader_def.fields {
+            if self.is_varying(field) {
+                varying_packer.pack_var(self.string, field.ident, field.ty_expr.ty.borrow().as_ref().unwrap());
+            }
+        }
+        writeln!(self.string, "    return varyings;").unwrap();
+        writeln!(self.string, "}}").unwrap();
+    }
+
+    fn generate_pixel_main(&mut self) {
+        let packed_varyings_slots = self.compute_packed_varyings_slots();
+
+        writeln!(self.string, "@fragment").unwrap();
+        writeln!(self.string, "fn pixel_main(varyings: Varyings) -> @location(0) vec4<f32> {{").unwrap();
+        self.generate_uniform_block_unpack();
+        self.generate_live_unpack();
+
+        let mut varying_unpacker = PackedSlots::new("varyings.packed_varying", packed_varyings_slots);
+        for field in &self.draw_shader_def.fields {
+            if self.is_varying(field) {
+                varying_unpacker.unpack_var(self.string, field.ident, field.ty_expr.ty.borrow().as_ref().unwrap());
+            }
+        }
+
+        let pixel_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, Ident(live_id!(pixel))).unwrap();
+        writeln!(self.string, "    return {}();", DisplayFnName(pixel_def.fn_ptr, pixel_def.ident)).unwrap();
+        writeln!(self.string, "}}").unwrap();
+    }
+
+    fn is_varying(&self, field: &DrawShaderFieldDef) -> bool {
+        match &field.kind {
+            DrawShaderFieldKind::Geometry {is_used_in_pixel_shader, ..} => is_used_in_pixel_shader.get(),
+            DrawShaderFieldKind::Instance {is_used_in_pixel_shader, ..} => is_used_in_pixel_shader.get(),
+            DrawShaderFieldKind::Varying {..} => true,
+            _ => false
+        }
+    }
+
+    fn generate_uniform_block_unpack(&mut self) {
+        for (ident, vec) in self.fields_as_uniform_blocks {
+            let table = format!("{}_table", ident);
+            let mut slots = 0;
+            for (index, _item) in vec {
+                let field = &self.draw_shader_def.fields[*index];
+                let ty = field.ty_expr.ty.borrow();
+                let ty = ty.as_ref().unwrap();
+                write!(self.string, "    {} = ", DisplayDsIdent(field.ident)).unwrap();
+                self.write_uniform_ty_unpack(ty, &table, slots);
+                writeln!(self.string, ";").unwrap();
+                slots += ty.slots();
+            }
+        }
+    }
+
+    fn generate_live_unpack(&mut self) {
+        let mut slots = 0;
+        for (live_ref, ty) in self.draw_shader_def.all_live_refs.borrow().iter() {
+            write!(self.string, "    {} = ", live_ref).unwrap();
+            self.write_uniform_ty_unpack(ty, "live_table", slots);
+            writeln!(self.string, ";").unwrap();
+            slots += ty.slots();
+        }
+    }
+
+    fn write_uniform_ty_unpack(&mut self, ty: &Ty, table: &str, slot: usize) {
+        let (ty_lit, unpack): (Option<TyLit>, fn(&mut String, &DisplayTableSlot)) = match ty {
+            Ty::Bool => (None, | string, slot | write!(string, "{} > 0.5", slot).unwrap()),
+            Ty::Int => (None, | string, slot | write!(string, "i32({})", slot).unwrap()),
+            Ty::Float | Ty::Enum(_) => (None, | string, slot | write!(string, "{}", slot).unwrap()),
+            Ty::Bvec2 | Ty::Bvec3 | Ty::Bvec4 => (ty.maybe_ty_lit(), | string, slot | write!(string, "{} > 0.5", slot).unwrap()),
+            Ty::Ivec2 | Ty::Ivec3 | Ty::Ivec4 => (ty.maybe_ty_lit(), | string, slot | write!(string, "i32({})", slot).unwrap()),
+            Ty::Vec2 | Ty::Vec3 | Ty::Vec4 | Ty::Mat2 | Ty::Mat3 | Ty::Mat4 => (ty.maybe_ty_lit(), | string, slot | write!(string, "{}", slot).unwrap()),
+            _ => panic!("unexpected as initializeable type {:?}", ty),
+        };
+        if let Some(ty_lit) = ty_lit {
+            self.backend_writer.write_ty_lit(self.string, ty_lit);
+            write!(self.string, "(").unwrap();
+        }
+        for index in 0..ty.slots() {
+            if index != 0 {
+                write!(self.string, ", ").unwrap();
+            }
+            unpack(self.string, &DisplayTableSlot(table, slot + index));
+        }
+        if ty_lit.is_some() {
+            write!(self.string, ")").unwrap();
+        }
+    }
+
+    fn calc_live_slots(&self) -> usize {
+        let mut slots = 0;
+        for (_, ty) in self.draw_shader_def.all_live_refs.borrow().iter() {
+            slots += ty.slots();
+        }
+        slots
+    }
+
+    fn compute_packed_geometries_slots(&self) -> usize {
+        let mut packed_attributes_size = 0;
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Geometry {..} = field.kind {
+                packed_attributes_size += field.ty_expr.ty.borrow().as_ref().unwrap().slots();
+            }
+        }
+        packed_attributes_size
+    }
+
+    fn compute_packed_instances_slots(&self) -> usize {
+        let mut packed_instances_size = 0;
+        for field in &self.draw_shader_def.fields {
+            if let DrawShaderFieldKind::Instance {..} = field.kind {
+                packed_instances_size += field.ty_expr.ty.borrow().as_ref().unwrap().slots();
+            }
+        }
+        packed_instances_size
+    }
+
+    fn compute_packed_varyings_slots(&self) -> usize {
+        let mut packed_varyings_size = 0;
+        for field in &self.draw_shader_def.fields {
+            if self.is_varying(field) {
+                packed_varyings_size += field.ty_expr.ty.borrow().as_ref().unwrap().slots();
+            }
+        }
+        packed_varyings_size
+    }
+
+    fn write_var_decl(&mut self, ident: &dyn fmt::Display, ty: &Ty) {
+        self.backend_writer.write_var_decl(self.string, "", false, false, ident, ty);
+    }
+}
+
+fn vec4_count(slots: usize) -> usize {
+    slots.div_ceil(4)
+}
+
+struct DisplayTableSlot<'a>(&'a str, usize);
+impl fmt::Display for DisplayTableSlot<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}[{}].{}", self.0, self.1 >> 2, ["x", "y", "z", "w"][self.1 & 3])
+    }
+}
+
+// wgsl cannot assign to a multi component swizzle, so the packed
+// attributes are copied one component at a time
+struct PackedSlots<'a> {
+    packed_var_name: &'a str,
+    packed_vars_size: usize,
+    packed_var_index: usize,
+    packed_var_size: usize,
+    packed_var_offset: usize,
+}
+
+impl<'a> PackedSlots<'a> {
+    fn new(packed_var_name: &'a str, packed_vars_size: usize) -> Self {
+        Self {
+            packed_var_name,
+            packed_vars_size,
+            packed_var_index: 0,
+            packed_var_size: packed_vars_size.min(4),
+            packed_var_offset: 0,
+        }
+    }
+
+    fn next_slot(&mut self) -> String {
+        let mut slot = format!("{}_{}", self.packed_var_name, self.packed_var_index);
+        if self.packed_var_size > 1 {
+            write!(slot, ".{}", Swizzle::from_range(self.packed_var_offset, self.packed_var_offset + 1)).unwrap();
+        }
+        self.packed_var_offset += 1;
+        if self.packed_var_offset == self.packed_var_size {
+            self.packed_vars_size -= self.packed_var_size;
+            self.packed_var_index += 1;
+            self.packed_var_size = self.packed_vars_size.min(4);
+            self.packed_var_offset = 0;
+        }
+        slot
+    }
+
+    fn var_component(ident: Ident, ty: &Ty, index: usize) -> String {
+        match ty {
+            Ty::Float | Ty::Enum(_) => format!("{}", DisplayDsIdent(ident)),
+            Ty::Vec2 | Ty::Vec3 | Ty::Vec4 => format!("{}.{}", DisplayDsIdent(ident), Swizzle::from_range(index, index + 1)),
+            Ty::Mat2 => format!("{}[{}][{}]", DisplayDsIdent(ident), index / 2, index % 2),
+            Ty::Mat3 => format!("{}[{}][{}]", DisplayDsIdent(ident), index / 3, index % 3),
+            Ty::Mat4 => format!("{}[{}][{}]", DisplayDsIdent(ident), index / 4, index % 4),
+            _ => panic!("unsupported type for packed attributes {:?}", ty)
+        }
+    }
+
+    fn pack_var(&mut self, string: &mut String, ident: Ident, ty: &Ty) {
+        for index in 0..ty.slots() {
+            let slot = self.next_slot();
+            writeln!(string, "    {} = {};", slot, Self::var_component(ident, ty, index)).unwrap();
+        }
+    }
+
+    fn unpack_var(&mut self, string: &mut String, ident: Ident, ty: &Ty) {
+        for index in 0..ty.slots() {
+            let slot = self.next_slot();
+            // padding only takes up space
+            if ident != Ident(LiveId(0)) {
+                writeln!(string, "    {} = {};", Self::var_component(ident, ty, index), slot).unwrap();
+            }
+        }
+    }
+}
+
+struct WgslBackendWriter<'a> {
+    pub shader_registry: &'a ShaderRegistry,
+    pub polyfills: RefCell<BTreeMap<String, String >>,
+}
+
+impl<'a> WgslBackendWriter<'a> {
+    fn write_ty(&self, string: &mut String, ty: &Ty) {
+        match ty {
+            Ty::Void => panic!("void is not a wgsl type"),
+            Ty::Array {elem_ty, len} => {
+                write!(string, "array<").unwrap();
+                self.write_ty(string, elem_ty);
+                write!(string, ", {}>", len).unwrap();
+            }
+            Ty::Struct(struct_ptr) => {
+                write!(string, "{}", struct_ptr).unwrap();
+            }
+            Ty::Enum(_) => {
+                write!(string, "f32").unwrap();
+            }
+            Ty::Texture2D => self.write_ty_lit(string, TyLit::Texture2D),
+            Ty::TextureOES => self.write_ty_lit(string, TyLit::TextureOES),
+            Ty::DrawShader(_) | Ty::ClosureDef(_) | Ty::ClosureDecl => panic!("{:?} is not a wgsl type", ty),
+            _ => self.write_ty_lit(string, ty.maybe_ty_lit().unwrap())
+        }
+    }
+
+    fn polyfill_body(&self, ident: Ident, arg_tys: &[Ty], return_ty: &Ty) -> Option<String> {
+        let ty_name = | ty: &Ty | {
+            let mut string = String::new();
+            self.write_ty(&mut string, ty);
+            string
+        };
+        let body = match (ident, arg_tys) {
+            (Ident(live_id!(mod)), _) => "return a - b * floor(a / b);".to_string(),
+            (Ident(live_id!(max)), [_, Ty::Float]) if return_ty.is_vector() => {
+                format!("return max(a, {}(b));", ty_name(return_ty))
+            }
+            (Ident(live_id!(min)), [_, Ty::Float]) if return_ty.is_vector() => {
+                format!("return min(a, {}(b));", ty_name(return_ty))
+            }
+            (Ident(live_id!(clamp)), [_, Ty::Float, Ty::Float]) if return_ty.is_vector() => {
+                format!("return clamp(a, {0}(b), {0}(c));", ty_name(return_ty))
+            }
+            (Ident(live_id!(step)), [Ty::Float, _]) if return_ty.is_vector() => {
+                format!("return step({}(a), b);", ty_name(return_ty))
+            }
+            (Ident(live_id!(smoothstep)), [Ty::Float, Ty::Float, _]) if return_ty.is_vector() => {
+                format!("return smoothstep({0}(a), {0}(b), c);", ty_name(return_ty))
+            }
+            (Ident(live_id!(dot)), [Ty::Float, Ty::Float]) => "return a * b;".to_string(),
+            (Ident(live_id!(normalize)), [Ty::Float]) => "return sign(a);".to_string(),
+            (Ident(live_id!(reflect)), [Ty::Float, Ty::Float]) => "return a - 2.0 * b * a * b;".to_string(),
+            (Ident(live_id!(refract)), [Ty::Float, Ty::Float, Ty::Float]) => {
+                "let k = 1.0 - c * c * (1.0 - b * a * b * a);\n    return select(c * a - (c * b * a + sqrt(k)) * b, 0.0, k < 0.0);".to_string()
+            }
+            (Ident(live_id!(faceforward)), [Ty::Float, Ty::Float, Ty::Float]) => "return select(-a, a, c * b < 0.0);".to_string(),
+            (Ident(live_id!(equal)), _) => "return a == b;".to_string(),
+            (Ident(live_id!(notEqual)), _) => "return a != b;".to_string(),
+            (Ident(live_id!(lessThan)), _) => "return a < b;".to_string(),
+            (Ident(live_id!(lessThanEqual)), _) => "return a <= b;".to_string(),
+            (Ident(live_id!(greaterThan)), _) => "return a > b;".to_string(),
+            (Ident(live_id!(greaterThanEqual)), _) => "return a >= b;".to_string(),
+            (Ident(live_id!(not)), _) => "return !a;".to_string(),
+            (Ident(live_id!(matrixCompMult)), _) => {
+                let columns = match return_ty {
+                    Ty::Mat2 => 2,
+                    Ty::Mat3 => 3,
+                    _ => 4
+                };
+                let columns: Vec<String> = (0..columns).map( | i | format!("a[{0}] * b[{0}]", i)).collect();
+                format!("return {}({});", ty_name(return_ty), columns.join(", "))
+            }
+            (Ident(live_id!(inverse)), _) => {
+                let mut body = String::new();
+                for c in 0..4 {
+                    for r in 0..4 {
+                        writeln!(body, "let m{0}{1} = a[{0}][{1}];", c, r).unwrap();
+                    }
+                }
+                body.push_str(concat!(
+                    "let b00 = m00 * m11 - m01 * m10;\n",
+                    "let b01 = m00 * m12 - m02 * m10;\n",
+                    "let b02 = m00 * m13 - m03 * m10;\n",
+                    "let b03 = m01 * m12 - m02 * m11;\n",
+                    "let b04 = m01 * m13 - m03 * m11;\n",
+                    "let b05 = m02 * m13 - m03 * m12;\n",
+                    "let b06 = m20 * m31 - m21 * m30;\n",
+                    "let b07 = m20 * m32 - m22 * m30;\n",
+                    "let b08 = m20 * m33 - m23 * m30;\n",
+                    "let b09 = m21 * m32 - m22 * m31;\n",
+                    "let b10 = m21 * m33 - m23 * m31;\n",
+                    "let b11 = m22 * m33 - m23 * m32;\n",
+                    "let det = b00 * b11 - b01 * b10 + b02 * b09 + b03 * b08 - b04 * b07 + b05 * b06;\n",
+                    "return mat4x4<f32>(\n",
+                    "    m11 * b11 - m12 * b10 + m13 * b09, m02 * b10 - m01 * b11 - m03 * b09,\n",
+                    "    m31 * b05 - m32 * b04 + m33 * b03, m22 * b04 - m21 * b05 - m23 * b03,\n",
+                    "    m12 * b08 - m10 * b11 - m13 * b07, m00 * b11 - m02 * b08 + m03 * b07,\n",
+                    "    m32 * b02 - m30 * b05 - m33 * b01, m20 * b05 - m22 * b02 + m23 * b01,\n",
+                    "    m10 * b10 - m11 * b08 + m13 * b06, m01 * b08 - m00 * b10 - m03 * b06,\n",
+                    "    m30 * b04 - m31 * b02 + m33 * b00, m21 * b02 - m20 * b04 - m23 * b00,\n",
+                    "    m11 * b07 - m10 * b09 - m12 * b06, m00 * b09 - m01 * b07 + m02 * b06,\n",
+                    "    m31 * b01 - m30 * b03 - m32 * b00, m20 * b03 - m21 * b01 + m22 * b00\n",
+                    ") * (1.0 / det);"
+                ));
+                body.replace('\n', "\n    ")
+            }
+            // render targets have the same orientation as textures in wgpu, so sample2d_rt needs no flip
+            (Ident(live_id!(sample2d)), _) | (Ident(live_id!(sample2d_rt)), _) => {
+                "return textureSampleLevel(a, default_sampler, b, 0.0);".to_string()
+            }
+            _ => return None
+        };
+        Some(body)
+    }
+}
+
+impl<'a> BackendWriter for WgslBackendWriter<'a> {
+    fn get_struct_cons_type(&self) -> StructConsType {
+        StructConsType::Paren
+    }
+
+    fn needs_mul_fn_for_matrix_multiplication(&self) -> bool {
+        false
+    }
+
+    fn needs_unpack_for_matrix_multiplication(&self) -> bool {
+        false
+    }
+
+    fn enum_is_float(&self) -> bool {
+        true
+    }
+
+    fn const_table_is_vec4(&self) -> bool {
+        true
+    }
+
+    fn needs_select_for_cond_expr(&self) -> bool {
+        true
+    }
+
+    fn needs_assignment_as_stmt(&self) -> bool {
+        true
+    }
+
+    fn inout_is_pointer(&self) -> bool {
+        true
+    }
+
+    fn params_are_immutable(&self) -> bool {
+        true
+    }
+
+    fn use_cons_fn(&self, what: &str) -> bool {
+        // wgsl has no matrix resizing or scalar to matrix constructors
+        matches!(
+            what,
+            "consfn_mat4_mat3" | "consfn_mat4_mat2" |
+            "consfn_mat3_mat4" | "consfn_mat3_mat2" |
+            "consfn_mat2_mat4" | "consfn_mat2_mat3" |
+            "consfn_mat4_float" | "consfn_mat3_float" | "consfn_mat2_float"
+        )
+    }
+
+    fn write_var_decl(
+        &self,
+        string: &mut String,
+        sep: &'static str,
+        is_inout: bool,
+        _is_packed: bool,
+        ident: &dyn fmt::Display,
+        ty: &Ty,
+    ) -> bool {
+        if let Ty::DrawShader(_) | Ty::ClosureDef(_) | Ty::ClosureDecl = ty {
+            return false
+        }
+        write!(string, "{}{}: ", sep, ident).unwrap();
+        if is_inout {
+            write!(string, "ptr<function, ").unwrap();
+            self.write_ty(string, ty);
+            write!(string, ">").unwrap();
+        }
+        else {
+            self.write_ty(string, ty);
+        }
+        true
+    }
+
+    fn write_fn_def_start(&self, string: &mut String, ident: &dyn fmt::Display, _return_ty: &Ty) {
+        write!(string, "fn {}(", ident).unwrap();
+    }
+
+    fn write_fn_def_end(&self, string: &mut String, return_ty: &Ty) {
+        if *return_ty == Ty::Void {
+            write!(string, ") ").unwrap();
+        }
+        else {
+            write!(string, ") -> ").unwrap();
+            self.write_ty(string, return_ty);
+            write!(string, " ").unwrap();
+        }
+    }
+
+    fn write_let_decl(&self, string: &mut String, ident: &dyn fmt::Display, ty: &Ty) {
+        write!(string, "var {}: ", ident).unwrap();
+        self.write_ty(string, ty);
+    }
+
+    fn write_call_expr_hidden_args(&self, _string: &mut String, _hidden_args: &BTreeSet<HiddenArgKind >, _sep: &str) {
+    }
+
+    fn write_fn_def_hidden_params(&self, _string: &mut String, _hidden_args: &BTreeSet<HiddenArgKind >, _sep: &str) {
+    }
+
+    fn generate_live_value_prefix(&self, _string: &mut String) {
+    }
+
+    fn generate_draw_shader_field_expr(&self, string: &mut String, field_ident: Ident, _ty: &Ty) {
+        write!(string, "{}", &DisplayDsIdent(field_ident)).unwrap();
+    }
+
+    fn write_ty_lit(&self, string: &mut String, ty_lit: TyLit) {
+        write!(
+            string,
+            "{}",
+            match ty_lit {
+                TyLit::Bool => "bool",
+                TyLit::Int => "i32",
+                TyLit::Float => "f32",
+                TyLit::Bvec2 => "vec2<bool>",
+                TyLit::Bvec3 => "vec3<bool>",
+                TyLit::Bvec4 => "vec4<bool>",
+                TyLit::Ivec2 => "vec2<i32>",
+                TyLit::Ivec3 => "vec3<i32>",
+                TyLit::Ivec4 => "vec4<i32>",
+                TyLit::Vec2 => "vec2<f32>",
+                TyLit::Vec3 => "vec3<f32>",
+                TyLit::Vec4 => "vec4<f32>",
+                TyLit::Mat2 => "mat2x2<f32>",
+                TyLit::Mat3 => "mat3x3<f32>",
+                TyLit::Mat4 => "mat4x4<f32>",
+                TyLit::Texture2D => "texture_2d<f32>",
+                TyLit::TextureOES => panic!("TextureOES is only available on Android"),
+            }
+        ).unwrap();
+    }
+
+    fn write_builtin_call_ident(&self, string: &mut String, ident: Ident, arg_exprs: &[Expr]) {
+        let arg_tys: Vec<Ty> = arg_exprs.iter().map( | arg_expr | arg_expr.ty.borrow().clone().unwrap()).collect();
+        let return_ty = self.shader_registry.builtins.get(&ident)
+            .and_then( | builtin | builtin.return_tys.get(&arg_tys));
+        if let Some(return_ty) = return_ty {
+            if let Some(body) = self.polyfill_body(ident, &arg_tys, return_ty) {
+                let mut name = format!("{}", ident);
+                for arg_ty in &arg_tys {
+                    write!(name, "_{}", arg_ty).unwrap();
+                }
+                write!(string, "{}", name).unwrap();
+                self.polyfills.borrow_mut().entry(name.clone()).or_insert_with( || {
+                    let mut polyfill = format!("fn {}(", name);
+                    for (arg_ty, param) in arg_tys.iter().zip(["a", "b", "c"]) {
+                        self.write_var_decl(&mut polyfill, if param == "a" {""} else {", "}, false, false, &param, arg_ty);
+                    }
+                    self.write_fn_def_end(&mut polyfill, return_ty);
+                    writeln!(polyfill, "{{\n    {}\n}}", body).unwrap();
+                    polyfill
+                });
+                return
+            }
+        }
+        match ident {
+            Ident(live_id!(atan)) if arg_exprs.len() == 2 => {
+                write!(string, "atan2").unwrap();
+            }
+            Ident(live_id!(inversesqrt)) => {
+                write!(string, "inverseSqrt").unwrap();
+            }
+            Ident(live_id!(dFdx)) => {
+                write!(string, "dpdx").unwrap();
+            }
+            Ident(live_id!(dFdy)) => {
+                write!(string, "dpdy").unwrap();
+            }
+            Ident(live_id!(sample2dOES)) => panic!("TextureOES is only available on Android"),
+            _ => {
+                write!(string, "{}", ident).unwrap()
+            }
+        }
+    }
+}
@@ -0,0 +1,1506 @@
+use{
+    std::{
+        collections::{BTreeMap, HashMap},
+        rc::Rc,
+    },
+    crate::{
+        makepad_live_id::{LiveId, live_id},
+        makepad_live_compiler::{
+            makepad_math::{Vec2, Vec3, Vec4, Mat4, vec2, vec3, vec4},
+            live_eval_value,
+            LiveError,
+            LiveErrorOrigin,
+            live_error_origin,
+            LiveRegistry,
+            LiveValue,
+            TokenSpan
+        },
+        shader_ast::*,
+        shader_registry::ShaderRegistry,
+        swizzle::Swizzle,
+    }
+};
+
+// Executes the type checked shader AST on the cpu. The interpreter walks the same
+// tree the glsl/hlsl/metal generators do, so it only works on draw shaders that
+// made it through analyse_draw_shader. Values follow glsl semantics: matrices are
+// column major, ints wrap and divide by zero to zero, and structs/arrays copy.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShaderValue {
+    Void,
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Bvec2([bool; 2]),
+    Bvec3([bool; 3]),
+    Bvec4([bool; 4]),
+    Ivec2([i32; 2]),
+    Ivec3([i32; 3]),
+    Ivec4([i32; 4]),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Mat2([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4(Mat4),
+    Texture(Ident),
+    Array(Vec<ShaderValue>),
+    Struct(StructPtr, Vec<ShaderValue>),
+    Closure(Rc<ShaderClosure>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderClosure {
+    pub fn_ptr: FnPtr,
+    pub closure_def_index: ClosureDefIndex,
+    pub closed_over: Vec<(Ident, ScopeSymShadow, ShaderValue)>,
+}
+
+// the components of a scalar or vector value
+#[derive(Clone, Copy, Debug)]
+enum Lanes {
+    Bool(usize, [bool; 4]),
+    Int(usize, [i32; 4]),
+    Float(usize, [f32; 4]),
+}
+
+impl Lanes {
+    fn len(&self) -> usize {
+        match self {
+            Lanes::Bool(len, _) | Lanes::Int(len, _) | Lanes::Float(len, _) => *len
+        }
+    }
+
+    fn to_f32(self, index: usize) -> f32 {
+        match self {
+            Lanes::Bool(_, v) => if v[index] {1.0} else {0.0},
+            Lanes::Int(_, v) => v[index] as f32,
+            Lanes::Float(_, v) => v[index]
+        }
+    }
+
+    fn to_i32(self, index: usize) -> i32 {
+        match self {
+            Lanes::Bool(_, v) => v[index] as i32,
+            Lanes::Int(_, v) => v[index],
+            Lanes::Float(_, v) => v[index] as i32
+        }
+    }
+
+    fn to_bool(self, index: usize) -> bool {
+        match self {
+            Lanes::Bool(_, v) => v[index],
+            Lanes::Int(_, v) => v[index] != 0,
+            Lanes::Float(_, v) => v[index] != 0.0
+        }
+    }
+
+    fn pick(&self, indices: &[usize]) -> Option<Lanes> {
+        if indices.len() > 4 || indices.iter().any( | index | *index >= self.len()) {
+            return None
+        }
+        Some(match self {
+            Lanes::Bool(_, v) => {
+                let mut out = [false; 4];
+                for (i, index) in indices.iter().enumerate() {out[i] = v[*index]}
+                Lanes::Bool(indices.len(), out)
+            }
+            Lanes::Int(_, v) => {
+                let mut out = [0; 4];
+                for (i, index) in indices.iter().enumerate() {out[i] = v[*index]}
+                Lanes::Int(indices.len(), out)
+            }
+            Lanes::Float(_, v) => {
+                let mut out = [0.0; 4];
+                for (i, index) in indices.iter().enumerate() {out[i] = v[*index]}
+                Lanes::Float(indices.len(), out)
+            }
+        })
+    }
+
+    fn put(&mut self, indices: &[usize], src: &Lanes) -> Option<()> {
+        if indices.len() != src.len() || indices.iter().any( | index | *index >= self.len()) {
+            return None
+        }
+        for (i, index) in indices.iter().enumerate() {
+            match self {
+                Lanes::Bool(_, v) => v[*index] = src.to_bool(i),
+                Lanes::Int(_, v) => v[*index] = src.to_i32(i),
+                Lanes::Float(_, v) => v[*index] = src.to_f32(i),
+            }
+        }
+        Some(())
+    }
+}
+
+impl ShaderValue {
+    /// The zero value of a type, which is also what uninitialized glsl variables hold
+    pub fn default_for_ty(ty: &Ty, shader_registry: &ShaderRegistry) -> ShaderValue {
+        match ty {
+            Ty::Void | Ty::DrawShader(_) | Ty::ClosureDef(_) | Ty::ClosureDecl => ShaderValue::Void,
+            Ty::Bool => ShaderValue::Bool(false),
+            Ty::Int | Ty::Enum(_) => ShaderValue::Int(0),
+            Ty::Float => ShaderValue::Float(0.0),
+            Ty::Bvec2 => ShaderValue::Bvec2([false; 2]),
+            Ty::Bvec3 => ShaderValue::Bvec3([false; 3]),
+            Ty::Bvec4 => ShaderValue::Bvec4([false; 4]),
+            Ty::Ivec2 => ShaderValue::Ivec2([0; 2]),
+            Ty::Ivec3 => ShaderValue::Ivec3([0; 3]),
+            Ty::Ivec4 => ShaderValue::Ivec4([0; 4]),
+            Ty::Vec2 => ShaderValue::Vec2(Vec2::default()),
+            Ty::Vec3 => ShaderValue::Vec3(Vec3::default()),
+            Ty::Vec4 => ShaderValue::Vec4(Vec4::default()),
+            Ty::Mat2 => ShaderValue::Mat2([0.0; 4]),
+            Ty::Mat3 => ShaderValue::Mat3([0.0; 9]),
+            Ty::Mat4 => ShaderValue::Mat4(Mat4 {v: [0.0; 16]}),
+            Ty::Texture2D | Ty::TextureOES => ShaderValue::Texture(Ident(LiveId(0))),
+            Ty::Array {elem_ty, len} => {
+                ShaderValue::Array(vec![Self::default_for_ty(elem_ty, shader_registry); *len])
+            }
+            Ty::Struct(struct_ptr) => {
+                let struct_def = shader_registry.structs.get(struct_ptr).unwrap();
+                ShaderValue::Struct(*struct_ptr, struct_def.fields.iter().map( | field | {
+                    Self::default_for_ty(field.ty_expr.ty.borrow().as_ref().unwrap(), shader_registry)
+                }).collect())
+            }
+        }
+    }
+
+    /// Builds a value from the f32 slots a draw call uploads for an instance, uniform or geometry field
+    pub fn from_slots(ty: &Ty, slots: &[f32]) -> Option<ShaderValue> {
+        if slots.len() < ty.slots() {
+            return None
+        }
+        let s = slots;
+        Some(match ty {
+            Ty::Bool => ShaderValue::Bool(s[0] != 0.0),
+            Ty::Int => ShaderValue::Int(s[0] as i32),
+            Ty::Float => ShaderValue::Float(s[0]),
+            // enums are stored as a float on gl and as the raw integer bits elsewhere
+            Ty::Enum(_) => ShaderValue::Int(if s[0].to_bits() < 0x10000 {s[0].to_bits() as i32} else {s[0] as i32}),
+            Ty::Vec2 => ShaderValue::Vec2(vec2(s[0], s[1])),
+            Ty::Vec3 => ShaderValue::Vec3(vec3(s[0], s[1], s[2])),
+            Ty::Vec4 => ShaderValue::Vec4(vec4(s[0], s[1], s[2], s[3])),
+            Ty::Mat2 => ShaderValue::Mat2([s[0], s[1], s[2], s[3]]),
+            Ty::Mat3 => {
+                let mut m = [0.0; 9];
+                m.copy_from_slice(&s[0..9]);
+                ShaderValue::Mat3(m)
+            }
+            Ty::Mat4 => {
+                let mut m = Mat4 {v: [0.0; 16]};
+                m.v.copy_from_slice(&s[0..16]);
+                ShaderValue::Mat4(m)
+            }
+            _ => return None
+        })
+    }
+
+    /// The f32 slots of a scalar, vector or matrix value, the inverse of `from_slots`
+    pub fn to_slots(&self) -> Option<Vec<f32>> {
+        Some(match self {
+            ShaderValue::Bool(v) => vec![if *v {1.0} else {0.0}],
+            ShaderValue::Int(v) => vec![*v as f32],
+            ShaderValue::Float(v) => vec![*v],
+            ShaderValue::Vec2(v) => vec![v.x, v.y],
+            ShaderValue::Vec3(v) => vec![v.x, v.y, v.z],
+            ShaderValue::Vec4(v) => vec![v.x, v.y, v.z, v.w],
+            ShaderValue::Mat2(m) => m.to_vec(),
+            ShaderValue::Mat3(m) => m.to_vec(),
+            ShaderValue::Mat4(m) => m.v.to_vec(),
+            _ => return None
+        })
+    }
+
+    pub fn from_val(val: &Val) -> ShaderValue {
+        match val {
+            Val::Bool(v) => ShaderValue::Bool(*v),
+            Val::Int(v) => ShaderValue::Int(*v),
+            Val::Float(v) => ShaderValue::Float(*v),
+            Val::Vec4(v) => ShaderValue::Vec4(*v),
+        }
+    }
+
+    fn from_live_value(value: &LiveValue, ty: &Ty) -> Option<ShaderValue> {
+        Some(match (value, ty) {
+            (LiveValue::Bool(v), Ty::Bool) => ShaderValue::Bool(*v),
+            (LiveValue::Int64(v), Ty::Int) => ShaderValue::Int(*v as i32),
+            (LiveValue::Int64(v), Ty::Float) => ShaderValue::Float(*v as f32),
+            (LiveValue::Float64(v), Ty::Float) => ShaderValue::Float(*v as f32),
+            (LiveValue::Float32(v), Ty::Float) => ShaderValue::Float(*v),
+            (LiveValue::Float64(v), Ty::Int) => ShaderValue::Int(*v as i32),
+            (LiveValue::Vec2(v), Ty::Vec2) => ShaderValue::Vec2(*v),
+            (LiveValue::Vec3(v), Ty::Vec3) => ShaderValue::Vec3(*v),
+            (LiveValue::Vec4(v), Ty::Vec4) => ShaderValue::Vec4(*v),
+            (LiveValue::Color(v), Ty::Vec4) => ShaderValue::Vec4(Vec4::from_u32(*v)),
+            _ => return None
+        })
+    }
+
+    pub fn to_f32(&self) -> Option<f32> {
+        match self {
+            ShaderValue::Float(v) => Some(*v),
+            ShaderValue::Int(v) => Some(*v as f32),
+            _ => None
+        }
+    }
+
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            ShaderValue::Bool(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    pub fn to_vec2(&self) -> Option<Vec2> {
+        match self {
+            ShaderValue::Vec2(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    pub fn to_vec4(&self) -> Option<Vec4> {
+        match self {
+            ShaderValue::Vec4(v) => Some(*v),
+            _ => None
+        }
+    }
+
+    fn to_lanes(&self) -> Option<Lanes> {
+        Some(match self {
+            ShaderValue::Bool(v) => Lanes::Bool(1, [*v, false, false, false]),
+            ShaderValue::Int(v) => Lanes::Int(1, [*v, 0, 0, 0]),
+            ShaderValue::Float(v) => Lanes::Float(1, [*v, 0.0, 0.0, 0.0]),
+            ShaderValue::Bvec2(v) => Lanes::Bool(2, [v[0], v[1], false, false]),
+            ShaderValue::Bvec3(v) => Lanes::Bool(3, [v[0], v[1], v[2], false]),
+            ShaderValue::Bvec4(v) => Lanes::Bool(4, *v),
+            ShaderValue::Ivec2(v) => Lanes::Int(2, [v[0], v[1], 0, 0]),
+            ShaderValue::Ivec3(v) => Lanes::Int(3, [v[0], v[1], v[2], 0]),
+            ShaderValue::Ivec4(v) => Lanes::Int(4, *v),
+            ShaderValue::Vec2(v) => Lanes::Float(2, [v.x, v.y, 0.0, 0.0]),
+            ShaderValue::Vec3(v) => Lanes::Float(3, [v.x, v.y, v.z, 0.0]),
+            ShaderValue::Vec4(v) => Lanes::Float(4, [v.x, v.y, v.z, v.w]),
+            _ => return None
+        })
+    }
+
+    fn from_lanes(lanes: Lanes) -> ShaderValue {
+        match lanes {
+            Lanes::Bool(1, v) => ShaderValue::Bool(v[0]),
+            Lanes::Bool(2, v) => ShaderValue::Bvec2([v[0], v[1]]),
+            Lanes::Bool(3, v) => ShaderValue::Bvec3([v[0], v[1], v[2]]),
+            Lanes::Bool(_, v) => ShaderValue::Bvec4(v),
+            Lanes::Int(1, v) => ShaderValue::Int(v[0]),
+            Lanes::Int(2, v) => ShaderValue::Ivec2([v[0], v[1]]),
+            Lanes::Int(3, v) => ShaderValue::Ivec3([v[0], v[1], v[2]]),
+            Lanes::Int(_, v) => ShaderValue::Ivec4(v),
+            Lanes::Float(1, v) => ShaderValue::Float(v[0]),
+            Lanes::Float(2, v) => ShaderValue::Vec2(vec2(v[0], v[1])),
+            Lanes::Float(3, v) => ShaderValue::Vec3(vec3(v[0], v[1], v[2])),
+            Lanes::Float(_, v) => ShaderValue::Vec4(vec4(v[0], v[1], v[2], v[3])),
+        }
+    }
+
+    fn float_lanes(&self) -> Option<(usize, [f32; 4])> {
+        match self.to_lanes() ? {
+            Lanes::Float(len, v) => Some((len, v)),
+            _ => None
+        }
+    }
+
+    fn from_float_lanes(len: usize, v: [f32; 4]) -> ShaderValue {
+        Self::from_lanes(Lanes::Float(len, v))
+    }
+
+    fn to_mat(&self) -> Option<(usize, &[f32])> {
+        match self {
+            ShaderValue::Mat2(m) => Some((2, m)),
+            ShaderValue::Mat3(m) => Some((3, m)),
+            ShaderValue::Mat4(m) => Some((4, &m.v)),
+            _ => None
+        }
+    }
+
+    fn from_mat(dim: usize, m: &[f32]) -> ShaderValue {
+        match dim {
+            2 => ShaderValue::Mat2([m[0], m[1], m[2], m[3]]),
+            3 => {
+                let mut out = [0.0; 9];
+                out.copy_from_slice(&m[0..9]);
+                ShaderValue::Mat3(out)
+            }
+            _ => {
+                let mut out = Mat4 {v: [0.0; 16]};
+                out.v.copy_from_slice(&m[0..16]);
+                ShaderValue::Mat4(out)
+            }
+        }
+    }
+
+    // all the scalar components, used by constructors like vec4(a.xy, b)
+    fn components(&self, out: &mut Vec<f32>) -> Option<()> {
+        if let Some(lanes) = self.to_lanes() {
+            for i in 0..lanes.len() {
+                out.push(lanes.to_f32(i));
+            }
+            return Some(())
+        }
+        let (_, m) = self.to_mat() ?;
+        out.extend_from_slice(m);
+        Some(())
+    }
+}
+
+fn map1(a: &ShaderValue, f: impl Fn(f32) -> f32) -> Option<ShaderValue> {
+    let (len, mut v) = a.float_lanes() ?;
+    for v in &mut v[0..len] {
+        *v = f(*v);
+    }
+    Some(ShaderValue::from_float_lanes(len, v))
+}
+
+// componentwise over two values, a scalar broadcasts to the length of the other side
+fn map2(a: &ShaderValue, b: &ShaderValue, f: impl Fn(f32, f32) -> f32) -> Option<ShaderValue> {
+    let (alen, av) = a.float_lanes() ?;
+    let (blen, bv) = b.float_lanes() ?;
+    let len = alen.max(blen);
+    if alen != len && alen != 1 || blen != len && blen != 1 {
+        return None
+    }
+    let mut out = [0.0; 4];
+    for i in 0..len {
+        out[i] = f(av[if alen == 1 {0} else {i}], bv[if blen == 1 {0} else {i}]);
+    }
+    Some(ShaderValue::from_float_lanes(len, out))
+}
+
+fn map3(a: &ShaderValue, b: &ShaderValue, c: &ShaderValue, f: impl Fn(f32, f32, f32) -> f32) -> Option<ShaderValue> {
+    let (alen, av) = a.float_lanes() ?;
+    let (blen, bv) = b.float_lanes() ?;
+    let (clen, cv) = c.float_lanes() ?;
+    let len = alen.max(blen).max(clen);
+    if alen != len && alen != 1 || blen != len && blen != 1 || clen != len && clen != 1 {
+        return None
+    }
+    let mut out = [0.0; 4];
+    for i in 0..len {
+        out[i] = f(
+            av[if alen == 1 {0} else {i}],
+            bv[if blen == 1 {0} else {i}],
+            cv[if clen == 1 {0} else {i}]
+        );
+    }
+    Some(ShaderValue::from_float_lanes(len, out))
+}
+
+fn dot(a: &ShaderValue, b: &ShaderValue) -> Option<f32> {
+    let (alen, av) = a.float_lanes() ?;
+    let (blen, bv) = b.float_lanes() ?;
+    if alen != blen {
+        return None
+    }
+    Some((0..alen).map( | i | av[i] * bv[i]).sum())
+}
+
+fn compare(a: &ShaderValue, b: &ShaderValue, f: impl Fn(f32, f32) -> bool) -> Option<ShaderValue> {
+    let al = a.to_lanes() ?;
+    let bl = b.to_lanes() ?;
+    if al.len() != bl.len() {
+        return None
+    }
+    let mut out = [false; 4];
+    for (i, out) in out.iter_mut().enumerate().take(al.len()) {
+        *out = f(al.to_f32(i), bl.to_f32(i));
+    }
+    Some(ShaderValue::from_lanes(Lanes::Bool(al.len(), out)))
+}
+
+fn mat_mul(dim: usize, a: &[f32], b: &[f32]) -> Vec<f32> {
+    let mut out = vec![0.0; dim * dim];
+    for col in 0..dim {
+        for row in 0..dim {
+            out[col * dim + row] = (0..dim).map( | k | a[k * dim + row] * b[col * dim + k]).sum();
+        }
+    }
+    out
+}
+
+fn arith(op: BinOp, a: &ShaderValue, b: &ShaderValue) -> Option<ShaderValue> {
+    match (a.to_mat(), b.to_mat()) {
+        (Some((adim, am)), Some((bdim, bm))) => {
+            if adim != bdim {
+                return None
+            }
+            let out: Vec<f32> = match op {
+                BinOp::Mul => mat_mul(adim, am, bm),
+                BinOp::Add => am.iter().zip(bm).map( | (a, b) | a + b).collect(),
+                BinOp::Sub => am.iter().zip(bm).map( | (a, b) | a - b).collect(),
+                BinOp::Div => am.iter().zip(bm).map( | (a, b) | a / b).collect(),
+                _ => return None
+            };
+            return Some(ShaderValue::from_mat(adim, &out))
+        }
+        (Some((dim, m)), None) => {
+            if let ShaderValue::Float(s) = b {
+                let out: Vec<f32> = m.iter().map( | v | scalar_arith(op, *v, *s)).collect();
+                return Some(ShaderValue::from_mat(dim, &out))
+            }
+            // mat * column vector
+            let (len, v) = b.float_lanes() ?;
+            if !matches!(op, BinOp::Mul) || len != dim {
+                return None
+            }
+            let mut out = [0.0; 4];
+            for row in 0..dim {
+                out[row] = (0..dim).map( | col | m[col * dim + row] * v[col]).sum();
+            }
+            return Some(ShaderValue::from_float_lanes(dim, out))
+        }
+        (None, Some((dim, m))) => {
+            if let ShaderValue::Float(s) = a {
+                let out: Vec<f32> = m.iter().map( | v | scalar_arith(op, *s, *v)).collect();
+                return Some(ShaderValue::from_mat(dim, &out))
+            }
+            // row vector * mat
+            let (len, v) = a.float_lanes() ?;
+            if !matches!(op, BinOp::Mul) || len != dim {
+                return None
+            }
+            let mut out = [0.0; 4];
+            for col in 0..dim {
+                out[col] = (0..dim).map( | row | v[row] * m[col * dim + row]).sum();
+            }
+            return Some(ShaderValue::from_float_lanes(dim, out))
+        }
+        (None, None) => ()
+    }
+    match (a.to_lanes() ?, b.to_lanes() ?) {
+        (Lanes::Int(alen, av), Lanes::Int(blen, bv)) => {
+            let len = alen.max(blen);
+            if alen != len && alen != 1 || blen != len && blen != 1 {
+                return None
+            }
+            let mut out = [0; 4];
+            for i in 0..len {
+                let a = av[if alen == 1 {0} else {i}];
+                let b = bv[if blen == 1 {0} else {i}];
+                out[i] = match op {
+                    BinOp::Add => a.wrapping_add(b),
+                    BinOp::Sub => a.wrapping_sub(b),
+                    BinOp::Mul => a.wrapping_mul(b),
+                    BinOp::Div => if b == 0 {0} else {a.wrapping_div(b)},
+                    _ => return None
+                };
+            }
+            Some(ShaderValue::from_lanes(Lanes::Int(len, out)))
+        }
+        (Lanes::Float(..), Lanes::Float(..)) => map2(a, b, | a, b | scalar_arith(op, a, b)),
+        _ => None
+    }
+}
+
+fn scalar_arith(op: BinOp, a: f32, b: f32) -> f32 {
+    match op {
+        BinOp::Add => a + b,
+        BinOp::Sub => a - b,
+        BinOp::Mul => a * b,
+        _ => a / b,
+    }
+}
+
+// glsl style constructor, vec4(a.xy, 0.0, 1.0), vec3(1.0), mat3(m4) and friends
+fn construct(ty_lit: TyLit, args: &[ShaderValue]) -> Option<ShaderValue> {
+    let mat_dim = match ty_lit {
+        TyLit::Mat2 => Some(2),
+        TyLit::Mat3 => Some(3),
+        TyLit::Mat4 => Some(4),
+        _ => None
+    };
+    if let Some(dim) = mat_dim {
+        let mut out = vec![0.0; dim * dim];
+        match args {
+            [arg] if arg.to_mat().is_some() => {
+                let (src_dim, src) = arg.to_mat().unwrap();
+                for col in 0..dim {
+                    for row in 0..dim {
+                        out[col * dim + row] = if col < src_dim && row < src_dim {
+                            src[col * src_dim + row]
+                        }
+                        else if col == row {1.0} else {0.0};
+                    }
+                }
+            }
+            [arg] => {
+                let s = arg.to_f32() ?;
+                for i in 0..dim {
+                    out[i * dim + i] = s;
+                }
+            }
+            _ => {
+                let mut comps = Vec::new();
+                for arg in args {
+                    arg.components(&mut comps) ?;
+                }
+                if comps.len() < dim * dim {
+                    return None
+                }
+                out.copy_from_slice(&comps[0..dim * dim]);
+            }
+        }
+        return Some(ShaderValue::from_mat(dim, &out))
+    }
+    let len = ty_lit.to_ty().slots();
+    let mut comps = Vec::new();
+    for arg in args {
+        arg.components(&mut comps) ?;
+    }
+    if comps.len() == 1 {
+        comps.resize(len, comps[0]);
+    }
+    if comps.len() < len || len > 4 {
+        return None
+    }
+    let mut lanes = match ty_lit {
+        TyLit::Bool | TyLit::Bvec2 | TyLit::Bvec3 | TyLit::Bvec4 => Lanes::Bool(len, [false; 4]),
+        TyLit::Int | TyLit::Ivec2 | TyLit::Ivec3 | TyLit::Ivec4 => Lanes::Int(len, [0; 4]),
+        TyLit::Float | TyLit::Vec2 | TyLit::Vec3 | TyLit::Vec4 => Lanes::Float(len, [0.0; 4]),
+        _ => return None
+    };
+    let indices: Vec<usize> = (0..len).collect();
+    let mut src = [0.0; 4];
+    src[0..len].copy_from_slice(&comps[0..len]);
+    lanes.put(&indices, &Lanes::Float(len, src)) ?;
+    Some(ShaderValue::from_lanes(lanes))
+}
+
+enum Flow {
+    Normal,
+    Break,
+    Continue,
+    Return(ShaderValue)
+}
+
+// an assignable location, a local or draw shader field followed by a path into it
+enum Place {
+    Local(Ident, ScopeSymShadow),
+    Field(Ident),
+}
+
+enum Access {
+    Field(usize),
+    Index(usize),
+    Swizzle(Swizzle),
+}
+
+fn read_access(value: &ShaderValue, access: &Access) -> Option<ShaderValue> {
+    match access {
+        Access::Field(index) => match value {
+            ShaderValue::Struct(_, fields) => fields.get(*index).cloned(),
+            _ => None
+        }
+        Access::Index(index) => match value {
+            ShaderValue::Array(items) => items.get(*index).cloned(),
+            _ => {
+                if let Some((dim, m)) = value.to_mat() {
+                    if *index >= dim {
+                        return None
+                    }
+                    let mut col = [0.0; 4];
+                    col[0..dim].copy_from_slice(&m[index * dim..index * dim + dim]);
+                    return Some(ShaderValue::from_float_lanes(dim, col))
+                }
+                Some(ShaderValue::from_lanes(value.to_lanes() ?.pick(&[*index]) ?))
+            }
+        }
+        Access::Swizzle(swizzle) => {
+            let indices: Vec<usize> = swizzle.iter().cloned().collect();
+            Some(ShaderValue::from_lanes(value.to_lanes() ?.pick(&indices) ?))
+        }
+    }
+}
+
+fn write_access(target: &mut ShaderValue, access: &Access, value: ShaderValue) -> Option<()> {
+    let indices: Vec<usize> = match access {
+        Access::Field(index) => {
+            if let ShaderValue::Struct(_, fields) = target {
+                *fields.get_mut(*index) ? = value;
+                return Some(())
+            }
+            return None
+        }
+        Access::Index(index) => {
+            if let ShaderValue::Array(items) = target {
+                *items.get_mut(*index) ? = value;
+                return Some(())
+            }
+            if let Some((dim, m)) = target.to_mat() {
+                let (len, col) = value.float_lanes() ?;
+                if len != dim || *index >= dim {
+                    return None
+                }
+                let mut m = m.to_vec();
+                m[index * dim..index * dim + dim].copy_from_slice(&col[0..dim]);
+                *target = ShaderValue::from_mat(dim, &m);
+                return Some(())
+            }
+            vec![*index]
+        }
+        Access::Swizzle(swizzle) => swizzle.iter().cloned().collect()
+    };
+    let mut lanes = target.to_lanes() ?;
+    lanes.put(&indices, &value.to_lanes() ?) ?;
+    *target = ShaderValue::from_lanes(lanes);
+    Some(())
+}
+
+fn store(target: &mut ShaderValue, path: &[Access], value: ShaderValue) -> Option<()> {
+    match path.split_first() {
+        None => {
+            *target = value;
+            Some(())
+        }
+        Some((access, rest)) => {
+            match (access, &mut *target) {
+                (Access::Field(index), ShaderValue::Struct(_, fields)) => {
+                    return store(fields.get_mut(*index) ?, rest, value)
+                }
+                (Access::Index(index), ShaderValue::Array(items)) => {
+                    return store(items.get_mut(*index) ?, rest, value)
+                }
+                _ => ()
+            }
+            let mut sub = read_access(target, access) ?;
+            store(&mut sub, rest, value) ?;
+            write_access(target, access, sub)
+        }
+    }
+}
+
+/// The locals of one function (or closure) invocation, handed to the step hook
+pub struct ShaderFrame<'a> {
+    fn_def: &'a FnDef,
+    locals: Vec<(Ident, ScopeSymShadow, ShaderValue)>,
+    closures: Vec<Option<Rc<ShaderClosure >> >,
+}
+
+impl<'a> ShaderFrame<'a> {
+    fn new(fn_def: &'a FnDef) -> Self {
+        Self {
+            fn_def,
+            locals: Vec::new(),
+            closures: vec![None; fn_def.params.len()]
+        }
+    }
+
+    pub fn fn_ident(&self) -> Ident {
+        self.fn_def.ident
+    }
+
+    /// The innermost visible local with this name
+    pub fn local(&self, id: LiveId) -> Option<&ShaderValue> {
+        self.locals.iter().rev().find( | (ident, _, _) | ident.0 == id).map( | (_, _, value) | value)
+    }
+
+    pub fn locals(&self) -> impl Iterator<Item = (Ident, &ShaderValue)> {
+        self.locals.iter().map( | (ident, _, value) | (*ident, value))
+    }
+
+    fn get(&self, ident: Ident, shadow: ScopeSymShadow) -> Option<&ShaderValue> {
+        self.locals.iter().rev().find( | (i, s, _) | *i == ident && *s == shadow).map( | (_, _, value) | value)
+    }
+
+    fn get_mut(&mut self, ident: Ident, shadow: ScopeSymShadow) -> Option<&mut ShaderValue> {
+        self.locals.iter_mut().rev().find( | (i, s, _) | *i == ident && *s == shadow).map( | (_, _, value) | value)
+    }
+
+    fn set(&mut self, ident: Ident, shadow: ScopeSymShadow, value: ShaderValue) {
+        if let Some(local) = self.get_mut(ident, shadow) {
+            *local = value;
+        }
+        else {
+            self.locals.push((ident, shadow, value));
+        }
+    }
+}
+
+// dFdx/dFdy need the neighbouring pixels. those are run first recording the argument
+// of every derivative call in order, the pixel itself then subtracts its own value
+#[derive(Default)]
+struct Derivatives {
+    index: usize,
+    record: Vec<ShaderValue>,
+    right: Vec<ShaderValue>,
+    below: Vec<ShaderValue>,
+}
+
+type StepHook<'a> = Box<dyn FnMut(TokenSpan, &ShaderFrame) + 'a>;
+
+pub struct ShaderInterpreter<'a> {
+    shader_registry: &'a ShaderRegistry,
+    draw_shader_def: &'a DrawShaderDef,
+    const_table: Option<&'a DrawShaderConstTable>,
+    fields: HashMap<Ident, ShaderValue>,
+    live_values: BTreeMap<ValuePtr, ShaderValue>,
+    sampler: Option<Box<dyn Fn(Ident, Vec2) -> Vec4 + 'a >>,
+    step_hook: Option<StepHook<'a >>,
+    derivatives: Derivatives,
+}
+
+fn error(span: TokenSpan, message: String) -> LiveError {
+    LiveError {
+        origin: live_error_origin!(),
+        span: span.into(),
+        message
+    }
+}
+
+impl<'a> ShaderInterpreter<'a> {
+    pub fn new(shader_registry: &'a ShaderRegistry, draw_shader_def: &'a DrawShaderDef) -> Self {
+        let mut fields = HashMap::new();
+        for field in &draw_shader_def.fields {
+            let value = match &field.kind {
+                DrawShaderFieldKind::Texture {..} => ShaderValue::Texture(field.ident),
+                _ => ShaderValue::default_for_ty(field.ty_expr.ty.borrow().as_ref().unwrap(), shader_registry)
+            };
+            fields.insert(field.ident, value);
+        }
+        Self {
+            shader_registry,
+            draw_shader_def,
+            const_table: None,
+            fields,
+            live_values: BTreeMap::new(),
+            sampler: None,
+            step_hook: None,
+            derivatives: Derivatives::default(),
+        }
+    }
+
+    /// Reads consts from a const table instead of the values folded in at compile time,
+    /// which is what the gpu backends do when live editing
+    pub fn set_const_table(&mut self, const_table: &'a DrawShaderConstTable) {
+        self.const_table = Some(const_table);
+    }
+
+    /// Fetches the live values the shader refers to and the defaults of the
+    /// fields that have one in the live_design
+    pub fn load_live_values(&mut self, live_registry: &LiveRegistry) -> Result<(), LiveError> {
+        for (value_ptr, ty) in self.draw_shader_def.all_live_refs.borrow().iter() {
+            let value = Self::eval_live_node(live_registry, value_ptr.0, ty) ?;
+            self.live_values.insert(*value_ptr, value);
+        }
+        for field in &self.draw_shader_def.fields {
+            let var_def_ptr = match &field.kind {
+                DrawShaderFieldKind::Instance {var_def_ptr: Some(var_def_ptr), ..} |
+                DrawShaderFieldKind::Uniform {var_def_ptr: Some(var_def_ptr), ..} => *var_def_ptr,
+                _ => continue
+            };
+            let ty = field.ty_expr.ty.borrow();
+            if let Ok(value) = Self::eval_live_node(live_registry, var_def_ptr.0, ty.as_ref().unwrap()) {
+                self.fields.insert(field.ident, value);
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_live_node(live_registry: &LiveRegistry, live_ptr: crate::makepad_live_compiler::LivePtr, ty: &Ty) -> Result<ShaderValue, LiveError> {
+        let (nodes, mut index) = live_registry.ptr_to_nodes_index(live_ptr);
+        let span = nodes[index].origin.token_id().map( | token_id | token_id.into()).unwrap_or_default();
+        let value = live_eval_value(live_registry, &mut index, nodes, nodes) ?;
+        ShaderValue::from_live_value(&value, ty).ok_or_else( || {
+            error(span, format!("cannot use live value {:?} as {}", value, ty))
+        })
+    }
+
+    pub fn set_live_value(&mut self, value_ptr: ValuePtr, value: ShaderValue) {
+        self.live_values.insert(value_ptr, value);
+    }
+
+    /// Sets an instance, uniform, geometry or varying field as seen through `self`
+    pub fn set_field(&mut self, id: LiveId, value: ShaderValue) {
+        self.fields.insert(Ident(id), value);
+    }
+
+    /// Sets a field from the f32 slots of an instance or uniform buffer
+    pub fn set_field_slots(&mut self, id: LiveId, slots: &[f32]) -> bool {
+        if let Some(field) = self.draw_shader_def.find_field(Ident(id)) {
+            if let Some(value) = ShaderValue::from_slots(field.ty_expr.ty.borrow().as_ref().unwrap(), slots) {
+                self.fields.insert(Ident(id), value);
+                return true
+            }
+        }
+        false
+    }
+
+    pub fn field(&self, id: LiveId) -> Option<&ShaderValue> {
+        self.fields.get(&Ident(id))
+    }
+
+    /// Called for sample2d on any texture, with the texture field and the uv
+    pub fn set_sampler(&mut self, sampler: impl Fn(Ident, Vec2) -> Vec4 + 'a) {
+        self.sampler = Some(Box::new(sampler));
+    }
+
+    /// Called before every statement, for stepping through a shader
+    pub fn set_step_hook(&mut self, step_hook: impl FnMut(TokenSpan, &ShaderFrame) + 'a) {
+        self.step_hook = Some(Box::new(step_hook));
+    }
+
+    /// Runs `fn vertex` and returns the clip space position. Varyings it writes
+    /// are kept on the fields for a following `run_pixel`
+    pub fn run_vertex(&mut self) -> Result<Vec4, LiveError> {
+        self.run_entry(live_id!(vertex))
+    }
+
+    /// Runs `fn pixel` and returns its color. Derivatives evaluate to zero,
+    /// use `run_pixel_with_neighbours` if the shader needs them
+    pub fn run_pixel(&mut self) -> Result<Vec4, LiveError> {
+        self.derivatives = Derivatives::default();
+        self.run_entry(live_id!(pixel))
+    }
+
+    /// Runs `fn pixel` with dFdx/dFdy taken against the pixel to the right and the one below,
+    /// given as the fields (usually varyings) that differ from this pixel
+    pub fn run_pixel_with_neighbours(&mut self, right: &[(LiveId, ShaderValue)], below: &[(LiveId, ShaderValue)]) -> Result<Vec4, LiveError> {
+        if !self.uses_derivatives() {
+            return self.run_pixel()
+        }
+        let mut records = Vec::new();
+        for neighbour in [right, below] {
+            let saved: Vec<(Ident, Option<ShaderValue>)> = neighbour.iter().map( | (id, value) | {
+                (Ident(*id), self.fields.insert(Ident(*id), value.clone()))
+            }).collect();
+            self.derivatives = Derivatives::default();
+            let result = self.run_entry(live_id!(pixel));
+            for (ident, value) in saved.into_iter().rev() {
+                if let Some(value) = value {
+                    self.fields.insert(ident, value);
+                }
+                else {
+                    self.fields.remove(&ident);
+                }
+            }
+            result ?;
+            records.push(std::mem::take(&mut self.derivatives.record));
+        }
+        self.derivatives = Derivatives {
+            below: records.pop().unwrap(),
+            right: records.pop().unwrap(),
+            ..Default::default()
+        };
+        let result = self.run_entry(live_id!(pixel));
+        self.derivatives = Derivatives::default();
+        result
+    }
+
+    fn uses_derivatives(&self) -> bool {
+        self.draw_shader_def.pixel_fns.borrow().iter().any( | fn_ptr | {
+            self.shader_registry.all_fns.get(fn_ptr).is_some_and( | fn_def | {
+                fn_def.builtin_deps.borrow().as_ref().is_some_and( | deps | {
+                    deps.contains(&Ident(live_id!(dFdx))) || deps.contains(&Ident(live_id!(dFdy)))
+                })
+            })
+        })
+    }
+
+    /// Calls any method of the draw shader with the given arguments, closures excepted
+    pub fn call_method(&mut self, id: LiveId, args: &[ShaderValue]) -> Result<ShaderValue, LiveError> {
+        let fn_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, Ident(id)).ok_or_else( || {
+            error(TokenSpan::default(), format!("draw shader has no method {}", id))
+        }) ?;
+        let params = &fn_def.params[1..];
+        if params.len() != args.len() {
+            return Err(error(fn_def.span, format!("{} takes {} arguments, got {}", id, params.len(), args.len())))
+        }
+        let mut frame = ShaderFrame::new(fn_def);
+        for (param, arg) in params.iter().zip(args) {
+            if let Some(shadow) = param.shadow.get() {
+                frame.set(param.ident, shadow, arg.clone());
+            }
+        }
+        self.run_fn_body(&mut frame)
+    }
+
+    fn run_entry(&mut self, id: LiveId) -> Result<Vec4, LiveError> {
+        let value = self.call_method(id, &[]) ?;
+        value.to_vec4().ok_or_else( || {
+            error(TokenSpan::default(), format!("fn {} returned {:?} instead of a vec4", id, value))
+        })
+    }
+
+    fn run_fn_body(&mut self, frame: &mut ShaderFrame<'a>) -> Result<ShaderValue, LiveError> {
+        let fn_def = frame.fn_def;
+        match self.run_block(frame, &fn_def.block) ? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(ShaderValue::Void)
+        }
+    }
+
+    fn run_block(&mut self, frame: &mut ShaderFrame<'a>, block: &'a Block) -> Result<Flow, LiveError> {
+        for stmt in &block.stmts {
+            match self.run_stmt(frame, stmt) ? {
+                Flow::Normal => (),
+                flow => return Ok(flow)
+            }
+        }
+        Ok(Flow::Normal)
+    }
+
+    fn run_stmt(&mut self, frame: &mut ShaderFrame<'a>, stmt: &'a Stmt) -> Result<Flow, LiveError> {
+        if let Some(step_hook) = &mut self.step_hook {
+            let span = match stmt {
+                Stmt::Break {span} |
+                Stmt::Continue {span} |
+                Stmt::For {span, ..} |
+                Stmt::If {span, ..} |
+                Stmt::Match {span, ..} |
+                Stmt::Let {span, ..} |
+                Stmt::Return {span, ..} |
+                Stmt::Block {span, ..} |
+                Stmt::Expr {span, ..} => *span
+            };
+            step_hook(span, frame);
+        }
+        match stmt {
+            Stmt::Break {..} => Ok(Flow::Break),
+            Stmt::Continue {..} => Ok(Flow::Continue),
+            Stmt::For {ident, from_expr, to_expr, step_expr, block, ..} => {
+                let from = self.eval_int(frame, from_expr) ?;
+                let to = self.eval_int(frame, to_expr) ?;
+                let step = if let Some(step_expr) = step_expr {
+                    self.eval_int(frame, step_expr) ?.abs().max(1)
+                } else {1};
+                // same bounds as the generated `for` loop
+                let mut i = if from <= to {from} else {from - 1};
+                while if from <= to {i < to} else {i >= to} {
+                    frame.set(*ident, ScopeSymShadow(0), ShaderValue::Int(i));
+                    match self.run_block(frame, block) ? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        _ => ()
+                    }
+                    i = if from <= to {i + step} else {i - step};
+                }
+                Ok(Flow::Normal)
+            }
+            Stmt::If {expr, block_if_true, block_if_false, ..} => {
+                if self.eval_bool(frame, expr) ? {
+                    self.run_block(frame, block_if_true)
+                }
+                else if let Some(block_if_false) = block_if_false {
+                    self.run_block(frame, block_if_false)
+                }
+                else {
+                    Ok(Flow::Normal)
+                }
+            }
+            Stmt::Match {expr, matches, ..} => {
+                let value = self.eval_expr(frame, expr) ?;
+                let value = match value {
+                    ShaderValue::Int(v) => v as f32,
+                    ShaderValue::Float(v) => v,
+                    _ => return Err(error(expr.span, format!("cannot match on {:?}", value)))
+                };
+                for match_item in matches {
+                    if (value - match_item.enum_value.get().unwrap() as f32).abs() < 0.5 {
+                        return self.run_block(frame, &match_item.block)
+                    }
+                }
+                Ok(Flow::Normal)
+            }
+            Stmt::Let {ty, shadow, ident, expr, ..} => {
+                let value = if let Some(expr) = expr {
+                    self.eval_expr(frame, expr) ?
+                }
+                else {
+                    ShaderValue::default_for_ty(ty.borrow().as_ref().unwrap(), self.shader_registry)
+                };
+                frame.set(*ident, shadow.get().unwrap(), value);
+                Ok(Flow::Normal)
+            }
+            Stmt::Return {expr, ..} => {
+                let value = if let Some(expr) = expr {
+                    self.eval_expr(frame, expr) ?
+                }
+                else {
+                    ShaderValue::Void
+                };
+                Ok(Flow::Return(value))
+            }
+            Stmt::Block {block, ..} => self.run_block(frame, block),
+            Stmt::Expr {expr, ..} => {
+                self.eval_expr(frame, expr) ?;
+                Ok(Flow::Normal)
+            }
+        }
+    }
+
+    fn eval_int(&mut self, frame: &mut ShaderFrame<'a>, expr: &'a Expr) -> Result<i32, LiveError> {
+        match self.eval_expr(frame, expr) ? {
+            ShaderValue::Int(v) => Ok(v),
+            ShaderValue::Float(v) => Ok(v as i32),
+            value => Err(error(expr.span, format!("expected an int, got {:?}", value)))
+        }
+    }
+
+    fn eval_bool(&mut self, frame: &mut ShaderFrame<'a>, expr: &'a Expr) -> Result<bool, LiveError> {
+        match self.eval_expr(frame, expr) ? {
+            ShaderValue::Bool(v) => Ok(v),
+            value => Err(error(expr.span, format!("expected a bool, got {:?}", value)))
+        }
+    }
+
+    fn const_value(&self, frame: &ShaderFrame<'a>, expr: &Expr) -> Option<ShaderValue> {
+        let const_val = expr.const_val.borrow();
+        let val = const_val.as_ref()?.as_ref() ?;
+        if let (Some(const_table), Some(index)) = (self.const_table, expr.const_index.get()) {
+            if let Some(offset) = const_table.offsets.get(&frame.fn_def.fn_ptr) {
+                let table = &const_table.table[offset + index..];
+                match val {
+                    Val::Float(_) => return Some(ShaderValue::Float(table[0])),
+                    Val::Vec4(_) => return Some(ShaderValue::Vec4(vec4(table[0], table[1], table[2], table[3]))),
+                    _ => ()
+                }
+            }
+        }
+        Some(ShaderValue::from_val(val))
+    }
+
+    fn eval_expr(&mut self, frame: &mut ShaderFrame<'a>, expr: &'a Expr) -> Result<ShaderValue, LiveError> {
+        if let Some(value) = self.const_value(frame, expr) {
+            return Ok(value)
+        }
+        match &expr.kind {
+            ExprKind::Cond {expr: cond, expr_if_true, expr_if_false, ..} => {
+                if self.eval_bool(frame, cond) ? {
+                    self.eval_expr(frame, expr_if_true)
+                }
+                else {
+                    self.eval_expr(frame, expr_if_false)
+                }
+            }
+            ExprKind::Bin {op, left_expr, right_expr, ..} => self.eval_bin_expr(frame, expr.span, *op, left_expr, right_expr),
+            ExprKind::Un {op, expr: operand, ..} => {
+                let value = self.eval_expr(frame, operand) ?;
+                let result = match op {
+                    UnOp::Not => value.to_bool().map( | v | ShaderValue::Bool(!v)),
+                    UnOp::Neg => {
+                        if let Some((dim, m)) = value.to_mat() {
+                            Some(ShaderValue::from_mat(dim, &m.iter().map( | v | -v).collect::<Vec<_ >> ()))
+                        }
+                        else {
+                            match value.to_lanes() {
+                                Some(Lanes::Int(len, mut v)) => {
+                                    for v in &mut v[0..len] {*v = v.wrapping_neg()}
+                                    Some(ShaderValue::from_lanes(Lanes::Int(len, v)))
+                                }
+                                _ => map1(&value, | v | -v)
+                            }
+                        }
+                    }
+                };
+                result.ok_or_else( || error(expr.span, format!("cannot apply {} to {:?}", op, value)))
+            }
+            ExprKind::Field {expr: base, field_ident, ..} => {
+                if let Some(Ty::DrawShader(_)) = base.ty.borrow().as_ref() {
+                    return self.fields.get(field_ident).cloned().ok_or_else( || {
+                        error(expr.span, format!("draw shader field {} has no value", field_ident))
+                    })
+                }
+                let value = self.eval_expr(frame, base) ?;
+                let access = self.field_access(base, *field_ident).ok_or_else( || {
+                    error(expr.span, format!("unknown field {}", field_ident))
+                }) ?;
+                read_access(&value, &access).ok_or_else( || error(expr.span, format!("cannot read {} of {:?}", field_ident, value)))
+            }
+            ExprKind::Index {expr: base, index_expr, ..} => {
+                let value = self.eval_expr(frame, base) ?;
+                let index = self.eval_int(frame, index_expr) ?;
+                read_access(&value, &Access::Index(index.max(0) as usize)).ok_or_else( || {
+                    error(expr.span, format!("index {} out of range of {:?}", index, value))
+                })
+            }
+            ExprKind::MethodCall {ident, arg_exprs, ..} => {
+                let span = expr.span;
+                match arg_exprs[0].ty.borrow().as_ref().unwrap() {
+                    Ty::Struct(struct_ptr) => {
+                        let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+                        let fn_def = self.shader_registry.struct_method_decl_from_ident(struct_def, *ident).ok_or_else( || {
+                            error(span, format!("struct has no method {}", ident))
+                        }) ?;
+                        self.call_fn(frame, fn_def, 0, arg_exprs)
+                    }
+                    Ty::DrawShader(_) => {
+                        let fn_def = self.shader_registry.draw_shader_method_decl_from_ident(self.draw_shader_def, *ident).ok_or_else( || {
+                            error(span, format!("draw shader has no method {}", ident))
+                        }) ?;
+                        self.call_fn(frame, fn_def, 1, &arg_exprs[1..])
+                    }
+                    ty => Err(error(span, format!("cannot call method {} on {}", ident, ty)))
+                }
+            }
+            ExprKind::PlainCall {fn_ptr, param_index, arg_exprs, ..} => {
+                if let Some(param_index) = param_index.get() {
+                    return self.call_closure(frame, expr.span, param_index, arg_exprs)
+                }
+                let fn_def = self.shader_registry.all_fns.get(&fn_ptr.unwrap()).unwrap();
+                self.call_fn(frame, fn_def, 0, arg_exprs)
+            }
+            ExprKind::BuiltinCall {ident, arg_exprs, ..} => {
+                let mut args = Vec::new();
+                for arg_expr in arg_exprs {
+                    args.push(self.eval_expr(frame, arg_expr) ?);
+                }
+                self.call_builtin(*ident, &args).ok_or_else( || {
+                    error(expr.span, format!("builtin {} not supported for {:?}", ident, args))
+                })
+            }
+            ExprKind::ClosureDef(_) => Ok(ShaderValue::Void),
+            ExprKind::ConsCall {ty_lit, arg_exprs, ..} => {
+                let mut args = Vec::new();
+                for arg_expr in arg_exprs {
+                    args.push(self.eval_expr(frame, arg_expr) ?);
+                }
+                construct(*ty_lit, &args).ok_or_else( || error(expr.span, format!("cannot construct {} from {:?}", ty_lit, args)))
+            }
+            ExprKind::StructCons {struct_ptr, args, ..} => {
+                let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+                let mut fields = Vec::new();
+                for field in &struct_def.fields {
+                    let (_, arg) = args.iter().find( | (ident, _) | *ident == field.ident).ok_or_else( || {
+                        error(expr.span, format!("missing struct field {}", field.ident))
+                    }) ?;
+                    fields.push(self.eval_expr(frame, arg) ?);
+                }
+                Ok(ShaderValue::Struct(*struct_ptr, fields))
+            }
+            ExprKind::Var {kind, ident, ..} => match kind.get() {
+                Some(VarKind::Local {ident, shadow}) | Some(VarKind::MutLocal {ident, shadow}) => {
+                    frame.get(ident, shadow).cloned().ok_or_else( || error(expr.span, format!("variable {} has no value", ident)))
+                }
+                Some(VarKind::LiveValue(value_ptr)) => {
+                    self.live_values.get(&value_ptr).cloned().ok_or_else( || {
+                        error(expr.span, format!("live value {:?} not loaded", ident))
+                    })
+                }
+                None => Err(error(expr.span, format!("unresolved variable {:?}", ident)))
+            }
+            ExprKind::Lit {lit, ..} => Ok(ShaderValue::from_val(&lit.to_val())),
+        }
+    }
+
+    fn field_access(&self, base: &Expr, field_ident: Ident) -> Option<Access> {
+        if let Some(Ty::Struct(struct_ptr)) = base.ty.borrow().as_ref() {
+            let struct_def = self.shader_registry.structs.get(struct_ptr).unwrap();
+            return struct_def.fields.iter().position( | field | field.ident == field_ident).map(Access::Field)
+        }
+        Swizzle::parse(field_ident).map(Access::Swizzle)
+    }
+
+    fn eval_bin_expr(&mut self, frame: &mut ShaderFrame<'a>, span: TokenSpan, op: BinOp, left_expr: &'a Expr, right_expr: &'a Expr) -> Result<ShaderValue, LiveError> {
+        let arith_op = match op {
+            BinOp::Assign => {
+                let value = self.eval_expr(frame, right_expr) ?;
+                self.assign(frame, left_expr, value) ?;
+                return Ok(ShaderValue::Void)
+            }
+            BinOp::AddAssign => Some(BinOp::Add),
+            BinOp::SubAssign => Some(BinOp::Sub),
+            BinOp::MulAssign => Some(BinOp::Mul),
+            BinOp::DivAssign => Some(BinOp::Div),
+            BinOp::Or | BinOp::And => {
+                let left = self.eval_bool(frame, left_expr) ?;
+                if left == matches!(op, BinOp::Or) {
+                    return Ok(ShaderValue::Bool(left))
+                }
+                return Ok(ShaderValue::Bool(self.eval_bool(frame, right_expr) ?))
+            }
+            _ => None
+        };
+        let left = self.eval_expr(frame, left_expr) ?;
+        let right = self.eval_expr(frame, right_expr) ?;
+        if let Some(arith_op) = arith_op {
+            let value = arith(arith_op, &left, &right).ok_or_else( || {
+                error(span, format!("cannot apply {} to {:?} and {:?}", op, left, right))
+            }) ?;
+            self.assign(frame, left_expr, value) ?;
+            return Ok(ShaderValue::Void)
+        }
+        let result = match op {
+            BinOp::Eq => Some(ShaderValue::Bool(left == right)),
+            BinOp::Ne => Some(ShaderValue::Bool(left != right)),
+            BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => match (left.to_f32(), right.to_f32()) {
+                (Some(a), Some(b)) => Some(ShaderValue::Bool(match op {
+                    BinOp::Lt => a < b,
+                    BinOp::Le => a <= b,
+                    BinOp::Gt => a > b,
+                    _ => a >= b
+                })),
+                _ => None
+            }
+            _ => arith(op, &left, &right)
+        };
+        result.ok_or_else( || error(span, format!("cannot apply {} to {:?} and {:?}", op, left, right)))
+    }
+
+    fn resolve_place(&mut self, frame: &mut ShaderFrame<'a>, expr: &'a Expr, path: &mut Vec<Access>) -> Result<Place, LiveError> {
+        match &expr.kind {
+            ExprKind::Var {kind, ..} => match kind.get() {
+                Some(VarKind::Local {ident, shadow}) | Some(VarKind::MutLocal {ident, shadow}) => Ok(Place::Local(ident, shadow)),
+                _ => Err(error(expr.span, "cannot assign to this variable".to_string()))
+            }
+            ExprKind::Field {expr: base, field_ident, ..} => {
+                if let Some(Ty::DrawShader(_)) = base.ty.borrow().as_ref() {
+                    return Ok(Place::Field(*field_ident))
+                }
+                let place = self.resolve_place(frame, base, path) ?;
+                path.push(self.field_access(base, *field_ident).ok_or_else( || {
+                    error(expr.span, format!("unknown field {}", field_ident))
+                }) ?);
+                Ok(place)
+            }
+            ExprKind::Index {expr: base, index_expr, ..} => {
+                let place = self.resolve_place(frame, base, path) ?;
+                let index = self.eval_int(frame, index_expr) ?;
+                path.push(Access::Index(index.max(0) as usize));
+                Ok(place)
+            }
+            _ => Err(error(expr.span, "expression is not assignable".to_string()))
+        }
+    }
+
+    fn assign(&mut self, frame: &mut ShaderFrame<'a>, expr: &'a Expr, value: ShaderValue) -> Result<(), LiveError> {
+        let mut path = Vec::new();
+        let target = match self.resolve_place(frame, expr, &mut path) ? {
+            Place::Local(ident, shadow) => frame.get_mut(ident, shadow),
+            Place::Field(ident) => self.fields.get_mut(&ident),
+        };
+        let target = target.ok_or_else( || error(expr.span, "assignment to a variable without value".to_string())) ?;
+        store(target, &path, value).ok_or_else( || error(expr.span, "invalid assignment".to_string()))
+    }
+
+    // params before `param_offset` have no argument expression, the draw shader `self`
+    fn call_fn(&mut self, frame: &mut ShaderFrame<'a>, fn_def: &'a FnDef, param_offset: usize, arg_exprs: &'a [Expr]) -> Result<ShaderValue, LiveError> {
+        let mut callee = ShaderFrame::new(fn_def);
+        let mut inouts = Vec::new();
+        for (index, arg_expr) in arg_exprs.iter().enumerate() {
+            let param_index = index + param_offset;
+            let param = &fn_def.params[param_index];
+            if let Some(Ty::ClosureDef(closure_def_index)) = arg_expr.ty.borrow().as_ref() {
+                callee.closures[param_index] = Some(Rc::new(self.make_closure(frame, *closure_def_index)));
+                continue;
+            }
+            let value = self.eval_expr(frame, arg_expr) ?;
+            if let Some(shadow) = param.shadow.get() {
+                callee.set(param.ident, shadow, value);
+                if param.is_inout {
+                    inouts.push((arg_expr, param.ident, shadow));
+                }
+            }
+        }
+        let result = self.run_fn_body(&mut callee) ?;
+        for (arg_expr, ident, shadow) in inouts {
+            let value = callee.get(ident, shadow).cloned().unwrap();
+            self.assign(frame, arg_expr, value) ?;
+        }
+        Ok(result)
+    }
+
+    fn make_closure(&self, frame: &ShaderFrame<'a>, closure_def_index: ClosureDefIndex) -> ShaderClosure {
+        let closure_def = &frame.fn_def.closure_defs[closure_def_index.0];
+        let mut closed_over = Vec::new();
+        for sym in closure_def.closed_over_syms.borrow().as_ref().unwrap() {
+            if let Some(value) = frame.get(sym.ident, sym.shadow) {
+                closed_over.push((sym.ident, sym.shadow, value.clone()));
+            }
+        }
+        ShaderClosure {
+            fn_ptr: frame.fn_def.fn_ptr,
+            closure_def_index,
+            closed_over
+        }
+    }
+
+    fn call_closure(&mut self, frame: &mut ShaderFrame<'a>, span: TokenSpan, param_index: usize, arg_exprs: &'a [Expr]) -> Result<ShaderValue, LiveError> {
+        let closure = frame.closures.get(param_index).cloned().flatten().ok_or_else( || {
+            error(span, "call of a closure parameter without closure".to_string())
+        }) ?;
+        let decl_params = match &frame.fn_def.params[param_index].ty_expr.kind {
+            TyExprKind::ClosureDecl {params, ..} => params,
+            _ => return Err(error(span, "closure parameter without closure type".to_string()))
+        };
+        let fn_def = self.shader_registry.all_fns.get(&closure.fn_ptr).unwrap();
+        let closure_def = &fn_def.closure_defs[closure.closure_def_index.0];
+
+        let mut callee = ShaderFrame::new(fn_def);
+        callee.locals = closure.closed_over.clone();
+        let mut inouts = Vec::new();
+        for (index, arg_expr) in arg_exprs.iter().enumerate() {
+            let value = self.eval_expr(frame, arg_expr) ?;
+            let param = &closure_def.params[index];
+            let shadow = param.shadow.get().unwrap();
+            callee.set(param.ident, shadow, value);
+            if decl_params[index].is_inout {
+                inouts.push((arg_expr, param.ident, shadow));
+            }
+        }
+        let result = match &closure_def.kind {
+            ClosureDefKind::Expr(expr) => self.eval_expr(&mut callee, expr) ?,
+            ClosureDefKind::Block(block) => match self.run_block(&mut callee, block) ? {
+                Flow::Return(value) => value,
+                _ => ShaderValue::Void
+            }
+        };
+        for (arg_expr, ident, shadow) in inouts {
+            let value = callee.get(ident, shadow).cloned().unwrap();
+            self.assign(frame, arg_expr, value) ?;
+        }
+        Ok(result)
+    }
+
+    fn derivative(&mut self, value: &ShaderValue, is_x: bool) -> Option<ShaderValue> {
+        let index = self.derivatives.index;
+        self.derivatives.index += 1;
+        self.derivatives.record.push(value.clone());
+        let neighbour = if is_x {&self.derivatives.right} else {&self.derivatives.below};
+        match neighbour.get(index) {
+            Some(neighbour) if neighbour.float_lanes().map(|(len, _)| len) == value.float_lanes().map(|(len, _)| len) => {
+                map2(neighbour, value, | a, b | a - b)
+            }
+            _ => map1(value, | _ | 0.0)
+        }
+    }
+
+    fn call_builtin(&mut self, ident: Ident, args: &[ShaderValue]) -> Option<ShaderValue> {
+        let a = args.first();
+        let b = args.get(1);
+        let c = args.get(2);
+        Some(match ident.0 {
+            live_id!(abs) => {
+                if let Some(Lanes::Int(len, mut v)) = a?.to_lanes() {
+                    for v in &mut v[0..len] {*v = v.wrapping_abs()}
+                    return Some(ShaderValue::from_lanes(Lanes::Int(len, v)))
+                }
+                map1(a?, f32::abs) ?
+            }
+            live_id!(acos) => map1(a?, f32::acos) ?,
+            live_id!(asin) => map1(a?, f32::asin) ?,
+            live_id!(atan) => match b {
+                Some(b) => map2(a?, b, f32::atan2) ?,
+                None => map1(a?, f32::atan) ?
+            }
+            live_id!(ceil) => map1(a?, f32::ceil) ?,
+            live_id!(cos) => map1(a?, f32::cos) ?,
+            live_id!(degrees) => map1(a?, f32::to_degrees) ?,
+            live_id!(exp) => map1(a?, f32::exp) ?,
+            live_id!(exp2) => map1(a?, f32::exp2) ?,
+            live_id!(floor) => map1(a?, f32::floor) ?,
+            live_id!(fract) => map1(a?, | v | v - v.floor()) ?,
+            live_id!(inversesqrt) => map1(a?, | v | 1.0 / v.sqrt()) ?,
+            live_id!(log) => map1(a?, f32::ln) ?,
+            live_id!(log2) => map1(a?, f32::log2) ?,
+            live_id!(radians) => map1(a?, f32::to_radians) ?,
+            live_id!(sign) => map1(a?, | v | if v > 0.0 {1.0} else if v < 0.0 {-1.0} else {0.0}) ?,
+            live_id!(sin) => map1(a?, f32::sin) ?,
+            live_id!(sqrt) => map1(a?, f32::sqrt) ?,
+            live_id!(tan) => map1(a?, f32::tan) ?,
+            live_id!(max) => map2(a?, b?, f32::max) ?,
+            live_id!(min) => map2(a?, b?, f32::min) ?,
+            live_id!(mod) => map2(a?, b?, | x, y | x - y * (x / y).floor()) ?,
+            live_id!(pow) => map2(a?, b?, f32::powf) ?,
+            live_id!(step) => map2(a?, b?, | edge, x | if x < edge {0.0} else {1.0}) ?,
+            live_id!(clamp) => map3(a?, b?, c?, | x, lo, hi | x.max(lo).min(hi)) ?,
+            live_id!(mix) => map3(a?, b?, c?, | x, y, t | x * (1.0 - t) + y * t) ?,
+            live_id!(smoothstep) => map3(a?, b?, c?, | e0, e1, x | {
+                let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+                t * t * (3.0 - 2.0 * t)
+            }) ?,
+            live_id!(length) => ShaderValue::Float(dot(a?, a?) ?.sqrt()),
+            live_id!(distance) => {
+                let d = map2(a?, b?, | x, y | x - y) ?;
+                ShaderValue::Float(dot(&d, &d) ?.sqrt())
+            }
+            live_id!(dot) => ShaderValue::Float(dot(a?, b?) ?),
+            live_id!(normalize) => {
+                let len = dot(a?, a?) ?.sqrt();
+                map1(a?, | v | v / len) ?
+            }
+            live_id!(cross) => {
+                let (a, b) = match (a?, b?) {
+                    (ShaderValue::Vec3(a), ShaderValue::Vec3(b)) => (*a, *b),
+                    _ => return None
+                };
+                ShaderValue::Vec3(vec3(a.y * b.z - a.z * b.y, a.z * b.x - a.x * b.z, a.x * b.y - a.y * b.x))
+            }
+            live_id!(reflect) => {
+                let d = dot(b?, a?) ?;
+                map2(a?, b?, | i, n | i - 2.0 * d * n) ?
+            }
+            live_id!(refract) => {
+                let eta = c?.to_f32() ?;
+                let d = dot(b?, a?) ?;
+                let k = 1.0 - eta * eta * (1.0 - d * d);
+                if k < 0.0 {
+                    map1(a?, | _ | 0.0) ?
+                }
+                else {
+                    map2(a?, b?, | i, n | eta * i - (eta * d + k.sqrt()) * n) ?
+                }
+            }
+            live_id!(faceforward) => {
+                if dot(c?, b?) ? < 0.0 {a?.clone()} else {map1(a?, | v | -v) ?}
+            }
+            live_id!(dFdx) => self.derivative(a?, true) ?,
+            live_id!(dFdy) => self.derivative(a?, false) ?,
+            live_id!(all) | live_id!(any) => {
+                let lanes = a?.to_lanes() ?;
+                let mut bits = (0..lanes.len()).map( | i | lanes.to_bool(i));
+                ShaderValue::Bool(if ident.0 == live_id!(all) {bits.all( | v | v)} else {bits.any( | v | v)})
+            }
+            live_id!(not) => {
+                let lanes = a?.to_lanes() ?;
+                let mut out = [false; 4];
+                for (i, out) in out.iter_mut().enumerate().take(lanes.len()) {*out = !lanes.to_bool(i)}
+                ShaderValue::from_lanes(Lanes::Bool(lanes.len(), out))
+            }
+            live_id!(equal) => compare(a?, b?, | x, y | x == y) ?,
+            live_id!(notEqual) => compare(a?, b?, | x, y | x != y) ?,
+            live_id!(lessThan) => compare(a?, b?, | x, y | x < y) ?,
+            live_id!(lessThanEqual) => compare(a?, b?, | x, y | x <= y) ?,
+            live_id!(greaterThan) => compare(a?, b?, | x, y | x > y) ?,
+            live_id!(greaterThanEqual) => compare(a?, b?, | x, y | x >= y) ?,
+            live_id!(matrixCompMult) => {
+                let (dim, am) = a?.to_mat() ?;
+                let (_, bm) = b?.to_mat() ?;
+                ShaderValue::from_mat(dim, &am.iter().zip(bm).map( | (a, b) | a * b).collect::<Vec<_ >> ())
+            }
+            live_id!(transpose) => {
+                let (dim, m) = a?.to_mat() ?;
+                let mut out = vec![0.0; dim * dim];
+                for col in 0..dim {
+                    for row in 0..dim {
+                        out[row * dim + col] = m[col * dim + row];
+                    }
+                }
+                ShaderValue::from_mat(dim, &out)
+            }
+            live_id!(inverse) => match a? {
+                ShaderValue::Mat4(m) => ShaderValue::Mat4(m.invert()),
+                _ => return None
+            }
+            live_id!(sample2d) | live_id!(sample2d_rt) | live_id!(sample2dOES) => {
+                let texture = match a? {
+                    ShaderValue::Texture(texture) => *texture,
+                    _ => return None
+                };
+                let pos = b?.to_vec2() ?;
+                ShaderValue::Vec4(match &self.sampler {
+                    Some(sampler) => sampler(texture, pos),
+                    None => Vec4::default()
+                })
+            }
+            _ => return None
+        })
+    }
+}
@@ -14,13 +14,14 @@
 pub mod swizzle;
 pub mod util;
 pub mod generate;
+pub mod interpret;
 
-#[cfg(any(target_os = "android", target_os = "linux", target_arch = "wasm32"))]
+// every backend is built on every platform, so that the generated code of each can be
+// checked against its snapshots in draw/tests/generate.rs
 pub mod generate_glsl;
-#[cfg(any(target_os = "macos", target_os="ios", target_os="tvos"))]
 pub mod generate_metal;
-#[cfg(any(target_os = "windows"))]
 pub mod generate_hlsl;
+pub mod generate_wgsl;
 
 pub use makepad_live_compiler;
 pub use makepad_live_compiler::makepad_math;
@@ -45,6 +46,10 @@
             ShaderEnum,
             ShaderRegistry,
             DrawShaderQuery
+        },
+        interpret::{
+            ShaderInterpreter,
+            ShaderValue,
         }
     }
 };
@@ -917,6 +917,10 @@ pub fn from_mul_op(token: LiveToken) -> Option<BinOp> {
             _ => None,
         }
     }
+    
+    pub fn is_assign(&self) -> bool {
+        matches!(self, BinOp::Assign | BinOp::AddAssign | BinOp::SubAssign | BinOp::MulAssign | BinOp::DivAssign)
+    }
 }
 
 impl fmt::Display for BinOp {
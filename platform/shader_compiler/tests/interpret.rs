@@ -0,0 +1,217 @@
+use makepad_shader_compiler::{
+    makepad_live_compiler::*,
+    makepad_live_id::{LiveId, live_id},
+    makepad_math::*,
+    DrawShaderPtr,
+    ShaderInterpreter,
+    ShaderRegistry,
+    ShaderValue,
+};
+
+struct TestShader;
+
+// registers a live file holding `TestShader = {{TestShader}} {..}` and analyses the draw shader
+fn compile(source: &str) -> (LiveRegistry, ShaderRegistry, DrawShaderPtr) {
+    let type_info = LiveTypeInfo {
+        live_type: LiveType::of::<TestShader>(),
+        type_name: LiveId::from_str("TestShader"),
+        module_id: LiveModuleId::from_str("test::interpret").unwrap(),
+        live_ignore: false,
+        fields: Vec::new(),
+    };
+    let mut live_registry = LiveRegistry::default();
+    live_registry.main_module = Some(type_info.clone());
+    live_registry.register_live_file(
+        "interpret.rs",
+        "",
+        type_info.module_id,
+        source.to_string(),
+        vec![type_info.clone()],
+        TextPos {line: 0, column: 0}
+    ).unwrap_or_else( | e | panic!("{}", e.message));
+    // the expander always looks for this file
+    live_registry.register_live_file(
+        "draw_trapezoid.rs",
+        "",
+        LiveModuleId::from_str("test::draw_trapezoid").unwrap(),
+        String::new(),
+        Vec::new(),
+        TextPos {line: 0, column: 0}
+    ).unwrap_or_else( | e | panic!("{}", e.message));
+    let mut errors = Vec::new();
+    live_registry.expand_all_documents(&mut errors);
+    assert!(errors.is_empty(), "{:?}", errors.iter().map( | e | &e.message).collect::<Vec<_ >> ());
+
+    let ptr = live_registry.module_id_and_name_to_ptr(type_info.module_id, live_id!(TestShader)).unwrap();
+    let mut shader_registry = ShaderRegistry::new(false);
+    shader_registry.analyse_draw_shader(&live_registry, DrawShaderPtr(ptr), | _, _, _, _, _, _ | {})
+        .unwrap_or_else( | e | panic!("{}", e.message));
+    (live_registry, shader_registry, DrawShaderPtr(ptr))
+}
+
+fn assert_near(a: Vec4, b: Vec4) {
+    let d = a - b;
+    assert!(d.x.abs() + d.y.abs() + d.z.abs() + d.w.abs() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn vertex_writes_varyings_for_pixel() {
+    let (live_registry, shader_registry, ptr) = compile(r#"
+        TestShader = {{TestShader}} {
+            uniform tint: #f80
+            instance scale: 2.0
+            varying pos: vec2
+            fn vertex(self) -> vec4 {
+                self.pos = vec2(0.25, 0.5) * self.scale;
+                return vec4(self.pos, 0.0, 1.0);
+            }
+            fn pixel(self) -> vec4 {
+                return vec4(self.tint.rgb * self.pos.y, self.tint.a);
+            }
+        }
+    "#);
+    let def = shader_registry.draw_shader_defs.get(&ptr).unwrap();
+    let mut interp = ShaderInterpreter::new(&shader_registry, def);
+    interp.load_live_values(&live_registry).unwrap();
+    assert_near(interp.run_vertex().unwrap(), vec4(0.5, 1.0, 0.0, 1.0));
+    assert_near(interp.run_pixel().unwrap(), vec4(1.0, 0.53333336, 0.0, 1.0));
+
+    interp.set_field(live_id!(scale), ShaderValue::Float(1.0));
+    interp.run_vertex().unwrap();
+    assert_eq!(interp.field(live_id!(pos)), Some(&ShaderValue::Vec2(vec2(0.25, 0.5))));
+    assert!(interp.set_field_slots(live_id!(tint), &[0.0, 0.0, 1.0, 1.0]));
+    assert_near(interp.run_pixel().unwrap(), vec4(0.0, 0.0, 0.5, 1.0));
+}
+
+#[test]
+fn control_flow_structs_and_closures() {
+    let (_, shader_registry, ptr) = compile(r#"
+        TestShader = {{TestShader}} {
+            Acc = struct {
+                field sum: float
+                field count: float
+                fn add(inout self, v: float) {
+                    self.sum += v;
+                    self.count += 1.0;
+                }
+            }
+            fn apply(self, v: float, f: fn(x: float) -> float) -> float {
+                return f(v) * 2.0;
+            }
+            fn bump(self, inout v: vec2) {
+                v.yx = v.xy + vec2(1.0, 2.0);
+            }
+            fn vertex(self) -> vec4 {
+                return vec4(0.0);
+            }
+            fn pixel(self) -> vec4 {
+                let acc = Acc {sum: 0.0, count: 0.0};
+                for i in 0..5 {
+                    if float(i) == 3.0 {
+                        continue;
+                    }
+                    acc.add(float(i));
+                }
+                let offset = 10.0;
+                let c = self.apply(acc.sum, |x| x + offset);
+                let v = vec2(1.0, 2.0);
+                self.bump(v);
+                let m = mat2(1.0, 2.0, 3.0, 4.0);
+                let mv = m * vec2(1.0, 1.0);
+                return vec4(acc.count, c, v.x + v.y * 10.0, mv.y + (vec2(0.0, 1.0) * m).y);
+            }
+        }
+    "#);
+    let def = shader_registry.draw_shader_defs.get(&ptr).unwrap();
+    let mut interp = ShaderInterpreter::new(&shader_registry, def);
+    // sum is 0+1+2+4, the closure adds 10 and apply doubles it
+    // bump swaps to (4, 2), column major m * (1, 1) is (4, 6) and (0, 1) * m picks column 1
+    assert_near(interp.run_pixel().unwrap(), vec4(4.0, 34.0, 24.0, 10.0));
+}
+
+#[test]
+fn builtins_and_helpers() {
+    let (_, shader_registry, ptr) = compile(r#"
+        TestShader = {{TestShader}} {
+            fn circle(self, p: vec2, r: float) -> float {
+                return length(p) - r;
+            }
+            fn vertex(self) -> vec4 {
+                return vec4(0.0);
+            }
+            fn pixel(self) -> vec4 {
+                let a = clamp(vec3(-1.0, 0.5, 2.0), 0.0, 1.0);
+                let s = smoothstep(0.0, 1.0, 0.5);
+                let d = dot(normalize(vec2(3.0, 4.0)), vec2(3.0, 4.0));
+                return vec4(a.x + a.y + a.z, s, d, mix(2.0, 4.0, 0.25) + step(0.5, 0.2));
+            }
+        }
+    "#);
+    let def = shader_registry.draw_shader_defs.get(&ptr).unwrap();
+    let mut interp = ShaderInterpreter::new(&shader_registry, def);
+    assert_near(interp.run_pixel().unwrap(), vec4(1.5, 0.5, 5.0, 2.5));
+    let d = interp.call_method(live_id!(circle), &[ShaderValue::Vec2(vec2(3.0, 4.0)), ShaderValue::Float(2.0)]).unwrap();
+    assert_eq!(d, ShaderValue::Float(3.0));
+}
+
+#[test]
+fn textures_and_derivatives() {
+    let (_, shader_registry, ptr) = compile(r#"
+        TestShader = {{TestShader}} {
+            texture image: texture2d
+            varying uv: vec2
+            fn vertex(self) -> vec4 {
+                return vec4(0.0);
+            }
+            fn pixel(self) -> vec4 {
+                let texel = sample2d(self.image, self.uv);
+                let scale = length(vec2(dFdx(self.uv.x), dFdy(self.uv.y)));
+                return vec4(texel.xy, scale, 1.0);
+            }
+        }
+    "#);
+    let def = shader_registry.draw_shader_defs.get(&ptr).unwrap();
+    let mut interp = ShaderInterpreter::new(&shader_registry, def);
+    interp.set_sampler( | texture, pos | {
+        assert_eq!(texture.0, live_id!(image));
+        vec4(pos.x, pos.y, 0.0, 1.0)
+    });
+    interp.set_field(live_id!(uv), ShaderValue::Vec2(vec2(0.5, 0.25)));
+    assert_near(interp.run_pixel().unwrap(), vec4(0.5, 0.25, 0.0, 1.0));
+    let color = interp.run_pixel_with_neighbours(
+        &[(live_id!(uv), ShaderValue::Vec2(vec2(0.53, 0.25)))],
+        &[(live_id!(uv), ShaderValue::Vec2(vec2(0.5, 0.29)))],
+    ).unwrap();
+    assert_near(color, vec4(0.5, 0.25, 0.05, 1.0));
+    // the neighbour values are not left behind
+    assert_eq!(interp.field(live_id!(uv)), Some(&ShaderValue::Vec2(vec2(0.5, 0.25))));
+}
+
+#[test]
+fn step_hook_sees_locals() {
+    let (_, shader_registry, ptr) = compile(r#"
+        TestShader = {{TestShader}} {
+            fn vertex(self) -> vec4 {
+                return vec4(0.0);
+            }
+            fn pixel(self) -> vec4 {
+                let x = 1.0;
+                let x = x + 1.0;
+                return vec4(x);
+            }
+        }
+    "#);
+    let def = shader_registry.draw_shader_defs.get(&ptr).unwrap();
+    let steps = std::cell::RefCell::new(Vec::new());
+    let mut interp = ShaderInterpreter::new(&shader_registry, def);
+    interp.set_step_hook( | _span, frame | {
+        steps.borrow_mut().push(frame.local(live_id!(x)).cloned());
+    });
+    interp.run_pixel().unwrap();
+    drop(interp);
+    assert_eq!(steps.into_inner(), vec![
+        None,
+        Some(ShaderValue::Float(1.0)),
+        Some(ShaderValue::Float(2.0))
+    ]);
+}
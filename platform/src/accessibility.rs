@@ -0,0 +1,352 @@
+use {
+    std::collections::{HashMap, HashSet},
+    crate::{
+        makepad_math::Rect,
+        area::Area,
+        cx::Cx,
+    }
+};
+
+/// Identifies a node in the accessibility tree. Widgets use their widget uid, other contributors only need an
+/// id that is unique within the tree and stable across frames.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AccessibilityId(pub u64);
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AccessibilityRole {
+    Window,
+    Group,
+    Button,
+    CheckBox,
+    RadioButton,
+    Slider,
+    TextInput,
+    Label,
+    List,
+    TabList,
+    Tab,
+}
+
+/// The things an assistive technology can ask a node to do.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AccessibilityAction {
+    Click,
+    Focus,
+    SetValue,
+    Increment,
+    Decrement,
+    Select,
+    Close,
+}
+
+/// The numeric value of a slider like node.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AccessibilityRange {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+/// The semantic description of a single widget, as a screen reader would present it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityNode {
+    pub id: AccessibilityId,
+    pub role: AccessibilityRole,
+    pub name: String,
+    pub value: Option<String>,
+    pub range: Option<AccessibilityRange>,
+    pub checked: Option<bool>,
+    pub selected: bool,
+    pub focused: bool,
+    pub disabled: bool,
+    pub actions: Vec<AccessibilityAction>,
+    /// The area the node was drawn in, and its rect at the end of drawing.
+    pub area: Area,
+    pub rect: Rect,
+    pub parent: Option<AccessibilityId>,
+    pub children: Vec<AccessibilityId>,
+}
+
+impl AccessibilityNode {
+    pub fn new(role: AccessibilityRole) -> Self {
+        Self {
+            id: AccessibilityId::default(),
+            role,
+            name: String::new(),
+            value: None,
+            range: None,
+            checked: None,
+            selected: false,
+            focused: false,
+            disabled: false,
+            actions: Vec::new(),
+            area: Area::Empty,
+            rect: Rect::default(),
+            parent: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn with_range(mut self, range: AccessibilityRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn with_focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    pub fn with_action(mut self, action: AccessibilityAction) -> Self {
+        if !self.actions.contains(&action) {
+            self.actions.push(action);
+        }
+        self
+    }
+
+    pub fn has_action(&self, action: AccessibilityAction) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+struct AccessibilityScope {
+    id: AccessibilityId,
+    is_node: bool,
+    children: Vec<AccessibilityId>,
+}
+
+/// The accessibility tree of the application, rebuilt while drawing.
+///
+/// Contributors open a node with `begin_node` before drawing their content and close it with `end_node`
+/// afterwards, nodes opened in between become its children. Contributors without a node of their own are
+/// transparent, their content goes to the nearest node around them. Parts of the ui that are not redrawn in a
+/// frame keep their nodes for as long as their area stays valid.
+#[derive(Default)]
+pub struct AccessibilityTree {
+    nodes: HashMap<AccessibilityId, AccessibilityNode>,
+    roots: Vec<AccessibilityId>,
+    stack: Vec<AccessibilityScope>,
+    drawn: HashSet<AccessibilityId>,
+}
+
+impl AccessibilityTree {
+    pub fn begin_frame(&mut self) {
+        self.stack.clear();
+        self.drawn.clear();
+    }
+
+    /// Opens a node, or a transparent scope when `node` is None. Opening an id that is still open resumes it,
+    /// which happens when drawing continues after a draw step.
+    pub fn begin_node(&mut self, id: AccessibilityId, node: Option<AccessibilityNode>) {
+        let is_open = self.stack.iter().any( | scope | scope.id == id);
+        if let Some(mut node) = node {
+            node.id = id;
+            if let Some(old) = self.nodes.get(&id) {
+                node.parent = old.parent;
+                node.children = old.children.clone();
+                node.area = old.area;
+                node.rect = old.rect;
+            }
+            self.nodes.insert(id, node);
+            self.drawn.insert(id);
+            if !is_open {
+                self.stack.push(AccessibilityScope {id, is_node: true, children: Vec::new()});
+            }
+        }
+        else if !is_open {
+            self.stack.push(AccessibilityScope {id, is_node: false, children: Vec::new()});
+        }
+    }
+
+    /// Closes the node opened with `id`. Children from an earlier frame that were not drawn now are kept when
+    /// `is_alive` says their area is still valid, so cached parts of the ui stay in the tree.
+    pub fn end_node(&mut self, id: AccessibilityId, area: Area, rect: Rect, is_alive: &dyn Fn(Area) -> bool) {
+        let Some(index) = self.stack.iter().rposition( | scope | scope.id == id) else {
+            return
+        };
+        // anything still open above us was abandoned halfway
+        self.stack.truncate(index + 1);
+        let scope = self.stack.pop().unwrap();
+        if !scope.is_node {
+            return
+        }
+        let mut children = scope.children;
+        let old_children = self.nodes.get(&id).map( | node | node.children.clone()).unwrap_or_default();
+        for child in old_children {
+            if children.contains(&child) || self.drawn.contains(&child) {
+                continue
+            }
+            if self.nodes.get(&child).is_some_and( | node | is_alive(node.area)) {
+                children.push(child);
+            }
+            else {
+                self.remove_subtree(child);
+            }
+        }
+        for child in &children {
+            if let Some(node) = self.nodes.get_mut(child) {
+                node.parent = Some(id);
+            }
+        }
+        let parent = self.stack.iter_mut().rev().find( | scope | scope.is_node);
+        let parent_id = parent.as_ref().map( | scope | scope.id);
+        if let Some(parent) = parent {
+            if !parent.children.contains(&id) {
+                parent.children.push(id);
+            }
+        }
+        else if !self.roots.contains(&id) {
+            self.roots.push(id);
+        }
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.children = children;
+            node.parent = parent_id;
+            node.area = area;
+            node.rect = rect;
+        }
+    }
+
+    /// Drops the roots that went away and every node that is no longer reachable from a root.
+    pub fn end_frame(&mut self, is_alive: &dyn Fn(Area) -> bool) {
+        self.stack.clear();
+        let nodes = &self.nodes;
+        let drawn = &self.drawn;
+        self.roots.retain( | id | {
+            drawn.contains(id) || nodes.get(id).is_some_and( | node | node.parent.is_none() && is_alive(node.area))
+        });
+        let mut reachable = HashSet::new();
+        let mut todo = self.roots.clone();
+        while let Some(id) = todo.pop() {
+            if reachable.insert(id) {
+                if let Some(node) = self.nodes.get(&id) {
+                    todo.extend(node.children.iter().cloned());
+                }
+            }
+        }
+        self.nodes.retain( | id, _ | reachable.contains(id));
+    }
+
+    fn remove_subtree(&mut self, id: AccessibilityId) {
+        if let Some(node) = self.nodes.remove(&id) {
+            for child in node.children {
+                if !self.drawn.contains(&child) {
+                    self.remove_subtree(child);
+                }
+            }
+        }
+    }
+
+    pub fn roots(&self) -> &[AccessibilityId] {
+        &self.roots
+    }
+
+    pub fn node(&self, id: AccessibilityId) -> Option<&AccessibilityNode> {
+        self.nodes.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn parent(&self, id: AccessibilityId) -> Option<&AccessibilityNode> {
+        self.nodes.get(&id).and_then( | node | node.parent).and_then( | parent | self.nodes.get(&parent))
+    }
+
+    pub fn children(&self, id: AccessibilityId) -> Vec<&AccessibilityNode> {
+        self.nodes.get(&id).map( | node | {
+            node.children.iter().filter_map( | child | self.nodes.get(child)).collect()
+        }).unwrap_or_default()
+    }
+
+    /// All nodes in depth first order, starting with the first root.
+    pub fn iter(&self) -> Vec<&AccessibilityNode> {
+        fn walk<'a>(tree: &'a AccessibilityTree, id: AccessibilityId, out: &mut Vec<&'a AccessibilityNode>) {
+            if let Some(node) = tree.nodes.get(&id) {
+                out.push(node);
+                for child in &node.children {
+                    walk(tree, *child, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        for root in &self.roots {
+            walk(self, *root, &mut out);
+        }
+        out
+    }
+
+    pub fn find_by_role(&self, role: AccessibilityRole) -> Vec<&AccessibilityNode> {
+        self.iter().into_iter().filter( | node | node.role == role).collect()
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&AccessibilityNode> {
+        self.iter().into_iter().find( | node | node.name == name)
+    }
+
+    pub fn focused(&self) -> Option<&AccessibilityNode> {
+        self.iter().into_iter().find( | node | node.focused)
+    }
+}
+
+impl Cx {
+    pub fn accessibility_tree(&self) -> &AccessibilityTree {
+        &self.accessibility_tree
+    }
+
+    pub fn begin_accessibility_node(&mut self, id: AccessibilityId, node: Option<AccessibilityNode>) {
+        self.accessibility_tree.begin_node(id, node);
+    }
+
+    pub fn end_accessibility_node(&mut self, id: AccessibilityId, area: Area) {
+        let rect = if area.is_valid(self) {area.rect(self)} else {Rect::default()};
+        let mut tree = std::mem::take(&mut self.accessibility_tree);
+        tree.end_node(id, area, rect, &| area | area.is_valid(self));
+        self.accessibility_tree = tree;
+    }
+
+    /// Adds a node without children, for parts of a widget that are not widgets themselves.
+    pub fn add_accessibility_node(&mut self, id: AccessibilityId, node: AccessibilityNode, area: Area) {
+        self.begin_accessibility_node(id, Some(node));
+        self.end_accessibility_node(id, area);
+    }
+
+    pub (crate) fn begin_accessibility_frame(&mut self) {
+        self.accessibility_tree.begin_frame();
+    }
+
+    pub (crate) fn end_accessibility_frame(&mut self) {
+        let mut tree = std::mem::take(&mut self.accessibility_tree);
+        tree.end_frame(&| area | area.is_valid(self));
+        self.accessibility_tree = tree;
+    }
+}
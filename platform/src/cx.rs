@@ -23,6 +23,8 @@
         debug::Debug,
         display_context::DisplayContext,
         performance_stats::PerformanceStats,
+        accessibility::AccessibilityTree,
+        file_dialogs::FileDialogEvent,
         event::{
             DrawEvent,
             CxFingers,
@@ -90,6 +92,8 @@ pub struct Cx {
     
     pub new_actions: ActionsBuf,
     
+    pub (crate) new_file_dialog_events: Vec<FileDialogEvent>,
+    
     pub (crate) dependencies: HashMap<String, CxDependency>,
     
     pub (crate) triggers: HashMap<Area, Vec<Trigger >>,
@@ -133,6 +137,8 @@ pub struct Cx {
     /// This is primarily used when adaptive views change their active variant,
     /// as the widget hierarchy changes require parent views to rebuild their widget queries.
     pub widget_query_invalidation_event: Option<u64>,
+
+    pub (crate) accessibility_tree: AccessibilityTree,
 }
 
 #[derive(Clone)]
@@ -186,6 +192,7 @@ pub enum OsType {
     OpenHarmony(OpenHarmonyParams),
     LinuxWindow (LinuxWindowParams),
     LinuxDirect,
+    LinuxHeadless,
     Web(WebParams)
 }
 
@@ -268,6 +275,7 @@ pub fn new(event_handler: Box<dyn FnMut(&mut Cx, &Event)>) -> Self {
             
             new_draw_event: Default::default(),
             new_actions: Default::default(),
+            new_file_dialog_events: Default::default(),
             
             redraw_id: 1,
             event_id: 1,
@@ -313,6 +321,7 @@ pub fn new(event_handler: Box<dyn FnMut(&mut Cx, &Event)>) -> Self {
             display_context: Default::default(),
 
             widget_query_invalidation_event: None,
+            accessibility_tree: Default::default(),
         }
     }
 }
\ No newline at end of file
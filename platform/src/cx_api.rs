@@ -1,4 +1,4 @@
-use crate::file_dialogs::FileDialog;
+use crate::file_dialogs::{FileDialog, FileDialogKind, FileDialogEvent};
 
 use {
     crate::{
@@ -682,21 +682,34 @@ pub fn println_resources(&self) {
     }
 
     pub fn open_system_savefile_dialog(&mut self) {
-        self.platform_ops.push(CxOsOp::SaveFileDialog(FileDialog::new()));
+        self.open_file_dialog(FileDialogKind::SaveFile, FileDialog::new());
     }
 
     pub fn open_system_openfile_dialog(&mut self) {
-        self.platform_ops.push(CxOsOp::SelectFileDialog(FileDialog::new()));
+        self.open_file_dialog(FileDialogKind::SelectFile, FileDialog::new());
     }
 
     pub fn open_system_savefolder_dialog(&mut self) {
-        self.platform_ops.push(CxOsOp::SaveFolderDialog(FileDialog::new()));
-
+        self.open_file_dialog(FileDialogKind::SaveFolder, FileDialog::new());
     }
 
     pub fn open_system_openfolder_dialog(&mut self) {
-        self.platform_ops.push(CxOsOp::SelectFolderDialog(FileDialog::new()));
+        self.open_file_dialog(FileDialogKind::SelectFolder, FileDialog::new());
+    }
+
+    /// Opens a file dialog, the result comes back as `Event::FileDialog`.
+    pub fn open_file_dialog(&mut self, kind: FileDialogKind, settings: FileDialog) {
+        self.platform_ops.push(match kind {
+            FileDialogKind::SaveFile => CxOsOp::SaveFileDialog(settings),
+            FileDialogKind::SelectFile => CxOsOp::SelectFileDialog(settings),
+            FileDialogKind::SaveFolder => CxOsOp::SaveFolderDialog(settings),
+            FileDialogKind::SelectFolder => CxOsOp::SelectFolderDialog(settings),
+        });
+    }
 
+    /// Queues an `Event::FileDialog`, for dialogs that are not native such as a drawn fallback dialog.
+    pub fn send_file_dialog_event(&mut self, event: FileDialogEvent) {
+        self.new_file_dialog_events.push(event);
     }
 
     pub fn event_id(&self) -> u64 {
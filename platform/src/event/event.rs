@@ -24,6 +24,7 @@
         audio::AudioDevicesEvent,
         midi::MidiPortsEvent,
         video::VideoInputsEvent,
+        file_dialogs::FileDialogEvent,
         draw_list::DrawListId,
     },
 };
@@ -220,6 +221,9 @@ pub enum Event {
     ToWasmMsg(ToWasmMsgEvent),
     
     DesignerPick(DesignerPickEvent),
+
+    /// A file dialog opened with one of the file dialog `CxOsOp`s was closed.
+    FileDialog(FileDialogEvent),
 }
 
 impl Event{
@@ -295,6 +299,7 @@ pub fn name_from_u32(v:u32)->&'static str{
             52=>"ToWasmMsg",
             
             53=>"DesignerPick",
+            54=>"FileDialog",
             _=>panic!()
         }
     }
@@ -367,6 +372,7 @@ pub fn to_u32(&self)->u32{
             Self::ToWasmMsg(_)=>52,
             
             Self::DesignerPick(_) =>53,
+            Self::FileDialog(_) =>54,
         }
     }
 
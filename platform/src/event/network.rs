@@ -66,6 +66,10 @@ fn default() -> Self {
 }
 
 
+/// An http request, sent with `Cx::http_request`.
+///
+/// The Linux desktop client (X11 and direct) speaks plain http/1.1 only: an `https` url
+/// gets a `NetworkResponse::HttpRequestError` back instead of a response.
 #[derive(PartialEq, Debug)]
 pub struct HttpRequest {
     pub metadata_id: LiveId,
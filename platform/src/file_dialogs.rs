@@ -1,9 +1,12 @@
 // mildly stripped down version of native_dialog_rs dialog interface.
-use std::path::{PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver, Sender},
+};
 
 
 /// Represents a set of file extensions and their description.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Filter {
     pub description: String,
     pub extensions: Vec<String>,
@@ -11,7 +14,7 @@ pub struct Filter {
 
 /// Builds and shows file dialogs.
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct FileDialog {
     pub filename: Option<String>,
     pub location: Option<PathBuf>,
@@ -81,8 +84,68 @@ pub fn remove_all_filters(mut self) -> Self {
         self
     }
 
+    /// Returns if a file passes the filters, a dialog without filters accepts every file. Extensions
+    /// compare without case and may be given with or without a leading dot.
+    pub fn accepts(&self, path: &Path) -> bool {
+        let mut extensions = self.filters.iter().flat_map( | filter | filter.extensions.iter());
+        if self.filters.is_empty() || extensions.clone().any( | filter_ext | filter_ext == "*") {
+            return true
+        }
+        let Some(ext) = path.extension().and_then( | ext | ext.to_str()) else {
+            return false
+        };
+        extensions.any( | filter_ext | filter_ext.trim_start_matches('.').eq_ignore_ascii_case(ext))
+    }
+}
+
+/// The kind of file dialog, matching the `CxOsOp` that opens it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileDialogKind {
+    SaveFile,
+    SelectFile,
+    SaveFolder,
+    SelectFolder,
+}
+
+impl FileDialogKind {
+    pub fn is_save(&self) -> bool {
+        matches!(self, Self::SaveFile | Self::SaveFolder)
+    }
+
+    pub fn is_folder(&self) -> bool {
+        matches!(self, Self::SaveFolder | Self::SelectFolder)
+    }
+}
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileDialogResponse {
+    Selected(Vec<PathBuf>),
+    Cancelled,
+    /// There is no native dialog on this system. The settings are handed back so a drawn dialog, such as
+    /// the `FileChooser` widget, can take over.
+    Unavailable(FileDialog),
+}
 
+/// Sent as `Event::FileDialog` when a file dialog closes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileDialogEvent {
+    pub kind: FileDialogKind,
+    pub response: FileDialogResponse,
+}
+
+pub struct FileDialogChannel {
+    pub receiver: Receiver<FileDialogEvent>,
+    pub sender: Sender<FileDialogEvent>,
+}
+
+impl Default for FileDialogChannel {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            sender,
+            receiver
+        }
+    }
 }
 
 
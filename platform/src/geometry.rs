@@ -135,6 +135,8 @@ pub struct CxGeometry{
     pub indices: Vec<u32>,
     pub vertices: Vec<f32>,
     pub dirty: bool,
+    // the software renderer reads the vertices directly
+    #[cfg_attr(headless, allow(dead_code))]
     pub os: CxOsGeometry
 }
 
@@ -19,7 +19,9 @@
 
 pub mod thread;
 pub mod audio;
+pub mod wav;
 pub mod midi;
+pub mod midi_file;
 pub mod video;
 pub mod scope;
 
@@ -57,6 +59,8 @@
 
 pub mod display_context;
 
+pub mod accessibility;
+
 #[macro_use]
 mod app_main;
 
@@ -152,7 +156,16 @@
             InstanceArea
         },
         midi::*,
+        midi_file::*,
+        file_dialogs::{
+            FileDialog,
+            FileDialogKind,
+            FileDialogResponse,
+            FileDialogEvent,
+        },
+        accessibility::*,
         audio::*,
+        wav::*,
         thread::*,
         video::*,
         web_socket::{WebSocket,WebSocketMessage},
@@ -0,0 +1,541 @@
+use {
+    std::{
+        fmt,
+        io,
+        path::Path,
+    },
+    crate::midi::{MidiData, MidiInput},
+};
+
+/// How the tracks of a Standard MIDI File relate to each other.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MidiFileFormat {
+    /// Type 0, a single track holding every channel.
+    SingleTrack,
+    /// Type 1, simultaneous tracks sharing the tempo map of the first one.
+    MultiTrack,
+}
+
+/// The unit of the delta times in a Standard MIDI File.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MidiTimeDivision {
+    TicksPerQuarter(u16),
+    /// `frames_per_second` is 24, 25, 29 (for 29.97 drop frame) or 30.
+    Smpte {frames_per_second: u8, ticks_per_frame: u8},
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MidiMetaEvent {
+    /// Microseconds per quarter note.
+    Tempo(u32),
+    /// The denominator is a power of two, so 6/8 is stored as `numerator: 6, denominator: 3`.
+    TimeSignature {numerator: u8, denominator: u8, clocks_per_click: u8, thirty_seconds_per_quarter: u8},
+    TrackName(String),
+    EndOfTrack,
+    Other {kind: u8, data: Vec<u8>},
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MidiTrackEventKind {
+    /// A channel message. Messages with a single data byte leave the last byte zero.
+    Midi(MidiData),
+    /// A system exclusive message, without the leading 0xF0.
+    SysEx(Vec<u8>),
+    /// An escaped sequence of raw bytes, stored in the file behind 0xF7.
+    Escape(Vec<u8>),
+    Meta(MidiMetaEvent),
+}
+
+/// An event in a track, at an absolute tick from the start of the track.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MidiTrackEvent {
+    pub tick: u64,
+    pub kind: MidiTrackEventKind,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MidiTrack {
+    pub events: Vec<MidiTrackEvent>,
+}
+
+impl MidiTrack {
+    /// Adds an event, keeping the events sorted by tick. Events at the same tick stay in the order they were pushed.
+    pub fn push(&mut self, tick: u64, kind: MidiTrackEventKind) {
+        let index = self.events.partition_point( | event | event.tick <= tick);
+        self.events.insert(index, MidiTrackEvent {tick, kind});
+    }
+
+    pub fn push_midi(&mut self, tick: u64, data: impl Into<MidiData>) {
+        self.push(tick, MidiTrackEventKind::Midi(data.into()))
+    }
+
+    pub fn push_meta(&mut self, tick: u64, meta: MidiMetaEvent) {
+        self.push(tick, MidiTrackEventKind::Meta(meta))
+    }
+
+    pub fn end_tick(&self) -> u64 {
+        self.events.last().map( | event | event.tick).unwrap_or(0)
+    }
+}
+
+#[derive(Debug)]
+pub enum MidiFileError {
+    Io(io::Error),
+    NotMidiFile,
+    UnexpectedEnd,
+    UnsupportedFormat(u16),
+    InvalidEvent(&'static str),
+}
+
+impl fmt::Display for MidiFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::NotMidiFile => write!(f, "not a Standard MIDI File"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of file"),
+            Self::UnsupportedFormat(format) => write!(f, "unsupported MIDI file format {}", format),
+            Self::InvalidEvent(reason) => write!(f, "invalid event: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MidiFileError {}
+
+impl From<io::Error> for MidiFileError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The contents of a type 0 or type 1 Standard MIDI File.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MidiFile {
+    pub format: MidiFileFormat,
+    pub division: MidiTimeDivision,
+    pub tracks: Vec<MidiTrack>,
+}
+
+impl MidiFile {
+    pub fn new(format: MidiFileFormat, division: MidiTimeDivision) -> Self {
+        Self {
+            format,
+            division,
+            tracks: Vec::new()
+        }
+    }
+
+    pub fn read_from_path(path: impl AsRef<Path>) -> Result<Self, MidiFileError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> Result<(), MidiFileError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, MidiFileError> {
+        let mut reader = Reader {data, offset: 0};
+        if reader.bytes(4).ok() != Some(b"MThd".as_slice()) {
+            return Err(MidiFileError::NotMidiFile)
+        }
+        let header_size = reader.u32()? as usize;
+        if header_size < 6 {
+            return Err(MidiFileError::NotMidiFile)
+        }
+        let header = reader.bytes(header_size)?;
+        let format = match u16::from_be_bytes([header[0], header[1]]) {
+            0 => MidiFileFormat::SingleTrack,
+            1 => MidiFileFormat::MultiTrack,
+            format => return Err(MidiFileError::UnsupportedFormat(format))
+        };
+        let track_count = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let division = if header[4] & 0x80 == 0 {
+            MidiTimeDivision::TicksPerQuarter(u16::from_be_bytes([header[4], header[5]]))
+        }
+        else {
+            // the frame rate is stored as a negative number
+            MidiTimeDivision::Smpte {frames_per_second: (header[4] as i8).unsigned_abs(), ticks_per_frame: header[5]}
+        };
+        let mut tracks = Vec::with_capacity(track_count);
+        while tracks.len() < track_count {
+            let id = reader.bytes(4)?;
+            let size = reader.u32()? as usize;
+            let chunk = reader.bytes(size)?;
+            // unknown chunks have to be skipped
+            if id == b"MTrk" {
+                tracks.push(read_track(chunk)?);
+            }
+        }
+        Ok(Self {
+            format,
+            division,
+            tracks
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"MThd");
+        out.extend_from_slice(&6u32.to_be_bytes());
+        let format: u16 = match self.format {
+            MidiFileFormat::SingleTrack => 0,
+            MidiFileFormat::MultiTrack => 1,
+        };
+        out.extend_from_slice(&format.to_be_bytes());
+        out.extend_from_slice(&(self.tracks.len() as u16).to_be_bytes());
+        match self.division {
+            MidiTimeDivision::TicksPerQuarter(ticks) => out.extend_from_slice(&(ticks & 0x7fff).to_be_bytes()),
+            MidiTimeDivision::Smpte {frames_per_second, ticks_per_frame} => {
+                out.push((frames_per_second as i8).wrapping_neg() as u8);
+                out.push(ticks_per_frame);
+            }
+        }
+        for track in &self.tracks {
+            let data = write_track(track);
+            out.extend_from_slice(b"MTrk");
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            out.extend_from_slice(&data);
+        }
+        out
+    }
+
+    /// The tempo changes of all tracks, for converting ticks to seconds.
+    pub fn tempo_map(&self) -> MidiTempoMap {
+        let mut tempos = Vec::new();
+        for track in &self.tracks {
+            for event in &track.events {
+                if let MidiTrackEventKind::Meta(MidiMetaEvent::Tempo(micros_per_quarter)) = event.kind {
+                    tempos.push((event.tick, micros_per_quarter));
+                }
+            }
+        }
+        MidiTempoMap::new(self.division, tempos)
+    }
+
+    /// Every channel message of every track with its time in seconds, in playback order.
+    pub fn timed_midi_events(&self) -> Vec<(f64, MidiData)> {
+        let tempo_map = self.tempo_map();
+        let mut events = Vec::new();
+        for track in &self.tracks {
+            for event in &track.events {
+                if let MidiTrackEventKind::Midi(data) = event.kind {
+                    events.push((event.tick, data));
+                }
+            }
+        }
+        // a stable sort keeps simultaneous events in track order
+        events.sort_by_key( | (tick, _) | *tick);
+        events.into_iter().map( | (tick, data) | (tempo_map.tick_to_seconds(tick), data)).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TempoSegment {
+    tick: u64,
+    seconds: f64,
+    seconds_per_tick: f64,
+}
+
+/// Converts between ticks and seconds, following the tempo changes of a file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MidiTempoMap {
+    segments: Vec<TempoSegment>,
+}
+
+impl MidiTempoMap {
+    /// The tempo of a file without tempo events, 120 beats per minute.
+    pub const DEFAULT_MICROS_PER_QUARTER: u32 = 500_000;
+
+    /// Builds a tempo map from `(tick, microseconds per quarter)` pairs, which do not need to be sorted.
+    /// The tempo is ignored for SMPTE divisions, as their ticks have a fixed length.
+    pub fn new(division: MidiTimeDivision, mut tempos: Vec<(u64, u32)>) -> Self {
+        let seconds_per_quarter_tick = match division {
+            MidiTimeDivision::TicksPerQuarter(ticks) => 1e-6 / ticks.max(1) as f64,
+            MidiTimeDivision::Smpte {frames_per_second, ticks_per_frame} => {
+                let frames_per_second = if frames_per_second == 29 {29.97} else {frames_per_second as f64};
+                return Self {
+                    segments: vec![TempoSegment {
+                        tick: 0,
+                        seconds: 0.0,
+                        seconds_per_tick: 1.0 / (frames_per_second * ticks_per_frame.max(1) as f64)
+                    }]
+                }
+            }
+        };
+        tempos.sort_by_key( | (tick, _) | *tick);
+        let mut segments = vec![TempoSegment {
+            tick: 0,
+            seconds: 0.0,
+            seconds_per_tick: Self::DEFAULT_MICROS_PER_QUARTER as f64 * seconds_per_quarter_tick
+        }];
+        for (tick, micros_per_quarter) in tempos {
+            let last = segments.last_mut().unwrap();
+            let seconds_per_tick = micros_per_quarter as f64 * seconds_per_quarter_tick;
+            // a later tempo event at the same tick wins
+            if last.tick == tick {
+                last.seconds_per_tick = seconds_per_tick;
+            }
+            else {
+                let seconds = last.seconds + (tick - last.tick) as f64 * last.seconds_per_tick;
+                segments.push(TempoSegment {tick, seconds, seconds_per_tick});
+            }
+        }
+        Self {segments}
+    }
+
+    pub fn tick_to_seconds(&self, tick: u64) -> f64 {
+        let index = self.segments.partition_point( | segment | segment.tick <= tick) - 1;
+        let segment = &self.segments[index];
+        segment.seconds + (tick - segment.tick) as f64 * segment.seconds_per_tick
+    }
+
+    /// The tick at `seconds`, rounded to the nearest tick.
+    pub fn seconds_to_tick(&self, seconds: f64) -> u64 {
+        let index = self.segments.partition_point( | segment | segment.seconds <= seconds).max(1) - 1;
+        let segment = &self.segments[index];
+        segment.tick + ((seconds - segment.seconds).max(0.0) / segment.seconds_per_tick).round() as u64
+    }
+}
+
+/// Captures incoming MIDI messages so they can be saved as a Standard MIDI File.
+///
+/// Times are in seconds on any clock that the caller keeps using, such as `Cx::seconds_since_app_start`.
+#[derive(Clone, Debug, Default)]
+pub struct MidiRecorder {
+    start_time: Option<f64>,
+    events: Vec<(f64, MidiData)>,
+}
+
+impl MidiRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts recording, dropping anything recorded before.
+    pub fn start(&mut self, time: f64) {
+        self.start_time = Some(time);
+        self.events.clear();
+    }
+
+    pub fn stop(&mut self) {
+        self.start_time = None;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.start_time.is_some()
+    }
+
+    /// The recorded messages, with their time in seconds from the start of the recording.
+    pub fn events(&self) -> &[(f64, MidiData)] {
+        &self.events
+    }
+
+    /// Records a message received at `time`. System messages are not recorded, as they are not channel messages.
+    pub fn record(&mut self, time: f64, data: MidiData) {
+        if let Some(start_time) = self.start_time {
+            if data.status() != 0xF {
+                self.events.push(((time - start_time).max(0.0), data));
+            }
+        }
+    }
+
+    /// Records every message waiting in `input` at `time`, so the timing is as precise as the polling.
+    pub fn poll(&mut self, time: f64, input: &mut MidiInput) {
+        while let Some((_port, data)) = input.receive() {
+            self.record(time, data);
+        }
+    }
+
+    /// Turns the recording into a type 0 file at a fixed tempo.
+    pub fn to_midi_file(&self, ticks_per_quarter: u16, micros_per_quarter: u32) -> MidiFile {
+        let division = MidiTimeDivision::TicksPerQuarter(ticks_per_quarter);
+        let tempo_map = MidiTempoMap::new(division, vec![(0, micros_per_quarter)]);
+        let mut track = MidiTrack::default();
+        track.push_meta(0, MidiMetaEvent::Tempo(micros_per_quarter));
+        for (time, data) in &self.events {
+            track.push_midi(tempo_map.seconds_to_tick(*time), *data);
+        }
+        let end_tick = track.end_tick();
+        track.push_meta(end_tick, MidiMetaEvent::EndOfTrack);
+        let mut file = MidiFile::new(MidiFileFormat::SingleTrack, division);
+        file.tracks.push(track);
+        file
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn u8(&mut self) -> Result<u8, MidiFileError> {
+        let byte = *self.data.get(self.offset).ok_or(MidiFileError::UnexpectedEnd)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    fn u32(&mut self) -> Result<u32, MidiFileError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], MidiFileError> {
+        let end = self.offset.checked_add(len).filter( | end | *end <= self.data.len()).ok_or(MidiFileError::UnexpectedEnd)?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn var_len(&mut self) -> Result<u32, MidiFileError> {
+        let mut value = 0u32;
+        // variable length quantities are at most four bytes
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7f) as u32;
+            if byte & 0x80 == 0 {
+                return Ok(value)
+            }
+        }
+        Err(MidiFileError::InvalidEvent("variable length quantity is too long"))
+    }
+}
+
+fn channel_message_len(status: u8) -> usize {
+    match status >> 4 {
+        0xC | 0xD => 1,
+        _ => 2
+    }
+}
+
+fn read_track(data: &[u8]) -> Result<MidiTrack, MidiFileError> {
+    let mut reader = Reader {data, offset: 0};
+    let mut track = MidiTrack::default();
+    let mut tick = 0u64;
+    let mut running_status = None;
+    while !reader.is_empty() {
+        tick += reader.var_len()? as u64;
+        let first = reader.u8()?;
+        let kind = match first {
+            0xFF => {
+                running_status = None;
+                let kind = reader.u8()?;
+                let len = reader.var_len()? as usize;
+                let data = reader.bytes(len)?;
+                MidiTrackEventKind::Meta(match (kind, data.len()) {
+                    (0x51, 3) => MidiMetaEvent::Tempo(u32::from_be_bytes([0, data[0], data[1], data[2]])),
+                    (0x58, 4) => MidiMetaEvent::TimeSignature {
+                        numerator: data[0],
+                        denominator: data[1],
+                        clocks_per_click: data[2],
+                        thirty_seconds_per_quarter: data[3]
+                    },
+                    (0x03, _) => MidiMetaEvent::TrackName(String::from_utf8_lossy(data).into_owned()),
+                    (0x2F, _) => MidiMetaEvent::EndOfTrack,
+                    _ => MidiMetaEvent::Other {kind, data: data.to_vec()}
+                })
+            }
+            0xF0 | 0xF7 => {
+                running_status = None;
+                let len = reader.var_len()? as usize;
+                let data = reader.bytes(len)?.to_vec();
+                if first == 0xF0 {MidiTrackEventKind::SysEx(data)} else {MidiTrackEventKind::Escape(data)}
+            }
+            0xF1..=0xFE => return Err(MidiFileError::InvalidEvent("system message in a track")),
+            _ => {
+                // without a status byte the previous status is repeated
+                let (status, first_data) = if first & 0x80 != 0 {
+                    (first, reader.u8()?)
+                }
+                else {
+                    (running_status.ok_or(MidiFileError::InvalidEvent("data byte without a status"))?, first)
+                };
+                running_status = Some(status);
+                let second_data = if channel_message_len(status) == 2 {reader.u8()?} else {0};
+                MidiTrackEventKind::Midi(MidiData {data: [status, first_data, second_data]})
+            }
+        };
+        let is_end = kind == MidiTrackEventKind::Meta(MidiMetaEvent::EndOfTrack);
+        track.events.push(MidiTrackEvent {tick, kind});
+        if is_end {
+            break;
+        }
+    }
+    Ok(track)
+}
+
+fn write_var_len(value: u32, out: &mut Vec<u8>) {
+    let mut bytes = [0u8; 5];
+    let mut len = 0;
+    let mut value = value;
+    loop {
+        bytes[len] = (value & 0x7f) as u8 | if len > 0 {0x80} else {0};
+        len += 1;
+        value >>= 7;
+        if value == 0 {
+            break;
+        }
+    }
+    out.extend(bytes[..len].iter().rev());
+}
+
+fn write_track(track: &MidiTrack) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut last_tick = 0;
+    let mut running_status = None;
+    let mut has_end = false;
+    for event in &track.events {
+        // the events should be sorted, but never write a negative delta
+        let tick = event.tick.max(last_tick);
+        write_var_len((tick - last_tick).min(0x0fff_ffff) as u32, &mut out);
+        last_tick = tick;
+        match &event.kind {
+            MidiTrackEventKind::Midi(data) => {
+                let status = data.data[0] | 0x80;
+                if running_status != Some(status) {
+                    out.push(status);
+                    running_status = Some(status);
+                }
+                out.push(data.data[1] & 0x7f);
+                if channel_message_len(status) == 2 {
+                    out.push(data.data[2] & 0x7f);
+                }
+            }
+            MidiTrackEventKind::SysEx(data) | MidiTrackEventKind::Escape(data) => {
+                running_status = None;
+                out.push(if let MidiTrackEventKind::SysEx(_) = event.kind {0xF0} else {0xF7});
+                write_var_len(data.len() as u32, &mut out);
+                out.extend_from_slice(data);
+            }
+            MidiTrackEventKind::Meta(meta) => {
+                running_status = None;
+                let (kind, data) = match meta {
+                    MidiMetaEvent::Tempo(micros_per_quarter) => (0x51, micros_per_quarter.to_be_bytes()[1..].to_vec()),
+                    MidiMetaEvent::TimeSignature {numerator, denominator, clocks_per_click, thirty_seconds_per_quarter} => {
+                        (0x58, vec![*numerator, *denominator, *clocks_per_click, *thirty_seconds_per_quarter])
+                    }
+                    MidiMetaEvent::TrackName(name) => (0x03, name.as_bytes().to_vec()),
+                    MidiMetaEvent::EndOfTrack => (0x2F, Vec::new()),
+                    MidiMetaEvent::Other {kind, data} => (*kind, data.clone()),
+                };
+                out.push(0xFF);
+                out.push(kind);
+                write_var_len(data.len() as u32, &mut out);
+                out.extend_from_slice(&data);
+                if kind == 0x2F {
+                    has_end = true;
+                    break;
+                }
+            }
+        }
+    }
+    // every track has to end with an end of track event
+    if !has_end {
+        out.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+    }
+    out
+}
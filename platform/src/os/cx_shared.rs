@@ -190,11 +190,22 @@ pub fn handle_actions(&mut self) {
         }
     }
     
+    pub fn handle_file_dialog_events(&mut self) {
+        while !self.new_file_dialog_events.is_empty() {
+            let event = self.new_file_dialog_events.remove(0);
+            self.inner_call_event_handler(&Event::FileDialog(event));
+            self.inner_key_focus_change();
+            self.handle_triggers();
+            self.handle_actions();
+        }
+    }
+    
     pub (crate) fn call_event_handler(&mut self, event: &Event) {
         self.inner_call_event_handler(event);
         self.inner_key_focus_change();
         self.handle_triggers();
         self.handle_actions();
+        self.handle_file_dialog_events();
     }
 
     // helpers
@@ -211,7 +222,9 @@ pub fn handle_actions(&mut self) {
         let mut draw_event = DrawEvent::default();
         std::mem::swap(&mut draw_event, &mut self.new_draw_event);
         self.in_draw_event = true;
+        self.begin_accessibility_frame();
         self.call_event_handler(&Event::Draw(draw_event));
+        self.end_accessibility_frame();
         self.in_draw_event = false;
     }
 
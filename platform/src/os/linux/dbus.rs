@@ -0,0 +1,568 @@
+// A minimal D-Bus client for the session bus, just enough to call a method and wait for a signal.
+// It speaks the little endian wire format and authenticates with EXTERNAL, like every local bus expects.
+use std::{
+    io::{self, Read, Write},
+    os::unix::{
+        fs::MetadataExt,
+        net::UnixStream,
+    },
+};
+
+const MESSAGE_METHOD_CALL: u8 = 1;
+const MESSAGE_METHOD_RETURN: u8 = 2;
+const MESSAGE_ERROR: u8 = 3;
+const MESSAGE_SIGNAL: u8 = 4;
+
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_ERROR_NAME: u8 = 4;
+const FIELD_REPLY_SERIAL: u8 = 5;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SIGNATURE: u8 = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DbusValue {
+    Byte(u8),
+    Bool(bool),
+    Int16(i16),
+    Uint16(u16),
+    Int32(i32),
+    Uint32(u32),
+    Int64(i64),
+    Uint64(u64),
+    Double(f64),
+    String(String),
+    ObjectPath(String),
+    Signature(String),
+    /// The signature of the elements, so empty arrays keep their type.
+    Array(String, Vec<DbusValue>),
+    Struct(Vec<DbusValue>),
+    DictEntry(Box<DbusValue>, Box<DbusValue>),
+    Variant(Box<DbusValue>),
+}
+
+impl DbusValue {
+    pub fn signature(&self) -> String {
+        match self {
+            Self::Byte(_) => "y".into(),
+            Self::Bool(_) => "b".into(),
+            Self::Int16(_) => "n".into(),
+            Self::Uint16(_) => "q".into(),
+            Self::Int32(_) => "i".into(),
+            Self::Uint32(_) => "u".into(),
+            Self::Int64(_) => "x".into(),
+            Self::Uint64(_) => "t".into(),
+            Self::Double(_) => "d".into(),
+            Self::String(_) => "s".into(),
+            Self::ObjectPath(_) => "o".into(),
+            Self::Signature(_) => "g".into(),
+            Self::Array(element, _) => format!("a{}", element),
+            Self::Struct(fields) => format!("({})", fields.iter().map( | field | field.signature()).collect::<String>()),
+            Self::DictEntry(key, value) => format!("{{{}{}}}", key.signature(), value.signature()),
+            Self::Variant(_) => "v".into(),
+        }
+    }
+
+    /// An `a{sv}` dictionary, the usual shape of an options argument.
+    pub fn dict(entries: Vec<(&str, DbusValue)>) -> Self {
+        Self::Array("{sv}".into(), entries.into_iter().map( | (key, value) | {
+            Self::DictEntry(Box::new(Self::String(key.into())), Box::new(Self::Variant(Box::new(value))))
+        }).collect())
+    }
+
+    /// Looks up a key in an `a{sv}` dictionary.
+    pub fn dict_get(&self, key: &str) -> Option<&DbusValue> {
+        let Self::Array(_, entries) = self else {return None};
+        entries.iter().find_map( | entry | match entry {
+            Self::DictEntry(k, v) if **k == Self::String(key.into()) => match &**v {
+                Self::Variant(v) => Some(&**v),
+                v => Some(v)
+            }
+            _ => None
+        })
+    }
+}
+
+fn alignment(signature: &str) -> usize {
+    match signature.as_bytes().first() {
+        Some(b'n') | Some(b'q') => 2,
+        Some(b'b') | Some(b'i') | Some(b'u') | Some(b's') | Some(b'o') | Some(b'a') => 4,
+        Some(b'x') | Some(b't') | Some(b'd') | Some(b'(') | Some(b'{') => 8,
+        _ => 1
+    }
+}
+
+// splits a signature into its complete types
+fn split_signature(signature: &str) -> io::Result<Vec<&str>> {
+    let bytes = signature.as_bytes();
+    let mut types = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let mut end = start;
+        while bytes[end] == b'a' {
+            end += 1;
+            if end == bytes.len() {
+                return Err(invalid_data("unterminated array signature"))
+            }
+        }
+        if bytes[end] == b'(' || bytes[end] == b'{' {
+            let mut depth = 0;
+            loop {
+                match bytes.get(end) {
+                    Some(b'(') | Some(b'{') => depth += 1,
+                    Some(b')') | Some(b'}') => depth -= 1,
+                    Some(_) => (),
+                    None => return Err(invalid_data("unbalanced signature"))
+                }
+                end += 1;
+                if depth == 0 {
+                    break
+                }
+            }
+        }
+        else {
+            end += 1;
+        }
+        types.push(&signature[start..end]);
+        start = end;
+    }
+    Ok(types)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("dbus: {}", message))
+}
+
+#[derive(Default)]
+struct DbusWriter {
+    buf: Vec<u8>,
+}
+
+impl DbusWriter {
+    fn align(&mut self, n: usize) {
+        while !self.buf.len().is_multiple_of(n) {
+            self.buf.push(0);
+        }
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.align(4);
+        self.buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        self.buf.extend_from_slice(s.as_bytes());
+        self.buf.push(0);
+    }
+
+    fn write_value(&mut self, value: &DbusValue) {
+        match value {
+            DbusValue::Byte(v) => self.buf.push(*v),
+            DbusValue::Bool(v) => {
+                self.align(4);
+                self.buf.extend_from_slice(&(*v as u32).to_le_bytes());
+            }
+            DbusValue::Int16(v) => {
+                self.align(2);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Uint16(v) => {
+                self.align(2);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Int32(v) => {
+                self.align(4);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Uint32(v) => {
+                self.align(4);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Int64(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Uint64(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::Double(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            DbusValue::String(s) | DbusValue::ObjectPath(s) => self.write_str(s),
+            DbusValue::Signature(s) => {
+                self.buf.push(s.len() as u8);
+                self.buf.extend_from_slice(s.as_bytes());
+                self.buf.push(0);
+            }
+            DbusValue::Array(element, items) => {
+                self.align(4);
+                let len_pos = self.buf.len();
+                self.buf.extend_from_slice(&[0; 4]);
+                // the padding to the first element is not part of the length
+                self.align(alignment(element));
+                let start = self.buf.len();
+                for item in items {
+                    self.write_value(item);
+                }
+                let len = (self.buf.len() - start) as u32;
+                self.buf[len_pos..len_pos + 4].copy_from_slice(&len.to_le_bytes());
+            }
+            DbusValue::Struct(fields) => {
+                self.align(8);
+                for field in fields {
+                    self.write_value(field);
+                }
+            }
+            DbusValue::DictEntry(key, value) => {
+                self.align(8);
+                self.write_value(key);
+                self.write_value(value);
+            }
+            DbusValue::Variant(value) => {
+                self.write_value(&DbusValue::Signature(value.signature()));
+                self.write_value(value);
+            }
+        }
+    }
+}
+
+struct DbusReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DbusReader<'a> {
+    fn align(&mut self, n: usize) {
+        self.pos = self.pos.div_ceil(n) * n;
+    }
+
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.pos + n > self.buf.len() {
+            return Err(invalid_data("message too short"))
+        }
+        let bytes = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn take_aligned<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        self.align(N);
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn read_str(&mut self, len: usize) -> io::Result<String> {
+        let bytes = self.take(len)?;
+        self.take(1)?;
+        String::from_utf8(bytes.to_vec()).map_err( | _ | invalid_data("string is not utf8"))
+    }
+
+    fn read_value(&mut self, signature: &str) -> io::Result<DbusValue> {
+        Ok(match signature.as_bytes().first() {
+            Some(b'y') => DbusValue::Byte(self.take(1)?[0]),
+            Some(b'b') => DbusValue::Bool(u32::from_le_bytes(self.take_aligned()?) != 0),
+            Some(b'n') => DbusValue::Int16(i16::from_le_bytes(self.take_aligned()?)),
+            Some(b'q') => DbusValue::Uint16(u16::from_le_bytes(self.take_aligned()?)),
+            Some(b'i') => DbusValue::Int32(i32::from_le_bytes(self.take_aligned()?)),
+            Some(b'u') => DbusValue::Uint32(u32::from_le_bytes(self.take_aligned()?)),
+            Some(b'x') => DbusValue::Int64(i64::from_le_bytes(self.take_aligned()?)),
+            Some(b't') => DbusValue::Uint64(u64::from_le_bytes(self.take_aligned()?)),
+            Some(b'd') => DbusValue::Double(f64::from_le_bytes(self.take_aligned()?)),
+            Some(b's') | Some(b'o') => {
+                let len = u32::from_le_bytes(self.take_aligned()?) as usize;
+                let s = self.read_str(len)?;
+                if signature.starts_with('s') {DbusValue::String(s)} else {DbusValue::ObjectPath(s)}
+            }
+            Some(b'g') => {
+                let len = self.take(1)?[0] as usize;
+                DbusValue::Signature(self.read_str(len)?)
+            }
+            Some(b'v') => {
+                let len = self.take(1)?[0] as usize;
+                let inner = self.read_str(len)?;
+                DbusValue::Variant(Box::new(self.read_value(&inner)?))
+            }
+            Some(b'a') => {
+                let element = &signature[1..];
+                let len = u32::from_le_bytes(self.take_aligned()?) as usize;
+                self.align(alignment(element));
+                let end = self.pos + len;
+                let mut items = Vec::new();
+                while self.pos < end {
+                    items.push(self.read_value(element)?);
+                }
+                DbusValue::Array(element.to_string(), items)
+            }
+            Some(b'(') => {
+                self.align(8);
+                let mut fields = Vec::new();
+                for field in split_signature(&signature[1..signature.len() - 1])? {
+                    fields.push(self.read_value(field)?);
+                }
+                DbusValue::Struct(fields)
+            }
+            Some(b'{') => {
+                self.align(8);
+                let types = split_signature(&signature[1..signature.len() - 1])?;
+                if types.len() != 2 {
+                    return Err(invalid_data("dict entry needs a key and a value"))
+                }
+                let key = self.read_value(types[0])?;
+                let value = self.read_value(types[1])?;
+                DbusValue::DictEntry(Box::new(key), Box::new(value))
+            }
+            _ => return Err(invalid_data("unsupported type in signature"))
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DbusMessage {
+    pub kind: u8,
+    pub serial: u32,
+    pub reply_serial: Option<u32>,
+    pub path: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub error_name: Option<String>,
+    pub body: Vec<DbusValue>,
+}
+
+impl DbusMessage {
+    fn encode(&self, destination: Option<&str>) -> Vec<u8> {
+        let mut body = DbusWriter::default();
+        for value in &self.body {
+            body.write_value(value);
+        }
+        let mut fields = Vec::new();
+        let mut field = | code: u8, value: DbusValue | {
+            fields.push(DbusValue::Struct(vec![DbusValue::Byte(code), DbusValue::Variant(Box::new(value))]));
+        };
+        if let Some(path) = &self.path {
+            field(FIELD_PATH, DbusValue::ObjectPath(path.clone()));
+        }
+        if let Some(interface) = &self.interface {
+            field(FIELD_INTERFACE, DbusValue::String(interface.clone()));
+        }
+        if let Some(member) = &self.member {
+            field(FIELD_MEMBER, DbusValue::String(member.clone()));
+        }
+        if let Some(destination) = destination {
+            field(FIELD_DESTINATION, DbusValue::String(destination.into()));
+        }
+        if !self.body.is_empty() {
+            field(FIELD_SIGNATURE, DbusValue::Signature(self.body.iter().map( | value | value.signature()).collect()));
+        }
+        let mut header = DbusWriter::default();
+        header.buf.extend_from_slice(&[b'l', self.kind, 0, 1]);
+        header.write_value(&DbusValue::Uint32(body.buf.len() as u32));
+        header.write_value(&DbusValue::Uint32(self.serial));
+        header.write_value(&DbusValue::Array("(yv)".into(), fields));
+        header.align(8);
+        header.buf.extend_from_slice(&body.buf);
+        header.buf
+    }
+
+    fn decode(header: &[u8], body: &[u8]) -> io::Result<Self> {
+        let mut reader = DbusReader {buf: header, pos: 8};
+        let mut message = DbusMessage {
+            kind: header[1],
+            serial: u32::from_le_bytes(reader.take_aligned()?),
+            ..Default::default()
+        };
+        let mut signature = String::new();
+        if let DbusValue::Array(_, fields) = reader.read_value("a(yv)")? {
+            for field in fields {
+                let DbusValue::Struct(field) = field else {continue};
+                let (Some(DbusValue::Byte(code)), Some(DbusValue::Variant(value))) = (field.first(), field.get(1)) else {
+                    continue
+                };
+                match (*code, &**value) {
+                    (FIELD_PATH, DbusValue::ObjectPath(v)) => message.path = Some(v.clone()),
+                    (FIELD_INTERFACE, DbusValue::String(v)) => message.interface = Some(v.clone()),
+                    (FIELD_MEMBER, DbusValue::String(v)) => message.member = Some(v.clone()),
+                    (FIELD_ERROR_NAME, DbusValue::String(v)) => message.error_name = Some(v.clone()),
+                    (FIELD_REPLY_SERIAL, DbusValue::Uint32(v)) => message.reply_serial = Some(*v),
+                    (FIELD_SIGNATURE, DbusValue::Signature(v)) => signature = v.clone(),
+                    _ => ()
+                }
+            }
+        }
+        let mut reader = DbusReader {buf: body, pos: 0};
+        for value in split_signature(&signature)? {
+            message.body.push(reader.read_value(value)?);
+        }
+        Ok(message)
+    }
+}
+
+pub struct DbusConnection {
+    stream: UnixStream,
+    serial: u32,
+    pub unique_name: String,
+    // messages that arrived while waiting for something else
+    queue: Vec<DbusMessage>,
+}
+
+impl DbusConnection {
+    /// Connects to the session bus named by `DBUS_SESSION_BUS_ADDRESS`, or the usual per user socket.
+    pub fn session() -> io::Result<Self> {
+        let uid = std::fs::metadata("/proc/self")?.uid();
+        let address = std::env::var("DBUS_SESSION_BUS_ADDRESS")
+            .unwrap_or_else( | _ | format!("unix:path=/run/user/{}/bus", uid));
+        let stream = Self::connect(&address)?;
+        let mut connection = Self {stream, serial: 0, unique_name: String::new(), queue: Vec::new()};
+        connection.authenticate(uid)?;
+        let reply = connection.call("org.freedesktop.DBus", "/org/freedesktop/DBus", "org.freedesktop.DBus", "Hello", vec![])?;
+        if let Some(DbusValue::String(name)) = reply.body.first() {
+            connection.unique_name = name.clone();
+        }
+        Ok(connection)
+    }
+
+    fn connect(address: &str) -> io::Result<UnixStream> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "dbus: no usable bus address");
+        for address in address.split(';') {
+            let Some(params) = address.strip_prefix("unix:") else {continue};
+            for param in params.split(',') {
+                let result = if let Some(path) = param.strip_prefix("path=") {
+                    UnixStream::connect(unescape_address(path))
+                }
+                else if let Some(name) = param.strip_prefix("abstract=") {
+                    use std::os::linux::net::SocketAddrExt;
+                    std::os::unix::net::SocketAddr::from_abstract_name(unescape_address(name))
+                        .and_then( | addr | UnixStream::connect_addr(&addr))
+                }
+                else {
+                    continue
+                };
+                match result {
+                    Ok(stream) => return Ok(stream),
+                    Err(err) => last_error = err
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    fn authenticate(&mut self, uid: u32) -> io::Result<()> {
+        let hex_uid: String = uid.to_string().bytes().map( | b | format!("{:02x}", b)).collect();
+        self.stream.write_all(format!("\0AUTH EXTERNAL {}\r\n", hex_uid).as_bytes())?;
+        let mut line = Vec::new();
+        let mut byte = [0u8];
+        while !line.ends_with(b"\r\n") {
+            self.stream.read_exact(&mut byte)?;
+            line.push(byte[0]);
+        }
+        if !line.starts_with(b"OK ") {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "dbus: authentication rejected"))
+        }
+        self.stream.write_all(b"BEGIN\r\n")
+    }
+
+    fn send(&mut self, destination: Option<&str>, mut message: DbusMessage) -> io::Result<u32> {
+        self.serial += 1;
+        message.serial = self.serial;
+        self.stream.write_all(&message.encode(destination))?;
+        Ok(self.serial)
+    }
+
+    fn read_message(&mut self) -> io::Result<DbusMessage> {
+        let mut fixed = [0u8; 16];
+        self.stream.read_exact(&mut fixed)?;
+        if fixed[0] != b'l' {
+            return Err(invalid_data("only little endian messages are supported"))
+        }
+        let body_len = u32::from_le_bytes(fixed[4..8].try_into().unwrap()) as usize;
+        let fields_len = u32::from_le_bytes(fixed[12..16].try_into().unwrap()) as usize;
+        let header_len = (16 + fields_len).div_ceil(8) * 8;
+        let mut header = vec![0u8; header_len];
+        header[0..16].copy_from_slice(&fixed);
+        self.stream.read_exact(&mut header[16..])?;
+        let mut body = vec![0u8; body_len];
+        self.stream.read_exact(&mut body)?;
+        DbusMessage::decode(&header, &body)
+    }
+
+    /// Calls a method and waits for its reply, an error reply becomes an `io::Error`.
+    pub fn call(&mut self, destination: &str, path: &str, interface: &str, member: &str, args: Vec<DbusValue>) -> io::Result<DbusMessage> {
+        let serial = self.send(Some(destination), DbusMessage {
+            kind: MESSAGE_METHOD_CALL,
+            path: Some(path.into()),
+            interface: Some(interface.into()),
+            member: Some(member.into()),
+            body: args,
+            ..Default::default()
+        })?;
+        loop {
+            let message = self.read_message()?;
+            if message.reply_serial == Some(serial) {
+                match message.kind {
+                    MESSAGE_METHOD_RETURN => return Ok(message),
+                    MESSAGE_ERROR => {
+                        let detail = match message.body.first() {
+                            Some(DbusValue::String(detail)) => detail.clone(),
+                            _ => String::new()
+                        };
+                        return Err(io::Error::other(format!("dbus: {} {}", message.error_name.unwrap_or_default(), detail)))
+                    }
+                    _ => ()
+                }
+            }
+            else if message.kind == MESSAGE_SIGNAL {
+                self.queue.push(message);
+            }
+        }
+    }
+
+    pub fn add_match(&mut self, rule: &str) -> io::Result<()> {
+        self.call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "AddMatch",
+            vec![DbusValue::String(rule.into())]
+        ).map( | _ | ())
+    }
+
+    /// Blocks until a matching signal arrives, the signal needs a match rule to be delivered at all.
+    pub fn wait_for_signal(&mut self, path: &str, interface: &str, member: &str) -> io::Result<DbusMessage> {
+        let matches = | message: &DbusMessage | {
+            message.kind == MESSAGE_SIGNAL
+                && message.path.as_deref() == Some(path)
+                && message.interface.as_deref() == Some(interface)
+                && message.member.as_deref() == Some(member)
+        };
+        if let Some(index) = self.queue.iter().position(matches) {
+            return Ok(self.queue.remove(index))
+        }
+        loop {
+            let message = self.read_message()?;
+            if matches(&message) {
+                return Ok(message)
+            }
+        }
+    }
+}
+
+fn unescape_address(value: &str) -> String {
+    String::from_utf8_lossy(&percent_decode(value)).into_owned()
+}
+
+/// Decodes `%xx` escapes, as used in bus addresses and file uris.
+pub fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then( | hex | u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
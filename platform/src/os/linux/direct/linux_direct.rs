@@ -10,7 +10,8 @@
     self::super::super::{
         gl_sys,
         select_timer::SelectTimers,
-        linux_media::CxLinuxMedia
+        linux_media::CxLinuxMedia,
+        http::LinuxHttpRequests,
     },
     crate::{
         cx_api::{CxOsOp, CxOsApi, OpenUrlInPlace},
@@ -21,6 +22,7 @@
             TimerEvent,
             Event,
             WindowGeom,
+            NetworkResponseChannel,
         },
         window::CxWindowPool,
         pass::CxPassParent,
@@ -184,7 +186,7 @@ fn direct_event_callback(
                     if SignalToUI::check_and_clear_action_signal() {
                         self.handle_action_receiver();
                     }
-
+                    self.handle_networking_events();
                 }
                 else {
                     self.call_event_handler(&Event::Timer(e))
@@ -203,6 +205,18 @@ fn direct_event_callback(
         }
     }
     
+    pub(crate) fn handle_networking_events(&mut self) {
+        let mut out = Vec::new();
+        while let Ok(item) = self.os.network_response.receiver.try_recv() {
+            // remove the request object on error or end
+            self.os.http_requests.handle_response_item(&item);
+            out.push(item);
+        }
+        if !out.is_empty() {
+            self.call_event_handler(&Event::NetworkResponses(out))
+        }
+    }
+    
     pub fn draw_pass_to_fullscreen(
         &mut self,
         pass_id: PassId,
@@ -304,6 +318,12 @@ fn handle_platform_ops(&mut self, direct_app: &mut DirectApp) -> EventFlow {
                 CxOsOp::StopTimer(timer_id) => {
                     direct_app.timers.stop_timer(timer_id);
                 },
+                CxOsOp::HttpRequest {request_id, request} => {
+                    self.os.http_requests.make_http_request(request_id, request, self.os.network_response.sender.clone());
+                },
+                CxOsOp::CancelHttpRequest {request_id} => {
+                    self.os.http_requests.cancel_http_request(request_id);
+                },
                 e=>{
                     crate::error!("Not implemented on this platform: CxOsOp::{:?}", e);
                 }
@@ -340,13 +360,17 @@ fn seconds_since_app_start(&self)->f64{
 pub struct CxOs {
     pub (crate) media: CxLinuxMedia,
     pub (crate) start_time: Instant,
+    pub (crate) network_response: NetworkResponseChannel,
+    pub (crate) http_requests: LinuxHttpRequests,
 }
 
 impl Default for CxOs {
     fn default() -> Self {
         Self {
             start_time: Instant::now(),
-            media: Default::default()
+            media: Default::default(),
+            network_response: Default::default(),
+            http_requests: Default::default(),
         }
     }
 }
\ No newline at end of file
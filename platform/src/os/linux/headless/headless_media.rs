@@ -0,0 +1,70 @@
+use {
+    crate::{
+        cx::Cx,
+        audio::*,
+        midi::*,
+        video::*,
+        media_api::CxMediaApi,
+    }
+};
+
+// the headless backend has no audio, midi or video devices,
+// the media api is accepted and ignored so apps run unchanged
+
+impl Cx {
+    pub (crate) fn handle_media_signals(&mut self) {
+    }
+}
+
+pub struct OsMidiOutput;
+
+pub struct OsMidiInput;
+
+impl OsMidiOutput {
+    pub fn send(&self, _port_id: Option<MidiPortId>, _d: MidiData) {
+    }
+}
+
+impl OsMidiInput {
+    pub fn receive(&mut self) -> Option<(MidiPortId, MidiData)> {
+        None
+    }
+}
+
+impl CxMediaApi for Cx {
+    
+    fn midi_input(&mut self) -> MidiInput {
+        MidiInput(Some(OsMidiInput))
+    }
+    
+    fn midi_output(&mut self) -> MidiOutput {
+        MidiOutput(Some(OsMidiOutput))
+    }
+    
+    fn midi_reset(&mut self) {
+    }
+    
+    fn use_midi_inputs(&mut self, _ports: &[MidiPortId]) {
+    }
+    
+    fn use_midi_outputs(&mut self, _ports: &[MidiPortId]) {
+    }
+    
+    fn use_audio_inputs(&mut self, _devices: &[AudioDeviceId]) {
+    }
+    
+    fn use_audio_outputs(&mut self, _devices: &[AudioDeviceId]) {
+    }
+    
+    fn audio_output_box(&mut self, _index: usize, _f: AudioOutputFn){
+    }
+    
+    fn audio_input_box(&mut self, _index: usize, _f: AudioInputFn){
+    }
+    
+    fn video_input_box(&mut self, _index: usize, _f: VideoInputFn){
+    }
+    
+    fn use_video_input(&mut self, _inputs: &[(VideoInputId, VideoFormatId)]) {
+    }
+}
@@ -0,0 +1,338 @@
+use {
+    std::rc::Rc,
+    std::time::{Duration, Instant},
+    std::cell::RefCell,
+    self::super::{
+        software_renderer::SoftwareFrame,
+    },
+    self::super::super::{
+        select_timer::SelectTimers,
+        http::LinuxHttpRequests,
+    },
+    crate::{
+        cx_api::{CxOsOp, CxOsApi, OpenUrlInPlace},
+        makepad_math::*,
+        thread::SignalToUI,
+        event::{
+            TimerEvent,
+            Event,
+            WindowGeom,
+            NetworkResponseChannel,
+        },
+        window::{CxWindowPool, WindowId},
+        pass::CxPassParent,
+        cx::{Cx, OsType,},
+        gpu_info::GpuPerformance,
+        os::cx_native::EventFlow,
+    }
+};
+
+/// Runs the app without a display or gpu. Configured with environment variables:
+/// `MAKEPAD_HEADLESS_SIZE=1280x720` window size in logical pixels
+/// `MAKEPAD_HEADLESS_DPI=2.0` dpi factor of the windows
+/// `MAKEPAD_HEADLESS_FRAMES=10` quit after this many painted frames instead of when idle
+/// `MAKEPAD_HEADLESS_SCREENSHOT=out.png` write the main window to a png on exit
+pub struct HeadlessApp {
+    timers: SelectTimers,
+    window_size: Option<DVec2>,
+    dpi_factor: f64,
+    max_frames: Option<u64>,
+    screenshot: Option<String>,
+    frames: u64,
+    window_count: usize,
+}
+
+pub enum HeadlessEvent {
+    Paint,
+    Timer(TimerEvent),
+}
+
+impl HeadlessApp {
+    fn new() -> Self {
+        let window_size = std::env::var("MAKEPAD_HEADLESS_SIZE").ok().and_then( | size | {
+            let (w, h) = size.split_once('x')?;
+            Some(dvec2(w.trim().parse().ok()?, h.trim().parse().ok()?))
+        });
+        let dpi_factor = std::env::var("MAKEPAD_HEADLESS_DPI").ok()
+            .and_then( | dpi | dpi.parse().ok()).unwrap_or(1.0);
+        let max_frames = std::env::var("MAKEPAD_HEADLESS_FRAMES").ok()
+            .and_then( | frames | frames.parse().ok());
+        let screenshot = std::env::var("MAKEPAD_HEADLESS_SCREENSHOT").ok();
+        Self {
+            timers: SelectTimers::new(),
+            window_size,
+            dpi_factor,
+            max_frames,
+            screenshot,
+            frames: 0,
+            window_count: 0,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        if let Some(max_frames) = self.max_frames {
+            return self.frames >= max_frames
+        }
+        false
+    }
+}
+
+impl Cx {
+    pub fn event_loop(cx: Rc<RefCell<Cx >>) {
+
+        let mut cx = cx.borrow_mut();
+
+        cx.os_type = OsType::LinuxHeadless;
+        cx.gpu_info.performance = GpuPerformance::Tier1;
+
+        cx.call_event_handler(&Event::Startup);
+        cx.redraw_all();
+
+        let mut headless_app = HeadlessApp::new();
+        headless_app.timers.start_timer(0, 0.008, true);
+        let mut timer_ids = Vec::new();
+
+        'event_loop: loop {
+            headless_app.timers.update_timers(&mut timer_ids);
+            let time = headless_app.timers.time_now();
+            for timer_id in &timer_ids {
+                let event = HeadlessEvent::Timer(TimerEvent {
+                    timer_id: *timer_id,
+                    time: Some(time)
+                });
+                if cx.headless_event_callback(&mut headless_app, event) == EventFlow::Exit {
+                    break 'event_loop;
+                }
+            }
+            match cx.headless_event_callback(&mut headless_app, HeadlessEvent::Paint) {
+                EventFlow::Exit => break,
+                EventFlow::Wait => {
+                    // without a frame limit we are done when nothing is left to do
+                    // besides our own signal timer
+                    if headless_app.max_frames.is_none()
+                        && headless_app.frames > 0
+                        && headless_app.timers.timers.len() <= 1
+                        && cx.os.http_requests.is_empty() {
+                        break
+                    }
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                EventFlow::Poll => ()
+            }
+            if headless_app.is_done() {
+                break
+            }
+        }
+        cx.call_event_handler(&Event::Shutdown);
+
+        if let Some(path) = &headless_app.screenshot {
+            if let Some(frame) = cx.headless_window_frame(CxWindowPool::id_zero()) {
+                if let Err(e) = frame.save_png(path) {
+                    crate::error!("Cannot write headless screenshot {}: {}", path, e);
+                }
+            }
+        }
+    }
+
+    fn headless_event_callback(
+        &mut self,
+        headless_app: &mut HeadlessApp,
+        event: HeadlessEvent,
+    ) -> EventFlow {
+        if let EventFlow::Exit = self.handle_platform_ops(headless_app) {
+            return EventFlow::Exit
+        }
+
+        match event {
+            HeadlessEvent::Paint => {
+                if !self.new_next_frames.is_empty() {
+                    self.call_next_frame_event(headless_app.timers.time_now());
+                }
+                if self.need_redrawing() {
+                    self.call_draw_event();
+                    self.headless_compile_shaders();
+                }
+                self.handle_repaint(headless_app);
+            }
+            HeadlessEvent::Timer(e) => {
+                if e.timer_id == 0 {
+                    if SignalToUI::check_and_clear_ui_signal() {
+                        self.handle_media_signals();
+                        self.call_event_handler(&Event::Signal);
+                    }
+                    if SignalToUI::check_and_clear_action_signal() {
+                        self.handle_action_receiver();
+                    }
+                    self.handle_networking_events();
+                }
+                else {
+                    self.call_event_handler(&Event::Timer(e))
+                }
+
+                if self.handle_live_edit() {
+                    self.call_event_handler(&Event::LiveEdit);
+                    self.redraw_all();
+                }
+            }
+        }
+        if self.any_passes_dirty() || self.need_redrawing() || !self.new_next_frames.is_empty() {
+            EventFlow::Poll
+        } else {
+            EventFlow::Wait
+        }
+    }
+
+    pub(crate) fn handle_networking_events(&mut self) {
+        let mut out = Vec::new();
+        while let Ok(item) = self.os.network_response.receiver.try_recv() {
+            // remove the request object on error or end
+            self.os.http_requests.handle_response_item(&item);
+            out.push(item);
+        }
+        if !out.is_empty() {
+            self.call_event_handler(&Event::NetworkResponses(out))
+        }
+    }
+
+    pub (crate) fn handle_repaint(&mut self, headless_app: &mut HeadlessApp) {
+        let mut passes_todo = Vec::new();
+        self.compute_pass_repaint_order(&mut passes_todo);
+        if passes_todo.is_empty() {
+            return
+        }
+        self.repaint_id += 1;
+        for pass_id in &passes_todo {
+            self.passes[*pass_id].set_time(headless_app.timers.time_now() as f32);
+            match self.passes[*pass_id].parent.clone() {
+                CxPassParent::Window(_window_id) => {
+                    self.draw_pass_to_window_frame(*pass_id);
+                }
+                CxPassParent::Pass(_) => {
+                    self.draw_pass_to_magic_texture(*pass_id);
+                },
+                CxPassParent::None => {
+                    self.draw_pass_to_magic_texture(*pass_id);
+                }
+            }
+        }
+        headless_app.frames += 1;
+    }
+
+    /// The last frame the software renderer painted for a window
+    pub fn headless_window_frame(&self, window_id: WindowId) -> Option<&SoftwareFrame> {
+        let pass_id = self.windows[window_id].main_pass_id?;
+        let frame = &self.passes[pass_id].os.frame;
+        if frame.width == 0 || frame.height == 0 {
+            return None
+        }
+        Some(frame)
+    }
+
+    fn handle_platform_ops(&mut self, headless_app: &mut HeadlessApp) -> EventFlow {
+        let mut ret = EventFlow::Poll;
+        while let Some(op) = self.platform_ops.pop() {
+            match op {
+                CxOsOp::CreateWindow(window_id) => {
+                    let window = &mut self.windows[window_id];
+                    let size = headless_app.window_size
+                        .or(window.create_inner_size)
+                        .unwrap_or(dvec2(800., 600.));
+                    window.window_geom = WindowGeom {
+                        dpi_factor: headless_app.dpi_factor,
+                        can_fullscreen: false,
+                        xr_is_presenting: false,
+                        is_fullscreen: false,
+                        is_topmost: false,
+                        position: window.create_position.unwrap_or(dvec2(0.0, 0.0)),
+                        inner_size: size,
+                        outer_size: size
+                    };
+                    window.is_created = true;
+                    headless_app.window_count += 1;
+                },
+                CxOsOp::CloseWindow(window_id) => {
+                    if self.windows[window_id].is_created {
+                        self.windows[window_id].is_created = false;
+                        headless_app.window_count -= 1;
+                        if headless_app.window_count == 0 {
+                            ret = EventFlow::Exit
+                        }
+                    }
+                },
+                CxOsOp::Quit => {
+                    ret = EventFlow::Exit
+                }
+                CxOsOp::StartTimer {timer_id, interval, repeats} => {
+                    headless_app.timers.start_timer(timer_id, interval, repeats);
+                },
+                CxOsOp::StopTimer(timer_id) => {
+                    headless_app.timers.stop_timer(timer_id);
+                },
+                CxOsOp::HttpRequest {request_id, request} => {
+                    self.os.http_requests.make_http_request(request_id, request, self.os.network_response.sender.clone());
+                },
+                CxOsOp::CancelHttpRequest {request_id} => {
+                    self.os.http_requests.cancel_http_request(request_id);
+                },
+                CxOsOp::MinimizeWindow(_) |
+                CxOsOp::Deminiaturize(_) |
+                CxOsOp::MaximizeWindow(_) |
+                CxOsOp::FullscreenWindow(_) |
+                CxOsOp::NormalizeWindow(_) |
+                CxOsOp::RestoreWindow(_) |
+                CxOsOp::HideWindow(_) |
+                CxOsOp::SetTopmost(_, _) |
+                CxOsOp::ShowTextIME(_, _) |
+                CxOsOp::HideTextIME |
+                CxOsOp::SetCursor(_) |
+                CxOsOp::ShowClipboardActions(_) |
+                CxOsOp::CopyToClipboard(_) => {
+                    // nothing to show, these are fine to drop
+                }
+                e=>{
+                    crate::error!("Not implemented on this platform: CxOsOp::{:?}", e);
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl CxOsApi for Cx {
+    fn init_cx_os(&mut self) {
+        if let Some(item) = std::option_env!("MAKEPAD_PACKAGE_DIR"){
+            self.live_registry.borrow_mut().package_root = Some(item.to_string());
+        }
+        self.live_expand();
+        self.live_scan_dependencies();
+        self.native_load_dependencies();
+    }
+
+    fn spawn_thread<F>(&mut self, f: F) where F: FnOnce() + Send + 'static {
+        std::thread::spawn(f);
+    }
+
+    fn open_url(&mut self, _url:&str, _in_place:OpenUrlInPlace){
+        crate::error!("open_url not implemented on this platform");
+    }
+
+    fn seconds_since_app_start(&self)->f64{
+        Instant::now().duration_since(self.os.start_time).as_secs_f64()
+    }
+}
+
+pub struct CxOs {
+    pub (crate) start_time: Instant,
+    pub (crate) network_response: NetworkResponseChannel,
+    pub (crate) http_requests: LinuxHttpRequests,
+}
+
+impl Default for CxOs {
+    fn default() -> Self {
+        Self {
+            start_time: Instant::now(),
+            network_response: Default::default(),
+            http_requests: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod linux_headless;
+pub mod headless_media;
+pub mod software_renderer;
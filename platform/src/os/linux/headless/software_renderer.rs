@@ -0,0 +1,965 @@
+use {
+    std::{
+        fs::File,
+        io::{self, Write},
+        path::Path,
+    },
+    crate::{
+        cx::Cx,
+        makepad_live_id::*,
+        makepad_math::*,
+        draw_list::DrawListId,
+        makepad_live_compiler::LiveError,
+        makepad_shader_compiler::{
+            shader_ast::{DrawShaderFieldKind, Ty},
+            interpret::{ShaderInterpreter, ShaderValue},
+        },
+        draw_shader::{CxDrawShaderMapping, DrawShaderInputs},
+        pass::{PassClearColor, PassClearDepth, PassId},
+        texture::{CxTexture, Texture, TextureFormat},
+    }
+};
+
+/// A CPU side framebuffer holding premultiplied rgba colors and a depth value per pixel.
+/// Rows are stored top to bottom.
+#[derive(Clone, Default, Debug)]
+pub struct SoftwareFrame {
+    pub width: usize,
+    pub height: usize,
+    pub color: Vec<Vec4>,
+    pub depth: Vec<f32>,
+}
+
+impl SoftwareFrame {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut frame = Self::default();
+        frame.resize(width, height);
+        frame
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if self.width != width || self.height != height {
+            self.width = width;
+            self.height = height;
+            self.color = vec![Vec4::default(); width * height];
+            self.depth = vec![1.0; width * height];
+        }
+    }
+
+    pub fn clear_color(&mut self, color: Vec4) {
+        self.color.iter_mut().for_each( | c | *c = color);
+    }
+
+    pub fn clear_depth(&mut self, depth: f32) {
+        self.depth.iter_mut().for_each( | d | *d = depth);
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Vec4 {
+        self.color[y * self.width + x]
+    }
+
+    /// The frame as 8 bit rgba, the way a gpu readback of the framebuffer would return it
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.color.len() * 4);
+        for c in &self.color {
+            for v in [c.x, c.y, c.z, c.w] {
+                out.push((v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8);
+            }
+        }
+        out
+    }
+
+    /// Writes the frame as an uncompressed rgba png
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(&self.encode_png())
+    }
+
+    pub fn encode_png(&self) -> Vec<u8> {
+        let rgba = self.to_rgba8();
+        // every scanline is prefixed with filter type 0
+        let mut raw = Vec::with_capacity(rgba.len() + self.height);
+        for row in rgba.chunks(self.width.max(1) * 4).take(self.height) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits per channel, color type rgba, default compression, filter and interlace
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, ty: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(ty);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {(crc >> 1) ^ 0xedb8_8320} else {crc >> 1};
+        }
+    }
+    !crc
+}
+
+/// zlib stream made of stored (uncompressed) deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut chunks = data.chunks(65535).peekable();
+    if chunks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(chunk) = chunks.next() {
+        out.push(if chunks.peek().is_none() {1} else {0});
+        let len = chunk.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// How the rasterizer runs a draw shader. The stock text shaders are recognised by
+/// their instance layout and emulated, everything else runs on the shader interpreter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SoftwareShaderKind {
+    /// `fn vertex` and `fn pixel` run on the cpu through `ShaderInterpreter`
+    Interpreted,
+    /// `DrawText` glyphs from the sdf font atlas
+    Text,
+    /// `DrawText2` glyphs from the grayscale or color atlas
+    Text2,
+}
+
+/// What pixels get when their shader fails on the interpreter: a magenta and black
+/// checkerboard, so it can't be mistaken for something a shader drew on purpose
+pub fn placeholder_color(x: usize, y: usize) -> Vec4 {
+    if (x / 8 + y / 8) & 1 == 0 {vec4(1.0, 0.0, 1.0, 1.0)} else {vec4(0.0, 0.0, 0.0, 1.0)}
+}
+
+#[derive(Clone, Debug)]
+pub struct CxOsDrawShader {
+    pub kind: SoftwareShaderKind,
+    geom_pos: Option<usize>,
+    rect_pos: Option<usize>,
+    rect_size: Option<usize>,
+    draw_clip: Option<usize>,
+    draw_depth: Option<usize>,
+    char_depth: Option<usize>,
+    color: Option<usize>,
+    draw_color: Option<usize>,
+    font_t1: Option<usize>,
+    font_t2: Option<usize>,
+    t_min: Option<usize>,
+    t_max: Option<usize>,
+    texture_index: Option<usize>,
+    radius: Option<usize>,
+    cutoff: Option<usize>,
+    grayscale_atlas_size: Option<usize>,
+}
+
+fn input_offset(inputs: &DrawShaderInputs, id: LiveId) -> Option<usize> {
+    inputs.inputs.iter().find( | input | input.id == id).map( | input | input.offset)
+}
+
+impl CxOsDrawShader {
+    pub fn new(mapping: &CxDrawShaderMapping) -> Self {
+        let instance = | id | input_offset(&mapping.instances, id);
+        let user = | id | input_offset(&mapping.user_uniforms, id);
+        let mut sh = Self {
+            kind: SoftwareShaderKind::Interpreted,
+            geom_pos: input_offset(&mapping.geometries, live_id!(geom_pos)),
+            rect_pos: mapping.rect_pos,
+            rect_size: mapping.rect_size,
+            draw_clip: mapping.draw_clip,
+            draw_depth: instance(live_id!(draw_depth)),
+            char_depth: instance(live_id!(char_depth)),
+            color: instance(live_id!(color)),
+            draw_color: instance(live_id!(draw_color)),
+            font_t1: instance(live_id!(font_t1)),
+            font_t2: instance(live_id!(font_t2)),
+            t_min: instance(live_id!(t_min)),
+            t_max: instance(live_id!(t_max)),
+            texture_index: instance(live_id!(texture_index)),
+            radius: user(live_id!(radius)),
+            cutoff: user(live_id!(cutoff)),
+            grayscale_atlas_size: user(live_id!(grayscale_atlas_size)),
+        };
+        if sh.is_rect() {
+            if sh.font_t1.is_some() && sh.font_t2.is_some() && !mapping.textures.is_empty() {
+                sh.kind = SoftwareShaderKind::Text
+            }
+            else if sh.t_min.is_some() && sh.t_max.is_some() && mapping.textures.len() > 1 {
+                sh.kind = SoftwareShaderKind::Text2
+            }
+        }
+        sh
+    }
+
+    /// whether the shader draws a rect, which is where the placeholder goes when its vertex shader fails
+    fn is_rect(&self) -> bool {
+        self.geom_pos.is_some() && self.rect_pos.is_some() && self.rect_size.is_some()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CxOsPass {
+    pub frame: SoftwareFrame,
+}
+
+#[derive(Clone, Default)]
+pub struct CxOsView {
+}
+
+#[derive(Clone, Default)]
+pub struct CxOsDrawCall {
+}
+
+#[derive(Clone, Default)]
+pub struct CxOsTexture {
+    /// contents of a render target or depth texture
+    pub frame: Option<SoftwareFrame>,
+}
+
+#[derive(Clone, Default)]
+pub struct CxOsGeometry {
+}
+
+/// The pixels a pass renders into
+pub struct SoftwareTarget {
+    pub frame: SoftwareFrame,
+    pub depth_test: bool,
+}
+
+#[derive(Clone, Copy, Default)]
+struct RasterVertex {
+    x: f32,
+    y: f32,
+    z: f32,
+    inv_w: f32,
+}
+
+#[derive(Clone, Default)]
+struct ShadedVertex {
+    pos: RasterVertex,
+    varyings: Vec<f32>,
+}
+
+/// The varyings of a triangle, interpolated perspective correct at any point of the screen
+struct TriangleVaryings<'a> {
+    pos: [RasterVertex; 3],
+    varyings: [&'a [f32]; 3],
+    area: f32,
+}
+
+fn edge(a: &RasterVertex, b: &RasterVertex, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+impl<'a> TriangleVaryings<'a> {
+    fn at(&self, x: f32, y: f32, out: &mut Vec<f32>) {
+        let [p0, p1, p2] = &self.pos;
+        let c0 = edge(p1, p2, x, y) / self.area * p0.inv_w;
+        let c1 = edge(p2, p0, x, y) / self.area * p1.inv_w;
+        let c2 = edge(p0, p1, x, y) / self.area * p2.inv_w;
+        let inv = 1.0 / (c0 + c1 + c2);
+        let [v0, v1, v2] = self.varyings;
+        out.clear();
+        out.extend((0..v0.len()).map( | i | (v0[i] * c0 + v1[i] * c1 + v2[i] * c2) * inv));
+    }
+}
+
+/// Everything a single draw call needs besides the target
+struct DrawCallContext<'a> {
+    sh: &'a CxOsDrawShader,
+    instance: &'a [f32],
+    user_uniforms: &'a [f32],
+    textures: &'a [Option<&'a CxTexture>],
+    /// camera_projection * camera_view * view_transform
+    transform: Mat4,
+    view_clip: Vec4,
+    view_shift: Vec2,
+    zbias: f32,
+    dpi_factor: f32,
+}
+
+impl<'a> DrawCallContext<'a> {
+    fn vec2(&self, offset: Option<usize>) -> Vec2 {
+        offset.map( | o | vec2(self.instance[o], self.instance[o + 1])).unwrap_or_default()
+    }
+
+    fn vec4(&self, offset: Option<usize>, default: Vec4) -> Vec4 {
+        offset.map( | o | vec4(self.instance[o], self.instance[o + 1], self.instance[o + 2], self.instance[o + 3])).unwrap_or(default)
+    }
+
+    fn float(&self, offset: Option<usize>, default: f32) -> f32 {
+        offset.map( | o | self.instance[o]).unwrap_or(default)
+    }
+
+    fn user_float(&self, offset: Option<usize>, default: f32) -> f32 {
+        offset.map( | o | self.user_uniforms[o]).unwrap_or(default)
+    }
+
+    fn project(&self, p: Vec2, depth: f32) -> Vec4 {
+        self.transform.transform_vec4(vec4(p.x, p.y, depth + self.zbias, 1.0))
+    }
+
+    /// the emulated vertex shaders, for an interpreted shader the rect of the `DrawQuad` vertex shader
+    fn vertex(&self, geom_pos: Vec2) -> (Vec4, Vec<f32>) {
+        let sh = self.sh;
+        let rect_pos = self.vec2(sh.rect_pos);
+        let rect_size = self.vec2(sh.rect_size);
+        let draw_clip = self.vec4(sh.draw_clip, vec4(-100000.0, -100000.0, 100000.0, 100000.0));
+        // same as the gpu shaders, an empty clip rect (min > max) clips everything away instead of panicking
+        let clip = | p: Vec2, c: Vec4 | vec2(p.x.max(c.x).min(c.z), p.y.max(c.y).min(c.w));
+        match sh.kind {
+            SoftwareShaderKind::Interpreted => {
+                let p = clip(clip(geom_pos * rect_size + rect_pos, draw_clip) + self.view_shift, self.view_clip);
+                (self.project(p, self.float(sh.draw_depth, 1.0)), Vec::new())
+            }
+            SoftwareShaderKind::Text => {
+                let min_pos = rect_pos;
+                let max_pos = vec2(rect_pos.x + rect_size.x, rect_pos.y - rect_size.y);
+                let p = clip(clip(mix2(min_pos, max_pos, geom_pos), draw_clip) + self.view_shift, self.view_clip);
+                let n = (p - min_pos) / vec2(rect_size.x, -rect_size.y);
+                let t1 = self.vec2(sh.font_t1);
+                let t2 = self.vec2(sh.font_t2);
+                let t = mix2(vec2(t1.x, 1.0 - t1.y), vec2(t2.x, 1.0 - t2.y), n);
+                // dFdx / dFdy of the texture coordinate are constant over a glyph quad
+                let dxt = ((t2.x - t1.x) / (rect_size.x * self.dpi_factor)).abs();
+                let dyt = ((t2.y - t1.y) / (rect_size.y * self.dpi_factor)).abs();
+                (self.project(p, self.float(sh.char_depth, 1.0)), vec![t.x, t.y, (dxt + dyt) * 4096.0 * 0.5])
+            }
+            SoftwareShaderKind::Text2 => {
+                let p = clip(mix2(rect_pos, rect_pos + rect_size, geom_pos), draw_clip);
+                let n = (p - rect_pos) / rect_size;
+                let t_min = self.vec2(sh.t_min);
+                let t_max = self.vec2(sh.t_max);
+                let t = mix2(t_min, t_max, n);
+                let dxt = ((t_max.x - t_min.x) / (rect_size.x * self.dpi_factor)).abs();
+                let dyt = ((t_max.y - t_min.y) / (rect_size.y * self.dpi_factor)).abs();
+                (self.project(p, self.float(sh.draw_depth, 1.0)), vec![t.x, t.y, (dxt + dyt) * 0.5])
+            }
+        }
+    }
+
+    /// the emulated pixel shaders, returns a premultiplied color
+    fn pixel(&self, varyings: &[f32], x: usize, y: usize) -> Vec4 {
+        let sh = self.sh;
+        match sh.kind {
+            SoftwareShaderKind::Interpreted => placeholder_color(x, y),
+            SoftwareShaderKind::Text => {
+                let sdf_radius = 8.0;
+                let sdf_cutoff = 0.25;
+                let scale = varyings[2].max(0.0001);
+                let s = sample_texture(self.textures[0], vec2(varyings[0], varyings[1])).x;
+                let s = ((s - (1.0 - sdf_cutoff)) * sdf_radius / scale + 0.5).clamp(0.0, 1.0);
+                let c = self.vec4(sh.color, vec4(1.0, 1.0, 1.0, 1.0));
+                vec4(s * c.x * c.w, s * c.y * c.w, s * c.z * c.w, s * c.w)
+            }
+            SoftwareShaderKind::Text2 => {
+                let t = vec2(varyings[0], varyings[1]);
+                if self.float(sh.texture_index, 0.0) == 0.0 {
+                    let atlas_size = self.user_float(sh.grayscale_atlas_size, 4096.0);
+                    let scale = (varyings[2] * atlas_size).max(0.0001);
+                    let s = sample_texture(self.textures[0], t).x;
+                    let radius = self.user_float(sh.radius, 8.0);
+                    let cutoff = self.user_float(sh.cutoff, 0.25);
+                    let s = ((s - (1.0 - cutoff)) * radius / scale + 0.5).clamp(0.0, 1.0);
+                    let c = self.vec4(sh.draw_color, vec4(1.0, 1.0, 1.0, 1.0));
+                    vec4(s * c.x, s * c.y, s * c.z, s * c.w)
+                }
+                else {
+                    let c = sample_texture(self.textures[1], t);
+                    vec4(c.x * c.w, c.y * c.w, c.z * c.w, c.w)
+                }
+            }
+        }
+    }
+}
+
+/// Runs the vertex and pixel shader of a draw call on the shader interpreter
+struct InterpretedDrawCall<'a> {
+    interp: ShaderInterpreter<'a>,
+    mapping: &'a CxDrawShaderMapping,
+    /// the varyings in the order their slots are interpolated
+    varyings: Vec<(LiveId, Ty)>,
+    varying_slots: Vec<f32>,
+}
+
+impl<'a> InterpretedDrawCall<'a> {
+    fn set_inputs(interp: &mut ShaderInterpreter, inputs: &DrawShaderInputs, slots: &[f32]) {
+        for input in &inputs.inputs {
+            if let Some(slots) = slots.get(input.offset..input.offset + input.slots) {
+                interp.set_field_slots(input.id, slots);
+            }
+        }
+    }
+
+    fn set_instance(&mut self, instance: &[f32]) {
+        Self::set_inputs(&mut self.interp, &self.mapping.instances, instance);
+    }
+
+    fn vertex(&mut self, geometry: &[f32]) -> Result<(Vec4, Vec<f32>), LiveError> {
+        Self::set_inputs(&mut self.interp, &self.mapping.geometries, geometry);
+        let clip = self.interp.run_vertex() ?;
+        let mut varyings = Vec::new();
+        for (id, ty) in &self.varyings {
+            match self.interp.field(*id).and_then( | value | value.to_slots()) {
+                Some(slots) => varyings.extend(slots),
+                // a varying the vertex shader didn't write, or one that can't be interpolated
+                None => varyings.resize(varyings.len() + ty.slots(), 0.0),
+            }
+        }
+        Ok((clip, varyings))
+    }
+
+    fn varying_values(&self, slots: &[f32]) -> Vec<(LiveId, ShaderValue)> {
+        let mut offset = 0;
+        self.varyings.iter().filter_map( | (id, ty) | {
+            let value = ShaderValue::from_slots(ty, &slots[offset..]);
+            offset += ty.slots();
+            Some((*id, value?))
+        }).collect()
+    }
+
+    fn pixel(&mut self, varyings: &TriangleVaryings, px: f32, py: f32) -> Result<Vec4, LiveError> {
+        let mut slots = std::mem::take(&mut self.varying_slots);
+        varyings.at(px + 1.0, py, &mut slots);
+        let right = self.varying_values(&slots);
+        varyings.at(px, py + 1.0, &mut slots);
+        let below = self.varying_values(&slots);
+        varyings.at(px, py, &mut slots);
+        for (id, value) in self.varying_values(&slots) {
+            self.interp.set_field(id, value);
+        }
+        self.varying_slots = slots;
+        self.interp.run_pixel_with_neighbours(&right, &below)
+    }
+}
+
+fn mix2(a: Vec2, b: Vec2, t: Vec2) -> Vec2 {
+    vec2(a.x + (b.x - a.x) * t.x, a.y + (b.y - a.y) * t.y)
+}
+
+fn mix4(a: Vec4, b: Vec4, t: f32) -> Vec4 {
+    a + (b - a) * t
+}
+
+fn texel(texture: &CxTexture, x: usize, y: usize) -> Vec4 {
+    fn u8n(v: u8) -> f32 {v as f32 / 255.0}
+    match &texture.format {
+        TextureFormat::VecBGRAu8_32 {width, data: Some(data), ..} |
+        TextureFormat::VecMipBGRAu8_32 {width, data: Some(data), ..} => {
+            let p = data[y * width + x];
+            vec4(u8n((p >> 16) as u8), u8n((p >> 8) as u8), u8n(p as u8), u8n((p >> 24) as u8))
+        }
+        TextureFormat::VecRGBAf32 {width, data: Some(data), ..} => {
+            let i = (y * width + x) * 4;
+            vec4(data[i], data[i + 1], data[i + 2], data[i + 3])
+        }
+        TextureFormat::VecRu8 {width, data: Some(data), unpack_row_length, ..} => {
+            vec4(u8n(data[y * unpack_row_length.unwrap_or(*width) + x]), 0.0, 0.0, 1.0)
+        }
+        TextureFormat::VecRGu8 {width, data: Some(data), unpack_row_length, ..} => {
+            let i = (y * unpack_row_length.unwrap_or(*width) + x) * 2;
+            vec4(u8n(data[i]), u8n(data[i + 1]), 0.0, 1.0)
+        }
+        TextureFormat::VecRf32 {width, data: Some(data), ..} => {
+            vec4(data[y * width + x], 0.0, 0.0, 1.0)
+        }
+        _ => if let Some(frame) = &texture.os.frame {
+            frame.pixel(x, y)
+        }
+        else {
+            Vec4::default()
+        }
+    }
+}
+
+fn texture_size(texture: &CxTexture) -> (usize, usize) {
+    match &texture.format {
+        TextureFormat::VecBGRAu8_32 {width, height, data: Some(_), ..} |
+        TextureFormat::VecMipBGRAu8_32 {width, height, data: Some(_), ..} |
+        TextureFormat::VecRGBAf32 {width, height, data: Some(_), ..} |
+        TextureFormat::VecRu8 {width, height, data: Some(_), ..} |
+        TextureFormat::VecRGu8 {width, height, data: Some(_), ..} |
+        TextureFormat::VecRf32 {width, height, data: Some(_), ..} => (*width, *height),
+        _ => if let Some(frame) = &texture.os.frame {
+            (frame.width, frame.height)
+        }
+        else {
+            (0, 0)
+        }
+    }
+}
+
+/// Bilinear sample with clamp to edge, matching the gl texture setup
+pub fn sample_texture(texture: Option<&CxTexture>, uv: Vec2) -> Vec4 {
+    let texture = if let Some(texture) = texture {texture} else {return Vec4::default()};
+    let (width, height) = texture_size(texture);
+    if width == 0 || height == 0 {
+        return Vec4::default()
+    }
+    let fx = (uv.x * width as f32 - 0.5).clamp(0.0, (width - 1) as f32);
+    let fy = (uv.y * height as f32 - 0.5).clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (fx.floor() as usize, fy.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (tx, ty) = (fx - x0 as f32, fy - y0 as f32);
+    let top = mix4(texel(texture, x0, y0), texel(texture, x1, y0), tx);
+    let bottom = mix4(texel(texture, x0, y1), texel(texture, x1, y1), tx);
+    mix4(top, bottom, ty)
+}
+
+impl SoftwareTarget {
+    fn to_screen(&self, clip: Vec4) -> Option<RasterVertex> {
+        if clip.w <= 0.0 {
+            return None
+        }
+        let inv_w = 1.0 / clip.w;
+        Some(RasterVertex {
+            x: (clip.x * inv_w * 0.5 + 0.5) * self.frame.width as f32,
+            y: (0.5 - clip.y * inv_w * 0.5) * self.frame.height as f32,
+            z: clip.z * inv_w * 0.5 + 0.5,
+            inv_w,
+        })
+    }
+
+    /// Fills a triangle using the top-left rule with LEQUAL depth testing and
+    /// premultiplied alpha blending (ONE, ONE_MINUS_SRC_ALPHA). `shade` gets the
+    /// varyings of the triangle and the pixel
+    fn fill_triangle(&mut self, v: [&ShadedVertex; 3], shade: &mut dyn FnMut(&TriangleVaryings, usize, usize) -> Vec4) {
+        let [a, mut b, mut c] = v;
+        let mut area = edge(&a.pos, &b.pos, c.pos.x, c.pos.y);
+        if area == 0.0 {
+            return
+        }
+        if area < 0.0 {
+            std::mem::swap(&mut b, &mut c);
+            area = -area;
+        }
+        let (p0, p1, p2) = (a.pos, b.pos, c.pos);
+        let varyings = TriangleVaryings {
+            pos: [p0, p1, p2],
+            varyings: [&a.varyings, &b.varyings, &c.varyings],
+            area,
+        };
+
+        let min_x = p0.x.min(p1.x).min(p2.x).floor().max(0.0) as usize;
+        let min_y = p0.y.min(p1.y).min(p2.y).floor().max(0.0) as usize;
+        let max_x = (p0.x.max(p1.x).max(p2.x).ceil().max(0.0) as usize).min(self.frame.width);
+        let max_y = (p0.y.max(p1.y).max(p2.y).ceil().max(0.0) as usize).min(self.frame.height);
+
+        // pixel centers exactly on an edge belong to only one of the two triangles sharing it
+        let is_top_left = | a: &RasterVertex, b: &RasterVertex | (a.y == b.y && b.x > a.x) || b.y < a.y;
+        let (tl0, tl1, tl2) = (is_top_left(&p1, &p2), is_top_left(&p2, &p0), is_top_left(&p0, &p1));
+        let inside = | w: f32, tl: bool | w > 0.0 || (w == 0.0 && tl);
+
+        for y in min_y..max_y {
+            let py = y as f32 + 0.5;
+            for x in min_x..max_x {
+                let px = x as f32 + 0.5;
+                let w0 = edge(&p1, &p2, px, py);
+                let w1 = edge(&p2, &p0, px, py);
+                let w2 = edge(&p0, &p1, px, py);
+                if !inside(w0, tl0) || !inside(w1, tl1) || !inside(w2, tl2) {
+                    continue;
+                }
+                let (b0, b1, b2) = (w0 / area, w1 / area, w2 / area);
+                let index = y * self.frame.width + x;
+                let z = b0 * p0.z + b1 * p1.z + b2 * p2.z;
+                if self.depth_test && z > self.frame.depth[index] {
+                    continue;
+                }
+                let src = shade(&varyings, x, y);
+                let dst = self.frame.color[index];
+                self.frame.color[index] = src + dst * (1.0 - src.w);
+                if self.depth_test {
+                    self.frame.depth[index] = z;
+                }
+            }
+        }
+    }
+}
+
+impl Cx {
+
+    pub (crate) fn render_view(
+        &mut self,
+        pass_id: PassId,
+        draw_list_id: DrawListId,
+        zbias: &mut f32,
+        zbias_step: f32,
+        target: &mut SoftwareTarget,
+    ) {
+        let draw_items_len = self.draw_lists[draw_list_id].draw_items.len();
+        self.draw_lists[draw_list_id].uniform_view_transform(&Mat4::identity());
+
+        for draw_item_id in 0..draw_items_len {
+            if let Some(sub_list_id) = self.draw_lists[draw_list_id].draw_items[draw_item_id].kind.sub_list() {
+                self.render_view(
+                    pass_id,
+                    sub_list_id,
+                    zbias,
+                    zbias_step,
+                    target,
+                );
+                continue;
+            }
+            let draw_item = &mut self.draw_lists[draw_list_id].draw_items[draw_item_id];
+            let draw_call = if let Some(draw_call) = draw_item.kind.draw_call_mut() {
+                draw_call
+            }else {
+                continue;
+            };
+            let sh = &self.draw_shaders.shaders[draw_call.draw_shader.draw_shader_id];
+            if sh.os_shader_id.is_none() { // shader didnt compile somehow
+                continue;
+            }
+            if sh.mapping.uses_time {
+                self.demo_time_repaint = true;
+            }
+            draw_call.draw_uniforms.set_zbias(*zbias);
+            draw_call.instance_dirty = false;
+            draw_call.uniforms_dirty = false;
+            *zbias += zbias_step;
+
+            // the cpu copies of vec textures are sampled directly
+            for i in 0..sh.mapping.textures.len() {
+                if let Some(texture) = &draw_call.texture_slots[i] {
+                    let cxtexture = &mut self.textures[texture.texture_id()];
+                    if cxtexture.format.is_vec() {
+                        cxtexture.alloc_vec();
+                        cxtexture.take_updated();
+                    }
+                }
+            }
+
+            let draw_list = &self.draw_lists[draw_list_id];
+            let draw_item = &draw_list.draw_items[draw_item_id];
+            let draw_call = draw_item.kind.draw_call().unwrap();
+            let sh = &self.draw_shaders.shaders[draw_call.draw_shader.draw_shader_id];
+            let shp = &self.draw_shaders.os_shaders[sh.os_shader_id.unwrap()];
+            let geometry = if let Some(geometry_id) = draw_call.geometry_id {
+                &self.geometries[geometry_id]
+            }
+            else {
+                continue;
+            };
+            let instances = draw_item.instances.as_ref().unwrap();
+            let total_slots = sh.mapping.instances.total_slots;
+            if total_slots == 0 || instances.len() < total_slots {
+                continue;
+            }
+
+            let pass_uniforms = self.passes[pass_id].pass_uniforms.as_slice();
+            let camera_projection = Mat4 {v: pass_uniforms[0..16].try_into().unwrap()};
+            let camera_view = Mat4 {v: pass_uniforms[16..32].try_into().unwrap()};
+            let dpi_factor = pass_uniforms[48];
+            let view_transform = Mat4 {v: draw_list.draw_list_uniforms.view_transform};
+            let transform = Mat4::mul(&camera_projection, &Mat4::mul(&camera_view, &view_transform));
+
+            let textures: Vec<Option<&CxTexture>> = (0..sh.mapping.textures.len()).map( | i | {
+                draw_call.texture_slots[i].as_ref().map( | texture | &self.textures[texture.texture_id()])
+            }).collect();
+
+            let mut interpreted = None;
+            if shp.kind == SoftwareShaderKind::Interpreted {
+                if let Some(def) = self.shader_registry.draw_shader_defs.get(&draw_call.draw_shader.draw_shader_ptr) {
+                    let mut interp = ShaderInterpreter::new(&self.shader_registry, def);
+                    interp.set_const_table(&sh.mapping.const_table);
+                    match interp.load_live_values(&self.live_registry.borrow()) {
+                        Ok(()) => {
+                            let mapping = &sh.mapping;
+                            InterpretedDrawCall::set_inputs(&mut interp, &mapping.pass_uniforms, pass_uniforms);
+                            InterpretedDrawCall::set_inputs(&mut interp, &mapping.view_uniforms, draw_list.draw_list_uniforms.as_slice());
+                            InterpretedDrawCall::set_inputs(&mut interp, &mapping.draw_uniforms, draw_call.draw_uniforms.as_slice());
+                            InterpretedDrawCall::set_inputs(&mut interp, &mapping.user_uniforms, &draw_call.user_uniforms);
+                            InterpretedDrawCall::set_inputs(&mut interp, &mapping.live_uniforms, &mapping.live_uniforms_buf);
+                            let texture_ids: Vec<LiveId> = mapping.textures.iter().map( | t | t.id).collect();
+                            let sampler_textures = textures.clone();
+                            interp.set_sampler(move | ident, uv | {
+                                let texture = texture_ids.iter().position( | id | *id == ident.0).and_then( | i | sampler_textures[i]);
+                                sample_texture(texture, uv)
+                            });
+                            let varyings = def.fields.iter().filter_map( | field | match field.kind {
+                                DrawShaderFieldKind::Varying {..} => Some((field.ident.0, field.ty_expr.ty.borrow().clone().unwrap())),
+                                _ => None
+                            }).collect();
+                            interpreted = Some(InterpretedDrawCall {interp, mapping, varyings, varying_slots: Vec::new()});
+                        }
+                        Err(err) => if sh.mapping.flags.debug {
+                            crate::log!("Software renderer cannot load the live values of shader {:?}: {:?}", sh.type_name, err);
+                        }
+                    }
+                }
+            }
+            let mut logged_error = false;
+            let mut log_error = | stage: &str, err: LiveError | if sh.mapping.flags.debug && !logged_error {
+                logged_error = true;
+                crate::log!("Software renderer cannot run the {} shader of {:?}, drawing a placeholder: {:?}", stage, sh.type_name, err);
+            };
+
+            let geom_slots = sh.mapping.geometries.total_slots;
+            if geom_slots == 0 {
+                continue;
+            }
+            for instance in instances.chunks_exact(total_slots) {
+                let ctx = DrawCallContext {
+                    sh: shp,
+                    instance,
+                    user_uniforms: &draw_call.user_uniforms,
+                    textures: &textures,
+                    transform,
+                    view_clip: draw_list.draw_list_uniforms.view_clip,
+                    view_shift: draw_list.draw_list_uniforms.view_shift,
+                    zbias: draw_call.draw_uniforms.draw_zbias,
+                    dpi_factor,
+                };
+                let emulated_vertices = | | -> Vec<Option<ShadedVertex>> {
+                    let geom_pos = match shp.geom_pos {
+                        Some(geom_pos) if shp.is_rect() || shp.kind != SoftwareShaderKind::Interpreted => geom_pos,
+                        _ => return Vec::new()
+                    };
+                    geometry.vertices.chunks_exact(geom_slots).map( | v | {
+                        let (clip, varyings) = ctx.vertex(vec2(v[geom_pos], v[geom_pos + 1]));
+                        target.to_screen(clip).map( | pos | ShadedVertex {pos, varyings})
+                    }).collect()
+                };
+
+                // if the vertex shader fails the rect of the instance is drawn with the placeholder
+                let mut interpret_pixels = false;
+                let vertices = if let Some(interpreted) = &mut interpreted {
+                    interpreted.set_instance(instance);
+                    let vertices: Result<Vec<Option<ShadedVertex>>, LiveError> = geometry.vertices.chunks_exact(geom_slots).map( | v | {
+                        let (clip, varyings) = interpreted.vertex(v) ?;
+                        Ok(target.to_screen(clip).map( | pos | ShadedVertex {pos, varyings}))
+                    }).collect();
+                    match vertices {
+                        Ok(vertices) => {
+                            interpret_pixels = true;
+                            vertices
+                        }
+                        Err(err) => {
+                            log_error("vertex", err);
+                            emulated_vertices()
+                        }
+                    }
+                }
+                else {
+                    emulated_vertices()
+                };
+
+                for tri in geometry.indices.chunks_exact(3) {
+                    if let (Some(Some(a)), Some(Some(b)), Some(Some(c))) = (
+                        vertices.get(tri[0] as usize),
+                        vertices.get(tri[1] as usize),
+                        vertices.get(tri[2] as usize)
+                    ) {
+                        match &mut interpreted {
+                            Some(interpreted) if interpret_pixels => {
+                                target.fill_triangle([a, b, c], &mut | varyings, x, y | {
+                                    match interpreted.pixel(varyings, x as f32 + 0.5, y as f32 + 0.5) {
+                                        Ok(color) => color,
+                                        Err(err) => {
+                                            log_error("pixel", err);
+                                            placeholder_color(x, y)
+                                        }
+                                    }
+                                });
+                            }
+                            _ => {
+                                let mut slots = Vec::new();
+                                target.fill_triangle([a, b, c], &mut | varyings, x, y | {
+                                    varyings.at(x as f32 + 0.5, y as f32 + 0.5, &mut slots);
+                                    ctx.pixel(&slots, x, y)
+                                })
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn setup_render_pass(&mut self, pass_id: PassId,) -> Option<DVec2> {
+        let dpi_factor = self.passes[pass_id].dpi_factor.unwrap();
+        let pass_rect = self.get_pass_rect(pass_id, dpi_factor).unwrap();
+        self.passes[pass_id].paint_dirty = false;
+
+        if pass_rect.size.x <0.5 || pass_rect.size.y < 0.5 {
+            return None
+        }
+
+        self.passes[pass_id].set_matrix(pass_rect.pos, pass_rect.size);
+        self.passes[pass_id].set_dpi_factor(dpi_factor);
+        Some(pass_rect.size)
+    }
+
+    pub (crate) fn draw_pass_to_frame(&mut self, pass_id: PassId, target: &mut SoftwareTarget) {
+        let draw_list_id = self.passes[pass_id].main_draw_list_id.unwrap();
+        let mut zbias = 0.0;
+        let zbias_step = self.passes[pass_id].zbias_step;
+        self.render_view(
+            pass_id,
+            draw_list_id,
+            &mut zbias,
+            zbias_step,
+            target
+        );
+    }
+
+    pub (crate) fn draw_pass_to_window_frame(&mut self, pass_id: PassId) {
+        let pass_size = if let Some(pz) = self.setup_render_pass(pass_id) {
+            pz
+        }
+        else {
+            return
+        };
+        let dpi_factor = self.passes[pass_id].dpi_factor.unwrap();
+        let size = pass_size * dpi_factor;
+
+        let mut frame = std::mem::take(&mut self.passes[pass_id].os.frame);
+        frame.resize(size.x as usize, size.y as usize);
+
+        let pass = &self.passes[pass_id];
+        if !pass.dont_clear {
+            let clear_color = if pass.color_textures.is_empty() {
+                pass.clear_color
+            }
+            else {
+                match pass.color_textures[0].clear_color {
+                    PassClearColor::InitWith(color) => color,
+                    PassClearColor::ClearWith(color) => color
+                }
+            };
+            let clear_depth = match pass.clear_depth {
+                PassClearDepth::InitWith(depth) => depth,
+                PassClearDepth::ClearWith(depth) => depth
+            };
+            frame.clear_color(clear_color);
+            frame.clear_depth(clear_depth);
+        }
+        let mut target = SoftwareTarget {frame, depth_test: true};
+        self.draw_pass_to_frame(pass_id, &mut target);
+        self.passes[pass_id].os.frame = target.frame;
+    }
+
+    pub fn draw_pass_to_texture(&mut self, pass_id: PassId, texture: &Texture) {
+        self.draw_pass_to_texture_inner(pass_id, Some(texture))
+    }
+
+    pub fn draw_pass_to_magic_texture(&mut self, pass_id: PassId) {
+        self.draw_pass_to_texture_inner(pass_id, None)
+    }
+
+    fn draw_pass_to_texture_inner(
+        &mut self,
+        pass_id: PassId,
+        maybe_texture: Option<&Texture>,
+    ) {
+        let pass_size = if let Some(pz) = self.setup_render_pass(pass_id) {
+            pz
+        }
+        else {
+            return
+        };
+        let dpi_factor = self.passes[pass_id].dpi_factor.unwrap();
+        let size = pass_size * dpi_factor;
+        let (width, height) = (size.x as usize, size.y as usize);
+
+        let color_texture = if let Some(texture) = maybe_texture {
+            Some((texture.clone(), PassClearColor::ClearWith(self.passes[pass_id].clear_color)))
+        }
+        else {
+            self.passes[pass_id].color_textures.first().map( | ct | (ct.texture.clone(), ct.clear_color.clone()))
+        };
+
+        // render into the color texture, or a scratch frame if the pass only has depth
+        let mut frame = None;
+        if let Some((texture, clear_color)) = &color_texture {
+            let cxtexture = &mut self.textures[texture.texture_id()];
+            cxtexture.alloc_render(width, height);
+            let mut color = cxtexture.os.frame.take().unwrap_or_default();
+            color.resize(width, height);
+            match clear_color {
+                PassClearColor::InitWith(clear_color) => {
+                    if cxtexture.take_initial() {
+                        color.clear_color(*clear_color);
+                    }
+                }
+                PassClearColor::ClearWith(clear_color) => {
+                    color.clear_color(*clear_color);
+                }
+            }
+            frame = Some(color);
+        }
+        let mut frame = frame.unwrap_or_else( || SoftwareFrame::new(width, height));
+
+        let depth_texture = self.passes[pass_id].depth_texture.clone();
+        if let Some(depth_texture) = &depth_texture {
+            let cxtexture = &mut self.textures[depth_texture.texture_id()];
+            cxtexture.alloc_depth(width, height);
+            let mut depth = cxtexture.os.frame.take().unwrap_or_default();
+            depth.resize(width, height);
+            match self.passes[pass_id].clear_depth {
+                PassClearDepth::InitWith(clear_depth) => {
+                    if cxtexture.take_initial() {
+                        depth.clear_depth(clear_depth);
+                    }
+                }
+                PassClearDepth::ClearWith(clear_depth) => {
+                    depth.clear_depth(clear_depth);
+                }
+            }
+            frame.depth = std::mem::take(&mut depth.depth);
+            cxtexture.os.frame = Some(depth);
+        }
+
+        let mut target = SoftwareTarget {frame, depth_test: depth_texture.is_some()};
+        self.draw_pass_to_frame(pass_id, &mut target);
+
+        if let Some(depth_texture) = &depth_texture {
+            if let Some(depth) = &mut self.textures[depth_texture.texture_id()].os.frame {
+                depth.depth = std::mem::take(&mut target.frame.depth);
+                target.frame.depth = vec![1.0; width * height];
+            }
+        }
+        if let Some((texture, _)) = &color_texture {
+            self.textures[texture.texture_id()].os.frame = Some(target.frame);
+        }
+    }
+
+    pub fn headless_compile_shaders(&mut self) {
+        for draw_shader_ptr in &self.draw_shaders.compile_set {
+            if let Some(item) = self.draw_shaders.ptr_to_item.get(draw_shader_ptr) {
+                let cx_shader = &mut self.draw_shaders.shaders[item.draw_shader_id];
+                let shp = CxOsDrawShader::new(&cx_shader.mapping);
+                cx_shader.os_shader_id = Some(self.draw_shaders.os_shaders.len());
+                self.draw_shaders.os_shaders.push(shp);
+            }
+        }
+        self.draw_shaders.compile_set.clear();
+    }
+}
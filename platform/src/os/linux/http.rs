@@ -0,0 +1,380 @@
+use {
+    std::{
+        io::{Read},
+        net::{TcpStream, Shutdown},
+        sync::{Arc, Mutex},
+        sync::atomic::{AtomicBool, Ordering},
+        sync::mpsc::{Sender},
+    },
+    makepad_http::utils::write_bytes_to_tcp_stream_no_error,
+    crate::{
+        makepad_live_id::*,
+        event::{
+            NetworkResponseItem,
+            NetworkResponse,
+            HttpError,
+            HttpProgress,
+            HttpRequest,
+            HttpResponse
+        },
+    }
+};
+
+const READ_BUFFER_SIZE: usize = 65536;
+const MAX_HEADER_SIZE: usize = 65536;
+
+struct HttpReq{
+    request_id: LiveId,
+    cancelled: Arc<AtomicBool>,
+    stream: Arc<Mutex<Option<TcpStream>>>,
+}
+
+#[derive(Default)]
+pub struct LinuxHttpRequests{
+    requests: Vec<HttpReq>
+}
+
+impl LinuxHttpRequests{
+    pub fn is_empty(&self)->bool{
+        self.requests.is_empty()
+    }
+
+    pub fn cancel_http_request(&mut self, request_id: LiveId){
+        self.requests.retain(|v|{
+            if v.request_id == request_id{
+                v.cancelled.store(true, Ordering::SeqCst);
+                // shutting down the socket unblocks the reader thread
+                if let Some(stream) = v.stream.lock().unwrap().as_ref(){
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+                false
+            }
+            else{
+                true
+            }
+        })
+    }
+
+    pub fn handle_response_item(&mut self, item:&NetworkResponseItem){
+        match &item.response{
+            NetworkResponse::HttpRequestError(_) |
+            NetworkResponse::HttpResponse(_) |
+            NetworkResponse::HttpStreamComplete(_) => {
+                self.requests.retain(|v| v.request_id != item.request_id);
+            }
+            _=>{
+            }
+        }
+    }
+
+    pub fn make_http_request(&mut self, request_id: LiveId, request: HttpRequest, networking_sender: Sender<NetworkResponseItem>) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let stream = Arc::new(Mutex::new(None));
+        self.requests.push(HttpReq{
+            request_id,
+            cancelled: cancelled.clone(),
+            stream: stream.clone(),
+        });
+        let _request_thread = std::thread::spawn(move || {
+            let metadata_id = request.metadata_id;
+            let conn = HttpConnection{
+                request_id,
+                metadata_id,
+                sender: networking_sender,
+                cancelled,
+            };
+            if let Err(message) = conn.run(&request, &stream){
+                // a cancelled request goes away silently
+                if !conn.cancelled.load(Ordering::SeqCst){
+                    let _ = conn.sender.send(NetworkResponseItem{
+                        request_id,
+                        response: NetworkResponse::HttpRequestError(HttpError{
+                            metadata_id,
+                            message
+                        })
+                    });
+                }
+            }
+            if let Some(stream) = stream.lock().unwrap().take(){
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        });
+    }
+}
+
+struct HttpConnection{
+    request_id: LiveId,
+    metadata_id: LiveId,
+    sender: Sender<NetworkResponseItem>,
+    cancelled: Arc<AtomicBool>,
+}
+
+enum BodyKind{
+    Chunked,
+    Length(u64),
+    UntilClose,
+    Empty
+}
+
+/// Buffered reader so the status line, headers and chunk sizes can be read line by line
+struct HttpReader{
+    stream: TcpStream,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl HttpReader{
+    fn fill(&mut self)->Result<usize, String>{
+        if self.pos > 0{
+            self.buf.drain(0..self.pos);
+            self.pos = 0;
+        }
+        let mut tmp = [0u8; READ_BUFFER_SIZE];
+        match self.stream.read(&mut tmp){
+            Ok(n)=>{
+                self.buf.extend_from_slice(&tmp[0..n]);
+                Ok(n)
+            }
+            Err(e)=>Err(format!("Error reading from http connection: {}", e))
+        }
+    }
+
+    fn available(&self)->&[u8]{
+        &self.buf[self.pos..]
+    }
+
+    fn read_line(&mut self)->Result<String, String>{
+        loop{
+            if let Some(end) = self.available().windows(2).position(|w| w == b"\r\n"){
+                let line = String::from_utf8_lossy(&self.buf[self.pos..self.pos + end]).to_string();
+                self.pos += end + 2;
+                return Ok(line)
+            }
+            if self.available().len() > MAX_HEADER_SIZE{
+                return Err("Http header line too long".into())
+            }
+            if self.fill()? == 0{
+                return Err("Http connection closed unexpectedly".into())
+            }
+        }
+    }
+
+    /// returns up to max bytes, None on end of stream
+    fn read_some(&mut self, max: usize)->Result<Option<Vec<u8>>, String>{
+        if self.available().is_empty() && self.fill()? == 0{
+            return Ok(None)
+        }
+        let len = self.available().len().min(max);
+        let data = self.buf[self.pos..self.pos + len].to_vec();
+        self.pos += len;
+        Ok(Some(data))
+    }
+}
+
+impl HttpConnection{
+    fn is_cancelled(&self)->bool{
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn send(&self, response: NetworkResponse)->Result<(), String>{
+        if self.is_cancelled(){
+            return Err("Http request cancelled".into())
+        }
+        self.sender.send(NetworkResponseItem{
+            request_id: self.request_id,
+            response
+        }).map_err(|_| "Http response channel closed".to_string())
+    }
+
+    fn run(&self, request: &HttpRequest, shared_stream: &Arc<Mutex<Option<TcpStream>>>)->Result<(), String>{
+        let split = request.split_url();
+        match split.proto{
+            "http"=>(),
+            "https"=>return Err(format!("Https is not supported by the linux http client: {}", request.url)),
+            proto=>return Err(format!("Unsupported protocol {} in url {}", proto, request.url))
+        }
+
+        let mut stream = TcpStream::connect(format!("{}:{}", split.host, split.port))
+            .map_err(|e| format!("Error connecting to {}:{}: {}", split.host, split.port, e))?;
+        *shared_stream.lock().unwrap() = Some(stream.try_clone().map_err(|e| e.to_string())?);
+        if self.is_cancelled(){
+            return Err("Http request cancelled".into())
+        }
+
+        let host = if split.port == "80"{split.host.to_string()} else {format!("{}:{}", split.host, split.port)};
+        let mut http_request = format!("{} /{} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", request.method.to_string(), split.file, host);
+        if !request.headers.keys().any(|k| k.eq_ignore_ascii_case("user-agent")){
+            http_request.push_str("User-Agent: makepad\r\n");
+        }
+        if !request.headers.keys().any(|k| k.eq_ignore_ascii_case("accept")){
+            http_request.push_str("Accept: */*\r\n");
+        }
+        if request.is_streaming && !request.headers.keys().any(|k| k.eq_ignore_ascii_case("cache-control")){
+            http_request.push_str("Cache-Control: no-cache\r\n");
+        }
+        http_request.push_str(&request.get_headers_string());
+        if let Some(body) = &request.body{
+            if !request.headers.keys().any(|k| k.eq_ignore_ascii_case("content-length")){
+                http_request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+            }
+        }
+        http_request.push_str("\r\n");
+
+        if write_bytes_to_tcp_stream_no_error(&mut stream, http_request.as_bytes()){
+            return Err("Error writing http request headers".into())
+        }
+        if let Some(body) = &request.body{
+            if write_bytes_to_tcp_stream_no_error(&mut stream, body){
+                return Err("Error writing http request body".into())
+            }
+        }
+
+        let mut reader = HttpReader{
+            stream,
+            buf: Vec::new(),
+            pos: 0
+        };
+
+        // read the status line and headers, skipping any 1xx interim responses
+        let (status_code, mut response) = loop{
+            let status_line = reader.read_line()?;
+            let status_code = parse_status_line(&status_line)?;
+            let mut response = HttpResponse{
+                metadata_id: self.metadata_id,
+                status_code,
+                headers: Default::default(),
+                body: None
+            };
+            loop{
+                let line = reader.read_line()?;
+                if line.is_empty(){
+                    break;
+                }
+                if let Some((key, value)) = line.split_once(':'){
+                    response.set_header(key.trim().to_string(), value.trim().to_string());
+                }
+            }
+            if (100..200).contains(&status_code){
+                continue;
+            }
+            break (status_code, response)
+        };
+
+        let body_kind = if request.method.to_string() == "HEAD" || status_code == 204 || status_code == 304{
+            BodyKind::Empty
+        }
+        else if header_value(&response, "transfer-encoding").map(|v| v.to_ascii_lowercase().contains("chunked")).unwrap_or(false){
+            BodyKind::Chunked
+        }
+        else if let Some(len) = header_value(&response, "content-length"){
+            BodyKind::Length(len.trim().parse().map_err(|_| format!("Invalid content-length {}", len))?)
+        }
+        else{
+            BodyKind::UntilClose
+        };
+
+        let total = if let BodyKind::Length(len) = body_kind{len} else {0};
+        let mut body = Vec::new();
+        let mut loaded = 0u64;
+        {
+            let is_streaming = request.is_streaming;
+            let mut on_data = |data: Vec<u8>, this:&HttpConnection|->Result<(), String>{
+                if data.is_empty(){
+                    return Ok(())
+                }
+                loaded += data.len() as u64;
+                if is_streaming{
+                    this.send(NetworkResponse::HttpStreamResponse(HttpResponse{
+                        headers: Default::default(),
+                        metadata_id: this.metadata_id,
+                        status_code,
+                        body: Some(data)
+                    }))
+                }
+                else{
+                    body.extend_from_slice(&data);
+                    this.send(NetworkResponse::HttpProgress(HttpProgress{loaded, total}))
+                }
+            };
+
+            match body_kind{
+                BodyKind::Empty=>(),
+                BodyKind::Length(len)=>{
+                    let mut left = len;
+                    while left > 0{
+                        match reader.read_some(left.min(READ_BUFFER_SIZE as u64) as usize)?{
+                            Some(data)=>{
+                                left -= data.len() as u64;
+                                on_data(data, self)?;
+                            }
+                            None=>return Err("Http connection closed before end of body".into())
+                        }
+                    }
+                }
+                BodyKind::UntilClose=>{
+                    while let Some(data) = reader.read_some(READ_BUFFER_SIZE)?{
+                        on_data(data, self)?;
+                    }
+                }
+                BodyKind::Chunked=>{
+                    loop{
+                        let line = reader.read_line()?;
+                        let size = line.split(';').next().unwrap_or("").trim();
+                        let size = u64::from_str_radix(size, 16).map_err(|_| format!("Invalid http chunk size {}", line))?;
+                        if size == 0{
+                            // read optional trailers up to the final empty line
+                            while !reader.read_line()?.is_empty(){}
+                            break;
+                        }
+                        // the size comes from the server, so the chunk is passed on as it arrives
+                        // instead of allocating it up front
+                        let mut left = size;
+                        while left > 0{
+                            match reader.read_some(left.min(READ_BUFFER_SIZE as u64) as usize)?{
+                                Some(data)=>{
+                                    left -= data.len() as u64;
+                                    on_data(data, self)?;
+                                }
+                                None=>return Err("Http connection closed inside chunk".into())
+                            }
+                        }
+                        if !reader.read_line()?.is_empty(){
+                            return Err("Invalid http chunk terminator".into())
+                        }
+                    }
+                }
+            }
+        }
+
+        if request.is_streaming{
+            self.send(NetworkResponse::HttpStreamComplete(HttpResponse{
+                headers: response.headers,
+                metadata_id: self.metadata_id,
+                status_code,
+                body: None
+            }))
+        }
+        else{
+            response.body = Some(body);
+            self.send(NetworkResponse::HttpResponse(response))
+        }
+    }
+}
+
+fn header_value<'a>(response: &'a HttpResponse, name: &str)->Option<&'a str>{
+    response.headers.iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .and_then(|(_, v)| v.last())
+        .map(|v| v.as_str())
+}
+
+fn parse_status_line(line: &str)->Result<u16, String>{
+    let mut parts = line.split(' ');
+    let version = parts.next().unwrap_or("");
+    if !version.starts_with("HTTP/"){
+        return Err(format!("Invalid http status line {}", line))
+    }
+    parts.next()
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("Invalid http status line {}", line))
+}
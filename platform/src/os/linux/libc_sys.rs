@@ -38,6 +38,7 @@ pub struct fd_set {
 
 pub const RTLD_LAZY: c_int = 1;
 pub const RTLD_LOCAL: c_int = 0;
+pub const LC_CTYPE: c_int = 0;
     
 extern "C"{
     pub fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
@@ -55,6 +56,7 @@ pub fn select(
         timeout: *mut timeval,
     ) -> c_int;
     pub fn read(fd: c_int, buf: *mut c_void, count: size_t) -> c_int;
+    pub fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char;
 }
 
 pub unsafe fn FD_SET(fd: c_int, set: *mut fd_set) -> () {
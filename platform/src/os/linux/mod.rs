@@ -1,15 +1,23 @@
-#[cfg(not(any(linux_direct, target_env="ohos", target_os="android")))]
+#[cfg(not(any(linux_direct, headless, target_env="ohos", target_os="android")))]
 pub mod x11; 
+#[cfg(not(any(linux_direct, headless, target_env="ohos", target_os="android")))]
+pub mod dbus;
+#[cfg(not(any(linux_direct, headless, target_env="ohos", target_os="android")))]
+pub mod xdg_portal;
 
 #[cfg(linux_direct)]
 pub mod direct;
 
+#[cfg(headless)]
+pub mod headless;
+
 #[cfg(target_env="ohos")]
 pub mod open_harmony;
 
 pub mod egl_sys;
 pub mod gl_sys;
 pub mod libc_sys;
+#[cfg(not(headless))]
 pub mod opengl;
 pub mod module_loader;
 
@@ -18,33 +26,36 @@
 #[cfg(not(any(target_env="ohos", target_os="android")))]
 pub mod ipc;
 
-#[cfg(not(any(target_env="ohos", target_os="android")))]
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))]
 pub mod alsa_sys;
-#[cfg(not(any(target_env="ohos", target_os="android")))]
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))]
 pub mod linux_media;
-#[cfg(not(any(target_env="ohos", target_os="android")))]
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))]
 pub mod alsa_audio;
-#[cfg(not(any(target_env="ohos", target_os="android")))]
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))]
 pub mod alsa_midi;
 
 #[cfg(not(target_os="android"))]
 pub mod select_timer;
 
-#[cfg(not(any(target_env="ohos", target_os="android")))] 
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))] 
 pub mod pulse_audio; 
-#[cfg(not(any(target_env="ohos", target_os="android")))]
+#[cfg(not(any(headless, target_env="ohos", target_os="android")))]
 pub mod pulse_sys;
 
 #[cfg(not(target_os="android"))]
 mod web_socket;
 
+#[cfg(not(any(target_env="ohos", target_os="android")))]
+pub mod http;
+
 #[cfg(target_os="android")]
 pub mod android;
 
 #[cfg(target_os="android")]
 pub(crate) use self::android::android::CxOs;
 
-#[cfg(not(any(linux_direct, target_os="android", target_env="ohos")))]
+#[cfg(not(any(linux_direct, headless, target_os="android", target_env="ohos")))]
 pub(crate) use self::x11::linux_x11::*;
 
 #[cfg(target_env="ohos")]
@@ -53,9 +64,19 @@
 #[cfg(linux_direct)]
 pub(crate) use self::direct::linux_direct::*;
 
+#[cfg(headless)]
+pub(crate) use self::headless::linux_headless::*;
+
+#[cfg(headless)]
+pub(crate) use self::headless::software_renderer::*;
+
+#[cfg(headless)]
+pub use self::headless::software_renderer::SoftwareFrame;
+
+#[cfg(not(headless))]
 pub(crate) use self::opengl::*;
 
-#[cfg(not(any(target_os="android", target_env="ohos")))]
+#[cfg(not(any(headless, target_os="android", target_env="ohos")))]
 pub(crate) use self::alsa_midi::{OsMidiInput, OsMidiOutput};
 
 #[cfg(target_os="android")]
@@ -11,14 +11,18 @@
         x11::xlib_event::*,
         x11::xlib_app::*,
         x11::x11_sys,
-        linux_media::CxLinuxMedia
+        linux_media::CxLinuxMedia,
+        http::LinuxHttpRequests,
+        xdg_portal,
     },
     crate::{
         cx_api::{CxOsOp, CxOsApi, OpenUrlInPlace}, 
         makepad_math::dvec2,
         makepad_live_id::*,
         thread::SignalToUI,
-        event::Event,
+        event::{Event, MouseUpEvent, MouseButton, NetworkResponseChannel},
+        window::CxWindowPool,
+        file_dialogs::{FileDialog, FileDialogKind, FileDialogEvent, FileDialogChannel},
         pass::CxPassParent,
         cx::{Cx, OsType,LinuxWindowParams}, 
         os::cx_stdin::{PollTimers},
@@ -175,13 +179,27 @@ fn xlib_event_callback(
                 self.call_event_handler(&Event::TextInput(e))
             }
             XlibEvent::Drag(e) => {
-                self.call_event_handler(&Event::Drag(e))
+                self.call_event_handler(&Event::Drag(e));
+                self.drag_drop.cycle_drag();
             }
             XlibEvent::Drop(e) => {
-                self.call_event_handler(&Event::Drop(e))
+                self.call_event_handler(&Event::Drop(e));
+                self.drag_drop.cycle_drag();
             }
             XlibEvent::DragEnd => {
-                self.call_event_handler(&Event::DragEnd)
+                // the pointer grab of a drag we started swallows the mouse up, so send one
+                self.call_event_handler(&Event::MouseUp(MouseUpEvent {
+                    abs: dvec2(-100000.0, -100000.0),
+                    button: MouseButton::PRIMARY,
+                    window_id: CxWindowPool::id_zero(),
+                    modifiers: Default::default(),
+                    time: 0.0
+                }));
+                self.fingers.mouse_up(MouseButton::PRIMARY);
+                self.fingers.cycle_hover_area(live_id!(mouse).into());
+
+                self.call_event_handler(&Event::DragEnd);
+                self.drag_drop.cycle_drag();
             }
             XlibEvent::KeyDown(e) => {
                 self.keyboard.process_key_down(e.clone());
@@ -205,6 +223,8 @@ fn xlib_event_callback(
                         self.call_event_handler(&Event::Signal);
                     }
                     self.handle_action_receiver();
+                    self.handle_networking_events();
+                    self.handle_file_dialog_results();
                 }
                 else{
                     self.call_event_handler(&Event::Timer(e))
@@ -226,6 +246,32 @@ fn xlib_event_callback(
     }
 
     pub(crate) fn handle_networking_events(&mut self) {
+        let mut out = Vec::new();
+        while let Ok(item) = self.os.network_response.receiver.try_recv() {
+            // remove the request object on error or end
+            self.os.http_requests.handle_response_item(&item);
+            out.push(item);
+        }
+        if !out.is_empty() {
+            self.call_event_handler(&Event::NetworkResponses(out))
+        }
+    }
+    
+    pub(crate) fn handle_file_dialog_results(&mut self) {
+        while let Ok(event) = self.os.file_dialogs.receiver.try_recv() {
+            self.call_event_handler(&Event::FileDialog(event))
+        }
+    }
+    
+    // the portal call blocks until the dialog closes, the result comes back through the ui signal
+    fn open_file_dialog_thread(&mut self, kind: FileDialogKind, settings: FileDialog, opengl_windows: &[OpenglWindow]) {
+        let parent_window = opengl_windows.first().and_then( | w | w.xlib_window.window);
+        let sender = self.os.file_dialogs.sender.clone();
+        std::thread::spawn(move || {
+            let response = xdg_portal::file_dialog(kind, &settings, parent_window);
+            let _ = sender.send(FileDialogEvent {kind, response});
+            SignalToUI::set_ui_signal();
+        });
     }
     
     pub (crate) fn handle_repaint(&mut self, opengl_windows: &mut Vec<OpenglWindow>) {
@@ -257,6 +303,16 @@ pub(crate) fn handle_networking_events(&mut self) {
     fn handle_platform_ops(&mut self, opengl_windows: &mut Vec<OpenglWindow>, xlib_app: &mut XlibApp) -> EventFlow {
         let mut ret = EventFlow::Poll;
         while let Some(op) = self.platform_ops.pop() {
+            // ops for a window can be queued after the one creating it, which makes them come first here
+            if let CxOsOp::MinimizeWindow(window_id) | CxOsOp::Deminiaturize(window_id) | CxOsOp::MaximizeWindow(window_id)
+            | CxOsOp::FullscreenWindow(window_id) | CxOsOp::NormalizeWindow(window_id) | CxOsOp::RestoreWindow(window_id)
+            | CxOsOp::HideWindow(window_id) | CxOsOp::SetTopmost(window_id, _) = op {
+                if !opengl_windows.iter().any( | w | w.window_id == window_id)
+                    && self.platform_ops.contains(&CxOsOp::CreateWindow(window_id)) {
+                    self.platform_ops.insert(0, op);
+                    continue;
+                }
+            }
             match op {
                 CxOsOp::CreateWindow(window_id) => {
                     let window = &mut self.windows[window_id];
@@ -289,8 +345,16 @@ fn handle_platform_ops(&mut self, opengl_windows: &mut Vec<OpenglWindow>, xlib_a
                         window.xlib_window.minimize();
                     }
                 },
-                CxOsOp::Deminiaturize(_window_id) => todo!(),
-                CxOsOp::HideWindow(_window_id) => todo!(),
+                CxOsOp::Deminiaturize(window_id) => {
+                    if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
+                        window.xlib_window.deminiaturize();
+                    }
+                },
+                CxOsOp::HideWindow(window_id) => {
+                    if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
+                        window.xlib_window.hide();
+                    }
+                },
                 CxOsOp::MaximizeWindow(window_id) => {
                     if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
                         window.xlib_window.maximize();
@@ -301,6 +365,21 @@ fn handle_platform_ops(&mut self, opengl_windows: &mut Vec<OpenglWindow>, xlib_a
                         window.xlib_window.restore();
                     }
                 },
+                CxOsOp::FullscreenWindow(window_id) => {
+                    if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
+                        window.xlib_window.fullscreen();
+                    }
+                },
+                CxOsOp::NormalizeWindow(window_id) => {
+                    if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
+                        window.xlib_window.normalize();
+                    }
+                },
+                CxOsOp::SetTopmost(window_id, is_topmost) => {
+                    if let Some(window) = opengl_windows.iter_mut().find( | w | w.window_id == window_id) {
+                        window.xlib_window.set_topmost(is_topmost);
+                    }
+                },
                 CxOsOp::ShowClipboardActions(_) =>{
                 },
                 CxOsOp::CopyToClipboard(content) => {
@@ -319,6 +398,40 @@ fn handle_platform_ops(&mut self, opengl_windows: &mut Vec<OpenglWindow>, xlib_a
                 CxOsOp::StopTimer(timer_id) => {
                     xlib_app.stop_timer(timer_id);
                 },
+                CxOsOp::HttpRequest {request_id, request} => {
+                    self.os.http_requests.make_http_request(request_id, request, self.os.network_response.sender.clone());
+                },
+                CxOsOp::CancelHttpRequest {request_id} => {
+                    self.os.http_requests.cancel_http_request(request_id);
+                },
+                CxOsOp::ShowTextIME(area, pos) => {
+                    let pos = area.clipped_rect(self).pos + pos;
+                    opengl_windows.iter_mut().for_each( | w | {
+                        w.xlib_window.set_ime_spot(pos);
+                    });
+                },
+                CxOsOp::HideTextIME => {
+                    opengl_windows.iter_mut().for_each( | w | {
+                        w.xlib_window.hide_ime();
+                    });
+                },
+                CxOsOp::StartDragging(items) => {
+                    if let Some(window) = opengl_windows.iter().find_map( | w | w.xlib_window.window) {
+                        unsafe {xlib_app.start_dragging(window, items)};
+                    }
+                },
+                CxOsOp::SaveFileDialog(settings) => {
+                    self.open_file_dialog_thread(FileDialogKind::SaveFile, settings, opengl_windows);
+                },
+                CxOsOp::SelectFileDialog(settings) => {
+                    self.open_file_dialog_thread(FileDialogKind::SelectFile, settings, opengl_windows);
+                },
+                CxOsOp::SaveFolderDialog(settings) => {
+                    self.open_file_dialog_thread(FileDialogKind::SaveFolder, settings, opengl_windows);
+                },
+                CxOsOp::SelectFolderDialog(settings) => {
+                    self.open_file_dialog_thread(FileDialogKind::SelectFolder, settings, opengl_windows);
+                },
                 e=>{
                     crate::error!("Not implemented on this platform: CxOsOp::{:?}", e);
                 }
@@ -362,5 +475,8 @@ pub struct CxOs {
     pub (crate) start_time: Option<Instant>,
     // HACK(eddyb) generalize this to EGL, properly.
     pub(super) opengl_cx: Option<OpenglCx>,
+    pub (crate) network_response: NetworkResponseChannel,
+    pub (crate) http_requests: LinuxHttpRequests,
+    pub (crate) file_dialogs: FileDialogChannel,
 }
 
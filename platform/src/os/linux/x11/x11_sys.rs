@@ -48,6 +48,7 @@
 pub const PropModeReplace: u32 = 0;
 pub const DestroyNotify: u32 = 17;
 pub const ConfigureNotify: u32 = 22;
+pub const PropertyNotify: u32 = 28;
 pub const EnterNotify: u32 = 7;
 pub const LeaveNotify: u32 = 8;
 pub const MotionNotify: u32 = 6;
@@ -78,8 +79,10 @@
 pub const KeyReleaseMask: u32 = 2;
 pub const VisibilityChangeMask: u32 = 65536;
 pub const FocusChangeMask: u32 = 2097152;
+pub const PropertyChangeMask: u32 = 4194304;
 pub const EnterWindowMask: u32 = 16;
 pub const LeaveWindowMask: u32 = 32;
+pub const GrabModeAsync: i32 = 1;
 pub const XBufferOverflow: i32 = -1;
 
 pub const QueuedAlready: i32 = 0;
@@ -93,12 +96,21 @@
 pub const VisibilityPartiallyObscured: i32 = 1;
 pub const VisibilityFullyObscured: i32 = 2;
 
+pub const XIMPreeditCallbacks: u32 = 2;
+pub const XIMPreeditPosition: u32 = 4;
 pub const XIMPreeditNothing: u32 = 8;
 pub const XIMStatusNothing: u32 = 1024;
 
 pub const XNInputStyle: &'static [u8; 11usize] = b"inputStyle\0";
 pub const XNClientWindow: &'static [u8; 13usize] = b"clientWindow\0";
 pub const XNFocusWindow: &'static [u8; 12usize] = b"focusWindow\0";
+pub const XNQueryInputStyle: &'static [u8; 16usize] = b"queryInputStyle\0";
+pub const XNPreeditAttributes: &'static [u8; 18usize] = b"preeditAttributes\0";
+pub const XNSpotLocation: &'static [u8; 13usize] = b"spotLocation\0";
+pub const XNPreeditStartCallback: &'static [u8; 21usize] = b"preeditStartCallback\0";
+pub const XNPreeditDoneCallback: &'static [u8; 20usize] = b"preeditDoneCallback\0";
+pub const XNPreeditDrawCallback: &'static [u8; 20usize] = b"preeditDrawCallback\0";
+pub const XNPreeditCaretCallback: &'static [u8; 21usize] = b"preeditCaretCallback\0";
 
 pub const Mod1Mask: u32 = 8;
 pub const ShiftMask: u32 = 1;
@@ -375,6 +387,22 @@ pub fn XStoreName(
     
     pub fn XCreateIC(arg1: XIM, ...) -> XIC;
     
+    pub fn XDestroyIC(arg1: XIC);
+    
+    pub fn XGetIMValues(arg1: XIM, ...) -> *mut c_char;
+    
+    pub fn XSetICValues(arg1: XIC, ...) -> *mut c_char;
+    
+    pub fn XSetICFocus(arg1: XIC);
+    
+    pub fn XUnsetICFocus(arg1: XIC);
+    
+    pub fn XVaCreateNestedList(arg1: c_int, ...) -> *mut c_void;
+    
+    pub fn XSetLocaleModifiers(arg1: *const c_char) -> *mut c_char;
+    
+    pub fn XFilterEvent(arg1: *mut XEvent, arg2: Window) -> c_int;
+    
     pub fn XDestroyWindow(arg1: *mut Display, arg2: Window) -> c_int;
     
     pub fn XIconifyWindow(
@@ -383,6 +411,14 @@ pub fn XIconifyWindow(
         arg3: c_int,
     ) -> c_int;
     
+    pub fn XWithdrawWindow(
+        arg1: *mut Display,
+        arg2: Window,
+        arg3: c_int,
+    ) -> c_int;
+    
+    pub fn XMapRaised(arg1: *mut Display, arg2: Window) -> c_int;
+    
     pub fn XGetWindowAttributes(
         arg1: *mut Display,
         arg2: Window,
@@ -419,6 +455,41 @@ pub fn XSetInputFocus(
     
     pub fn XUngrabPointer(arg1: *mut Display, arg2: Time) -> c_int;
     
+    pub fn XGrabPointer(
+        arg1: *mut Display,
+        arg2: Window,
+        arg3: c_int,
+        arg4: c_uint,
+        arg5: c_int,
+        arg6: c_int,
+        arg7: Window,
+        arg8: Cursor,
+        arg9: Time,
+    ) -> c_int;
+    
+    pub fn XTranslateCoordinates(
+        arg1: *mut Display,
+        arg2: Window,
+        arg3: Window,
+        arg4: c_int,
+        arg5: c_int,
+        arg6: *mut c_int,
+        arg7: *mut c_int,
+        arg8: *mut Window,
+    ) -> c_int;
+    
+    pub fn XQueryPointer(
+        arg1: *mut Display,
+        arg2: Window,
+        arg3: *mut Window,
+        arg4: *mut Window,
+        arg5: *mut c_int,
+        arg6: *mut c_int,
+        arg7: *mut c_int,
+        arg8: *mut c_int,
+        arg9: *mut c_uint,
+    ) -> c_int;
+    
     pub fn XSetSelectionOwner(
         arg1: *mut Display,
         arg2: Atom,
@@ -470,6 +541,55 @@ pub struct _XIC {
     _unused: [u8; 0],
 }
 
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XPoint {
+    pub x: c_short,
+    pub y: c_short,
+}
+
+pub type XIMStyle = c_ulong;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIMStyles {
+    pub count_styles: u16,
+    pub supported_styles: *mut XIMStyle,
+}
+
+// the callback is stored untyped, the start callback returns a value where the others don't
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIMCallback {
+    pub client_data: XPointer,
+    pub callback: *const c_void,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union XIMTextString {
+    pub multi_byte: *mut c_char,
+    pub wide_char: *mut u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct XIMText {
+    pub length: u16,
+    pub feedback: *mut c_ulong,
+    pub encoding_is_wchar: c_int,
+    pub string: XIMTextString,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct XIMPreeditDrawCallbackStruct {
+    pub caret: c_int,
+    pub chg_first: c_int,
+    pub chg_length: c_int,
+    pub text: *mut XIMText,
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _XComposeStatus {
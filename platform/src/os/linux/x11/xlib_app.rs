@@ -11,6 +11,8 @@
         x11_sys,
         xlib_event::XlibEvent,
         xlib_window::*,
+        xlib_dnd::Dnd,
+        xlib_ime::open_input_method,
         super::select_timer::SelectTimers,
     },
     crate::{
@@ -39,6 +41,7 @@ pub struct XlibApp {
     pub display: *mut x11_sys::Display,
     event_loop_running: bool,
     pub xim: x11_sys::XIM,
+    pub xim_style: x11_sys::XIMStyle,
     pub clipboard: String,
     pub display_fd: c_int,
     //pub signal_fds: [c_int; 2],
@@ -64,7 +67,7 @@ pub fn new(event_callback: Box<dyn FnMut(&mut XlibApp, XlibEvent) -> EventFlow>)
         unsafe {
             let display = x11_sys::XOpenDisplay(ptr::null());
             let display_fd = x11_sys::XConnectionNumber(display);
-            let xim = x11_sys::XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+            let (xim, xim_style) = open_input_method(display);
             //let mut signal_fds = [0, 0];
             //libc_sys::pipe(signal_fds.as_mut_ptr());
             x11_sys::XrmInitialize();
@@ -73,6 +76,7 @@ pub fn new(event_callback: Box<dyn FnMut(&mut XlibApp, XlibEvent) -> EventFlow>)
                 event_callback: Some(event_callback),
                 atoms: XlibAtoms::new(display),
                 xim,
+                xim_style,
                 display,
                 display_fd,
                 //signal_fds,
@@ -99,11 +103,15 @@ pub unsafe fn event_loop_poll(&mut self) {
             let mut event = mem::MaybeUninit::uninit();
             x11_sys::XNextEvent(self.display, event.as_mut_ptr());
             let mut event = event.assume_init();
+            // the input method gets the first look at keys, it swallows the ones that go into composed text
+            if x11_sys::XFilterEvent(&mut event, 0) != 0 {
+                continue;
+            }
             match event.type_ as u32 {
                 x11_sys::SelectionNotify => {
                     let selection = event.xselection;
-                    if selection.property == self.dnd.atoms.selection {
-                        self.dnd.handle_selection_event(&selection);
+                    if selection.selection == self.dnd.atoms.selection {
+                        self.dnd_handle_selection_notify(&selection);
                     } else {
                         // first get the size of the thing
                         let mut actual_type = mem::MaybeUninit::uninit();
@@ -162,6 +170,9 @@ pub unsafe fn event_loop_poll(&mut self) {
                 },
                 x11_sys::SelectionRequest => {
                     let request = event.xselectionrequest;
+                    if self.dnd_handle_selection_request(&request) {
+                        continue;
+                    }
                     let mut response = x11_sys::XSelectionEvent {
                         type_: x11_sys::SelectionNotify as i32,
                         serial: 0,
@@ -221,6 +232,16 @@ pub unsafe fn event_loop_poll(&mut self) {
                         }
                     }
                 },
+                x11_sys::PropertyNotify => {
+                    // the window manager keeps the maximized, fullscreen and topmost state in _NET_WM_STATE
+                    let property = event.xproperty;
+                    if property.atom == self.atoms.net_wm_state {
+                        if let Some(window_ptr) = self.window_map.get(&property.window) {
+                            let window = &mut (**window_ptr);
+                            window.send_change_event();
+                        }
+                    }
+                },
                 x11_sys::EnterNotify => {},
                 x11_sys::LeaveNotify => {
                     let crossing = event.xcrossing;
@@ -247,6 +268,10 @@ pub unsafe fn event_loop_poll(&mut self) {
                 },
                 x11_sys::MotionNotify => { // mousemove
                     let motion = event.xmotion;
+                    if self.dnd.is_dragging() {
+                        self.dnd_source_motion(motion.x_root, motion.y_root, motion.time);
+                        continue;
+                    }
                     if let Some(window_ptr) = self.window_map.get(&motion.window) {
                         let window = &mut (**window_ptr);
                         let x = motion.x;
@@ -415,6 +440,10 @@ pub unsafe fn event_loop_poll(&mut self) {
                 },
                 x11_sys::ButtonRelease => { // mouse up
                     let button = event.xbutton;
+                    if self.dnd.is_dragging() {
+                        self.dnd_source_release(button.x_root, button.y_root, button.time);
+                        continue;
+                    }
                     if let Some(window_ptr) = self.window_map.get(&button.window) {
                         let window = &mut (**window_ptr);
                         window.send_mouse_up(
@@ -424,6 +453,12 @@ pub unsafe fn event_loop_poll(&mut self) {
                     }
                 },
                 x11_sys::KeyPress => {
+                    if self.dnd.is_dragging() {
+                        if self.xkeyevent_to_keycode(&mut event.xkey) == KeyCode::Escape {
+                            self.dnd_source_cancel();
+                        }
+                        continue;
+                    }
                     if let Some(window_ptr) = self.window_map.get(&event.xkey.window) {
                         let window = &mut (**window_ptr);
                         let block_text = if event.xkey.keycode != 0 {
@@ -488,30 +523,17 @@ pub unsafe fn event_loop_poll(&mut self) {
                         }else {false};
                         
                         if !block_text {
-                            // decode the character
-                            let mut buffer = [0u8; 32];
-                            let mut keysym = mem::MaybeUninit::uninit();
-                            let mut status = mem::MaybeUninit::uninit();
-                            let count = x11_sys::Xutf8LookupString(
-                                window.xic.unwrap(),
-                                &mut event.xkey,
-                                buffer.as_mut_ptr() as *mut c_char,
-                                buffer.len() as c_int,
-                                keysym.as_mut_ptr(),
-                                status.as_mut_ptr(),
-                            );
-                            //let keysym = keysym.assume_init();
-                            let status = status.assume_init();
-                            if status != x11_sys::XBufferOverflow {
-                                let utf8 = std::str::from_utf8(&buffer[..count as usize]).unwrap_or("").to_string();
-                                let char_code = utf8.chars().next().unwrap_or('\0');
-                                if char_code >= ' ' && char_code != 127 as char {
-                                    self.do_callback(XlibEvent::TextInput(TextInputEvent {
-                                        input: utf8,
-                                        was_paste: false,
-                                        replace_last: false
-                                    }));
-                                }
+                            // decode the character, or the text the input method committed
+                            let utf8 = self.lookup_string(window.xic, &mut event.xkey);
+                            let char_code = utf8.chars().next().unwrap_or('\0');
+                            if char_code >= ' ' && char_code != 127 as char {
+                                // the committed text takes the place of what was being composed
+                                let replace_last = !window.take_ime_preedit().is_empty();
+                                self.do_callback(XlibEvent::TextInput(TextInputEvent {
+                                    input: utf8,
+                                    was_paste: false,
+                                    replace_last
+                                }));
                             }
                         }
                     }
@@ -547,13 +569,17 @@ pub unsafe fn event_loop_poll(&mut self) {
                         }
                     }
                     if event.message_type == self.dnd.atoms.enter {
-                        self.dnd.handle_enter_event(&event);
+                        self.dnd_handle_enter(&event);
                     } else if event.message_type == self.dnd.atoms.drop {
-                        self.dnd.handle_drop_event(&event);
+                        self.dnd_handle_drop(&event);
                     } else if event.message_type == self.dnd.atoms.leave {
-                        self.dnd.handle_leave_event(&event);
+                        self.dnd_handle_leave(&event);
                     } else if event.message_type == self.dnd.atoms.position {
-                        self.dnd.handle_position_event(&event);
+                        self.dnd_handle_position(&event);
+                    } else if event.message_type == self.dnd.atoms.status {
+                        self.dnd_handle_status(&event);
+                    } else if event.message_type == self.dnd.atoms.finished {
+                        self.dnd_handle_finished(&event);
                     }
                 },
                 x11_sys::Expose => {
@@ -748,7 +774,39 @@ fn xbutton_to_mouse_button(&self, button: u32) -> MouseButton {
         }
     }
 
-    fn xkeystate_to_modifiers(&self, state: c_uint) -> KeyModifiers {
+    unsafe fn lookup_string(&self, xic: Option<x11_sys::XIC>, key_event: &mut x11_sys::XKeyEvent) -> String {
+        let mut buffer = vec![0u8; 32];
+        let mut keysym = mem::MaybeUninit::uninit();
+        let Some(xic) = xic else {
+            let count = x11_sys::XLookupString(
+                key_event,
+                buffer.as_mut_ptr() as *mut c_char,
+                buffer.len() as c_int,
+                keysym.as_mut_ptr(),
+                ptr::null_mut(),
+            );
+            return String::from_utf8_lossy(&buffer[..count.max(0) as usize]).into_owned()
+        };
+        loop {
+            let mut status = mem::MaybeUninit::uninit();
+            let count = x11_sys::Xutf8LookupString(
+                xic,
+                key_event,
+                buffer.as_mut_ptr() as *mut c_char,
+                buffer.len() as c_int,
+                keysym.as_mut_ptr(),
+                status.as_mut_ptr(),
+            );
+            // committed text can be longer than a key, the count is the size needed
+            if status.assume_init() == x11_sys::XBufferOverflow {
+                buffer.resize(count as usize, 0);
+                continue;
+            }
+            return std::str::from_utf8(&buffer[..count.max(0) as usize]).unwrap_or("").to_string()
+        }
+    }
+    
+    pub fn xkeystate_to_modifiers(&self, state: c_uint) -> KeyModifiers {
         KeyModifiers {
             alt: state & x11_sys::Mod1Mask != 0,
             shift: state & x11_sys::ShiftMask != 0,
@@ -927,6 +985,9 @@ pub struct XlibAtoms {
     pub wm_class: x11_sys::Atom,
     pub motif_wm_hints: x11_sys::Atom,
     pub net_wm_state: x11_sys::Atom,
+    pub net_wm_state_fullscreen: x11_sys::Atom,
+    pub net_wm_state_above: x11_sys::Atom,
+    pub net_active_window: x11_sys::Atom,
     pub new_wm_state_maximized_horz: x11_sys::Atom,
     pub new_wm_state_maximized_vert: x11_sys::Atom,
     pub targets: x11_sys::Atom,
@@ -948,6 +1009,9 @@ fn new(display: *mut x11_sys::Display) -> Self {
             wm_class: x11_sys::XInternAtom(display, "WM_CLASS\0".as_ptr() as *const _, 0),
             motif_wm_hints: x11_sys::XInternAtom(display, "_MOTIF_WM_HINTS\0".as_ptr() as *const _, 0),
             net_wm_state: x11_sys::XInternAtom(display, "_NET_WM_STATE\0".as_ptr() as *const _, 0),
+            net_wm_state_fullscreen: x11_sys::XInternAtom(display, "_NET_WM_STATE_FULLSCREEN\0".as_ptr() as *const _, 0),
+            net_wm_state_above: x11_sys::XInternAtom(display, "_NET_WM_STATE_ABOVE\0".as_ptr() as *const _, 0),
+            net_active_window: x11_sys::XInternAtom(display, "_NET_ACTIVE_WINDOW\0".as_ptr() as *const _, 0),
             new_wm_state_maximized_horz: x11_sys::XInternAtom(display, "_NET_WM_STATE_MAXIMIZED_HORZ\0".as_ptr() as *const _, 0),
             new_wm_state_maximized_vert: x11_sys::XInternAtom(display, "_NET_WM_STATE_MAXIMIZED_VERT\0".as_ptr() as *const _, 0),
             targets: x11_sys::XInternAtom(display, "TARGETS\0".as_ptr() as *const _, 0),
@@ -0,0 +1,775 @@
+// XDND drag and drop, both as a target for drags coming from other applications and as the source of drags
+// started with `CxOsOp::StartDragging`. Drags between our own windows never leave the process, they turn into
+// Drag and Drop events directly.
+use {
+    std::{
+        mem,
+        ptr,
+        sync::{Arc, Mutex},
+        os::raw::{c_int, c_long, c_uchar, c_uint, c_ulong, c_void},
+    },
+    self::super::{
+        x11_sys,
+        xlib_app::XlibApp,
+        xlib_event::XlibEvent,
+        super::dbus::percent_decode,
+    },
+    crate::{
+        makepad_live_id::LiveId,
+        makepad_math::DVec2,
+        event::{DragEvent, DropEvent, DragItem, DragResponse, KeyModifiers},
+    },
+};
+
+// the version we speak, which hasn't changed since 2002
+const XDND_VERSION: c_long = 5;
+const XA_ATOM: x11_sys::Atom = 4;
+
+pub struct Dnd {
+    pub atoms: DndAtoms,
+    pub display: *mut x11_sys::Display,
+    target: Option<DndTarget>,
+    source: Option<DndSource>,
+}
+
+// a drag from another application that is over one of our windows
+struct DndTarget {
+    source_window: x11_sys::Window,
+    target_window: x11_sys::Window,
+    data_type: Option<x11_sys::Atom>,
+    requested: bool,
+    items: Option<Arc<Vec<DragItem >>>,
+    abs: DVec2,
+    drop_requested: bool,
+}
+
+// what a drag we started is over
+#[derive(Clone, Copy, PartialEq)]
+enum DndSourceTarget {
+    Nothing,
+    Internal(x11_sys::Window),
+    Foreign {window: x11_sys::Window, version: c_long},
+}
+
+// a drag we started
+struct DndSource {
+    window: x11_sys::Window,
+    items: Arc<Vec<DragItem >>,
+    types: Vec<x11_sys::Atom>,
+    target: DndSourceTarget,
+    accepted: bool,
+    action: x11_sys::Atom,
+    // the protocol wants one position in flight at a time, newer ones wait for the status
+    waiting_for_status: bool,
+    pending_position: Option<(c_int, c_int, x11_sys::Time)>,
+    // dropped on another application, which still has to fetch the data
+    dropped: bool,
+}
+
+impl Dnd {
+    pub unsafe fn new(display: *mut x11_sys::Display) -> Dnd {
+        Dnd {
+            atoms: DndAtoms::new(display),
+            display,
+            target: None,
+            source: None,
+        }
+    }
+
+    /// Enables drag-and-drop for the given window.
+    pub unsafe fn enable_for_window(&mut self, window: x11_sys::Window) {
+        // To enable drag-and-drop for a window, we need to set the XDndAware property of the window
+        // to the version of XDnd we support.
+        let version = XDND_VERSION as c_ulong;
+        x11_sys::XChangeProperty(
+            self.display,
+            window,
+            self.atoms.aware,
+            XA_ATOM,
+            32,
+            x11_sys::PropModeReplace as c_int,
+            &version as *const c_ulong as *const c_uchar,
+            1
+        );
+    }
+
+    /// Whether a drag we started is following the mouse.
+    pub fn is_dragging(&self) -> bool {
+        self.source.as_ref().is_some_and( | source | !source.dropped)
+    }
+
+    fn response_to_action(&self, response: DragResponse) -> x11_sys::Atom {
+        match response {
+            DragResponse::None => 0,
+            DragResponse::Copy => self.atoms.action_copy,
+            DragResponse::Move => self.atoms.action_move,
+            DragResponse::Link => self.atoms.action_link,
+        }
+    }
+}
+
+impl XlibApp {
+    unsafe fn root_window(&self) -> x11_sys::Window {
+        x11_sys::XRootWindow(self.display, x11_sys::XDefaultScreen(self.display))
+    }
+
+    unsafe fn send_dnd_message(&self, to: x11_sys::Window, message_type: x11_sys::Atom, l: [c_long; 5]) {
+        let mut xclient = x11_sys::XClientMessageEvent {
+            type_: x11_sys::ClientMessage as c_int,
+            serial: 0,
+            send_event: 0,
+            display: self.display,
+            window: to,
+            message_type,
+            format: 32,
+            data: {
+                let mut data = mem::zeroed::<x11_sys::XClientMessageEvent__bindgen_ty_1>();
+                data.l = l;
+                data
+            }
+        };
+        x11_sys::XSendEvent(
+            self.display,
+            to,
+            x11_sys::False as c_int,
+            x11_sys::NoEventMask as c_long,
+            &mut xclient as *mut _ as *mut x11_sys::XEvent
+        );
+        x11_sys::XFlush(self.display);
+    }
+
+    unsafe fn get_property(&self, window: x11_sys::Window, property: x11_sys::Atom, type_: x11_sys::Atom) -> (x11_sys::Atom, Vec<u8>) {
+        let mut data = Vec::new();
+        let mut offset = 0;
+        let length = 1024;
+        let mut actual_type = 0;
+        loop {
+            let mut actual_format = 0;
+            let mut nitems = 0;
+            let mut bytes_after = 0;
+            let mut prop = ptr::null_mut();
+            x11_sys::XGetWindowProperty(
+                self.display,
+                window,
+                property,
+                offset,
+                length,
+                x11_sys::False as c_int,
+                type_,
+                &mut actual_type,
+                &mut actual_format,
+                &mut nitems,
+                &mut bytes_after,
+                &mut prop,
+            );
+            if prop.is_null() {
+                break
+            }
+            // 32 bit items are stored as longs on the client side
+            let item_size = match actual_format {
+                32 => mem::size_of::<c_ulong>(),
+                16 => 2,
+                _ => 1
+            };
+            data.extend_from_slice(std::slice::from_raw_parts(prop, nitems as usize * item_size));
+            x11_sys::XFree(prop as *mut c_void);
+            if bytes_after == 0 {
+                break;
+            }
+            // the offset counts 32 bit units of the property as the server stores it
+            offset += (nitems as usize * actual_format as usize / 32) as c_long;
+        }
+        (actual_type, data)
+    }
+
+    unsafe fn get_atom_list_property(&self, window: x11_sys::Window, property: x11_sys::Atom) -> Vec<x11_sys::Atom> {
+        let (_, data) = self.get_property(window, property, XA_ATOM);
+        data.chunks_exact(mem::size_of::<c_ulong>())
+            .map( | chunk | c_ulong::from_ne_bytes(chunk.try_into().unwrap()) as x11_sys::Atom)
+            .collect()
+    }
+
+    // the XdndAware version of a window, None when it doesn't take drops
+    unsafe fn dnd_aware_version(&self, window: x11_sys::Window) -> Option<c_long> {
+        self.get_atom_list_property(window, self.dnd.atoms.aware).first().map( | version | *version as c_long)
+    }
+
+    // walks down from the root to the innermost window under the pointer that takes drops
+    unsafe fn find_dnd_target(&self, root_x: c_int, root_y: c_int) -> DndSourceTarget {
+        let root = self.root_window();
+        let mut window = root;
+        for _ in 0..32 {
+            let mut child = 0;
+            let (mut x, mut y) = (0, 0);
+            if x11_sys::XTranslateCoordinates(self.display, root, window, root_x, root_y, &mut x, &mut y, &mut child) == 0 || child == 0 {
+                break
+            }
+            window = child;
+            if self.window_map.contains_key(&window) {
+                return DndSourceTarget::Internal(window)
+            }
+            if let Some(version) = self.dnd_aware_version(window) {
+                return DndSourceTarget::Foreign {window, version: version.min(XDND_VERSION)}
+            }
+        }
+        DndSourceTarget::Nothing
+    }
+
+    // root coordinates to the position in one of our windows
+    unsafe fn dnd_window_pos(&self, window: x11_sys::Window, root_x: c_int, root_y: c_int) -> DVec2 {
+        let Some(window_ptr) = self.window_map.get(&window) else {
+            return DVec2::default()
+        };
+        let (mut x, mut y) = (0, 0);
+        let mut child = 0;
+        x11_sys::XTranslateCoordinates(self.display, self.root_window(), window, root_x, root_y, &mut x, &mut y, &mut child);
+        let dpi_factor = (**window_ptr).last_window_geom.dpi_factor;
+        DVec2 {x: x as f64 / dpi_factor, y: y as f64 / dpi_factor}
+    }
+
+    unsafe fn pointer_modifiers(&self) -> KeyModifiers {
+        let (mut root, mut child) = (0, 0);
+        let (mut root_x, mut root_y, mut x, mut y) = (0, 0, 0, 0);
+        let mut mask: c_uint = 0;
+        x11_sys::XQueryPointer(self.display, self.root_window(), &mut root, &mut child, &mut root_x, &mut root_y, &mut x, &mut y, &mut mask);
+        self.xkeystate_to_modifiers(mask)
+    }
+
+    unsafe fn dnd_send_drag(&mut self, abs: DVec2, items: Arc<Vec<DragItem >>) -> DragResponse {
+        let response = Arc::new(Mutex::new(DragResponse::None));
+        self.do_callback(XlibEvent::Drag(DragEvent {
+            modifiers: self.pointer_modifiers(),
+            handled: Arc::new(Mutex::new(false)),
+            abs,
+            items,
+            response: response.clone(),
+        }));
+        let response = *response.lock().unwrap();
+        response
+    }
+
+    unsafe fn dnd_send_drop(&mut self, abs: DVec2, items: Arc<Vec<DragItem >>) -> bool {
+        let handled = Arc::new(Mutex::new(false));
+        self.do_callback(XlibEvent::Drop(DropEvent {
+            modifiers: self.pointer_modifiers(),
+            handled: handled.clone(),
+            abs,
+            items,
+        }));
+        let handled = *handled.lock().unwrap();
+        handled
+    }
+
+    /// Handles a XDndEnter event, another application drags something into one of our windows.
+    pub unsafe fn dnd_handle_enter(&mut self, event: &x11_sys::XClientMessageEvent) {
+        let source_window = event.data.l[0] as x11_sys::Window;
+        let has_more_types = event.data.l[1] & (1 << 0) != 0;
+
+        // If the has_more_types flags is set, we have to obtain the list of supported types from
+        // the XDndTypeList property. Otherwise, we can obtain the list of supported types from the
+        // event itself.
+        let type_list: Vec<x11_sys::Atom> = if has_more_types {
+            self.get_atom_list_property(source_window, self.dnd.atoms.type_list)
+        } else {
+            event.data.l[2..5]
+                .iter()
+                .map( | &l | l as x11_sys::Atom)
+                .filter( | &atom | atom != x11_sys::None as x11_sys::Atom)
+                .collect()
+        };
+        let preferred = [self.dnd.atoms.makepad_items, self.dnd.atoms.uri_list, self.atoms.utf8_string, self.atoms.text_plain];
+        self.dnd.target = Some(DndTarget {
+            source_window,
+            target_window: event.window,
+            data_type: preferred.into_iter().find( | atom | type_list.contains(atom)),
+            requested: false,
+            items: None,
+            abs: DVec2::default(),
+            drop_requested: false,
+        });
+    }
+
+    /// Handles a XDndPosition event, sent every time the mouse moves during the drag.
+    pub unsafe fn dnd_handle_position(&mut self, event: &x11_sys::XClientMessageEvent) {
+        let Some(target) = self.dnd.target.as_ref() else {return};
+        let (source_window, target_window) = (target.source_window, target.target_window);
+        if event.data.l[0] as x11_sys::Window != source_window {
+            return
+        }
+        let root_x = (event.data.l[2] >> 16) as c_int;
+        let root_y = (event.data.l[2] & 0xffff) as c_int;
+        let time = event.data.l[3] as x11_sys::Time;
+        let abs = self.dnd_window_pos(target_window, root_x, root_y);
+        let target = self.dnd.target.as_mut().unwrap();
+        target.abs = abs;
+
+        let Some(data_type) = target.data_type else {
+            self.dnd_send_status(source_window, target_window, DragResponse::None);
+            return
+        };
+        // the data is fetched once, the widgets can only respond to the drag once it is in
+        if let Some(items) = target.items.clone() {
+            let response = self.dnd_send_drag(abs, items);
+            self.dnd_send_status(source_window, target_window, response);
+        }
+        else {
+            if !target.requested {
+                target.requested = true;
+                x11_sys::XConvertSelection(self.display, self.dnd.atoms.selection, data_type, self.dnd.atoms.selection, target_window, time);
+            }
+            self.dnd_send_status(source_window, target_window, DragResponse::Copy);
+        }
+    }
+
+    /// Handles a XDndDrop event, the mouse button was released over our window.
+    pub unsafe fn dnd_handle_drop(&mut self, event: &x11_sys::XClientMessageEvent) {
+        let Some(target) = self.dnd.target.as_mut() else {return};
+        if event.data.l[0] as x11_sys::Window != target.source_window {
+            return
+        }
+        target.drop_requested = true;
+        if target.items.is_some() {
+            self.dnd_finish_drop();
+        }
+        else if let (false, Some(data_type)) = (target.requested, target.data_type) {
+            target.requested = true;
+            let time = event.data.l[2] as x11_sys::Time;
+            x11_sys::XConvertSelection(self.display, self.dnd.atoms.selection, data_type, self.dnd.atoms.selection, target.target_window, time);
+        }
+        else if target.data_type.is_none() {
+            self.dnd_finish_drop();
+        }
+    }
+
+    /// Handles a XDndLeave event, the drag left our window or was cancelled.
+    pub unsafe fn dnd_handle_leave(&mut self, event: &x11_sys::XClientMessageEvent) {
+        if self.dnd.target.as_ref().is_some_and( | target | target.source_window == event.data.l[0] as x11_sys::Window) {
+            self.dnd.target = None;
+            self.do_callback(XlibEvent::DragEnd);
+        }
+    }
+
+    /// Handles the XSelectionEvent answering our request for the dragged data.
+    pub unsafe fn dnd_handle_selection_notify(&mut self, event: &x11_sys::XSelectionEvent) {
+        let Some(target) = self.dnd.target.as_ref() else {return};
+        if event.requestor != target.target_window {
+            return
+        }
+        let items = if event.property == 0 {
+            Vec::new()
+        }
+        else {
+            let (_, data) = self.get_property(event.requestor, event.property, x11_sys::AnyPropertyType as x11_sys::Atom);
+            let data = String::from_utf8_lossy(&data);
+            let data_type = target.data_type.unwrap_or(0);
+            if data_type == self.dnd.atoms.makepad_items {
+                decode_makepad_items(&data)
+            }
+            else if data_type == self.dnd.atoms.uri_list {
+                decode_uri_list(&data)
+            }
+            else {
+                vec![DragItem::String {value: data.into_owned(), internal_id: None}]
+            }
+        };
+        let items = Arc::new(items);
+        let target = self.dnd.target.as_mut().unwrap();
+        target.items = Some(items.clone());
+        if target.drop_requested {
+            self.dnd_finish_drop();
+        }
+        else {
+            // let the widgets show their drop position without waiting for the next move
+            let abs = target.abs;
+            self.dnd_send_drag(abs, items);
+        }
+    }
+
+    unsafe fn dnd_finish_drop(&mut self) {
+        let Some(target) = self.dnd.target.take() else {return};
+        let items = target.items.unwrap_or_default();
+        let accepted = !items.is_empty() && self.dnd_send_drop(target.abs, items);
+        self.do_callback(XlibEvent::DragEnd);
+        self.send_dnd_message(target.source_window, self.dnd.atoms.finished, [
+            target.target_window as c_long,
+            accepted as c_long,
+            if accepted {self.dnd.atoms.action_copy as c_long} else {0},
+            0,
+            0
+        ]);
+    }
+
+    /// Sends a XDndStatus event to the source window.
+    unsafe fn dnd_send_status(&self, source_window: x11_sys::Window, target_window: x11_sys::Window, response: DragResponse) {
+        let accepted = response != DragResponse::None;
+        self.send_dnd_message(source_window, self.dnd.atoms.status, [
+            target_window as c_long,
+            accepted as c_long,
+            0,
+            0,
+            self.dnd.response_to_action(response) as c_long
+        ]);
+    }
+
+    /// Starts dragging items from one of our windows. Until the mouse button is released the pointer is grabbed,
+    /// so moves and the release come to us wherever the mouse goes.
+    pub unsafe fn start_dragging(&mut self, window: x11_sys::Window, items: Vec<DragItem>) {
+        if items.is_empty() {
+            return
+        }
+        let mut types = vec![self.dnd.atoms.makepad_items];
+        if items.iter().any( | item | matches!(item, DragItem::FilePath {path, ..} if !path.is_empty())) {
+            types.push(self.dnd.atoms.uri_list);
+        }
+        types.push(self.atoms.utf8_string);
+        types.push(self.atoms.text_plain);
+        if types.len() > 3 {
+            let list: Vec<c_ulong> = types.iter().map( | atom | *atom as c_ulong).collect();
+            x11_sys::XChangeProperty(
+                self.display,
+                window,
+                self.dnd.atoms.type_list,
+                XA_ATOM,
+                32,
+                x11_sys::PropModeReplace as c_int,
+                list.as_ptr() as *const c_uchar,
+                list.len() as c_int
+            );
+        }
+        x11_sys::XSetSelectionOwner(self.display, self.dnd.atoms.selection, window, x11_sys::CurrentTime as x11_sys::Time);
+        x11_sys::XGrabPointer(
+            self.display,
+            window,
+            x11_sys::False as c_int,
+            (x11_sys::PointerMotionMask | x11_sys::ButtonMotionMask | x11_sys::ButtonReleaseMask) as c_uint,
+            x11_sys::GrabModeAsync,
+            x11_sys::GrabModeAsync,
+            0,
+            0,
+            x11_sys::CurrentTime as x11_sys::Time
+        );
+        x11_sys::XFlush(self.display);
+        self.dnd.source = Some(DndSource {
+            window,
+            items: Arc::new(items),
+            types,
+            target: DndSourceTarget::Nothing,
+            accepted: false,
+            action: 0,
+            waiting_for_status: false,
+            pending_position: None,
+            dropped: false,
+        });
+    }
+
+    // tells whatever the drag was over that it has left
+    unsafe fn dnd_source_leave_target(&mut self) {
+        let Some(source) = self.dnd.source.as_ref() else {return};
+        match source.target {
+            DndSourceTarget::Foreign {window, ..} => {
+                self.send_dnd_message(window, self.dnd.atoms.leave, [source.window as c_long, 0, 0, 0, 0]);
+            }
+            DndSourceTarget::Internal(_) => {
+                // a drag far outside of every window, so drop targets see it go
+                let items = source.items.clone();
+                self.dnd_send_drag(DVec2 {x: -100000.0, y: -100000.0}, items);
+            }
+            DndSourceTarget::Nothing => ()
+        }
+    }
+
+    /// Follows the mouse while dragging.
+    pub unsafe fn dnd_source_motion(&mut self, root_x: c_int, root_y: c_int, time: x11_sys::Time) {
+        let target = self.find_dnd_target(root_x, root_y);
+        let Some(source) = self.dnd.source.as_ref() else {return};
+        if source.target != target {
+            self.dnd_source_leave_target();
+            let source = self.dnd.source.as_mut().unwrap();
+            source.target = target;
+            source.accepted = false;
+            source.action = 0;
+            source.waiting_for_status = false;
+            source.pending_position = None;
+            if let DndSourceTarget::Foreign {window, version} = target {
+                let (source_window, types) = (source.window, source.types.clone());
+                let type_at = | i: usize | types.get(i).cloned().unwrap_or(0) as c_long;
+                self.send_dnd_message(window, self.dnd.atoms.enter, [
+                    source_window as c_long,
+                    (version << 24) | (types.len() > 3) as c_long,
+                    type_at(0),
+                    type_at(1),
+                    type_at(2)
+                ]);
+            }
+        }
+        let source = self.dnd.source.as_mut().unwrap();
+        match target {
+            DndSourceTarget::Internal(window) => {
+                let items = source.items.clone();
+                let abs = self.dnd_window_pos(window, root_x, root_y);
+                let response = self.dnd_send_drag(abs, items);
+                let action = self.dnd.response_to_action(response);
+                if let Some(source) = self.dnd.source.as_mut() {
+                    source.accepted = response != DragResponse::None;
+                    source.action = action;
+                }
+            }
+            DndSourceTarget::Foreign {window, ..} => {
+                if source.waiting_for_status {
+                    source.pending_position = Some((root_x, root_y, time));
+                }
+                else {
+                    source.waiting_for_status = true;
+                    let source_window = source.window;
+                    self.dnd_send_position(window, source_window, root_x, root_y, time);
+                }
+            }
+            DndSourceTarget::Nothing => ()
+        }
+    }
+
+    unsafe fn dnd_send_position(&self, window: x11_sys::Window, source_window: x11_sys::Window, root_x: c_int, root_y: c_int, time: x11_sys::Time) {
+        self.send_dnd_message(window, self.dnd.atoms.position, [
+            source_window as c_long,
+            0,
+            ((root_x as c_long) << 16) | (root_y as c_long & 0xffff),
+            time as c_long,
+            self.dnd.atoms.action_copy as c_long
+        ]);
+    }
+
+    /// The mouse button was released while dragging, drops on whatever the drag is over.
+    pub unsafe fn dnd_source_release(&mut self, root_x: c_int, root_y: c_int, time: x11_sys::Time) {
+        x11_sys::XUngrabPointer(self.display, time);
+        let Some(source) = self.dnd.source.as_mut() else {return};
+        match source.target {
+            DndSourceTarget::Internal(window) => {
+                let items = source.items.clone();
+                self.dnd.source = None;
+                let abs = self.dnd_window_pos(window, root_x, root_y);
+                self.dnd_send_drop(abs, items);
+            }
+            DndSourceTarget::Foreign {window, ..} if source.accepted => {
+                // we keep the selection until the target says it has the data
+                source.dropped = true;
+                let source_window = source.window;
+                self.send_dnd_message(window, self.dnd.atoms.drop, [source_window as c_long, 0, time as c_long, 0, 0]);
+            }
+            _ => {
+                self.dnd_source_leave_target();
+                self.dnd.source = None;
+            }
+        }
+        self.do_callback(XlibEvent::DragEnd);
+    }
+
+    /// Cancels the drag we started, for instance when escape is pressed.
+    pub unsafe fn dnd_source_cancel(&mut self) {
+        if !self.dnd.is_dragging() {
+            return
+        }
+        x11_sys::XUngrabPointer(self.display, x11_sys::CurrentTime as x11_sys::Time);
+        self.dnd_source_leave_target();
+        self.dnd.source = None;
+        self.do_callback(XlibEvent::DragEnd);
+    }
+
+    /// Handles a XDndStatus event, the target telling whether it would take the drop.
+    pub unsafe fn dnd_handle_status(&mut self, event: &x11_sys::XClientMessageEvent) {
+        let Some(source) = self.dnd.source.as_mut() else {return};
+        let DndSourceTarget::Foreign {window, ..} = source.target else {return};
+        if event.data.l[0] as x11_sys::Window != window {
+            return
+        }
+        source.accepted = event.data.l[1] & 1 != 0;
+        source.action = event.data.l[4] as x11_sys::Atom;
+        source.waiting_for_status = false;
+        if let Some((root_x, root_y, time)) = source.pending_position.take() {
+            source.waiting_for_status = true;
+            let source_window = source.window;
+            self.dnd_send_position(window, source_window, root_x, root_y, time);
+        }
+    }
+
+    /// Handles a XDndFinished event, the target is done with the data of a drop.
+    pub unsafe fn dnd_handle_finished(&mut self, _event: &x11_sys::XClientMessageEvent) {
+        if self.dnd.source.as_ref().is_some_and( | source | source.dropped) {
+            self.dnd.source = None;
+        }
+    }
+
+    /// Answers a request for the data of a drag we started, returns false when the request is not about a drag.
+    pub unsafe fn dnd_handle_selection_request(&mut self, request: &x11_sys::XSelectionRequestEvent) -> bool {
+        if request.selection != self.dnd.atoms.selection {
+            return false
+        }
+        let mut response = x11_sys::XSelectionEvent {
+            type_: x11_sys::SelectionNotify as i32,
+            serial: 0,
+            send_event: 0,
+            display: self.display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            time: request.time,
+            property: request.property,
+        };
+        let source = self.dnd.source.as_ref();
+        let data = source.and_then( | source | {
+            if request.target == self.dnd.atoms.makepad_items {
+                Some(encode_makepad_items(&source.items))
+            }
+            else if request.target == self.dnd.atoms.uri_list {
+                Some(encode_uri_list(&source.items))
+            }
+            else if request.target == self.atoms.utf8_string || request.target == self.atoms.text_plain || request.target == self.atoms.string {
+                Some(encode_text(&source.items))
+            }
+            else {
+                None
+            }
+        });
+        if let (true, Some(source)) = (request.target == self.atoms.targets, source) {
+            let mut targets: Vec<c_ulong> = source.types.iter().map( | atom | *atom as c_ulong).collect();
+            targets.push(self.atoms.targets as c_ulong);
+            x11_sys::XChangeProperty(
+                self.display,
+                request.requestor,
+                request.property,
+                XA_ATOM,
+                32,
+                x11_sys::PropModeReplace as c_int,
+                targets.as_ptr() as *const c_uchar,
+                targets.len() as c_int
+            );
+        }
+        else if let Some(data) = data {
+            x11_sys::XChangeProperty(
+                self.display,
+                request.requestor,
+                request.property,
+                request.target,
+                8,
+                x11_sys::PropModeReplace as c_int,
+                data.as_ptr(),
+                data.len() as c_int
+            );
+        }
+        else {
+            response.property = 0;
+        }
+        x11_sys::XSendEvent(self.display, request.requestor, 1, 0, &mut response as *mut _ as *mut x11_sys::XEvent);
+        x11_sys::XFlush(self.display);
+        true
+    }
+}
+
+pub fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            out.push(byte as char);
+        }
+        else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+pub fn encode_uri_list(items: &[DragItem]) -> String {
+    items.iter().filter_map( | item | match item {
+        DragItem::FilePath {path, ..} if !path.is_empty() => Some(format!("file://{}\r\n", percent_encode(path))),
+        _ => None
+    }).collect()
+}
+
+fn encode_text(items: &[DragItem]) -> String {
+    items.iter().map( | item | match item {
+        DragItem::FilePath {path, ..} => path.as_str(),
+        DragItem::String {value, ..} => value.as_str(),
+    }).collect::<Vec<_>>().join("\n")
+}
+
+// our own format keeps the internal ids, so drags between makepad applications work like drags within one.
+// every item is a line with its kind, the id or a dash, and the percent encoded value
+pub fn encode_makepad_items(items: &[DragItem]) -> String {
+    items.iter().map( | item | {
+        let (kind, value, internal_id) = match item {
+            DragItem::FilePath {path, internal_id} => ("file", path, internal_id),
+            DragItem::String {value, internal_id} => ("string", value, internal_id),
+        };
+        let id = internal_id.map( | id | id.0.to_string()).unwrap_or_else( | | "-".to_string());
+        format!("{} {} {}\n", kind, id, percent_encode(value))
+    }).collect()
+}
+
+pub fn decode_makepad_items(data: &str) -> Vec<DragItem> {
+    data.lines().filter_map( | line | {
+        let mut parts = line.splitn(3, ' ');
+        let (kind, id, value) = (parts.next()?, parts.next()?, parts.next().unwrap_or(""));
+        let internal_id = id.parse::<u64>().ok().map(LiveId);
+        let value = String::from_utf8_lossy(&percent_decode(value)).into_owned();
+        match kind {
+            "file" => Some(DragItem::FilePath {path: value, internal_id}),
+            "string" => Some(DragItem::String {value, internal_id}),
+            _ => None
+        }
+    }).collect()
+}
+
+pub fn decode_uri_list(data: &str) -> Vec<DragItem> {
+    data.lines().filter_map( | line | {
+        let line = line.trim_end_matches('\r');
+        if line.is_empty() || line.starts_with('#') {
+            return None
+        }
+        if let Some(rest) = line.strip_prefix("file://") {
+            // skip the host, file uris are local anyway
+            let path = &rest[rest.find('/')?..];
+            let path = String::from_utf8_lossy(&percent_decode(path)).into_owned();
+            Some(DragItem::FilePath {path, internal_id: None})
+        }
+        else {
+            Some(DragItem::String {value: line.to_string(), internal_id: None})
+        }
+    }).collect()
+}
+
+pub struct DndAtoms {
+    pub action_copy: x11_sys::Atom,
+    pub action_move: x11_sys::Atom,
+    pub action_link: x11_sys::Atom,
+    pub aware: x11_sys::Atom,
+    pub drop: x11_sys::Atom,
+    pub enter: x11_sys::Atom,
+    pub finished: x11_sys::Atom,
+    pub leave: x11_sys::Atom,
+    pub position: x11_sys::Atom,
+    pub selection: x11_sys::Atom,
+    pub status: x11_sys::Atom,
+    pub type_list: x11_sys::Atom,
+    pub uri_list: x11_sys::Atom,
+    pub makepad_items: x11_sys::Atom,
+}
+
+impl DndAtoms {
+    pub unsafe fn new(display: *mut x11_sys::Display) -> DndAtoms {
+        DndAtoms {
+            action_copy: x11_sys::XInternAtom(display, "XdndActionCopy\0".as_ptr() as *const _, 0),
+            action_move: x11_sys::XInternAtom(display, "XdndActionMove\0".as_ptr() as *const _, 0),
+            action_link: x11_sys::XInternAtom(display, "XdndActionLink\0".as_ptr() as *const _, 0),
+            aware: x11_sys::XInternAtom(display, "XdndAware\0".as_ptr() as *const _, 0),
+            drop: x11_sys::XInternAtom(display, "XdndDrop\0".as_ptr() as *const _, 0),
+            enter: x11_sys::XInternAtom(display, "XdndEnter\0".as_ptr() as *const _, 0),
+            finished: x11_sys::XInternAtom(display, "XdndFinished\0".as_ptr() as *const _, 0),
+            leave: x11_sys::XInternAtom(display, "XdndLeave\0".as_ptr() as *const _, 0),
+            position: x11_sys::XInternAtom(display, "XdndPosition\0".as_ptr() as *const _, 0),
+            selection: x11_sys::XInternAtom(display, "XdndSelection\0".as_ptr() as *const _, 0),
+            status: x11_sys::XInternAtom(display, "XdndStatus\0".as_ptr() as *const _, 0),
+            type_list: x11_sys::XInternAtom(display, "XdndTypeList\0".as_ptr() as *const _, 0),
+            uri_list: x11_sys::XInternAtom(display, "text/uri-list\0".as_ptr() as *const _, 0),
+            makepad_items: x11_sys::XInternAtom(display, "application/x-makepad-drag-items\0".as_ptr() as *const _, 0),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+// Composed text entry through X input methods, which is also how IBus and Fcitx reach X11 applications.
+// When the input method supports it, the text being composed comes to us and goes to the focused widget as
+// TextInputEvents that replace each other until the final text is committed. Otherwise the input method
+// shows the composition in a window of its own, at the spot set with ShowTextIME.
+use {
+    std::{
+        ffi::CStr,
+        ptr,
+        os::raw::{c_int, c_short, c_void},
+    },
+    self::super::{
+        x11_sys,
+        xlib_app::get_xlib_app_global,
+        xlib_event::XlibEvent,
+        xlib_window::XlibWindow,
+        super::libc_sys,
+    },
+    crate::{
+        makepad_math::DVec2,
+        event::TextInputEvent,
+    },
+};
+
+/// Opens the input method XMODIFIERS names and picks the richest input style it supports.
+pub unsafe fn open_input_method(display: *mut x11_sys::Display) -> (x11_sys::XIM, x11_sys::XIMStyle) {
+    // without a locale Xlib only knows the C locale, which no input method serves
+    libc_sys::setlocale(libc_sys::LC_CTYPE, "\0".as_ptr() as *const _);
+    x11_sys::XSetLocaleModifiers("\0".as_ptr() as *const _);
+    let mut xim = x11_sys::XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+    if xim.is_null() {
+        // the configured input method isn't running, Xlib can still do dead keys and compose by itself
+        x11_sys::XSetLocaleModifiers("@im=none\0".as_ptr() as *const _);
+        xim = x11_sys::XOpenIM(display, ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+    }
+    let fallback = (x11_sys::XIMPreeditNothing | x11_sys::XIMStatusNothing) as x11_sys::XIMStyle;
+    if xim.is_null() {
+        return (xim, fallback)
+    }
+    let mut styles: *mut x11_sys::XIMStyles = ptr::null_mut();
+    let mut supported = Vec::new();
+    if x11_sys::XGetIMValues(xim, x11_sys::XNQueryInputStyle.as_ptr(), &mut styles, ptr::null_mut::<c_void>()).is_null() && !styles.is_null() {
+        supported = std::slice::from_raw_parts((*styles).supported_styles, (*styles).count_styles as usize).to_vec();
+        x11_sys::XFree(styles as *mut c_void);
+    }
+    let style = [
+        x11_sys::XIMPreeditCallbacks | x11_sys::XIMStatusNothing,
+        x11_sys::XIMPreeditPosition | x11_sys::XIMStatusNothing,
+    ].into_iter()
+        .map( | style | style as x11_sys::XIMStyle)
+        .find( | style | supported.contains(style))
+        .unwrap_or(fallback);
+    (xim, style)
+}
+
+impl XlibWindow {
+    pub unsafe fn create_input_context(&mut self, window: x11_sys::Window) {
+        let app = get_xlib_app_global();
+        if app.xim.is_null() {
+            return
+        }
+        let mut xic = ptr::null_mut();
+        if app.xim_style & x11_sys::XIMPreeditCallbacks as x11_sys::XIMStyle != 0 {
+            // the callbacks find their window through its id, the window struct itself can move
+            let client_data = window as x11_sys::XPointer;
+            let callbacks = Box::new([
+                x11_sys::XIMCallback {client_data, callback: preedit_start as *const c_void},
+                x11_sys::XIMCallback {client_data, callback: preedit_done as *const c_void},
+                x11_sys::XIMCallback {client_data, callback: preedit_draw as *const c_void},
+                x11_sys::XIMCallback {client_data, callback: preedit_caret as *const c_void},
+            ]);
+            let preedit_attributes = x11_sys::XVaCreateNestedList(
+                0,
+                x11_sys::XNPreeditStartCallback.as_ptr(),
+                &callbacks[0] as *const x11_sys::XIMCallback,
+                x11_sys::XNPreeditDoneCallback.as_ptr(),
+                &callbacks[1] as *const x11_sys::XIMCallback,
+                x11_sys::XNPreeditDrawCallback.as_ptr(),
+                &callbacks[2] as *const x11_sys::XIMCallback,
+                x11_sys::XNPreeditCaretCallback.as_ptr(),
+                &callbacks[3] as *const x11_sys::XIMCallback,
+                ptr::null_mut::<c_void>()
+            );
+            xic = x11_sys::XCreateIC(
+                app.xim,
+                x11_sys::XNInputStyle.as_ptr(),
+                app.xim_style,
+                x11_sys::XNClientWindow.as_ptr(),
+                window,
+                x11_sys::XNFocusWindow.as_ptr(),
+                window,
+                x11_sys::XNPreeditAttributes.as_ptr(),
+                preedit_attributes,
+                ptr::null_mut::<c_void>()
+            );
+            x11_sys::XFree(preedit_attributes);
+            self.ime_callbacks = Some(callbacks);
+        }
+        else if app.xim_style & x11_sys::XIMPreeditPosition as x11_sys::XIMStyle != 0 {
+            let spot = x11_sys::XPoint {x: 0, y: 0};
+            let preedit_attributes = x11_sys::XVaCreateNestedList(
+                0,
+                x11_sys::XNSpotLocation.as_ptr(),
+                &spot as *const x11_sys::XPoint,
+                ptr::null_mut::<c_void>()
+            );
+            xic = x11_sys::XCreateIC(
+                app.xim,
+                x11_sys::XNInputStyle.as_ptr(),
+                app.xim_style,
+                x11_sys::XNClientWindow.as_ptr(),
+                window,
+                x11_sys::XNFocusWindow.as_ptr(),
+                window,
+                x11_sys::XNPreeditAttributes.as_ptr(),
+                preedit_attributes,
+                ptr::null_mut::<c_void>()
+            );
+            x11_sys::XFree(preedit_attributes);
+        }
+        if xic.is_null() {
+            xic = x11_sys::XCreateIC(
+                app.xim,
+                x11_sys::XNInputStyle.as_ptr(),
+                (x11_sys::XIMPreeditNothing | x11_sys::XIMStatusNothing) as x11_sys::XIMStyle,
+                x11_sys::XNClientWindow.as_ptr(),
+                window,
+                x11_sys::XNFocusWindow.as_ptr(),
+                window,
+                ptr::null_mut::<c_void>()
+            );
+        }
+        self.xic = if xic.is_null() {None} else {Some(xic)};
+    }
+
+    pub fn destroy_input_context(&mut self) {
+        if let Some(xic) = self.xic.take() {
+            unsafe {x11_sys::XDestroyIC(xic)};
+        }
+        self.ime_callbacks = None;
+        self.ime_preedit.clear();
+    }
+
+    /// Moves the candidate window of the input method to `spot`, in logical pixels within the window, and
+    /// lets the input method see the keys typed from now on.
+    pub fn set_ime_spot(&mut self, spot: DVec2) {
+        self.ime_spot = spot;
+        let Some(xic) = self.xic else {return};
+        let dpi_factor = self.last_window_geom.dpi_factor;
+        let spot = x11_sys::XPoint {
+            x: (spot.x * dpi_factor) as c_short,
+            y: (spot.y * dpi_factor) as c_short
+        };
+        unsafe {
+            let preedit_attributes = x11_sys::XVaCreateNestedList(
+                0,
+                x11_sys::XNSpotLocation.as_ptr(),
+                &spot as *const x11_sys::XPoint,
+                ptr::null_mut::<c_void>()
+            );
+            x11_sys::XSetICValues(xic, x11_sys::XNPreeditAttributes.as_ptr(), preedit_attributes, ptr::null_mut::<c_void>());
+            x11_sys::XFree(preedit_attributes);
+            x11_sys::XSetICFocus(xic);
+        }
+    }
+
+    /// Stops composing, keys go straight to the application again.
+    pub fn hide_ime(&mut self) {
+        if let Some(xic) = self.xic {
+            unsafe {x11_sys::XUnsetICFocus(xic)};
+        }
+        self.ime_preedit.clear();
+    }
+
+    /// Replaces the text being composed, the widget sees the new text replacing the previous one.
+    pub fn set_ime_preedit(&mut self, preedit: String) {
+        if preedit == self.ime_preedit {
+            return
+        }
+        let replace_last = !self.ime_preedit.is_empty();
+        self.ime_preedit = preedit.clone();
+        self.do_callback(XlibEvent::TextInput(TextInputEvent {
+            input: preedit,
+            was_paste: false,
+            replace_last,
+        }));
+    }
+
+    /// Takes the text being composed once the input method commits the final text, which replaces it.
+    pub fn take_ime_preedit(&mut self) -> String {
+        std::mem::take(&mut self.ime_preedit)
+    }
+}
+
+unsafe fn window_for_client_data(client_data: x11_sys::XPointer) -> Option<&'static mut XlibWindow> {
+    let window = client_data as x11_sys::Window;
+    get_xlib_app_global().window_map.get(&window).map( | window_ptr | &mut **window_ptr)
+}
+
+unsafe extern "C" fn preedit_start(_xic: x11_sys::XIC, client_data: x11_sys::XPointer, _call_data: x11_sys::XPointer) -> c_int {
+    if let Some(window) = window_for_client_data(client_data) {
+        window.ime_preedit.clear();
+    }
+    // no limit on the length of the composed text
+    -1
+}
+
+unsafe extern "C" fn preedit_done(_xic: x11_sys::XIC, _client_data: x11_sys::XPointer, _call_data: x11_sys::XPointer) {
+    // the input method clears the text with a last draw, or commits it
+}
+
+unsafe extern "C" fn preedit_draw(_xic: x11_sys::XIC, client_data: x11_sys::XPointer, call_data: x11_sys::XPointer) {
+    let Some(window) = window_for_client_data(client_data) else {return};
+    let draw = &*(call_data as *const x11_sys::XIMPreeditDrawCallbackStruct);
+    let text = if draw.text.is_null() {
+        Vec::new()
+    }
+    else {
+        let text = &*draw.text;
+        if text.encoding_is_wchar != 0 {
+            if text.string.wide_char.is_null() {
+                return
+            }
+            std::slice::from_raw_parts(text.string.wide_char, text.length as usize)
+                .iter()
+                .filter_map( | c | char::from_u32(*c))
+                .collect()
+        }
+        else {
+            // a null string means only the highlighting changed
+            if text.string.multi_byte.is_null() {
+                return
+            }
+            CStr::from_ptr(text.string.multi_byte).to_string_lossy().chars().take(text.length as usize).collect()
+        }
+    };
+    let mut chars: Vec<char> = window.ime_preedit.chars().collect();
+    let first = (draw.chg_first.max(0) as usize).min(chars.len());
+    let end = (first + draw.chg_length.max(0) as usize).min(chars.len());
+    chars.splice(first..end, text);
+    window.set_ime_preedit(chars.into_iter().collect());
+}
+
+unsafe extern "C" fn preedit_caret(_xic: x11_sys::XIC, _client_data: x11_sys::XPointer, _call_data: x11_sys::XPointer) {
+    // the cursor of the widget stays at the end of the composed text
+}
@@ -3,9 +3,9 @@
         mem,
         cell::Cell,
         rc::Rc,
-        os::raw::{c_ulong, c_long, c_void, c_char},
+        os::raw::{c_int, c_ulong, c_long, c_char},
         ptr,
-        ffi::{CStr, CString, OsStr},
+        ffi::{CStr, OsStr},
     },
     self::super::{
         x11_sys,
@@ -34,6 +34,8 @@ pub struct XlibWindow {
     pub last_window_geom: WindowGeom,
     
     pub ime_spot: DVec2,
+    pub ime_preedit: String,
+    pub ime_callbacks: Option<Box<[x11_sys::XIMCallback; 4]>>,
     pub current_cursor: MouseCursor,
     pub last_mouse_pos: DVec2,
 }
@@ -62,6 +64,8 @@ pub fn new(window_id: WindowId) -> XlibWindow {
             last_window_geom: WindowGeom::default(),
             last_nc_mode: None,
             ime_spot: DVec2::default(),
+            ime_preedit: String::new(),
+            ime_callbacks: None,
             current_cursor: MouseCursor::Default,
             last_mouse_pos: DVec2::default(),
         }
@@ -96,6 +100,7 @@ pub fn init(&mut self, title: &str, size: DVec2, position: Option<DVec2>, visual
                     | x11_sys::FocusChangeMask
                     | x11_sys::EnterWindowMask
                     | x11_sys::LeaveWindowMask
+                    | x11_sys::PropertyChangeMask
             ) as c_long;
             
             let dpi_factor = self.get_dpi_factor();
@@ -186,16 +191,7 @@ pub fn init(&mut self, title: &str, size: DVec2, position: Option<DVec2>, visual
                 x11_sys::XFlush(display);
             }
             
-            let xic = x11_sys::XCreateIC(
-                get_xlib_app_global().xim,
-                x11_sys::XNInputStyle.as_ptr(),
-                (x11_sys::XIMPreeditNothing | x11_sys::XIMStatusNothing) as i32,
-                x11_sys::XNClientWindow.as_ptr(),
-                window,
-                x11_sys::XNFocusWindow.as_ptr(),
-                window,
-                ptr::null_mut() as *mut c_void
-            );
+            self.create_input_context(window);
             
             // Create a window
             get_xlib_app_global().window_map.insert(window, self);
@@ -203,7 +199,6 @@ pub fn init(&mut self, title: &str, size: DVec2, position: Option<DVec2>, visual
             self.attributes = Some(attributes);
             self.visual_info = Some(visual_info);
             self.window = Some(window);
-            self.xic = Some(xic);
             self.last_window_geom = self.get_window_geom();
             
             let new_geom = self.get_window_geom();
@@ -216,7 +211,9 @@ pub fn init(&mut self, title: &str, size: DVec2, position: Option<DVec2>, visual
         }
     }
     
-    fn restore_or_maximize(&self, add_remove: c_long) {
+    // asks the window manager to add or remove up to two _NET_WM_STATE properties, it ignores the message
+    // while the window isn't mapped
+    fn send_wm_state(&self, action: c_long, first: x11_sys::Atom, second: x11_sys::Atom) {
         unsafe {
             let default_screen = x11_sys::XDefaultScreen(get_xlib_app_global().display);
             let root_window = x11_sys::XRootWindow(get_xlib_app_global().display, default_screen);
@@ -230,9 +227,11 @@ fn restore_or_maximize(&self, add_remove: c_long) {
                 format: 32,
                 data: {
                     let mut msg = mem::zeroed::<x11_sys::XClientMessageEvent__bindgen_ty_1>();
-                    msg.l[0] = add_remove;
-                    msg.l[1] = get_xlib_app_global().atoms.new_wm_state_maximized_horz as c_long;
-                    msg.l[2] = get_xlib_app_global().atoms.new_wm_state_maximized_vert as c_long;
+                    msg.l[0] = action;
+                    msg.l[1] = first as c_long;
+                    msg.l[2] = second as c_long;
+                    // the request comes from a normal application
+                    msg.l[3] = 1;
                     msg
                 }
             };
@@ -243,19 +242,32 @@ fn restore_or_maximize(&self, add_remove: c_long) {
                 (x11_sys::SubstructureNotifyMask | x11_sys::SubstructureRedirectMask) as c_long,
                 &mut xclient as *mut _ as *mut x11_sys::XEvent
             );
+            x11_sys::XFlush(get_xlib_app_global().display);
         }
     }
     
     pub fn restore(&self) {
-        self.restore_or_maximize(_NET_WM_STATE_REMOVE);
+        let atoms = &get_xlib_app_global().atoms;
+        self.send_wm_state(_NET_WM_STATE_REMOVE, atoms.new_wm_state_maximized_horz, atoms.new_wm_state_maximized_vert);
+        self.send_wm_state(_NET_WM_STATE_REMOVE, atoms.net_wm_state_fullscreen, 0);
     }
     
     pub fn maximize(&self) {
-        self.restore_or_maximize(_NET_WM_STATE_ADD);
+        let atoms = &get_xlib_app_global().atoms;
+        self.send_wm_state(_NET_WM_STATE_ADD, atoms.new_wm_state_maximized_horz, atoms.new_wm_state_maximized_vert);
+    }
+    
+    pub fn fullscreen(&self) {
+        self.send_wm_state(_NET_WM_STATE_ADD, get_xlib_app_global().atoms.net_wm_state_fullscreen, 0);
+    }
+    
+    pub fn normalize(&self) {
+        self.send_wm_state(_NET_WM_STATE_REMOVE, get_xlib_app_global().atoms.net_wm_state_fullscreen, 0);
     }
     
     pub fn close_window(&mut self) {
         unsafe {
+            self.destroy_input_context();
             x11_sys::XDestroyWindow(get_xlib_app_global().display, self.window.unwrap());
             self.window = None;
             // lets remove us from the mapping
@@ -271,19 +283,71 @@ pub fn minimize(&self) {
         }
     }
     
-    pub fn set_topmost(&self, _topmost: bool) {
+    /// Brings the window back from being minimized or hidden, and activates it.
+    pub fn deminiaturize(&self) {
+        unsafe {
+            let display = get_xlib_app_global().display;
+            let default_screen = x11_sys::XDefaultScreen(display);
+            let root_window = x11_sys::XRootWindow(display, default_screen);
+            x11_sys::XMapRaised(display, self.window.unwrap());
+            let mut xclient = x11_sys::XClientMessageEvent {
+                type_: x11_sys::ClientMessage as i32,
+                serial: 0,
+                send_event: 0,
+                display,
+                window: self.window.unwrap(),
+                message_type: get_xlib_app_global().atoms.net_active_window,
+                format: 32,
+                data: {
+                    let mut msg = mem::zeroed::<x11_sys::XClientMessageEvent__bindgen_ty_1>();
+                    msg.l[0] = 1;
+                    msg.l[1] = x11_sys::CurrentTime as c_long;
+                    msg
+                }
+            };
+            x11_sys::XSendEvent(
+                display,
+                root_window,
+                0,
+                (x11_sys::SubstructureNotifyMask | x11_sys::SubstructureRedirectMask) as c_long,
+                &mut xclient as *mut _ as *mut x11_sys::XEvent
+            );
+            x11_sys::XFlush(display);
+        }
+    }
+    
+    /// Takes the window off the screen and out of the taskbar, `deminiaturize` shows it again.
+    pub fn hide(&self) {
+        unsafe {
+            let display = get_xlib_app_global().display;
+            let default_screen = x11_sys::XDefaultScreen(display);
+            x11_sys::XWithdrawWindow(display, self.window.unwrap(), default_screen);
+            x11_sys::XFlush(display);
+        }
+    }
+    
+    pub fn set_topmost(&self, topmost: bool) {
+        let action = if topmost {_NET_WM_STATE_ADD} else {_NET_WM_STATE_REMOVE};
+        self.send_wm_state(action, get_xlib_app_global().atoms.net_wm_state_above, 0);
     }
     
     pub fn get_is_topmost(&self) -> bool {
-        false
+        self.get_wm_state().contains(&get_xlib_app_global().atoms.net_wm_state_above)
     }
     
     pub fn get_window_geom(&self) -> WindowGeom {
+        let wm_state = self.get_wm_state();
+        let atoms = &get_xlib_app_global().atoms;
         WindowGeom {
             xr_is_presenting: false,
-            can_fullscreen: false,
-            is_topmost: self.get_is_topmost(),
-            is_fullscreen: self.get_is_maximized(),
+            can_fullscreen: true,
+            is_topmost: wm_state.contains(&atoms.net_wm_state_above),
+            // like on windows this covers maximized windows, the caption buttons toggle with it
+            is_fullscreen: wm_state.iter().any( | state | {
+                *state == atoms.new_wm_state_maximized_horz
+                    || *state == atoms.new_wm_state_maximized_vert
+                    || *state == atoms.net_wm_state_fullscreen
+            }),
             inner_size: self.get_inner_size(),
             outer_size: self.get_outer_size(),
             dpi_factor: self.get_dpi_factor(),
@@ -291,8 +355,9 @@ pub fn get_window_geom(&self) -> WindowGeom {
         }
     }
     
-    pub fn get_is_maximized(&self) -> bool {
-        let mut maximized = false;
+    // the _NET_WM_STATE properties the window manager set on the window
+    fn get_wm_state(&self) -> Vec<x11_sys::Atom> {
+        let mut state = Vec::new();
         unsafe {
             let mut prop_type = mem::MaybeUninit::uninit();
             let mut format = mem::MaybeUninit::uninit();
@@ -313,49 +378,34 @@ pub fn get_is_maximized(&self) -> bool {
                 bytes_after.as_mut_ptr(),
                 properties.as_mut_ptr()
             );
-            //let prop_type = prop_type.assume_init();
-            //let format = format.assume_init();
             let n_item = n_item.assume_init();
-            //let bytes_after = bytes_after.assume_init();
             let properties = properties.assume_init();
-            if result == 0 && properties != ptr::null_mut() {
-                let items = std::slice::from_raw_parts::<c_ulong>(properties as *mut _, n_item as usize);
-                for item in items {
-                    if *item == get_xlib_app_global().atoms.new_wm_state_maximized_horz
-                        || *item == get_xlib_app_global().atoms.new_wm_state_maximized_vert {
-                        maximized = true;
-                        break;
-                    }
-                }
+            if result == 0 && !properties.is_null() {
+                state.extend_from_slice(std::slice::from_raw_parts::<c_ulong>(properties as *mut _, n_item as usize));
                 x11_sys::XFree(properties as *mut _);
             }
         }
-        maximized
+        state
     }
     
-    pub fn set_ime_spot(&mut self, spot: DVec2) {
-        self.ime_spot = spot;
+    pub fn get_is_maximized(&self) -> bool {
+        let atoms = &get_xlib_app_global().atoms;
+        self.get_wm_state().iter().any( | state | {
+            *state == atoms.new_wm_state_maximized_horz || *state == atoms.new_wm_state_maximized_vert
+        })
     }
     
     pub fn get_position(&self) -> DVec2 {
         unsafe {
-            let mut xwa = mem::MaybeUninit::uninit();
+            // the window sits in a frame of the window manager, so its own x and y are relative to that
             let display = get_xlib_app_global().display;
-            x11_sys::XGetWindowAttributes(
-                display,
-                self.window.unwrap(),
-                xwa.as_mut_ptr()
-            );
-            let xwa = xwa.assume_init();
-            return DVec2 {x: xwa.x as f64, y: xwa.y as f64}
-            /*
-            let mut child = mem::uninitialized();
-            let default_screen = X11_sys::XDefaultScreen(display);
-            let root_window = X11_sys::XRootWindow(display, default_screen);
-            let mut x:c_int = 0;
-            let mut y:c_int = 0;
-            X11_sys::XTranslateCoordinates(display, self.window.unwrap(), root_window, 0, 0, &mut x, &mut y, &mut child );
-            */
+            let default_screen = x11_sys::XDefaultScreen(display);
+            let root_window = x11_sys::XRootWindow(display, default_screen);
+            let mut child = 0;
+            let mut x: c_int = 0;
+            let mut y: c_int = 0;
+            x11_sys::XTranslateCoordinates(display, self.window.unwrap(), root_window, 0, 0, &mut x, &mut y, &mut child);
+            DVec2 {x: x as f64, y: y as f64}
         }
     }
     
@@ -545,274 +595,3 @@ struct MwmHints {
 pub const _NET_WM_STATE_TOGGLE: c_long = 2;/* toggle property  */
 
 /* move via keyboard */
-
-pub struct Dnd {
-    pub atoms: DndAtoms,
-    pub display: *mut x11_sys::Display,
-    pub type_list: Option<Vec<x11_sys::Atom >>,
-    pub selection: Option<CString>,
-}
-
-impl Dnd {
-    pub unsafe fn new(display: *mut x11_sys::Display) -> Dnd {
-        Dnd {
-            atoms: DndAtoms::new(display),
-            display,
-            type_list: None,
-            selection: None,
-        }
-    }
-    
-    /// Enables drag-and-drop for the given window.
-    pub unsafe fn enable_for_window(&mut self, window: x11_sys::Window) {
-        // To enable drag-and-drop for a window, we need to set the XDndAware property of the window
-        // to the version of XDnd we support.
-        
-        // I took this value from the Winit source code. Apparently, this is the latest version, and
-        // hasn't changed since 2002.
-        let version = 5 as c_ulong;
-        
-        x11_sys::XChangeProperty(
-            self.display,
-            window,
-            self.atoms.aware,
-            4, // XA_ATOM
-            32,
-            x11_sys::PropModeReplace as std::os::raw::c_int,
-            &version as *const c_ulong as *const std::os::raw::c_uchar,
-            1
-        );
-    }
-    
-    /// Handles a XDndEnter event.
-    pub unsafe fn handle_enter_event(&mut self, event: &x11_sys::XClientMessageEvent) {
-        // The XDndEnter event is sent by the source window when a drag begins. That is, the mouse
-        // enters the client rectangle of the target window. The target window is supposed to
-        // respond to this by requesting the list of types supported by the source.
-        
-        let source_window = event.data.l[0] as x11_sys::Window;
-        let has_more_types = event.data.l[1] & (1 << 0) != 0;
-        
-        // If the has_more_types flags is set, we have to obtain the list of supported types from
-        // the XDndTypeList property. Otherwise, we can obtain the list of supported types from the
-        // event itself.
-        self.type_list = Some(if has_more_types {
-            self.get_type_list_property(source_window)
-        } else {
-            event.data.l[2..4]
-                .iter()
-                .map( | &l | l as x11_sys::Atom)
-                .filter( | &atom | atom != x11_sys::None as x11_sys::Atom)
-                .collect()
-        });
-    }
-    
-    /// Handles a XDndDrop event.
-    pub unsafe fn handle_drop_event(&mut self, event: &x11_sys::XClientMessageEvent) {
-        // The XDndLeave event is sent by the source window when a drag is confirmed. That is, the
-        // mouse button is released while the mouse is inside the client rectangle of the target
-        // window. The target window is supposed to respond to this by requesting that the selection
-        // representing the thing being dragged is converted to the appropriate data type (in our
-        // case, a URI list). The source window, in turn, is supposed to respond this by sending a
-        // selection event containing the data to the source window.
-        
-        let target_window = event.window as x11_sys::Window;
-        self.convert_selection(target_window);
-        self.type_list = None;
-    }
-    
-    /// Handles a XDndLeave event.
-    pub unsafe fn handle_leave_event(&mut self, _event: &x11_sys::XClientMessageEvent) {
-        // The XDndLeave event is sent by the source window when a drag is canceled. That is, the
-        // mouse leaves the client rectangle of the target window. The target window is supposed to
-        // repsond this this by pretending the drag never happened.
-        
-        self.type_list = None;
-    }
-    
-    /// Handles a XDndPosition event.
-    pub unsafe fn handle_position_event(&mut self, event: &x11_sys::XClientMessageEvent) {
-        // The XDndPosition event is sent by the source window after the XDndEnter event, every time
-        // the mouse is moved. The target window is supposed to respond to this by sending a status
-        // event to the source window notifying whether it can accept the drag at this position.
-        
-        let target_window = event.window as x11_sys::Window;
-        let source_window = event.data.l[0] as x11_sys::Window;
-        
-        // For now we accept te drag if and only if the list of types supported by the source
-        // includes a uri list.
-        //
-        // TODO: Extend this test by taking into account the position of the mouse as well.
-        let accepted = self.type_list.as_ref().map_or(false, | type_list | type_list.contains(&self.atoms.uri_list));
-        
-        // Notify the source window whether we can accept the drag at this position.
-        self.send_status_event(source_window, target_window, accepted);
-        
-        // If this is the first time we've accepted the drag, request that the drag-and-drop
-        // selection be converted to a URI list. The target window is supposed to respond to this by
-        // sending a XSelectionEvent containing the URI list.
-        
-        // Since this is an asynchronous operation, its possible for another XDndPosition event to
-        // come in before the response to the first conversion request has been received. In this
-        // case, a second conversion request will be sent, the response to which will be ignored.
-        if accepted && self.selection.is_none() {
-        }
-    }
-    
-    /// Handles a XSelectionEvent.
-    pub unsafe fn handle_selection_event(&mut self, _event: &x11_sys::XSelectionEvent) {
-        // The XSelectionEvent is sent by the source window in response to a request by the source
-        // window to convert the selection representing the thing being dragged to the appropriate
-        // data type. This request is always sent in response to a XDndDrop event, so this event
-        // should only be received after a drop operation has completed.
-        
-        //let source_window = event.requestor;
-        //let selection = CString::new(self.get_selection_property(source_window)).unwrap();
-        
-        // TODO: Actually use the selection
-    }
-    
-    /// Gets the XDndSelection property from the source window.
-    pub unsafe fn get_selection_property(&mut self, source_window: x11_sys::Window) -> Vec< std::os::raw::c_uchar> {
-        let mut selection = Vec::new();
-        let mut offset = 0;
-        let length = 1024;
-        let mut actual_type = 0;
-        let mut actual_format = 0;
-        let mut nitems = 0;
-        let mut bytes_after = 0;
-        let mut prop = ptr::null_mut();
-        loop {
-            x11_sys::XGetWindowProperty(
-                self.display,
-                source_window,
-                self.atoms.selection,
-                offset,
-                length,
-                x11_sys::False as std::os::raw::c_int,
-                self.atoms.uri_list,
-                &mut actual_type,
-                &mut actual_format,
-                &mut nitems,
-                &mut bytes_after,
-                &mut prop,
-            );
-            selection.extend_from_slice(std::slice::from_raw_parts(prop as *mut  std::os::raw::c_uchar, nitems as usize));
-            x11_sys::XFree(prop as *mut c_void);
-            if bytes_after == 0 {
-                break;
-            }
-            offset += length;
-        };
-        selection
-    }
-    
-    /// Gets the XDndTypeList property from the source window.
-    pub unsafe fn get_type_list_property(&mut self, source_window: x11_sys::Window) -> Vec<x11_sys::Atom> {
-        let mut type_list = Vec::new();
-        let mut offset = 0;
-        let length = 1024;
-        let mut actual_type = 0;
-        let mut actual_format = 0;
-        let mut nitems = 0;
-        let mut bytes_after = 0;
-        let mut prop = ptr::null_mut();
-        loop {
-            x11_sys::XGetWindowProperty(
-                self.display,
-                source_window,
-                self.atoms.type_list,
-                offset,
-                length,
-                x11_sys::False as  std::os::raw::c_int,
-                4, // XA_ATOM,
-                &mut actual_type,
-                &mut actual_format,
-                &mut nitems,
-                &mut bytes_after,
-                &mut prop,
-            );
-            type_list.extend_from_slice(std::slice::from_raw_parts(prop as *mut x11_sys::Atom, nitems as usize));
-            x11_sys::XFree(prop as *mut c_void);
-            if bytes_after == 0 {
-                break;
-            }
-            offset += length;
-        };
-        type_list
-    }
-    
-    /// Sends a XDndStatus event to the target window.
-    pub unsafe fn send_status_event(&mut self, source_window: x11_sys::Window, target_window: x11_sys::Window, accepted: bool) {
-        x11_sys::XSendEvent(
-            self.display,
-            source_window,
-            x11_sys::False as  std::os::raw::c_int,
-            x11_sys::NoEventMask as  std::os::raw::c_long,
-            &mut x11_sys::XClientMessageEvent {
-                type_: x11_sys::ClientMessage as  std::os::raw::c_int,
-                serial: 0,
-                send_event: 0,
-                display: self.display,
-                window: source_window,
-                message_type: self.atoms.status,
-                format: 32,
-                data: {
-                    let mut data = mem::zeroed::<x11_sys::XClientMessageEvent__bindgen_ty_1>();
-                    data.l[0] = target_window as c_long;
-                    data.l[1] = if accepted {1 << 0} else {0};
-                    data.l[2] = 0;
-                    data.l[3] = 0;
-                    data.l[4] = if accepted {self.atoms.action_private} else {self.atoms.none} as c_long;
-                    data
-                }
-            } as *mut x11_sys::XClientMessageEvent as *mut x11_sys::XEvent
-        );
-        x11_sys::XFlush(self.display);
-    }
-    
-    // Requests that the selection representing the thing being dragged is converted to the
-    // appropriate data type (in our case, a URI list).
-    pub unsafe fn convert_selection(&self, target_window: x11_sys::Window) {
-        x11_sys::XConvertSelection(
-            self.display,
-            self.atoms.selection,
-            self.atoms.uri_list,
-            self.atoms.selection,
-            target_window,
-            x11_sys::CurrentTime as x11_sys::Time,
-        );
-    }
-}
-
-pub struct DndAtoms {
-    pub action_private: x11_sys::Atom,
-    pub aware: x11_sys::Atom,
-    pub drop: x11_sys::Atom,
-    pub enter: x11_sys::Atom,
-    pub leave: x11_sys::Atom,
-    pub none: x11_sys::Atom,
-    pub position: x11_sys::Atom,
-    pub selection: x11_sys::Atom,
-    pub status: x11_sys::Atom,
-    pub type_list: x11_sys::Atom,
-    pub uri_list: x11_sys::Atom,
-}
-
-impl DndAtoms {
-    pub unsafe fn new(display: *mut x11_sys::Display) -> DndAtoms {
-        DndAtoms {
-            action_private: x11_sys::XInternAtom(display, "XdndActionPrivate\0".as_ptr() as *const _, 0),
-            aware: x11_sys::XInternAtom(display, "XdndAware\0".as_ptr() as *const _, 0),
-            drop: x11_sys::XInternAtom(display, "XdndDrop\0".as_ptr() as *const _, 0),
-            enter: x11_sys::XInternAtom(display, "XdndEnter\0".as_ptr() as *const _, 0),
-            leave: x11_sys::XInternAtom(display, "XdndLeave\0".as_ptr() as *const _, 0),
-            none: x11_sys::XInternAtom(display, "None\0".as_ptr() as *const _, 0),
-            position: x11_sys::XInternAtom(display, "XdndPosition\0".as_ptr() as *const _, 0),
-            selection: x11_sys::XInternAtom(display, "XdndSelection\0".as_ptr() as *const _, 0),
-            status: x11_sys::XInternAtom(display, "XdndStatus\0".as_ptr() as *const _, 0),
-            type_list: x11_sys::XInternAtom(display, "XdndTypeList\0".as_ptr() as *const _, 0),
-            uri_list: x11_sys::XInternAtom(display, "text/uri-list\0".as_ptr() as *const _, 0),
-        }
-    }
-}
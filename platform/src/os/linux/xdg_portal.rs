@@ -0,0 +1,124 @@
+// File dialogs through the XDG desktop portal, which every major desktop implements with its own native dialog.
+// The call blocks until the user closes the dialog, so it runs on its own thread.
+use {
+    std::{
+        io,
+        path::PathBuf,
+        os::unix::ffi::OsStringExt,
+        sync::atomic::{AtomicU32, Ordering},
+    },
+    self::super::dbus::{DbusConnection, DbusValue, percent_decode},
+    crate::file_dialogs::{FileDialog, FileDialogKind, FileDialogResponse},
+};
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const FILE_CHOOSER_INTERFACE: &str = "org.freedesktop.portal.FileChooser";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+
+static REQUEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Shows a portal file dialog and waits for it to close. When there is no portal the settings come back as
+/// `Unavailable`, so a drawn dialog can take over.
+pub fn file_dialog(kind: FileDialogKind, settings: &FileDialog, parent_window: Option<u64>) -> FileDialogResponse {
+    match portal_file_dialog(kind, settings, parent_window) {
+        Ok(response) => response,
+        Err(err) => {
+            crate::log!("File dialog portal unavailable: {}", err);
+            FileDialogResponse::Unavailable(settings.clone())
+        }
+    }
+}
+
+fn portal_file_dialog(kind: FileDialogKind, settings: &FileDialog, parent_window: Option<u64>) -> io::Result<FileDialogResponse> {
+    let mut connection = DbusConnection::session()?;
+
+    // the request object path is known up front, subscribing before the call means the response can't be missed
+    let token = format!("makepad{}", REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed));
+    let sender = connection.unique_name.trim_start_matches(':').replace('.', "_");
+    let mut request_path = format!("{}/request/{}/{}", PORTAL_PATH, sender, token);
+    connection.add_match(&response_match_rule(&request_path))?;
+
+    let mut options = vec![
+        ("handle_token", DbusValue::String(token)),
+        ("modal", DbusValue::Bool(true)),
+        ("multiple", DbusValue::Bool(false)),
+    ];
+    if kind.is_folder() {
+        options.push(("directory", DbusValue::Bool(true)));
+    }
+    if kind == FileDialogKind::SaveFolder {
+        options.push(("accept_label", DbusValue::String("Save".into())));
+    }
+    if !settings.filters.is_empty() && !kind.is_folder() {
+        options.push(("filters", filters_value(settings)));
+    }
+    if let Some(location) = &settings.location {
+        // a nul terminated byte array, paths need not be utf8
+        let mut bytes = location.clone().into_os_string().into_vec();
+        bytes.push(0);
+        options.push(("current_folder", DbusValue::Array("y".into(), bytes.into_iter().map(DbusValue::Byte).collect())));
+    }
+    if kind == FileDialogKind::SaveFile {
+        if let Some(filename) = &settings.filename {
+            options.push(("current_name", DbusValue::String(filename.clone())));
+        }
+    }
+    let title = settings.title.clone().unwrap_or_else( | | match kind {
+        FileDialogKind::SaveFile => "Save File",
+        FileDialogKind::SelectFile => "Open File",
+        FileDialogKind::SaveFolder => "Save Folder",
+        FileDialogKind::SelectFolder => "Open Folder",
+    }.to_string());
+    let parent_window = parent_window.map( | window | format!("x11:{:x}", window)).unwrap_or_default();
+
+    let method = if kind == FileDialogKind::SaveFile {"SaveFile"} else {"OpenFile"};
+    let reply = connection.call(PORTAL_DESTINATION, PORTAL_PATH, FILE_CHOOSER_INTERFACE, method, vec![
+        DbusValue::String(parent_window),
+        DbusValue::String(title),
+        DbusValue::dict(options),
+    ])?;
+    // older portals pick their own request path
+    if let Some(DbusValue::ObjectPath(path)) = reply.body.first() {
+        if *path != request_path {
+            request_path = path.clone();
+            connection.add_match(&response_match_rule(&request_path))?;
+        }
+    }
+
+    let response = connection.wait_for_signal(&request_path, REQUEST_INTERFACE, "Response")?;
+    match (response.body.first(), response.body.get(1)) {
+        (Some(DbusValue::Uint32(0)), Some(results)) => {
+            let mut paths = Vec::new();
+            if let Some(DbusValue::Array(_, uris)) = results.dict_get("uris") {
+                for uri in uris {
+                    if let DbusValue::String(uri) = uri {
+                        if let Some(path) = uri.strip_prefix("file://") {
+                            paths.push(PathBuf::from(std::ffi::OsString::from_vec(percent_decode(path))));
+                        }
+                    }
+                }
+            }
+            Ok(FileDialogResponse::Selected(paths))
+        }
+        _ => Ok(FileDialogResponse::Cancelled)
+    }
+}
+
+fn response_match_rule(request_path: &str) -> String {
+    format!("type='signal',interface='{}',member='Response',path='{}'", REQUEST_INTERFACE, request_path)
+}
+
+// the portal takes a(sa(us)), a list of named filters holding (0, glob) patterns
+fn filters_value(settings: &FileDialog) -> DbusValue {
+    DbusValue::Array("(sa(us))".into(), settings.filters.iter().map( | filter | {
+        DbusValue::Struct(vec![
+            DbusValue::String(filter.description.clone()),
+            DbusValue::Array("(us)".into(), filter.extensions.iter().map( | ext | {
+                let ext = ext.trim_start_matches('.');
+                let pattern = if ext == "*" {"*".to_string()} else {format!("*.{}", ext)};
+                DbusValue::Struct(vec![DbusValue::Uint32(0), DbusValue::String(pattern)])
+            }).collect())
+        ])
+    }).collect())
+}
@@ -34,9 +34,12 @@
 #[cfg(target_os = "android")]
 pub use crate::os::linux::android::android_media::*;
 
-#[cfg(all(target_os = "linux", not(target_env="ohos")))]
+#[cfg(all(target_os = "linux", not(target_env="ohos"), not(headless)))]
 pub use crate::os::linux::linux_media::*;
 
+#[cfg(headless)]
+pub use crate::os::linux::headless::headless_media::*;
+
 #[cfg(target_env="ohos")]
 pub use crate::os::linux::open_harmony::oh_media::*;
 
@@ -0,0 +1,285 @@
+use {
+    std::{
+        fmt,
+        io,
+        path::Path,
+    },
+    crate::audio::AudioBuffer,
+};
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+// The tail of the subformat GUIDs of WAVE_FORMAT_EXTENSIBLE, the first two bytes are the format tag
+const KSDATAFORMAT_SUBTYPE_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71
+];
+
+/// The encoding of the samples in a wav file.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum WavSampleFormat {
+    U8,
+    I16,
+    I24,
+    I32,
+    F32,
+}
+
+impl WavSampleFormat {
+    pub fn bits_per_sample(&self) -> u16 {
+        match self {
+            Self::U8 => 8,
+            Self::I16 => 16,
+            Self::I24 => 24,
+            Self::I32 | Self::F32 => 32,
+        }
+    }
+
+    pub fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample() as usize / 8
+    }
+
+    fn format_tag(&self) -> u16 {
+        match self {
+            Self::F32 => WAVE_FORMAT_IEEE_FLOAT,
+            _ => WAVE_FORMAT_PCM
+        }
+    }
+
+    fn from_format(format_tag: u16, bits_per_sample: u16) -> Result<Self, WavError> {
+        match (format_tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8) => Ok(Self::U8),
+            (WAVE_FORMAT_PCM, 16) => Ok(Self::I16),
+            (WAVE_FORMAT_PCM, 24) => Ok(Self::I24),
+            (WAVE_FORMAT_PCM, 32) => Ok(Self::I32),
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => Ok(Self::F32),
+            _ => Err(WavError::UnsupportedFormat {format_tag, bits_per_sample})
+        }
+    }
+
+    fn read_sample(&self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::U8 => (bytes[0] as f32 - 128.0) / 128.0,
+            Self::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            // shift the 24 bits into the top of an i32 so the sign is extended
+            Self::I24 => (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0,
+            Self::I32 => (i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64 / 2147483648.0) as f32,
+            Self::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+
+    fn write_sample(&self, sample: f32, out: &mut Vec<u8>) {
+        // integer samples are scaled the same way they are read, so integer data survives a round trip
+        fn quantize(sample: f32, scale: f64) -> f64 {
+            (sample as f64 * scale).round().max(-scale).min(scale - 1.0)
+        }
+        match self {
+            Self::U8 => out.push((quantize(sample, 128.0) + 128.0) as u8),
+            Self::I16 => out.extend_from_slice(&(quantize(sample, 32768.0) as i16).to_le_bytes()),
+            Self::I24 => out.extend_from_slice(&(quantize(sample, 8388608.0) as i32).to_le_bytes()[0..3]),
+            Self::I32 => out.extend_from_slice(&(quantize(sample, 2147483648.0) as i32).to_le_bytes()),
+            Self::F32 => out.extend_from_slice(&sample.to_le_bytes()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WavError {
+    Io(io::Error),
+    NotRiffWave,
+    MissingChunk(&'static str),
+    InvalidFormat(&'static str),
+    UnsupportedFormat {format_tag: u16, bits_per_sample: u16},
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "io error: {}", err),
+            Self::NotRiffWave => write!(f, "not a RIFF/WAVE file"),
+            Self::MissingChunk(id) => write!(f, "missing {} chunk", id),
+            Self::InvalidFormat(reason) => write!(f, "invalid format: {}", reason),
+            Self::UnsupportedFormat {format_tag, bits_per_sample} => write!(
+                f,
+                "unsupported format tag {:#06x} with {} bits per sample",
+                format_tag,
+                bits_per_sample
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WavError {}
+
+impl From<io::Error> for WavError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The contents of a RIFF/WAVE file.
+#[derive(Clone, Debug)]
+pub struct WavFile {
+    pub buffer: AudioBuffer,
+    pub sample_rate: u32,
+    pub sample_format: WavSampleFormat,
+}
+
+impl WavFile {
+    pub fn new(buffer: AudioBuffer, sample_rate: u32, sample_format: WavSampleFormat) -> Self {
+        Self {
+            buffer,
+            sample_rate,
+            sample_format
+        }
+    }
+
+    pub fn read_from_path(path: impl AsRef<Path>) -> Result<Self, WavError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> Result<(), WavError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, WavError> {
+        if data.len() < 12 || &data[0..4] != b"RIFF" || &data[8..12] != b"WAVE" {
+            return Err(WavError::NotRiffWave)
+        }
+        let mut fmt = None;
+        let mut samples = None;
+        let mut offset = 12;
+        while offset + 8 <= data.len() {
+            let id = &data[offset..offset + 4];
+            let size = read_u32(data, offset + 4) as usize;
+            let start = offset + 8;
+            // writers that stream to disk often leave the size of the last chunk unpatched
+            let end = start.saturating_add(size).min(data.len());
+            match id {
+                b"fmt " => fmt = Some(&data[start..end]),
+                b"data" => samples = Some(&data[start..end]),
+                _ => ()
+            }
+            // chunks are padded to an even size
+            offset = start.saturating_add(size).saturating_add(size & 1);
+        }
+        let fmt = fmt.ok_or(WavError::MissingChunk("fmt "))?;
+        let samples = samples.ok_or(WavError::MissingChunk("data"))?;
+        if fmt.len() < 16 {
+            return Err(WavError::InvalidFormat("fmt chunk is too short"))
+        }
+        let mut format_tag = read_u16(fmt, 0);
+        let channel_count = read_u16(fmt, 2) as usize;
+        let sample_rate = read_u32(fmt, 4);
+        let block_align = read_u16(fmt, 12) as usize;
+        let bits_per_sample = read_u16(fmt, 14);
+        if format_tag == WAVE_FORMAT_EXTENSIBLE {
+            if fmt.len() < 40 || fmt[26..40] != KSDATAFORMAT_SUBTYPE_TAIL {
+                return Err(WavError::InvalidFormat("unknown extensible subformat"))
+            }
+            format_tag = read_u16(fmt, 24);
+        }
+        let sample_format = WavSampleFormat::from_format(format_tag, bits_per_sample)?;
+        if channel_count == 0 {
+            return Err(WavError::InvalidFormat("no channels"))
+        }
+        if block_align != channel_count * sample_format.bytes_per_sample() {
+            return Err(WavError::InvalidFormat("block align does not match the sample format"))
+        }
+        let frame_count = samples.len() / block_align;
+        let mut buffer = AudioBuffer::new_with_size(frame_count, channel_count);
+        let bytes_per_sample = sample_format.bytes_per_sample();
+        for frame in 0..frame_count {
+            for channel in 0..channel_count {
+                let at = frame * block_align + channel * bytes_per_sample;
+                buffer.data[frame + channel * frame_count] = sample_format.read_sample(&samples[at..at + bytes_per_sample]);
+            }
+        }
+        Ok(Self {
+            buffer,
+            sample_rate,
+            sample_format
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.buffer.to_wav(self.sample_rate, self.sample_format)
+    }
+}
+
+impl AudioBuffer {
+    /// Decodes a RIFF/WAVE file, returning the samples and their sample rate.
+    pub fn from_wav(data: &[u8]) -> Result<(Self, u32), WavError> {
+        let wav = WavFile::from_bytes(data)?;
+        Ok((wav.buffer, wav.sample_rate))
+    }
+
+    /// Encodes the samples as a RIFF/WAVE file.
+    pub fn to_wav(&self, sample_rate: u32, sample_format: WavSampleFormat) -> Vec<u8> {
+        let channel_count = self.channel_count();
+        let frame_count = self.frame_count();
+        let bytes_per_sample = sample_format.bytes_per_sample();
+        let block_align = channel_count * bytes_per_sample;
+        let data_size = frame_count * block_align;
+        // more than two channels need WAVE_FORMAT_EXTENSIBLE, float needs the cbSize field and a fact chunk
+        let extensible = channel_count > 2;
+        let is_float = sample_format == WavSampleFormat::F32;
+        let fmt_size = if extensible {40} else if is_float {18} else {16};
+        let fact_size = if is_float {12} else {0};
+        let riff_size = 4 + (8 + fmt_size) + fact_size + (8 + data_size + (data_size & 1));
+
+        let mut out = Vec::with_capacity(8 + riff_size);
+        out.extend_from_slice(b"RIFF");
+        out.extend_from_slice(&(riff_size as u32).to_le_bytes());
+        out.extend_from_slice(b"WAVE");
+
+        out.extend_from_slice(b"fmt ");
+        out.extend_from_slice(&(fmt_size as u32).to_le_bytes());
+        let format_tag = if extensible {WAVE_FORMAT_EXTENSIBLE} else {sample_format.format_tag()};
+        out.extend_from_slice(&format_tag.to_le_bytes());
+        out.extend_from_slice(&(channel_count as u16).to_le_bytes());
+        out.extend_from_slice(&sample_rate.to_le_bytes());
+        out.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        out.extend_from_slice(&(block_align as u16).to_le_bytes());
+        out.extend_from_slice(&sample_format.bits_per_sample().to_le_bytes());
+        if extensible {
+            out.extend_from_slice(&22u16.to_le_bytes());
+            out.extend_from_slice(&sample_format.bits_per_sample().to_le_bytes());
+            // no channel mask, the channels are not assigned to speakers
+            out.extend_from_slice(&0u32.to_le_bytes());
+            out.extend_from_slice(&sample_format.format_tag().to_le_bytes());
+            out.extend_from_slice(&KSDATAFORMAT_SUBTYPE_TAIL);
+        }
+        else if is_float {
+            out.extend_from_slice(&0u16.to_le_bytes());
+        }
+
+        if is_float {
+            out.extend_from_slice(b"fact");
+            out.extend_from_slice(&4u32.to_le_bytes());
+            out.extend_from_slice(&(frame_count as u32).to_le_bytes());
+        }
+
+        out.extend_from_slice(b"data");
+        out.extend_from_slice(&(data_size as u32).to_le_bytes());
+        for frame in 0..frame_count {
+            for channel in 0..channel_count {
+                sample_format.write_sample(self.data[frame + channel * frame_count], &mut out);
+            }
+        }
+        if data_size & 1 == 1 {
+            out.push(0);
+        }
+        out
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
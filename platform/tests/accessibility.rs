@@ -0,0 +1,121 @@
+use makepad_platform::{
+    AccessibilityAction, AccessibilityId, AccessibilityNode, AccessibilityRole, AccessibilityTree, Area, Rect,
+};
+
+const WINDOW: AccessibilityId = AccessibilityId(1);
+const VIEW: AccessibilityId = AccessibilityId(2);
+const OK: AccessibilityId = AccessibilityId(3);
+const CANCEL: AccessibilityId = AccessibilityId(4);
+const LIST: AccessibilityId = AccessibilityId(5);
+
+fn alive(_area: Area) -> bool {
+    true
+}
+
+fn dead(_area: Area) -> bool {
+    false
+}
+
+fn begin(tree: &mut AccessibilityTree, id: AccessibilityId, role: Option<AccessibilityRole>, name: &str) {
+    tree.begin_node(id, role.map( | role | AccessibilityNode::new(role).with_name(name)));
+}
+
+fn end(tree: &mut AccessibilityTree, id: AccessibilityId) {
+    tree.end_node(id, Area::Empty, Rect::default(), &alive);
+}
+
+fn leaf(tree: &mut AccessibilityTree, id: AccessibilityId, node: AccessibilityNode) {
+    tree.begin_node(id, Some(node));
+    end(tree, id);
+}
+
+fn button(name: &str) -> AccessibilityNode {
+    AccessibilityNode::new(AccessibilityRole::Button).with_name(name).with_action(AccessibilityAction::Click)
+}
+
+// a window with a transparent view holding two buttons
+fn draw_dialog(tree: &mut AccessibilityTree, with_cancel: bool, is_alive: fn(Area) -> bool) {
+    tree.begin_frame();
+    begin(tree, WINDOW, Some(AccessibilityRole::Window), "Dialog");
+    begin(tree, VIEW, None, "");
+    leaf(tree, OK, button("Ok").with_focused(true));
+    if with_cancel {
+        leaf(tree, CANCEL, button("Cancel"));
+    }
+    end(tree, VIEW);
+    tree.end_node(WINDOW, Area::Empty, Rect::default(), &is_alive);
+    tree.end_frame(&is_alive);
+}
+
+#[test]
+fn transparent_scopes_attach_children_to_the_nearest_node() {
+    let mut tree = AccessibilityTree::default();
+    draw_dialog(&mut tree, true, alive);
+    assert_eq!(tree.roots(), &[WINDOW]);
+    assert_eq!(tree.len(), 3);
+    assert!(tree.node(VIEW).is_none());
+    let children: Vec<_> = tree.children(WINDOW).iter().map( | node | node.name.clone()).collect();
+    assert_eq!(children, ["Ok", "Cancel"]);
+    assert_eq!(tree.parent(CANCEL).unwrap().id, WINDOW);
+    let order: Vec<_> = tree.iter().iter().map( | node | node.id).collect();
+    assert_eq!(order, [WINDOW, OK, CANCEL]);
+}
+
+#[test]
+fn query_nodes() {
+    let mut tree = AccessibilityTree::default();
+    draw_dialog(&mut tree, true, alive);
+    assert_eq!(tree.find_by_role(AccessibilityRole::Button).len(), 2);
+    let cancel = tree.find_by_name("Cancel").unwrap();
+    assert_eq!(cancel.id, CANCEL);
+    assert!(cancel.has_action(AccessibilityAction::Click));
+    assert!(!cancel.has_action(AccessibilityAction::Focus));
+    assert_eq!(tree.focused().unwrap().id, OK);
+    assert!(tree.find_by_name("Apply").is_none());
+}
+
+#[test]
+fn nodes_that_are_not_drawn_again_go_away() {
+    let mut tree = AccessibilityTree::default();
+    draw_dialog(&mut tree, true, alive);
+    draw_dialog(&mut tree, false, dead);
+    assert_eq!(tree.children(WINDOW).len(), 1);
+    assert!(tree.node(CANCEL).is_none());
+    assert_eq!(tree.len(), 2);
+}
+
+#[test]
+fn cached_nodes_stay_while_their_area_is_valid() {
+    let mut tree = AccessibilityTree::default();
+    draw_dialog(&mut tree, true, alive);
+    // only the ok button redraws, cancel sits in a draw list that was not redrawn
+    draw_dialog(&mut tree, false, alive);
+    let children: Vec<_> = tree.children(WINDOW).iter().map( | node | node.id).collect();
+    assert_eq!(children, [OK, CANCEL]);
+    // a frame without any drawing keeps the whole tree
+    tree.begin_frame();
+    tree.end_frame(&alive);
+    assert_eq!(tree.len(), 3);
+}
+
+#[test]
+fn drawing_resumes_into_open_nodes_after_a_step() {
+    let mut tree = AccessibilityTree::default();
+    tree.begin_frame();
+    begin(&mut tree, WINDOW, Some(AccessibilityRole::Window), "");
+    begin(&mut tree, LIST, Some(AccessibilityRole::List), "");
+    // the list returned a draw step, its items are drawn from outside
+    leaf(&mut tree, OK, button("First"));
+    leaf(&mut tree, CANCEL, button("Second"));
+    // then drawing resumes from the top and finishes
+    begin(&mut tree, WINDOW, Some(AccessibilityRole::Window), "");
+    begin(&mut tree, LIST, Some(AccessibilityRole::List), "");
+    end(&mut tree, LIST);
+    end(&mut tree, WINDOW);
+    tree.end_frame(&alive);
+
+    assert_eq!(tree.roots(), &[WINDOW]);
+    assert_eq!(tree.children(WINDOW).len(), 1);
+    let items: Vec<_> = tree.children(LIST).iter().map( | node | node.name.clone()).collect();
+    assert_eq!(items, ["First", "Second"]);
+}
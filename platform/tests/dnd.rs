@@ -0,0 +1,68 @@
+// The formats that drags to and from other X11 applications are encoded in
+#![cfg(all(target_os = "linux", not(target_env = "ohos")))]
+use makepad_platform::{
+    *,
+    os::linux::x11::xlib_dnd::*,
+};
+
+fn file(path: &str) -> DragItem {
+    DragItem::FilePath {path: path.to_string(), internal_id: None}
+}
+
+fn string(value: &str) -> DragItem {
+    DragItem::String {value: value.to_string(), internal_id: None}
+}
+
+#[test]
+fn percent_encode_keeps_path_characters() {
+    assert_eq!(percent_encode("/home/me/notes-1_v2.~txt"), "/home/me/notes-1_v2.~txt");
+    assert_eq!(percent_encode("/a b/100%#?"), "/a%20b/100%25%23%3F");
+    // multibyte characters are encoded byte by byte
+    assert_eq!(percent_encode("/é"), "/%C3%A9");
+    assert_eq!(percent_encode(""), "");
+}
+
+#[test]
+fn decode_uri_list_reads_file_uris() {
+    let items = decode_uri_list("# a comment\r\nfile:///home/me/a%20b.txt\r\nfile://host/tmp/%C3%A9\r\n\r\n");
+    assert_eq!(items, [file("/home/me/a b.txt"), file("/tmp/é")]);
+}
+
+#[test]
+fn decode_uri_list_keeps_other_uris_as_strings() {
+    let items = decode_uri_list("https://makepad.dev/\nfile:///x\n");
+    assert_eq!(items, [string("https://makepad.dev/"), file("/x")]);
+    // a file uri without a path is skipped
+    assert_eq!(decode_uri_list("file://host"), []);
+    // bad escapes are left as they are
+    assert_eq!(decode_uri_list("file:///a%zz%4"), [file("/a%zz%4")]);
+}
+
+#[test]
+fn uri_list_round_trips_file_paths() {
+    let items = vec![file("/home/me/a b%.txt"), file("/tmp/é"), string("not a file"), file("")];
+    let encoded = encode_uri_list(&items);
+    assert_eq!(encoded, "file:///home/me/a%20b%25.txt\r\nfile:///tmp/%C3%A9\r\n");
+    assert_eq!(decode_uri_list(&encoded), [file("/home/me/a b%.txt"), file("/tmp/é")]);
+}
+
+#[test]
+fn makepad_items_keep_internal_ids() {
+    let items = vec![
+        DragItem::FilePath {path: "/a b".to_string(), internal_id: Some(LiveId(42))},
+        DragItem::String {value: "line\nbreak".to_string(), internal_id: None},
+        DragItem::String {value: String::new(), internal_id: Some(LiveId(u64::MAX))},
+    ];
+    let encoded = encode_makepad_items(&items);
+    assert_eq!(encoded, format!("file 42 /a%20b\nstring - line%0Abreak\nstring {} \n", u64::MAX));
+    assert_eq!(decode_makepad_items(&encoded), items);
+}
+
+#[test]
+fn decode_makepad_items_skips_bad_lines() {
+    let items = decode_makepad_items("folder 1 /x\nfile\n\nstring x hi\nfile 7\n");
+    assert_eq!(items, [
+        DragItem::String {value: "hi".to_string(), internal_id: None},
+        DragItem::FilePath {path: String::new(), internal_id: Some(LiveId(7))},
+    ]);
+}
@@ -0,0 +1,40 @@
+use {
+    makepad_platform::{FileDialog, FileDialogKind},
+    std::path::Path,
+};
+
+#[test]
+fn dialog_without_filters_accepts_everything() {
+    let dialog = FileDialog::new();
+    assert!(dialog.accepts(Path::new("notes.txt")));
+    assert!(dialog.accepts(Path::new("Makefile")));
+}
+
+#[test]
+fn filters_match_extensions() {
+    let dialog = FileDialog::new()
+        .add_filter("Rust".into(), vec!["rs".into()])
+        .add_filter("Images".into(), vec![".png".into(), "JPG".into()]);
+    assert!(dialog.accepts(Path::new("src/main.rs")));
+    assert!(dialog.accepts(Path::new("photo.png")));
+    // extensions compare without case
+    assert!(dialog.accepts(Path::new("photo.jpg")));
+    assert!(dialog.accepts(Path::new("PHOTO.RS")));
+    assert!(!dialog.accepts(Path::new("notes.txt")));
+    assert!(!dialog.accepts(Path::new("Makefile")));
+}
+
+#[test]
+fn wildcard_filter_accepts_any_extension() {
+    let dialog = FileDialog::new().add_filter("All files".into(), vec!["*".into()]);
+    assert!(dialog.accepts(Path::new("notes.txt")));
+    assert!(dialog.accepts(Path::new("Makefile")));
+}
+
+#[test]
+fn dialog_kinds() {
+    assert!(FileDialogKind::SaveFile.is_save());
+    assert!(!FileDialogKind::SaveFile.is_folder());
+    assert!(FileDialogKind::SelectFolder.is_folder());
+    assert!(!FileDialogKind::SelectFolder.is_save());
+}
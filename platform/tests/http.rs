@@ -0,0 +1,184 @@
+// Runs the linux http client against the makepad-http server on a local port
+#![cfg(all(target_os = "linux", not(target_env = "ohos")))]
+use {
+    std::{
+        net::{TcpListener, SocketAddr},
+        sync::mpsc,
+        time::Duration,
+    },
+    makepad_http::server::*,
+    makepad_platform::{
+        *,
+        os::linux::http::LinuxHttpRequests,
+    },
+};
+
+/// Serves every request with the given raw header and body, and hands out what was requested
+fn serve(header: &str, body: &[u8]) -> (SocketAddr, mpsc::Receiver<(String, Vec<u8>)>) {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let listen_address = SocketAddr::from(([127, 0, 0, 1], port));
+    let (request, requests) = mpsc::channel();
+    start_http_server(HttpServer {
+        listen_address,
+        request,
+        post_max_size: 1024 * 1024,
+    }).unwrap();
+    let (seen_tx, seen) = mpsc::channel();
+    let (header, body) = (header.to_string(), body.to_vec());
+    std::thread::spawn(move || {
+        let respond = | response: mpsc::Sender<HttpServerResponse> | {
+            let _ = response.send(HttpServerResponse {header: header.clone(), body: body.clone()});
+        };
+        while let Ok(request) = requests.recv() {
+            match request {
+                HttpServerRequest::Get {headers, response_sender} => {
+                    let _ = seen_tx.send((headers.path, Vec::new()));
+                    respond(response_sender);
+                }
+                HttpServerRequest::Post {headers, body, response} => {
+                    let _ = seen_tx.send((headers.path, body));
+                    respond(response);
+                }
+                _ => ()
+            }
+        }
+    });
+    (listen_address, seen)
+}
+
+/// Makes the request and collects the responses up to the final one
+fn fetch(mut request: HttpRequest, addr: SocketAddr, path: &str) -> Vec<NetworkResponse> {
+    request.url = format!("http://{}{}", addr, path);
+    let (sender, receiver) = mpsc::channel();
+    let mut requests = LinuxHttpRequests::default();
+    requests.make_http_request(live_id!(test), request, sender);
+    let mut responses = Vec::new();
+    loop {
+        let item = receiver.recv_timeout(Duration::from_secs(5)).expect("http request timed out");
+        assert_eq!(item.request_id, live_id!(test));
+        let done = matches!(item.response,
+            NetworkResponse::HttpResponse(_) |
+            NetworkResponse::HttpStreamComplete(_) |
+            NetworkResponse::HttpRequestError(_)
+        );
+        responses.push(item.response);
+        if done {
+            return responses
+        }
+    }
+}
+
+fn get(addr: SocketAddr, path: &str) -> Vec<NetworkResponse> {
+    fetch(HttpRequest::new(String::new(), HttpMethod::GET), addr, path)
+}
+
+fn body(responses: &[NetworkResponse]) -> Vec<u8> {
+    match responses.last() {
+        Some(NetworkResponse::HttpResponse(response)) => response.body.clone().unwrap(),
+        _ => panic!("expected a response")
+    }
+}
+
+fn error(responses: &[NetworkResponse]) -> String {
+    match responses.last() {
+        Some(NetworkResponse::HttpRequestError(err)) => err.message.clone(),
+        _ => panic!("expected an error")
+    }
+}
+
+#[test]
+fn content_length_body() {
+    let (addr, seen) = serve("HTTP/1.1 200 OK\r\nContent-Length: 11\r\nX-Test: yes\r\n\r\n", b"hello world");
+    let responses = get(addr, "/file.txt");
+    assert_eq!(seen.recv().unwrap().0, "/file.txt");
+    assert_eq!(body(&responses), b"hello world");
+    match responses.last() {
+        Some(NetworkResponse::HttpResponse(response)) => {
+            assert_eq!(response.status_code, 200);
+            assert_eq!(response.headers.get("X-Test").unwrap(), &vec!["yes".to_string()]);
+        }
+        _ => unreachable!()
+    }
+    // progress counts up to the content length
+    assert!(responses.iter().any( | r | matches!(r, NetworkResponse::HttpProgress(p) if p.loaded == 11 && p.total == 11)));
+}
+
+#[test]
+fn body_until_close() {
+    let (addr, _seen) = serve("HTTP/1.1 404 Not Found\r\n\r\n", b"missing");
+    let responses = get(addr, "/");
+    assert_eq!(body(&responses), b"missing");
+    match responses.last() {
+        Some(NetworkResponse::HttpResponse(response)) => assert_eq!(response.status_code, 404),
+        _ => unreachable!()
+    }
+}
+
+#[test]
+fn chunked_body() {
+    let (addr, _seen) = serve(
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n",
+        b"5\r\nhello\r\n6;name=value\r\n world\r\n0\r\nX-Trailer: 1\r\n\r\n"
+    );
+    assert_eq!(body(&get(addr, "/")), b"hello world");
+}
+
+#[test]
+fn chunked_stream() {
+    let (addr, _seen) = serve(
+        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n",
+        b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n"
+    );
+    let mut request = HttpRequest::new(String::new(), HttpMethod::GET);
+    request.is_streaming = true;
+    let responses = fetch(request, addr, "/");
+    let mut streamed = Vec::new();
+    for response in &responses {
+        if let NetworkResponse::HttpStreamResponse(response) = response {
+            streamed.extend_from_slice(response.body.as_ref().unwrap());
+        }
+    }
+    assert_eq!(streamed, b"hello world");
+    assert!(matches!(responses.last(), Some(NetworkResponse::HttpStreamComplete(_))));
+}
+
+#[test]
+fn huge_chunk_size_is_not_allocated() {
+    // a chunk of 256 terabytes that ends after 3 bytes
+    let (addr, _seen) = serve("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n", b"ffffffffffff\r\nabc");
+    assert_eq!(error(&get(addr, "/")), "Http connection closed inside chunk");
+}
+
+#[test]
+fn invalid_responses() {
+    let (addr, _seen) = serve("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n", b"zz\r\n");
+    assert!(error(&get(addr, "/")).starts_with("Invalid http chunk size"));
+    let (addr, _seen) = serve("HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n", b"short");
+    assert_eq!(error(&get(addr, "/")), "Http connection closed before end of body");
+    let (addr, _seen) = serve("SMTP ready\r\n\r\n", b"");
+    assert!(error(&get(addr, "/")).starts_with("Invalid http status line"));
+}
+
+#[test]
+fn post_body() {
+    let (addr, seen) = serve("HTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\n", b"ok");
+    let mut request = HttpRequest::new(String::new(), HttpMethod::POST);
+    request.set_body(b"name=makepad".to_vec());
+    let responses = fetch(request, addr, "/form");
+    assert_eq!(body(&responses), b"ok");
+    assert_eq!(seen.recv().unwrap(), ("/form".to_string(), b"name=makepad".to_vec()));
+}
+
+#[test]
+fn connection_errors() {
+    let responses = fetch(HttpRequest::new(String::new(), HttpMethod::GET), "127.0.0.1:1".parse().unwrap(), "/");
+    assert!(error(&responses).starts_with("Error connecting"));
+    let mut request = HttpRequest::new(String::new(), HttpMethod::GET);
+    request.url = "https://localhost/".to_string();
+    let (sender, receiver) = mpsc::channel();
+    LinuxHttpRequests::default().make_http_request(live_id!(test), request, sender);
+    match receiver.recv_timeout(Duration::from_secs(5)).unwrap().response {
+        NetworkResponse::HttpRequestError(err) => assert!(err.message.starts_with("Https is not supported")),
+        _ => panic!("expected an error")
+    }
+}
@@ -0,0 +1,149 @@
+use makepad_platform::{
+    MidiData,
+    MidiFile,
+    MidiFileError,
+    MidiFileFormat,
+    MidiMetaEvent,
+    MidiNote,
+    MidiProgramChange,
+    MidiRecorder,
+    MidiTempoMap,
+    MidiTimeDivision,
+    MidiTrack,
+    MidiTrackEventKind,
+};
+
+fn note(is_on: bool, note_number: u8) -> MidiData {
+    MidiNote {is_on, channel: 1, note_number, velocity: if is_on {100} else {0}}.into()
+}
+
+// a type 0 file with running status, a program change and a two byte delta time
+const TYPE_0: &[u8] = &[
+    b'M', b'T', b'h', b'd', 0, 0, 0, 6, 0, 0, 0, 1, 0, 96,
+    b'M', b'T', b'r', b'k', 0, 0, 0, 26,
+    0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20,
+    0x00, 0xC1, 0x05,
+    0x00, 0x91, 60, 100,
+    0x81, 0x40, 60, 0,
+    0x00, 0xB1, 7, 90,
+    0x00, 0xFF, 0x2F, 0x00,
+];
+
+#[test]
+fn reads_type_0_with_running_status() {
+    let file = MidiFile::from_bytes(TYPE_0).unwrap();
+    assert_eq!(file.format, MidiFileFormat::SingleTrack);
+    assert_eq!(file.division, MidiTimeDivision::TicksPerQuarter(96));
+    let events = &file.tracks[0].events;
+    assert_eq!(events.len(), 6);
+    assert_eq!(events[0].kind, MidiTrackEventKind::Meta(MidiMetaEvent::Tempo(500_000)));
+    assert_eq!(events[1].kind, MidiTrackEventKind::Midi(MidiData {data: [0xC1, 5, 0]}));
+    assert_eq!(events[2].kind, MidiTrackEventKind::Midi(MidiData {data: [0x91, 60, 100]}));
+    // the note off reuses the note on status
+    assert_eq!(events[3].tick, 192);
+    assert_eq!(events[3].kind, MidiTrackEventKind::Midi(MidiData {data: [0x91, 60, 0]}));
+    assert_eq!(events[5].kind, MidiTrackEventKind::Meta(MidiMetaEvent::EndOfTrack));
+}
+
+#[test]
+fn writes_with_running_status() {
+    let file = MidiFile::from_bytes(TYPE_0).unwrap();
+    assert_eq!(file.to_bytes(), TYPE_0);
+}
+
+#[test]
+fn round_trips_type_1() {
+    let mut file = MidiFile::new(MidiFileFormat::MultiTrack, MidiTimeDivision::TicksPerQuarter(480));
+    let mut tempo = MidiTrack::default();
+    tempo.push_meta(0, MidiMetaEvent::TrackName("tempo".into()));
+    tempo.push_meta(0, MidiMetaEvent::TimeSignature {
+        numerator: 6,
+        denominator: 3,
+        clocks_per_click: 24,
+        thirty_seconds_per_quarter: 8
+    });
+    tempo.push_meta(0, MidiMetaEvent::Tempo(400_000));
+    tempo.push_meta(1920, MidiMetaEvent::Tempo(600_000));
+    let mut notes = MidiTrack::default();
+    notes.push_midi(0, MidiProgramChange {channel: 1, hi: 10, lo: 0});
+    notes.push(10, MidiTrackEventKind::SysEx(vec![0x7E, 0x7F, 0x09, 0x01, 0xF7]));
+    notes.push_midi(480, note(true, 64));
+    notes.push_midi(300_000, note(false, 64));
+    notes.push_meta(300_000, MidiMetaEvent::Other {kind: 0x7F, data: vec![1, 2, 3]});
+    file.tracks.push(tempo);
+    file.tracks.push(notes);
+
+    let read = MidiFile::from_bytes(&file.to_bytes()).unwrap();
+    // the writer ends every track
+    for track in &mut file.tracks {
+        let end_tick = track.end_tick();
+        track.push_meta(end_tick, MidiMetaEvent::EndOfTrack);
+    }
+    assert_eq!(read, file);
+}
+
+#[test]
+fn tempo_map_follows_tempo_changes() {
+    let map = MidiTempoMap::new(MidiTimeDivision::TicksPerQuarter(480), vec![(960, 1_000_000), (0, 250_000)]);
+    assert_eq!(map.tick_to_seconds(480), 0.25);
+    assert_eq!(map.tick_to_seconds(960), 0.5);
+    assert_eq!(map.tick_to_seconds(1440), 1.5);
+    assert_eq!(map.seconds_to_tick(1.5), 1440);
+    assert_eq!(map.seconds_to_tick(0.125), 240);
+    // without tempo events the tempo is 120 beats per minute
+    let map = MidiTempoMap::new(MidiTimeDivision::TicksPerQuarter(100), Vec::new());
+    assert_eq!(map.tick_to_seconds(100), 0.5);
+    let map = MidiTempoMap::new(MidiTimeDivision::Smpte {frames_per_second: 25, ticks_per_frame: 40}, vec![(0, 1)]);
+    assert_eq!(map.tick_to_seconds(1000), 1.0);
+}
+
+#[test]
+fn merges_tracks_in_time_order() {
+    let mut file = MidiFile::new(MidiFileFormat::MultiTrack, MidiTimeDivision::TicksPerQuarter(2));
+    let mut first = MidiTrack::default();
+    first.push_meta(2, MidiMetaEvent::Tempo(1_000_000));
+    first.push_midi(2, note(true, 1));
+    let mut second = MidiTrack::default();
+    second.push_midi(1, note(true, 2));
+    second.push_midi(2, note(true, 3));
+    second.push_midi(4, note(true, 4));
+    file.tracks.push(first);
+    file.tracks.push(second);
+    let events = file.timed_midi_events();
+    let notes: Vec<(f64, u8)> = events.iter().map( | (time, data) | (*time, data.data[1])).collect();
+    assert_eq!(notes, vec![(0.25, 2), (0.5, 1), (0.5, 3), (1.5, 4)]);
+}
+
+#[test]
+fn rejects_invalid_files() {
+    assert!(matches!(MidiFile::from_bytes(b"RIFF"), Err(MidiFileError::NotMidiFile)));
+    let mut type_2 = TYPE_0.to_vec();
+    type_2[9] = 2;
+    assert!(matches!(MidiFile::from_bytes(&type_2), Err(MidiFileError::UnsupportedFormat(2))));
+    assert!(matches!(MidiFile::from_bytes(&TYPE_0[..30]), Err(MidiFileError::UnexpectedEnd)));
+    let mut no_status = TYPE_0.to_vec();
+    // make the tempo event a bare data byte
+    no_status[23] = 0x40;
+    assert!(matches!(MidiFile::from_bytes(&no_status), Err(MidiFileError::InvalidEvent(_))));
+}
+
+#[test]
+fn records_to_a_file() {
+    let mut recorder = MidiRecorder::new();
+    recorder.record(1.0, note(true, 60));
+    assert!(recorder.events().is_empty());
+    recorder.start(10.0);
+    recorder.record(10.5, note(true, 60));
+    recorder.record(10.5, MidiData {data: [0xF8, 0, 0]});
+    recorder.record(11.0, note(false, 60));
+    recorder.stop();
+    recorder.record(12.0, note(true, 62));
+    assert_eq!(recorder.events(), &[(0.5, note(true, 60)), (1.0, note(false, 60))]);
+
+    let file = recorder.to_midi_file(96, 250_000);
+    let read = MidiFile::from_bytes(&file.to_bytes()).unwrap();
+    assert_eq!(read, file);
+    let ticks: Vec<u64> = read.tracks[0].events.iter().map( | event | event.tick).collect();
+    assert_eq!(ticks, vec![0, 192, 384, 384]);
+    assert_eq!(read.timed_midi_events(), vec![(0.5, note(true, 60)), (1.0, note(false, 60))]);
+}
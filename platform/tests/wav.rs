@@ -0,0 +1,118 @@
+use makepad_platform::{AudioBuffer, WavError, WavFile, WavSampleFormat};
+
+// a few frames per channel covering silence, both extremes and some values in between
+fn test_buffer(channel_count: usize) -> AudioBuffer {
+    let frame = [0.0, 0.5, -0.5, 0.25, -1.0, 0.999, -0.125, 0.0625];
+    let mut data = Vec::new();
+    for channel in 0..channel_count {
+        for (i, sample) in frame.iter().enumerate() {
+            data.push(if (i + channel) % 2 == 0 {*sample} else {-*sample});
+        }
+    }
+    AudioBuffer::from_data(data, channel_count)
+}
+
+fn assert_near(a: &AudioBuffer, b: &AudioBuffer, epsilon: f32) {
+    assert_eq!(a.channel_count(), b.channel_count());
+    assert_eq!(a.frame_count(), b.frame_count());
+    for (x, y) in a.data.iter().zip(b.data.iter()) {
+        assert!((x - y).abs() <= epsilon, "{} != {}", x, y);
+    }
+}
+
+#[test]
+fn round_trip_all_formats() {
+    let formats = [
+        (WavSampleFormat::U8, 1.0 / 128.0),
+        (WavSampleFormat::I16, 1.0 / 32768.0),
+        (WavSampleFormat::I24, 1.0 / 8388608.0),
+        (WavSampleFormat::I32, 1e-7),
+        (WavSampleFormat::F32, 0.0),
+    ];
+    for (sample_format, epsilon) in formats {
+        for channel_count in [1, 2, 6] {
+            let buffer = test_buffer(channel_count);
+            let bytes = buffer.to_wav(44100, sample_format);
+            let wav = WavFile::from_bytes(&bytes).unwrap();
+            assert_eq!(wav.sample_rate, 44100);
+            assert_eq!(wav.sample_format, sample_format);
+            assert_near(&wav.buffer, &buffer, epsilon);
+            // decoding and encoding again gives the exact same file
+            assert_eq!(wav.to_bytes(), bytes);
+        }
+    }
+}
+
+#[test]
+fn writes_canonical_header() {
+    let buffer = AudioBuffer::from_data(vec![0.0, 0.5, -0.5, 1.0], 2);
+    let bytes = buffer.to_wav(48000, WavSampleFormat::I16);
+    assert_eq!(bytes.len(), 44 + 8);
+    assert_eq!(&bytes[0..4], b"RIFF");
+    assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 44 + 8 - 8);
+    assert_eq!(&bytes[8..16], b"WAVEfmt ");
+    // PCM, 2 channels, 48000 Hz, 192000 bytes per second, block align 4, 16 bits
+    assert_eq!(&bytes[20..36], &[1, 0, 2, 0, 0x80, 0xBB, 0, 0, 0, 0xEE, 2, 0, 4, 0, 16, 0]);
+    assert_eq!(&bytes[36..44], b"data\x08\x00\x00\x00");
+    // frames are interleaved, the second channel holds -0.5 and 1.0
+    assert_eq!(&bytes[44..52], &[0, 0, 0x00, 0xC0, 0x00, 0x40, 0xFF, 0x7F]);
+}
+
+#[test]
+fn clamps_out_of_range_samples() {
+    let buffer = AudioBuffer::from_data(vec![2.0, -2.0], 1);
+    let wav = WavFile::from_bytes(&buffer.to_wav(8000, WavSampleFormat::I16)).unwrap();
+    assert_eq!(wav.buffer.data, vec![32767.0 / 32768.0, -1.0]);
+}
+
+#[test]
+fn skips_unknown_chunks_and_odd_padding() {
+    let buffer = AudioBuffer::from_data(vec![0.5, -0.25, 0.125], 1);
+    let bytes = buffer.to_wav(22050, WavSampleFormat::U8);
+    // three bytes of samples are padded to an even chunk size
+    assert_eq!(bytes.len(), 44 + 4);
+    // insert a LIST chunk with an odd size in front of the data chunk
+    let mut with_list = bytes[..36].to_vec();
+    with_list.extend_from_slice(b"LIST\x03\x00\x00\x00abc\x00");
+    with_list.extend_from_slice(&bytes[36..]);
+    let riff_size = (with_list.len() - 8) as u32;
+    with_list[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    let (decoded, sample_rate) = AudioBuffer::from_wav(&with_list).unwrap();
+    assert_eq!(sample_rate, 22050);
+    assert_eq!(decoded.data, vec![0.5, -0.25, 0.125]);
+}
+
+#[test]
+fn reads_truncated_data_chunk() {
+    let buffer = test_buffer(2);
+    let mut bytes = buffer.to_wav(44100, WavSampleFormat::I24);
+    // cut off the last frame and a half, like a recording that was never finalized
+    bytes.truncate(bytes.len() - 9);
+    let wav = WavFile::from_bytes(&bytes).unwrap();
+    assert_eq!(wav.buffer.frame_count(), buffer.frame_count() - 2);
+    assert_eq!(wav.buffer.channel(1)[0], buffer.channel(1)[0]);
+}
+
+#[test]
+fn rejects_invalid_files() {
+    assert!(matches!(WavFile::from_bytes(b"RIFX\0\0\0\0WAVE"), Err(WavError::NotRiffWave)));
+    assert!(matches!(WavFile::from_bytes(b"RIFF\x04\0\0\0WAVE"), Err(WavError::MissingChunk("fmt "))));
+    let mut bytes = test_buffer(1).to_wav(44100, WavSampleFormat::I16);
+    // 12 bit samples
+    bytes[34] = 12;
+    assert!(matches!(
+        WavFile::from_bytes(&bytes),
+        Err(WavError::UnsupportedFormat {format_tag: 1, bits_per_sample: 12})
+    ));
+}
+
+#[test]
+fn reads_and_writes_files() {
+    let path = std::env::temp_dir().join(format!("makepad_wav_test_{}.wav", std::process::id()));
+    let wav = WavFile::new(test_buffer(2), 96000, WavSampleFormat::F32);
+    wav.write_to_path(&path).unwrap();
+    let read = WavFile::read_from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read.sample_rate, 96000);
+    assert_eq!(read.buffer.data, wav.buffer.data);
+}
@@ -14,6 +14,8 @@
     log_list::*,
     makepad_code_editor::{CodeSession,text::{Position}},
     ai_chat::ai_chat_manager::AiChatManager,
+    search::search_manager::SearchManager,
+    lsp::{lsp_client::LspServerConfig, lsp_manager::LspManager},
     build_manager::{
         build_protocol::BuildProcess,
         build_manager::{
@@ -51,6 +53,7 @@ fn live_register(cx: &mut Cx) {
         crate::studio_file_tree::live_design(cx);
         crate::app_ui::live_design(cx);
         crate::ai_chat::ai_chat_view::live_design(cx);
+        crate::search::search_view::live_design(cx);
         // for macos
         cx.start_stdin_service();
     }
@@ -140,6 +143,8 @@ pub struct AppData{
     pub build_manager: BuildManager,
     pub file_system: FileSystem,
     pub ai_chat_manager: AiChatManager,
+    pub search_manager: SearchManager,
+    pub lsp_manager: LspManager,
 }
 
 // all global app commands coming in from keybindings, and UI components
@@ -151,6 +156,7 @@ pub enum AppAction{
     SwapSelection(SwapSelection),
     RedrawLog,
     RedrawProfiler,
+    RedrawSearch,
     RedrawFile(LiveId),
     FocusDesign(LiveId),
     EditFile(EditFile),
@@ -171,16 +177,26 @@ pub enum AppAction{
 impl MatchEvent for App{
     fn handle_startup(&mut self, cx:&mut Cx){
         let mut root = "./".to_string();
+        // --lsp=<command and args> runs that language server for rust files instead of rust-analyzer
+        let mut lsp_config = LspServerConfig::rust_analyzer();
         for arg in std::env::args(){
             if let Some(prefix) = arg.strip_prefix("--root="){
                 root = prefix.to_string();
-                break;
+            }
+            if let Some(prefix) = arg.strip_prefix("--lsp="){
+                let mut parts = prefix.split_whitespace().map(|part| part.to_string());
+                if let Some(command) = parts.next(){
+                    lsp_config.command = command;
+                    lsp_config.args = parts.collect();
+                }
             }
         }
         let root_path = env::current_dir().unwrap().join(root);
                 
         self.data.file_system.init(cx, &root_path);
         self.data.build_manager.init(cx, &root_path);
+        self.data.search_manager.init(&root_path);
+        self.data.lsp_manager.init(&root_path, vec![lsp_config]);
         
                 
         //self.data.build_manager.discover_external_ip(cx);
@@ -194,6 +210,7 @@ fn handle_action(&mut self, cx:&mut Cx, action:&Action){
         let log_list = self.ui.log_list(id!(log_list));
         let run_list = self.ui.view(id!(run_list_tab));
         let profiler = self.ui.view(id!(profiler));
+        let search = self.ui.view(id!(search));
         
         match action.cast(){
             AppAction::SwapSelection(ss)=>{
@@ -361,6 +378,9 @@ fn handle_action(&mut self, cx:&mut Cx, action:&Action){
             AppAction::RedrawProfiler=>{
                 profiler.redraw(cx);
             }
+            AppAction::RedrawSearch=>{
+                search.redraw(cx);
+            }
             AppAction::RedrawLog=>{
                 log_list.redraw(cx);
             }
@@ -644,6 +664,7 @@ fn handle_actions(&mut self, cx: &mut Cx, actions:&Actions){
     
     fn handle_shutdown(&mut self, _cx:&mut Cx){
         self.data.build_manager.clear_active_builds();
+        self.data.lsp_manager.shutdown();
     }
 }
 
@@ -656,6 +677,8 @@ fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
         self.data.file_system.handle_event(cx, event, &self.ui);
         self.data.build_manager.handle_event(cx, event, &mut self.data.file_system); 
         self.data.ai_chat_manager.handle_event(cx, event, &mut self.data.file_system);
+        self.data.search_manager.handle_event(cx, event, &mut self.data.file_system);
+        self.data.lsp_manager.handle_event(cx, event, &mut self.data.file_system);
         if self.ui.dock(id!(dock)).check_and_clear_need_save(){
             self.save_state(0);
         }
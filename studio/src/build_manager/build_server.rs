@@ -338,6 +338,8 @@ pub fn run(&self, what: BuildProcess, cmd_id: LiveId, http: String) {
                             msg_sender.send_bare_message(cmd_id, LogLevel::Error, line);
                         }
                     }
+                    // builds are started line by line
+                    ChildStdIO::StdOutData(_) => (),
                     ChildStdIO::Term => {
                         msg_sender.send_bare_message(
                             cmd_id,
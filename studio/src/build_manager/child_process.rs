@@ -19,6 +19,8 @@ pub struct ChildProcess {
 
 pub enum ChildStdIO {
     StdOut(String),
+    /// Raw stdout bytes, as read, for processes started with `start_with_stdout_data`.
+    StdOutData(Vec<u8>),
     StdErr(String),
     Term,
     Kill
@@ -32,6 +34,17 @@ pub enum ChildStdIn {
 impl ChildProcess {
     
     pub fn start(cmd: &str, args: &[String], current_dir: PathBuf, env: &[(&str, &str)], aux_chan:bool) -> Result<ChildProcess, std::io::Error> {
+        Self::start_inner(cmd, args, current_dir, env, aux_chan, true)
+    }
+    
+    /// Starts a process whose stdout is passed on as `ChildStdIO::StdOutData` as soon as it is
+    /// read, instead of line by line. This is for protocols that frame messages by their length,
+    /// whose last message need not end in a newline. The end of stdout is sent as `ChildStdIO::Term`.
+    pub fn start_with_stdout_data(cmd: &str, args: &[String], current_dir: PathBuf, env: &[(&str, &str)]) -> Result<ChildProcess, std::io::Error> {
+        Self::start_inner(cmd, args, current_dir, env, false, false)
+    }
+    
+    fn start_inner(cmd: &str, args: &[String], current_dir: PathBuf, env: &[(&str, &str)], aux_chan:bool, stdout_lines:bool) -> Result<ChildProcess, std::io::Error> {
         let (mut child, aux_chan_host_endpoint) = if aux_chan{
             let (aux_chan_host_endpoint, aux_chan_client_endpoint) =
                 aux_chan::make_host_and_client_endpoint_pair()?;
@@ -82,6 +95,23 @@ pub fn start(cmd: &str, args: &[String], current_dir: PathBuf, env: &[(&str, &st
             let line_sender = line_sender.clone();
             let stdin_sender = stdin_sender.clone();
             thread::spawn(move || {
+                if !stdout_lines{
+                    let mut stdout = stdout;
+                    let mut buf = [0u8; 4096];
+                    loop{
+                        match stdout.read(&mut buf){
+                            Ok(0) | Err(_) => {
+                                let _ = line_sender.send(ChildStdIO::Term);
+                                let _ = stdin_sender.send(ChildStdIn::Term);
+                                break;
+                            }
+                            Ok(len) => if line_sender.send(ChildStdIO::StdOutData(buf[..len].to_vec())).is_err(){
+                                break;
+                            }
+                        }
+                    }
+                    return
+                }
                 let mut reader = BufReader::new(stdout);
                 loop{
                     let mut line = String::new();
@@ -2,7 +2,7 @@
     std::collections::{HashMap, hash_map},
     std::path::Path,
     crate::{
-        makepad_code_editor::{CodeDocument, decoration::{Decoration, DecorationSet}, CodeSession},
+        makepad_code_editor::{CodeDocument, decoration::{Decoration, DecorationSet}, CodeSession, TokenizerRegistry},
         makepad_platform::makepad_live_compiler::LiveFileChange,
         makepad_widgets::*,
         makepad_widgets::file_tree::*,
@@ -30,7 +30,8 @@ pub struct FileSystem {
     pub path_to_file_node_id: HashMap<String, LiveId>,
     pub tab_id_to_file_node_id: HashMap<LiveId, LiveId>,
     pub tab_id_to_session: HashMap<LiveId, EditSession>,
-    pub open_documents: HashMap<LiveId, OpenDocument>
+    pub open_documents: HashMap<LiveId, OpenDocument>,
+    pub tokenizers: TokenizerRegistry,
 }
 
 pub enum EditSession {
@@ -193,7 +194,8 @@ pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, ui: &WidgetRef) {
                                     match self.open_documents.get(&file_id){
                                         Some(OpenDocument::CodeLoading(dec))=>{
                                             let dec = dec.clone();
-                                            self.open_documents.insert(file_id, OpenDocument::Code(CodeDocument::new(response.data.into(), dec)));
+                                            let tokenizer = self.tokenizers.get_for_path(self.file_node_id_to_path(file_id).unwrap_or(""));
+                                            self.open_documents.insert(file_id, OpenDocument::Code(CodeDocument::with_tokenizer(response.data.into(), dec, &*tokenizer)));
                                         }
                                         Some(OpenDocument::Code(_))=>{
                                         }
@@ -441,6 +443,16 @@ pub fn request_save_file_for_file_node_id(&mut self, file_id: LiveId, patch:bool
         }
     }
     
+    pub fn request_save_file_data(&mut self, file_id: LiveId, data: String, patch:bool) {
+        let path = self.file_node_path(file_id);
+        self.file_client.send_request(FileRequest::SaveFile{
+            path,
+            data,
+            id: file_id.0,
+            patch
+        });
+    }
+
     pub fn clear_decorations(&mut self, file_node_id: &LiveId) {
         // ok lets see if we have a document
         // ifnot, we create a new one
@@ -12,6 +12,8 @@
 pub mod profiler;
 pub mod integration;
 pub mod ai_chat;
+pub mod search;
+pub mod lsp;
 
 //pub use makepad_code_editor;
 
@@ -0,0 +1,384 @@
+use {
+    crate::{
+        build_manager::child_process::{ChildProcess, ChildStdIO, ChildStdIn},
+        lsp::lsp_protocol::*,
+        makepad_code_editor::{text::{Edit, Text}, CodeDocument},
+        makepad_micro_serde::*,
+        makepad_platform::*,
+    },
+    std::{
+        collections::HashMap,
+        path::Path,
+        sync::mpsc::{self, Receiver},
+    },
+};
+
+/// How to start a language server, and which files it is for.
+#[derive(Clone, Debug)]
+pub struct LspServerConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    /// The `languageId` documents are opened with.
+    pub language_id: String,
+    pub extensions: Vec<String>,
+}
+
+impl LspServerConfig {
+    pub fn rust_analyzer() -> Self {
+        Self {
+            command: "rust-analyzer".to_string(),
+            args: Vec::new(),
+            language_id: "rust".to_string(),
+            extensions: vec!["rs".to_string()],
+        }
+    }
+
+    pub fn handles(&self, path: &str) -> bool {
+        Path::new(path)
+            .extension()
+            .and_then( | extension | extension.to_str())
+            .is_some_and( | extension | self.extensions.iter().any( | handled | handled == extension))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum LspEvent {
+    /// The server answered `initialize`. Requests made before are sent now.
+    Initialized,
+    Diagnostics {uri: String, diagnostics: Vec<LspDiagnostic>},
+    Completion {request_id: u64, items: Vec<LspCompletionItem>},
+    Hover {request_id: u64, text: Option<String>},
+    Definition {request_id: u64, locations: Vec<LspLocation>},
+    /// A request failed.
+    Error {request_id: u64, message: String},
+    /// A line the server wrote to stderr, or a message it wants shown.
+    Log(String),
+    Exited,
+}
+
+enum LspIncoming {
+    Message(JsonValue),
+    Log(String),
+    Exited,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum LspRequestKind {
+    Initialize,
+    Shutdown,
+    Completion,
+    Hover,
+    Definition,
+}
+
+/// A language server running as a child process, spoken to over its stdin and stdout.
+pub struct LspClient {
+    process: ChildProcess,
+    next_request_id: u64,
+    pending: HashMap<u64, LspRequestKind>,
+    is_initialized: bool,
+    // messages that have to wait for the answer to `initialize`
+    held_back: Vec<String>,
+    recv_incoming: ToUIReceiver<LspIncoming>,
+}
+
+impl LspClient {
+    /// Starts the server and sends it `initialize`, with `root_path` as the workspace.
+    pub fn start(config: &LspServerConfig, root_path: &Path) -> Result<Self, std::io::Error> {
+        let mut process = ChildProcess::start_with_stdout_data(&config.command, &config.args, root_path.to_path_buf(), &[])?;
+
+        // the output is decoded on a thread of its own that wakes up the UI for every message
+        let (_, unused_receiver) = mpsc::channel();
+        let output = std::mem::replace(&mut process.line_receiver, unused_receiver);
+        let recv_incoming = ToUIReceiver::default();
+        let sender = recv_incoming.sender();
+        std::thread::spawn(move || {
+            let mut decoder = MessageDecoder::default();
+            while let Ok(output) = output.recv() {
+                match output {
+                    ChildStdIO::StdOutData(data) => {
+                        decoder.push(&data);
+                        while let Some(body) = decoder.next_message() {
+                            if let Ok(message) = JsonValue::deserialize_json(&body) {
+                                let _ = sender.send(LspIncoming::Message(message));
+                            }
+                        }
+                    }
+                    ChildStdIO::StdOut(line) | ChildStdIO::StdErr(line) => {
+                        let _ = sender.send(LspIncoming::Log(line.trim_end().to_string()));
+                    }
+                    ChildStdIO::Term | ChildStdIO::Kill => break
+                }
+            }
+            let _ = sender.send(LspIncoming::Exited);
+        });
+
+        let mut client = Self {
+            process,
+            next_request_id: 1,
+            pending: HashMap::new(),
+            is_initialized: false,
+            held_back: Vec::new(),
+            recv_incoming,
+        };
+        let root_uri = path_to_uri(root_path);
+        let root_name = root_path.file_name().map_or(String::new(), | name | name.to_string_lossy().into_owned());
+        let params = json_object([
+            ("processId", JsonValue::U64(std::process::id() as u64)),
+            ("rootUri", json_string(root_uri.clone())),
+            ("workspaceFolders", JsonValue::Array(vec![json_object([
+                ("uri", json_string(root_uri)),
+                ("name", json_string(root_name)),
+            ])])),
+            ("capabilities", json_object([
+                ("textDocument", json_object([
+                    ("synchronization", json_object([("dynamicRegistration", JsonValue::Bool(false))])),
+                    ("completion", json_object([
+                        ("completionItem", json_object([("snippetSupport", JsonValue::Bool(false))])),
+                    ])),
+                    ("hover", json_object([
+                        ("contentFormat", JsonValue::Array(vec![json_string("plaintext"), json_string("markdown")])),
+                    ])),
+                    ("definition", json_object([("linkSupport", JsonValue::Bool(true))])),
+                    ("publishDiagnostics", json_object([])),
+                ])),
+            ])),
+        ]);
+        client.send_request(LspRequestKind::Initialize, "initialize", params);
+        Ok(client)
+    }
+
+    pub fn did_open(&mut self, uri: &str, language_id: &str, version: u64, text: &str) {
+        self.send_notification("textDocument/didOpen", json_object([
+            ("textDocument", json_object([
+                ("uri", json_string(uri)),
+                ("languageId", json_string(language_id)),
+                ("version", JsonValue::U64(version)),
+                ("text", json_string(text)),
+            ])),
+        ]));
+    }
+
+    /// Sends `changes`, made with [`content_change`], which take the document to `version`.
+    pub fn did_change(&mut self, uri: &str, version: u64, changes: Vec<JsonValue>) {
+        self.send_notification("textDocument/didChange", json_object([
+            ("textDocument", json_object([
+                ("uri", json_string(uri)),
+                ("version", JsonValue::U64(version)),
+            ])),
+            ("contentChanges", JsonValue::Array(changes)),
+        ]));
+    }
+
+    pub fn did_close(&mut self, uri: &str) {
+        self.send_notification("textDocument/didClose", json_object([
+            ("textDocument", json_object([("uri", json_string(uri))])),
+        ]));
+    }
+
+    /// Asks for completions at `position`. The answer comes as an `LspEvent::Completion` with
+    /// the returned id.
+    pub fn completion(&mut self, uri: &str, position: LspPosition) -> u64 {
+        self.send_request(LspRequestKind::Completion, "textDocument/completion", text_document_position(uri, position))
+    }
+
+    pub fn hover(&mut self, uri: &str, position: LspPosition) -> u64 {
+        self.send_request(LspRequestKind::Hover, "textDocument/hover", text_document_position(uri, position))
+    }
+
+    pub fn definition(&mut self, uri: &str, position: LspPosition) -> u64 {
+        self.send_request(LspRequestKind::Definition, "textDocument/definition", text_document_position(uri, position))
+    }
+
+    /// Asks the server to shut down and exit. It should be dropped afterwards.
+    pub fn shutdown(&mut self) {
+        self.send_request(LspRequestKind::Shutdown, "shutdown", JsonValue::Null);
+        self.send_notification("exit", JsonValue::Null);
+    }
+
+    /// Everything that came in from the server since the last call.
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let mut events = Vec::new();
+        while let Ok(incoming) = self.recv_incoming.try_recv() {
+            match incoming {
+                LspIncoming::Message(message) => self.handle_message(message, &mut events),
+                LspIncoming::Log(line) => events.push(LspEvent::Log(line)),
+                LspIncoming::Exited => events.push(LspEvent::Exited),
+            }
+        }
+        events
+    }
+
+    fn handle_message(&mut self, message: JsonValue, events: &mut Vec<LspEvent>) {
+        let method = message.key("method").and_then( | method | method.string());
+        let params = message.key("params").unwrap_or(&JsonValue::Null);
+        match (method, message.key("id")) {
+            (Some(method), Some(id)) => {
+                // the server asks us something. nothing it asks for is supported, but
+                // `workspace/configuration` expects an answer for every item
+                let result = match method.as_str() {
+                    "workspace/configuration" => JsonValue::Array(
+                        params.key("items").and_then( | items | items.array())
+                            .map_or(Vec::new(), | items | items.iter().map( | _ | JsonValue::Null).collect())
+                    ),
+                    _ => JsonValue::Null,
+                };
+                self.send(json_object([
+                    ("jsonrpc", json_string("2.0")),
+                    ("id", id.clone()),
+                    ("result", result),
+                ]), false);
+            }
+            (Some(method), None) => match method.as_str() {
+                "textDocument/publishDiagnostics" => {
+                    let Some(uri) = params.key("uri").and_then( | uri | uri.string()) else {return};
+                    let diagnostics = params.key("diagnostics").and_then( | diagnostics | diagnostics.array())
+                        .map_or(Vec::new(), | diagnostics | diagnostics.iter().filter_map(LspDiagnostic::from_json).collect());
+                    events.push(LspEvent::Diagnostics {uri: uri.clone(), diagnostics});
+                }
+                "window/logMessage" | "window/showMessage" => {
+                    if let Some(message) = params.key("message").and_then( | message | message.string()) {
+                        events.push(LspEvent::Log(message.clone()));
+                    }
+                }
+                _ => ()
+            }
+            (None, Some(id)) => {
+                let Some(request_id) = id.u64() else {return};
+                let Some(kind) = self.pending.remove(&request_id) else {return};
+                if let Some(error) = message.key("error") {
+                    let message = error.key("message").and_then( | message | message.string()).cloned().unwrap_or_default();
+                    events.push(LspEvent::Error {request_id, message});
+                    return
+                }
+                let result = message.key("result").unwrap_or(&JsonValue::Null);
+                match kind {
+                    LspRequestKind::Initialize => {
+                        self.is_initialized = true;
+                        self.send_notification("initialized", json_object([]));
+                        for body in std::mem::take(&mut self.held_back) {
+                            let _ = self.process.stdin_sender.send(ChildStdIn::Send(frame_message(&body)));
+                        }
+                        events.push(LspEvent::Initialized);
+                    }
+                    LspRequestKind::Shutdown => (),
+                    LspRequestKind::Completion => events.push(LspEvent::Completion {
+                        request_id,
+                        items: LspCompletionItem::list_from_json(result),
+                    }),
+                    LspRequestKind::Hover => events.push(LspEvent::Hover {
+                        request_id,
+                        text: if result.is_null() {None} else {hover_text(result)},
+                    }),
+                    LspRequestKind::Definition => events.push(LspEvent::Definition {
+                        request_id,
+                        locations: LspLocation::list_from_json(result),
+                    }),
+                }
+            }
+            (None, None) => ()
+        }
+    }
+
+    fn send_request(&mut self, kind: LspRequestKind, method: &str, params: JsonValue) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        self.pending.insert(request_id, kind);
+        let mut message = json_object([
+            ("jsonrpc", json_string("2.0")),
+            ("id", JsonValue::U64(request_id)),
+            ("method", json_string(method)),
+        ]);
+        add_params(&mut message, params);
+        self.send(message, !matches!(kind, LspRequestKind::Initialize));
+        request_id
+    }
+
+    fn send_notification(&mut self, method: &str, params: JsonValue) {
+        let mut message = json_object([
+            ("jsonrpc", json_string("2.0")),
+            ("method", json_string(method)),
+        ]);
+        add_params(&mut message, params);
+        self.send(message, method != "initialized");
+    }
+
+    fn send(&mut self, message: JsonValue, wait_for_initialize: bool) {
+        let body = message.serialize_json();
+        if wait_for_initialize && !self.is_initialized {
+            self.held_back.push(body);
+            return
+        }
+        let _ = self.process.stdin_sender.send(ChildStdIn::Send(frame_message(&body)));
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let _ = self.process.stdin_sender.send(ChildStdIn::Term);
+        let _ = self.process.child.kill();
+        let _ = self.process.child.wait();
+    }
+}
+
+fn add_params(message: &mut JsonValue, params: JsonValue) {
+    if let (JsonValue::Object(message), false) = (message, params.is_null()) {
+        message.insert("params".to_string(), params);
+    }
+}
+
+fn text_document_position(uri: &str, position: LspPosition) -> JsonValue {
+    json_object([
+        ("textDocument", json_object([("uri", json_string(uri))])),
+        ("position", position.to_json()),
+    ])
+}
+
+/// A document that is open on a server. It keeps the text as the server has it, which is what
+/// the positions in the changes that are sent refer to.
+pub struct LspDocument {
+    pub uri: String,
+    version: u64,
+    text: Text,
+    edit_receiver: Receiver<Vec<Edit>>,
+}
+
+impl LspDocument {
+    /// Opens `document` on the server and starts following its edits.
+    pub fn open(client: &mut LspClient, uri: String, language_id: &str, document: &CodeDocument) -> Self {
+        let (edit_sender, edit_receiver) = mpsc::channel();
+        document.add_edit_listener(edit_sender);
+        let text = document.as_text().clone();
+        client.did_open(&uri, language_id, 0, &text.to_string());
+        Self {
+            uri,
+            version: 0,
+            text,
+            edit_receiver,
+        }
+    }
+
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+
+    /// Sends the edits made since the last call as one change of version.
+    pub fn sync(&mut self, client: &mut LspClient) {
+        let mut changes = Vec::new();
+        while let Ok(edits) = self.edit_receiver.try_recv() {
+            for edit in edits {
+                changes.push(content_change(&self.text, &edit.change));
+                self.text.apply_change(edit.change);
+            }
+        }
+        if changes.is_empty() {
+            return
+        }
+        self.version += 1;
+        client.did_change(&self.uri, self.version, changes);
+    }
+
+    pub fn close(self, client: &mut LspClient) {
+        client.did_close(&self.uri);
+    }
+}
@@ -0,0 +1,303 @@
+use {
+    crate::{
+        app::AppAction,
+        file_system::file_system::{FileSystem, OpenDocument},
+        lsp::{
+            lsp_client::{LspClient, LspDocument, LspEvent, LspServerConfig},
+            lsp_protocol::*,
+        },
+        makepad_code_editor::{
+            decoration::{Decoration, DecorationType},
+            text::Position,
+        },
+        makepad_platform::studio::JumpToFile,
+        makepad_widgets::*,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+    },
+};
+
+// build diagnostics use decoration id 0, these can be replaced without touching them
+pub const LSP_DECORATION_ID: usize = 1;
+
+/// Completions shown for the word that starts at `position` in a tab.
+pub struct LspCompletion {
+    pub tab_id: LiveId,
+    pub position: Position,
+    pub items: Vec<LspCompletionItem>,
+    /// What was typed of the word so far, which the items are filtered by.
+    pub prefix: String,
+    pub selected: usize,
+}
+
+impl LspCompletion {
+    pub fn visible_items(&self) -> Vec<&LspCompletionItem> {
+        let prefix = self.prefix.to_lowercase();
+        self.items.iter().filter( | item | item.label.to_lowercase().starts_with(&prefix)).collect()
+    }
+
+    pub fn selected_item(&self) -> Option<&LspCompletionItem> {
+        self.visible_items().get(self.selected).copied()
+    }
+}
+
+pub struct LspHover {
+    pub tab_id: LiveId,
+    /// Where the mouse was when the hover was asked for.
+    pub abs: DVec2,
+    pub text: String,
+}
+
+struct PendingRequest {
+    server_index: usize,
+    request_id: u64,
+    tab_id: LiveId,
+}
+
+/// Runs a language server for every kind of file that is open and has one configured, keeps
+/// them in sync with the open documents, and turns what they send back into decorations,
+/// completions, hovers and jumps.
+#[derive(Default)]
+pub struct LspManager {
+    root_path: PathBuf,
+    configs: Vec<LspServerConfig>,
+    clients: HashMap<usize, LspClient>,
+    // servers that could not be started or exited, they are not tried again
+    failed: HashSet<usize>,
+    documents: HashMap<LiveId, (usize, LspDocument)>,
+    pending_completion: Option<(PendingRequest, Position)>,
+    pending_hover: Option<(PendingRequest, DVec2)>,
+    pending_definition: Option<PendingRequest>,
+    pub completion: Option<LspCompletion>,
+    pub hover: Option<LspHover>,
+}
+
+impl LspManager {
+    pub fn init(&mut self, root_path: &std::path::Path, configs: Vec<LspServerConfig>) {
+        // servers answer with canonical paths, which have to be made relative to the root again
+        self.root_path = root_path.canonicalize().unwrap_or_else( | _ | root_path.to_path_buf());
+        self.configs = configs;
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, fs: &mut FileSystem) {
+        self.sync_documents(fs);
+        if let Event::Signal = event {
+            let server_indices: Vec<usize> = self.clients.keys().copied().collect();
+            for server_index in server_indices {
+                let events = self.clients.get_mut(&server_index).unwrap().poll();
+                for event in events {
+                    self.handle_lsp_event(cx, server_index, event, fs);
+                }
+            }
+        }
+    }
+
+    /// Opens the code documents that are new on their server, sends the edits made to the
+    /// others, and closes the ones that are gone.
+    pub fn sync_documents(&mut self, fs: &FileSystem) {
+        for (file_id, document) in &fs.open_documents {
+            let OpenDocument::Code(document) = document else {continue};
+            if let Some((server_index, lsp_document)) = self.documents.get_mut(file_id) {
+                if let Some(client) = self.clients.get_mut(server_index) {
+                    lsp_document.sync(client);
+                }
+                continue
+            }
+            let Some(path) = fs.file_node_id_to_path(*file_id) else {continue};
+            let Some(server_index) = self.configs.iter().position( | config | config.handles(path)) else {continue};
+            let uri = path_to_uri(&self.root_path.join(path));
+            let language_id = self.configs[server_index].language_id.clone();
+            let Some(client) = self.client(server_index) else {continue};
+            let lsp_document = LspDocument::open(client, uri, &language_id, document);
+            self.documents.insert(*file_id, (server_index, lsp_document));
+        }
+        let closed: Vec<LiveId> = self.documents.keys()
+            .filter( | file_id | !matches!(fs.open_documents.get(file_id), Some(OpenDocument::Code(_))))
+            .copied()
+            .collect();
+        for file_id in closed {
+            let (server_index, lsp_document) = self.documents.remove(&file_id).unwrap();
+            if let Some(client) = self.clients.get_mut(&server_index) {
+                lsp_document.close(client);
+            }
+        }
+    }
+
+    /// Asks for completions at `cursor` in the document of a tab, for the word that starts at
+    /// `word_start`.
+    pub fn request_completion(&mut self, fs: &FileSystem, tab_id: LiveId, cursor: Position, word_start: Position) {
+        self.completion = None;
+        let Some((server_index, uri, position)) = self.request_target(fs, tab_id, cursor) else {return};
+        let request_id = self.clients.get_mut(&server_index).unwrap().completion(&uri, position);
+        self.pending_completion = Some((PendingRequest {server_index, request_id, tab_id}, word_start));
+    }
+
+    pub fn request_hover(&mut self, fs: &FileSystem, tab_id: LiveId, position: Position, abs: DVec2) {
+        self.hover = None;
+        let Some((server_index, uri, position)) = self.request_target(fs, tab_id, position) else {return};
+        let request_id = self.clients.get_mut(&server_index).unwrap().hover(&uri, position);
+        self.pending_hover = Some((PendingRequest {server_index, request_id, tab_id}, abs));
+    }
+
+    pub fn request_definition(&mut self, fs: &FileSystem, tab_id: LiveId, position: Position) {
+        let Some((server_index, uri, position)) = self.request_target(fs, tab_id, position) else {return};
+        let request_id = self.clients.get_mut(&server_index).unwrap().definition(&uri, position);
+        self.pending_definition = Some(PendingRequest {server_index, request_id, tab_id});
+    }
+
+    pub fn close_completion(&mut self) {
+        self.completion = None;
+        self.pending_completion = None;
+    }
+
+    pub fn close_hover(&mut self) {
+        self.hover = None;
+        self.pending_hover = None;
+    }
+
+    pub fn shutdown(&mut self) {
+        for client in self.clients.values_mut() {
+            client.shutdown();
+        }
+        self.clients.clear();
+        self.documents.clear();
+    }
+
+    fn client(&mut self, server_index: usize) -> Option<&mut LspClient> {
+        if self.failed.contains(&server_index) {
+            return None
+        }
+        if !self.clients.contains_key(&server_index) {
+            let config = &self.configs[server_index];
+            match LspClient::start(config, &self.root_path) {
+                Ok(client) => {
+                    self.clients.insert(server_index, client);
+                }
+                Err(err) => {
+                    log!("Cannot start language server {}: {}", config.command, err);
+                    self.failed.insert(server_index);
+                    return None
+                }
+            }
+        }
+        self.clients.get_mut(&server_index)
+    }
+
+    // the server, uri and position to ask about `position` in the document of a tab, after
+    // bringing the server up to date with it
+    fn request_target(&mut self, fs: &FileSystem, tab_id: LiveId, position: Position) -> Option<(usize, String, LspPosition)> {
+        let file_id = fs.tab_id_to_file_node_id.get(&tab_id)?;
+        let (server_index, lsp_document) = self.documents.get_mut(file_id)?;
+        let client = self.clients.get_mut(server_index)?;
+        lsp_document.sync(client);
+        let position = LspPosition::from_position(lsp_document.text().as_lines(), position);
+        Some((*server_index, lsp_document.uri.clone(), position))
+    }
+
+    fn handle_lsp_event(&mut self, cx: &mut Cx, server_index: usize, event: LspEvent, fs: &mut FileSystem) {
+        match event {
+            LspEvent::Diagnostics {uri, diagnostics} => {
+                let Some(file_id) = self.documents.iter()
+                    .find( | (_, (index, document)) | *index == server_index && document.uri == uri)
+                    .map( | (file_id, _) | *file_id) else {return};
+                let Some(OpenDocument::Code(document)) = fs.open_documents.get_mut(&file_id) else {return};
+                let decorations: Vec<Decoration> = {
+                    let text = document.as_text();
+                    let lines = text.as_lines();
+                    diagnostics.iter().filter_map( | diagnostic | {
+                        let ty = match diagnostic.severity {
+                            LspSeverity::Error => DecorationType::Error,
+                            LspSeverity::Warning => DecorationType::Warning,
+                            LspSeverity::Information | LspSeverity::Hint => return None,
+                        };
+                        let start = diagnostic.range.start.to_position(lines);
+                        let end = diagnostic.range.end.to_position(lines);
+                        Some(Decoration::new(LSP_DECORATION_ID, start, end, ty))
+                    }).collect()
+                };
+                document.remove_decorations_with_id(LSP_DECORATION_ID);
+                for decoration in decorations {
+                    document.add_decoration(decoration);
+                }
+                cx.action(AppAction::RedrawFile(file_id));
+            }
+            LspEvent::Completion {request_id, items} => {
+                let Some((pending, word_start)) = self.pending_completion.take_if( | (pending, _) | {
+                    pending.server_index == server_index && pending.request_id == request_id
+                }) else {return};
+                if !items.is_empty() {
+                    self.completion = Some(LspCompletion {
+                        tab_id: pending.tab_id,
+                        position: word_start,
+                        items,
+                        prefix: String::new(),
+                        selected: 0,
+                    });
+                }
+                self.redraw_tab(cx, fs, pending.tab_id);
+            }
+            LspEvent::Hover {request_id, text} => {
+                let Some((pending, abs)) = self.pending_hover.take_if( | (pending, _) | {
+                    pending.server_index == server_index && pending.request_id == request_id
+                }) else {return};
+                if let Some(text) = text {
+                    self.hover = Some(LspHover {tab_id: pending.tab_id, abs, text});
+                }
+                self.redraw_tab(cx, fs, pending.tab_id);
+            }
+            LspEvent::Definition {request_id, locations} => {
+                if self.pending_definition.take_if( | pending | {
+                    pending.server_index == server_index && pending.request_id == request_id
+                }).is_none() {
+                    return
+                }
+                if let Some(jump_to) = locations.first().and_then( | location | self.location_to_jump(fs, location)) {
+                    cx.action(AppAction::JumpTo(jump_to));
+                }
+            }
+            LspEvent::Error {request_id, message} => {
+                let is_request = | pending: &PendingRequest | pending.server_index == server_index && pending.request_id == request_id;
+                if self.pending_completion.take_if( | (pending, _) | is_request(pending)).is_some() ||
+                    self.pending_hover.take_if( | (pending, _) | is_request(pending)).is_some() ||
+                    self.pending_definition.take_if( | pending | is_request(pending)).is_some() {
+                    log!("Language server request failed: {}", message);
+                }
+            }
+            LspEvent::Exited => {
+                log!("Language server {} exited", self.configs[server_index].command);
+                self.clients.remove(&server_index);
+                self.failed.insert(server_index);
+                self.documents.retain( | _, (index, _) | *index != server_index);
+            }
+            LspEvent::Initialized | LspEvent::Log(_) => ()
+        }
+    }
+
+    fn redraw_tab(&self, cx: &mut Cx, fs: &FileSystem, tab_id: LiveId) {
+        if let Some(file_id) = fs.tab_id_to_file_node_id.get(&tab_id) {
+            cx.action(AppAction::RedrawFile(*file_id));
+        }
+    }
+
+    // definitions outside of the project can't be opened
+    fn location_to_jump(&self, fs: &FileSystem, location: &LspLocation) -> Option<JumpToFile> {
+        let relative = location.path.strip_prefix(&self.root_path).ok()?;
+        let file_name = relative.to_string_lossy().replace('\\', "/");
+        let file_id = fs.path_to_file_node_id(&file_name)?;
+        let position = match fs.open_documents.get(&file_id) {
+            Some(OpenDocument::Code(document)) => location.range.start.to_position(document.as_text().as_lines()),
+            _ => {
+                let text = std::fs::read_to_string(&location.path).ok()?;
+                let lines: Vec<String> = text.lines().map( | line | line.to_string()).collect();
+                location.range.start.to_position(&lines)
+            }
+        };
+        Some(JumpToFile {
+            file_name,
+            line: position.line_index as u32,
+            column: position.byte_index as u32,
+        })
+    }
+}
@@ -0,0 +1,333 @@
+use {
+    crate::{
+        makepad_code_editor::text::{Change, Position, Text},
+        makepad_micro_serde::*,
+    },
+    std::path::{Path, PathBuf},
+};
+
+// the Language Server Protocol messages studio sends and understands, as JSON-RPC over stdio.
+// positions in LSP count lines and UTF-16 code units, where the editor counts lines and bytes.
+
+/// Puts the header LSP expects in front of a message body.
+pub fn frame_message(body: &str) -> String {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+}
+
+/// Splits what a server writes to its stdout back up into message bodies.
+#[derive(Default)]
+pub struct MessageDecoder {
+    buffer: Vec<u8>,
+}
+
+impl MessageDecoder {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// The body of the next message that came in completely, if any.
+    pub fn next_message(&mut self) -> Option<String> {
+        loop {
+            let header_end = self.buffer.windows(4).position( | window | window == b"\r\n\r\n")?;
+            let body_start = header_end + 4;
+            let header = String::from_utf8_lossy(&self.buffer[..header_end]);
+            let content_length = header.lines().find_map( | line | {
+                let (name, value) = line.split_once(':')?;
+                if !name.trim().eq_ignore_ascii_case("content-length") {
+                    return None
+                }
+                value.trim().parse::<usize>().ok()
+            });
+            let Some(content_length) = content_length else {
+                // there is no telling where a body without a length ends, so skip the header
+                self.buffer.drain(..body_start);
+                continue
+            };
+            let Some(body_end) = body_start.checked_add(content_length) else {
+                // no body is that long, so the length is garbage and so is the message
+                self.buffer.drain(..body_start);
+                continue
+            };
+            if self.buffer.len() < body_end {
+                return None
+            }
+            let body = String::from_utf8_lossy(&self.buffer[body_start..body_end]).into_owned();
+            self.buffer.drain(..body_end);
+            return Some(body)
+        }
+    }
+}
+
+pub fn json_object<const N: usize>(fields: [(&str, JsonValue); N]) -> JsonValue {
+    JsonValue::Object(fields.into_iter().map( | (key, value) | (key.to_string(), value)).collect())
+}
+
+pub fn json_string(value: impl Into<String>) -> JsonValue {
+    JsonValue::String(value.into())
+}
+
+pub fn json_u64(value: usize) -> JsonValue {
+    JsonValue::U64(value as u64)
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LspPosition {
+    pub line: usize,
+    /// In UTF-16 code units.
+    pub character: usize,
+}
+
+impl LspPosition {
+    pub fn from_position(lines: &[String], position: Position) -> Self {
+        let line = lines.get(position.line_index).map_or("", | line | line.as_str());
+        Self {
+            line: position.line_index,
+            character: line
+                .char_indices()
+                .take_while( | (byte_index, _) | *byte_index < position.byte_index)
+                .map( | (_, char) | char.len_utf16())
+                .sum(),
+        }
+    }
+
+    /// The position in `lines`, clamped to the text, since the server can be behind on edits.
+    pub fn to_position(self, lines: &[String]) -> Position {
+        let Some(line) = lines.get(self.line) else {
+            let line_index = lines.len().saturating_sub(1);
+            return Position {
+                line_index,
+                byte_index: lines.get(line_index).map_or(0, | line | line.len()),
+            }
+        };
+        let mut character = 0;
+        for (byte_index, char) in line.char_indices() {
+            if character >= self.character {
+                return Position {line_index: self.line, byte_index}
+            }
+            character += char.len_utf16();
+        }
+        Position {line_index: self.line, byte_index: line.len()}
+    }
+
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        Some(Self {
+            line: value.key("line")?.u64()? as usize,
+            character: value.key("character")?.u64()? as usize,
+        })
+    }
+
+    pub fn to_json(self) -> JsonValue {
+        json_object([("line", json_u64(self.line)), ("character", json_u64(self.character))])
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LspRange {
+    pub start: LspPosition,
+    pub end: LspPosition,
+}
+
+impl LspRange {
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        Some(Self {
+            start: LspPosition::from_json(value.key("start")?)?,
+            end: LspPosition::from_json(value.key("end")?)?,
+        })
+    }
+
+    pub fn to_json(self) -> JsonValue {
+        json_object([("start", self.start.to_json()), ("end", self.end.to_json())])
+    }
+}
+
+/// The `contentChanges` entry for `change`, which is about to be applied to `text`.
+pub fn content_change(text: &Text, change: &Change) -> JsonValue {
+    let lines = text.as_lines();
+    let (start, end, new_text) = match change {
+        Change::Insert(position, inserted) => (*position, *position, inserted.to_string()),
+        Change::Delete(start, length) => (*start, *start + *length, String::new()),
+    };
+    let range = LspRange {
+        start: LspPosition::from_position(lines, start),
+        end: LspPosition::from_position(lines, end),
+    };
+    json_object([("range", range.to_json()), ("text", json_string(new_text))])
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LspSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Clone, Debug)]
+pub struct LspDiagnostic {
+    pub range: LspRange,
+    pub severity: LspSeverity,
+    pub message: String,
+}
+
+impl LspDiagnostic {
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        Some(Self {
+            range: LspRange::from_json(value.key("range")?)?,
+            // a diagnostic without a severity is up to the client, and most of them are errors
+            severity: match value.key("severity").and_then( | severity | severity.u64()) {
+                Some(2) => LspSeverity::Warning,
+                Some(3) => LspSeverity::Information,
+                Some(4) => LspSeverity::Hint,
+                _ => LspSeverity::Error,
+            },
+            message: value.key("message")?.string()?.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LspCompletionItem {
+    pub label: String,
+    pub detail: Option<String>,
+    /// The text to put in place of the word being completed.
+    pub insert_text: String,
+}
+
+impl LspCompletionItem {
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        let label = value.key("label")?.string()?.clone();
+        let insert_text = value.key("textEdit")
+            .and_then( | edit | edit.key("newText"))
+            .or_else( | | value.key("insertText"))
+            .and_then( | text | text.string())
+            .cloned()
+            .unwrap_or_else( | | label.clone());
+        let is_snippet = value.key("insertTextFormat").and_then( | format | format.u64()) == Some(2);
+        Some(Self {
+            detail: value.key("detail").and_then( | detail | detail.string()).cloned(),
+            insert_text: if is_snippet {strip_snippet(&insert_text)} else {insert_text},
+            label,
+        })
+    }
+
+    /// The items of a completion result, which is either a list of them or an object that has one.
+    pub fn list_from_json(result: &JsonValue) -> Vec<Self> {
+        let items = result.array().or_else( | | result.key("items").and_then( | items | items.array()));
+        items.map_or(Vec::new(), | items | items.iter().filter_map(Self::from_json).collect())
+    }
+}
+
+/// Turns a snippet such as `push(${1:value})$0` into the text it stands for, `push(value)`.
+fn strip_snippet(snippet: &str) -> String {
+    let mut text = String::new();
+    let mut chars = snippet.chars().peekable();
+    let mut placeholder_depth = 0;
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => text.extend(chars.next()),
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                // skip the tab stop number, keep the placeholder
+                while chars.next_if( | char | char.is_ascii_digit()).is_some() {}
+                chars.next_if_eq(&':');
+                placeholder_depth += 1;
+            }
+            '$' if chars.peek().is_some_and( | char | char.is_ascii_digit()) => {
+                while chars.next_if( | char | char.is_ascii_digit()).is_some() {}
+            }
+            '}' if placeholder_depth > 0 => placeholder_depth -= 1,
+            _ => text.push(char),
+        }
+    }
+    text
+}
+
+/// The text of a hover result, which can be plain text, markdown or a list of either.
+pub fn hover_text(result: &JsonValue) -> Option<String> {
+    fn content_text(content: &JsonValue) -> Option<String> {
+        if let Some(text) = content.string() {
+            return Some(text.clone())
+        }
+        if let Some(contents) = content.array() {
+            let texts: Vec<String> = contents.iter().filter_map(content_text).collect();
+            return Some(texts.join("\n\n"))
+        }
+        content.key("value").and_then( | value | value.string()).cloned()
+    }
+    let text = content_text(result.key("contents")?)?;
+    // tooltips show plain text, so the fences around markdown code blocks go
+    let lines: Vec<&str> = text.lines().filter( | line | !line.trim_start().starts_with("```")).collect();
+    let text = lines.join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return None
+    }
+    Some(text.to_string())
+}
+
+#[derive(Clone, Debug)]
+pub struct LspLocation {
+    pub path: PathBuf,
+    pub range: LspRange,
+}
+
+impl LspLocation {
+    pub fn from_json(value: &JsonValue) -> Option<Self> {
+        // either a Location or a LocationLink
+        let (uri, range) = match value.key("uri") {
+            Some(uri) => (uri, value.key("range")?),
+            None => (value.key("targetUri")?, value.key("targetSelectionRange")?),
+        };
+        Some(Self {
+            path: uri_to_path(uri.string()?)?,
+            range: LspRange::from_json(range)?,
+        })
+    }
+
+    pub fn list_from_json(result: &JsonValue) -> Vec<Self> {
+        match result.array() {
+            Some(locations) => locations.iter().filter_map(Self::from_json).collect(),
+            None => Self::from_json(result).into_iter().collect(),
+        }
+    }
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'/' | b'-' | b'.' | b'_' | b'~' => uri.push(byte as char),
+            // keeps windows drive letters as they are
+            b':' if uri.len() == 9 => uri.push(':'),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' && tail.len() >= 2 {
+            if let Ok(decoded) = u8::from_str_radix(std::str::from_utf8(&tail[..2]).ok()?, 16) {
+                bytes.push(decoded);
+                rest = &tail[2..];
+                continue
+            }
+        }
+        bytes.push(byte);
+        rest = tail;
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `/C:/dir` on windows
+    if path.as_bytes().get(2) == Some(&b':') {
+        return Some(PathBuf::from(&path[1..]))
+    }
+    Some(PathBuf::from(path))
+}
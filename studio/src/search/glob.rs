@@ -0,0 +1,116 @@
+// Include and exclude patterns for the project search, matched against paths relative to the
+// project root with `/` as separator.
+
+/// A single pattern. `*` matches anything but a `/`, `**` matches anything including `/`, `?`
+/// matches one character and `{a,b}` matches either alternative.
+/// A pattern without a `/` is matched against each part of the path, so `*.rs` finds rust files
+/// anywhere and `examples` everything inside a folder of that name. A pattern with a `/` is
+/// matched against the path and each of its folders, from the root.
+#[derive(Clone, Debug)]
+pub struct Glob {
+    alternatives: Vec<Vec<char>>,
+    is_anchored: bool,
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.trim().trim_start_matches("./").trim_end_matches('/');
+        Self {
+            alternatives: expand_braces(pattern).into_iter().map( | alternative | alternative.chars().collect()).collect(),
+            is_anchored: pattern.contains('/'),
+        }
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        let matches = | candidate: &str | {
+            let candidate: Vec<char> = candidate.chars().collect();
+            self.alternatives.iter().any( | alternative | match_chars(alternative, &candidate))
+        };
+        if self.is_anchored {
+            path.match_indices('/').any( | (index, _) | matches(&path[..index])) || matches(path)
+        }
+        else {
+            path.split('/').any(matches)
+        }
+    }
+}
+
+/// A comma separated list of include patterns and one of exclude patterns. A path passes if it
+/// matches any include pattern, or there are none, and no exclude pattern.
+#[derive(Clone, Debug, Default)]
+pub struct PathFilter {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
+
+impl PathFilter {
+    pub fn new(include: &str, exclude: &str) -> Self {
+        Self {
+            include: split_patterns(include).map(Glob::new).collect(),
+            exclude: split_patterns(exclude).map(Glob::new).collect(),
+        }
+    }
+
+    pub fn accepts(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any( | glob | glob.matches(path)))
+            && !self.exclude.iter().any( | glob | glob.matches(path))
+    }
+}
+
+// splits at the commas that aren't inside braces
+fn split_patterns(patterns: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    patterns.split(move | c | {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => ()
+        }
+        c == ',' && depth == 0
+    }).map( | pattern | pattern.trim()).filter( | pattern | !pattern.is_empty())
+}
+
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(start) = pattern.find('{') else {
+        return vec![pattern.to_string()]
+    };
+    let Some(len) = pattern[start..].find('}') else {
+        return vec![pattern.to_string()]
+    };
+    let end = start + len;
+    let mut expanded = Vec::new();
+    for alternative in pattern[start + 1..end].split(',') {
+        for rest in expand_braces(&pattern[end + 1..]) {
+            expanded.push(format!("{}{}{}", &pattern[..start], alternative, rest));
+        }
+    }
+    expanded
+}
+
+fn match_chars(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // `**/` also matches no folders at all
+            if let ['/', after_slash @ ..] = rest {
+                if match_chars(after_slash, path) {
+                    return true
+                }
+            }
+            (0..=path.len()).any( | index | match_chars(rest, &path[index..]))
+        }
+        ['*', rest @ ..] => {
+            for index in 0..=path.len() {
+                if match_chars(rest, &path[index..]) {
+                    return true
+                }
+                if path.get(index) == Some(&'/') {
+                    break
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => matches!(path.first(), Some(c) if *c != '/') && match_chars(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && match_chars(rest, &path[1..]),
+    }
+}
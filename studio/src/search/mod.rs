@@ -0,0 +1,3 @@
+pub mod glob;
+pub mod search_manager;
+pub mod search_view;
@@ -0,0 +1,281 @@
+use {
+    crate::{
+        app::AppAction,
+        file_system::file_system::{FileSystem, OpenDocument},
+        makepad_code_editor::{
+            regex::{Regex, RegexError},
+            SearchQuery,
+        },
+        makepad_widgets::*,
+        search::glob::PathFilter,
+    },
+    std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    },
+};
+
+// files larger than this are skipped, they are hardly ever source code
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub line_index: usize,
+    pub start: usize,
+    pub end: usize,
+    pub line: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchFile {
+    pub path: String,
+    pub hits: Vec<SearchHit>,
+}
+
+pub enum SearchMessage {
+    File {generation: u64, file: SearchFile},
+    Done {generation: u64},
+    Replaced {path: String, data: String, count: usize},
+}
+
+/// Searches all files of the project on a thread of its own, and replaces matches across them.
+#[derive(Default)]
+pub struct SearchManager {
+    root_path: PathBuf,
+    pub files: Vec<SearchFile>,
+    pub hit_count: usize,
+    pub is_searching: bool,
+    error: Option<String>,
+    replaced_count: Option<usize>,
+    query: Option<(SearchQuery, Regex)>,
+    /// The query and replacement of the last `replace_all`, for the files it replaces on disk.
+    replacing: Option<(SearchQuery, Regex, String)>,
+    filter: PathFilter,
+    generation: u64,
+    cancel: Arc<AtomicBool>,
+    recv_message: ToUIReceiver<SearchMessage>,
+}
+
+impl SearchManager {
+    pub fn init(&mut self, path: &Path) {
+        self.root_path = path.to_path_buf();
+    }
+
+    /// Starts searching with `query` in the files that `include` and `exclude` let through, which
+    /// are comma separated glob patterns. Results come in while the search runs.
+    pub fn start_search(&mut self, cx: &mut Cx, fs: &FileSystem, query: SearchQuery, include: &str, exclude: &str) -> Result<(), RegexError> {
+        self.stop_search();
+        self.files.clear();
+        self.hit_count = 0;
+        self.error = None;
+        self.replaced_count = None;
+        self.query = None;
+        cx.action(AppAction::RedrawSearch);
+        if query.text.is_empty() {
+            return Ok(())
+        }
+        let regex = match query.compile() {
+            Ok(regex) => regex,
+            Err(err) => {
+                self.error = Some(err.to_string());
+                return Err(err)
+            }
+        };
+        self.filter = PathFilter::new(include, exclude);
+        self.query = Some((query, regex.clone()));
+        self.is_searching = true;
+
+        let (paths, open_texts) = self.collect_paths(fs);
+        let root_path = self.root_path.clone();
+        let generation = self.generation;
+        let cancel = self.cancel.clone();
+        let sender = self.recv_message.sender();
+        std::thread::spawn(move || {
+            for path in paths {
+                if cancel.load(Ordering::Relaxed) {
+                    return
+                }
+                let text = match open_texts.get(&path) {
+                    Some(text) => text.clone(),
+                    None => match read_text_file(&root_path.join(&path)) {
+                        Some(text) => text,
+                        None => continue
+                    }
+                };
+                let hits = find_hits(&regex, &text);
+                if !hits.is_empty() {
+                    let _ = sender.send(SearchMessage::File {generation, file: SearchFile {path, hits}});
+                }
+            }
+            let _ = sender.send(SearchMessage::Done {generation});
+        });
+        Ok(())
+    }
+
+    pub fn stop_search(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.generation += 1;
+        self.is_searching = false;
+    }
+
+    /// Replaces every match in the files that were found. Open documents are changed in place,
+    /// the others on disk. Either way the new contents are saved as patches.
+    pub fn replace_all(&mut self, cx: &mut Cx, fs: &mut FileSystem, replacement: &str) {
+        let Some((query, regex)) = self.query.clone() else {
+            return
+        };
+        self.stop_search();
+        let paths: Vec<String> = self.files.drain(..).map( | file | file.path).collect();
+        self.hit_count = 0;
+        let mut closed_paths = Vec::new();
+        let mut count = 0;
+        for path in paths {
+            let Some(file_id) = fs.path_to_file_node_id(&path) else {continue};
+            match replace_in_document(cx, fs, file_id, &query, &regex, replacement) {
+                Some(replaced) => count += replaced,
+                None => closed_paths.push(path)
+            }
+        }
+        self.replaced_count = Some(count);
+        self.replacing = Some((query.clone(), regex.clone(), replacement.to_string()));
+        cx.action(AppAction::RedrawSearch);
+        if closed_paths.is_empty() {
+            return
+        }
+        let root_path = self.root_path.clone();
+        let replacement = replacement.to_string();
+        let sender = self.recv_message.sender();
+        std::thread::spawn(move || {
+            for path in closed_paths {
+                let Some(text) = read_text_file(&root_path.join(&path)) else {continue};
+                let (data, count) = query.replace_all(&regex, &text, &replacement);
+                if count > 0 {
+                    let _ = sender.send(SearchMessage::Replaced {path, data, count});
+                }
+            }
+        });
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, fs: &mut FileSystem) {
+        if let Event::Signal = event {
+            let mut changed = false;
+            while let Ok(message) = self.recv_message.try_recv() {
+                match message {
+                    SearchMessage::File {generation, file} if generation == self.generation => {
+                        self.hit_count += file.hits.len();
+                        self.files.push(file);
+                        changed = true;
+                    }
+                    SearchMessage::Done {generation} if generation == self.generation => {
+                        self.is_searching = false;
+                        changed = true;
+                    }
+                    SearchMessage::Replaced {path, data, count} => {
+                        let Some(file_id) = fs.path_to_file_node_id(&path) else {continue};
+                        // The file can have been opened while we replaced it on disk. Its
+                        // document is newer than what we read, so we replace in there instead.
+                        let count = match &self.replacing {
+                            Some((query, regex, replacement)) => replace_in_document(cx, fs, file_id, query, regex, replacement),
+                            None => None
+                        }.unwrap_or_else( || {
+                            fs.request_save_file_data(file_id, data, true);
+                            count
+                        });
+                        *self.replaced_count.get_or_insert(0) += count;
+                        changed = true;
+                    }
+                    // left over from a search that was stopped
+                    SearchMessage::File {..} | SearchMessage::Done {..} => ()
+                }
+            }
+            if changed {
+                cx.action(AppAction::RedrawSearch);
+            }
+        }
+    }
+
+    /// A line about the state of the search to show above the results.
+    pub fn status(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone()
+        }
+        if let Some(count) = self.replaced_count {
+            return format!("Replaced {} matches", count)
+        }
+        if self.query.is_none() {
+            return String::new()
+        }
+        let searching = if self.is_searching {", searching..."} else {""};
+        format!("{} results in {} files{}", self.hit_count, self.files.len(), searching)
+    }
+
+    // The files to search sorted by path, along with the text of the ones that are open, which
+    // can differ from what is on disk.
+    fn collect_paths(&self, fs: &FileSystem) -> (Vec<String>, HashMap<String, String>) {
+        let mut paths: Vec<(String, LiveId)> = fs.path_to_file_node_id.iter()
+            .filter( | (path, file_id) | {
+                fs.file_nodes.get(file_id).is_some_and( | node | node.is_file()) && self.filter.accepts(path)
+            })
+            .map( | (path, file_id) | (path.clone(), *file_id))
+            .collect();
+        paths.sort();
+        let mut open_texts = HashMap::new();
+        for (path, file_id) in &paths {
+            if let Some(OpenDocument::Code(doc)) = fs.open_documents.get(file_id) {
+                open_texts.insert(path.clone(), doc.as_text().to_string());
+            }
+        }
+        (paths.into_iter().map( | (path, _) | path).collect(), open_texts)
+    }
+}
+
+/// Replaces every match in the document of `file_id` and saves it, returning the number of
+/// matches. Returns `None` if the file isn't open.
+fn replace_in_document(cx: &mut Cx, fs: &mut FileSystem, file_id: LiveId, query: &SearchQuery, regex: &Regex, replacement: &str) -> Option<usize> {
+    let Some(OpenDocument::Code(doc)) = fs.open_documents.get(&file_id) else {
+        return None
+    };
+    let (data, count) = query.replace_all(regex, &doc.as_text().to_string(), replacement);
+    if count > 0 {
+        doc.replace(data.into());
+        fs.request_save_file_for_file_node_id(file_id, true);
+        cx.action(AppAction::RedrawFile(file_id));
+    }
+    Some(count)
+}
+
+fn read_text_file(path: &Path) -> Option<String> {
+    if std::fs::metadata(path).ok()?.len() > MAX_FILE_SIZE {
+        return None
+    }
+    // binary files don't decode as utf8
+    let text = String::from_utf8(std::fs::read(path).ok()?).ok()?;
+    if text.contains('\0') {
+        return None
+    }
+    Some(text)
+}
+
+fn find_hits(regex: &Regex, text: &str) -> Vec<SearchHit> {
+    let mut hits = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        for captures in regex.captures_iter(line) {
+            let range = captures.range();
+            if range.is_empty() {
+                continue
+            }
+            hits.push(SearchHit {
+                line_index,
+                start: range.start,
+                end: range.end,
+                line: line.to_string(),
+            });
+        }
+    }
+    hits
+}
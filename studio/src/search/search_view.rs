@@ -0,0 +1,252 @@
+use {
+    crate::{
+        app::{AppAction, AppData},
+        makepad_code_editor::SearchQuery,
+        makepad_platform::studio::JumpToFile,
+        makepad_widgets::*,
+        search::search_manager::SearchManager,
+    },
+};
+
+live_design!{
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::theme::*;
+
+    SearchItem = <View> {
+        width: Fill, height: Fit,
+        padding: <THEME_MSPACE_H_2> {}
+        spacing: (THEME_SPACE_2)
+        show_bg: true,
+        draw_bg: {
+            instance is_even: 0.0
+            fn pixel(self) -> vec4 {
+                return mix(
+                    THEME_COLOR_BG_EVEN,
+                    THEME_COLOR_BG_ODD,
+                    self.is_even
+                );
+            }
+        }
+    }
+
+    SearchToggle = <CheckBoxCustom> {
+        padding: 0.
+        text: ""
+        draw_check: { check_type: None }
+        draw_icon: {
+            color: (THEME_COLOR_D_3),
+            color_active: (THEME_COLOR_U_5),
+        }
+    }
+
+    pub SearchView = {{SearchView}}{
+        width: Fill, height: Fill,
+        flow: Down,
+        show_bg: true,
+        draw_bg: {color: (THEME_COLOR_BG_CONTAINER)}
+
+        <DockToolbar> {
+            content = {
+                spacing: (THEME_SPACE_2)
+                align: { y: 0.5 }
+                query = <TextInput> {
+                    width: Fill,
+                    empty_message: "Search",
+                }
+                case_sensitive = <SearchToggle> {
+                    icon_walk: {width: 14.}
+                    draw_icon: {
+                        svg_file: dep("crate://self/resources/icons/icon_search_case_sensitive.svg"),
+                    }
+                }
+                whole_word = <SearchToggle> {
+                    icon_walk: {width: 16.}
+                    draw_icon: {
+                        svg_file: dep("crate://self/resources/icons/icon_search_full_word.svg"),
+                    }
+                }
+                regex = <SearchToggle> {
+                    icon_walk: {width: 12.}
+                    draw_icon: {
+                        svg_file: dep("crate://self/resources/icons/icon_search_regex.svg"),
+                    }
+                }
+            }
+        }
+        <View> {
+            width: Fill, height: Fit,
+            flow: Down,
+            padding: <THEME_MSPACE_2> {}
+            spacing: (THEME_SPACE_1)
+            <View> {
+                width: Fill, height: Fit,
+                spacing: (THEME_SPACE_2)
+                align: { y: 0.5 }
+                replace = <TextInput> {
+                    width: Fill,
+                    empty_message: "Replace",
+                }
+                replace_all = <ButtonFlat> { width: Fit, text: "Replace all" }
+            }
+            include = <TextInput> {
+                width: Fill,
+                empty_message: "Files to include, e.g. *.rs, src/**",
+            }
+            exclude = <TextInput> {
+                width: Fill,
+                empty_message: "Files to exclude, e.g. target, *.lock",
+            }
+            status = <Label> {
+                width: Fill,
+                draw_text: {color: (THEME_COLOR_TEXT_META)}
+                text: ""
+            }
+        }
+        list = <PortalList> {
+            height: Fill, width: Fill,
+            flow: Down,
+            grab_key_focus: false,
+            drag_scrolling: false,
+
+            SearchFile = <SearchItem> {
+                padding: <THEME_MSPACE_2> {}
+                path = <Label> {
+                    width: Fill,
+                    draw_text: {wrap: Ellipsis}
+                    text: ""
+                }
+                count = <Label> {
+                    draw_text: {color: (THEME_COLOR_TEXT_META)}
+                    text: ""
+                }
+            }
+            SearchHit = <SearchItem> {
+                cursor: Hand,
+                padding: { left: 20., top: (THEME_SPACE_1), bottom: (THEME_SPACE_1), right: (THEME_SPACE_2) }
+                line_number = <Label> {
+                    width: 40.,
+                    draw_text: {color: (THEME_COLOR_TEXT_META)}
+                    text: ""
+                }
+                line = <Label> {
+                    width: Fill,
+                    draw_text: {wrap: Ellipsis}
+                    text: ""
+                }
+            }
+            Empty = <SearchItem> {
+                height: 25.,
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct SearchView{
+    #[deref] view: View,
+    // every row of the list, as the index of a file and the index of one of its hits, if any
+    #[rust] rows: Vec<(usize, Option<usize>)>,
+}
+
+impl SearchView{
+    fn draw_results(&mut self, cx: &mut Cx2d, list: &mut PortalList, search_manager: &SearchManager){
+        self.rows.clear();
+        for (file_index, file) in search_manager.files.iter().enumerate() {
+            self.rows.push((file_index, None));
+            self.rows.extend((0..file.hits.len()).map( | hit_index | (file_index, Some(hit_index))));
+        }
+        list.set_item_range(cx, 0, self.rows.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let is_even = if item_id & 1 == 0 {1.0} else {0.0};
+            let Some((file_index, hit_index)) = self.rows.get(item_id).copied() else {
+                let item = list.item(cx, item_id, live_id!(Empty));
+                item.apply_over(cx, live!{draw_bg: {is_even: (is_even)}});
+                item.draw_all(cx, &mut Scope::empty());
+                continue
+            };
+            let file = &search_manager.files[file_index];
+            let item = match hit_index {
+                Some(hit_index) => {
+                    let hit = &file.hits[hit_index];
+                    let item = list.item(cx, item_id, live_id!(SearchHit));
+                    item.label(id!(line_number)).set_text(cx, &format!("{}", hit.line_index + 1));
+                    item.label(id!(line)).set_text(cx, hit.line.trim());
+                    item
+                }
+                None => {
+                    let item = list.item(cx, item_id, live_id!(SearchFile));
+                    item.label(id!(path)).set_text(cx, &file.path);
+                    item.label(id!(count)).set_text(cx, &format!("{}", file.hits.len()));
+                    item
+                }
+            };
+            item.apply_over(cx, live!{draw_bg: {is_even: (is_even)}});
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    fn restart_search(&mut self, cx: &mut Cx, data: &mut AppData){
+        let query = SearchQuery::new(self.view.text_input(id!(query)).text())
+            .with_ignore_case(!self.view.check_box(id!(case_sensitive)).selected(cx))
+            .with_whole_word(self.view.check_box(id!(whole_word)).selected(cx))
+            .with_regex(self.view.check_box(id!(regex)).selected(cx));
+        let include = self.view.text_input(id!(include)).text();
+        let exclude = self.view.text_input(id!(exclude)).text();
+        // an invalid regex shows up in the status line
+        let _ = data.search_manager.start_search(cx, &data.file_system, query, &include, &exclude);
+    }
+}
+
+impl WidgetMatchEvent for SearchView{
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope){
+        let data = scope.data.get_mut::<AppData>().unwrap();
+        if self.view.text_input(id!(query)).changed(actions).is_some() ||
+            self.view.text_input(id!(include)).changed(actions).is_some() ||
+            self.view.text_input(id!(exclude)).changed(actions).is_some() ||
+            self.view.check_box(id!(case_sensitive)).changed(actions).is_some() ||
+            self.view.check_box(id!(whole_word)).changed(actions).is_some() ||
+            self.view.check_box(id!(regex)).changed(actions).is_some() {
+            self.restart_search(cx, data);
+        }
+        if self.view.button(id!(replace_all)).clicked(actions) {
+            let replacement = self.view.text_input(id!(replace)).text();
+            data.search_manager.replace_all(cx, &mut data.file_system, &replacement);
+        }
+        let list = self.view.portal_list(id!(list));
+        for (item_id, item) in list.items_with_actions(actions) {
+            if item.as_view().finger_up(actions).is_none() {
+                continue
+            }
+            let Some((file_index, Some(hit_index))) = self.rows.get(item_id).copied() else {
+                continue
+            };
+            if let Some(file) = data.search_manager.files.get(file_index) {
+                let hit = &file.hits[hit_index];
+                cx.action(AppAction::JumpTo(JumpToFile{
+                    file_name: file.path.clone(),
+                    line: hit.line_index as u32,
+                    column: hit.start as u32
+                }));
+            }
+        }
+    }
+}
+
+impl Widget for SearchView {
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope:&mut Scope, walk:Walk)->DrawStep{
+        let status = scope.data.get::<AppData>().unwrap().search_manager.status();
+        self.view.label(id!(status)).set_text(cx, &status);
+        while let Some(step) = self.view.draw_walk(cx, scope, walk).step(){
+            if let Some(mut list) = step.as_portal_list().borrow_mut(){
+                self.draw_results(cx, &mut list, &scope.data.get::<AppData>().unwrap().search_manager)
+            }
+        }
+        DrawStep::done()
+    }
+
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope){
+        self.widget_match_event(cx, event, scope);
+        self.view.handle_event(cx, event, scope);
+    }
+}
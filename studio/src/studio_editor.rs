@@ -1,9 +1,8 @@
-
 use {
     crate::{
         app::{AppData},
         makepad_widgets::*,
-        makepad_code_editor::CodeEditor,
+        makepad_code_editor::{CodeEditor, CodeSession, code_editor::CodeEditorAction, text::Position},
         file_system::file_system::EditSession,
     },
     std::{
@@ -12,17 +11,260 @@
 };
 
 live_design!{
+    use link::shaders::*;
+    use link::widgets::*;
+    use link::theme::*;
     use makepad_code_editor::code_editor::CodeEditor;
-    
+
     pub StudioCodeEditor = {{StudioCodeEditor}}{
         editor: <CodeEditor>{
         }
+        hover_tooltip: <Tooltip>{}
+        completion_width: 360.
+        draw_completion_bg: {color: (THEME_COLOR_BG_APP)}
+        draw_completion_selected: {color: (THEME_COLOR_CTRL_SELECTED)}
+        draw_completion_text: {
+            text_style: <THEME_FONT_CODE> {}
+            color: (THEME_COLOR_TEXT_DEFAULT)
+        }
+        draw_completion_detail: {
+            text_style: <THEME_FONT_CODE> {}
+            color: (THEME_COLOR_TEXT_META)
+        }
     }
-} 
- 
-#[derive(Live, LiveHook, Widget)] 
+}
+
+// the most completions shown at once, the list scrolls with the selection
+const COMPLETION_ROWS: usize = 12;
+
+#[derive(Live, LiveHook, Widget)]
 pub struct StudioCodeEditor{
-    #[wrap] #[live] pub editor: CodeEditor
+    #[wrap] #[live] pub editor: CodeEditor,
+    #[live] hover_tooltip: Tooltip,
+    #[rust] is_hover_shown: bool,
+    #[rust] hover_timer: Timer,
+    #[rust] hover_abs: DVec2,
+    #[rust(DrawList2d::new(cx))] completion_list: DrawList2d,
+    #[live] completion_width: f64,
+    #[live] draw_completion_bg: DrawColor,
+    #[live] draw_completion_selected: DrawColor,
+    #[live] draw_completion_text: DrawText,
+    #[live] draw_completion_detail: DrawText,
+}
+
+// the cursor, and the start of the identifier it is at the end of
+struct CursorWord {
+    cursor: Position,
+    word_start: Position,
+    // whether the word comes right after a `.` or `::`, which is where completions are asked for
+    is_after_trigger: bool,
+}
+
+impl CursorWord {
+    fn new(session: &CodeSession) -> Option<Self> {
+        let cursor = session.selections().last()?.cursor.position;
+        let text = session.document().as_text();
+        let line = text.as_lines().get(cursor.line_index)?;
+        let before = line.get(..cursor.byte_index)?;
+        let word_len: usize = before
+            .chars()
+            .rev()
+            .take_while( | char | char.is_alphanumeric() || *char == '_')
+            .map( | char | char.len_utf8())
+            .sum();
+        let before_word = &before[..before.len() - word_len];
+        Some(Self {
+            cursor,
+            word_start: Position {
+                line_index: cursor.line_index,
+                byte_index: cursor.byte_index - word_len,
+            },
+            is_after_trigger: before_word.ends_with('.') || before_word.ends_with("::"),
+        })
+    }
+
+    fn prefix<'a>(&self, line: &'a str) -> &'a str {
+        &line[self.word_start.byte_index..self.cursor.byte_index]
+    }
+}
+
+impl StudioCodeEditor{
+    // keys for the language server features, which are handled before the editor sees them
+    fn handle_lsp_key(&mut self, cx: &mut Cx, key_event: &KeyEvent, tab_id: LiveId, data: &mut AppData) -> LspKey {
+        let is_completing = data.lsp_manager.completion.as_ref().is_some_and( | completion | completion.tab_id == tab_id);
+        match key_event.key_code {
+            KeyCode::Space if key_event.modifiers.control => {
+                let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return LspKey::Ignored};
+                if let Some(word) = CursorWord::new(session) {
+                    data.lsp_manager.request_completion(&data.file_system, tab_id, word.cursor, word.word_start);
+                }
+                LspKey::Handled
+            }
+            KeyCode::F12 => {
+                let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return LspKey::Ignored};
+                if let Some(word) = CursorWord::new(session) {
+                    data.lsp_manager.request_definition(&data.file_system, tab_id, word.cursor);
+                }
+                LspKey::Handled
+            }
+            KeyCode::ArrowUp | KeyCode::ArrowDown if is_completing => {
+                let completion = data.lsp_manager.completion.as_mut().unwrap();
+                let count = completion.visible_items().len().max(1);
+                completion.selected = if key_event.key_code == KeyCode::ArrowUp {
+                    (completion.selected + count - 1) % count
+                } else {
+                    (completion.selected + 1) % count
+                };
+                self.completion_list.redraw(cx);
+                LspKey::Handled
+            }
+            KeyCode::ReturnKey | KeyCode::Tab if is_completing => {
+                let completion = data.lsp_manager.completion.take().unwrap();
+                data.lsp_manager.close_completion();
+                self.completion_list.redraw(cx);
+                let Some(item) = completion.selected_item() else {return LspKey::Handled};
+                let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return LspKey::Handled};
+                let Some(word) = CursorWord::new(session) else {return LspKey::Handled};
+                let prefix = word.prefix(&session.document().as_text().as_lines()[word.cursor.line_index]).to_string();
+                session.replace_before_cursor(&prefix, item.insert_text.as_str().into());
+                data.file_system.handle_sessions();
+                self.editor.redraw(cx);
+                LspKey::Edited
+            }
+            KeyCode::Escape if is_completing => {
+                data.lsp_manager.close_completion();
+                self.completion_list.redraw(cx);
+                LspKey::Handled
+            }
+            _ => LspKey::Ignored
+        }
+    }
+
+    // keeps an open completion in line with the word at the cursor, or closes it once the cursor
+    // has left that word
+    fn update_completion(&mut self, cx: &Cx, tab_id: LiveId, data: &mut AppData) {
+        let Some(completion) = data.lsp_manager.completion.as_mut().filter( | completion | completion.tab_id == tab_id) else {return};
+        let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return};
+        let prefix = CursorWord::new(session)
+            .filter( | word | word.word_start == completion.position && self.editor.has_key_focus(cx))
+            .map( | word | word.prefix(&session.document().as_text().as_lines()[word.cursor.line_index]).to_string());
+        let Some(prefix) = prefix else {
+            data.lsp_manager.close_completion();
+            return
+        };
+        if prefix != completion.prefix {
+            completion.prefix = prefix;
+            completion.selected = 0;
+        }
+        if completion.visible_items().is_empty() {
+            data.lsp_manager.close_completion();
+        }
+    }
+
+    fn handle_text_did_change(&mut self, cx: &mut Cx, tab_id: LiveId, data: &mut AppData) {
+        data.lsp_manager.close_hover();
+        self.update_completion(cx, tab_id, data);
+        let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return};
+        let Some(word) = CursorWord::new(session) else {return};
+        if word.is_after_trigger && word.cursor == word.word_start {
+            data.lsp_manager.request_completion(&data.file_system, tab_id, word.cursor, word.word_start);
+        }
+        self.completion_list.redraw(cx);
+    }
+
+    fn handle_hover(&mut self, cx: &mut Cx, event: &Event, tab_id: LiveId, data: &mut AppData) {
+        // the tooltip follows what the language server answered
+        let hover = data.lsp_manager.hover.as_ref().filter( | hover | hover.tab_id == tab_id);
+        match hover {
+            Some(hover) if !self.is_hover_shown => {
+                let pos = hover.abs + dvec2(0.0, self.editor.line_height());
+                self.hover_tooltip.show_with_options(cx, pos, &hover.text);
+                self.is_hover_shown = true;
+            }
+            None if self.is_hover_shown => {
+                self.hover_tooltip.hide(cx);
+                self.is_hover_shown = false;
+            }
+            _ => ()
+        }
+
+        if self.hover_timer.is_event(event).is_some() {
+            let Some(EditSession::Code(session)) = data.file_system.get_session_mut(tab_id) else {return};
+            if let Some(position) = self.editor.pick_position(cx, session, self.hover_abs) {
+                data.lsp_manager.request_hover(&data.file_system, tab_id, position, self.hover_abs);
+            }
+            return
+        }
+        let Event::MouseMove(_) = event else {return};
+        match event.hits(cx, self.editor.area()) {
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                // small moves leave the tooltip where it is
+                if self.is_hover_shown && (fe.abs - self.hover_abs).length() < self.editor.line_height() * 0.5 {
+                    return
+                }
+                data.lsp_manager.close_hover();
+                cx.stop_timer(self.hover_timer);
+                self.hover_timer = cx.start_timeout(0.5);
+                self.hover_abs = fe.abs;
+            }
+            Hit::FingerHoverOut(_) => {
+                data.lsp_manager.close_hover();
+                cx.stop_timer(self.hover_timer);
+            }
+            _ => ()
+        }
+    }
+
+    fn draw_completion(&mut self, cx: &mut Cx2d, tab_id: LiveId, data: &mut AppData) {
+        self.update_completion(cx, tab_id, data);
+        self.completion_list.begin_overlay_reuse(cx);
+        cx.begin_pass_sized_turtle(Layout::default());
+        let completion = data.lsp_manager.completion.as_ref().filter( | completion | completion.tab_id == tab_id);
+        if let (Some(completion), Some(EditSession::Code(session))) = (completion, data.file_system.tab_id_to_session.get(&tab_id)) {
+            let items = completion.visible_items();
+            let line_height = self.editor.line_height();
+            let row_height = line_height + 4.0;
+            let first = completion.selected.saturating_sub(COMPLETION_ROWS - 1);
+            let rows = items.len().min(COMPLETION_ROWS);
+            let pos = self.editor.position_to_abs(session, completion.position) + dvec2(0.0, line_height);
+            let label_width = self.completion_width * 0.6;
+            self.draw_completion_bg.draw_abs(cx, Rect {
+                pos,
+                size: dvec2(self.completion_width, rows as f64 * row_height),
+            });
+            for (row, item) in items.iter().enumerate().skip(first).take(rows) {
+                let row_pos = pos + dvec2(0.0, (row - first) as f64 * row_height);
+                if row == completion.selected {
+                    self.draw_completion_selected.draw_abs(cx, Rect {
+                        pos: row_pos,
+                        size: dvec2(self.completion_width, row_height),
+                    });
+                }
+                // the label and the detail are each clipped to their part of the row
+                cx.begin_turtle(Walk::abs_rect(Rect {pos: row_pos, size: dvec2(label_width, row_height)}), Layout::default());
+                self.draw_completion_text.draw_abs(cx, row_pos + dvec2(6.0, 2.0), &item.label);
+                cx.end_turtle();
+                if let Some(detail) = &item.detail {
+                    let detail_pos = row_pos + dvec2(label_width, 0.0);
+                    cx.begin_turtle(Walk::abs_rect(Rect {
+                        pos: detail_pos,
+                        size: dvec2(self.completion_width - label_width, row_height),
+                    }), Layout::default());
+                    self.draw_completion_detail.draw_abs(cx, detail_pos + dvec2(6.0, 2.0), detail);
+                    cx.end_turtle();
+                }
+            }
+        }
+        cx.end_pass_sized_turtle();
+        self.completion_list.end(cx);
+    }
+}
+
+enum LspKey {
+    Ignored,
+    Handled,
+    // the key changed the text, the editor does not know about it
+    Edited,
 }
 
 impl Widget for StudioCodeEditor {
@@ -36,18 +278,38 @@ fn draw_walk(&mut self, cx: &mut Cx2d, scope:&mut Scope, walk:Walk)->DrawStep{
         else{
             self.editor.draw_empty_editor(cx, walk);
         }
+        self.draw_completion(cx, session_id, app_scope);
+        self.hover_tooltip.draw_all(cx, &mut Scope::empty());
         DrawStep::done()
     }
-    
+
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope){
         let session_id = scope.path.from_end(1);
         let data = scope.data.get_mut::<AppData>().unwrap();
         let uid = self.widget_uid();
+        if let Event::KeyDown(key_event) = event {
+            if self.editor.has_key_focus(cx) {
+                match self.handle_lsp_key(cx, key_event, session_id, data) {
+                    LspKey::Ignored => (),
+                    LspKey::Handled => return,
+                    LspKey::Edited => {
+                        cx.widget_action(uid, &scope.path, CodeEditorAction::TextDidChange);
+                        return
+                    }
+                }
+            }
+        }
         if let Some(EditSession::Code(session)) = data.file_system.get_session_mut(session_id){
+            let mut text_did_change = false;
             for action in self.editor.handle_event(cx, event, &mut Scope::empty(), session){
+                text_did_change |= matches!(action, CodeEditorAction::TextDidChange);
                 cx.widget_action(uid, &scope.path, action);
             }
             data.file_system.handle_sessions();
+            if text_did_change {
+                self.handle_text_did_change(cx, session_id, data);
+            }
         }
+        self.handle_hover(cx, event, session_id, data);
     }
-}
\ No newline at end of file
+}
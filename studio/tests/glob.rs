@@ -0,0 +1,89 @@
+use makepad_studio::search::glob::{Glob, PathFilter};
+
+fn matches(pattern: &str, path: &str) -> bool {
+    Glob::new(pattern).matches(path)
+}
+
+#[test]
+fn star_stays_within_a_part() {
+    assert!(matches("*.rs", "main.rs"));
+    assert!(matches("*.rs", "src/main.rs"));
+    assert!(matches("*.rs", "src/deep/er/main.rs"));
+    assert!(!matches("*.rs", "main.rsx"));
+    assert!(!matches("*.rs", "main.rs.bak"));
+    assert!(matches("ma*n.rs", "main.rs"));
+    assert!(!matches("a*b", "a/b"));
+}
+
+#[test]
+fn pattern_without_slash_matches_folders() {
+    assert!(matches("examples", "examples/app/src/main.rs"));
+    assert!(matches("examples", "studio/examples/x.rs"));
+    assert!(!matches("examples", "examples2/x.rs"));
+    assert!(matches("target", "target"));
+}
+
+#[test]
+fn pattern_with_slash_is_anchored() {
+    assert!(matches("src/*.rs", "src/main.rs"));
+    assert!(!matches("src/*.rs", "src/os/main.rs"));
+    assert!(!matches("src/*.rs", "studio/src/main.rs"));
+    // each folder of the path counts, so a folder pattern takes everything in it
+    assert!(matches("studio/src", "studio/src/app.rs"));
+    assert!(!matches("studio/src", "studio/srcs/app.rs"));
+}
+
+#[test]
+fn double_star_crosses_folders() {
+    assert!(matches("src/**/*.rs", "src/main.rs"));
+    assert!(matches("src/**/*.rs", "src/a/b/main.rs"));
+    assert!(!matches("src/**/*.rs", "src/a/b/main.toml"));
+    assert!(matches("**/tests/*.rs", "tests/lsp.rs"));
+    assert!(matches("**/tests/*.rs", "studio/tests/lsp.rs"));
+    assert!(matches("a/**", "a/b/c"));
+}
+
+#[test]
+fn question_mark_is_one_char_but_not_a_slash() {
+    assert!(matches("?.rs", "a.rs"));
+    assert!(matches("?.rs", "é.rs"));
+    assert!(!matches("?.rs", "ab.rs"));
+    assert!(!matches("a?b", "a/b"));
+}
+
+#[test]
+fn braces_are_alternatives() {
+    assert!(matches("*.{rs,toml}", "Cargo.toml"));
+    assert!(matches("*.{rs,toml}", "lib.rs"));
+    assert!(!matches("*.{rs,toml}", "README.md"));
+    assert!(matches("{a,b}{1,2}", "b2"));
+    // an unclosed brace is a literal
+    assert!(matches("{a", "{a"));
+}
+
+#[test]
+fn leading_dot_slash_and_trailing_slash_are_ignored() {
+    assert!(matches("./src/*.rs", "src/main.rs"));
+    assert!(matches("target/", "target/debug/studio"));
+    assert!(matches("  *.rs ", "main.rs"));
+}
+
+#[test]
+fn path_filter() {
+    let filter = PathFilter::new("", "");
+    assert!(filter.accepts("anything/at/all"));
+
+    let filter = PathFilter::new("*.{rs,toml}, *.md", "target, examples/*.rs");
+    assert!(filter.accepts("src/main.rs"));
+    assert!(filter.accepts("Cargo.toml"));
+    assert!(filter.accepts("README.md"));
+    assert!(!filter.accepts("logo.png"));
+    assert!(!filter.accepts("target/debug/build.rs"));
+    assert!(!filter.accepts("examples/app.rs"));
+    assert!(filter.accepts("examples/app/src/main.rs"));
+
+    // empty patterns between commas are skipped
+    let filter = PathFilter::new(",, ,", "*.lock,");
+    assert!(filter.accepts("src/main.rs"));
+    assert!(!filter.accepts("Cargo.lock"));
+}
@@ -0,0 +1,431 @@
+// Runs the language server client against a tiny scripted server. The server is this same
+// test executable, started again with `MAKEPAD_FAKE_LSP_SERVER` set so that only the
+// `fake_lsp_server` test runs, and that test runs the server.
+
+use {
+    makepad_studio::{
+        lsp::{lsp_client::*, lsp_protocol::*},
+        makepad_code_editor::{
+            decoration::DecorationSet,
+            history::NewGroup,
+            selection::Affinity,
+            session::SelectionMode,
+            text::Position,
+            CodeDocument, CodeSession,
+        },
+        makepad_micro_serde::*,
+    },
+    std::{
+        collections::{HashMap, VecDeque},
+        io::{BufRead, Write},
+        ops::{Deref, DerefMut},
+        time::{Duration, Instant},
+    },
+};
+
+const FAKE_LSP_SERVER: &str = "MAKEPAD_FAKE_LSP_SERVER";
+
+// the client, along with the events it sent that no test has looked at yet
+struct TestClient {
+    client: LspClient,
+    events: VecDeque<LspEvent>,
+}
+
+impl Deref for TestClient {
+    type Target = LspClient;
+    fn deref(&self) -> &LspClient {&self.client}
+}
+
+impl DerefMut for TestClient {
+    fn deref_mut(&mut self) -> &mut LspClient {&mut self.client}
+}
+
+impl TestClient {
+    fn start() -> Self {
+        std::env::set_var(FAKE_LSP_SERVER, "1");
+        let config = LspServerConfig {
+            command: std::env::current_exe().unwrap().to_string_lossy().into_owned(),
+            // the test harness prints a line before the test runs, which the client skips as it
+            // comes before the first header, but nothing while it runs when it is quiet
+            args: ["fake_lsp_server", "--exact", "--quiet", "--nocapture"].map(String::from).to_vec(),
+            language_id: "rust".to_string(),
+            extensions: vec!["rs".to_string()],
+        };
+        Self {
+            client: LspClient::start(&config, &std::env::temp_dir()).unwrap(),
+            events: VecDeque::new(),
+        }
+    }
+
+    // polls the client until an event passes `filter`, skipping the ones before it
+    fn wait_for<T>(&mut self, mut filter: impl FnMut(LspEvent) -> Option<T>) -> T {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            while let Some(event) = self.events.pop_front() {
+                if let LspEvent::Exited = event {
+                    panic!("the fake server exited")
+                }
+                if let Some(value) = filter(event) {
+                    return value
+                }
+            }
+            assert!(Instant::now() < deadline, "timed out waiting for the fake server");
+            std::thread::sleep(Duration::from_millis(5));
+            self.events.extend(self.client.poll());
+        }
+    }
+
+    fn wait_for_diagnostics(&mut self, uri: &str) -> Vec<LspDiagnostic> {
+        self.wait_for( | event | match event {
+            LspEvent::Diagnostics {uri: diagnostics_uri, diagnostics} if diagnostics_uri == uri => Some(diagnostics),
+            _ => None
+        })
+    }
+
+    // the server logs its copy of a document after every change
+    fn wait_for_server_text(&mut self) -> String {
+        self.wait_for( | event | match event {
+            LspEvent::Log(line) => line.strip_prefix("text:").map( | text | text.to_string()),
+            _ => None
+        })
+    }
+}
+
+fn lsp_position(line: usize, character: usize) -> LspPosition {
+    LspPosition {line, character}
+}
+
+#[test]
+fn initialize_and_publish_diagnostics() {
+    let mut client = TestClient::start();
+    let document = CodeDocument::new("fn main() {\n    error();\n    warn();\n}".into(), DecorationSet::new());
+    // sent before the server is initialized, so it is held back until it is
+    let lsp_document = LspDocument::open(&mut client, "file:///main.rs".to_string(), "rust", &document);
+    client.wait_for(| event | matches!(event, LspEvent::Initialized).then_some(()));
+
+    let diagnostics = client.wait_for_diagnostics(&lsp_document.uri);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].severity, LspSeverity::Error);
+    assert_eq!(diagnostics[0].range, LspRange {start: lsp_position(1, 4), end: lsp_position(1, 9)});
+    assert_eq!(diagnostics[0].message, "    error();");
+    assert_eq!(diagnostics[1].severity, LspSeverity::Warning);
+    assert_eq!(diagnostics[1].range.start, lsp_position(2, 4));
+}
+
+#[test]
+fn edits_are_synced_incrementally() {
+    let mut client = TestClient::start();
+    let document = CodeDocument::new("fn main() {\n}".into(), DecorationSet::new());
+    let mut session = CodeSession::new(document.clone());
+    let mut lsp_document = LspDocument::open(&mut client, "file:///edit.rs".to_string(), "rust", &document);
+    assert!(client.wait_for_diagnostics(&lsp_document.uri).is_empty());
+
+    // characters outside of the basic plane take two UTF-16 code units
+    session.set_selection(Position {line_index: 0, byte_index: 11}, Affinity::Before, SelectionMode::Simple, NewGroup::Yes);
+    session.insert("\n    let 😀 = error;".into());
+    session.handle_changes();
+    lsp_document.sync(&mut client);
+    assert_eq!(client.wait_for_server_text(), document.as_text().to_string());
+    let diagnostics = client.wait_for_diagnostics(&lsp_document.uri);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, LspRange {start: lsp_position(1, 13), end: lsp_position(1, 18)});
+    // which is a position after the emoji in the editor
+    assert_eq!(diagnostics[0].range.start.to_position(document.as_text().as_lines()), Position {line_index: 1, byte_index: 15});
+
+    // several edits at once, each relative to the text after the ones before it
+    for _ in 0..6 {
+        session.backspace();
+        session.handle_changes();
+    }
+    session.insert("ok;".into());
+    session.handle_changes();
+    session.set_selection(Position {line_index: 0, byte_index: 3}, Affinity::Before, SelectionMode::Simple, NewGroup::Yes);
+    session.insert("real_".into());
+    session.handle_changes();
+    session.undo();
+    lsp_document.sync(&mut client);
+    assert_eq!(client.wait_for_server_text(), document.as_text().to_string());
+    assert_eq!(client.wait_for_diagnostics(&lsp_document.uri).len(), 0);
+
+    lsp_document.close(&mut client);
+}
+
+#[test]
+fn completion() {
+    let mut client = TestClient::start();
+    let document = CodeDocument::new("fn main() {\n    items.\n}".into(), DecorationSet::new());
+    let lsp_document = LspDocument::open(&mut client, "file:///completion.rs".to_string(), "rust", &document);
+    let request_id = client.completion(&lsp_document.uri, lsp_position(1, 10));
+    let items = client.wait_for(| event | match event {
+        LspEvent::Completion {request_id: id, items} if id == request_id => Some(items),
+        _ => None
+    });
+    let texts: Vec<(&str, &str)> = items.iter().map( | item | (item.label.as_str(), item.insert_text.as_str())).collect();
+    assert_eq!(texts, [("len", "len()"), ("push", "push"), ("iter", "iter()")]);
+    assert_eq!(items[0].detail.as_deref(), Some("fn(&self) -> usize"));
+}
+
+#[test]
+fn hover_and_definition() {
+    let mut client = TestClient::start();
+    let document = CodeDocument::new("fn main() {\n    main();\n}".into(), DecorationSet::new());
+    let lsp_document = LspDocument::open(&mut client, "file:///tmp/hover%20me.rs".to_string(), "rust", &document);
+
+    let request_id = client.hover(&lsp_document.uri, lsp_position(1, 5));
+    let text = client.wait_for(| event | match event {
+        LspEvent::Hover {request_id: id, text} if id == request_id => Some(text),
+        _ => None
+    });
+    // the markdown code fences are gone
+    assert_eq!(text.as_deref(), Some("fn main()\nhovering line 1"));
+
+    // an empty hover is no hover
+    let request_id = client.hover(&lsp_document.uri, lsp_position(2, 0));
+    let text = client.wait_for(| event | match event {
+        LspEvent::Hover {request_id: id, text} if id == request_id => Some(text),
+        _ => None
+    });
+    assert_eq!(text, None);
+
+    let request_id = client.definition(&lsp_document.uri, lsp_position(1, 5));
+    let locations = client.wait_for(| event | match event {
+        LspEvent::Definition {request_id: id, locations} if id == request_id => Some(locations),
+        _ => None
+    });
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].path, std::path::Path::new("/tmp/hover me.rs"));
+    assert_eq!(locations[0].range.start, lsp_position(0, 3));
+
+    let request_id = client.definition(&lsp_document.uri, lsp_position(0, 0));
+    let message = client.wait_for(| event | match event {
+        LspEvent::Error {request_id: id, message} if id == request_id => Some(message),
+        _ => None
+    });
+    assert_eq!(message, "no definition");
+}
+
+#[test]
+fn shutdown() {
+    let mut client = TestClient::start();
+    client.wait_for(| event | matches!(event, LspEvent::Initialized).then_some(()));
+    client.shutdown();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !client.poll().iter().any( | event | matches!(event, LspEvent::Exited)) {
+        assert!(Instant::now() < deadline, "the fake server did not exit");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
+// The fake server. It keeps its own copy of every open document, applies the incremental
+// changes it gets to it, and reports an error for every `error` and a warning for every `warn`
+// in it. Anything it doesn't expect makes it exit with an error, which fails the test.
+
+#[test]
+fn fake_lsp_server() {
+    // the variable is set in here as well once another test has started a server, which is
+    // why it only counts when this test is all the harness was asked to run
+    if std::env::var_os(FAKE_LSP_SERVER).is_some() && std::env::args().nth(1).as_deref() == Some("fake_lsp_server") {
+        run_fake_server();
+        std::process::exit(0)
+    }
+}
+
+fn run_fake_server() {
+    let mut stdin = std::io::stdin().lock();
+    let mut documents: HashMap<String, (u64, String)> = HashMap::new();
+    let mut is_initialized = false;
+    while let Some(message) = read_message(&mut stdin) {
+        let method = message.key("method").and_then( | method | method.string()).cloned().unwrap_or_default();
+        let params = message.key("params").cloned().unwrap_or(JsonValue::Null);
+        let id = message.key("id").cloned();
+        if method != "initialize" && method != "initialized" && !method.is_empty() && !is_initialized {
+            fail(&format!("{} before initialized", method));
+        }
+        match method.as_str() {
+            "initialize" => {
+                respond(id, json_object([
+                    ("capabilities", json_object([
+                        ("textDocumentSync", JsonValue::U64(2)),
+                        ("hoverProvider", JsonValue::Bool(true)),
+                        ("definitionProvider", JsonValue::Bool(true)),
+                    ])),
+                ]));
+            }
+            "initialized" => {
+                is_initialized = true;
+                // servers ask the client things too, which it has to answer
+                send(json_object([
+                    ("jsonrpc", json_string("2.0")),
+                    ("id", JsonValue::U64(1000)),
+                    ("method", json_string("workspace/configuration")),
+                    ("params", json_object([("items", JsonValue::Array(vec![json_object([])]))])),
+                ]));
+            }
+            "textDocument/didOpen" => {
+                let document = params.key("textDocument").unwrap();
+                let uri = document.key("uri").unwrap().string().unwrap().clone();
+                let text = document.key("text").unwrap().string().unwrap().clone();
+                publish_diagnostics(&uri, &text);
+                documents.insert(uri, (0, text));
+            }
+            "textDocument/didChange" => {
+                let document = params.key("textDocument").unwrap();
+                let uri = document.key("uri").unwrap().string().unwrap();
+                let version = document.key("version").unwrap().u64().unwrap();
+                let Some((last_version, text)) = documents.get_mut(uri) else {fail("change to a closed document")};
+                if version != *last_version + 1 {
+                    fail("versions out of order");
+                }
+                *last_version = version;
+                for change in params.key("contentChanges").unwrap().array().unwrap() {
+                    let range = LspRange::from_json(change.key("range").unwrap()).unwrap();
+                    let start = byte_offset(text, range.start);
+                    let end = byte_offset(text, range.end);
+                    text.replace_range(start..end, change.key("text").unwrap().string().unwrap());
+                }
+                log_message(&format!("text:{}", text));
+                publish_diagnostics(uri, text);
+            }
+            "textDocument/didClose" => {
+                let uri = params.key("textDocument").unwrap().key("uri").unwrap().string().unwrap();
+                documents.remove(uri);
+            }
+            "textDocument/completion" => {
+                let position = LspPosition::from_json(params.key("position").unwrap()).unwrap();
+                let range = LspRange {start: position, end: position}.to_json();
+                respond(id, json_object([
+                    ("isIncomplete", JsonValue::Bool(false)),
+                    ("items", JsonValue::Array(vec![
+                        json_object([
+                            ("label", json_string("len")),
+                            ("detail", json_string("fn(&self) -> usize")),
+                            ("textEdit", json_object([("range", range), ("newText", json_string("len()"))])),
+                        ]),
+                        json_object([("label", json_string("push"))]),
+                        json_object([("label", json_string("iter")), ("insertText", json_string("iter()"))]),
+                    ])),
+                ]));
+            }
+            "textDocument/hover" => {
+                let position = LspPosition::from_json(params.key("position").unwrap()).unwrap();
+                let value = if position.line == 1 {
+                    "```rust\nfn main()\n```\nhovering line 1"
+                } else {
+                    ""
+                };
+                respond(id, json_object([
+                    ("contents", json_object([("kind", json_string("markdown")), ("value", json_string(value))])),
+                ]));
+            }
+            "textDocument/definition" => {
+                let uri = params.key("textDocument").unwrap().key("uri").unwrap().clone();
+                let position = LspPosition::from_json(params.key("position").unwrap()).unwrap();
+                if position.line == 0 {
+                    send(json_object([
+                        ("jsonrpc", json_string("2.0")),
+                        ("id", id.unwrap()),
+                        ("error", json_object([("code", JsonValue::I64(-32603)), ("message", json_string("no definition"))])),
+                    ]));
+                    continue
+                }
+                let range = LspRange {start: lsp_position(0, 3), end: lsp_position(0, 7)};
+                respond(id, JsonValue::Array(vec![json_object([("uri", uri), ("range", range.to_json())])]));
+            }
+            "shutdown" => respond(id, JsonValue::Null),
+            "exit" => return,
+            // the answer to workspace/configuration
+            "" if id.as_ref().and_then( | id | id.u64()) == Some(1000) => {
+                let answered = message.key("result").and_then( | result | result.array()).map( | result | result.len());
+                if answered != Some(1) {
+                    fail("workspace/configuration was not answered");
+                }
+            }
+            _ => fail(&format!("unexpected message {}", method)),
+        }
+    }
+}
+
+fn read_message(stdin: &mut impl BufRead) -> Option<JsonValue> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if stdin.read_line(&mut line).ok()? == 0 {
+            return None
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break
+        }
+        if let Some(length) = line.strip_prefix("Content-Length: ") {
+            content_length = length.parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; content_length?];
+    stdin.read_exact(&mut body).ok()?;
+    Some(JsonValue::deserialize_json(std::str::from_utf8(&body).ok()?).unwrap())
+}
+
+fn send(message: JsonValue) {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(frame_message(&message.serialize_json()).as_bytes()).unwrap();
+    stdout.flush().unwrap();
+}
+
+fn respond(id: Option<JsonValue>, result: JsonValue) {
+    send(json_object([("jsonrpc", json_string("2.0")), ("id", id.unwrap()), ("result", result)]));
+}
+
+fn log_message(message: &str) {
+    send(json_object([
+        ("jsonrpc", json_string("2.0")),
+        ("method", json_string("window/logMessage")),
+        ("params", json_object([("type", JsonValue::U64(4)), ("message", json_string(message))])),
+    ]));
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("fake server: {}", message);
+    std::process::exit(1)
+}
+
+fn publish_diagnostics(uri: &str, text: &str) {
+    let mut diagnostics = Vec::new();
+    for (line_index, line) in text.split('\n').enumerate() {
+        for (word, severity) in [("error", 1), ("warn", 2)] {
+            let Some(byte_index) = line.find(word) else {continue};
+            let character: usize = line[..byte_index].chars().map( | char | char.len_utf16()).sum();
+            let range = LspRange {
+                start: lsp_position(line_index, character),
+                end: lsp_position(line_index, character + word.len()),
+            };
+            diagnostics.push(json_object([
+                ("range", range.to_json()),
+                ("severity", JsonValue::U64(severity)),
+                ("message", json_string(line)),
+            ]));
+        }
+    }
+    send(json_object([
+        ("jsonrpc", json_string("2.0")),
+        ("method", json_string("textDocument/publishDiagnostics")),
+        ("params", json_object([("uri", json_string(uri)), ("diagnostics", JsonValue::Array(diagnostics))])),
+    ]));
+}
+
+fn byte_offset(text: &str, position: LspPosition) -> usize {
+    let mut offset = 0;
+    for (line_index, line) in text.split('\n').enumerate() {
+        if line_index == position.line {
+            let mut character = 0;
+            for (byte_index, char) in line.char_indices() {
+                if character >= position.character {
+                    return offset + byte_index
+                }
+                character += char.len_utf16();
+            }
+            return offset + line.len()
+        }
+        offset += line.len() + 1;
+    }
+    fail("position past the end of the document")
+}
@@ -0,0 +1,150 @@
+use {
+    makepad_studio::{
+        file_system::{
+            file_system::{FileEdge, FileNode, FileSystem, OpenDocument},
+            FileClientInner,
+        },
+        makepad_code_editor::{decoration::DecorationSet, text::Text, CodeDocument, SearchQuery},
+        makepad_file_protocol::FileRequest,
+        makepad_widgets::*,
+        search::search_manager::SearchManager,
+    },
+    std::{
+        path::PathBuf,
+        sync::mpsc::{self, Receiver},
+        time::{Duration, Instant},
+    },
+};
+
+/// A project on disk with a file system that hands the requests it makes to the test.
+struct Project {
+    cx: Cx,
+    fs: FileSystem,
+    search: SearchManager,
+    requests: Receiver<FileRequest>,
+    root: PathBuf,
+}
+
+impl Project {
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let root = std::env::temp_dir().join(format!("makepad-search-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let mut fs = FileSystem::default();
+        let (request_sender, requests) = mpsc::channel();
+        let (_, message_receiver) = mpsc::channel();
+        fs.file_client.inner = Some(FileClientInner {
+            request_sender,
+            message_signal: SignalToUI::default(),
+            message_receiver,
+        });
+        let root_id = live_id!(root);
+        let mut child_edges = Vec::new();
+        for (path, text) in files {
+            std::fs::write(root.join(path), text).unwrap();
+            let file_id = LiveId::from_str(path);
+            child_edges.push(FileEdge {name: path.to_string(), file_node_id: file_id});
+            fs.file_nodes.insert(file_id, FileNode {
+                parent_edge: Some(FileEdge {name: path.to_string(), file_node_id: root_id}),
+                name: path.to_string(),
+                child_edges: None,
+            });
+            fs.path_to_file_node_id.insert(path.to_string(), file_id);
+        }
+        fs.file_nodes.insert(root_id, FileNode {parent_edge: None, name: String::new(), child_edges: Some(child_edges)});
+        let mut search = SearchManager::default();
+        search.init(&root);
+        Self {cx: Cx::new(Box::new( | _, _ | {})), fs, search, requests, root}
+    }
+
+    fn open(&mut self, path: &str, text: &str) {
+        let doc = CodeDocument::new(Text::from(text), DecorationSet::new());
+        self.fs.open_documents.insert(LiveId::from_str(path), OpenDocument::Code(doc));
+    }
+
+    fn document(&self, path: &str) -> String {
+        match self.fs.open_documents.get(&LiveId::from_str(path)) {
+            Some(OpenDocument::Code(doc)) => doc.as_text().to_string(),
+            _ => panic!("{} is not open", path)
+        }
+    }
+
+    fn search(&mut self, text: &str) {
+        self.search.start_search(&mut self.cx, &self.fs, SearchQuery::new(text), "", "").unwrap();
+        self.wait_for( | project | !project.search.is_searching);
+    }
+
+    /// Handles the messages of the search thread until `done` holds.
+    fn wait_for(&mut self, mut done: impl FnMut(&mut Self) -> bool) {
+        let start = Instant::now();
+        while !done(self) {
+            assert!(start.elapsed() < Duration::from_secs(5), "timed out");
+            std::thread::sleep(Duration::from_millis(1));
+            self.search.handle_event(&mut self.cx, &Event::Signal, &mut self.fs);
+        }
+    }
+
+    /// Waits for `count` files to be saved, and returns their ids and contents sorted by id.
+    fn saved(&mut self, count: usize) -> Vec<(LiveId, String)> {
+        let mut saved = Vec::new();
+        self.wait_for( | project | {
+            while let Ok(request) = project.requests.try_recv() {
+                if let FileRequest::SaveFile {id, data, patch, ..} = request {
+                    assert!(patch, "replacements are saved as patches");
+                    saved.push((LiveId(id), data));
+                }
+            }
+            saved.len() >= count
+        });
+        saved.sort();
+        saved
+    }
+}
+
+impl Drop for Project {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn searches_open_documents_and_files_on_disk() {
+    let mut project = Project::new("find", &[("a.rs", "foo\nlet foo = foo;\n"), ("b.rs", "foo\n"), ("c.md", "bar\n")]);
+    project.open("b.rs", "bar\nfoo foo\n");
+    project.search("foo");
+    let files: Vec<_> = project.search.files.iter().map( | file | {
+        (file.path.as_str(), file.hits.iter().map( | hit | (hit.line_index, hit.start, hit.end)).collect::<Vec<_>>())
+    }).collect();
+    assert_eq!(files, [("a.rs", vec![(0, 0, 3), (1, 4, 7), (1, 10, 13)]), ("b.rs", vec![(1, 0, 3), (1, 4, 7)])]);
+    assert_eq!(project.search.status(), "5 results in 2 files");
+}
+
+#[test]
+fn replace_all_changes_open_documents_in_place() {
+    let mut project = Project::new("replace", &[("a.rs", "let foo = foo;\n"), ("b.rs", "foo\n"), ("c.md", "bar\n")]);
+    project.open("b.rs", "foo bar foo");
+    project.search("foo");
+    project.search.replace_all(&mut project.cx, &mut project.fs, "baz");
+    assert_eq!(project.document("b.rs"), "baz bar baz");
+    let mut expected = vec![
+        (LiveId::from_str("a.rs"), "let baz = baz;\n".to_string()),
+        (LiveId::from_str("b.rs"), "baz bar baz".to_string()),
+    ];
+    expected.sort();
+    assert_eq!(project.saved(2), expected);
+    assert_eq!(project.search.status(), "Replaced 4 matches");
+    // the files on disk are only changed by the file server
+    assert_eq!(std::fs::read_to_string(project.root.join("a.rs")).unwrap(), "let foo = foo;\n");
+}
+
+#[test]
+fn replace_all_uses_documents_opened_while_it_runs() {
+    let mut project = Project::new("reopen", &[("a.rs", "foo foo\n")]);
+    project.search("foo");
+    project.search.replace_all(&mut project.cx, &mut project.fs, "baz");
+    // the file is opened and edited before the replacement on disk comes in
+    project.open("a.rs", "foo edited\n");
+    assert_eq!(project.saved(1), [(LiveId::from_str("a.rs"), "baz edited\n".to_string())]);
+    assert_eq!(project.document("a.rs"), "baz edited\n");
+    assert_eq!(project.search.status(), "Replaced 1 matches");
+}
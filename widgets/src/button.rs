@@ -457,6 +457,21 @@ fn set_text(&mut self, cx:&mut Cx, v: &str) {
         self.text.as_mut_empty().push_str(v);
         self.redraw(cx);
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        if !self.visible {
+            return None
+        }
+        let mut node = AccessibilityNode::new(AccessibilityRole::Button)
+            .with_name(self.text.as_ref())
+            .with_disabled(!self.enabled)
+            .with_focused(cx.has_key_focus(self.draw_bg.area()))
+            .with_action(AccessibilityAction::Click);
+        if self.grab_key_focus {
+            node = node.with_action(AccessibilityAction::Focus);
+        }
+        Some(node)
+    }
 }
 
 impl Button {
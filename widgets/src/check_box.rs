@@ -422,6 +422,18 @@ fn set_text(&mut self, cx:&mut Cx, v: &str) {
         self.text.as_mut_empty().push_str(v);
         self.redraw(cx);
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        let role = match self.draw_check.check_type {
+            CheckType::Radio => AccessibilityRole::RadioButton,
+            _ => AccessibilityRole::CheckBox
+        };
+        Some(AccessibilityNode::new(role)
+            .with_name(self.text.as_ref())
+            .with_checked(self.animator_in_state(cx, id!(selected.on)))
+            .with_focused(cx.has_key_focus(self.draw_check.area()))
+            .with_action(AccessibilityAction::Click))
+    }
 }
 
 impl CheckBoxRef {
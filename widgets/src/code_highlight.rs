@@ -0,0 +1,228 @@
+use {
+    std::{collections::HashMap, rc::Rc},
+    crate::{
+        makepad_draw::*,
+        makepad_draw::makepad_live_tokenizer as live_tokenizer,
+    },
+    makepad_rust_tokenizer as rust_tokenizer,
+};
+
+live_design!{
+    link widgets;
+
+    pub CodeColors = {{CodeColors}} {
+        unknown: #C0C0C0,
+        branch_keyword: #C485BE,
+        comment: #638D54,
+        constant: #CC917B,
+        delimiter: #a,
+        identifier: #D4D4D4,
+        loop_keyword: #FF8C00,
+        number: #B6CEAA,
+        other_keyword: #5B9BD3,
+        function: #fffcc9,
+        punctuator: #D4D4D4,
+        string: #CC917B,
+        typename: #56C9B1,
+    }
+}
+
+/// The colors used to syntax highlight code blocks, one for each [`CodeTokenKind`].
+#[derive(Live, LiveHook, LiveRegister)]
+pub struct CodeColors {
+    #[live] pub unknown: Vec4,
+    #[live] pub branch_keyword: Vec4,
+    #[live] pub comment: Vec4,
+    #[live] pub constant: Vec4,
+    #[live] pub delimiter: Vec4,
+    #[live] pub identifier: Vec4,
+    #[live] pub loop_keyword: Vec4,
+    #[live] pub number: Vec4,
+    #[live] pub other_keyword: Vec4,
+    #[live] pub function: Vec4,
+    #[live] pub punctuator: Vec4,
+    #[live] pub string: Vec4,
+    #[live] pub typename: Vec4,
+}
+
+impl CodeColors {
+    /// Returns the color for the given token kind, or `None` for whitespace.
+    pub fn color(&self, kind: CodeTokenKind) -> Option<Vec4> {
+        Some(match kind {
+            CodeTokenKind::Unknown => self.unknown,
+            CodeTokenKind::BranchKeyword => self.branch_keyword,
+            CodeTokenKind::Comment => self.comment,
+            CodeTokenKind::Constant => self.constant,
+            CodeTokenKind::Delimiter => self.delimiter,
+            CodeTokenKind::Identifier => self.identifier,
+            CodeTokenKind::LoopKeyword => self.loop_keyword,
+            CodeTokenKind::OtherKeyword => self.other_keyword,
+            CodeTokenKind::Number => self.number,
+            CodeTokenKind::Punctuator => self.punctuator,
+            CodeTokenKind::Typename => self.typename,
+            CodeTokenKind::Function => self.function,
+            CodeTokenKind::String => self.string,
+            CodeTokenKind::Whitespace => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CodeTokenKind {
+    Unknown,
+    BranchKeyword,
+    Comment,
+    Constant,
+    Delimiter,
+    Identifier,
+    LoopKeyword,
+    OtherKeyword,
+    Number,
+    Punctuator,
+    Typename,
+    Function,
+    String,
+    Whitespace,
+}
+
+/// A token of a highlighted code block. `len` is in bytes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CodeToken {
+    pub len: usize,
+    pub kind: CodeTokenKind,
+}
+
+/// A function that splits a piece of code into tokens covering all of it.
+pub type CodeTokenizer = Rc<dyn Fn(&str) -> Vec<CodeToken>>;
+
+/// The table of tokenizers used to highlight code blocks, by language name.
+///
+/// This is stored as a global on `Cx`, so other crates can plug in tokenizers
+/// for more languages with [`CodeTokenizers::register`]. Out of the box it only
+/// knows about Rust and live design; `makepad_code_editor::live_design` adds the
+/// languages of its tokenizer registry.
+pub struct CodeTokenizers {
+    tokenizers: HashMap<String, CodeTokenizer>,
+}
+
+impl Default for CodeTokenizers {
+    fn default() -> Self {
+        let mut tokenizers = Self {
+            tokenizers: HashMap::new(),
+        };
+        tokenizers.insert(&["rust", "rs"], Rc::new(tokenize_rust));
+        tokenizers.insert(&["live", "makepad"], Rc::new(tokenize_live));
+        tokenizers
+    }
+}
+
+impl CodeTokenizers {
+    fn insert(&mut self, languages: &[&str], tokenizer: CodeTokenizer) {
+        for language in languages {
+            self.tokenizers.insert(language.to_lowercase(), tokenizer.clone());
+        }
+    }
+
+    fn lazy_create(cx: &mut Cx) -> &mut Self {
+        if !cx.has_global::<Self>() {
+            cx.set_global(Self::default());
+        }
+        cx.get_global::<Self>()
+    }
+
+    /// Registers `tokenizer` for the given language names, replacing any existing ones.
+    pub fn register<F>(cx: &mut Cx, languages: &[&str], tokenizer: F)
+    where
+        F: Fn(&str) -> Vec<CodeToken> + 'static,
+    {
+        Self::lazy_create(cx).insert(languages, Rc::new(tokenizer));
+    }
+
+    /// Tokenizes `code` with the tokenizer for `language`, which is the info string
+    /// of a fenced code block such as `rust` or `rust,ignore`.
+    ///
+    /// Returns `None` if no tokenizer is registered for the language.
+    pub fn tokenize(cx: &mut Cx, language: &str, code: &str) -> Option<Vec<CodeToken>> {
+        let language = language
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        let tokenizer = Self::lazy_create(cx).tokenizers.get(&language)?.clone();
+        Some(tokenizer(code))
+    }
+}
+
+// The rust and live tokenizers are two copies of the same tokenizer, so we
+// generate the same conversion to code tokens for both of them.
+macro_rules! full_token_tokenizer {
+    ($name:ident, $tokenizer:ident, [$($keyword:literal),*]) => {
+        fn $name(code: &str) -> Vec<CodeToken> {
+            use $tokenizer::{Cursor, FullToken, State};
+            let chars: Vec<char> = code.chars().collect();
+            let mut scratch = String::new();
+            let mut cursor = Cursor::new(&chars, &mut scratch);
+            let mut state = State::default();
+            let mut tokens = Vec::new();
+            let mut char_pos = 0;
+            let mut byte_pos = 0;
+            loop {
+                let (next_state, token) = state.next(&mut cursor);
+                state = next_state;
+                let Some(token) = token else { break };
+                let len: usize = chars[char_pos..char_pos + token.len].iter().map(|c| c.len_utf8()).sum();
+                let string = &code[byte_pos..byte_pos + len];
+                let kind = match token.token {
+                    FullToken::Ident(_) => classify_identifier(string, &code[byte_pos + len..], &[$($keyword),*]),
+                    FullToken::Punct(_) => CodeTokenKind::Punctuator,
+                    FullToken::Open(_) | FullToken::Close(_) => CodeTokenKind::Delimiter,
+                    FullToken::String { .. } => CodeTokenKind::String,
+                    FullToken::Bool(_) => CodeTokenKind::OtherKeyword,
+                    FullToken::Color(_) | FullToken::Float(_) | FullToken::Int(_) | FullToken::OtherNumber => CodeTokenKind::Number,
+                    FullToken::Lifetime => CodeTokenKind::Identifier,
+                    FullToken::Comment => CodeTokenKind::Comment,
+                    FullToken::Whitespace => CodeTokenKind::Whitespace,
+                    FullToken::Unknown => CodeTokenKind::Unknown,
+                };
+                tokens.push(CodeToken { len, kind });
+                char_pos += token.len;
+                byte_pos += len;
+            }
+            // The tokenizers stop at a nul char, so we keep whatever is left unhighlighted
+            if byte_pos < code.len() {
+                tokens.push(CodeToken { len: code.len() - byte_pos, kind: CodeTokenKind::Unknown });
+            }
+            tokens
+        }
+    }
+}
+
+full_token_tokenizer!(tokenize_rust, rust_tokenizer, []);
+full_token_tokenizer!(tokenize_live, live_tokenizer, ["link", "instance", "uniform", "varying", "texture"]);
+
+fn classify_identifier(string: &str, rest: &str, extra_keywords: &[&str]) -> CodeTokenKind {
+    match string {
+        "else" | "if" | "match" | "return" => CodeTokenKind::BranchKeyword,
+        "break" | "continue" | "for" | "loop" | "while" => CodeTokenKind::LoopKeyword,
+        "Self" | "as" | "async" | "await" | "const" | "crate" | "dyn" | "enum"
+        | "extern" | "fn" | "impl" | "in" | "let" | "mod" | "move" | "mut"
+        | "pub" | "ref" | "self" | "static" | "struct" | "super" | "trait"
+        | "type" | "unsafe" | "use" | "where" | "usize" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" | "vec2" | "vec3" | "vec4"
+        | "bool" | "f32" | "f64" => CodeTokenKind::OtherKeyword,
+        _ if extra_keywords.contains(&string) => CodeTokenKind::OtherKeyword,
+        _ => {
+            let mut chars = string.chars();
+            if chars.next().is_some_and(|c| c.is_uppercase()) {
+                match chars.next() {
+                    Some(c) if c.is_uppercase() => CodeTokenKind::Constant,
+                    _ => CodeTokenKind::Typename,
+                }
+            } else if rest.starts_with('(') {
+                CodeTokenKind::Function
+            } else {
+                CodeTokenKind::Identifier
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use {
+    std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+    },
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        makepad_platform::{FileDialog, FileDialogKind, FileDialogResponse, FileDialogEvent},
+        file_tree::*,
+        button::*,
+        label::*,
+        text_input::*,
+        view::*,
+        widget::*,
+    }
+};
+
+live_design!{
+    link widgets;
+    use link::widgets::*;
+    use link::theme::*;
+    use makepad_draw::shader::std::*;
+
+    pub FileChooserBase = {{FileChooser}} {}
+    pub FileChooser = <FileChooserBase> {
+        width: 500
+        height: 400
+        flow: Down
+        padding: <THEME_MSPACE_2> {}
+        spacing: (THEME_SPACE_2)
+        show_bg: true
+        draw_bg: {
+            color: (THEME_COLOR_BG_APP)
+        }
+
+        title = <Label> {
+            text: ""
+        }
+
+        tree = <FileTree> {}
+
+        filename_row = <View> {
+            width: Fill
+            height: Fit
+            filename = <TextInput> {
+                width: Fill
+                empty_message: "File name"
+            }
+        }
+
+        buttons = <View> {
+            width: Fill
+            height: Fit
+            align: {x: 1.0}
+            spacing: (THEME_SPACE_2)
+            cancel = <Button> {
+                text: "Cancel"
+            }
+            ok = <Button> {
+                text: "Ok"
+            }
+        }
+    }
+}
+
+struct FileChooserEntry {
+    node_id: LiveId,
+    name: String,
+    path: PathBuf,
+    is_folder: bool,
+}
+
+/// A file dialog drawn with makepad widgets, for systems without a native one.
+///
+/// It stays empty until an `Event::FileDialog` comes by with an `Unavailable` response, then it shows the
+/// requested dialog and answers with a new `Event::FileDialog` once the user picks something or cancels.
+/// Place it in an overlay where it can be seen on top of the rest of the ui.
+#[derive(Live, LiveHook, Widget)]
+pub struct FileChooser {
+    #[deref] view: View,
+    #[rust] request: Option<(FileDialogKind, FileDialog)>,
+    #[rust] root: PathBuf,
+    #[rust] listings: HashMap<PathBuf, Vec<FileChooserEntry>>,
+    #[rust] node_paths: HashMap<LiveId, (PathBuf, bool)>,
+    #[rust] selected: Option<(PathBuf, bool)>,
+}
+
+impl Widget for FileChooser {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if let Event::FileDialog(FileDialogEvent {kind, response: FileDialogResponse::Unavailable(settings)}) = event {
+            self.open(cx, *kind, settings.clone());
+            return
+        }
+        if self.request.is_none() {
+            return
+        }
+        let actions = cx.capture_actions( | cx | self.view.handle_event(cx, event, scope));
+        let tree = self.view.file_tree(id!(tree));
+        if let Some(node_id) = tree.file_clicked(&actions).or_else( | | tree.folder_clicked(&actions)) {
+            if let Some((path, is_folder)) = self.node_paths.get(&node_id).cloned() {
+                if !is_folder {
+                    if let Some(name) = path.file_name() {
+                        self.view.text_input(id!(filename)).set_text(cx, &name.to_string_lossy());
+                    }
+                }
+                self.selected = Some((path, is_folder));
+            }
+        }
+        if self.view.button(id!(cancel)).clicked(&actions) {
+            self.respond(cx, FileDialogResponse::Cancelled);
+        }
+        else if self.view.button(id!(ok)).clicked(&actions) || self.view.text_input(id!(filename)).returned(&actions).is_some() {
+            if let Some(path) = self.chosen_path() {
+                self.respond(cx, FileDialogResponse::Selected(vec![path]));
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        let Some((kind, settings)) = &self.request else {
+            return DrawStep::done()
+        };
+        let (kind, settings) = (*kind, settings.clone());
+        let tree = self.view.file_tree(id!(tree));
+        while let Some(next) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut tree) = tree.borrow_mut_if_eq(&next) {
+                let root_id = LiveId::from_str(&self.root.to_string_lossy());
+                tree.set_folder_is_open(cx, root_id, true, Animate::No);
+                let root_name = self.root.to_string_lossy().into_owned();
+                let root = self.root.clone();
+                self.node_paths.insert(root_id, (root.clone(), true));
+                if tree.begin_folder(cx, root_id, &root_name).is_ok() {
+                    self.draw_folder(cx, &mut tree, kind, &settings, &root);
+                    tree.end_folder();
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl FileChooser {
+    fn open(&mut self, cx: &mut Cx, kind: FileDialogKind, settings: FileDialog) {
+        self.root = settings.location.clone()
+            .or_else( | | std::env::current_dir().ok())
+            .unwrap_or_else( | | PathBuf::from("/"));
+        self.listings.clear();
+        self.node_paths.clear();
+        self.selected = None;
+        let title = settings.title.clone().unwrap_or_else( | | match kind {
+            FileDialogKind::SaveFile => "Save File",
+            FileDialogKind::SelectFile => "Open File",
+            FileDialogKind::SaveFolder => "Save Folder",
+            FileDialogKind::SelectFolder => "Open Folder",
+        }.to_string());
+        self.view.label(id!(title)).set_text(cx, &title);
+        self.view.view(id!(filename_row)).set_visible(cx, kind.is_save());
+        self.view.text_input(id!(filename)).set_text(cx, settings.filename.as_deref().unwrap_or(""));
+        self.view.button(id!(ok)).set_text(cx, if kind.is_save() {"Save"} else {"Open"});
+        let tree = self.view.file_tree(id!(tree));
+        if let Some(mut tree) = tree.borrow_mut() {
+            tree.forget();
+        }
+        self.request = Some((kind, settings));
+        self.view.redraw(cx);
+    }
+
+    fn respond(&mut self, cx: &mut Cx, response: FileDialogResponse) {
+        if let Some((kind, _)) = self.request.take() {
+            cx.send_file_dialog_event(FileDialogEvent {kind, response});
+        }
+        self.listings.clear();
+        self.node_paths.clear();
+        self.view.redraw(cx);
+    }
+
+    // the folder a save goes into, when a file is selected that is the folder holding it
+    fn selected_folder(&self) -> PathBuf {
+        match &self.selected {
+            Some((path, true)) => path.clone(),
+            Some((path, false)) => path.parent().map( | p | p.to_path_buf()).unwrap_or_else( | | self.root.clone()),
+            None => self.root.clone()
+        }
+    }
+
+    fn chosen_path(&self) -> Option<PathBuf> {
+        let (kind, _) = self.request.as_ref()?;
+        let filename = self.view.text_input(id!(filename)).text();
+        match kind {
+            FileDialogKind::SelectFile => match &self.selected {
+                Some((path, false)) => Some(path.clone()),
+                _ => None
+            }
+            FileDialogKind::SelectFolder => Some(self.selected_folder()),
+            FileDialogKind::SaveFile if filename.is_empty() => None,
+            FileDialogKind::SaveFolder if filename.is_empty() => Some(self.selected_folder()),
+            FileDialogKind::SaveFile | FileDialogKind::SaveFolder => Some(self.selected_folder().join(filename)),
+        }
+    }
+
+    fn draw_folder(&mut self, cx: &mut Cx2d, tree: &mut FileTree, kind: FileDialogKind, settings: &FileDialog, path: &Path) {
+        // directories are only read once they are opened
+        if !self.listings.contains_key(path) {
+            let entries = Self::read_folder(kind, settings, path);
+            for entry in &entries {
+                self.node_paths.insert(entry.node_id, (entry.path.clone(), entry.is_folder));
+            }
+            self.listings.insert(path.to_path_buf(), entries);
+        }
+        let children: Vec<_> = self.listings[path].iter().map( | entry | {
+            (entry.node_id, entry.name.clone(), entry.path.clone(), entry.is_folder)
+        }).collect();
+        for (node_id, name, path, is_folder) in children {
+            if is_folder {
+                if tree.begin_folder(cx, node_id, &name).is_ok() {
+                    self.draw_folder(cx, tree, kind, settings, &path);
+                    tree.end_folder();
+                }
+            }
+            else {
+                tree.file(cx, node_id, &name);
+            }
+        }
+    }
+
+    // folders first, then the files that pass the filters, both by name and without hidden entries
+    fn read_folder(kind: FileDialogKind, settings: &FileDialog, path: &Path) -> Vec<FileChooserEntry> {
+        let Ok(read_dir) = std::fs::read_dir(path) else {
+            return Vec::new()
+        };
+        let mut entries: Vec<_> = read_dir.filter_map( | entry | {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                return None
+            }
+            let path = entry.path();
+            let is_folder = path.is_dir();
+            if !is_folder && (kind.is_folder() || !settings.accepts(&path)) {
+                return None
+            }
+            Some(FileChooserEntry {
+                node_id: LiveId::from_str(&path.to_string_lossy()),
+                name,
+                path,
+                is_folder
+            })
+        }).collect();
+        entries.sort_by( | a, b | b.is_folder.cmp(&a.is_folder).then_with( | | a.name.cmp(&b.name)));
+        entries
+    }
+}
+
+impl FileChooserRef {
+    /// Whether a dialog is showing.
+    pub fn is_open(&self) -> bool {
+        self.borrow().is_some_and( | inner | inner.request.is_some())
+    }
+}
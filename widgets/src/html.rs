@@ -324,6 +324,11 @@ fn open_header_tag(cx: &mut Cx2d, tf: &mut TextFlow, scale: f64, trim: &mut Trim
                 trim_whitespace_in_text = TrimWhitespaceInText::Trim;
             }
             some_id!(code) => {
+                // `<code class="language-rust">` as produced by most markdown renderers
+                let language = node.find_attr_lc(live_id!(class))
+                    .and_then(|class| class.split_whitespace().find_map(|c| c.strip_prefix("language-")))
+                    .unwrap_or("");
+                tf.code_languages.push(language.to_string());
                 const FIXED_FONT_SIZE_SCALE: f64 = 0.85;
                 tf.push_size_rel_scale(FIXED_FONT_SIZE_SCALE);
                 //tf.top_drop.push(1.2/FIXED_FONT_SIZE_SCALE); // to achieve a top_drop of 1.2
@@ -490,7 +495,7 @@ fn handle_close_tag(
                 tf.end_quote(cx);
             }
             some_id!(code) => {
-                
+                tf.code_languages.pop();
                 tf.inline_code.pop();
                 //tf.top_drop.pop();
                 tf.font_sizes.pop();
@@ -536,7 +541,13 @@ pub fn handle_text_node(
             } else {
                 text
             };
-            tf.draw_text(cx, text);
+            match tf.code_languages.last() {
+                Some(language) if !language.is_empty() => {
+                    let language = language.clone();
+                    tf.draw_code(cx, &language, text);
+                }
+                _ => tf.draw_text(cx, text),
+            }
             true
         }
         else {
@@ -267,6 +267,9 @@ pub enum ImageError {
     /// The image data was in an unsupported format.
     /// Currently, only JPEG and PNG are supported.
     UnsupportedFormat,
+    /// The image could not be fetched from its URL.
+    /// The error message of the failed request is included.
+    RequestFailed(String),
 }
 
 impl Error for ImageError {}
@@ -317,6 +320,17 @@ fn load_jpg_from_data(&mut self, cx: &mut Cx, data: &[u8], id:usize) -> Result<(
         }
     }
     
+    /// Loads an image from `data`, detecting whether it is a PNG or a JPEG from its header.
+    fn load_image_from_data(&mut self, cx: &mut Cx, data: &[u8], id:usize) -> Result<(), ImageError> {
+        if data.starts_with(&[0x89, b'P', b'N', b'G']) {
+            self.load_png_from_data(cx, data, id)
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            self.load_jpg_from_data(cx, data, id)
+        } else {
+            Err(ImageError::UnsupportedFormat)
+        }
+    }
+    
     fn image_size_by_path(image_path:&Path)-> Result<(usize,usize), ImageError> {
         if let Ok(mut f) = File::open(image_path){
             let mut data = vec![0u8;1024]; // yolo chunk size
@@ -546,4 +560,64 @@ fn load_image_dep_by_path(
             }
         }
     }
+    
+    /// Starts loading the image at the given http(s) `url`.
+    ///
+    /// Returns `true` if the image was already cached, in which case its texture has been set.
+    /// Otherwise the image is requested with a request id of `LiveId::from_str(url)`, and the
+    /// response has to be passed to `process_image_url_response` when it arrives.
+    fn load_image_url(&mut self, cx: &mut Cx, url: &str, id: usize) -> bool {
+        match cx.get_global::<ImageCache>().map.get(Path::new(url)){
+            Some(ImageCacheEntry::Loaded(texture))=>{
+                let texture = texture.clone();
+                self.set_texture(Some(texture), id);
+                return true
+            }
+            // another widget already requested this url, we get the same response
+            Some(ImageCacheEntry::Loading(_,_))=>{
+                return false
+            }
+            None=>()
+        }
+        cx.get_global::<ImageCache>().map.insert(url.into(), ImageCacheEntry::Loading(0,0));
+        cx.http_request(LiveId::from_str(url), HttpRequest::new(url.to_string(), HttpMethod::GET));
+        false
+    }
+    
+    /// Processes the network `response` to a request started by `load_image_url`,
+    /// decoding the image and storing it in the image cache.
+    fn process_image_url_response(&mut self, cx: &mut Cx, url: &str, id: usize, response: &NetworkResponse) -> Result<(), ImageError> {
+        if let Some(ImageCacheEntry::Loaded(texture)) = cx.get_global::<ImageCache>().map.get(Path::new(url)){
+            let texture = texture.clone();
+            self.set_texture(Some(texture), id);
+            return Ok(())
+        }
+        let result = match response{
+            NetworkResponse::HttpResponse(res) if res.status_code == 200 =>{
+                match res.get_body(){
+                    Some(body)=>self.load_image_from_data(cx, body, id),
+                    None=>Err(ImageError::EmptyData)
+                }
+            }
+            NetworkResponse::HttpResponse(res)=>{
+                Err(ImageError::RequestFailed(format!("status code {}", res.status_code)))
+            }
+            NetworkResponse::HttpRequestError(err)=>{
+                Err(ImageError::RequestFailed(err.message.clone()))
+            }
+            _=>return Ok(())
+        };
+        match result{
+            Ok(())=>{
+                if let Some(texture) = self.get_texture(id).clone(){
+                    cx.get_global::<ImageCache>().map.insert(url.into(), ImageCacheEntry::Loaded(texture));
+                }
+            }
+            Err(ref err)=>{
+                error!("process_image_url_response: Cannot load image from url: {} {}", url, err);
+                cx.get_global::<ImageCache>().map.remove(Path::new(url));
+            }
+        }
+        result
+    }
 }
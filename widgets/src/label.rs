@@ -231,6 +231,10 @@ fn set_text(&mut self, cx:&mut Cx, v:&str){
         self.redraw(cx);
     }
 
+    fn accessibility_node(&self, _cx: &Cx) -> Option<AccessibilityNode> {
+        Some(AccessibilityNode::new(AccessibilityRole::Label).with_name(self.text.as_ref()))
+    }
+
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         let uid = self.widget_uid();
                 
@@ -42,6 +42,7 @@
 pub mod html;
 pub mod markdown;
 pub mod text_flow;
+pub mod code_highlight;
 pub mod multi_image;
 pub mod modal;
 pub mod tooltip;
@@ -57,6 +58,7 @@
 pub mod keyboard_view;
 pub mod flat_list;
 pub mod file_tree;
+pub mod file_chooser;
 pub mod slides_view;
 pub mod color_picker;
 pub mod root;
@@ -111,6 +113,7 @@
     slider::*,
     root::*,
     text_flow::*,
+    code_highlight::*,
     markdown::*,
     html::*,
     check_box::*,
@@ -228,12 +231,14 @@ pub fn live_design(cx: &mut Cx) {
     crate::dock::live_design(cx);
     crate::color_picker::live_design(cx);
     crate::file_tree::live_design(cx);
+    crate::file_chooser::live_design(cx);
     crate::slides_view::live_design(cx);
     crate::tab_close_button::live_design(cx);
     crate::keyboard_view::live_design(cx);
     crate::vectorline::live_design(cx);
     crate::stack_navigation::live_design(cx);
     crate::expandable_panel::live_design(cx);
+    crate::code_highlight::live_design(cx);
     crate::text_flow::live_design(cx);
     crate::markdown::live_design(cx);
     crate::html::live_design(cx);
@@ -4,14 +4,18 @@
     widget::*,
     text_flow::TextFlow,
     link_label::LinkLabel,
+    image::Image,
+    image_cache::ImageCacheImpl,
     WidgetMatchEvent,
 };
 
-use pulldown_cmark::{Event as MdEvent, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::path::Path;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event as MdEvent, HeadingLevel, Options, Parser, Tag, TagEnd};
 
 live_design!{
     link widgets;
     use link::theme::*;
+    use link::widgets::*;
     use makepad_draw::shader::std::*;
 
     pub MarkdownLinkBase = {{MarkdownLink}} {
@@ -23,6 +27,8 @@
         }
     }
 
+    pub MarkdownImageBase = {{MarkdownImage}} {}
+    
     pub MarkdownBase = {{Markdown}} {
         // ok so we can use one drawtext
         // change to italic, change bold (SDF), strikethrough
@@ -125,6 +131,13 @@ fn get_color(self) -> vec4 {
         }
     }
     
+    pub MarkdownImage = <MarkdownImageBase> {
+        image: <Image> {
+            width: Fit, height: Fit,
+            fit: Stretch,
+        }
+    }
+    
     pub Markdown = <MarkdownBase> {
         width:Fill, height:Fit,
         flow: RightWrap,
@@ -193,6 +206,17 @@ fn get_color(self) -> vec4 {
             height: Fit, width: Fill,
         }
         
+        table_layout: {
+            flow: Down,
+            padding: 1.
+        }
+        table_walk: { width: Fill, height: Fit }
+        table_row_layout: { flow: Right }
+        table_cell_layout: {
+            flow: RightWrap,
+            padding: <THEME_MSPACE_2> {}
+        }
+        
         sep_walk: {
             width: Fill, height: 4.
             margin: <THEME_MSPACE_V_1> {}
@@ -282,12 +306,77 @@ fn pixel(self) -> vec4 {
                         sdf.fill(self.line_color);
                         return sdf.result;
                     }
+                    FlowBlockType::Table => {
+                        sdf.box(
+                            0.5,
+                            0.5,
+                            self.rect_size.x - 1.,
+                            self.rect_size.y - 1.,
+                            2.
+                        );
+                        sdf.stroke(self.sep_color, 1.);
+                        return sdf.result;
+                    }
+                    FlowBlockType::TableHeader => {
+                        sdf.rect(
+                            0.,
+                            0.,
+                            self.rect_size.x,
+                            self.rect_size.y
+                        );
+                        sdf.fill(self.code_color);
+                        sdf.rect(
+                            0.,
+                            self.rect_size.y - 1.,
+                            self.rect_size.x,
+                            1.
+                        );
+                        sdf.fill(self.sep_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::TableRow => {
+                        sdf.rect(
+                            0.,
+                            self.rect_size.y - 1.,
+                            self.rect_size.x,
+                            1.
+                        );
+                        sdf.fill(self.sep_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::Checkbox => {
+                        sdf.box(
+                            1.,
+                            1.,
+                            self.rect_size.x - 2.,
+                            self.rect_size.y - 2.,
+                            2.
+                        );
+                        sdf.stroke(self.line_color, 1.);
+                        return sdf.result;
+                    }
+                    FlowBlockType::CheckboxChecked => {
+                        sdf.box(
+                            1.,
+                            1.,
+                            self.rect_size.x - 2.,
+                            self.rect_size.y - 2.,
+                            2.
+                        );
+                        sdf.stroke(self.line_color, 1.);
+                        sdf.move_to(self.rect_size.x * 0.25, self.rect_size.y * 0.5);
+                        sdf.line_to(self.rect_size.x * 0.45, self.rect_size.y * 0.7);
+                        sdf.line_to(self.rect_size.x * 0.75, self.rect_size.y * 0.3);
+                        sdf.stroke(self.line_color, 1.5);
+                        return sdf.result;
+                    }
                 }
                 return #f00
             }
         }
         
         link = <MarkdownLink> {}
+        image = <MarkdownImage> {}
     }
     
 } 
@@ -301,6 +390,7 @@ pub struct Markdown{
     #[live(false)] use_code_block_widget:bool,
     #[rust] in_code_block: bool,
     #[rust] code_block_string: String,
+    #[rust] code_block_language: Option<String>,
     #[rust] auto_id: u64
 }
 
@@ -334,10 +424,24 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
         let tf = &mut self.text_flow;
         // Track state for nested formatting
         let mut list_stack = Vec::new();
+        let mut table = MarkdownTable::default();
+        let mut table_shares = Vec::new();
+        let mut table_column = 0;
+        let mut in_image = 0;
 
-        let parser = Parser::new_ext(self.body.as_ref(), Options::ENABLE_TABLES);        
+        // We collect the events so we can look ahead for task list markers and table columns
+        let events = parse_markdown(self.body.as_ref());
         
-        for event in parser.into_iter() {
+        for (index, event) in events.iter().cloned().enumerate() {
+            // Skip the alt text of images
+            if in_image > 0 {
+                match event {
+                    MdEvent::Start(Tag::Image { .. }) => in_image += 1,
+                    MdEvent::End(TagEnd::Image) => in_image -= 1,
+                    _ => {}
+                }
+                continue;
+            }
             match event {
                 MdEvent::Start(Tag::Heading { level, .. }) => {
                     cx.turtle_new_line_with_spacing(self.paragraph_spacing);
@@ -378,12 +482,16 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
                 }
                 MdEvent::Start(Tag::Item) => {
                     cx.turtle_new_line();
-                    let marker = if let Some(Some(n)) = list_stack.last() {
-                        format!("{}.", n)
+                    if let Some(checked) = task_list_marker(&events, index) {
+                        tf.begin_task_list_item(cx, checked, 1.5);
                     } else {
-                        "•".to_string()
-                    };
-                    tf.begin_list_item(cx, &marker, 1.5);
+                        let marker = if let Some(Some(n)) = list_stack.last() {
+                            format!("{}.", n)
+                        } else {
+                            "•".to_string()
+                        };
+                        tf.begin_list_item(cx, &marker, 1.5);
+                    }
                 }
                 MdEvent::End(TagEnd::Item) => {
                     tf.end_list_item(cx);
@@ -415,23 +523,27 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
                 MdEvent::End(TagEnd::Link) => {
                     // Link handling is done in Start event
                 }
-                MdEvent::Start(Tag::Image { dest_url, title, .. }) => {
-                    tf.draw_text(cx, "Image[name:");
-                    tf.draw_text(cx, &title);
-                    tf.draw_text(cx, ", url:");
-                    tf.draw_text(cx, &dest_url);
-                    tf.draw_text(cx, "]");
+                MdEvent::Start(Tag::Image { dest_url, .. }) => {
+                    self.auto_id += 1;
+                    let item = tf.item(cx, LiveId(self.auto_id), live_id!(image));
+                    item.as_markdown_image().set_src(cx, &dest_url);
+                    item.draw_all_unscoped(cx);
+                    in_image = 1;
                 }
-                MdEvent::Start(Tag::CodeBlock(_kind)) => {
+                MdEvent::Start(Tag::CodeBlock(kind)) => {
                     if self.use_code_block_widget {
                         self.in_code_block = true;
                         self.code_block_string.clear();
                         cx.turtle_new_line_with_spacing(self.pre_code_spacing);
-                        
-                        // TODO: Handle language info if available for syntax highlighting
-                        // if let CodeBlockKind::Fenced(lang) = kind {
-                        // }
                     } else {
+                        // Fenced code blocks with a language are collected and highlighted at the end
+                        if let CodeBlockKind::Fenced(lang) = kind {
+                            if !lang.is_empty() {
+                                self.code_block_language = Some(lang.to_string());
+                                self.code_block_string.clear();
+                            }
+                        }
+
                         const FIXED_FONT_SIZE_SCALE: f64 = 0.85;
                         tf.push_size_rel_scale(FIXED_FONT_SIZE_SCALE);
                         // alright lets check if we need to use a widget
@@ -457,6 +569,9 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
                         });
                     }
                     else{
+                        if let Some(lang) = self.code_block_language.take() {
+                            tf.draw_code(cx, &lang, &self.code_block_string);
+                        }
                         tf.font_sizes.pop();
                         //tf.top_drop.pop();
                         tf.fixed.pop();
@@ -476,7 +591,7 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
                     tf.inline_code.pop();
                 }
                 MdEvent::Text(text) => {
-                    if self.in_code_block {
+                    if self.in_code_block || self.code_block_language.is_some() {
                         self.code_block_string.push_str(&text);
                     } else {
                         tf.draw_text(cx, &text);
@@ -501,31 +616,41 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
                     tf.sep(cx);
                 }
                 MdEvent::TaskListMarker(_) => {
-                    // TODO: Implement task list markers
+                    // Task list markers are drawn by the list item
                 }
                 MdEvent::Start(Tag::Table(_)) => {
-                    // TODO: Implement table support
+                    cx.turtle_new_line_with_spacing(self.paragraph_spacing);
+                    table = MarkdownTable::from_events(&events, index);
+                    table_shares = table.column_shares();
+                    tf.begin_table(cx);
                 }
                 MdEvent::End(TagEnd::Table) => {
-                    // TODO: Implement table support
+                    tf.end_table(cx);
                 }
                 MdEvent::Start(Tag::TableHead) => {
-                    // TODO: Implement table header support
+                    table_column = 0;
+                    tf.bold.push();
+                    tf.begin_table_row(cx, true);
                 }
                 MdEvent::End(TagEnd::TableHead) => {
-                    // TODO: Implement table header support
+                    tf.end_table_row(cx);
+                    tf.bold.pop();
                 }
                 MdEvent::Start(Tag::TableRow) => {
-                    // TODO: Implement table row support
+                    table_column = 0;
+                    tf.begin_table_row(cx, false);
                 }
                 MdEvent::End(TagEnd::TableRow) => {
-                    // TODO: Implement table row support
+                    tf.end_table_row(cx);
                 }
                 MdEvent::Start(Tag::TableCell) => {
-                    // TODO: Implement table cell support
+                    let share = table_shares.get(table_column).copied().unwrap_or(0.0);
+                    let align = table.aligns.get(table_column).copied().unwrap_or(0.0);
+                    tf.begin_table_cell(cx, share, align);
                 }
                 MdEvent::End(TagEnd::TableCell) => {
-                    // TODO: Implement table cell support
+                    tf.end_table_cell(cx);
+                    table_column += 1;
                 }
                 _ => {} // Unimplemented or unneceary events
             }
@@ -533,6 +658,88 @@ fn process_markdown_doc(&mut self, cx: &mut Cx2d) {
     }
 }
 
+/// Parses `body` with the extensions that the [`Markdown`] widget renders.
+pub fn parse_markdown(body: &str) -> Vec<MdEvent<'_>> {
+    Parser::new_ext(body, Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS).collect()
+}
+
+/// Returns whether the list item that starts at `events[item]` is a checked or unchecked
+/// task, or `None` if it is a plain list item or no list item starts there.
+pub fn task_list_marker(events: &[MdEvent], item: usize) -> Option<bool> {
+    let Some(MdEvent::Start(Tag::Item)) = events.get(item) else {
+        return None
+    };
+    // The task list marker directly follows the item, or its first paragraph
+    match (events.get(item + 1), events.get(item + 2)) {
+        (Some(MdEvent::TaskListMarker(checked)), _) |
+        (Some(MdEvent::Start(Tag::Paragraph)), Some(MdEvent::TaskListMarker(checked))) => Some(*checked),
+        _ => None
+    }
+}
+
+/// The columns of a markdown table, gathered before the table is drawn so that
+/// they can be sized by their content.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarkdownTable {
+    /// The horizontal alignment of each column, from 0.0 (left) to 1.0 (right).
+    pub aligns: Vec<f64>,
+    /// The length in chars of the longest cell of each column.
+    pub content_lens: Vec<usize>,
+}
+
+impl MarkdownTable {
+    /// Columns are never narrower than this many chars, so short columns stay readable.
+    const MIN_COLUMN_LEN: usize = 6;
+    /// Cells longer than this many chars wrap anyway, so they don't claim more width.
+    const MAX_COLUMN_LEN: usize = 40;
+
+    /// Gathers the columns of the table that starts at `events[start]`.
+    ///
+    /// The parser pads short rows with empty cells and drops the cells of long rows,
+    /// so every row has one cell per column.
+    pub fn from_events(events: &[MdEvent], start: usize) -> Self {
+        let Some(MdEvent::Start(Tag::Table(alignments))) = events.get(start) else {
+            return Self::default()
+        };
+        let mut table = Self {
+            aligns: alignments.iter().map(|alignment| match alignment {
+                Alignment::Center => 0.5,
+                Alignment::Right => 1.0,
+                Alignment::Left | Alignment::None => 0.0,
+            }).collect(),
+            content_lens: vec![0; alignments.len()],
+        };
+        let mut column = 0;
+        let mut len = 0;
+        for event in &events[start + 1..] {
+            match event {
+                MdEvent::End(TagEnd::Table) => break,
+                MdEvent::Start(Tag::TableHead) | MdEvent::Start(Tag::TableRow) => column = 0,
+                MdEvent::Start(Tag::TableCell) => len = 0,
+                MdEvent::Text(text) | MdEvent::Code(text) => len += text.chars().count(),
+                MdEvent::End(TagEnd::TableCell) => {
+                    if let Some(content_len) = table.content_lens.get_mut(column) {
+                        *content_len = (*content_len).max(len);
+                    }
+                    column += 1;
+                }
+                _ => {}
+            }
+        }
+        table
+    }
+
+    /// Returns the width of each column as a share of the width of the table,
+    /// in proportion to the length of its content.
+    pub fn column_shares(&self) -> Vec<f64> {
+        let lens: Vec<f64> = self.content_lens.iter().map(|len| {
+            (*len).clamp(Self::MIN_COLUMN_LEN, Self::MAX_COLUMN_LEN) as f64
+        }).collect();
+        let total: f64 = lens.iter().sum();
+        lens.iter().map(|len| len / total).collect()
+    }
+}
+
 impl MarkdownRef {
     pub fn set_text(&mut self, cx:&mut Cx, v:&str) {
         let Some(mut inner) = self.borrow_mut() else { return };
@@ -593,4 +800,71 @@ pub enum MarkdownAction {
     None,
     LinkNavigated(String),
 }
- 
\ No newline at end of file
+ 
+
+#[derive(Live, LiveHook, Widget)]
+struct MarkdownImage {
+    #[redraw]
+    #[live]
+    image: Image,
+    #[rust]
+    src: String,
+}
+
+impl Widget for MarkdownImage {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.image.handle_event(cx, event, scope);
+        if let Event::NetworkResponses(responses) = event {
+            for item in responses {
+                if item.request_id == LiveId::from_str(&self.src)
+                    && self.image.process_image_url_response(cx, &self.src, 0, &item.response).is_ok()
+                {
+                    self.image.redraw(cx);
+                }
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, _walk: Walk) -> DrawStep {
+        // Draw the image at its own size, scaled down to fit the available width
+        let mut walk = Walk::fit();
+        if let Some((width, height)) = self.image.size_in_pixels(cx) {
+            let (width, height) = (width as f64, height as f64);
+            let max_width = cx.turtle().padded_rect().size.x;
+            let scale = if max_width < width { max_width / width } else { 1.0 };
+            walk.width = Size::Fixed(width * scale);
+            walk.height = Size::Fixed(height * scale);
+        } else {
+            walk.width = Size::Fixed(0.0);
+            walk.height = Size::Fixed(0.0);
+        }
+        self.image.draw_walk(cx, walk)
+    }
+}
+
+impl MarkdownImage {
+    fn set_src(&mut self, cx: &mut Cx, src: &str) {
+        if self.src == src {
+            return;
+        }
+        self.src = src.to_string();
+        self.image.lazy_create_image_cache(cx);
+        // Errors are logged by the image cache, the image is simply not shown
+        if src.starts_with("http://") || src.starts_with("https://") {
+            self.image.load_image_url(cx, src, 0);
+        } else if src.starts_with("crate://") {
+            let _ = self.image.load_image_dep_by_path(cx, src, 0);
+        } else {
+            let _ = self.image.load_image_file_by_path(cx, Path::new(src), 0);
+        }
+    }
+}
+
+impl MarkdownImageRef {
+    pub fn set_src(&self, cx: &mut Cx, src: &str) {
+        let Some(mut inner) = self.borrow_mut() else {
+            return;
+        };
+        inner.set_src(cx, src);
+    }
+}
@@ -1008,6 +1008,11 @@ fn draw_walk(&mut self, cx: &mut Cx2d, _scope:&mut Scope, walk: Walk) -> DrawSte
         }
         DrawStep::done()
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        Some(AccessibilityNode::new(AccessibilityRole::List)
+            .with_focused(cx.has_key_focus(self.area)))
+    }
 }
 
 impl PortalListRef {
@@ -446,6 +446,13 @@ fn set_text(&mut self, cx:&mut Cx, v: &str) {
         self.text.as_mut_empty().push_str(v);
         self.redraw(cx);
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        Some(AccessibilityNode::new(AccessibilityRole::RadioButton)
+            .with_name(self.text.as_ref())
+            .with_checked(self.animator_in_state(cx, id!(selected.on)))
+            .with_action(AccessibilityAction::Click))
+    }
 }
 
 impl RadioButtonRef{
@@ -1876,6 +1876,22 @@ fn set_text(&mut self, cx:&mut Cx, v: &str) {
             self.update_text_input(cx);
         }
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        Some(AccessibilityNode::new(AccessibilityRole::Slider)
+            .with_name(&self.text)
+            .with_value(&self.text_input.text)
+            .with_range(AccessibilityRange {
+                value: self.to_external(),
+                min: self.min,
+                max: self.max,
+                step: self.step,
+            })
+            .with_focused(cx.has_key_focus(self.text_input.area()))
+            .with_action(AccessibilityAction::SetValue)
+            .with_action(AccessibilityAction::Increment)
+            .with_action(AccessibilityAction::Decrement))
+    }
         
 }
 
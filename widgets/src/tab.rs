@@ -255,6 +255,17 @@ pub fn draw(&mut self, cx: &mut Cx2d, name: &str) {
     pub fn area(&self) -> Area {
         self.draw_bg.area()
     }
+
+    pub fn accessibility_node(&self, name: &str) -> AccessibilityNode {
+        let mut node = AccessibilityNode::new(AccessibilityRole::Tab)
+            .with_name(name)
+            .with_selected(self.is_selected)
+            .with_action(AccessibilityAction::Select);
+        if self.closeable {
+            node = node.with_action(AccessibilityAction::Close);
+        }
+        node
+    }
     
     pub fn handle_event_with(
         &mut self,
@@ -208,6 +208,8 @@ pub fn begin(&mut self, cx: &mut Cx2d, selected_tab: Option<usize>, walk:Walk) {
         // }
         self.scroll_bars.begin(cx, walk, Layout::flow_right());
         self.tab_order.clear();
+        let node = AccessibilityNode::new(AccessibilityRole::TabList);
+        cx.begin_accessibility_node(AccessibilityId(self.widget_uid().0), Some(node));
     }
     
     pub fn end(&mut self, cx: &mut Cx2d) {
@@ -224,6 +226,7 @@ pub fn end(&mut self, cx: &mut Cx2d) {
         self.tabs.retain_visible();
         self.draw_fill.draw_walk(cx, Walk::size(Size::Fill, Size::Fill));
         self.scroll_bars.end(cx);
+        cx.end_accessibility_node(AccessibilityId(self.widget_uid().0), self.scroll_bars.area());
     }
     
     pub fn draw_tab(&mut self, cx: &mut Cx2d, tab_id: LiveId, name: &str, template:LiveId) {
@@ -237,6 +240,7 @@ pub fn draw_tab(&mut self, cx: &mut Cx2d, tab_id: LiveId, name: &str, template:L
                 tab.set_is_selected(cx, false, Animate::No);
             }
             tab.draw(cx, name);
+            cx.add_accessibility_node(AccessibilityId(tab_id.0), tab.accessibility_node(name), tab.area());
             if tab_order_len == selected_tab {
                 self.selected_tab_id = Some(tab_id);
             }
@@ -246,6 +250,7 @@ pub fn draw_tab(&mut self, cx: &mut Cx2d, tab_id: LiveId, name: &str, template:L
             self.tab_order.push(tab_id);
             let tab = self.get_or_create_tab(cx, tab_id, template);
             tab.draw(cx, name);
+            cx.add_accessibility_node(AccessibilityId(tab_id.0), tab.accessibility_node(name), tab.area());
         }
     }
     
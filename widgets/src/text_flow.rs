@@ -2,6 +2,7 @@
     makepad_derive_widget::*,
     makepad_draw::*,
     widget::*,
+    code_highlight::{CodeColors, CodeTokenizers},
 }; 
     
 live_design!{
@@ -135,6 +136,17 @@
             height: Fit, width: Fill,
         }
                 
+        table_layout: {
+            flow: Down,
+            padding: 1.
+        }
+        table_walk: { width: Fill, height: Fit }
+        table_row_layout: { flow: Right }
+        table_cell_layout: {
+            flow: RightWrap,
+            padding: <THEME_MSPACE_2> {}
+        }
+                
         inline_code_padding: <THEME_MSPACE_1> {},
         inline_code_margin: <THEME_MSPACE_1> {},
                 
@@ -228,6 +240,70 @@ fn pixel(self) -> vec4 {
                         sdf.fill(self.line_color);
                         return sdf.result;
                     }
+                    FlowBlockType::Table => {
+                        sdf.box(
+                            0.5,
+                            0.5,
+                            self.rect_size.x - 1.,
+                            self.rect_size.y - 1.,
+                            2.
+                        );
+                        sdf.stroke(self.sep_color, 1.);
+                        return sdf.result;
+                    }
+                    FlowBlockType::TableHeader => {
+                        sdf.rect(
+                            0.,
+                            0.,
+                            self.rect_size.x,
+                            self.rect_size.y
+                        );
+                        sdf.fill(self.code_color);
+                        sdf.rect(
+                            0.,
+                            self.rect_size.y - 1.,
+                            self.rect_size.x,
+                            1.
+                        );
+                        sdf.fill(self.sep_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::TableRow => {
+                        sdf.rect(
+                            0.,
+                            self.rect_size.y - 1.,
+                            self.rect_size.x,
+                            1.
+                        );
+                        sdf.fill(self.sep_color);
+                        return sdf.result;
+                    }
+                    FlowBlockType::Checkbox => {
+                        sdf.box(
+                            1.,
+                            1.,
+                            self.rect_size.x - 2.,
+                            self.rect_size.y - 2.,
+                            2.
+                        );
+                        sdf.stroke(self.line_color, 1.);
+                        return sdf.result;
+                    }
+                    FlowBlockType::CheckboxChecked => {
+                        sdf.box(
+                            1.,
+                            1.,
+                            self.rect_size.x - 2.,
+                            self.rect_size.y - 2.,
+                            2.
+                        );
+                        sdf.stroke(self.line_color, 1.);
+                        sdf.move_to(self.rect_size.x * 0.25, self.rect_size.y * 0.5);
+                        sdf.line_to(self.rect_size.x * 0.45, self.rect_size.y * 0.7);
+                        sdf.line_to(self.rect_size.x * 0.75, self.rect_size.y * 0.3);
+                        sdf.stroke(self.line_color, 1.5);
+                        return sdf.result;
+                    }
                 }
                 return #f00
             }
@@ -244,7 +320,12 @@ pub enum FlowBlockType {
     Code = shader_enum(3),
     InlineCode = shader_enum(4),
     Underline = shader_enum(5),
-    Strikethrough = shader_enum(6)
+    Strikethrough = shader_enum(6),
+    Table = shader_enum(7),
+    TableHeader = shader_enum(8),
+    TableRow = shader_enum(9),
+    Checkbox = shader_enum(10),
+    CheckboxChecked = shader_enum(11)
 }
 
 #[derive(Live, LiveHook, LiveRegister)]
@@ -301,6 +382,8 @@ pub struct TextFlow {
     #[rust] pub top_drop: SmallVec<[f64;4]>,
     #[rust] pub combine_spaces: SmallVec<[bool;4]>,
     #[rust] pub ignore_newlines: SmallVec<[bool;4]>,
+    /// The languages of the code elements we are in, empty if not known.
+    #[rust] pub code_languages: SmallVec<[String;2]>,
     #[rust] pub bold: StackCounter,
     #[rust] pub italic: StackCounter,
     #[rust] pub fixed: StackCounter,
@@ -309,6 +392,9 @@ pub struct TextFlow {
     #[rust] pub inline_code: StackCounter,
         
     #[rust] pub item_counter: u64,
+    /// Set while drawing the tokens of `draw_code`, which share one set of inline code margins.
+    #[rust] in_code_tokens: bool,
+    #[rust] table_cell_aligned: bool,
     
     #[rust] pub areas_tracker: RectAreasTracker,
     
@@ -321,8 +407,14 @@ pub struct TextFlow {
     #[live] sep_walk: Walk, 
     #[live] list_item_layout: Layout,
     #[live] list_item_walk: Walk,
+    #[live] table_layout: Layout,
+    #[live] table_walk: Walk,
+    #[live] table_row_layout: Layout,
+    #[live] table_cell_layout: Layout,
     #[live] pub inline_code_padding: Padding,
     #[live] pub inline_code_margin: Margin,
+    /// The colors used to syntax highlight code blocks with a known language.
+    #[live] pub code_colors: CodeColors,
         
     #[redraw] #[rust] area:Area,
     #[rust] draw_state: DrawStateWrap<DrawState>,
@@ -455,6 +547,7 @@ fn clear_stacks(&mut self){
         self.top_drop.clear();
         self.combine_spaces.clear();
         self.ignore_newlines.clear();
+        self.code_languages.clear();
     }
     
         
@@ -490,8 +583,7 @@ pub fn end_code(&mut self, cx:&mut Cx2d){
         self.draw_block.end(cx);
     }
     
-    pub fn begin_list_item(&mut self, cx:&mut Cx2d, dot:&str, pad:f64){
-        // alright we are going to push a block with a layout and a walk
+    fn begin_list_item_turtle(&mut self, cx:&mut Cx2d, pad:f64)->f64{
         let fs = self.font_sizes.last().unwrap_or(&self.font_size);
         self.draw_normal.text_style.font_size = *fs;
         let fc = self.font_colors.last().unwrap_or(&self.font_color);
@@ -504,6 +596,12 @@ pub fn begin_list_item(&mut self, cx:&mut Cx2d, dot:&str, pad:f64){
             },
             ..self.list_item_layout
         });
+        pad
+    }
+    
+    pub fn begin_list_item(&mut self, cx:&mut Cx2d, dot:&str, pad:f64){
+        // alright we are going to push a block with a layout and a walk
+        let pad = self.begin_list_item_turtle(cx, pad);
         // lets draw the 'marker' at -x 
         // lets get the turtle position and abs draw 
         
@@ -528,6 +626,78 @@ pub fn end_list_item(&mut self, cx:&mut Cx2d){
         cx.end_turtle();
     }
     
+    /// Begins a task list item, drawing a (checked) checkbox as its marker.
+    /// Ends with `end_list_item`.
+    pub fn begin_task_list_item(&mut self, cx:&mut Cx2d, checked:bool, pad:f64){
+        let pad = self.begin_list_item_turtle(cx, pad);
+        let size = self.draw_normal.get_font_size();
+        let pos = cx.turtle().pos() - dvec2(pad, -size * 0.25);
+        self.draw_block.line_color = self.draw_normal.color;
+        self.draw_block.block_type = if checked{
+            FlowBlockType::CheckboxChecked
+        }
+        else{
+            FlowBlockType::Checkbox
+        };
+        self.draw_block.draw_abs(cx, Rect{pos, size: dvec2(size, size)});
+    }
+    
+    pub fn begin_table(&mut self, cx:&mut Cx2d){
+        self.draw_block.block_type = FlowBlockType::Table;
+        self.draw_block.begin(cx, self.table_walk, self.table_layout);
+        self.area_stack.push(self.draw_block.draw_vars.area);
+    }
+    
+    pub fn end_table(&mut self, cx:&mut Cx2d){
+        self.draw_block.draw_vars.area = self.area_stack.pop().unwrap();
+        self.draw_block.end(cx);
+    }
+    
+    pub fn begin_table_row(&mut self, cx:&mut Cx2d, is_header:bool){
+        self.draw_block.block_type = if is_header{
+            FlowBlockType::TableHeader
+        }
+        else{
+            FlowBlockType::TableRow
+        };
+        self.draw_block.begin(cx, Walk::fill_fit(), self.table_row_layout);
+        self.area_stack.push(self.draw_block.draw_vars.area);
+    }
+    
+    pub fn end_table_row(&mut self, cx:&mut Cx2d){
+        self.draw_block.draw_vars.area = self.area_stack.pop().unwrap();
+        self.draw_block.end(cx);
+    }
+    
+    /// Begins a table cell that takes up `share` of the row's width, from 0.0 to 1.0.
+    /// `align` is the horizontal alignment of the cell's content, from 0.0 (left) to 1.0 (right).
+    pub fn begin_table_cell(&mut self, cx:&mut Cx2d, share:f64, align:f64){
+        let width = cx.turtle().padded_rect().size.x * share;
+        let width = if width.is_nan(){Size::Fit}else{Size::Fixed(width)};
+        let walk = Walk{width, height: Size::Fit, ..Walk::default()};
+        // RightWrap turtles don't support alignment, so aligned content goes
+        // in a Fit turtle that is aligned by the cell instead
+        self.table_cell_aligned = align != 0.0;
+        if self.table_cell_aligned{
+            cx.begin_turtle(walk, Layout{
+                flow: Flow::Right,
+                align: Align{x: align, y: 0.0},
+                ..self.table_cell_layout
+            });
+            cx.begin_turtle(Walk::fit(), Layout::flow_right());
+        }
+        else{
+            cx.begin_turtle(walk, self.table_cell_layout);
+        }
+    }
+    
+    pub fn end_table_cell(&mut self, cx:&mut Cx2d){
+        if self.table_cell_aligned{
+            cx.end_turtle();
+        }
+        cx.end_turtle();
+    }
+    
     pub fn sep(&mut self, cx:&mut Cx2d){
         self.draw_block.block_type = FlowBlockType::Sep;
         self.draw_block.draw_walk(cx, self.sep_walk);
@@ -686,16 +856,20 @@ pub fn draw_text(&mut self, cx:&mut Cx2d, text:&str){
             if self.inline_code.value() > 0{
                 let db = &mut self.draw_block;
                 db.block_type = FlowBlockType::InlineCode;
-                let rect = TextFlow::walk_margin(cx, self.inline_code_margin.left);
-                areas_tracker.track_rect(cx, rect);
+                if !self.in_code_tokens{
+                    let rect = TextFlow::walk_margin(cx, self.inline_code_margin.left);
+                    areas_tracker.track_rect(cx, rect);
+                }
                 dt.draw_walk_resumable_with(cx, text, |cx, mut rect|{
                     rect.pos -= self.inline_code_padding.left_top();
                     rect.size += self.inline_code_padding.size();
                     db.draw_abs(cx, rect);
                     areas_tracker.track_rect(cx, rect);
                 });
-                let rect = TextFlow::walk_margin(cx, self.inline_code_margin.right);
-                areas_tracker.track_rect(cx, rect);
+                if !self.in_code_tokens{
+                    let rect = TextFlow::walk_margin(cx, self.inline_code_margin.right);
+                    areas_tracker.track_rect(cx, rect);
+                }
             }
             else if self.strikethrough.value() > 0{
                 let db = &mut self.draw_block;
@@ -723,6 +897,47 @@ pub fn draw_text(&mut self, cx:&mut Cx2d, text:&str){
         }
     }
     
+    /// Draws `code` syntax highlighted with the tokenizer registered for `language`,
+    /// or as plain text if there is none.
+    pub fn draw_code(&mut self, cx:&mut Cx2d, language:&str, code:&str){
+        let Some(tokens) = CodeTokenizers::tokenize(cx, language, code) else {
+            self.draw_text(cx, code);
+            return
+        };
+        let inline_code = self.inline_code.value() > 0;
+        if inline_code{
+            let rect = TextFlow::walk_margin(cx, self.inline_code_margin.left);
+            self.areas_tracker.track_rect(cx, rect);
+        }
+        self.in_code_tokens = true;
+        let mut start: usize = 0;
+        for token in tokens{
+            // any tokenizer can be registered, so a token may run past the code or end
+            // inside a char, in which case we stop trusting the tokens
+            let Some(text) = start.checked_add(token.len).and_then(|end| code.get(start..end)) else{
+                break
+            };
+            start += token.len;
+            if let Some(color) = self.code_colors.color(token.kind){
+                self.font_colors.push(color);
+                self.draw_text(cx, text);
+                self.font_colors.pop();
+            }
+            else{
+                self.draw_text(cx, text);
+            }
+        }
+        // whatever the tokens didn't cover is drawn as plain text
+        if start < code.len(){
+            self.draw_text(cx, &code[start..]);
+        }
+        self.in_code_tokens = false;
+        if inline_code{
+            let rect = TextFlow::walk_margin(cx, self.inline_code_margin.right);
+            self.areas_tracker.track_rect(cx, rect);
+        }
+    }
+    
     pub fn walk_margin(cx:&mut Cx2d, margin:f64)->Rect{
         cx.walk_turtle(Walk{
             width: Size::Fixed(margin),
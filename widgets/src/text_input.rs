@@ -660,7 +660,8 @@ fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
                 was_paste,
             }) if !self.is_read_only => {
                 let input = self.filter_input(input, false);
-                if !input.is_empty() {
+                // an empty replacement removes text an input method was composing
+                if !input.is_empty() || replace_last {
                     let mut start = self.cursor.start().index;
                     let end = self.cursor.end().index;
                     if replace_last {
@@ -842,6 +843,21 @@ fn set_text(&mut self, cx:&mut Cx, text: &str) {
         self.history.clear();
         self.redraw(cx);
     }
+
+    fn accessibility_node(&self, cx: &Cx) -> Option<AccessibilityNode> {
+        let mut node = AccessibilityNode::new(AccessibilityRole::TextInput)
+            .with_name(&self.empty_message)
+            .with_focused(cx.has_key_focus(self.draw_bg.area()))
+            .with_action(AccessibilityAction::Focus);
+        // secret text is never handed to assistive technology
+        if !self.draw_text.text_style.is_secret {
+            node = node.with_value(&self.text);
+        }
+        if !self.is_read_only {
+            node = node.with_action(AccessibilityAction::SetValue);
+        }
+        Some(node)
+    }
 }
 
 /// The saved (checkpointed) state of a text input widget.
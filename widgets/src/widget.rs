@@ -117,6 +117,12 @@ fn text(&self) -> String {
 
     fn set_text(&mut self, _cx:&mut Cx, _v: &str) {}
 
+    /// The node this widget adds to the accessibility tree while it draws. Widgets without one are
+    /// transparent, the nodes of their children go to the nearest node around them.
+    fn accessibility_node(&self, _cx: &Cx) -> Option<AccessibilityNode> {
+        None
+    }
+
     /*fn set_text_and_redraw(&mut self, cx: &mut Cx, v: &str) {
         self.set_text(v);
         self.redraw(cx);
@@ -211,6 +217,19 @@ pub struct WidgetRegistry {
 pub struct WidgetRefInner {
     pub widget: Box<dyn Widget>,
 }
+
+impl WidgetRefInner {
+    fn begin_accessibility(&self, cx: &mut Cx) -> AccessibilityId {
+        let id = AccessibilityId(self.widget.widget_uid().0);
+        let node = self.widget.accessibility_node(cx);
+        cx.begin_accessibility_node(id, node);
+        id
+    }
+
+    fn end_accessibility(&self, cx: &mut Cx, id: AccessibilityId) {
+        cx.end_accessibility_node(id, self.widget.area());
+    }
+}
 #[derive(Clone, Default)]
 pub struct WidgetRef(Rc<RefCell<Option<WidgetRefInner>>>);
 
@@ -531,44 +550,54 @@ pub fn widget_set(&self, paths: &[&[LiveId]]) -> WidgetSet {
         WidgetSet::default()
     }
     
+    // a widget that returns a draw step keeps its accessibility node open, the step is drawn into it
+    // before drawing resumes
     pub fn draw_walk(&self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
+            let id = inner.begin_accessibility(cx);
             if let Some(nd) = inner.widget.draw_walk(cx, scope, walk).step() {
                 if nd.is_empty() {
                     return DrawStep::make_step_here(self.clone());
                 }
                 return DrawStep::make_step_here(nd);
             }
+            inner.end_accessibility(cx, id);
         }
         DrawStep::done()
     }
 
     pub fn draw_walk_all(&self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
-            inner.widget.draw_walk_all(cx, scope, walk)
+            let id = inner.begin_accessibility(cx);
+            inner.widget.draw_walk_all(cx, scope, walk);
+            inner.end_accessibility(cx, id);
         }
     }
 
     pub fn draw(&mut self, cx: &mut Cx2d, scope: &mut Scope) -> DrawStep {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
+            let id = inner.begin_accessibility(cx);
             if let Some(nd) = inner.widget.draw(cx, scope).step() {
                 if nd.is_empty() {
                     return DrawStep::make_step_here(self.clone());
                 }
                 return DrawStep::make_step_here(nd);
             }
+            inner.end_accessibility(cx, id);
         }
         DrawStep::done()
     }
     
     pub fn draw_unscoped(&mut self, cx: &mut Cx2d) -> DrawStep {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
+            let id = inner.begin_accessibility(cx);
             if let Some(nd) = inner.widget.draw(cx, &mut Scope::empty()).step() {
                 if nd.is_empty() {
                     return DrawStep::make_step_here(self.clone());
                 }
                 return DrawStep::make_step_here(nd);
             }
+            inner.end_accessibility(cx, id);
         }
         DrawStep::done()
     }
@@ -596,7 +625,9 @@ pub fn is_visible(&self) -> bool {
 
     pub fn draw_all(&self, cx: &mut Cx2d, scope: &mut Scope) {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
-            return inner.widget.draw_all(cx, scope);
+            let id = inner.begin_accessibility(cx);
+            inner.widget.draw_all(cx, scope);
+            inner.end_accessibility(cx, id);
         }
     }
     
@@ -613,7 +644,9 @@ pub fn filter_actions<'a>(&'a self, actions:&'a Actions)-> impl Iterator<Item =
     
     pub fn draw_all_unscoped(&self, cx: &mut Cx2d) {
         if let Some(inner) = self.0.borrow_mut().as_mut() {
-            return inner.widget.draw_all_unscoped(cx);
+            let id = inner.begin_accessibility(cx);
+            inner.widget.draw_all_unscoped(cx);
+            inner.end_accessibility(cx, id);
         }
     }
 
@@ -416,4 +416,8 @@ fn draw_walk(&mut self, cx: &mut Cx2d, scope:&mut Scope, walk: Walk) -> DrawStep
         
         DrawStep::done()
     }
+
+    fn accessibility_node(&self, _cx: &Cx) -> Option<AccessibilityNode> {
+        Some(AccessibilityNode::new(AccessibilityRole::Window))
+    }
 }
@@ -0,0 +1,88 @@
+use makepad_widgets::*;
+
+/// Returns the tables of `body`, in order.
+fn tables(body: &str) -> Vec<MarkdownTable> {
+    let events = parse_markdown(body);
+    (0..events.len())
+        .map(|index| MarkdownTable::from_events(&events, index))
+        .filter(|table| *table != MarkdownTable::default())
+        .collect()
+}
+
+/// Returns the table of `body`, which must have exactly one.
+fn table(body: &str) -> MarkdownTable {
+    let mut tables = tables(body);
+    assert_eq!(tables.len(), 1, "expected one table in {:?}", body);
+    tables.remove(0)
+}
+
+/// Returns the task list marker of each task list item in `body`.
+fn task_markers(body: &str) -> Vec<bool> {
+    let events = parse_markdown(body);
+    (0..events.len())
+        .filter_map(|index| task_list_marker(&events, index))
+        .collect()
+}
+
+#[test]
+fn table_alignment_row() {
+    let table = table("| a | b | c | d |\n|---|:--|:-:|--:|\n| 1 | 2 | 3 | 4 |");
+    assert_eq!(table.aligns, [0.0, 0.0, 0.5, 1.0]);
+    assert_eq!(table.content_lens, [1, 1, 1, 1]);
+}
+
+#[test]
+fn table_needs_an_alignment_row() {
+    assert!(tables("| a | b |\n| 1 | 2 |").is_empty());
+}
+
+#[test]
+fn ragged_rows_get_one_cell_per_column() {
+    // the short row is padded, and the cell past the last column is not counted
+    let body = "| a | b | c |\n|---|---|---|\n| 1 |\n| 1 | 22 | 3 | 4444444 |";
+    assert_eq!(table(body).content_lens, [1, 2, 1]);
+}
+
+#[test]
+fn columns_are_measured_by_their_longest_cell() {
+    let table = table("| name | `code` |\n|---|---|\n| a **bold** é | x |");
+    assert_eq!(table.content_lens, [8, 4]);
+}
+
+#[test]
+fn column_shares_follow_the_content() {
+    let table = MarkdownTable {
+        aligns: vec![0.0; 4],
+        content_lens: vec![0, 12, 24, 1000],
+    };
+    let shares = table.column_shares();
+    // short columns get a minimum width and long ones a maximum
+    assert_eq!(shares, [6.0 / 82.0, 12.0 / 82.0, 24.0 / 82.0, 40.0 / 82.0]);
+    assert!((shares.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(MarkdownTable::default().column_shares().is_empty());
+}
+
+#[test]
+fn table_ends_at_its_own_end() {
+    let tables = tables("| a |\n|---|\n| 1 |\n\ntext\n\n| b |\n|---|\n| a longer cell |");
+    let content_lens: Vec<_> = tables.iter().map(|table| table.content_lens.clone()).collect();
+    assert_eq!(content_lens, [vec![1], vec![13]]);
+}
+
+#[test]
+fn task_list_items() {
+    // plain items and malformed markers are not tasks
+    assert_eq!(
+        task_markers("- [ ] todo\n- [x] done\n- [X] also done\n- plain\n- [] not a task"),
+        [false, true, true]
+    );
+}
+
+#[test]
+fn task_list_items_in_loose_and_ordered_lists() {
+    // in a loose list the marker follows the item's paragraph
+    assert_eq!(task_markers("- [x] a\n\n- [ ] b"), [true, false]);
+    assert_eq!(task_markers("1. [x] a\n2. b"), [true]);
+    // a marker only counts at the start of the item
+    assert!(task_markers("- a [x]").is_empty());
+}